@@ -1,7 +1,10 @@
 //! Build WASI component from source.
 //!
-//! Supports multiple languages: Rust (default) and `TypeScript`.
-//! Uses cargo-component or jco depending on language.
+//! Supports multiple languages: Rust (default), `TypeScript`, Go and Python.
+//! Uses cargo-component, jco, TinyGo or componentize-py depending on language,
+//! with per-language settings in `[build.go]` and `[build.python]`.
+//! Release builds are optimized with wasm-opt and checked against the size
+//! budget in `[build]` (see [`optimize`](super::optimize)).
 //! Optionally composes all dependencies using wac.
 //! Outputs packaged component to dist/ folder.
 
@@ -12,9 +15,12 @@ use std::fs::{self, File};
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use wasmparser::{Parser, Payload};
 
+use super::optimize;
 use super::{check_tool, require_tool_with_info};
-use crate::manifest::{Dependency, Manifest};
+use crate::manifest::{Dependency, GoBuildConfig, Manifest, OptLevel, PythonBuildConfig};
+use crate::runtime::{component_openapi, core_adapter};
 use crate::ui;
 use crate::utils::{format_bytes, get_cargo_name};
 
@@ -23,6 +29,8 @@ fn normalize_language(lang: &str) -> &'static str {
     match lang.to_lowercase().as_str() {
         "rust" | "rs" => "rust",
         "typescript" | "ts" => "typescript",
+        "go" | "golang" | "tinygo" => "go",
+        "python" | "py" => "python",
         _ => "rust", // default
     }
 }
@@ -158,12 +166,73 @@ fn extract_schema() -> Result<Option<PathBuf>> {
     Ok(None)
 }
 
+/// Options for a build.
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Build in release mode with optimizations
+    pub release: bool,
+    /// Compose with dependencies using wac
+    pub compose: bool,
+    /// Override detected language (rust/typescript/go/python)
+    pub lang: Option<String>,
+    /// Skip OpenAPI schema extraction
+    pub no_schema: bool,
+}
+
+/// A component built and packaged to dist/.
+#[derive(Debug, Clone)]
+pub struct Built {
+    /// Project name.
+    pub name: String,
+    /// Packaged component (`dist/<name>[-composed].wasm`).
+    pub wasm: PathBuf,
+    /// OpenAPI schema next to it, if the component provides one.
+    pub schema: Option<PathBuf>,
+}
+
+/// A toolchain that exited with an error.
+///
+/// Displays as a one-line summary; the compiler output, already printed in
+/// an error box, is kept for the `mik dev` build error overlay.
+#[derive(Debug)]
+pub struct ToolchainError {
+    /// One-line summary, e.g. "Rust compilation failed".
+    pub summary: String,
+    /// What the toolchain printed (stderr, then stdout).
+    pub output: String,
+}
+
+impl std::fmt::Display for ToolchainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.summary)
+    }
+}
+
+impl std::error::Error for ToolchainError {}
+
+/// Print the error box for a failed toolchain run and return its error.
+fn toolchain_failed(title: &str, summary: &str, output: &std::process::Output) -> anyhow::Error {
+    ui::print_error_box_from_output(title, output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let output = [stderr.trim(), stdout.trim()]
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    ToolchainError {
+        summary: summary.to_string(),
+        output,
+    }
+    .into()
+}
+
 /// Build the component.
 ///
 /// # Arguments
 /// - `release`: Build in release mode with optimizations
 /// - `compose`: Compose with dependencies using wac
-/// - `lang_override`: Override detected language (rust/typescript)
+/// - `lang_override`: Override detected language (rust/typescript/go/python)
 /// - `no_schema`: Skip OpenAPI schema extraction
 pub async fn execute(
     release: bool,
@@ -171,6 +240,27 @@ pub async fn execute(
     lang_override: Option<String>,
     no_schema: bool,
 ) -> Result<()> {
+    let options = BuildOptions {
+        release,
+        compose,
+        lang: lang_override,
+        no_schema,
+    };
+    build(&options, true).await.map(|_| ())
+}
+
+/// Build the component and package it to dist/.
+///
+/// `schema_test` runs the `routes!` schema test for Rust projects without
+/// an embedded schema; watch mode skips it on rebuilds, as it recompiles the
+/// project for the host target.
+pub async fn build(options: &BuildOptions, schema_test: bool) -> Result<Built> {
+    let BuildOptions {
+        release,
+        compose,
+        ref lang,
+        no_schema,
+    } = *options;
     // Load mik.toml if it exists
     let manifest = match Manifest::load() {
         Ok(m) => Some(m),
@@ -182,7 +272,7 @@ pub async fn execute(
     };
 
     // Resolve language: flag > mik.toml > rust
-    let language = lang_override
+    let language = lang
         .as_deref()
         .or_else(|| {
             manifest
@@ -203,22 +293,36 @@ pub async fn execute(
 
     // Step 1: Extract schema BEFORE building WASM (for Rust projects only)
     // This runs cargo test which needs the native target, not wasm32-wasip2
-    let schema_path = if !no_schema && language == "rust" {
+    let schema_path = if !no_schema && schema_test && language == "rust" {
         extract_schema()?
     } else {
         None
     };
 
     // Step 2: Build based on language
+    let build_config = manifest
+        .as_ref()
+        .map(|m| m.build.clone())
+        .unwrap_or_default();
     let (wasm_path, target_base) = match language {
         "rust" => build_rust(&name, release)?,
         "typescript" => build_typescript(&name)?,
+        "go" => build_go(&name, release, &build_config.go)?,
+        "python" => build_python(&name, &build_config.python)?,
         _ => bail!("Unsupported language: {language}"),
     };
 
+    // Step 3: Prefer OpenAPI metadata embedded in the component. This must
+    // run before optimization, which strips custom sections
+    let schema_path = if no_schema {
+        None
+    } else {
+        extract_component_schema(&wasm_path).await?.or(schema_path)
+    };
+
     // Optimize WASM (strip debug info, names, etc.) in release mode
-    if release && language == "rust" {
-        optimize_wasm(&wasm_path)?;
+    if release && language != "typescript" {
+        optimize_wasm(&wasm_path, Some(build_config.opt_level))?;
     }
 
     // Compose HTTP handler with bridge (if enabled)
@@ -247,8 +351,15 @@ pub async fn execute(
     // Determine if we did any composition
     let did_compose = compose || http_composed.is_some();
 
+    // Enforce the size budget before replacing the previous artifact, which
+    // the size report compares against
+    if release && let Some(budget_kb) = build_config.max_size_kb {
+        let previous = Path::new("dist").join(dist_wasm_name(&name, did_compose));
+        optimize::check_budget(&final_wasm, Some(&previous), budget_kb)?;
+    }
+
     // Step 4: Package to dist/ folder (including schema if present)
-    package_to_dist(
+    let (wasm, schema) = package_to_dist(
         &final_wasm,
         &name,
         release,
@@ -256,7 +367,35 @@ pub async fn execute(
         schema_path.as_deref(),
     )?;
 
-    Ok(())
+    Ok(Built { name, wasm, schema })
+}
+
+/// Extract the OpenAPI document embedded in the built component, from a
+/// `mik:openapi` custom section or an exported `openapi()` function.
+///
+/// Writes it to `<module>.openapi.json` next to the wasm.
+///
+/// # Returns
+/// - `Ok(Some(path))` if the component carries OpenAPI metadata
+/// - `Ok(None)` if it does not
+async fn extract_component_schema(wasm_path: &Path) -> Result<Option<PathBuf>> {
+    let wasm =
+        fs::read(wasm_path).with_context(|| format!("Failed to read {}", wasm_path.display()))?;
+    let Some(spec) = component_openapi::extract(&wasm)
+        .await
+        .context("Failed to extract OpenAPI metadata from component")?
+    else {
+        return Ok(None);
+    };
+
+    let schema_path = wasm_path.with_extension("openapi.json");
+    fs::write(&schema_path, serde_json::to_vec_pretty(&spec)?)
+        .with_context(|| format!("Failed to write {}", schema_path.display()))?;
+    println!(
+        "  OpenAPI schema extracted from component: {}",
+        schema_path.display()
+    );
+    Ok(Some(schema_path))
 }
 
 // =============================================================================
@@ -304,8 +443,11 @@ fn build_rust(name: &str, release: bool) -> Result<(PathBuf, PathBuf)> {
     spinner.finish_and_clear();
 
     if !output.status.success() {
-        ui::print_error_box_from_output("Rust Build Failed", &output);
-        bail!("Rust compilation failed");
+        return Err(toolchain_failed(
+            "Rust Build Failed",
+            "Rust compilation failed",
+            &output,
+        ));
     }
 
     let build_type = if release { "release" } else { "debug" };
@@ -370,20 +512,7 @@ fn build_typescript(name: &str) -> Result<(PathBuf, PathBuf)> {
         bail!("npm not found. Install Node.js to build TypeScript projects.");
     }
 
-    // Fetch WIT dependencies if wit/deps doesn't exist but deps.toml does
-    if Path::new("wit/deps.toml").exists() && !Path::new("wit/deps").exists() {
-        println!("Fetching WIT dependencies...");
-        let output = Command::new("wkg")
-            .args(["wit", "fetch"])
-            .output()
-            .context("Failed to run wkg wit fetch. Install wkg: cargo install wkg")?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            eprintln!("Warning: wkg wit fetch failed: {stderr}");
-            eprintln!("You may need to install wkg: cargo install wkg");
-        }
-    }
+    fetch_wit_deps("wit")?;
 
     // Install dependencies if node_modules doesn't exist
     if !Path::new("node_modules").exists() {
@@ -410,8 +539,11 @@ fn build_typescript(name: &str) -> Result<(PathBuf, PathBuf)> {
     spinner.finish_and_clear();
 
     if !output.status.success() {
-        ui::print_error_box_from_output("TypeScript Build Failed", &output);
-        bail!("TypeScript build failed");
+        return Err(toolchain_failed(
+            "TypeScript Build Failed",
+            "TypeScript build failed",
+            &output,
+        ));
     }
 
     // Find the output wasm file
@@ -428,21 +560,302 @@ fn build_typescript(name: &str) -> Result<(PathBuf, PathBuf)> {
     Ok((wasm_path, PathBuf::from(".")))
 }
 
+/// Build Go project with TinyGo.
+///
+/// The `wasip2` target produces a component directly (TinyGo runs
+/// `wasm-tools` to embed the WIT world). The `wasip1` target produces a core
+/// module, which is adapted to a component with the WASI preview1 adapter.
+fn build_go(name: &str, release: bool, config: &GoBuildConfig) -> Result<(PathBuf, PathBuf)> {
+    if !Path::new("go.mod").exists() {
+        bail!("No go.mod found. Run from a Go project directory or use --lang flag.");
+    }
+    if !matches!(config.target.as_str(), "wasip1" | "wasip2") {
+        bail!(
+            "Unsupported TinyGo target: {}\n\
+             Fix: Set [build.go].target to \"wasip2\" or \"wasip1\" in mik.toml",
+            config.target
+        );
+    }
+
+    // TinyGo prints its version with `tinygo version`, not `--version`
+    let tinygo = Command::new("tinygo")
+        .arg("version")
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if !tinygo {
+        ui::print_section("Missing Required Tool: tinygo");
+        eprintln!();
+        eprintln!("TinyGo 0.33+ is required to build WASI components from Go.");
+        eprintln!();
+        eprintln!("Install from:");
+        eprintln!("  https://tinygo.org/getting-started/install/");
+        eprintln!();
+        bail!("Missing required tool: tinygo");
+    }
+    if config.target == "wasip2" {
+        require_tool_with_info(
+            "wasm-tools",
+            "cargo install wasm-tools",
+            Some("https://github.com/bytecodealliance/wasm-tools"),
+        )?;
+    }
+
+    fetch_wit_deps(&config.wit)?;
+
+    let build_type = if release { "release" } else { "debug" };
+    println!("Mode: {build_type}");
+    let target_base = PathBuf::from("target");
+    let out_dir = target_base.join("tinygo").join(build_type);
+    fs::create_dir_all(&out_dir)?;
+    let wasm_path = out_dir.join(format!("{name}.wasm"));
+
+    let spinner = ui::create_spinner("Building Go component...");
+
+    let output = Command::new("tinygo")
+        .args(tinygo_args(
+            config,
+            &wasm_path,
+            release,
+            Path::new(&config.wit).exists(),
+        ))
+        .output()
+        .context("Failed to run tinygo build")?;
+
+    spinner.finish_and_clear();
+
+    if !output.status.success() {
+        return Err(toolchain_failed(
+            "Go Build Failed",
+            "TinyGo compilation failed",
+            &output,
+        ));
+    }
+
+    if !wasm_path.exists() {
+        bail!("WASM output not found: {}", wasm_path.display());
+    }
+
+    adapt_core_module(&wasm_path)?;
+
+    Ok((wasm_path, target_base))
+}
+
+/// Arguments for `tinygo build`.
+///
+/// WIT flags are only passed when the WIT directory exists; without them
+/// TinyGo targets its built-in `wasi:cli/command` world.
+fn tinygo_args(config: &GoBuildConfig, output: &Path, release: bool, wit: bool) -> Vec<String> {
+    let mut args = vec![
+        "build".to_string(),
+        format!("-target={}", config.target),
+        "-o".to_string(),
+        output.to_string_lossy().into_owned(),
+    ];
+    if wit && config.target == "wasip2" {
+        args.extend([
+            "--wit-package".to_string(),
+            config.wit.clone(),
+            "--wit-world".to_string(),
+            config.world.clone(),
+        ]);
+    }
+    if !config.tags.is_empty() {
+        args.push(format!("-tags={}", config.tags.join(" ")));
+    }
+    if release {
+        args.extend(["-opt=z".to_string(), "-no-debug".to_string()]);
+    }
+    args.extend(config.args.iter().cloned());
+    args.push(config.package.clone());
+    args
+}
+
+/// Build Python project with componentize-py.
+///
+/// Uses `componentize-py` from `PATH`, or runs it through `uv` when only
+/// `uv` is installed.
+fn build_python(name: &str, config: &PythonBuildConfig) -> Result<(PathBuf, PathBuf)> {
+    let module_file = format!("{}.py", config.module.replace('.', "/"));
+    let module_dir = config.module.replace('.', "/");
+    let found = std::iter::once(".")
+        .chain(config.python_path.iter().map(String::as_str))
+        .any(|dir| {
+            let dir = Path::new(dir);
+            dir.join(&module_file).exists() || dir.join(&module_dir).join("__init__.py").exists()
+        });
+    if !found {
+        bail!(
+            "Python module '{}' not found. Run from a Python project directory, \
+             or set [build.python].module in mik.toml.",
+            config.module
+        );
+    }
+
+    fetch_wit_deps(&config.wit)?;
+    if !Path::new(&config.wit).exists() {
+        bail!(
+            "WIT directory not found: {}\n\
+             Fix: Add the WASI HTTP WIT files, or set [build.python].wit in mik.toml",
+            config.wit
+        );
+    }
+
+    let mut command = if check_tool("componentize-py").is_ok() {
+        Command::new("componentize-py")
+    } else if check_tool("uv").is_ok() {
+        let mut command = Command::new("uv");
+        command.args(["run", "--with", "componentize-py", "componentize-py"]);
+        command
+    } else {
+        ui::print_section("Missing Required Tool: componentize-py");
+        eprintln!();
+        eprintln!("componentize-py is required to build WASI components from Python.");
+        eprintln!();
+        eprintln!("Install with:");
+        eprintln!("  pip install componentize-py");
+        eprintln!();
+        eprintln!("Or install uv, which mik uses to run it on demand:");
+        eprintln!("  https://docs.astral.sh/uv/");
+        eprintln!();
+        bail!("Missing required tool: componentize-py");
+    };
+
+    let target_base = PathBuf::from("target");
+    let out_dir = target_base.join("componentize-py");
+    fs::create_dir_all(&out_dir)?;
+    let wasm_path = out_dir.join(format!("{name}.wasm"));
+
+    let spinner = ui::create_spinner("Building Python component...");
+
+    let output = command
+        .args(componentize_py_args(config, &wasm_path))
+        .output()
+        .context("Failed to run componentize-py")?;
+
+    spinner.finish_and_clear();
+
+    if !output.status.success() {
+        return Err(toolchain_failed(
+            "Python Build Failed",
+            "componentize-py failed",
+            &output,
+        ));
+    }
+
+    if !wasm_path.exists() {
+        bail!("WASM output not found: {}", wasm_path.display());
+    }
+
+    Ok((wasm_path, target_base))
+}
+
+/// Arguments for `componentize-py`.
+fn componentize_py_args(config: &PythonBuildConfig, output: &Path) -> Vec<String> {
+    let mut args = vec![
+        "-d".to_string(),
+        config.wit.clone(),
+        "-w".to_string(),
+        config.world.clone(),
+        "componentize".to_string(),
+        config.module.clone(),
+        "-p".to_string(),
+        ".".to_string(),
+    ];
+    for path in &config.python_path {
+        args.extend(["-p".to_string(), path.clone()]);
+    }
+    args.extend(config.args.iter().cloned());
+    args.extend(["-o".to_string(), output.to_string_lossy().into_owned()]);
+    args
+}
+
 // =============================================================================
 // Helper functions
 // =============================================================================
 
+/// Fetch WIT dependencies if `<wit>/deps` doesn't exist but `<wit>/deps.toml` does.
+///
+/// `wkg` looks for a `wit` directory, so it runs from the WIT directory's parent.
+fn fetch_wit_deps(wit: &str) -> Result<()> {
+    let wit = Path::new(wit);
+    if wit.join("deps.toml").exists() && !wit.join("deps").exists() {
+        let project = match wit.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        println!("Fetching WIT dependencies...");
+        let output = Command::new("wkg")
+            .args(["wit", "fetch"])
+            .current_dir(project)
+            .output()
+            .context("Failed to run wkg wit fetch. Install wkg: cargo install wkg")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            eprintln!("Warning: wkg wit fetch failed: {stderr}");
+            eprintln!("You may need to install wkg: cargo install wkg");
+        }
+    }
+    Ok(())
+}
+
+/// Adapt a core module built for `wasip1` to a component, in place.
+fn adapt_core_module(wasm_path: &Path) -> Result<()> {
+    if is_wasm_component(wasm_path)? {
+        return Ok(());
+    }
+    let wasm =
+        fs::read(wasm_path).with_context(|| format!("Failed to read {}", wasm_path.display()))?;
+    let component = core_adapter::componentize(&wasm)?;
+    fs::write(wasm_path, &component)
+        .with_context(|| format!("Failed to write {}", wasm_path.display()))?;
+    println!("  Adapted core module to a component with the WASI preview1 adapter");
+    Ok(())
+}
+
+/// Whether a component already exports `wasi:http/incoming-handler`, so it
+/// needs no bridge.
+fn exports_http_handler(wasm_path: &Path) -> Result<bool> {
+    let wasm =
+        fs::read(wasm_path).with_context(|| format!("Failed to read {}", wasm_path.display()))?;
+    let mut depth = 0usize;
+    for payload in Parser::new(0).parse_all(&wasm) {
+        match payload? {
+            Payload::ModuleSection { .. } | Payload::ComponentSection { .. } => depth += 1,
+            Payload::End(_) => depth = depth.saturating_sub(1),
+            Payload::ComponentExportSection(exports) if depth == 0 => {
+                for export in exports {
+                    if export?.name.0.starts_with("wasi:http/incoming-handler@") {
+                        return Ok(true);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+    Ok(false)
+}
+
 /// Package the built component to dist/ folder with tar.gz.
 ///
-/// If `schema_path` is provided, the OpenAPI schema is copied to `dist/<name>.openapi.json`
+/// If `schema_path` is provided, the OpenAPI schema is copied next to the wasm
+/// (`dist/<name>[-composed].openapi.json`, where gateway discovery finds it)
 /// and included in the tar.gz package.
+///
+/// Returns the paths of the packaged wasm and schema.
+/// File name of the packaged component in dist/.
+fn dist_wasm_name(name: &str, composed: bool) -> String {
+    let suffix = if composed { "-composed" } else { "" };
+    format!("{name}{suffix}.wasm")
+}
+
 fn package_to_dist(
     wasm_path: &Path,
     name: &str,
     release: bool,
     composed: bool,
     schema_path: Option<&Path>,
-) -> Result<()> {
+) -> Result<(PathBuf, Option<PathBuf>)> {
     // Create dist directory
     let dist_dir = Path::new("dist");
     fs::create_dir_all(dist_dir)?;
@@ -450,9 +863,9 @@ fn package_to_dist(
     // Determine output filename
     let suffix = if composed { "-composed" } else { "" };
     let mode = if release { "release" } else { "debug" };
-    let wasm_name = format!("{name}{suffix}.wasm");
+    let wasm_name = dist_wasm_name(name, composed);
     let tar_name = format!("{name}{suffix}-{mode}.tar.gz");
-    let schema_name = format!("{name}.openapi.json");
+    let schema_name = format!("{name}{suffix}.openapi.json");
 
     let dist_wasm = dist_dir.join(&wasm_name);
     let dist_tar = dist_dir.join(&tar_name);
@@ -517,7 +930,7 @@ fn package_to_dist(
     );
     ui::print_summary_footer();
 
-    Ok(())
+    Ok((dist_wasm, schema_path.map(|_| dist_schema)))
 }
 
 /// OCI reference for the bridge component.
@@ -540,6 +953,11 @@ async fn compose_http_handler(
         return Ok(None);
     }
 
+    // Components built against WASI HTTP directly (Go, Python) need no bridge
+    if exports_http_handler(handler)? {
+        return Ok(None);
+    }
+
     println!();
     println!("Composing HTTP handler with bridge...");
 
@@ -587,7 +1005,7 @@ async fn compose_http_handler(
     }
 
     // Optimize the composed output
-    optimize_wasm(&service)?;
+    optimize_wasm(&service, None)?;
 
     let size = fs::metadata(&service).map(|m| m.len()).unwrap_or(0);
     println!(
@@ -807,7 +1225,7 @@ fn compose_all(
 
     println!();
 
-    optimize_wasm(&output_path)?;
+    optimize_wasm(&output_path, None)?;
 
     let composed_size = fs::metadata(&output_path).map(|m| m.len()).unwrap_or(0);
     println!(
@@ -896,22 +1314,21 @@ fn find_target_dir() -> Result<PathBuf> {
 
 /// Optimize WASM file for size.
 ///
-/// For WASM Components: uses `wasm-tools strip --all` to remove debug info and names.
-/// For Core Modules: uses `wasm-opt -Oz` for aggressive size optimization.
+/// With a `level`, core modules (on their own or nested in a component) are
+/// first run through wasm-opt (see [`optimize`](super::optimize)).
+/// Components are then stripped with `wasm-tools strip --all` to remove debug
+/// info and names.
 ///
 /// This can significantly reduce file size (often 50-70% for components).
-fn optimize_wasm(wasm_path: &Path) -> Result<()> {
-    let size_before = fs::metadata(wasm_path).map(|m| m.len()).unwrap_or(0);
+fn optimize_wasm(wasm_path: &Path, level: Option<OptLevel>) -> Result<()> {
+    if let Some(level) = level {
+        optimize_modules(wasm_path, level)?;
+    }
 
     // Check if this is a WASM Component
-    let is_component = is_wasm_component(wasm_path)?;
-
-    if is_component {
-        // Use wasm-tools strip for components
+    if is_wasm_component(wasm_path)? {
+        let size_before = fs::metadata(wasm_path).map(|m| m.len()).unwrap_or(0);
         optimize_component(wasm_path, size_before)?;
-    } else {
-        // Use wasm-opt for core modules
-        optimize_core_module(wasm_path, size_before)?;
     }
 
     Ok(())
@@ -958,36 +1375,27 @@ fn optimize_component(wasm_path: &Path, size_before: u64) -> Result<()> {
     Ok(())
 }
 
-/// Optimize core WASM module using wasm-opt.
-fn optimize_core_module(wasm_path: &Path, size_before: u64) -> Result<()> {
+/// Optimize the core modules of a WASM file using wasm-opt.
+fn optimize_modules(wasm_path: &Path, level: OptLevel) -> Result<()> {
     // Check if wasm-opt is available (part of binaryen)
     if check_tool("wasm-opt").is_err() {
-        // wasm-opt not installed - skip silently, it's optional
+        eprintln!();
+        eprintln!("Tip: Install binaryen for smaller release builds (wasm-opt):");
+        eprintln!("  https://github.com/WebAssembly/binaryen/releases");
         return Ok(());
     }
 
-    let spinner = ui::create_spinner("Optimizing WASM for size...");
-
-    // Run wasm-opt -Oz (optimize for size) in-place
-    let output = Command::new("wasm-opt")
-        .args(["-Oz", "-o"])
-        .arg(wasm_path)
-        .arg(wasm_path)
-        .output()
-        .context("Failed to run wasm-opt")?;
-
+    let spinner = ui::create_spinner(&format!("Optimizing WASM (wasm-opt {})...", level.flag()));
+    let wasm = fs::read(wasm_path)?;
+    let result = optimize::optimize(&wasm, level);
     spinner.finish_and_clear();
 
-    if output.status.success() {
-        let size_after = fs::metadata(wasm_path).map(|m| m.len()).unwrap_or(0);
-        print_size_reduction("Optimized", size_before, size_after);
-    } else {
-        eprintln!("Warning: wasm-opt optimization failed, using unoptimized binary");
-        if !output.stderr.is_empty()
-            && let Ok(stderr) = String::from_utf8(output.stderr)
-        {
-            eprintln!("  {}", stderr.trim());
-        }
+    match result {
+        Ok(optimized) => {
+            fs::write(wasm_path, &optimized)?;
+            print_size_reduction("Optimized", wasm.len() as u64, optimized.len() as u64);
+        },
+        Err(e) => eprintln!("Warning: {e:#}, using unoptimized binary"),
     }
 
     Ok(())
@@ -1024,3 +1432,119 @@ fn is_wasm_component(path: &Path) -> Result<bool> {
     // Check version byte: 0x0d = component, 0x01 = core module
     Ok(header[4] == 0x0d)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("ts"), "typescript");
+        assert_eq!(normalize_language("TinyGo"), "go");
+        assert_eq!(normalize_language("py"), "python");
+        assert_eq!(normalize_language("cobol"), "rust");
+    }
+
+    #[test]
+    fn test_tinygo_args() {
+        let config = GoBuildConfig {
+            tags: vec!["purego".to_string(), "netgo".to_string()],
+            ..Default::default()
+        };
+        let args = tinygo_args(&config, Path::new("out.wasm"), true, true);
+        assert_eq!(
+            args,
+            [
+                "build",
+                "-target=wasip2",
+                "-o",
+                "out.wasm",
+                "--wit-package",
+                "wit",
+                "--wit-world",
+                "proxy",
+                "-tags=purego netgo",
+                "-opt=z",
+                "-no-debug",
+                "."
+            ]
+        );
+
+        // No WIT flags for wasip1 or without a WIT directory
+        let wasip1 = GoBuildConfig {
+            target: "wasip1".to_string(),
+            ..Default::default()
+        };
+        let args = tinygo_args(&wasip1, Path::new("out.wasm"), false, true);
+        assert!(!args.iter().any(|arg| arg.starts_with("--wit")));
+        let args = tinygo_args(
+            &GoBuildConfig::default(),
+            Path::new("out.wasm"),
+            false,
+            false,
+        );
+        assert!(!args.iter().any(|arg| arg.starts_with("--wit")));
+    }
+
+    #[test]
+    fn test_componentize_py_args() {
+        let config = PythonBuildConfig {
+            python_path: vec!["src".to_string()],
+            ..Default::default()
+        };
+        let args = componentize_py_args(&config, Path::new("out.wasm"));
+        assert_eq!(
+            args,
+            [
+                "-d",
+                "wit",
+                "-w",
+                "proxy",
+                "componentize",
+                "app",
+                "-p",
+                ".",
+                "-p",
+                "src",
+                "-o",
+                "out.wasm"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exports_http_handler() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("handler.wasm");
+
+        let handler = wat::parse_str(
+            r#"(component
+                (import "wasi:http/types@0.2.0" (instance $types))
+                (instance $handler)
+                (export "wasi:http/incoming-handler@0.2.0" (instance $handler)))"#,
+        )
+        .unwrap();
+        fs::write(&path, handler).unwrap();
+        assert!(exports_http_handler(&path).unwrap());
+
+        let library = wat::parse_str(
+            r#"(component (instance $i) (export "mik:core/handler@0.2.0" (instance $i)))"#,
+        )
+        .unwrap();
+        fs::write(&path, library).unwrap();
+        assert!(!exports_http_handler(&path).unwrap());
+    }
+
+    #[test]
+    fn test_adapt_core_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("handler.wasm");
+        let core =
+            wat::parse_str(r#"(module (memory (export "memory") 1) (func (export "_start")))"#)
+                .unwrap();
+        fs::write(&path, core).unwrap();
+
+        adapt_core_module(&path).unwrap();
+        assert!(is_wasm_component(&path).unwrap());
+    }
+}
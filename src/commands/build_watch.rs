@@ -0,0 +1,301 @@
+//! Watch mode for `mik build --watch` and `mik dev`.
+//!
+//! Builds once, then rebuilds whenever a source file changes. Changes are
+//! debounced, and a rebuild only starts when a file's contents actually
+//! changed: sources are fingerprinted, so files rewritten with the same
+//! contents (`src/bindings.rs` regenerated by cargo-component, `Cargo.lock`)
+//! do not loop. Build outputs, tool directories ([`IGNORED_DIRS`]), fetched
+//! WIT dependencies (`wit/deps`) and `.wasm` files are never sources.
+//!
+//! Rebuilds are incremental: the toolchain's own caches are reused and the
+//! Rust `routes!` schema test, which recompiles for the host target, only
+//! runs on the first build.
+
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use super::build::{self, BuildOptions, Built, ToolchainError};
+use crate::manifest::Manifest;
+
+/// Directories whose changes never trigger a rebuild.
+pub const IGNORED_DIRS: &[&str] = &[
+    "target",
+    "dist",
+    "modules",
+    "node_modules",
+    "__pycache__",
+    ".venv",
+    "venv",
+];
+
+/// Generated files whose changes never trigger a rebuild.
+const IGNORED_FILES: &[&str] = &["openapi.json"];
+
+/// How often pending filesystem events are collected.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Debounce for source changes: `[server].watch_debounce_ms` from mik.toml,
+/// or 300ms.
+pub fn debounce() -> Duration {
+    let ms = Manifest::load().map_or(300, |m| m.server.watch_debounce_ms);
+    Duration::from_millis(ms)
+}
+
+/// Build, then rebuild on every source change until Ctrl+C.
+///
+/// `on_build` is called with the result of each build. Failed builds are
+/// reported and watching continues.
+pub async fn watch<F>(options: BuildOptions, debounce: Duration, mut on_build: F) -> Result<()>
+where
+    F: FnMut(&Result<Built>),
+{
+    let result = build_once(&options, true).await;
+    on_build(&result);
+    rebuild_on_change(options, debounce, on_build).await
+}
+
+/// Rebuild on every source change until Ctrl+C, without building first.
+pub async fn rebuild_on_change<F>(
+    options: BuildOptions,
+    debounce: Duration,
+    mut on_build: F,
+) -> Result<()>
+where
+    F: FnMut(&Result<Built>),
+{
+    let root = std::env::current_dir()?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = RecommendedWatcher::new(
+        move |result: notify::Result<Event>| {
+            if let Ok(event) = result
+                && matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                )
+            {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+        },
+        notify::Config::default(),
+    )
+    .context("Failed to create file watcher")?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", root.display()))?;
+
+    let mut sources = Fingerprints::scan(&root);
+    println!("[watch] Watching for changes (Ctrl+C to stop)...");
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("\n[watch] Stopping watch mode...");
+                break;
+            }
+            changed = next_change(&mut rx, &root, debounce, &mut sources) => {
+                let first = changed.first().map_or_else(String::new, |path| {
+                    path.strip_prefix(&root).unwrap_or(path).display().to_string()
+                });
+                match changed.len() {
+                    1 => println!("[watch] Changed: {first}"),
+                    n => println!("[watch] Changed: {first} (+{} more)", n - 1),
+                }
+                let result = build_once(&options, false).await;
+                on_build(&result);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run one build, printing how it went.
+///
+/// The `routes!` schema test only runs when `first`.
+pub async fn build_once(options: &BuildOptions, first: bool) -> Result<Built> {
+    let start = Instant::now();
+    let result = build::build(options, first).await;
+    match &result {
+        Ok(built) => println!(
+            "[watch] Built {} in {:.1}s",
+            built.wasm.display(),
+            start.elapsed().as_secs_f64()
+        ),
+        Err(e) => eprintln!("[watch] Build failed: {e:#}"),
+    }
+    result
+}
+
+/// The full text of a build error: the toolchain's output when it failed,
+/// the error chain otherwise.
+pub fn error_details(error: &anyhow::Error) -> String {
+    match error.downcast_ref::<ToolchainError>() {
+        Some(failure) if !failure.output.is_empty() => {
+            format!("{}\n\n{}", failure.summary, failure.output)
+        },
+        _ => format!("{error:#}"),
+    }
+}
+
+/// Wait for source files to change, returning them once no event arrived
+/// for `debounce`.
+async fn next_change(
+    rx: &mut UnboundedReceiver<PathBuf>,
+    root: &Path,
+    debounce: Duration,
+    sources: &mut Fingerprints,
+) -> Vec<PathBuf> {
+    let mut changed = BTreeSet::new();
+    let mut last_event = Instant::now();
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        while let Ok(path) = rx.try_recv() {
+            if is_source(root, &path) && sources.update(&path) {
+                changed.insert(path);
+                last_event = Instant::now();
+            }
+        }
+        if !changed.is_empty() && last_event.elapsed() >= debounce {
+            return changed.into_iter().collect();
+        }
+    }
+}
+
+/// Whether a change to `path` can affect the build.
+fn is_source(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let names: Vec<_> = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect();
+    let hidden_or_ignored = names
+        .iter()
+        .any(|name| name.starts_with('.') || IGNORED_DIRS.contains(&name.as_ref()))
+        || names
+            .windows(2)
+            .any(|pair| pair[0] == "wit" && pair[1] == "deps");
+    if hidden_or_ignored {
+        return false;
+    }
+    let Some(name) = relative.file_name().map(|name| name.to_string_lossy()) else {
+        return false;
+    };
+    !(IGNORED_FILES.contains(&name.as_ref())
+        || name.ends_with('~')
+        || Path::new(name.as_ref())
+            .extension()
+            .is_some_and(|ext| ext == "wasm" || ext == "swp" || ext == "tmp"))
+}
+
+/// Content hashes of the source files, to tell real changes from rewrites.
+struct Fingerprints {
+    hashes: HashMap<PathBuf, Option<blake3::Hash>>,
+}
+
+impl Fingerprints {
+    /// Fingerprint the sources under `root`.
+    fn scan(root: &Path) -> Self {
+        let mut fingerprints = Self {
+            hashes: HashMap::new(),
+        };
+        let mut dirs = vec![root.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !is_source(root, &path) {
+                    continue;
+                }
+                if entry.file_type().is_ok_and(|ty| ty.is_dir()) {
+                    dirs.push(path);
+                } else {
+                    fingerprints.update(&path);
+                }
+            }
+        }
+        fingerprints
+    }
+
+    /// Record the current contents of `path`, returning whether they changed.
+    fn update(&mut self, path: &Path) -> bool {
+        if path.is_dir() {
+            return false;
+        }
+        let hash = std::fs::read(path).ok().map(|bytes| blake3::hash(&bytes));
+        self.hashes.insert(path.to_path_buf(), hash) != Some(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_source() {
+        let root = Path::new("/project");
+        assert!(is_source(root, Path::new("/project/src/lib.rs")));
+        assert!(is_source(root, Path::new("/project/mik.toml")));
+        assert!(is_source(root, Path::new("/project/wit/world.wit")));
+
+        assert!(!is_source(root, Path::new("/project/target/debug/x.rlib")));
+        assert!(!is_source(root, Path::new("/project/modules/app.wasm")));
+        assert!(!is_source(root, Path::new("/project/wit/deps/http.wit")));
+        assert!(!is_source(root, Path::new("/project/.git/index")));
+        assert!(!is_source(root, Path::new("/project/handler.wasm")));
+        assert!(!is_source(root, Path::new("/project/openapi.json")));
+        assert!(!is_source(root, Path::new("/project/src/.lib.rs.swp")));
+        assert!(!is_source(root, Path::new("/elsewhere/lib.rs")));
+    }
+
+    #[test]
+    fn test_fingerprints_ignore_rewrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.go");
+        std::fs::write(&file, "package main").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/out"), "x").unwrap();
+
+        let mut sources = Fingerprints::scan(dir.path());
+        assert_eq!(sources.hashes.len(), 1);
+
+        // Same contents: no change
+        std::fs::write(&file, "package main").unwrap();
+        assert!(!sources.update(&file));
+
+        std::fs::write(&file, "package main\n\nfunc main() {}").unwrap();
+        assert!(sources.update(&file));
+
+        // Removal is a change, once
+        std::fs::remove_file(&file).unwrap();
+        assert!(sources.update(&file));
+        assert!(!sources.update(&file));
+    }
+
+    #[test]
+    fn test_error_details_include_toolchain_output() {
+        let error: anyhow::Error = ToolchainError {
+            summary: "Rust compilation failed".to_string(),
+            output: "error[E0425]: cannot find value `x`".to_string(),
+        }
+        .into();
+        assert_eq!(
+            error_details(&error),
+            "Rust compilation failed\n\nerror[E0425]: cannot find value `x`"
+        );
+
+        let error = anyhow::anyhow!("Missing required tool: tinygo");
+        assert_eq!(error_details(&error), "Missing required tool: tinygo");
+    }
+}
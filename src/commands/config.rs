@@ -0,0 +1,504 @@
+//! Configuration inspection commands.
+//!
+//! - `mik config show` - Print the effective runtime settings with the source
+//!   of each value, or with `--toml` the resolved mik.toml
+//! - `mik config check` - Validate mik.toml and the effective settings
+//!
+//! The effective settings are what `mik run` would use: mik.toml with the
+//! active profile and `.env` variables applied, auto-detected limits, the
+//! `MIK_*` environment overrides, then `--port`. Secret-like values are
+//! masked.
+
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+use crate::ConfigAction;
+use crate::commands::run::{ENV_OVERRIDES, apply_env_overrides};
+use crate::manifest::{self, EnvFiles, Manifest, PROFILE_ENV};
+use crate::runtime::{HostConfig, Runtime, host_config};
+use crate::utils::format_bytes;
+
+/// Settings that are auto-detected from the machine when 0 or unset.
+const AUTO_DETECTED: &[&str] = &[
+    "cache_size",
+    "max_cache_mb",
+    "max_concurrent_requests",
+    "max_per_module_requests",
+];
+
+/// Shown for unset optional settings.
+const NONE: &str = "(none)";
+
+/// Execute a config subcommand.
+pub fn execute(action: ConfigAction) -> Result<()> {
+    let path = Path::new("mik.toml");
+    if !path.exists() {
+        bail!("No mik.toml found in the current directory");
+    }
+
+    match action {
+        ConfigAction::Show {
+            profile,
+            port,
+            toml,
+        } => {
+            select_profile(profile.as_deref());
+            if toml {
+                show_toml(path)
+            } else {
+                show_effective(path, port)
+            }
+        },
+        ConfigAction::Check { profile, port } => {
+            select_profile(profile.as_deref());
+            check(path, port)
+        },
+    }
+}
+
+fn select_profile(profile: Option<&str>) {
+    if let Some(profile) = profile {
+        // SAFETY: Single-threaded CLI command, no other threads read the environment.
+        unsafe { std::env::set_var(PROFILE_ENV, profile) };
+    }
+}
+
+/// Print mik.toml with the active profile and `.env` variables applied.
+fn show_toml(path: &Path) -> Result<()> {
+    let mut resolved = manifest::read_resolved(path, true)?;
+    manifest::mask_secret_keys(&mut resolved.table);
+
+    print_header(resolved.profile.as_deref(), &resolved.env_files);
+    print!(
+        "{}",
+        toml::to_string_pretty(&resolved.table).context("Failed to serialize configuration")?
+    );
+    Ok(())
+}
+
+/// Print the effective settings with the source of each value.
+fn show_effective(path: &Path, port: Option<u16>) -> Result<()> {
+    let sources = Sources::load(path, port.is_some())?;
+    let settings = effective_settings(path, port, &sources)?;
+
+    print_header(sources.profile.as_deref(), sources.env.files());
+    let key_width = settings.iter().map(|s| s.key.len()).max().unwrap_or(0);
+    let value_width = settings.iter().map(|s| s.value.len()).max().unwrap_or(0);
+    println!(
+        "{:<key_width$}  {:<value_width$}  Source",
+        "Setting", "Value"
+    );
+    for setting in &settings {
+        println!(
+            "{:<key_width$}  {:<value_width$}  {}",
+            setting.key, setting.value, setting.source
+        );
+    }
+    Ok(())
+}
+
+fn print_header(profile: Option<&str>, env_files: &[PathBuf]) {
+    println!("# profile: {}", profile.unwrap_or("(none)"));
+    if env_files.is_empty() {
+        println!("# env files: (none)");
+    } else {
+        for file in env_files {
+            println!("# env file: {}", file.display());
+        }
+    }
+    println!();
+}
+
+/// Validate mik.toml and the effective settings, listing every problem.
+fn check(path: &Path, port: Option<u16>) -> Result<()> {
+    // Nothing else can be checked when the file does not resolve
+    let config = effective_config(path, port, true)?;
+
+    let mut problems = Vec::new();
+    if let Err(e) = Manifest::load_resolved_from(path) {
+        problems.push(format!("{e:#}"));
+    }
+    if let Err(e) = config.validate() {
+        // Point at where the offending value comes from
+        let message = e.to_string();
+        let sources = Sources::load(path, port.is_some())?;
+        let origin = settings(&config)
+            .into_iter()
+            .find(|(key, _)| message.contains(&format!("invalid {key}=")))
+            .map(|(key, _)| {
+                let source = sources.source(key, false, &sources.references(key));
+                format!(" (from {source})")
+            })
+            .unwrap_or_default();
+        problems.push(format!("Invalid runtime configuration: {message}{origin}"));
+    }
+
+    let profile = manifest::active_profile()
+        .map(|name| format!(" (profile: {name})"))
+        .unwrap_or_default();
+    if problems.is_empty() {
+        println!("{} is valid{profile}", path.display());
+        return Ok(());
+    }
+    for problem in &problems {
+        eprintln!("{problem}\n");
+    }
+    bail!(
+        "{} has {} problem{}{profile}",
+        path.display(),
+        problems.len(),
+        if problems.len() == 1 { "" } else { "s" }
+    );
+}
+
+/// The configuration `mik run` would use, with or without the `MIK_*`
+/// environment overrides.
+fn effective_config(path: &Path, port: Option<u16>, env_overrides: bool) -> Result<HostConfig> {
+    let mut builder = Runtime::builder()
+        .from_manifest_file(path)
+        .with_context(|| format!("Failed to load {}", path.display()))?;
+    if env_overrides {
+        builder = apply_env_overrides(builder)?;
+    }
+    if let Some(port) = port {
+        builder = builder.port(port);
+    }
+    Ok(builder.config().clone())
+}
+
+/// A runtime setting with its effective value and where it came from.
+#[derive(Debug)]
+struct Setting {
+    key: &'static str,
+    value: String,
+    source: String,
+}
+
+/// The effective settings, with their sources.
+fn effective_settings(path: &Path, port: Option<u16>, sources: &Sources) -> Result<Vec<Setting>> {
+    let base = settings(&effective_config(path, port, false)?);
+    let effective = settings(&effective_config(path, port, true)?);
+
+    Ok(effective
+        .into_iter()
+        .zip(base)
+        .map(|((key, value), (_, base_value))| {
+            let references = sources.references(key);
+            let secret = manifest::is_secret_name(key)
+                || references.iter().any(|name| manifest::is_secret_name(name));
+            Setting {
+                key,
+                source: sources.source(key, value != base_value, &references),
+                value: if secret && value != NONE {
+                    manifest::MASK.to_string()
+                } else {
+                    value
+                },
+            }
+        })
+        .collect())
+}
+
+/// Settings of `config`, named and in the units of their mik.toml keys.
+fn settings(config: &HostConfig) -> Vec<(&'static str, String)> {
+    const MB: usize = 1024 * 1024;
+    let path = |path: &Option<PathBuf>| {
+        path.as_ref()
+            .map_or_else(|| NONE.to_string(), |path| path.display().to_string())
+    };
+    let text = |value: &Option<String>| value.clone().unwrap_or_else(|| NONE.to_string());
+    let list = |items: &[String]| {
+        if items.is_empty() {
+            NONE.to_string()
+        } else {
+            items.join(", ")
+        }
+    };
+
+    vec![
+        ("port", config.port.to_string()),
+        ("listen", list(&config.listen)),
+        ("modules", config.modules_path.display().to_string()),
+        ("user_modules", path(&config.user_modules_path)),
+        ("static", path(&config.static_dir)),
+        ("scripts", path(&config.scripts_dir)),
+        ("cache_size", config.cache_size.to_string()),
+        ("max_cache_mb", (config.max_cache_bytes / MB).to_string()),
+        (
+            "memory_limit_bytes",
+            format!(
+                "{} ({})",
+                config.memory_limit_bytes,
+                format_bytes(config.memory_limit_bytes as u64)
+            ),
+        ),
+        (
+            "max_concurrent_requests",
+            config.max_concurrent_requests.to_string(),
+        ),
+        (
+            "max_per_module_requests",
+            config.max_per_module_requests.to_string(),
+        ),
+        (
+            "max_per_tenant_requests",
+            config.max_per_tenant_requests.to_string(),
+        ),
+        ("module_queue_depth", config.module_queue_depth.to_string()),
+        (
+            "module_queue_timeout_ms",
+            config.module_queue_timeout_ms.to_string(),
+        ),
+        (
+            "adaptive_concurrency",
+            config.adaptive_concurrency.to_string(),
+        ),
+        (
+            "adaptive_concurrency_min",
+            config.adaptive_concurrency_min.to_string(),
+        ),
+        (
+            "execution_timeout_secs",
+            config.execution_timeout_secs.to_string(),
+        ),
+        (
+            "max_request_timeout_secs",
+            config.max_request_timeout_secs.to_string(),
+        ),
+        ("init_timeout_secs", config.init_timeout_secs.to_string()),
+        ("execution_retries", config.execution_retries.to_string()),
+        (
+            "execution_retry_backoff_ms",
+            config.execution_retry_backoff_ms.to_string(),
+        ),
+        (
+            "max_body_size_mb",
+            (config.max_body_size_bytes / MB).to_string(),
+        ),
+        (
+            "max_module_size_mb",
+            (config.max_module_size_bytes / MB).to_string(),
+        ),
+        (
+            "shutdown_timeout_secs",
+            config.shutdown_timeout_secs.to_string(),
+        ),
+        ("log_level", text(&config.log_level)),
+        ("logging", config.logging_enabled.to_string()),
+        ("http_allowed", list(&config.http_allowed)),
+        ("trusted_proxies", list(&config.trusted_proxies)),
+        ("proxy_protocol", config.proxy_protocol.to_string()),
+        ("tenant_uploads", config.tenant_uploads.to_string()),
+        ("sql_modules", list(&config.sql_modules)),
+        ("handlers_webhook", text(&config.handlers_webhook)),
+        (
+            "handlers_webhook_secret",
+            text(&config.handlers_webhook_secret),
+        ),
+        ("api_key", text(&config.api_key)),
+        ("module_signing_key", text(&config.module_signing_key)),
+        ("hot_reload", config.hot_reload.to_string()),
+        ("record_dir", path(&config.record_dir)),
+        ("build_error_file", path(&config.build_error_file)),
+        ("dev_proxy", text(&config.dev_proxy)),
+        ("dev_dashboard", path(&config.dev_dashboard)),
+    ]
+}
+
+/// Where setting values come from: the `[server]` sections of mik.toml and
+/// of the active profile, and the `.env` files.
+struct Sources {
+    server: Table,
+    profile: Option<String>,
+    profile_server: Table,
+    env: EnvFiles,
+    port_flag: bool,
+}
+
+impl Sources {
+    fn load(path: &Path, port_flag: bool) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let table: Table = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let server = |table: Option<&Value>| {
+            table
+                .and_then(|table| table.get("server"))
+                .and_then(Value::as_table)
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        let profile = manifest::active_profile();
+        let profile_server = server(
+            profile
+                .as_ref()
+                .and_then(|name| table.get("profiles")?.get(name)),
+        );
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        Ok(Self {
+            server: server(Some(&Value::Table(table))),
+            env: EnvFiles::load(dir, profile.as_deref())?,
+            profile,
+            profile_server,
+            port_flag,
+        })
+    }
+
+    /// The value of `key` in the manifest, and the layer that sets it.
+    fn layer(&self, key: &str) -> Option<(&Value, String)> {
+        if let Some(value) = self.profile_server.get(key) {
+            let name = self.profile.as_deref().unwrap_or_default();
+            return Some((value, format!("profile {name}")));
+        }
+        self.server
+            .get(key)
+            .map(|value| (value, "mik.toml".to_string()))
+    }
+
+    /// Variables the manifest value of `key` references.
+    fn references(&self, key: &str) -> Vec<String> {
+        let Some((value, _)) = self.layer(key) else {
+            return Vec::new();
+        };
+        match value {
+            Value::String(s) => manifest::variable_references(s),
+            Value::Array(items) => items
+                .iter()
+                .filter_map(Value::as_str)
+                .flat_map(manifest::variable_references)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Where the value of `key` comes from. `overridden` is whether a `MIK_*`
+    /// environment variable changed it.
+    fn source(&self, key: &str, overridden: bool, references: &[String]) -> String {
+        if key == "port" && self.port_flag {
+            return "--port".to_string();
+        }
+        if overridden {
+            let var = ENV_OVERRIDES
+                .iter()
+                .find(|(_, setting)| *setting == key)
+                .map_or_else(|| host_config::env_var(key), |(var, _)| (*var).to_string());
+            return format!("env {var}");
+        }
+
+        let layer = self.layer(key);
+        if AUTO_DETECTED.contains(&key)
+            && layer
+                .as_ref()
+                .is_none_or(|(value, _)| value.as_integer() == Some(0))
+        {
+            let auto = self
+                .layer("auto")
+                .and_then(|(value, _)| value.as_bool())
+                .unwrap_or(true);
+            return if auto { "auto-detected" } else { "default" }.to_string();
+        }
+
+        let Some((_, layer)) = layer else {
+            return "default".to_string();
+        };
+        if references.is_empty() {
+            return layer;
+        }
+        let variables: Vec<_> = references
+            .iter()
+            .map(|name| match self.env.origin(name) {
+                Some(origin) => format!("${{{name}}} from {origin}"),
+                None => format!("${{{name}}} unset"),
+            })
+            .collect();
+        format!("{layer}, {}", variables.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[project]
+name = "app"
+version = "0.1.0"
+
+[server]
+port = 3000
+memory_limit_bytes = 67108864
+max_concurrent_requests = 0
+http_allowed = ["${MIK_CONFIG_TEST_HOST}"]
+
+[profiles.production.server]
+port = 8080
+"#;
+
+    fn source(settings: &[Setting], key: &str) -> String {
+        settings
+            .iter()
+            .find(|setting| setting.key == key)
+            .unwrap()
+            .source
+            .clone()
+    }
+
+    #[test]
+    fn test_effective_settings_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.toml");
+        std::fs::write(&path, MANIFEST).unwrap();
+        std::fs::write(
+            dir.path().join(".env"),
+            "MIK_CONFIG_TEST_HOST=api.example.com\n",
+        )
+        .unwrap();
+
+        let sources = Sources::load(&path, false).unwrap();
+        let settings = effective_settings(&path, None, &sources).unwrap();
+        assert_eq!(source(&settings, "port"), "mik.toml");
+        assert_eq!(source(&settings, "memory_limit_bytes"), "mik.toml");
+        assert_eq!(source(&settings, "execution_timeout_secs"), "default");
+        assert_eq!(
+            source(&settings, "max_concurrent_requests"),
+            "auto-detected"
+        );
+        assert_eq!(
+            source(&settings, "http_allowed"),
+            "mik.toml, ${MIK_CONFIG_TEST_HOST} from .env"
+        );
+        let memory = settings
+            .iter()
+            .find(|setting| setting.key == "memory_limit_bytes")
+            .unwrap();
+        assert_eq!(memory.value, "67108864 (64.0 MB)");
+
+        let settings =
+            effective_settings(&path, Some(9000), &Sources::load(&path, true).unwrap()).unwrap();
+        assert_eq!(source(&settings, "port"), "--port");
+    }
+
+    #[test]
+    fn test_profile_source() {
+        let mut sources = Sources {
+            server: toml::from_str("port = 3000").unwrap(),
+            profile: Some("production".to_string()),
+            profile_server: toml::from_str("port = 8080\nauto = false").unwrap(),
+            env: EnvFiles::default(),
+            port_flag: false,
+        };
+        assert_eq!(sources.source("port", false, &[]), "profile production");
+        assert_eq!(sources.source("cache_size", false, &[]), "default");
+        assert_eq!(sources.source("api_key", true, &[]), "env MIK_API_KEY");
+        assert_eq!(
+            sources.source("memory_limit_bytes", true, &[]),
+            "env MIK_MEMORY_LIMIT_BYTES"
+        );
+
+        sources.profile_server = Table::new();
+        assert_eq!(sources.source("port", false, &[]), "mik.toml");
+        assert_eq!(sources.source("cache_size", false, &[]), "auto-detected");
+    }
+}
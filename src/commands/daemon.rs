@@ -9,10 +9,11 @@ use std::time::Duration;
 use sysinfo::{Pid, ProcessesToUpdate, System};
 
 use crate::daemon::config::DaemonConfig;
+use crate::daemon::http::client::DaemonClient;
 use crate::daemon::paths::{get_daemon_pid, get_state_path};
-use crate::daemon::process::{self, SpawnConfig};
+use crate::daemon::process::{self, RestartConfig, RestartPolicy, SpawnConfig};
 use crate::daemon::startup::ensure_daemon_running;
-use crate::daemon::state::{Instance, StateStore, Status};
+use crate::daemon::state::{Instance, RestartReason, StateStore, Status};
 
 /// Start the daemon for process management and scheduling.
 ///
@@ -55,7 +56,7 @@ pub async fn start(port: Option<u16>) -> Result<()> {
         println!("{}", services.join(", "));
     }
 
-    println!("  System:     /health, /version, /metrics");
+    println!("  System:     /health, /version, /metrics, /openapi.json");
 
     // Show disabled services warning
     if !config.services.kv_enabled
@@ -143,7 +144,7 @@ fn check_daemon_auto_exit(store: &StateStore) -> Result<()> {
 /// Run instance in detached mode with auto-daemon.
 ///
 /// Auto-starts daemon if not running, then creates the instance.
-pub async fn run_detached(name: &str, port: u16) -> Result<()> {
+pub async fn run_detached(name: &str, port: u16, restart: RestartConfig) -> Result<()> {
     // Ensure daemon is running
     ensure_daemon_running().await?;
 
@@ -179,12 +180,20 @@ pub async fn run_detached(name: &str, port: u16) -> Result<()> {
         config_path: config_path.clone(),
         working_dir: working_dir.clone(),
         hot_reload: false,
+        build_error_file: None,
+        dev_proxy: None,
+        dev_dashboard: false,
+        restart,
     };
 
     let info = process::spawn_instance(&spawn_config)?;
 
     // Save instance state
-    let instance = Instance::new(name, port, info.pid, config_path.clone());
+    let instance = Instance {
+        auto_restart: restart.policy != RestartPolicy::Never,
+        restart: Some(restart),
+        ..Instance::new(name, port, info.pid, config_path.clone())
+    };
     store.save_instance(&instance)?;
 
     println!("Instance '{}' started (PID: {})", name, info.pid);
@@ -200,7 +209,8 @@ pub async fn run_detached(name: &str, port: u16) -> Result<()> {
 
 /// List all tracked WASM instances.
 ///
-/// Shows status, port, PID, and uptime for each instance.
+/// Shows status, port, PID, CPU, memory, open file descriptors, and uptime
+/// for each instance.
 /// If `json` is true, outputs machine-readable JSON format.
 pub fn ps(json: bool) -> Result<()> {
     use crate::daemon::http::types::InstanceResponse;
@@ -229,10 +239,28 @@ pub fn ps(json: bool) -> Result<()> {
         return Ok(());
     }
 
+    let running: Vec<u32> = instances
+        .iter()
+        .filter(|i| i.status == Status::Running)
+        .map(|i| i.pid)
+        .collect();
+    let usage = process::sample_usage(&running);
+
     if json {
         // Convert to InstanceResponse for JSON serialization
-        let responses: Vec<InstanceResponse> =
-            instances.iter().map(InstanceResponse::from).collect();
+        let responses: Vec<InstanceResponse> = instances
+            .iter()
+            .map(|instance| {
+                let mut response = InstanceResponse::from(instance);
+                if instance.status == Status::Running {
+                    match usage.get(&instance.pid) {
+                        Some(sample) => response.resources = Some((*sample).into()),
+                        None => response.mark_crashed(),
+                    }
+                }
+                response
+            })
+            .collect();
         let json_output = serde_json::to_string_pretty(&responses)
             .context("Failed to serialize instances to JSON")?;
         println!("{json_output}");
@@ -241,10 +269,10 @@ pub fn ps(json: bool) -> Result<()> {
 
     // Print header
     println!(
-        "{:<12} {:<6} {:<8} {:<10} UPTIME",
-        "NAME", "PORT", "PID", "STATUS"
+        "{:<12} {:<6} {:<8} {:<10} {:<7} {:<10} {:<5} UPTIME",
+        "NAME", "PORT", "PID", "STATUS", "CPU", "MEM", "FDS"
     );
-    println!("{}", "─".repeat(60));
+    println!("{}", "─".repeat(78));
 
     for instance in instances {
         // Check if actually running
@@ -269,9 +297,25 @@ pub fn ps(json: bool) -> Result<()> {
             "-".to_string()
         };
 
+        let sample = usage
+            .get(&instance.pid)
+            .filter(|_| actual_status == "running");
+        let (cpu, mem, fds) = sample.map_or_else(
+            || ("-".to_string(), "-".to_string(), "-".to_string()),
+            |sample| {
+                (
+                    format!("{:.1}%", sample.cpu_percent),
+                    format_bytes(sample.rss_bytes),
+                    sample
+                        .open_fds
+                        .map_or_else(|| "-".to_string(), |n| n.to_string()),
+                )
+            },
+        );
+
         println!(
-            "{:<12} {:<6} {:<8} {:<10} {}",
-            instance.name, instance.port, instance.pid, actual_status, uptime
+            "{:<12} {:<6} {:<8} {:<10} {:<7} {:<10} {:<5} {}",
+            instance.name, instance.port, instance.pid, actual_status, cpu, mem, fds, uptime
         );
     }
 
@@ -415,6 +459,62 @@ pub async fn logs(name: &str, follow: bool, lines: usize) -> Result<()> {
     Ok(())
 }
 
+/// Show instance logs through a daemon's HTTP API.
+///
+/// Uses `GET /instances/:name/logs`; with `follow`, reads the daemon's
+/// Server-Sent Events stream until Ctrl+C or the daemon closes it.
+pub async fn remote_logs(daemon_url: &str, name: &str, follow: bool, lines: usize) -> Result<()> {
+    let client = DaemonClient::new(daemon_url)?.api_key_from_env();
+
+    if !follow {
+        let logs = client.logs(name, lines).await?;
+        if logs.lines.is_empty() {
+            println!("No logs found for instance '{name}'");
+        }
+        for line in logs.lines {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+
+    let mut response = client.follow_logs(name, lines).await?;
+    println!("Following logs for '{name}' from {daemon_url} (Ctrl+C to exit)...\n");
+
+    let mut buffer = String::new();
+    loop {
+        tokio::select! {
+            chunk = response.chunk() => {
+                let Some(chunk) = chunk.context("Log stream interrupted")? else {
+                    break;
+                };
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+                for line in take_sse_data(&mut buffer) {
+                    println!("{line}");
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract the `data:` payloads of complete SSE lines in `buffer`.
+///
+/// Incomplete trailing input stays in `buffer`; comments (keep-alives) and
+/// other fields are skipped.
+fn take_sse_data(buffer: &mut String) -> Vec<String> {
+    let Some(end) = buffer.rfind('\n') else {
+        return Vec::new();
+    };
+    let complete: String = buffer.drain(..=end).collect();
+    complete
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data).to_string())
+        .collect()
+}
+
 /// Show detailed information about an instance.
 ///
 /// Displays configuration, modules, and statistics.
@@ -458,6 +558,11 @@ pub fn inspect(name: &str) -> Result<()> {
     );
     println!("Uptime:     {uptime}");
     println!("Config:     {}", instance.config.display());
+    println!(
+        "Restart:    {} ({} restarts)",
+        instance.restart_config().policy,
+        instance.restart_count
+    );
 
     if !instance.modules.is_empty() {
         println!("Modules:");
@@ -478,6 +583,25 @@ pub fn inspect(name: &str) -> Result<()> {
         }
     }
 
+    if !instance.restart_history.is_empty() {
+        println!("\nExits:");
+        for event in &instance.restart_history {
+            let exit = match (event.reason, event.exit) {
+                (RestartReason::MemoryCeiling, _) => "memory ceiling".to_string(),
+                (RestartReason::Exited, Some(exit)) => exit.to_string(),
+                (RestartReason::Exited, None) => "unknown exit".to_string(),
+            };
+            let outcome = event.restarted_at.map_or_else(
+                || "not restarted".to_string(),
+                |at| format!("restarted {}", at.format("%H:%M:%S")),
+            );
+            println!(
+                "  {}  {exit}, {outcome}",
+                event.at.format("%Y-%m-%d %H:%M:%S UTC")
+            );
+        }
+    }
+
     Ok(())
 }
 
@@ -525,3 +649,19 @@ pub fn prune() -> Result<()> {
 
 // Use shared utilities for duration and bytes formatting
 use crate::utils::{format_bytes, format_duration};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_sse_data() {
+        let mut buffer = ": keep-alive\n\ndata: first\n\ndata: sec".to_string();
+        assert_eq!(take_sse_data(&mut buffer), vec!["first"]);
+        assert_eq!(buffer, "data: sec");
+
+        buffer.push_str("ond\n\n");
+        assert_eq!(take_sse_data(&mut buffer), vec!["second"]);
+        assert!(buffer.is_empty());
+    }
+}
@@ -1,15 +1,20 @@
 //! Development server command.
 //!
 //! `mik dev` provides an optimized development experience:
-//! - Watch mode: Auto-rebuilds on source changes
+//! - Watch mode: Auto-rebuilds on source changes and reloads the instance
+//!   with the new module (see [`build_watch`](super::build_watch))
+//! - Build error overlay: while the last build failed, module routes answer
+//!   with the compiler output instead of running the stale module
 //! - Embedded services: KV, SQL, Storage, Cron via daemon
 //! - Foreground: Interactive with nice output
 
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
 
-use crate::daemon::paths::{get_daemon_pid, get_state_path};
-use crate::daemon::process::{self, SpawnConfig};
+use super::build::{BuildOptions, Built};
+use super::build_watch;
+use crate::daemon::paths::{get_daemon_pid, get_logs_dir, get_state_path};
+use crate::daemon::process::{self, RestartConfig, SpawnConfig};
 use crate::daemon::startup::ensure_daemon_running_for_services;
 use crate::daemon::state::{Instance, StateStore, Status};
 use crate::manifest::Manifest;
@@ -17,8 +22,21 @@ use crate::manifest::Manifest;
 /// Execute the dev command.
 ///
 /// Starts a development server with watch mode and optional services.
-pub async fn execute(port: u16, no_services: bool) -> Result<()> {
+/// Unless `no_build`, the project is built first and rebuilt on changes.
+/// Variables from `env_file` are loaded by every instance it starts, and
+/// paths the runtime does not serve are forwarded to `proxy`.
+pub async fn execute(
+    port: u16,
+    no_services: bool,
+    no_build: bool,
+    env_file: Option<&str>,
+    proxy: Option<&str>,
+) -> Result<()> {
+    let proxy = proxy.map(validate_proxy_url).transpose()?;
     println!("Starting development server...\n");
+    if let Some(path) = env_file {
+        super::run::set_env_file(path)?;
+    }
 
     // Start daemon for services (unless disabled)
     if !no_services {
@@ -52,8 +70,35 @@ pub async fn execute(port: u16, no_services: bool) -> Result<()> {
         .map(|c| c.watch_debounce_ms)
         .unwrap_or(300);
 
+    // Build the project into modules/, then rebuild it on source changes
+    let logs_dir = get_logs_dir()?;
+    std::fs::create_dir_all(&logs_dir)?;
+    let build_error_file = logs_dir.join(format!("{name}.build-error"));
+    let _ = std::fs::remove_file(&build_error_file);
+    let builder = if !no_build && is_buildable() {
+        let options = BuildOptions::default();
+        let result = build_watch::build_once(&options, true).await;
+        install_build(&result, &modules_dir, &build_error_file);
+
+        let modules_dir = modules_dir.clone();
+        let error_file = build_error_file.clone();
+        let debounce = std::time::Duration::from_millis(debounce_ms);
+        Some(tokio::spawn(async move {
+            let install = |result: &Result<Built>| install_build(result, &modules_dir, &error_file);
+            if let Err(e) = build_watch::rebuild_on_change(options, debounce, install).await {
+                eprintln!("[dev] Source watching stopped: {e:#}");
+            }
+        }))
+    } else {
+        None
+    };
+
     println!("Watching for changes...");
     println!("Server: http://127.0.0.1:{port}");
+    println!("Dashboard: http://127.0.0.1:{port}/_dev/");
+    if let Some(url) = &proxy {
+        println!("Frontend: {url} (everything outside /run/ and /static/)");
+    }
     println!("Press Ctrl+C to stop\n");
 
     // Spawn initial instance
@@ -63,6 +108,10 @@ pub async fn execute(port: u16, no_services: bool) -> Result<()> {
         config_path: config_path.clone(),
         working_dir: working_dir.clone(),
         hot_reload: false,
+        build_error_file: builder.as_ref().map(|_| build_error_file.clone()),
+        dev_proxy: proxy.clone(),
+        dev_dashboard: true,
+        restart: RestartConfig::default(),
     };
 
     let info = process::spawn_instance(&spawn_config)?;
@@ -90,6 +139,10 @@ pub async fn execute(port: u16, no_services: bool) -> Result<()> {
     .await?;
 
     // Cleanup on exit
+    if let Some(builder) = builder {
+        builder.abort();
+    }
+    let _ = std::fs::remove_file(&build_error_file);
     let final_pid = current_pid.load(std::sync::atomic::Ordering::Relaxed);
     if process::is_running(final_pid)? {
         println!("\nStopping server...");
@@ -112,6 +165,62 @@ pub async fn execute(port: u16, no_services: bool) -> Result<()> {
     Ok(())
 }
 
+/// Whether the current directory has sources `mik build` can build: a
+/// language set in mik.toml, or a Rust crate.
+fn is_buildable() -> bool {
+    Manifest::load().is_ok_and(|m| m.project.language.is_some()) || Path::new("Cargo.toml").exists()
+}
+
+/// Install a successful build into `modules_dir`, where the module watcher
+/// picks it up and reloads the instance, or keep a failed build's error in
+/// `error_file` for the overlay.
+fn install_build(result: &Result<Built>, modules_dir: &Path, error_file: &Path) {
+    match result {
+        Ok(built) => {
+            let wasm = modules_dir.join(format!("{}.wasm", built.name));
+            let installed = std::fs::copy(&built.wasm, &wasm)
+                .with_context(|| {
+                    format!(
+                        "Failed to copy {} to {}",
+                        built.wasm.display(),
+                        wasm.display()
+                    )
+                })
+                .and_then(|_| match &built.schema {
+                    Some(schema) => {
+                        let target = modules_dir.join(format!("{}.openapi.json", built.name));
+                        std::fs::copy(schema, &target)
+                            .map(|_| ())
+                            .with_context(|| format!("Failed to copy {}", schema.display()))
+                    },
+                    None => Ok(()),
+                });
+            match installed {
+                Ok(()) => {
+                    let _ = std::fs::remove_file(error_file);
+                    println!("[dev] Installed {}", wasm.display());
+                },
+                Err(e) => eprintln!("[dev] {e:#}"),
+            }
+        },
+        Err(e) => {
+            if let Err(write_error) = std::fs::write(error_file, build_watch::error_details(e)) {
+                eprintln!("[dev] Failed to write build error: {write_error}");
+            }
+            println!("[dev] Module routes show the build error until the next successful build");
+        },
+    }
+}
+
+/// Check that `url` is an `http(s)://` URL for `--proxy`.
+fn validate_proxy_url(url: &str) -> Result<String> {
+    let parsed = reqwest::Url::parse(url).with_context(|| format!("Invalid --proxy URL: {url}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        anyhow::bail!("Invalid --proxy URL: {url} (expected http://host:port)");
+    }
+    Ok(url.trim_end_matches('/').to_string())
+}
+
 /// Get project name from mik.toml.
 fn get_project_name(config_path: &PathBuf) -> Option<String> {
     let content = std::fs::read_to_string(config_path).ok()?;
@@ -229,3 +338,18 @@ fn check_and_stop_daemon(store: &StateStore) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_proxy_url() {
+        assert_eq!(
+            validate_proxy_url("http://localhost:5173/").unwrap(),
+            "http://localhost:5173"
+        );
+        assert!(validate_proxy_url("localhost:5173").is_err());
+        assert!(validate_proxy_url("ws://localhost:5173").is_err());
+    }
+}
@@ -0,0 +1,35 @@
+//! Health probe for container healthchecks.
+//!
+//! `mik health` requests `/health` from a local instance and exits with
+//! status 1 unless it answers 200, so images without a shell or curl
+//! (distroless, scratch) can still declare a `HEALTHCHECK`.
+
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+use crate::manifest::Manifest;
+use crate::runtime::HEALTH_PATH;
+
+/// Execute the health command.
+pub async fn execute(port: Option<u16>, timeout_secs: u64) -> Result<()> {
+    let port = port
+        .or_else(Manifest::load_port)
+        .unwrap_or(crate::constants::DEFAULT_PORT);
+    let url = format!("http://127.0.0.1:{port}{HEALTH_PATH}");
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout_secs.max(1)))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let status = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {url}"))?
+        .status();
+    if !status.is_success() {
+        anyhow::bail!("{url} returned {status}");
+    }
+    println!("healthy");
+    Ok(())
+}
@@ -0,0 +1,170 @@
+//! Inspect a component before running it.
+//!
+//! `mik inspect <file.wasm>` prints what a component needs from the runtime
+//! and what it provides (see [`component_info`]):
+//!
+//! - its world as WIT;
+//! - its imports, each marked as provided, restricted (with the restriction)
+//!   or missing;
+//! - its exports, with the ones mik uses (HTTP handler, job, init hook,
+//!   OpenAPI document) labelled;
+//! - its custom sections and embedded OpenAPI document;
+//! - its size by section kind.
+//!
+//! Fails when mik cannot instantiate the component, so the check can gate a
+//! CI pipeline before a deploy fails at load time.
+//!
+//! [`component_info`]: crate::runtime::component_info
+
+use crate::runtime::component_info::{self, ComponentInfo, Support};
+use crate::utils::format_bytes;
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+
+/// Inspect the component at `path`.
+pub async fn execute(path: &str) -> Result<()> {
+    let wasm = std::fs::read(Path::new(path)).with_context(|| format!("Failed to read {path}"))?;
+    let info = component_info::inspect(&wasm)
+        .await
+        .with_context(|| format!("Failed to inspect {path}"))?;
+    print!("{}", render(path, &info));
+
+    let missing = info
+        .imports
+        .iter()
+        .filter(|(_, support)| *support == Support::Missing)
+        .count();
+    if missing > 0 {
+        bail!("{path} imports {missing} interface(s) mik does not provide");
+    }
+    if info.link_error.is_some() {
+        bail!("{path} cannot be instantiated by mik");
+    }
+    Ok(())
+}
+
+/// The report printed for `info`.
+fn render(path: &str, info: &ComponentInfo) -> String {
+    let mut out = String::new();
+    let mut line = |text: String| {
+        out.push_str(&text);
+        out.push('\n');
+    };
+
+    let kind = if info.core_module {
+        "core module (adapted to a component at load time)"
+    } else {
+        "component"
+    };
+    line(format!("Component:  {path}"));
+    line(format!("Kind:       {kind}"));
+    line(format!(
+        "Size:       {}",
+        format_bytes(info.size_bytes as u64)
+    ));
+
+    line(String::new());
+    line("World:".to_string());
+    for wit in info.wit.lines() {
+        line(format!("  {wit}").trim_end().to_string());
+    }
+
+    line(String::new());
+    line(format!("Imports ({}):", info.imports.len()));
+    for (entry, support) in &info.imports {
+        let (mark, note) = match support {
+            Support::Provided => ("ok", None),
+            Support::Restricted(restriction) => ("!", Some(*restriction)),
+            Support::Missing => ("MISSING", Some("not provided by mik")),
+        };
+        let name = if entry.function {
+            format!("{} (function)", entry.name)
+        } else {
+            entry.name.clone()
+        };
+        match note {
+            Some(note) => line(format!("  [{mark}] {name} - {note}")),
+            None => line(format!("  [{mark}] {name}")),
+        }
+    }
+
+    line(String::new());
+    line(format!("Exports ({}):", info.exports.len()));
+    for (entry, role) in &info.exports {
+        match role {
+            Some(role) => line(format!("  {} - {role}", entry.name)),
+            None => line(format!("  {}", entry.name)),
+        }
+    }
+
+    if !info.custom_sections.is_empty() {
+        line(String::new());
+        line("Custom sections:".to_string());
+        for (name, size) in &info.custom_sections {
+            line(format!("  {name:<32} {:>10}", format_bytes(*size as u64)));
+        }
+    }
+
+    line(String::new());
+    match &info.openapi {
+        Ok(Some(doc)) => {
+            let title = doc["info"]["title"].as_str().unwrap_or("untitled");
+            let version = doc["info"]["version"].as_str().unwrap_or("-");
+            let paths = doc["paths"].as_object().map_or(0, serde_json::Map::len);
+            line(format!("OpenAPI:    {title} {version} ({paths} path(s))"));
+        },
+        Ok(None) => line("OpenAPI:    none".to_string()),
+        Err(e) => line(format!("OpenAPI:    invalid ({e})")),
+    }
+
+    line(String::new());
+    line("Size breakdown:".to_string());
+    for (kind, size) in &info.sizes {
+        #[allow(clippy::cast_precision_loss)]
+        let percent = *size as f64 * 100.0 / info.size_bytes.max(1) as f64;
+        line(format!(
+            "  {kind:<20} {:>10} {percent:>5.1}%",
+            format_bytes(*size as u64)
+        ));
+    }
+
+    line(String::new());
+    match &info.link_error {
+        None => line("Instantiation: ok".to_string()),
+        Some(e) => line(format!("Instantiation: failed\n  {e}")),
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_render_marks_missing_imports() {
+        let wasm = wat::parse_str(
+            r#"(component
+                (import "wasi:clocks/monotonic-clock@0.2.0" (instance))
+                (import "acme:cache/store@1.0.0" (instance)))"#,
+        )
+        .unwrap();
+        let info = component_info::inspect(&wasm).await.unwrap();
+        let report = render("cache.wasm", &info);
+
+        assert!(report.contains("[ok] wasi:clocks/monotonic-clock@0.2.0"));
+        assert!(report.contains("[MISSING] acme:cache/store@1.0.0"));
+        assert!(report.contains("OpenAPI:    none"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_fails_on_missing_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.wasm");
+        let wasm =
+            wat::parse_str(r#"(component (import "acme:cache/store@1.0.0" (instance)))"#).unwrap();
+        std::fs::write(&path, wasm).unwrap();
+
+        let err = execute(path.to_str().unwrap()).await.unwrap_err();
+        assert!(err.to_string().contains("does not provide"), "{err}");
+    }
+}
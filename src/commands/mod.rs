@@ -6,26 +6,44 @@
 //! - [`dev`] - Development server with watch mode and services
 //! - [`new`] - Project scaffolding and template generation
 //! - [`build`] - WASM component compilation and composition
+//! - [`build_watch`] - Rebuilds on source changes (`build --watch`, `dev`)
 //! - [`run`] - Production-like server (foreground or detached)
 //! - [`daemon`] - Instance management (stop/ps/logs)
 //! - [`add`] - Dependency management (OCI/git/path)
 //! - [`pull`] - Pull components from registries
 //! - [`cache`] - AOT cache management
+//! - [`config`] - Resolved configuration display
+//! - [`health`] - Health probe for container healthchecks
+//! - [`inspect`] - Component world, imports and size breakdown
+//! - [`optimize`] - wasm-opt for modules and components, size budgets
+//! - [`package`] - Docker images for deployment
+//! - [`replay`] - Replay recorded traffic and diff responses
 //! - [`strip`] - WASM binary size reduction
+//! - [`test`] - Declarative component tests
 //! - [`static_cmd`] - Static file serving configuration
+//! - [`tune`] - Metrics-driven configuration recommendations
 
 #[cfg(feature = "registry")]
 pub mod add;
 pub mod build;
+pub mod build_watch;
 pub mod cache;
+pub mod config;
 pub mod daemon;
 pub mod dev;
+pub mod health;
+pub mod inspect;
 pub mod new;
+pub mod optimize;
+pub mod package;
 #[cfg(feature = "registry")]
 pub mod pull;
+pub mod replay;
 pub mod run;
 pub mod static_cmd;
 pub mod strip;
+pub mod test;
+pub mod tune;
 
 use anyhow::{Context, Result};
 use std::process::Command;
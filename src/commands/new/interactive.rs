@@ -2,6 +2,7 @@
 //!
 //! Uses simple stdin/stdout without additional dependencies.
 
+use super::services::Service;
 use super::templates::{Language, Template};
 use anyhow::Result;
 use std::io::{self, Write};
@@ -60,27 +61,67 @@ fn prompt_template(lang: Language, default: Template) -> Result<Template> {
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
 
-    let template = match input.trim().to_lowercase().as_str() {
-        "" | "1" => templates[0],
-        "2" if templates.len() > 1 => templates[1],
-        "basic" => Template::Basic,
-        "rest-api" | "restapi" | "rest_api" => {
-            if templates.contains(&Template::RestApi) {
-                Template::RestApi
-            } else {
-                println!("Template not available for {lang}, using basic");
-                Template::Basic
-            }
-        },
-        _ => {
+    let choice = input.trim().to_lowercase();
+    if choice.is_empty() {
+        return Ok(templates[0]);
+    }
+    let template = match choice.parse::<usize>() {
+        Ok(n) if (1..=templates.len()).contains(&n) => templates[n - 1],
+        Ok(_) => {
             println!("Invalid choice, using {default}");
             default
         },
+        Err(_) => match choice.parse::<Template>() {
+            Ok(t) if templates.contains(&t) => t,
+            Ok(_) => {
+                println!("Template not available for {lang}, using basic");
+                Template::Basic
+            },
+            Err(_) => {
+                println!("Invalid choice, using {default}");
+                default
+            },
+        },
     };
 
     Ok(template)
 }
 
+/// Prompt user to select the services the project uses.
+pub fn prompt_services() -> Result<Vec<Service>> {
+    println!();
+    println!("Select services (comma-separated, e.g. 1,3):");
+    for (i, s) in Service::ALL.iter().enumerate() {
+        println!("  [{}] {s:<8} - {}", i + 1, s.description());
+    }
+    print!("\nEnter choices [none]: ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(parse_choices(&input))
+}
+
+/// Services picked by number or name, ignoring invalid choices.
+fn parse_choices(input: &str) -> Vec<Service> {
+    let mut picked = Vec::new();
+    for choice in input.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        let service = match choice.parse::<usize>() {
+            Ok(n) if (1..=Service::ALL.len()).contains(&n) => Some(Service::ALL[n - 1]),
+            Ok(_) => None,
+            Err(_) => choice.parse::<Service>().ok(),
+        };
+        match service {
+            Some(service) => picked.push(service),
+            None => println!("Ignoring invalid choice: {choice}"),
+        }
+    }
+    picked.sort();
+    picked.dedup();
+    picked
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,6 +132,13 @@ mod tests {
         assert_eq!(Language::TypeScript.to_string(), "TypeScript");
     }
 
+    #[test]
+    fn test_parse_choices() {
+        assert_eq!(parse_choices("\n"), vec![]);
+        assert_eq!(parse_choices("2, kv ,2\n"), vec![Service::Kv, Service::Sql]);
+        assert_eq!(parse_choices("9,redis,6"), vec![Service::Http]);
+    }
+
     #[test]
     fn test_template_description() {
         assert_eq!(Template::Basic.description(), "Simple hello world handler");
@@ -4,10 +4,14 @@
 //! - Rust (default): mik-sdk based HTTP handlers
 //! - `TypeScript`: jco + esbuild workflow
 //!
+//! Rust projects can also pick the services they use (see [`services`]),
+//! which adds example code, mik.toml grants and `.env` settings for each.
+//!
 //! WIT interfaces are fetched from OCI registry to ensure consistency with the bridge.
 
 mod github;
 mod interactive;
+mod services;
 mod templates;
 
 use anyhow::{Context, Result};
@@ -15,6 +19,7 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+pub use services::{Service, parse_services};
 pub use templates::{Language, Template};
 
 /// OCI reference for the WIT package.
@@ -31,6 +36,8 @@ pub struct NewOptions {
     pub lang: Language,
     /// Template to use
     pub template: Template,
+    /// Services to generate examples and grants for
+    pub services: Vec<Service>,
     /// Skip interactive prompts
     pub yes: bool,
     /// `GitHub` template (overrides lang/template)
@@ -43,6 +50,7 @@ impl Default for NewOptions {
             name: String::new(),
             lang: Language::Rust,
             template: Template::Basic,
+            services: Vec::new(),
             yes: false,
             github_template: None,
         }
@@ -69,15 +77,34 @@ pub async fn execute(options: NewOptions) -> Result<()> {
     }
 
     // Determine language and template (interactive or from options)
-    let (lang, template) = if options.yes {
-        (options.lang, options.template)
-    } else if is_interactive() {
+    let interactive = !options.yes && is_interactive();
+    let (lang, template) = if interactive {
         interactive::prompt_options(options.lang, options.template)?
     } else {
         (options.lang, options.template)
     };
+    let services = if interactive && lang == Language::Rust && options.services.is_empty() {
+        interactive::prompt_services()?
+    } else {
+        options.services
+    };
 
-    println!("Creating new {lang} project: {project_name} (template: {template})");
+    if !lang.available_templates().contains(&template) {
+        anyhow::bail!("The {template} template is not available for {lang}");
+    }
+    if lang != Language::Rust && !services.is_empty() {
+        anyhow::bail!("Services are only available for Rust projects");
+    }
+
+    if services.is_empty() {
+        println!("Creating new {lang} project: {project_name} (template: {template})");
+    } else {
+        let names: Vec<_> = services.iter().map(ToString::to_string).collect();
+        println!(
+            "Creating new {lang} project: {project_name} (template: {template}, services: {})",
+            names.join(", ")
+        );
+    }
 
     // Fetch WIT from OCI (or use cached version)
     let wit_content = fetch_wit().await?;
@@ -99,7 +126,7 @@ pub async fn execute(options: NewOptions) -> Result<()> {
     };
 
     // Generate project files from template
-    templates::generate_project(project_dir, lang, template, &ctx, &wit_content)?;
+    templates::generate_project(project_dir, lang, template, &services, &ctx, &wit_content)?;
 
     // Initialize git repository
     let _ = Command::new("git")
@@ -112,7 +139,7 @@ pub async fn execute(options: NewOptions) -> Result<()> {
     println!();
 
     // Print next steps based on language
-    print_next_steps(project_name, lang);
+    print_next_steps(project_name, lang, template, &services);
 
     Ok(())
 }
@@ -190,11 +217,21 @@ async fn download_wit() -> Result<String> {
 }
 
 /// Print next steps based on language.
-fn print_next_steps(project_name: &str, lang: Language) {
+fn print_next_steps(project_name: &str, lang: Language, template: Template, services: &[Service]) {
     println!("Next steps:");
     println!("  cd {project_name}");
 
     match lang {
+        // mik dev starts the daemon the services run in
+        Language::Rust if !services.is_empty() => {
+            println!("  mik build -rc");
+            println!("  mik dev");
+        },
+        Language::Rust if template.uses_sql() => {
+            println!("  mik build -rc");
+            println!("  mik test");
+            println!("  mik dev");
+        },
         Language::Rust => {
             println!("  mik build -rc");
             println!("  mik run");
@@ -244,6 +281,7 @@ mod tests {
         let opts = NewOptions::default();
         assert_eq!(opts.lang, Language::Rust);
         assert_eq!(opts.template, Template::Basic);
+        assert!(opts.services.is_empty());
         assert!(!opts.yes);
     }
 }
@@ -0,0 +1,549 @@
+//! Services a new project uses, picked with `mik new --services` or in the
+//! interactive prompts.
+//!
+//! Each service adds example routes to the generated `src/lib.rs`, the grants
+//! the module needs in mik.toml, and local dev settings in `.env`:
+//!
+//! | Service   | Reached through                      | Grant / config                  |
+//! |-----------|--------------------------------------|---------------------------------|
+//! | `kv`      | daemon KV API over outgoing HTTP     | `http_allowed = ["127.0.0.1"]`  |
+//! | `sql`     | `mik:sql` host interface             | `sql_modules`                   |
+//! | `storage` | daemon storage API over outgoing HTTP| `http_allowed = ["127.0.0.1"]`  |
+//! | `queue`   | SQL table drained on a schedule      | `sql_modules`, `[[schedules]]`  |
+//! | `cron`    | `[[schedules]]` calling a route      | `[[schedules]]`                 |
+//! | `http`    | outgoing HTTP                        | `http_allowed`, `API_HOST`      |
+//!
+//! The daemon services (KV, SQL, storage, schedules) are started by
+//! `mik dev`.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A service the generated project uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Service {
+    Kv,
+    Sql,
+    Storage,
+    Queue,
+    Cron,
+    Http,
+}
+
+impl Service {
+    /// All services, in prompt order.
+    pub const ALL: &[Self] = &[
+        Self::Kv,
+        Self::Sql,
+        Self::Storage,
+        Self::Queue,
+        Self::Cron,
+        Self::Http,
+    ];
+
+    /// Get description for interactive prompt.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Kv => "Key-value store",
+            Self::Sql => "SQL database (mik:sql)",
+            Self::Storage => "Object storage",
+            Self::Queue => "Job queue drained on a schedule",
+            Self::Cron => "Scheduled task",
+            Self::Http => "Outbound HTTP to an external API",
+        }
+    }
+
+    /// Whether the service uses the `mik:sql` host interface.
+    pub const fn uses_sql(self) -> bool {
+        matches!(self, Self::Sql | Self::Queue)
+    }
+
+    /// Host the module must be allowed to call (`http_allowed`).
+    pub const fn http_host(self) -> Option<&'static str> {
+        match self {
+            Self::Kv | Self::Storage => Some("127.0.0.1"),
+            Self::Http => Some("${API_HOST:-httpbin.org}"),
+            _ => None,
+        }
+    }
+
+    /// `[[schedules]]` entry appended to mik.toml.
+    pub const fn schedule(self) -> Option<&'static str> {
+        match self {
+            Self::Queue => Some(QUEUE_SCHEDULE),
+            Self::Cron => Some(CRON_SCHEDULE),
+            _ => None,
+        }
+    }
+
+    /// Lines for the `.env` file.
+    pub const fn env(self) -> Option<&'static str> {
+        match self {
+            Self::Http => Some(HTTP_ENV),
+            _ => None,
+        }
+    }
+
+    /// Example `routes!` entries, `use` lines and code.
+    const fn example(self) -> Example {
+        match self {
+            Self::Kv => Example {
+                routes: KV_ROUTES,
+                imports: HTTP_CLIENT_IMPORT,
+                support: Some(DAEMON_SUPPORT),
+                code: KV_CODE,
+            },
+            Self::Sql => Example {
+                routes: SQL_ROUTES,
+                imports: SQL_IMPORT,
+                support: None,
+                code: SQL_CODE,
+            },
+            Self::Storage => Example {
+                routes: STORAGE_ROUTES,
+                imports: HTTP_CLIENT_IMPORT,
+                support: Some(DAEMON_SUPPORT),
+                code: STORAGE_CODE,
+            },
+            Self::Queue => Example {
+                routes: QUEUE_ROUTES,
+                imports: SQL_IMPORT,
+                support: None,
+                code: QUEUE_CODE,
+            },
+            Self::Cron => Example {
+                routes: CRON_ROUTES,
+                imports: "",
+                support: None,
+                code: CRON_CODE,
+            },
+            Self::Http => Example {
+                routes: HTTP_ROUTES,
+                imports: HTTP_CLIENT_IMPORT,
+                support: None,
+                code: HTTP_CODE,
+            },
+        }
+    }
+}
+
+impl FromStr for Service {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "kv" => Ok(Self::Kv),
+            "sql" | "db" => Ok(Self::Sql),
+            "storage" => Ok(Self::Storage),
+            "queue" => Ok(Self::Queue),
+            "cron" => Ok(Self::Cron),
+            "http" | "outbound-http" => Ok(Self::Http),
+            _ => Err(format!(
+                "unknown service: {s} (expected kv, sql, storage, queue, cron or http)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Kv => write!(f, "kv"),
+            Self::Sql => write!(f, "sql"),
+            Self::Storage => write!(f, "storage"),
+            Self::Queue => write!(f, "queue"),
+            Self::Cron => write!(f, "cron"),
+            Self::Http => write!(f, "http"),
+        }
+    }
+}
+
+/// Parse a comma-separated list of services, sorted and without duplicates.
+pub fn parse_services(list: &str) -> Result<Vec<Service>, String> {
+    let mut services = list
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(Service::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+    services.sort();
+    services.dedup();
+    Ok(services)
+}
+
+/// Example code for one service.
+struct Example {
+    /// Entries for the end of the `routes!` block.
+    routes: &'static str,
+    /// `use` lines.
+    imports: &'static str,
+    /// Items shared with other services.
+    support: Option<&'static str>,
+    /// Types and handlers.
+    code: &'static str,
+}
+
+/// Add the example routes and handlers of `services` to the rendered
+/// `lib.rs`, skipping `use` lines and shared items it already has.
+///
+/// Routes are added to the first `routes!` block, which must end with a `}`
+/// on its own line.
+pub fn add_examples(lib_rs: &str, services: &[Service]) -> String {
+    const PRELUDE: &str = "use mik_sdk::prelude::*;\n";
+    const ROUTES: &str = "routes! {\n";
+
+    let mut imports = String::new();
+    let mut routes = String::new();
+    let mut code = String::new();
+    for service in services {
+        let example = service.example();
+        for import in example.imports.lines() {
+            if !lib_rs.contains(import) && !imports.contains(import) {
+                imports.push_str(import);
+                imports.push('\n');
+            }
+        }
+        routes.push_str(example.routes);
+        if let Some(support) = example.support
+            && !code.contains(support)
+        {
+            code.push('\n');
+            code.push_str(support);
+        }
+        code.push('\n');
+        code.push_str(example.code);
+    }
+
+    // Routes go at the end of the routes! block
+    let mut out = lib_rs.to_string();
+    if let Some(at) = out
+        .find(ROUTES)
+        .and_then(|start| out[start..].find("\n}\n").map(|end| start + end + 1))
+    {
+        out.insert_str(at, &routes);
+    }
+    if let Some(at) = out.find(PRELUDE) {
+        out.insert_str(at + PRELUDE.len(), &imports);
+    }
+    out.push_str(&code);
+    out
+}
+
+// ============================================================================
+// Embedded Content
+// ============================================================================
+
+const HTTP_CLIENT_IMPORT: &str = "use mik_sdk::http_client as client;\n";
+
+const SQL_IMPORT: &str = "use bindings::mik::sql::database::{self, Value};\n";
+
+const DAEMON_SUPPORT: &str = r#"// ---- Daemon services ----
+
+/// The mik daemon, started by `mik dev` (`mik daemon` in production).
+/// Reached through outgoing HTTP: 127.0.0.1 is in http_allowed in mik.toml.
+const DAEMON_URL: &str = "http://127.0.0.1:9919";
+
+/// `text` as a JSON string literal.
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+"#;
+
+const KV_ROUTES: &str = r#"    GET "/kv/{key}" => kv_get(path: KeyPath),
+    PUT "/kv/{key}" => kv_set(path: KeyPath, body: KvInput),
+"#;
+
+const KV_CODE: &str = r#"// ---- KV ----
+
+/// Path parameter for KV keys
+#[derive(Path)]
+struct KeyPath {
+    key: String,
+}
+
+/// JSON body for storing a value
+#[derive(Type)]
+struct KvInput {
+    value: String,
+    /// Expiry in seconds
+    ttl: Option<u64>,
+}
+
+fn kv_get(path: KeyPath, _req: &Request) -> Response {
+    match client::get(&format!("{DAEMON_URL}/kv/{}", path.key)) {
+        Ok(response) => ok!({ "key": path.key, "entry": response.text() }),
+        Err(_) => error!(502, "KV service unavailable"),
+    }
+}
+
+fn kv_set(path: KeyPath, body: KvInput, _req: &Request) -> Response {
+    let ttl = body.ttl.map_or_else(String::new, |ttl| format!(",\"ttl\":{ttl}"));
+    let payload = format!("{{\"value\":{}{ttl}}}", json_string(&body.value));
+    let sent = client::request()
+        .put(&format!("{DAEMON_URL}/kv/{}", path.key))
+        .json(payload.as_bytes())
+        .send();
+
+    match sent {
+        Ok(_) => ok!({ "key": path.key, "stored": true }),
+        Err(_) => error!(502, "KV service unavailable"),
+    }
+}
+"#;
+
+const SQL_ROUTES: &str = r#"    GET "/records" => list_records,
+    POST "/records" => create_record(body: RecordInput),
+"#;
+
+const SQL_CODE: &str = r#"// ---- SQL ----
+
+/// JSON body for creating a record
+#[derive(Type)]
+struct RecordInput {
+    title: String,
+}
+
+const RECORDS_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    title TEXT NOT NULL
+)";
+
+fn list_records(_req: &Request) -> Response {
+    let listed = database::execute(RECORDS_SCHEMA, &[])
+        .and_then(|_| database::query("SELECT id, title FROM records ORDER BY id", &[]));
+    let Ok(result) = listed else {
+        return error!(500, "Failed to list records");
+    };
+
+    let titles: Vec<String> = result
+        .rows
+        .into_iter()
+        .filter_map(|row| match row.into_iter().nth(1) {
+            Some(Value::Text(title)) => Some(title),
+            _ => None,
+        })
+        .collect();
+    ok!({ "records": titles })
+}
+
+fn create_record(body: RecordInput, _req: &Request) -> Response {
+    guard!(!body.title.trim().is_empty(), 400, "Title is required");
+
+    let inserted = database::execute(RECORDS_SCHEMA, &[]).and_then(|_| {
+        database::execute(
+            "INSERT INTO records (title) VALUES (?)",
+            &[Value::Text(body.title)],
+        )
+    });
+    match inserted {
+        Ok(_) => ok!({ "created": true }),
+        Err(_) => error!(500, "Failed to create record"),
+    }
+}
+"#;
+
+const STORAGE_ROUTES: &str = r#"    GET "/files/{name}" => file_get(path: FilePath),
+    PUT "/files/{name}" => file_put(path: FilePath, body: FileInput),
+"#;
+
+const STORAGE_CODE: &str = r#"// ---- Storage ----
+
+/// Path parameter for stored files
+#[derive(Path)]
+struct FilePath {
+    name: String,
+}
+
+/// JSON body for storing a file
+#[derive(Type)]
+struct FileInput {
+    content: String,
+}
+
+fn file_get(path: FilePath, _req: &Request) -> Response {
+    match client::get(&format!("{DAEMON_URL}/storage/files/{}", path.name)) {
+        Ok(response) => ok!({ "name": path.name, "content": response.text() }),
+        Err(_) => error!(502, "Storage service unavailable"),
+    }
+}
+
+fn file_put(path: FilePath, body: FileInput, _req: &Request) -> Response {
+    let payload = format!("{{\"content\":{}}}", json_string(&body.content));
+    let sent = client::request()
+        .put(&format!("{DAEMON_URL}/storage/files/{}", path.name))
+        .json(payload.as_bytes())
+        .send();
+
+    match sent {
+        Ok(_) => ok!({ "name": path.name, "stored": true }),
+        Err(_) => error!(502, "Storage service unavailable"),
+    }
+}
+"#;
+
+const QUEUE_ROUTES: &str = r#"    POST "/queue/jobs" => enqueue_job(body: QueueJobInput),
+    POST "/queue/work" => drain_queue,
+"#;
+
+const QUEUE_CODE: &str = r#"// ---- Queue ----
+//
+// Jobs wait in the `queue` table; the schedule in mik.toml calls
+// POST /queue/work every 10 seconds to drain it.
+
+/// JSON body for queueing a job
+#[derive(Type)]
+struct QueueJobInput {
+    payload: String,
+}
+
+const QUEUE_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS queue (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    payload TEXT NOT NULL,
+    done INTEGER NOT NULL DEFAULT 0
+)";
+
+fn enqueue_job(body: QueueJobInput, _req: &Request) -> Response {
+    let queued = database::execute(QUEUE_SCHEMA, &[]).and_then(|_| {
+        database::execute(
+            "INSERT INTO queue (payload) VALUES (?)",
+            &[Value::Text(body.payload)],
+        )
+    });
+    match queued {
+        Ok(_) => ok!({ "queued": true }),
+        Err(_) => error!(500, "Failed to queue job"),
+    }
+}
+
+fn drain_queue(_req: &Request) -> Response {
+    let claimed = database::execute(QUEUE_SCHEMA, &[]).and_then(|_| {
+        database::query(
+            "UPDATE queue SET done = 1
+             WHERE id IN (SELECT id FROM queue WHERE done = 0 ORDER BY id LIMIT 10)
+             RETURNING payload",
+            &[],
+        )
+    });
+    let Ok(claimed) = claimed else {
+        return error!(500, "Failed to claim jobs");
+    };
+
+    // Process each job's payload here
+    ok!({ "processed": claimed.rows.len() })
+}
+"#;
+
+const QUEUE_SCHEDULE: &str = r#"
+# Drain the job queue every 10 seconds
+[[schedules]]
+name = "{{PROJECT_NAME}}-queue"
+module = "{{PROJECT_NAME}}"
+cron = "*/10 * * * * * *"
+method = "POST"
+path = "/queue/work"
+overlap = "skip"
+"#;
+
+const CRON_ROUTES: &str = r#"    POST "/tasks/tick" => tick,
+"#;
+
+const CRON_CODE: &str = r#"// ---- Cron ----
+
+/// Called every 5 minutes by the schedule in mik.toml.
+fn tick(_req: &Request) -> Response {
+    // Periodic work goes here
+    ok!({ "ran": true })
+}
+"#;
+
+const CRON_SCHEDULE: &str = r#"
+# Run POST /tasks/tick every 5 minutes
+[[schedules]]
+name = "{{PROJECT_NAME}}-tick"
+module = "{{PROJECT_NAME}}"
+cron = "0 */5 * * * * *"
+method = "POST"
+path = "/tasks/tick"
+overlap = "skip"
+"#;
+
+const HTTP_ROUTES: &str = r#"    GET "/upstream" => upstream,
+"#;
+
+const HTTP_CODE: &str = r#"// ---- Outbound HTTP ----
+
+/// External API. Its host must be allowed by http_allowed in mik.toml,
+/// which reads API_HOST from .env.
+const API_URL: &str = "https://httpbin.org/get";
+
+fn upstream(_req: &Request) -> Response {
+    match client::get(API_URL) {
+        Ok(response) => ok!({ "upstream": response.text() }),
+        Err(_) => error!(502, "Upstream API unavailable"),
+    }
+}
+"#;
+
+const HTTP_ENV: &str = "# Host the module may call (http_allowed in mik.toml)
+API_HOST=httpbin.org
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_services() {
+        assert_eq!(
+            parse_services("sql, kv,sql"),
+            Ok(vec![Service::Kv, Service::Sql])
+        );
+        assert_eq!(parse_services(""), Ok(vec![]));
+        assert!(parse_services("kv,redis").is_err());
+
+        for service in Service::ALL {
+            assert_eq!(service.to_string().parse::<Service>(), Ok(*service));
+        }
+    }
+
+    #[test]
+    fn test_add_examples() {
+        let lib_rs = "use mik_sdk::prelude::*;\n\nroutes! {\n    GET \"/health\" => health,\n}\n";
+        let out = add_examples(lib_rs, &[Service::Kv, Service::Storage, Service::Sql]);
+
+        assert!(
+            out.contains("    GET \"/health\" => health,\n    GET \"/kv/{key}\""),
+            "{out}"
+        );
+        assert!(
+            out.contains("create_record(body: RecordInput),\n}"),
+            "{out}"
+        );
+        // Shared imports and items appear once
+        assert_eq!(
+            out.matches("use mik_sdk::http_client as client;").count(),
+            1
+        );
+        assert_eq!(out.matches("const DAEMON_URL").count(), 1);
+        assert!(out.contains("fn list_records"));
+
+        // Imports the template already has are not repeated
+        let with_sql = format!("{SQL_IMPORT}{lib_rs}");
+        let out = add_examples(&with_sql, &[Service::Queue]);
+        assert_eq!(out.matches(SQL_IMPORT.trim()).count(), 1);
+    }
+}
@@ -1,6 +1,12 @@
 //! Templates for project scaffolding.
 //!
 //! WIT interfaces are fetched from OCI registry to ensure consistency with the bridge.
+//!
+//! Besides the basic handler and REST API, Rust has opinionated templates
+//! backed by the `mik:sql` host interface: a CRUD API, a queue worker and a
+//! scheduled job (both driven by `[[schedules]]`), and API key auth
+//! middleware. They come with `mik test` cases in `tests/` and typed
+//! `routes!` inputs, from which `mik build` extracts the OpenAPI schema.
 
 use std::fmt;
 use std::fs;
@@ -8,6 +14,8 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 
+use super::services::{self, Service};
+
 /// Default version for new projects.
 pub const DEFAULT_VERSION: &str = "0.1.0";
 
@@ -35,7 +43,14 @@ impl Language {
     /// Get available templates for this language.
     pub const fn available_templates(&self) -> &[Template] {
         match self {
-            Self::Rust => &[Template::Basic, Template::RestApi],
+            Self::Rust => &[
+                Template::Basic,
+                Template::RestApi,
+                Template::Crud,
+                Template::Worker,
+                Template::Cron,
+                Template::Auth,
+            ],
             Self::TypeScript => &[Template::Basic, Template::RestApi],
         }
     }
@@ -56,6 +71,10 @@ pub enum Template {
     #[default]
     Basic,
     RestApi,
+    Crud,
+    Worker,
+    Cron,
+    Auth,
 }
 
 impl std::str::FromStr for Template {
@@ -65,6 +84,10 @@ impl std::str::FromStr for Template {
         match s.to_lowercase().as_str() {
             "basic" => Ok(Self::Basic),
             "rest-api" | "restapi" | "rest_api" => Ok(Self::RestApi),
+            "crud" => Ok(Self::Crud),
+            "worker" | "queue-worker" => Ok(Self::Worker),
+            "cron" | "cron-job" => Ok(Self::Cron),
+            "auth" => Ok(Self::Auth),
             _ => Err(format!("unknown template: {s}")),
         }
     }
@@ -76,8 +99,17 @@ impl Template {
         match self {
             Self::Basic => "Simple hello world handler",
             Self::RestApi => "CRUD REST API with typed inputs",
+            Self::Crud => "CRUD REST API stored in SQL, with tests",
+            Self::Worker => "Queue worker draining a SQL job queue on a schedule",
+            Self::Cron => "Scheduled cleanup job run by [[schedules]]",
+            Self::Auth => "API key auth middleware in front of routes",
         }
     }
+
+    /// Whether the template uses the `mik:sql` host interface.
+    pub const fn uses_sql(self) -> bool {
+        matches!(self, Self::Crud | Self::Worker | Self::Cron | Self::Auth)
+    }
 }
 
 impl fmt::Display for Template {
@@ -85,6 +117,10 @@ impl fmt::Display for Template {
         match self {
             Self::Basic => write!(f, "basic"),
             Self::RestApi => write!(f, "rest-api"),
+            Self::Crud => write!(f, "crud"),
+            Self::Worker => write!(f, "worker"),
+            Self::Cron => write!(f, "cron"),
+            Self::Auth => write!(f, "auth"),
         }
     }
 }
@@ -116,18 +152,20 @@ impl TemplateContext {
     }
 }
 
-/// Generate a project from templates.
+/// Generate a project from templates, with examples for `services` (Rust
+/// only).
 ///
 /// WIT content is passed in from OCI download to ensure consistency with bridge.
 pub fn generate_project(
     dir: &Path,
     lang: Language,
     template: Template,
+    services: &[Service],
     ctx: &TemplateContext,
     wit_content: &str,
 ) -> Result<()> {
     match lang {
-        Language::Rust => generate_rust_project(dir, template, ctx, wit_content),
+        Language::Rust => generate_rust_project(dir, template, services, ctx, wit_content),
         Language::TypeScript => generate_typescript_project(dir, template, ctx, wit_content),
     }
 }
@@ -147,37 +185,92 @@ fn create_common_dirs(dir: &Path) -> Result<()> {
 fn generate_rust_project(
     dir: &Path,
     template: Template,
+    services: &[Service],
     ctx: &TemplateContext,
     wit_content: &str,
 ) -> Result<()> {
+    let uses_sql = template.uses_sql() || services.iter().any(|s| s.uses_sql());
+
     // Create directories
     create_common_dirs(dir)?;
     fs::create_dir_all(dir.join("modules")).context("failed to create modules directory")?;
 
     // Cargo.toml
-    let cargo_toml = ctx.render(RUST_CARGO_TOML);
+    let mut cargo_toml = ctx.render(RUST_CARGO_TOML);
+    if uses_sql {
+        cargo_toml.push_str(RUST_SQL_DEPENDENCY);
+    }
     fs::write(dir.join("Cargo.toml"), &cargo_toml).context("failed to write Cargo.toml")?;
 
     // mik.toml
-    let mik_toml = generate_mik_toml(ctx, Language::Rust)?;
+    let mut mik_toml = generate_mik_toml(ctx, Language::Rust, template, services)?;
+    let schedule = match template {
+        Template::Worker => Some(RUST_WORKER_SCHEDULE),
+        Template::Cron => Some(RUST_CRON_SCHEDULE),
+        _ => None,
+    };
+    for schedule in schedule
+        .into_iter()
+        .chain(services.iter().filter_map(|s| s.schedule()))
+    {
+        mik_toml.push_str(&ctx.render(schedule));
+    }
     fs::write(dir.join("mik.toml"), &mik_toml).context("failed to write mik.toml")?;
 
     // src/lib.rs
     let lib_content = match template {
         Template::Basic => RUST_BASIC_LIB_RS,
         Template::RestApi => RUST_RESTAPI_LIB_RS,
+        Template::Crud => RUST_CRUD_LIB_RS,
+        Template::Worker => RUST_WORKER_LIB_RS,
+        Template::Cron => RUST_CRON_LIB_RS,
+        Template::Auth => RUST_AUTH_LIB_RS,
     };
     let lib_rs = ctx.render(&format!("{RUST_LIB_HEADER}{lib_content}"));
+    let lib_rs = services::add_examples(&lib_rs, services);
     fs::write(dir.join("src/lib.rs"), &lib_rs).context("failed to write src/lib.rs")?;
 
-    // WIT files (fetched from OCI)
-    let world_wit = ctx.render(RUST_WORLD_WIT);
+    // WIT files (fetched from OCI; mik:sql ships with mik)
+    let world_wit = if uses_sql {
+        ctx.render(RUST_SQL_WORLD_WIT)
+    } else {
+        ctx.render(RUST_WORLD_WIT)
+    };
     fs::write(dir.join("wit/world.wit"), &world_wit).context("failed to write wit/world.wit")?;
     fs::write(dir.join("wit/deps/core/core.wit"), wit_content)
         .context("failed to write wit/deps/core/core.wit")?;
+    if uses_sql {
+        fs::create_dir_all(dir.join("wit/deps/sql"))
+            .context("failed to create wit/deps/sql directory")?;
+        fs::write(dir.join("wit/deps/sql/sql.wit"), SQL_WIT)
+            .context("failed to write wit/deps/sql/sql.wit")?;
+    }
+
+    // tests/ and README.md
+    let extras = match template {
+        Template::Crud => Some((RUST_CRUD_TESTS, RUST_CRUD_README)),
+        Template::Worker => Some((RUST_WORKER_TESTS, RUST_WORKER_README)),
+        Template::Cron => Some((RUST_CRON_TESTS, RUST_CRON_README)),
+        Template::Auth => Some((RUST_AUTH_TESTS, RUST_AUTH_README)),
+        Template::Basic | Template::RestApi => None,
+    };
+    if let Some((tests, readme)) = extras {
+        fs::create_dir_all(dir.join("tests")).context("failed to create tests directory")?;
+        fs::write(dir.join("tests/api.test.yaml"), ctx.render(tests))
+            .context("failed to write tests/api.test.yaml")?;
+        fs::write(dir.join("README.md"), ctx.render(readme))
+            .context("failed to write README.md")?;
+    }
+
+    // .env (local dev settings for the services)
+    let env: String = services.iter().filter_map(|s| s.env()).collect();
+    let mut gitignore = format!("{RUST_GITIGNORE_EXTRA}{COMMON_GITIGNORE}");
+    if !env.is_empty() {
+        fs::write(dir.join(".env"), &env).context("failed to write .env")?;
+        gitignore.push_str(".env\n");
+    }
 
     // .gitignore
-    let gitignore = format!("{RUST_GITIGNORE_EXTRA}{COMMON_GITIGNORE}");
     fs::write(dir.join(".gitignore"), &gitignore).context("failed to write .gitignore")?;
 
     // modules/.gitkeep
@@ -200,7 +293,7 @@ fn generate_typescript_project(
     create_common_dirs(dir)?;
 
     // mik.toml
-    let mik_toml = generate_mik_toml(ctx, Language::TypeScript)?;
+    let mik_toml = generate_mik_toml(ctx, Language::TypeScript, template, &[])?;
     fs::write(dir.join("mik.toml"), &mik_toml).context("failed to write mik.toml")?;
 
     // package.json
@@ -212,8 +305,8 @@ fn generate_typescript_project(
 
     // src/component.ts - select based on template
     let component_content = match template {
-        Template::Basic => TS_COMPONENT,
         Template::RestApi => TS_RESTAPI_COMPONENT,
+        _ => TS_COMPONENT,
     };
     let component_ts = ctx.render(component_content);
     fs::write(dir.join("src/component.ts"), &component_ts)
@@ -227,8 +320,8 @@ fn generate_typescript_project(
 
     // README.md - select based on template
     let readme_content = match template {
-        Template::Basic => TS_README,
         Template::RestApi => TS_RESTAPI_README,
+        _ => TS_README,
     };
     let readme = ctx.render(readme_content);
     fs::write(dir.join("README.md"), &readme).context("failed to write README.md")?;
@@ -244,9 +337,22 @@ fn generate_typescript_project(
 // mik.toml generation
 // ============================================================================
 
-fn generate_mik_toml(ctx: &TemplateContext, lang: Language) -> Result<String> {
+fn generate_mik_toml(
+    ctx: &TemplateContext,
+    lang: Language,
+    template: Template,
+    services: &[Service],
+) -> Result<String> {
     use crate::manifest::{Author, CompositionConfig, Manifest, Project, ServerConfig};
 
+    let uses_sql = template.uses_sql() || services.iter().any(|s| s.uses_sql());
+    let mut http_allowed: Vec<String> = Vec::new();
+    for host in services.iter().filter_map(|s| s.http_host()) {
+        if !http_allowed.iter().any(|h| h == host) {
+            http_allowed.push(host.to_string());
+        }
+    }
+
     let manifest = Manifest {
         project: Project {
             name: ctx.project_name.clone(),
@@ -267,6 +373,13 @@ fn generate_mik_toml(ctx: &TemplateContext, lang: Language) -> Result<String> {
         server: ServerConfig {
             port: 3000,
             modules: "modules/".to_string(),
+            // Grant the module the mik:sql host interface
+            sql_modules: if uses_sql {
+                vec![ctx.project_name.clone()]
+            } else {
+                Vec::new()
+            },
+            http_allowed,
             ..Default::default()
         },
         composition: CompositionConfig {
@@ -436,6 +549,925 @@ world {{PROJECT_NAME}} {
 }
 ";
 
+// --- Rust SQL-backed templates ---
+
+/// World for templates importing `mik:sql`.
+const RUST_SQL_WORLD_WIT: &str = r"package mik:{{PROJECT_NAME}}@{{VERSION}};
+
+world {{PROJECT_NAME}} {
+    // Query the mik SQL database (granted by sql_modules in mik.toml)
+    import mik:sql/database@0.1.0;
+
+    // Export the handler
+    export mik:core/handler@0.1.0;
+}
+";
+
+/// `mik:sql` WIT package, as served by the runtime.
+const SQL_WIT: &str = include_str!("../../../wit/sql.wit");
+
+/// Cargo.toml line resolving `mik:sql` from wit/deps.
+const RUST_SQL_DEPENDENCY: &str = r#""mik:sql" = { path = "wit/deps/sql" }
+"#;
+
+const RUST_CRUD_LIB_RS: &str = r#"//! {{PROJECT_NAME}} - A CRUD REST API stored in SQL
+//!
+//! Items live in the mik SQL database, reached through the `mik:sql` host
+//! interface (granted by `sql_modules` in mik.toml). The typed inputs in
+//! `routes!` are the API's OpenAPI schema: `mik build` writes it next to the
+//! component in dist/.
+
+use bindings::mik::sql::database::{self, Value};
+
+// ---- Types ----
+
+/// Path parameter for item ID
+#[derive(Path)]
+struct ItemPath {
+    id: i64,
+}
+
+/// Query parameters for listing items
+#[derive(Query)]
+struct ListQuery {
+    #[field(default = 1)]
+    page: u32,
+    #[field(default = 20)]
+    limit: u32,
+}
+
+/// JSON body for creating/updating items
+#[derive(Type)]
+struct ItemInput {
+    name: String,
+    description: Option<String>,
+}
+
+/// Item response structure
+#[derive(Type)]
+struct Item {
+    id: i64,
+    name: String,
+    description: Option<String>,
+}
+
+// ---- Routes with typed inputs ----
+
+routes! {
+    GET "/health" => health,
+    GET "/items" => list_items(query: ListQuery),
+    GET "/items/{id}" => get_item(path: ItemPath) -> Item,
+    POST "/items" => create_item(body: ItemInput) -> Item,
+    PUT "/items/{id}" => update_item(path: ItemPath, body: ItemInput) -> Item,
+    DELETE "/items/{id}" => delete_item(path: ItemPath),
+}
+
+// ---- Storage ----
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    description TEXT
+)";
+
+/// Query items, creating the table on first use.
+fn query_items(sql: &str, params: &[Value]) -> Result<Vec<Item>, database::SqlError> {
+    database::execute(SCHEMA, &[])?;
+    let result = database::query(sql, params)?;
+    Ok(result.rows.into_iter().map(item_from_row).collect())
+}
+
+/// An item from an `id, name, description` row.
+fn item_from_row(row: Vec<Value>) -> Item {
+    let mut values = row.into_iter();
+    let id = match values.next() {
+        Some(Value::Integer(id)) => id,
+        _ => 0,
+    };
+    let name = match values.next() {
+        Some(Value::Text(name)) => name,
+        _ => String::new(),
+    };
+    let description = match values.next() {
+        Some(Value::Text(description)) => Some(description),
+        _ => None,
+    };
+    Item { id, name, description }
+}
+
+fn text_or_null(value: Option<String>) -> Value {
+    value.map_or(Value::Null, Value::Text)
+}
+
+// ---- Handlers ----
+
+fn health(_req: &Request) -> Response {
+    ok!({ "status": "healthy" })
+}
+
+fn list_items(query: ListQuery, _req: &Request) -> Response {
+    let limit = query.limit.clamp(1, 100);
+    let offset = (query.page.max(1) - 1) * limit;
+    let Ok(items) = query_items(
+        "SELECT id, name, description FROM items ORDER BY id LIMIT ? OFFSET ?",
+        &[Value::Integer(limit.into()), Value::Integer(offset.into())],
+    ) else {
+        return error!(500, "Failed to list items");
+    };
+
+    ok!({
+        "items": items,
+        "page": query.page,
+        "limit": limit
+    })
+}
+
+fn get_item(path: ItemPath, _req: &Request) -> Response {
+    let Ok(mut items) = query_items(
+        "SELECT id, name, description FROM items WHERE id = ?",
+        &[Value::Integer(path.id)],
+    ) else {
+        return error!(500, "Failed to read item");
+    };
+
+    match items.pop() {
+        Some(item) => ok!(item),
+        None => not_found!("Item not found"),
+    }
+}
+
+fn create_item(body: ItemInput, _req: &Request) -> Response {
+    guard!(!body.name.trim().is_empty(), 400, "Name is required");
+
+    let Ok(mut items) = query_items(
+        "INSERT INTO items (name, description) VALUES (?, ?) RETURNING id, name, description",
+        &[Value::Text(body.name), text_or_null(body.description)],
+    ) else {
+        return error!(500, "Failed to create item");
+    };
+
+    match items.pop() {
+        Some(item) => created!("/items/{}", item.id, item),
+        None => error!(500, "Failed to create item"),
+    }
+}
+
+fn update_item(path: ItemPath, body: ItemInput, _req: &Request) -> Response {
+    guard!(!body.name.trim().is_empty(), 400, "Name is required");
+
+    let Ok(mut items) = query_items(
+        "UPDATE items SET name = ?, description = ? WHERE id = ? RETURNING id, name, description",
+        &[
+            Value::Text(body.name),
+            text_or_null(body.description),
+            Value::Integer(path.id),
+        ],
+    ) else {
+        return error!(500, "Failed to update item");
+    };
+
+    match items.pop() {
+        Some(item) => ok!(item),
+        None => not_found!("Item not found"),
+    }
+}
+
+fn delete_item(path: ItemPath, _req: &Request) -> Response {
+    let deleted = database::execute(SCHEMA, &[])
+        .and_then(|_| database::execute("DELETE FROM items WHERE id = ?", &[Value::Integer(path.id)]));
+
+    match deleted {
+        Ok(0) => not_found!("Item not found"),
+        Ok(_) => no_content!(),
+        Err(_) => error!(500, "Failed to delete item"),
+    }
+}
+"#;
+
+const RUST_CRUD_TESTS: &str = r#"# Run with: mik build && mik test
+cases:
+  - name: creates an item
+    request:
+      method: POST
+      path: /items
+      json: { name: Book, description: Paperback }
+    expect:
+      status: 201
+      json: { name: Book, description: Paperback }
+
+  - name: lists items
+    seed:
+      sql: |
+        CREATE TABLE items (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, description TEXT);
+        INSERT INTO items (name) VALUES ('Pen');
+    request:
+      path: /items
+    expect:
+      status: 200
+      json: { items: [{ name: Pen }], page: 1 }
+
+  - name: updates an item
+    seed:
+      sql: |
+        CREATE TABLE items (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, description TEXT);
+        INSERT INTO items (id, name) VALUES (7, 'Pen');
+    request:
+      method: PUT
+      path: /items/7
+      json: { name: Pencil }
+    expect:
+      status: 200
+      json: { id: 7, name: Pencil }
+
+  - name: unknown item is not found
+    request:
+      path: /items/999
+    expect:
+      status: 404
+
+  - name: rejects an empty name
+    request:
+      method: POST
+      path: /items
+      json: { name: "" }
+    expect:
+      status: 400
+"#;
+
+const RUST_CRUD_README: &str = r#"# {{PROJECT_NAME}}
+
+A CRUD REST API built with mik, storing items in the mik SQL database
+through the `mik:sql` host interface.
+
+## Build, test and run
+
+```bash
+mik build -rc   # Also writes dist/{{PROJECT_NAME}}-composed.openapi.json
+mik test        # Runs tests/api.test.yaml
+mik dev         # Serves /run/{{PROJECT_NAME}}/*, rebuilding on changes
+```
+
+## API
+
+| Method | Path | Description |
+|--------|------|-------------|
+| GET | /health | Health check |
+| GET | /items | List items (`?page=&limit=`) |
+| GET | /items/{id} | Get an item |
+| POST | /items | Create an item |
+| PUT | /items/{id} | Update an item |
+| DELETE | /items/{id} | Delete an item |
+
+```bash
+curl -X POST http://localhost:3000/run/{{PROJECT_NAME}}/items \
+  -H 'Content-Type: application/json' \
+  -d '{"name": "Book", "description": "Paperback"}'
+```
+
+SQL access is granted in mik.toml (`sql_modules = ["{{PROJECT_NAME}}"]`).
+The `items` table is created on first use.
+"#;
+
+const RUST_WORKER_SCHEDULE: &str = r#"
+# Drain the job queue every 10 seconds
+[[schedules]]
+name = "{{PROJECT_NAME}}-work"
+module = "{{PROJECT_NAME}}"
+cron = "*/10 * * * * * *"
+method = "POST"
+path = "/work"
+overlap = "skip"
+"#;
+
+const RUST_WORKER_LIB_RS: &str = r#"//! {{PROJECT_NAME}} - A queue worker
+//!
+//! Jobs are queued in the mik SQL database (`POST /jobs`) and processed in
+//! batches by `POST /work`, which a `[[schedules]]` entry in mik.toml calls
+//! every 10 seconds. Failed jobs are retried up to `MAX_ATTEMPTS` times.
+
+use bindings::mik::sql::database::{self, Value};
+
+/// Jobs claimed per `POST /work`.
+const BATCH_SIZE: i64 = 10;
+/// Attempts before a job is marked failed.
+const MAX_ATTEMPTS: i64 = 3;
+
+// ---- Types ----
+
+/// Path parameter for job ID
+#[derive(Path)]
+struct JobPath {
+    id: i64,
+}
+
+/// JSON body for queueing a job
+#[derive(Type)]
+struct JobInput {
+    kind: String,
+    payload: Option<String>,
+}
+
+/// Job response structure
+#[derive(Type)]
+struct Job {
+    id: i64,
+    kind: String,
+    status: String,
+    attempts: i64,
+    result: Option<String>,
+}
+
+// ---- Routes with typed inputs ----
+
+routes! {
+    GET "/health" => health,
+    POST "/jobs" => enqueue(body: JobInput) -> Job,
+    GET "/jobs/{id}" => get_job(path: JobPath) -> Job,
+    POST "/work" => work,
+}
+
+// ---- Queue ----
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    payload TEXT,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    result TEXT
+)";
+
+const JOB_COLUMNS: &str = "id, kind, status, attempts, result";
+
+/// Query jobs, creating the table on first use.
+fn query_jobs(sql: &str, params: &[Value]) -> Result<Vec<Job>, database::SqlError> {
+    database::execute(SCHEMA, &[])?;
+    let result = database::query(sql, params)?;
+    Ok(result.rows.into_iter().map(job_from_row).collect())
+}
+
+/// A job from a `JOB_COLUMNS` row.
+fn job_from_row(row: Vec<Value>) -> Job {
+    let mut job = Job {
+        id: 0,
+        kind: String::new(),
+        status: String::new(),
+        attempts: 0,
+        result: None,
+    };
+    for (column, value) in row.into_iter().enumerate() {
+        match (column, value) {
+            (0, Value::Integer(id)) => job.id = id,
+            (1, Value::Text(kind)) => job.kind = kind,
+            (2, Value::Text(status)) => job.status = status,
+            (3, Value::Integer(attempts)) => job.attempts = attempts,
+            (4, Value::Text(result)) => job.result = Some(result),
+            _ => {},
+        }
+    }
+    job
+}
+
+/// Do the work for one job. Replace with your own job kinds.
+fn process(kind: &str, payload: Option<&str>) -> Result<String, String> {
+    match kind {
+        "echo" => Ok(payload.unwrap_or_default().to_string()),
+        "uppercase" => Ok(payload.unwrap_or_default().to_uppercase()),
+        _ => Err(format!("unknown job kind: {kind}")),
+    }
+}
+
+// ---- Handlers ----
+
+fn health(_req: &Request) -> Response {
+    ok!({ "status": "healthy" })
+}
+
+fn enqueue(body: JobInput, _req: &Request) -> Response {
+    guard!(!body.kind.trim().is_empty(), 400, "Kind is required");
+
+    let payload = body.payload.map_or(Value::Null, Value::Text);
+    let Ok(mut jobs) = query_jobs(
+        &format!("INSERT INTO jobs (kind, payload) VALUES (?, ?) RETURNING {JOB_COLUMNS}"),
+        &[Value::Text(body.kind), payload],
+    ) else {
+        return error!(500, "Failed to queue job");
+    };
+
+    match jobs.pop() {
+        Some(job) => created!("/jobs/{}", job.id, job),
+        None => error!(500, "Failed to queue job"),
+    }
+}
+
+fn get_job(path: JobPath, _req: &Request) -> Response {
+    let Ok(mut jobs) = query_jobs(
+        &format!("SELECT {JOB_COLUMNS} FROM jobs WHERE id = ?"),
+        &[Value::Integer(path.id)],
+    ) else {
+        return error!(500, "Failed to read job");
+    };
+
+    match jobs.pop() {
+        Some(job) => ok!(job),
+        None => not_found!("Job not found"),
+    }
+}
+
+fn work(_req: &Request) -> Response {
+    // Claim a batch, so overlapping runs never process the same job
+    let claimed = database::execute(SCHEMA, &[]).and_then(|_| {
+        database::query(
+            "UPDATE jobs SET status = 'running', attempts = attempts + 1
+             WHERE id IN (SELECT id FROM jobs WHERE status = 'pending' ORDER BY id LIMIT ?)
+             RETURNING id, kind, payload, attempts",
+            &[Value::Integer(BATCH_SIZE)],
+        )
+    });
+    let Ok(claimed) = claimed else {
+        return error!(500, "Failed to claim jobs");
+    };
+
+    let (mut done, mut failed) = (0, 0);
+    for row in claimed.rows {
+        let (Some(Value::Integer(id)), Some(Value::Text(kind)), Some(payload), Some(Value::Integer(attempts))) =
+            (row.first(), row.get(1), row.get(2), row.get(3))
+        else {
+            continue;
+        };
+        let payload = match payload {
+            Value::Text(payload) => Some(payload.as_str()),
+            _ => None,
+        };
+
+        let (status, result) = match process(kind, payload) {
+            Ok(output) => {
+                done += 1;
+                ("done", output)
+            },
+            Err(e) => {
+                failed += 1;
+                let status = if *attempts >= MAX_ATTEMPTS { "failed" } else { "pending" };
+                (status, e)
+            },
+        };
+        let _ = database::execute(
+            "UPDATE jobs SET status = ?, result = ? WHERE id = ?",
+            &[
+                Value::Text(status.to_string()),
+                Value::Text(result),
+                Value::Integer(*id),
+            ],
+        );
+    }
+
+    ok!({ "done": done, "failed": failed })
+}
+"#;
+
+const RUST_WORKER_TESTS: &str = r#"# Run with: mik build && mik test
+cases:
+  - name: queues a job
+    request:
+      method: POST
+      path: /jobs
+      json: { kind: uppercase, payload: hello }
+    expect:
+      status: 201
+      json: { kind: uppercase, status: pending, attempts: 0 }
+
+  - name: processes pending jobs
+    seed:
+      sql: |
+        CREATE TABLE jobs (id INTEGER PRIMARY KEY AUTOINCREMENT, kind TEXT NOT NULL, payload TEXT,
+          status TEXT NOT NULL DEFAULT 'pending', attempts INTEGER NOT NULL DEFAULT 0, result TEXT);
+        INSERT INTO jobs (kind, payload) VALUES ('uppercase', 'hello'), ('unknown', NULL);
+    request:
+      method: POST
+      path: /work
+    expect:
+      status: 200
+      json: { done: 1, failed: 1 }
+
+  - name: reports a job's result
+    seed:
+      sql: |
+        CREATE TABLE jobs (id INTEGER PRIMARY KEY AUTOINCREMENT, kind TEXT NOT NULL, payload TEXT,
+          status TEXT NOT NULL DEFAULT 'pending', attempts INTEGER NOT NULL DEFAULT 0, result TEXT);
+        INSERT INTO jobs (id, kind, status, attempts, result) VALUES (3, 'echo', 'done', 1, 'hi');
+    request:
+      path: /jobs/3
+    expect:
+      status: 200
+      json: { id: 3, status: done, result: hi }
+"#;
+
+const RUST_WORKER_README: &str = r#"# {{PROJECT_NAME}}
+
+A queue worker built with mik. Jobs are queued in the mik SQL database and
+processed in batches by `POST /work`, which the `[[schedules]]` entry in
+mik.toml calls every 10 seconds (skipped while a run is still going).
+
+## Build, test and run
+
+```bash
+mik build -rc
+mik test        # Runs tests/api.test.yaml
+mik dev         # Starts the daemon, which runs the schedule
+```
+
+## API
+
+| Method | Path | Description |
+|--------|------|-------------|
+| GET | /health | Health check |
+| POST | /jobs | Queue a job (`{"kind": "uppercase", "payload": "hi"}`) |
+| GET | /jobs/{id} | Job status and result |
+| POST | /work | Process up to 10 pending jobs |
+
+Add job kinds in `process()` in src/lib.rs. A failing job is retried on
+the next run, up to 3 attempts.
+"#;
+
+const RUST_CRON_SCHEDULE: &str = r#"
+# Delete old events every day at 03:00 UTC
+[[schedules]]
+name = "{{PROJECT_NAME}}-cleanup"
+module = "{{PROJECT_NAME}}"
+cron = "0 0 3 * * * *"
+method = "POST"
+path = "/tasks/cleanup"
+overlap = "skip"
+"#;
+
+const RUST_CRON_LIB_RS: &str = r#"//! {{PROJECT_NAME}} - A scheduled job
+//!
+//! Records events in the mik SQL database, and deletes the ones older than
+//! the retention period in `POST /tasks/cleanup`, which a `[[schedules]]`
+//! entry in mik.toml calls every day.
+
+use bindings::mik::sql::database::{self, Value};
+
+// ---- Types ----
+
+/// JSON body for recording an event
+#[derive(Type)]
+struct EventInput {
+    name: String,
+}
+
+/// Query parameters for the cleanup task
+#[derive(Query)]
+struct CleanupQuery {
+    #[field(default = 30)]
+    days: u32,
+}
+
+// ---- Routes with typed inputs ----
+
+routes! {
+    GET "/health" => health,
+    POST "/events" => record_event(body: EventInput),
+    GET "/events/stats" => stats,
+    POST "/tasks/cleanup" => cleanup(query: CleanupQuery),
+}
+
+// ---- Storage ----
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+)";
+
+/// Run a statement, creating the table on first use.
+fn execute(sql: &str, params: &[Value]) -> Result<u64, database::SqlError> {
+    database::execute(SCHEMA, &[])?;
+    database::execute(sql, params)
+}
+
+// ---- Handlers ----
+
+fn health(_req: &Request) -> Response {
+    ok!({ "status": "healthy" })
+}
+
+fn record_event(body: EventInput, _req: &Request) -> Response {
+    guard!(!body.name.trim().is_empty(), 400, "Name is required");
+
+    match execute("INSERT INTO events (name) VALUES (?)", &[Value::Text(body.name)]) {
+        Ok(_) => created!("/events/stats", { "recorded": true }),
+        Err(_) => error!(500, "Failed to record event"),
+    }
+}
+
+fn stats(_req: &Request) -> Response {
+    let counted = database::execute(SCHEMA, &[])
+        .and_then(|_| database::query("SELECT COUNT(*) FROM events", &[]));
+
+    match counted.map(|result| result.rows.into_iter().next()) {
+        Ok(Some(row)) => match row.first() {
+            Some(Value::Integer(count)) => ok!({ "events": *count }),
+            _ => ok!({ "events": 0 }),
+        },
+        Ok(None) => ok!({ "events": 0 }),
+        Err(_) => error!(500, "Failed to count events"),
+    }
+}
+
+fn cleanup(query: CleanupQuery, _req: &Request) -> Response {
+    let cutoff_secs = i64::from(query.days) * 86_400;
+    match execute(
+        "DELETE FROM events WHERE created_at < unixepoch() - ?",
+        &[Value::Integer(cutoff_secs)],
+    ) {
+        Ok(deleted) => ok!({ "deleted": deleted, "retention_days": query.days }),
+        Err(_) => error!(500, "Cleanup failed"),
+    }
+}
+"#;
+
+const RUST_CRON_TESTS: &str = r#"# Run with: mik build && mik test
+cases:
+  - name: deletes events past retention
+    seed:
+      sql: |
+        CREATE TABLE events (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL,
+          created_at INTEGER NOT NULL DEFAULT (unixepoch()));
+        INSERT INTO events (name, created_at) VALUES ('old', unixepoch() - 40 * 86400);
+        INSERT INTO events (name) VALUES ('new');
+    request:
+      method: POST
+      path: /tasks/cleanup
+    expect:
+      status: 200
+      json: { deleted: 1, retention_days: 30 }
+
+  - name: honors a custom retention
+    seed:
+      sql: |
+        CREATE TABLE events (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL,
+          created_at INTEGER NOT NULL DEFAULT (unixepoch()));
+        INSERT INTO events (name, created_at) VALUES ('old', unixepoch() - 40 * 86400);
+    request:
+      method: POST
+      path: /tasks/cleanup?days=60
+    expect:
+      status: 200
+      json: { deleted: 0 }
+
+  - name: records an event
+    request:
+      method: POST
+      path: /events
+      json: { name: signup }
+    expect:
+      status: 201
+"#;
+
+const RUST_CRON_README: &str = r#"# {{PROJECT_NAME}}
+
+A scheduled job built with mik. `POST /tasks/cleanup` deletes events older
+than 30 days (`?days=` overrides it); the `[[schedules]]` entry in mik.toml
+calls it every day at 03:00 UTC.
+
+## Build, test and run
+
+```bash
+mik build -rc
+mik test        # Runs tests/api.test.yaml
+mik dev         # Starts the daemon, which runs the schedule
+```
+
+## API
+
+| Method | Path | Description |
+|--------|------|-------------|
+| GET | /health | Health check |
+| POST | /events | Record an event (`{"name": "signup"}`) |
+| GET | /events/stats | Number of stored events |
+| POST | /tasks/cleanup | Delete events past retention (`?days=30`) |
+
+Trigger the job by hand with `curl -X POST http://127.0.0.1:9919/cron/{{PROJECT_NAME}}-cleanup/trigger`,
+and see its runs with `mik cron history {{PROJECT_NAME}}-cleanup`.
+"#;
+
+const RUST_AUTH_LIB_RS: &str = r#"//! {{PROJECT_NAME}} - API key auth middleware
+//!
+//! Every route but `/health` goes through `authenticate`, which checks the
+//! `Authorization: Bearer <key>` header against the `api_keys` table in the
+//! mik SQL database and returns the caller's user ID, or the 401 response.
+
+use bindings::mik::sql::database::{self, Value};
+
+// ---- Types ----
+
+/// JSON body for creating a note
+#[derive(Type)]
+struct NoteInput {
+    text: String,
+}
+
+/// Note response structure
+#[derive(Type)]
+struct Note {
+    id: i64,
+    text: String,
+}
+
+// ---- Routes with typed inputs ----
+
+routes! {
+    GET "/health" => health,
+    GET "/me" => me,
+    GET "/notes" => list_notes,
+    POST "/notes" => create_note(body: NoteInput) -> Note,
+}
+
+// ---- Middleware ----
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS api_keys (
+    key TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    revoked INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL,
+    text TEXT NOT NULL
+)";
+
+/// The user the request's API key belongs to, or the response rejecting it.
+fn authenticate(req: &Request) -> Result<String, Response> {
+    let Some(key) = req
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return Err(error!(401, "Missing bearer token"));
+    };
+
+    let found = database::execute(SCHEMA, &[]).and_then(|_| {
+        database::query(
+            "SELECT user_id FROM api_keys WHERE key = ? AND revoked = 0",
+            &[Value::Text(key.trim().to_string())],
+        )
+    });
+    match found.map(|result| result.rows.into_iter().next()) {
+        Ok(Some(row)) => match row.into_iter().next() {
+            Some(Value::Text(user_id)) => Ok(user_id),
+            _ => Err(error!(401, "Invalid API key")),
+        },
+        Ok(None) => Err(error!(401, "Invalid API key")),
+        Err(_) => Err(error!(500, "Failed to check API key")),
+    }
+}
+
+// ---- Handlers ----
+
+fn health(_req: &Request) -> Response {
+    ok!({ "status": "healthy" })
+}
+
+fn me(req: &Request) -> Response {
+    match authenticate(req) {
+        Ok(user_id) => ok!({ "user_id": user_id }),
+        Err(response) => response,
+    }
+}
+
+fn list_notes(req: &Request) -> Response {
+    let user_id = match authenticate(req) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+
+    let Ok(result) = database::query(
+        "SELECT id, text FROM notes WHERE user_id = ? ORDER BY id",
+        &[Value::Text(user_id)],
+    ) else {
+        return error!(500, "Failed to list notes");
+    };
+    let notes: Vec<Note> = result
+        .rows
+        .into_iter()
+        .filter_map(|row| match (row.first(), row.get(1)) {
+            (Some(Value::Integer(id)), Some(Value::Text(text))) => Some(Note {
+                id: *id,
+                text: text.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    ok!({ "notes": notes })
+}
+
+fn create_note(body: NoteInput, req: &Request) -> Response {
+    let user_id = match authenticate(req) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    guard!(!body.text.trim().is_empty(), 400, "Text is required");
+
+    let created = database::query(
+        "INSERT INTO notes (user_id, text) VALUES (?, ?) RETURNING id",
+        &[Value::Text(user_id), Value::Text(body.text.clone())],
+    );
+    match created.map(|result| result.rows.into_iter().next()) {
+        Ok(Some(row)) => match row.first() {
+            Some(Value::Integer(id)) => created!("/notes/{}", id, Note { id: *id, text: body.text }),
+            _ => error!(500, "Failed to create note"),
+        },
+        _ => error!(500, "Failed to create note"),
+    }
+}
+"#;
+
+const RUST_AUTH_TESTS: &str = r#"# Run with: mik build && mik test
+cases:
+  - name: rejects requests without a key
+    request:
+      path: /me
+    expect:
+      status: 401
+
+  - name: rejects unknown keys
+    request:
+      path: /me
+      headers:
+        authorization: Bearer wrong
+    expect:
+      status: 401
+
+  - name: identifies the caller
+    seed:
+      sql: |
+        CREATE TABLE api_keys (key TEXT PRIMARY KEY, user_id TEXT NOT NULL, revoked INTEGER NOT NULL DEFAULT 0);
+        INSERT INTO api_keys (key, user_id) VALUES ('test-key', 'alice');
+    request:
+      path: /me
+      headers:
+        authorization: Bearer test-key
+    expect:
+      status: 200
+      json: { user_id: alice }
+
+  - name: rejects revoked keys
+    seed:
+      sql: |
+        CREATE TABLE api_keys (key TEXT PRIMARY KEY, user_id TEXT NOT NULL, revoked INTEGER NOT NULL DEFAULT 0);
+        INSERT INTO api_keys (key, user_id, revoked) VALUES ('old-key', 'alice', 1);
+    request:
+      path: /notes
+      headers:
+        authorization: Bearer old-key
+    expect:
+      status: 401
+
+  - name: health needs no key
+    request:
+      path: /health
+    expect:
+      status: 200
+"#;
+
+const RUST_AUTH_README: &str = r#"# {{PROJECT_NAME}}
+
+API key authentication built with mik. Every route but `/health` checks the
+`Authorization: Bearer <key>` header against the `api_keys` table in the
+mik SQL database (see `authenticate` in src/lib.rs).
+
+## Build, test and run
+
+```bash
+mik build -rc
+mik test        # Runs tests/api.test.yaml
+mik dev
+```
+
+## Issue a key
+
+```bash
+curl -X POST http://127.0.0.1:9919/sql/execute \
+  -H 'Content-Type: application/json' \
+  -d '{"sql": "INSERT INTO api_keys (key, user_id) VALUES (?, ?)", "params": ["my-secret-key", "alice"]}'
+
+curl http://localhost:3000/run/{{PROJECT_NAME}}/me -H 'Authorization: Bearer my-secret-key'
+```
+
+Revoke it with `UPDATE api_keys SET revoked = 1 WHERE key = ?`. Store a hash
+of the key instead of the key itself before going to production.
+
+## API
+
+| Method | Path | Description |
+|--------|------|-------------|
+| GET | /health | Health check (no key needed) |
+| GET | /me | The caller's user ID |
+| GET | /notes | The caller's notes |
+| POST | /notes | Create a note (`{"text": "..."}`) |
+"#;
+
 /// Common gitignore entries shared across languages.
 const COMMON_GITIGNORE: &str = "*.wasm\n";
 
@@ -879,7 +1911,15 @@ mod tests {
         assert_eq!("basic".parse::<Template>(), Ok(Template::Basic));
         assert_eq!("rest-api".parse::<Template>(), Ok(Template::RestApi));
         assert_eq!("restapi".parse::<Template>(), Ok(Template::RestApi));
+        assert_eq!("crud".parse::<Template>(), Ok(Template::Crud));
+        assert_eq!("queue-worker".parse::<Template>(), Ok(Template::Worker));
+        assert_eq!("cron".parse::<Template>(), Ok(Template::Cron));
+        assert_eq!("auth".parse::<Template>(), Ok(Template::Auth));
         assert!("invalid".parse::<Template>().is_err());
+
+        for template in Language::Rust.available_templates() {
+            assert_eq!(template.to_string().parse::<Template>(), Ok(*template));
+        }
     }
 
     #[test]
@@ -902,11 +1942,135 @@ mod tests {
     fn test_available_templates() {
         assert_eq!(
             Language::Rust.available_templates(),
-            &[Template::Basic, Template::RestApi]
+            &[
+                Template::Basic,
+                Template::RestApi,
+                Template::Crud,
+                Template::Worker,
+                Template::Cron,
+                Template::Auth,
+            ]
         );
         assert_eq!(
             Language::TypeScript.available_templates(),
             &[Template::Basic, Template::RestApi]
         );
     }
+
+    fn context(name: &str) -> TemplateContext {
+        TemplateContext {
+            project_name: name.to_string(),
+            project_name_underscore: name.replace('-', "_"),
+            author_name: None,
+            author_email: None,
+            year: "2025".to_string(),
+            version: DEFAULT_VERSION.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_generate_sql_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let ctx = context("jobs");
+        generate_project(dir.path(), Language::Rust, Template::Worker, &[], &ctx, "").unwrap();
+
+        let read = |path: &str| fs::read_to_string(dir.path().join(path)).unwrap();
+        let manifest: crate::manifest::Manifest = toml::from_str(&read("mik.toml")).unwrap();
+        assert_eq!(manifest.server.sql_modules, ["jobs"]);
+
+        let schedules: toml::Value = toml::from_str(&read("mik.toml")).unwrap();
+        let schedule = &schedules["schedules"][0];
+        assert_eq!(schedule["module"].as_str(), Some("jobs"));
+        assert_eq!(schedule["path"].as_str(), Some("/work"));
+
+        assert!(read("Cargo.toml").contains(r#""mik:sql" = { path = "wit/deps/sql" }"#));
+        assert!(read("wit/world.wit").contains("import mik:sql/database@0.1.0;"));
+        assert!(read("wit/deps/sql/sql.wit").contains("package mik:sql@0.1.0;"));
+        assert!(read("src/lib.rs").contains("database::query"));
+        assert!(read("tests/api.test.yaml").contains("cases:"));
+        assert!(read("README.md").starts_with("# jobs"));
+    }
+
+    #[test]
+    fn test_generate_basic_template_has_no_sql() {
+        let dir = tempfile::tempdir().unwrap();
+        generate_project(
+            dir.path(),
+            Language::Rust,
+            Template::Basic,
+            &[],
+            &context("hello"),
+            "",
+        )
+        .unwrap();
+
+        let mik_toml = fs::read_to_string(dir.path().join("mik.toml")).unwrap();
+        assert!(!mik_toml.contains("sql_modules"));
+        assert!(!mik_toml.contains("[[schedules]]"));
+        assert!(!dir.path().join("wit/deps/sql").exists());
+        assert!(!dir.path().join("tests").exists());
+        assert!(!dir.path().join(".env").exists());
+    }
+
+    #[test]
+    fn test_generate_services() {
+        let dir = tempfile::tempdir().unwrap();
+        let services = [Service::Kv, Service::Queue, Service::Cron, Service::Http];
+        generate_project(
+            dir.path(),
+            Language::Rust,
+            Template::Basic,
+            &services,
+            &context("shop"),
+            "",
+        )
+        .unwrap();
+
+        let read = |path: &str| fs::read_to_string(dir.path().join(path)).unwrap();
+        let manifest: crate::manifest::Manifest = toml::from_str(&read("mik.toml")).unwrap();
+        assert_eq!(manifest.server.sql_modules, ["shop"]);
+        assert_eq!(
+            manifest.server.http_allowed,
+            ["127.0.0.1", "${API_HOST:-httpbin.org}"]
+        );
+
+        let schedules: toml::Value = toml::from_str(&read("mik.toml")).unwrap();
+        let paths: Vec<_> = schedules["schedules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["path"].as_str().unwrap())
+            .collect();
+        assert_eq!(paths, ["/queue/work", "/tasks/tick"]);
+
+        let lib_rs = read("src/lib.rs");
+        assert!(lib_rs.contains("GET \"/kv/{key}\" => kv_get(path: KeyPath),"));
+        assert!(lib_rs.contains("fn drain_queue"));
+        assert!(lib_rs.contains("fn home"));
+        assert!(read("wit/world.wit").contains("mik:sql/database"));
+        assert!(read(".env").contains("API_HOST=httpbin.org"));
+        assert!(read(".gitignore").contains(".env"));
+    }
+
+    #[test]
+    fn test_template_tests_parse() {
+        for template in [
+            Template::Crud,
+            Template::Worker,
+            Template::Cron,
+            Template::Auth,
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            generate_project(
+                dir.path(),
+                Language::Rust,
+                template,
+                &[],
+                &context("app"),
+                "",
+            )
+            .unwrap();
+            crate::commands::test::load_test_file(&dir.path().join("tests/api.test.yaml")).unwrap();
+        }
+    }
 }
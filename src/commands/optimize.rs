@@ -0,0 +1,313 @@
+//! wasm-opt and size budgets for `mik build --release` and `mik strip`.
+//!
+//! wasm-opt (from binaryen) only reads core modules, so components are
+//! optimized module by module: each core module nested in the component is
+//! run through wasm-opt and spliced back in place, and everything else
+//! (component types, imports, canonical functions) is kept byte for byte. A
+//! module wasm-opt fails on is kept as is.
+//!
+//! A size budget (`[build] max_size_kb`) fails the build when the artifact is
+//! larger, with a report of where the bytes are compared to the previous
+//! artifact (see [`check_budget`]).
+
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use wasmparser::{Chunk, Parser, Payload};
+
+use crate::manifest::OptLevel;
+use crate::runtime::component_info;
+use crate::utils::format_bytes;
+
+/// Features wasm-opt may keep in its output: what the toolchains emit and
+/// wasmtime runs.
+const FEATURES: &[&str] = &[
+    "--enable-bulk-memory",
+    "--enable-sign-ext",
+    "--enable-mutable-globals",
+    "--enable-nontrapping-float-to-int",
+    "--enable-multivalue",
+    "--enable-reference-types",
+    "--enable-simd",
+];
+
+/// Component section id for a nested core module.
+const MODULE_SECTION: u8 = 1;
+/// Component section id for a nested component.
+const COMPONENT_SECTION: u8 = 4;
+
+/// Optimize the core module or component `wasm` with wasm-opt at `level`.
+pub fn optimize(wasm: &[u8], level: OptLevel) -> Result<Vec<u8>> {
+    if Parser::is_core_wasm(wasm) {
+        return wasm_opt(wasm, level);
+    }
+    map_core_modules(wasm, &mut |module| match wasm_opt(module, level) {
+        Ok(optimized) => Ok(optimized),
+        Err(e) => {
+            eprintln!("Warning: {e:#}, keeping the module unoptimized");
+            Ok(module.to_vec())
+        },
+    })
+}
+
+/// Run wasm-opt on the core module `module`.
+fn wasm_opt(module: &[u8], level: OptLevel) -> Result<Vec<u8>> {
+    let dir = tempfile::tempdir().context("Failed to create temp directory")?;
+    let input = dir.path().join("input.wasm");
+    let output = dir.path().join("output.wasm");
+    fs::write(&input, module)?;
+
+    let result = Command::new("wasm-opt")
+        .arg(level.flag())
+        .args(FEATURES)
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .output()
+        .context("Failed to run wasm-opt")?;
+    if !result.status.success() {
+        bail!(
+            "wasm-opt failed: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    fs::read(&output).context("Failed to read wasm-opt output")
+}
+
+/// Rewrite the component `wasm`, replacing each core module (in nested
+/// components too) with `f(module)`.
+fn map_core_modules(wasm: &[u8], f: &mut dyn FnMut(&[u8]) -> Result<Vec<u8>>) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(wasm.len());
+    let mut parser = Parser::new(0);
+    let mut offset = 0;
+    loop {
+        let (consumed, payload) = match parser
+            .parse(&wasm[offset..], true)
+            .context("Failed to parse component")?
+        {
+            Chunk::Parsed { consumed, payload } => (consumed, payload),
+            Chunk::NeedMoreData(_) => bail!("Truncated component"),
+        };
+        let nested = match payload {
+            Payload::ModuleSection {
+                unchecked_range, ..
+            } => Some((
+                MODULE_SECTION,
+                f(&wasm[unchecked_range.clone()])?,
+                unchecked_range,
+            )),
+            Payload::ComponentSection {
+                unchecked_range, ..
+            } => Some((
+                COMPONENT_SECTION,
+                map_core_modules(&wasm[unchecked_range.clone()], f)?,
+                unchecked_range,
+            )),
+            Payload::End(_) => break,
+            _ => None,
+        };
+        match nested {
+            Some((id, contents, range)) => {
+                // The parser already moved past the section's contents
+                out.push(id);
+                write_leb128(&mut out, contents.len());
+                out.extend_from_slice(&contents);
+                offset = range.end;
+            },
+            None => {
+                out.extend_from_slice(&wasm[offset..offset + consumed]);
+                offset += consumed;
+            },
+        }
+    }
+    Ok(out)
+}
+
+/// Append `value` as unsigned LEB128.
+fn write_leb128(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        #[allow(clippy::cast_possible_truncation)]
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Fail if the wasm at `path` is larger than `budget_kb`, with a size report
+/// against `baseline` (the previous artifact) when it exists.
+pub fn check_budget(path: &Path, baseline: Option<&Path>, budget_kb: u64) -> Result<()> {
+    let wasm = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let budget = budget_kb * 1024;
+    let size = wasm.len() as u64;
+    if size <= budget {
+        println!(
+            "Size budget: {} of {} ({} left)",
+            format_bytes(size),
+            format_bytes(budget),
+            format_bytes(budget - size)
+        );
+        return Ok(());
+    }
+
+    let baseline = baseline.and_then(|path| fs::read(path).ok());
+    eprintln!();
+    eprintln!(
+        "{} is {}, over its {} budget by {}",
+        path.display(),
+        format_bytes(size),
+        format_bytes(budget),
+        format_bytes(size - budget)
+    );
+    eprintln!();
+    eprint!("{}", size_report(baseline.as_deref(), &wasm)?);
+    bail!(
+        "Size budget exceeded: {} > {budget_kb} KiB",
+        format_bytes(size)
+    );
+}
+
+/// Bytes by section kind of `current`, with the change from `previous`.
+fn size_report(previous: Option<&[u8]>, current: &[u8]) -> Result<String> {
+    let (current_sizes, _) = component_info::section_sizes(current)?;
+    let previous_sizes = match previous {
+        Some(previous) => Some(component_info::section_sizes(previous)?.0),
+        None => None,
+    };
+
+    let mut report = String::new();
+    if previous_sizes.is_some() {
+        report.push_str(&format!(
+            "  {:<20} {:>10} {:>10} {:>11}\n",
+            "Section", "Previous", "Current", "Change"
+        ));
+    } else {
+        report.push_str(&format!("  {:<20} {:>10}\n", "Section", "Current"));
+    }
+    let mut row = |kind: &str, now: usize, before: Option<usize>| {
+        let line = match before {
+            Some(before) => format!(
+                "  {kind:<20} {:>10} {:>10} {:>11}\n",
+                format_bytes(before as u64),
+                format_bytes(now as u64),
+                signed_bytes(now as i64 - before as i64)
+            ),
+            None => format!("  {kind:<20} {:>10}\n", format_bytes(now as u64)),
+        };
+        report.push_str(&line);
+    };
+
+    let before = |kind: &str| {
+        previous_sizes.as_ref().map(|sizes| {
+            sizes
+                .iter()
+                .find(|(k, _)| *k == kind)
+                .map_or(0, |(_, size)| *size)
+        })
+    };
+    for (kind, size) in &current_sizes {
+        row(kind, *size, before(kind));
+    }
+    // Sections the previous artifact had and this one lost
+    if let Some(previous_sizes) = &previous_sizes {
+        for (kind, size) in previous_sizes {
+            if !current_sizes.iter().any(|(k, _)| k == kind) {
+                row(kind, 0, Some(*size));
+            }
+        }
+    }
+    row("total", current.len(), previous.map(<[u8]>::len));
+    Ok(report)
+}
+
+/// `bytes` with an explicit sign.
+fn signed_bytes(bytes: i64) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{sign}{}", format_bytes(bytes.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENT: &str = r#"
+        (component
+            (core module $a (func (export "a")))
+            (component $inner
+                (core module $b (func (export "b") (result i32) i32.const 1)))
+            (core instance (instantiate $a)))
+    "#;
+
+    #[test]
+    fn test_map_core_modules_is_identity_without_changes() {
+        let wasm = wat::parse_str(COMPONENT).unwrap();
+        let mapped = map_core_modules(&wasm, &mut |module| Ok(module.to_vec())).unwrap();
+        assert_eq!(mapped, wasm);
+    }
+
+    #[test]
+    fn test_map_core_modules_replaces_nested_modules() {
+        let wasm = wat::parse_str(COMPONENT).unwrap();
+        let replacement = wat::parse_str(r#"(module (func (export "a")) (func (export "b") (result i32) i32.const 2) (memory 1))"#).unwrap();
+        let mut seen = 0;
+        let mapped = map_core_modules(&wasm, &mut |_| {
+            seen += 1;
+            Ok(replacement.clone())
+        })
+        .unwrap();
+        assert_eq!(seen, 2);
+        assert_ne!(mapped, wasm);
+
+        wasmparser::Validator::new().validate_all(&mapped).unwrap();
+        let (sizes, _) = component_info::section_sizes(&mapped).unwrap();
+        assert!(sizes.iter().any(|(kind, _)| *kind == "code"));
+    }
+
+    #[test]
+    fn test_write_leb128() {
+        let mut out = Vec::new();
+        write_leb128(&mut out, 127);
+        write_leb128(&mut out, 128);
+        write_leb128(&mut out, 624_485);
+        assert_eq!(out, [0x7f, 0x80, 0x01, 0xe5, 0x8e, 0x26]);
+    }
+
+    #[test]
+    fn test_check_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.wasm");
+        let previous = dir.path().join("previous.wasm");
+        fs::write(&previous, wat::parse_str("(module)").unwrap()).unwrap();
+        fs::write(
+            &path,
+            wat::parse_str(r#"(module (memory 1) (data (i32.const 0) "x"))"#).unwrap(),
+        )
+        .unwrap();
+
+        assert!(check_budget(&path, Some(&previous), 1).is_ok());
+        let err = check_budget(&path, Some(&previous), 0).unwrap_err();
+        assert!(err.to_string().contains("Size budget exceeded"), "{err}");
+    }
+
+    #[test]
+    fn test_size_report_diffs_sections() {
+        let previous = wat::parse_str("(module (func))").unwrap();
+        let current =
+            wat::parse_str(r#"(module (memory 1) (data (i32.const 0) "hello"))"#).unwrap();
+        let report = size_report(Some(&previous), &current).unwrap();
+
+        assert!(report.contains("Previous"), "{report}");
+        assert!(report.contains("data"), "{report}");
+        // The code section is gone
+        let code = report.lines().find(|line| line.contains("code")).unwrap();
+        assert!(code.contains("-"), "{report}");
+        assert!(report.lines().last().unwrap().contains("total"));
+
+        let report = size_report(None, &current).unwrap();
+        assert!(!report.contains("Previous"), "{report}");
+    }
+}
@@ -0,0 +1,258 @@
+//! Package a project for deployment.
+//!
+//! `mik package --docker` writes a Docker build context holding `mik.toml`,
+//! the modules directory, and the `static` and `scripts` directories when
+//! they are configured, at the same relative paths so the manifest works
+//! unchanged. The generated Dockerfile copies the static mik binary from the
+//! release image onto `distroless/static`, runs `mik run` as a non-root
+//! user, and checks `/health` with `mik health`. With `--tag`, the image is
+//! built with `docker build`.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use crate::manifest::Manifest;
+
+/// Default output directory for the Docker build context.
+const DEFAULT_OUTPUT_DIR: &str = "dist/docker";
+
+/// Release image the mik binary is copied from.
+const MIK_IMAGE: &str = "ghcr.io/dufeutech/mik";
+
+/// Base image of the generated image: no shell, no package manager.
+const BASE_IMAGE: &str = "gcr.io/distroless/static-debian12:nonroot";
+
+/// First line of generated Dockerfiles.
+const GENERATED_HEADER: &str = "# Generated by `mik package --docker`";
+
+/// Execute the package command.
+pub fn execute(docker: bool, output: Option<&str>, tag: Option<&str>) -> Result<()> {
+    if !docker {
+        anyhow::bail!("Choose a package format: mik package --docker");
+    }
+    let manifest = Manifest::load().context("No mik.toml found")?;
+    let output = PathBuf::from(output.unwrap_or(DEFAULT_OUTPUT_DIR));
+
+    let copied = write_docker_context(Path::new("."), &manifest, &output)?;
+    println!("Docker build context: {}", output.display());
+    for dir in &copied {
+        println!("  + {dir}");
+    }
+
+    let Some(tag) = tag else {
+        println!("\nBuild the image with:");
+        println!(
+            "  docker build -t {} {}",
+            manifest.project.name.to_lowercase(),
+            output.display()
+        );
+        return Ok(());
+    };
+    super::check_tool("docker")?;
+    let status = Command::new("docker")
+        .args(["build", "-t", tag])
+        .arg(&output)
+        .status()
+        .context("Failed to run docker build")?;
+    if !status.success() {
+        anyhow::bail!("docker build failed");
+    }
+    println!("\nImage: {tag}");
+    println!("Run:   docker run -p {0}:{0} {tag}", manifest.server.port);
+    Ok(())
+}
+
+/// Write `mik.toml`, the project directories and a Dockerfile from
+/// `project` into `output`, replacing what was there. Returns the copied
+/// directories.
+fn write_docker_context(project: &Path, manifest: &Manifest, output: &Path) -> Result<Vec<String>> {
+    let mut dirs = vec![manifest.server.modules.clone()];
+    dirs.extend(manifest.server.r#static.clone());
+    dirs.extend(manifest.server.scripts.clone());
+    for dir in &dirs {
+        if !is_inside_project(dir) {
+            anyhow::bail!(
+                "Cannot package '{dir}': only directories inside the project can be copied into the image"
+            );
+        }
+    }
+
+    if output.exists() {
+        // Only replace a context written by an earlier run
+        let generated = fs::read_to_string(output.join("Dockerfile"))
+            .is_ok_and(|dockerfile| dockerfile.starts_with(GENERATED_HEADER));
+        if !generated && fs::read_dir(output)?.next().is_some() {
+            anyhow::bail!(
+                "{} is not empty and was not written by mik package",
+                output.display()
+            );
+        }
+        fs::remove_dir_all(output)
+            .with_context(|| format!("Failed to clear {}", output.display()))?;
+    }
+    fs::create_dir_all(output)?;
+    fs::copy(project.join("mik.toml"), output.join("mik.toml"))
+        .context("Failed to copy mik.toml")?;
+
+    let mut copied = Vec::new();
+    for dir in dirs {
+        let source = project.join(&dir);
+        if !source.is_dir() {
+            // Modules may come from [server.oci_modules] instead
+            if dir == manifest.server.modules {
+                continue;
+            }
+            anyhow::bail!("Directory not found: {}", source.display());
+        }
+        copy_dir(&source, &output.join(&dir))?;
+        copied.push(dir);
+    }
+
+    fs::write(
+        output.join("Dockerfile"),
+        render_dockerfile(manifest, &copied),
+    )?;
+    Ok(copied)
+}
+
+/// Whether `dir` is a relative path that stays inside the project.
+fn is_inside_project(dir: &str) -> bool {
+    let path = Path::new(dir);
+    path.components().any(|c| matches!(c, Component::Normal(_)))
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Copy `source` into `dest` recursively.
+fn copy_dir(source: &Path, dest: &Path) -> Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            fs::copy(&path, &target)
+                .with_context(|| format!("Failed to copy {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Render the Dockerfile for `manifest`, copying `dirs` into `/app`.
+fn render_dockerfile(manifest: &Manifest, dirs: &[String]) -> String {
+    let version = env!("CARGO_PKG_VERSION");
+    let port = manifest.server.port;
+    let copies: String = dirs
+        .iter()
+        .map(|dir| {
+            let dir = dir.trim_end_matches('/');
+            format!("COPY {dir}/ ./{dir}/\n")
+        })
+        .collect();
+    format!(
+        r#"{GENERATED_HEADER} for {name}.
+FROM {MIK_IMAGE}:{version} AS mik
+
+FROM {BASE_IMAGE}
+COPY --from=mik /mik /usr/local/bin/mik
+WORKDIR /app
+COPY mik.toml ./
+{copies}
+ENV RUST_LOG=info
+EXPOSE {port}
+HEALTHCHECK --interval=30s --timeout=5s --start-period=10s --retries=3 \
+    CMD ["/usr/local/bin/mik", "health", "--port", "{port}"]
+ENTRYPOINT ["/usr/local/bin/mik"]
+CMD ["run"]
+"#,
+        name = manifest.project.name,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[project]
+name = "orders"
+
+[server]
+port = 8080
+static = "public"
+"#;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("mik.toml"), MANIFEST).unwrap();
+        fs::create_dir_all(dir.path().join("modules")).unwrap();
+        fs::write(dir.path().join("modules/orders.wasm"), b"\0asm").unwrap();
+        fs::create_dir_all(dir.path().join("public/css")).unwrap();
+        fs::write(dir.path().join("public/css/app.css"), "body {}").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_write_docker_context() {
+        let project = project();
+        let manifest = Manifest::load_from(&project.path().join("mik.toml")).unwrap();
+        let output = project.path().join("dist/docker");
+        write_docker_context(project.path(), &manifest, &output).unwrap();
+        fs::write(output.join("stale.txt"), "old").unwrap();
+
+        let copied = write_docker_context(project.path(), &manifest, &output).unwrap();
+        assert_eq!(copied, ["modules/", "public"]);
+        assert!(output.join("mik.toml").is_file());
+        assert!(output.join("modules/orders.wasm").is_file());
+        assert!(output.join("public/css/app.css").is_file());
+        assert!(!output.join("stale.txt").exists());
+
+        let dockerfile = fs::read_to_string(output.join("Dockerfile")).unwrap();
+        assert!(dockerfile.contains(&format!(
+            "FROM ghcr.io/dufeutech/mik:{} AS mik",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(dockerfile.contains("FROM gcr.io/distroless/static-debian12:nonroot"));
+        assert!(dockerfile.contains("COPY modules/ ./modules/\n"));
+        assert!(dockerfile.contains("COPY public/ ./public/\n"));
+        assert!(dockerfile.contains("EXPOSE 8080"));
+        assert!(dockerfile.contains(r#"CMD ["/usr/local/bin/mik", "health", "--port", "8080"]"#));
+        assert!(dockerfile.contains(r#"ENTRYPOINT ["/usr/local/bin/mik"]"#));
+        assert!(dockerfile.ends_with("CMD [\"run\"]\n"));
+    }
+
+    #[test]
+    fn test_write_docker_context_rejects_outside_dirs() {
+        let project = project();
+        let mut manifest = Manifest::load_from(&project.path().join("mik.toml")).unwrap();
+        let output = project.path().join("dist/docker");
+
+        manifest.server.r#static = Some("../shared".into());
+        assert!(write_docker_context(project.path(), &manifest, &output).is_err());
+        manifest.server.r#static = Some("/srv/static".into());
+        assert!(write_docker_context(project.path(), &manifest, &output).is_err());
+        manifest.server.r#static = Some("missing".into());
+        assert!(write_docker_context(project.path(), &manifest, &output).is_err());
+
+        // Never clear a directory mik package didn't write
+        manifest.server.r#static = None;
+        let err = write_docker_context(project.path(), &manifest, project.path()).unwrap_err();
+        assert!(err.to_string().contains("not empty"), "{err}");
+        assert!(project.path().join("modules/orders.wasm").is_file());
+    }
+
+    #[test]
+    fn test_is_inside_project() {
+        assert!(is_inside_project("modules/"));
+        assert!(is_inside_project("./static/site"));
+        assert!(!is_inside_project(""));
+        assert!(!is_inside_project("."));
+        assert!(!is_inside_project("../modules"));
+        assert!(!is_inside_project("/srv/modules"));
+    }
+}
@@ -5,7 +5,9 @@
 //!
 //! OCI artifacts are cached globally in `~/.cache/mik/oci/` using
 //! content-addressable storage (digest-based). This allows reuse
-//! across multiple projects.
+//! across multiple projects. Downloaded blobs are checked against their
+//! digest by the OCI client, and cached blobs are checked again before use,
+//! so a corrupted or tampered cache entry is downloaded again instead.
 //!
 //! OCI is the preferred method. HTTP is supported as fallback.
 
@@ -21,45 +23,7 @@ use tokio::io::AsyncWriteExt;
 use crate::manifest::{Dependency, Manifest};
 use crate::registry;
 use crate::reliability::retry::{RetryConfig, retry_anyhow, retry_sync};
-
-/// Get the OCI cache directory for content-addressable storage.
-fn get_oci_cache_dir() -> Result<PathBuf> {
-    let cache_dir = dirs::cache_dir()
-        .ok_or_else(|| anyhow::anyhow!("Failed to determine cache directory"))?
-        .join("mik")
-        .join("oci")
-        .join("blobs");
-    Ok(cache_dir)
-}
-
-/// Get cached blob path from digest.
-fn get_cached_blob_path(digest: &str) -> Result<PathBuf> {
-    let cache_dir = get_oci_cache_dir()?;
-
-    // Digest format: "sha256:abc123..."
-    let (algo, hash) = digest
-        .split_once(':')
-        .ok_or_else(|| anyhow::anyhow!("Invalid digest format: {digest}"))?;
-
-    Ok(cache_dir.join(algo).join(hash))
-}
-
-/// Check if a blob is cached and return its path.
-fn get_cached_blob(digest: &str) -> Option<PathBuf> {
-    get_cached_blob_path(digest).ok().filter(|p| p.exists())
-}
-
-/// Save a blob to the cache.
-fn cache_blob(digest: &str, data: &[u8]) -> Result<PathBuf> {
-    let path = get_cached_blob_path(digest)?;
-
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    fs::write(&path, data)?;
-    Ok(path)
-}
+use crate::runtime::oci::{cache_blob, cached_blob, wasm_layer};
 
 /// Pull a single dependency.
 async fn pull_dependency(name: &str, dep: &Dependency) -> Result<String> {
@@ -157,31 +121,11 @@ pub async fn pull_oci(oci_ref: &str, output_path: &Path) -> Result<()> {
     .await
     .with_context(|| format!("Failed to pull manifest for {oci_ref}"))?;
 
-    // Get the first layer (should be the .wasm file)
-    let layers = match &manifest {
-        oci_client::manifest::OciManifest::Image(img) => &img.layers,
-        oci_client::manifest::OciManifest::ImageIndex(_) => {
-            anyhow::bail!("Image index not supported, expected single image");
-        },
-    };
-
-    if layers.is_empty() {
-        anyhow::bail!("No layers found in manifest for {oci_ref}");
-    }
-
-    // Find the wasm layer (usually application/wasm or application/vnd.wasm.content.layer.v1+wasm)
-    let wasm_layer = layers
-        .iter()
-        .find(|l| {
-            l.media_type.contains("wasm")
-                || l.media_type.contains("octet-stream")
-                || l.media_type.is_empty()
-        })
-        .or_else(|| layers.first())
-        .ok_or_else(|| anyhow::anyhow!("No suitable layer found in {oci_ref}"))?;
+    let wasm_layer =
+        wasm_layer(&manifest).with_context(|| format!("No suitable layer found in {oci_ref}"))?;
 
     // Check if blob is already cached
-    if let Some(cached_path) = get_cached_blob(&wasm_layer.digest) {
+    if let Some(cached_path) = cached_blob(&wasm_layer.digest) {
         // Cache hit - copy from cache
         fs::copy(&cached_path, output_path)
             .with_context(|| format!("Failed to copy cached blob to {}", output_path.display()))?;
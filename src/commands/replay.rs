@@ -0,0 +1,417 @@
+//! Replay recorded traffic against a running server.
+//!
+//! `mik run --record <dir>` writes every module request and its response to
+//! `<dir>` (see [`recorder`]). `mik replay <dir>` sends those requests, in
+//! the order they were recorded, to another build and compares what comes
+//! back:
+//!
+//! - the status code;
+//! - `content-type`;
+//! - the body: JSON bodies are compared as values, reporting each differing
+//!   field by path (`$.items[0].price`), other bodies byte for byte.
+//!
+//! Fields that legitimately change between runs (timestamps, generated IDs)
+//! are skipped with `--ignore <key>`, matching object keys at any depth.
+//! Redacted headers are not sent, so endpoints needing credentials replay
+//! without them. A recording of a failed request matches when the replay
+//! fails too (a 5xx or a closed connection).
+//!
+//! Requests are replayed as recorded, side effects included: replay against
+//! a disposable environment, not production.
+//!
+//! [`recorder`]: crate::runtime::recorder
+
+use crate::runtime::recorder::{self, REDACTED, Recording};
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+
+/// Server replayed against when no target is given.
+pub const DEFAULT_TARGET: &str = "http://127.0.0.1:3000";
+
+/// Request headers not replayed: set by the client, or would change the
+/// response encoding.
+const SKIPPED_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "connection",
+    "transfer-encoding",
+    "accept-encoding",
+];
+
+/// Differences listed per recording before the rest are summarized.
+const MAX_DIFFERENCES: usize = 10;
+
+/// Timeout for one replayed request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Replay the recordings in `dir` against `target`.
+///
+/// Only recordings of `module` are replayed when given. Object keys in
+/// `ignore` are skipped when comparing JSON bodies. Fails if any response
+/// differs.
+pub async fn execute(
+    dir: &str,
+    target: Option<&str>,
+    module: Option<&str>,
+    ignore: &[String],
+) -> Result<()> {
+    let target = target.unwrap_or(DEFAULT_TARGET).trim_end_matches('/');
+    let recordings = recorder::load_dir(Path::new(dir))?;
+    let recordings: Vec<_> = recordings
+        .into_iter()
+        .filter(|(_, recording)| module.is_none() || recording.module.as_deref() == module)
+        .collect();
+    if recordings.is_empty() {
+        bail!(
+            "No recordings found in {dir}.\n\n\
+             Record traffic first:\n  mik run --record {dir}"
+        );
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    println!("Replaying {} request(s) against {target}", recordings.len());
+    println!();
+    let (mut matched, mut differed) = (0usize, 0usize);
+    for (path, recording) in &recordings {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let label = format!("{} {}", recording.request.method, recording.request.uri);
+        let replayed = replay(&client, target, recording)
+            .await
+            .with_context(|| format!("Failed to replay {name}"))?;
+        let differences = compare(recording, &replayed, ignore);
+        if differences.is_empty() {
+            matched += 1;
+            println!("  ok    {label}");
+        } else {
+            differed += 1;
+            println!("  DIFF  {label} ({name})");
+            for difference in differences {
+                println!("        {difference}");
+            }
+        }
+    }
+
+    println!();
+    println!("{matched} matched, {differed} differed");
+    if differed > 0 {
+        bail!("{differed} response(s) differ from the recording");
+    }
+    Ok(())
+}
+
+/// What the target answered to a replayed request.
+#[derive(Debug)]
+enum Replayed {
+    Response {
+        status: u16,
+        content_type: Option<String>,
+        body: Vec<u8>,
+    },
+    /// The connection failed or was closed without a response.
+    Failed(String),
+}
+
+async fn replay(client: &reqwest::Client, target: &str, recording: &Recording) -> Result<Replayed> {
+    let request = &recording.request;
+    let method = reqwest::Method::from_bytes(request.method.as_bytes())
+        .with_context(|| format!("Invalid method '{}'", request.method))?;
+    let mut builder = client
+        .request(method, format!("{target}{}", request.uri))
+        .body(request.body.bytes()?);
+    for (name, value) in &request.headers {
+        if value != REDACTED && !SKIPPED_HEADERS.contains(&name.as_str()) {
+            builder = builder.header(name, value);
+        }
+    }
+
+    let response = match builder.send().await {
+        Ok(response) => response,
+        Err(e) if e.is_connect() => {
+            bail!("Failed to connect to {target}: is the server running?")
+        },
+        Err(e) => return Ok(Replayed::Failed(e.to_string())),
+    };
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    match response.bytes().await {
+        Ok(body) => Ok(Replayed::Response {
+            status,
+            content_type,
+            body: body.to_vec(),
+        }),
+        Err(e) => Ok(Replayed::Failed(e.to_string())),
+    }
+}
+
+/// Differences between a recording and its replay.
+fn compare(recording: &Recording, replayed: &Replayed, ignore: &[String]) -> Vec<String> {
+    let Some(expected) = &recording.response else {
+        return match replayed {
+            Replayed::Response { status, .. } if *status < 500 => vec![format!(
+                "recorded failure ({}), got status {status}",
+                recording.error.as_deref().unwrap_or("no response")
+            )],
+            _ => Vec::new(),
+        };
+    };
+    let (status, content_type, body) = match replayed {
+        Replayed::Response {
+            status,
+            content_type,
+            body,
+        } => (*status, content_type.as_deref(), body),
+        Replayed::Failed(error) => {
+            return vec![format!(
+                "recorded status {}, got no response ({error})",
+                expected.status
+            )];
+        },
+    };
+
+    let mut differences = Vec::new();
+    if status != expected.status {
+        differences.push(format!("status: {} -> {status}", expected.status));
+    }
+    let expected_type = expected.headers.get("content-type").map(String::as_str);
+    if content_type != expected_type {
+        differences.push(format!(
+            "content-type: {} -> {}",
+            expected_type.unwrap_or("(none)"),
+            content_type.unwrap_or("(none)")
+        ));
+    }
+
+    let expected_body = match expected.body.bytes() {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            differences.push(format!("{e:#}"));
+            return differences;
+        },
+    };
+    let json = (
+        serde_json::from_slice::<Value>(&expected_body),
+        serde_json::from_slice::<Value>(body),
+    );
+    if let (Ok(expected), Ok(actual)) = json {
+        let mut body_differences = Vec::new();
+        json_diff("$", &expected, &actual, ignore, &mut body_differences);
+        let total = body_differences.len();
+        differences.extend(
+            body_differences
+                .into_iter()
+                .take(MAX_DIFFERENCES)
+                .map(|difference| format!("body {difference}")),
+        );
+        if total > MAX_DIFFERENCES {
+            differences.push(format!(
+                "... and {} more body difference(s)",
+                total - MAX_DIFFERENCES
+            ));
+        }
+    } else if expected_body != *body {
+        differences.push(format!(
+            "body differs ({} -> {} bytes)",
+            expected_body.len(),
+            body.len()
+        ));
+    }
+    differences
+}
+
+/// Append the differences between `expected` and `actual` at `path` to `out`.
+fn json_diff(
+    path: &str,
+    expected: &Value,
+    actual: &Value,
+    ignore: &[String],
+    out: &mut Vec<String>,
+) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let keys = expected
+                .keys()
+                .chain(actual.keys().filter(|key| !expected.contains_key(*key)));
+            for key in keys {
+                if ignore.iter().any(|ignored| ignored == key) {
+                    continue;
+                }
+                let field = format!("{path}.{key}");
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => {
+                        json_diff(&field, expected, actual, ignore, out);
+                    },
+                    (Some(expected), None) => out.push(format!("{field}: {expected} -> (missing)")),
+                    (None, Some(actual)) => out.push(format!("{field}: (missing) -> {actual}")),
+                    (None, None) => {},
+                }
+            }
+        },
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (i, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                json_diff(&format!("{path}[{i}]"), expected, actual, ignore, out);
+            }
+        },
+        (Value::Array(expected), Value::Array(actual)) => out.push(format!(
+            "{path}: {} -> {} items",
+            expected.len(),
+            actual.len()
+        )),
+        _ if expected != actual => out.push(format!("{path}: {expected} -> {actual}")),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::recorder::{RecordedBody, RecordedRequest, RecordedResponse};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn recording(status: u16, body: &str) -> Recording {
+        Recording {
+            recorded_at: "2025-01-01T00:00:00+00:00".to_string(),
+            module: Some("orders".to_string()),
+            request: RecordedRequest {
+                method: "POST".to_string(),
+                uri: "/run/orders/?page=2".to_string(),
+                headers: BTreeMap::from([
+                    ("authorization".to_string(), REDACTED.to_string()),
+                    ("x-tenant".to_string(), "acme".to_string()),
+                ]),
+                body: RecordedBody {
+                    body: Some("{}".to_string()),
+                    body_base64: None,
+                },
+            },
+            response: Some(RecordedResponse {
+                status,
+                headers: BTreeMap::from([(
+                    "content-type".to_string(),
+                    "application/json".to_string(),
+                )]),
+                body: RecordedBody {
+                    body: Some(body.to_string()),
+                    body_base64: None,
+                },
+            }),
+            error: None,
+        }
+    }
+
+    fn response(status: u16, body: &str) -> Replayed {
+        Replayed::Response {
+            status,
+            content_type: Some("application/json".to_string()),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_json_diff() {
+        let mut out = Vec::new();
+        json_diff(
+            "$",
+            &json!({"id": 1, "items": [{"price": 5}], "at": "t1", "gone": true}),
+            &json!({"id": 1, "items": [{"price": 6}], "at": "t2", "new": null}),
+            &["at".to_string()],
+            &mut out,
+        );
+        out.sort();
+        assert_eq!(
+            out,
+            [
+                "$.gone: true -> (missing)",
+                "$.items[0].price: 5 -> 6",
+                "$.new: (missing) -> null",
+            ]
+        );
+
+        let mut out = Vec::new();
+        json_diff("$", &json!([1, 2]), &json!([1]), &[], &mut out);
+        assert_eq!(out, ["$: 2 -> 1 items"]);
+    }
+
+    #[test]
+    fn test_compare() {
+        let recorded = recording(200, r#"{"total": 3}"#);
+        // Formatting differences do not matter for JSON
+        assert!(compare(&recorded, &response(200, r#"{ "total":3 }"#), &[]).is_empty());
+
+        let differences = compare(&recorded, &response(500, r#"{"total": 4}"#), &[]);
+        assert_eq!(differences, ["status: 200 -> 500", "body $.total: 3 -> 4"]);
+
+        let text = recording(200, "hello");
+        assert_eq!(
+            compare(&text, &response(200, "hello!"), &[]),
+            ["body differs (5 -> 6 bytes)"]
+        );
+        assert_eq!(
+            compare(&recorded, &Replayed::Failed("closed".to_string()), &[]).len(),
+            1
+        );
+
+        let mut failed = recording(200, "");
+        failed.response = None;
+        failed.error = Some("Handler call failed".to_string());
+        assert!(compare(&failed, &Replayed::Failed("closed".to_string()), &[]).is_empty());
+        assert!(compare(&failed, &response(503, ""), &[]).is_empty());
+        assert_eq!(compare(&failed, &response(200, ""), &[]).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_sends_recorded_request() {
+        use axum::http::HeaderMap;
+
+        let app =
+            axum::Router::new().fallback(
+                |method: axum::http::Method,
+                 uri: axum::http::Uri,
+                 headers: HeaderMap,
+                 body: String| async move {
+                    axum::Json(json!({
+                        "method": method.as_str(),
+                        "uri": uri.to_string(),
+                        "tenant": headers.get("x-tenant").and_then(|v| v.to_str().ok()),
+                        "authorized": headers.contains_key("authorization"),
+                        "body": body,
+                    }))
+                },
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let recorded = recording(200, "");
+        let replayed = replay(&reqwest::Client::new(), &target, &recorded)
+            .await
+            .unwrap();
+        server.abort();
+
+        let Replayed::Response { status, body, .. } = replayed else {
+            panic!("no response: {replayed:?}");
+        };
+        assert_eq!(status, 200);
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "method": "POST",
+                "uri": "/run/orders/?page=2",
+                "tenant": "acme",
+                "authorized": false,
+                "body": "{}",
+            })
+        );
+    }
+}
@@ -18,7 +18,23 @@ use std::process::{Child, Command};
 
 use crate::manifest::{Manifest, TracingConfig};
 use crate::runtime::lb::LoadBalancerConfig;
-use crate::runtime::{Runtime, Server};
+use crate::runtime::{Runtime, RuntimeBuilder, Server, host_config};
+
+/// Directory requests are recorded to (`--record`), passed on to workers.
+const RECORD_DIR_ENV: &str = "MIK_RECORD_DIR";
+
+/// Load `path` after the `.env` files, in this process and the ones it
+/// spawns (see [`crate::manifest::ENV_FILE_ENV`]).
+///
+/// Must be called before spawning threads.
+pub(crate) fn set_env_file(path: &str) -> Result<()> {
+    let path =
+        std::fs::canonicalize(path).with_context(|| format!("Env file not found: {path}"))?;
+    // SAFETY: Called before spawning threads, as documented above.
+    unsafe { std::env::set_var(crate::manifest::ENV_FILE_ENV, &path) };
+    println!("Using env file: {}", path.display());
+    Ok(())
+}
 
 /// Run components with the embedded runtime.
 ///
@@ -42,12 +58,16 @@ use crate::runtime::{Runtime, Server};
 ///   - Round-robin with health checks
 ///
 /// - `mik run --workers 0` - Auto-detect workers (one per CPU core)
+#[allow(clippy::too_many_arguments)] // One per `mik run` flag
 pub async fn execute(
     component_path: Option<&str>,
     workers: u16,
     port_override: Option<u16>,
     local_only: bool,
     use_lb: bool,
+    profile: Option<&str>,
+    record: Option<&str>,
+    env_file: Option<&str>,
 ) -> Result<()> {
     // Set MIK_LOCAL env var if --local flag is set
     if local_only {
@@ -56,6 +76,30 @@ pub async fn execute(
         unsafe { std::env::set_var("MIK_LOCAL", "1") };
     }
 
+    // Select the manifest profile; workers inherit it through the environment
+    if let Some(profile) = profile {
+        // SAFETY: Called before spawning threads, as above.
+        unsafe { std::env::set_var(crate::manifest::PROFILE_ENV, profile) };
+    }
+    // Workers record to the same directory through the environment
+    if let Some(dir) = record {
+        // SAFETY: Called before spawning threads, as above.
+        unsafe { std::env::set_var(RECORD_DIR_ENV, dir) };
+        println!("Recording requests to: {dir}");
+    }
+    if let Some(path) = env_file {
+        set_env_file(path)?;
+    }
+    if let Some(profile) = crate::manifest::active_profile() {
+        println!("Using profile: {profile}");
+    }
+
+    // Fail fast on missing environment variables, before any worker starts
+    let manifest_path = std::path::Path::new("mik.toml");
+    if manifest_path.exists() {
+        crate::manifest::read_resolved(manifest_path, false)?;
+    }
+
     // Check if we're a spawned worker (internal flag)
     if std::env::var("MIK_WORKER_ID").is_ok() {
         // We're a worker - run single instance
@@ -116,9 +160,7 @@ async fn run_multi_worker(
     local_only: bool,
 ) -> Result<()> {
     // Get base port from override, mik.toml, or default
-    let base_port = port_override
-        .or_else(Manifest::load_port)
-        .unwrap_or(crate::constants::DEFAULT_PORT);
+    let base_port = base_port(port_override)?;
 
     println!("Starting {workers} workers...\n");
 
@@ -204,16 +246,14 @@ async fn run_with_lb(
     local_only: bool,
 ) -> Result<()> {
     // Load manifest for lb config and port
-    let manifest = Manifest::load().ok();
+    let manifest = Manifest::load_resolved().ok();
     let lb_manifest_config = manifest
         .as_ref()
         .and_then(|m| m.lb.clone())
         .unwrap_or_default();
 
     // Get base port from override, mik.toml, or default
-    let base_port = port_override
-        .or_else(Manifest::load_port)
-        .unwrap_or(crate::constants::DEFAULT_PORT);
+    let base_port = base_port(port_override)?;
 
     println!("Starting {workers} workers with integrated L7 load balancer...\n");
 
@@ -316,7 +356,7 @@ async fn run_single_instance(
     let tracing_config = load_tracing_config();
 
     // Initialize logging/tracing
-    init_tracing(&tracing_config);
+    init_tracing(&tracing_config, load_log_level().as_deref());
 
     // Determine mode based on arguments
     let mut builder = match component_path {
@@ -364,16 +404,12 @@ async fn run_single_instance(
         },
     };
 
-    // Apply port override if specified
+    // Environment overrides mik.toml, and flags override both
+    builder = apply_env_overrides(builder)?;
     if let Some(port) = port_override {
         builder = builder.port(port);
     }
 
-    // Check for hot-reload mode (bypasses AOT cache)
-    if std::env::var("MIK_HOT_RELOAD").is_ok() {
-        builder = builder.hot_reload(true);
-    }
-
     let port = builder.get_port();
     let runtime = builder.build().context("Failed to build runtime")?;
 
@@ -410,17 +446,120 @@ async fn run_single_instance(
     Ok(())
 }
 
+/// Runtime-only environment variables, with the setting each one changes
+/// (for `mik config show`). `MIK_<SETTING>` variables for `[server]`
+/// settings are listed in [`host_config::ENV_SETTINGS`].
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("MIK_HOT_RELOAD", "hot_reload"),
+    ("MIK_API_KEY", "api_key"),
+    ("MIK_MODULE_SIGNING_KEY", "module_signing_key"),
+    ("MIK_HANDLERS_WEBHOOK_SECRET", "handlers_webhook_secret"),
+    (RECORD_DIR_ENV, "record_dir"),
+    ("MIK_BUILD_ERROR_FILE", "build_error_file"),
+    ("MIK_DEV_PROXY", "dev_proxy"),
+    ("MIK_DEV_DASHBOARD", "dev_dashboard"),
+    ("MIK_WORKER_ID", "handlers_webhook"),
+];
+
+/// Apply the `MIK_*` environment overrides to a builder loaded from
+/// mik.toml: `MIK_<SETTING>` for `[server]` settings, then the runtime-only
+/// variables in [`ENV_OVERRIDES`].
+pub fn apply_env_overrides(builder: RuntimeBuilder) -> Result<RuntimeBuilder> {
+    let mut builder = builder
+        .env_overrides()
+        .context("Invalid environment override")?;
+
+    // Check for hot-reload mode (bypasses AOT cache)
+    if std::env::var("MIK_HOT_RELOAD").is_ok() {
+        builder = builder.hot_reload(true);
+    }
+    // `mik run --local` shows script traces to any caller
+    if std::env::var("MIK_LOCAL").is_ok() {
+        builder = builder.trace_scripts(true);
+    }
+
+    // Authenticates the gateway's management endpoints
+    if let Ok(key) = std::env::var("MIK_API_KEY")
+        && !key.is_empty()
+    {
+        builder = builder.api_key(key);
+    }
+    if let Ok(key) = std::env::var("MIK_MODULE_SIGNING_KEY")
+        && !key.is_empty()
+    {
+        builder = builder.module_signing_key(key);
+    }
+    if let Ok(key) = std::env::var("MIK_HANDLERS_WEBHOOK_SECRET")
+        && !key.is_empty()
+    {
+        builder = builder.handlers_webhook_secret(key);
+    }
+    if let Ok(dir) = std::env::var(RECORD_DIR_ENV)
+        && !dir.is_empty()
+    {
+        builder = builder.record_dir(dir);
+    }
+    // Set by `mik dev` for its build error overlay
+    if let Ok(file) = std::env::var("MIK_BUILD_ERROR_FILE")
+        && !file.is_empty()
+    {
+        builder = builder.build_error_file(file);
+    }
+    // Set by `mik dev --proxy`
+    if let Ok(url) = std::env::var("MIK_DEV_PROXY")
+        && !url.is_empty()
+    {
+        builder = builder.dev_proxy(url);
+    }
+    // Set by `mik dev` to the instance's log file
+    if let Ok(log_file) = std::env::var("MIK_DEV_DASHBOARD")
+        && !log_file.is_empty()
+    {
+        builder = builder.dev_dashboard(log_file);
+    }
+    // Workers share the module directories: only the first one notifies
+    if std::env::var("MIK_WORKER_ID").is_ok_and(|id| id != "0") {
+        builder = builder.disable_handlers_webhook();
+    }
+    Ok(builder)
+}
+
+/// Base port for workers: `--port`, `MIK_PORT`, mik.toml, or the default.
+fn base_port(port_override: Option<u16>) -> Result<u16> {
+    if let Some(port) = port_override {
+        return Ok(port);
+    }
+    let var = host_config::env_var("port");
+    if let Ok(port) = std::env::var(&var)
+        && !port.trim().is_empty()
+    {
+        return port
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid {var}='{port}': expected a port number"));
+    }
+    Ok(Manifest::load_port().unwrap_or(crate::constants::DEFAULT_PORT))
+}
+
 /// Load tracing configuration from mik.toml if present.
 fn load_tracing_config() -> TracingConfig {
     Manifest::load_tracing_config().unwrap_or_default()
 }
 
+/// Log filter from `MIK_LOG_LEVEL` or `[server] log_level` in mik.toml.
+fn load_log_level() -> Option<String> {
+    std::env::var(host_config::env_var("log_level"))
+        .ok()
+        .filter(|level| !level.trim().is_empty())
+        .or_else(|| Manifest::load_server_config().ok()?.log_level)
+}
+
 /// Initialize tracing/logging based on configuration.
 ///
 /// When the `otlp` feature is enabled and an OTLP endpoint is configured,
 /// traces are exported to the specified backend (Jaeger, Tempo, etc.).
 #[allow(unused_variables)]
-fn init_tracing(config: &TracingConfig) {
+fn init_tracing(config: &TracingConfig, log_level: Option<&str>) {
     // Try OTLP initialization if feature is enabled and endpoint is set
     #[cfg(feature = "otlp")]
     if let Some(endpoint) = &config.otlp_endpoint {
@@ -431,7 +570,7 @@ fn init_tracing(config: &TracingConfig) {
         if let Err(e) = init_with_otlp(otlp_config) {
             eprintln!("Warning: Failed to initialize OTLP tracing: {e}");
             eprintln!("Falling back to stdout logging");
-            init_stdout_logging();
+            init_stdout_logging(log_level);
         } else {
             tracing::info!(
                 endpoint = %endpoint,
@@ -443,19 +582,37 @@ fn init_tracing(config: &TracingConfig) {
     }
 
     // Default: stdout logging
-    init_stdout_logging();
+    init_stdout_logging(log_level);
 }
 
 /// Initialize stdout logging (fallback when OTLP is not configured).
-fn init_stdout_logging() {
-    use tracing_subscriber::{EnvFilter, fmt, prelude::*};
-
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+///
+/// `RUST_LOG` takes precedence over `log_level`. Without it, the filter
+/// follows `log_level` on configuration reloads.
+fn init_stdout_logging(log_level: Option<&str>) {
+    use tracing_subscriber::{EnvFilter, fmt, prelude::*, reload};
+
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer())
+            .init();
+        return;
+    }
 
+    // An invalid log_level is reported when the configuration is validated
+    let filter = log_level
+        .and_then(|level| EnvFilter::try_new(level).ok())
+        .unwrap_or_else(|| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
     tracing_subscriber::registry()
         .with(filter)
         .with(fmt::layer())
         .init();
+    crate::runtime::reload::set_log_filter_handler(move |level| {
+        handle.reload(EnvFilter::try_new(level)?)?;
+        Ok(())
+    });
 }
 
 /// Resolve a component path (handles globs and validates existence).
@@ -1,7 +1,10 @@
 //! Strip debug info and custom sections from WASM components.
 //!
 //! Wraps `wasm-tools strip` to remove debug info, names, and custom sections
-//! from WASM components, significantly reducing file size.
+//! from WASM components, significantly reducing file size. With
+//! `--opt-level`, the core modules are then optimized with wasm-opt, and
+//! `--max-size-kb` fails when the result is still over budget (see
+//! [`optimize`](super::optimize)).
 //!
 //! Auto-downloads wasm-tools if not installed.
 
@@ -9,6 +12,9 @@ use anyhow::{Context, Result, bail};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use super::optimize;
+use crate::manifest::OptLevel;
+
 /// Latest wasm-tools version to download
 const WASM_TOOLS_VERSION: &str = "1.225.0";
 
@@ -24,6 +30,10 @@ pub struct StripOptions {
     pub producers: bool,
     /// Output path (default: input with .stripped.wasm suffix)
     pub output: Option<String>,
+    /// Optimize with wasm-opt at this level after stripping
+    pub opt_level: Option<OptLevel>,
+    /// Fail if the output is larger than this many KiB
+    pub max_size_kb: Option<u64>,
 }
 
 impl Default for StripOptions {
@@ -34,6 +44,8 @@ impl Default for StripOptions {
             names: false,
             producers: false,
             output: None,
+            opt_level: None,
+            max_size_kb: None,
         }
     }
 }
@@ -272,6 +284,17 @@ pub fn execute(input: &str, options: StripOptions) -> Result<()> {
         bail!("wasm-tools strip failed: {stderr}");
     }
 
+    if let Some(level) = options.opt_level {
+        super::require_tool_with_info(
+            "wasm-opt",
+            "Download binaryen from GitHub releases",
+            Some("https://github.com/WebAssembly/binaryen/releases"),
+        )?;
+        let stripped = std::fs::read(&output_path)?;
+        let optimized = optimize::optimize(&stripped, level)?;
+        std::fs::write(&output_path, optimized)?;
+    }
+
     // Report results
     let input_size = std::fs::metadata(input)?.len();
     let output_size = std::fs::metadata(&output_path)?.len();
@@ -282,7 +305,12 @@ pub fn execute(input: &str, options: StripOptions) -> Result<()> {
         0.0
     };
 
-    println!("Stripped: {input} -> {output_path}");
+    let action = if options.opt_level.is_some() {
+        "Stripped and optimized"
+    } else {
+        "Stripped"
+    };
+    println!("{action}: {input} -> {output_path}");
     println!(
         "Size: {} -> {} ({:.1}% smaller, saved {})",
         format_size(input_size),
@@ -291,6 +319,10 @@ pub fn execute(input: &str, options: StripOptions) -> Result<()> {
         format_size(savings)
     );
 
+    if let Some(budget_kb) = options.max_size_kb {
+        optimize::check_budget(Path::new(&output_path), Some(input_path), budget_kb)?;
+    }
+
     Ok(())
 }
 
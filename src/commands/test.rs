@@ -0,0 +1,574 @@
+//! Declarative component tests.
+//!
+//! `mik test` runs test files against the project's built component, in an
+//! ephemeral runtime that does not bind a port. A test file holds cases, each
+//! one request and the response it should get:
+//!
+//! ```yaml
+//! cases:
+//!   - name: lists orders
+//!     seed:
+//!       sql: |
+//!         CREATE TABLE orders (id INTEGER PRIMARY KEY, item TEXT);
+//!         INSERT INTO orders (item) VALUES ('book');
+//!       kv:
+//!         "feature/orders": "on"
+//!     request:
+//!       method: GET
+//!       path: /orders
+//!       headers:
+//!         accept: application/json
+//!     expect:
+//!       status: 200
+//!       headers:
+//!         content-type: application/json
+//!       json:
+//!         orders: [{ item: book }]
+//! ```
+//!
+//! - `request.path` is relative to the component (`/orders` is sent to
+//!   `/run/<module>/orders`). The body is `body` (text) or `json`, which also
+//!   sets `Content-Type: application/json`. `method` defaults to `GET`.
+//! - `expect.status` defaults to any 2xx. Header names are case-insensitive.
+//!   `json` ignores object fields the expectation does not list; `body` must
+//!   match exactly and `body_contains` is a substring match.
+//! - Each case gets a fresh runtime with an empty `mik:sql` database, which
+//!   `seed.sql` runs against first. `seed.kv` is written through the local
+//!   daemon (started if needed) and deleted after the case. Non-string KV
+//!   values are stored as JSON.
+//!
+//! Without arguments, `*.test.yaml`, `*.test.yml` and `*.test.json` files
+//! under `tests/` are run. The runtime uses `[server]` settings from mik.toml
+//! when present. The command fails if any case fails, for CI.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::daemon::http::client::DaemonClient;
+use crate::daemon::services::sql::SqlService;
+use crate::daemon::startup::{DAEMON_PORT, ensure_daemon_running_with_message};
+use crate::manifest::Manifest;
+use crate::runtime::{Request, Response, Runtime};
+
+/// Directory searched when no test files are given.
+const DEFAULT_TEST_DIR: &str = "tests";
+
+/// Suffixes of test files found in directories.
+const TEST_SUFFIXES: [&str; 3] = [".test.yaml", ".test.yml", ".test.json"];
+
+/// Module name the component is served as.
+const MODULE: &str = "component";
+
+/// A test file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TestFile {
+    cases: Vec<TestCase>,
+}
+
+/// One request and the response it should get.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestCase {
+    name: String,
+    #[serde(default)]
+    seed: Seed,
+    request: TestRequest,
+    #[serde(default)]
+    expect: Expect,
+}
+
+/// State set up before the request.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Seed {
+    /// SQL run against the case's empty database.
+    sql: Option<String>,
+    /// KV entries written through the daemon.
+    #[serde(default)]
+    kv: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestRequest {
+    #[serde(default = "default_method")]
+    method: String,
+    path: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    json: Option<Value>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expect {
+    status: Option<u16>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    body_contains: Option<String>,
+    json: Option<Value>,
+}
+
+/// Run the test files in `paths` (default: `tests/`) against `component`
+/// (default: the project's build output in `dist/`).
+///
+/// Cases whose name does not contain `filter` are skipped.
+pub async fn execute(
+    paths: &[String],
+    component: Option<&str>,
+    filter: Option<&str>,
+) -> Result<()> {
+    let component = match component {
+        Some(path) => PathBuf::from(path),
+        None => find_component()?,
+    };
+    let wasm = fs::read(&component).with_context(|| {
+        format!(
+            "Failed to read component: {}\n\nBuild it first:\n  mik build",
+            component.display()
+        )
+    })?;
+
+    let files = if paths.is_empty() {
+        find_test_files(Path::new(DEFAULT_TEST_DIR))?
+    } else {
+        let mut files = Vec::new();
+        for path in paths.iter().map(Path::new) {
+            if path.is_dir() {
+                files.extend(find_test_files(path)?);
+            } else {
+                files.push(path.to_path_buf());
+            }
+        }
+        files
+    };
+    if files.is_empty() {
+        bail!(
+            "No test files found.\n\n\
+             Add cases to {DEFAULT_TEST_DIR}/<name>.test.yaml or pass test files explicitly."
+        );
+    }
+
+    println!("Testing: {}", component.display());
+    let manifest = Path::new("mik.toml")
+        .exists()
+        .then_some(Path::new("mik.toml"));
+    let (mut passed, mut failed) = (0usize, 0usize);
+
+    for file in &files {
+        let suite = load_test_file(file)?;
+        println!();
+        println!("{}", file.display());
+
+        for case in &suite.cases {
+            if filter.is_some_and(|filter| !case.name.contains(filter)) {
+                continue;
+            }
+            let started = Instant::now();
+            let failures = match run_case(&wasm, manifest, case).await {
+                Ok(failures) => failures,
+                Err(e) => vec![format!("{e:#}")],
+            };
+            let elapsed = started.elapsed().as_millis();
+            if failures.is_empty() {
+                passed += 1;
+                println!("  ok    {} ({elapsed} ms)", case.name);
+            } else {
+                failed += 1;
+                println!("  FAIL  {} ({elapsed} ms)", case.name);
+                for failure in failures {
+                    println!("        {failure}");
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{passed} passed, {failed} failed");
+    if failed > 0 {
+        bail!("{failed} test case(s) failed");
+    }
+    Ok(())
+}
+
+/// The project's built component: `dist/<name>-composed.wasm` or
+/// `dist/<name>.wasm`, or the only wasm in `dist/`.
+fn find_component() -> Result<PathBuf> {
+    let dist = Path::new("dist");
+    if let Ok(manifest) = Manifest::load() {
+        let name = &manifest.project.name;
+        for candidate in [format!("{name}-composed.wasm"), format!("{name}.wasm")] {
+            let path = dist.join(candidate);
+            if path.exists() {
+                return Ok(path);
+            }
+        }
+    }
+
+    let mut wasm: Vec<PathBuf> = fs::read_dir(dist)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    match wasm.len() {
+        1 => Ok(wasm.remove(0)),
+        0 => bail!(
+            "No built component found in dist/\n\n\
+             Build the component first:\n  mik build"
+        ),
+        _ => bail!("Several components in dist/, choose one with --component"),
+    }
+}
+
+/// Test files under `dir`, sorted.
+fn find_test_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.file_name().is_some_and(|name| {
+                let name = name.to_string_lossy();
+                TEST_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+            }) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Parse a test file, as JSON for `.json` and YAML otherwise.
+pub(crate) fn load_test_file(path: &Path) -> Result<TestFile> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let parsed = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&content).map_err(anyhow::Error::from)
+    } else {
+        serde_yaml::from_str(&content).map_err(anyhow::Error::from)
+    };
+    parsed.with_context(|| format!("Invalid test file: {}", path.display()))
+}
+
+/// Run one case in a fresh runtime, returning its failed expectations.
+async fn run_case(wasm: &[u8], manifest: Option<&Path>, case: &TestCase) -> Result<Vec<String>> {
+    let dir = tempfile::tempdir().context("Failed to create test directory")?;
+    let modules_dir = dir.path().join("modules");
+    fs::create_dir(&modules_dir)?;
+    fs::write(modules_dir.join(format!("{MODULE}.wasm")), wasm)?;
+
+    let database = dir.path().join("sql.db");
+    if let Some(sql) = &case.seed.sql {
+        let service = SqlService::file(&database)?;
+        service
+            .execute_batch(sql)
+            .await
+            .context("Failed to seed SQL")?;
+    }
+
+    let builder = match manifest {
+        Some(path) => Runtime::builder()
+            .from_manifest_file(path)
+            .context("Failed to load mik.toml")?,
+        None => Runtime::builder(),
+    };
+    let runtime = builder
+        .modules_dir(&modules_dir)
+        .sql_modules(vec!["*".to_string()])
+        .sql_database(&database)
+        .build()
+        .context("Failed to build runtime")?;
+
+    let kv = seed_kv(&case.seed.kv).await?;
+    let response = runtime.handle_request(build_request(&case.request)).await;
+    if let Some(client) = kv {
+        for key in case.seed.kv.keys() {
+            let _ = client.kv_delete(key).await;
+        }
+    }
+
+    Ok(check(&case.expect, &response.context("Request failed")?))
+}
+
+/// Write `entries` through the local daemon.
+async fn seed_kv(entries: &BTreeMap<String, Value>) -> Result<Option<DaemonClient>> {
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    ensure_daemon_running_with_message(false)
+        .await
+        .context("KV seeding needs the mik daemon")?;
+    let client = DaemonClient::local(DAEMON_PORT).api_key_from_env();
+    for (key, value) in entries {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        client
+            .kv_set(key, &value, None)
+            .await
+            .with_context(|| format!("Failed to seed KV key '{key}'"))?;
+    }
+    Ok(Some(client))
+}
+
+fn build_request(spec: &TestRequest) -> Request {
+    let path = format!("/run/{MODULE}/{}", spec.path.trim_start_matches('/'));
+    let mut request = Request::new(spec.method.to_uppercase(), path);
+    if let Some(json) = &spec.json {
+        request = request
+            .with_header("content-type", "application/json")
+            .with_body(json.to_string().into_bytes());
+    } else if let Some(body) = &spec.body {
+        request = request.with_body_str(body.clone());
+    }
+    for (name, value) in &spec.headers {
+        request = request.with_header(name.clone(), value.clone());
+    }
+    // WASI HTTP requires an authority
+    if request.header("host").is_none() {
+        request = request.with_header("host", "localhost");
+    }
+    request
+}
+
+/// Expectations `response` does not meet.
+fn check(expect: &Expect, response: &Response) -> Vec<String> {
+    let mut failures = Vec::new();
+    let text = String::from_utf8_lossy(&response.body);
+
+    match expect.status {
+        Some(status) if response.status != status => failures.push(format!(
+            "status: expected {status}, got {}",
+            response.status
+        )),
+        None if !(200..300).contains(&response.status) => {
+            failures.push(format!("status: expected 2xx, got {}", response.status))
+        },
+        _ => {},
+    }
+
+    for (name, expected) in &expect.headers {
+        match response.header(name) {
+            Some(actual) if actual == expected => {},
+            Some(actual) => failures.push(format!(
+                "header '{name}': expected \"{expected}\", got \"{actual}\""
+            )),
+            None => failures.push(format!("header '{name}': missing")),
+        }
+    }
+
+    if let Some(expected) = &expect.body
+        && text != expected.as_str()
+    {
+        failures.push(format!("body: expected \"{expected}\", got \"{text}\""));
+    }
+    if let Some(expected) = &expect.body_contains
+        && !text.contains(expected.as_str())
+    {
+        failures.push(format!(
+            "body: expected to contain \"{expected}\", got \"{text}\""
+        ));
+    }
+
+    if let Some(expected) = &expect.json {
+        match serde_json::from_slice::<Value>(&response.body) {
+            Ok(actual) if json_matches(expected, &actual) => {},
+            Ok(actual) => failures.push(format!("json: expected {expected}, got {actual}")),
+            Err(_) => failures.push(format!("json: expected {expected}, got \"{text}\"")),
+        }
+    }
+
+    failures
+}
+
+/// Whether `actual` matches `expected`, ignoring object fields `expected`
+/// does not list. Arrays must have the same length.
+fn json_matches(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| json_matches(value, actual))
+        }),
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(actual)
+                    .all(|(expected, actual)| json_matches(expected, actual))
+        },
+        _ => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_yaml_and_json() {
+        let yaml: TestFile = serde_yaml::from_str(
+            r"
+cases:
+  - name: create
+    seed:
+      sql: CREATE TABLE t (id INTEGER);
+      kv: { flag: on, count: 3 }
+    request:
+      method: post
+      path: /items
+      json: { name: book }
+    expect:
+      status: 201
+      json: { name: book }
+",
+        )
+        .unwrap();
+        let case = &yaml.cases[0];
+        assert_eq!(case.seed.kv["flag"], "on");
+        assert_eq!(case.seed.kv["count"], 3);
+        assert_eq!(case.request.json, Some(json!({ "name": "book" })));
+        assert_eq!(case.expect.status, Some(201));
+
+        let json: TestFile = serde_json::from_str(
+            r#"{ "cases": [{ "name": "health", "request": { "path": "/" } }] }"#,
+        )
+        .unwrap();
+        assert_eq!(json.cases[0].request.method, "GET");
+        assert!(json.cases[0].expect.status.is_none());
+
+        assert!(
+            serde_yaml::from_str::<TestFile>("cases: [{ name: x, request: { url: / } }]").is_err()
+        );
+    }
+
+    #[test]
+    fn test_build_request() {
+        let request = build_request(&TestRequest {
+            method: "post".to_string(),
+            path: "/items".to_string(),
+            headers: [("x-test".to_string(), "1".to_string())].into(),
+            body: None,
+            json: Some(json!({ "a": 1 })),
+        });
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/run/component/items");
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.header("x-test"), Some("1"));
+        assert_eq!(request.header("host"), Some("localhost"));
+    }
+
+    #[test]
+    fn test_check() {
+        let response = Response::new(201)
+            .with_header("Content-Type", "application/json")
+            .with_body_str(r#"{"id":7,"name":"book","tags":["a"]}"#);
+
+        let expect = Expect {
+            status: Some(201),
+            headers: [("content-type".to_string(), "application/json".to_string())].into(),
+            body_contains: Some("book".to_string()),
+            json: Some(json!({ "name": "book", "tags": ["a"] })),
+            ..Expect::default()
+        };
+        assert!(check(&expect, &response).is_empty());
+
+        let expect = Expect {
+            status: Some(200),
+            headers: [("x-missing".to_string(), "1".to_string())].into(),
+            body: Some("book".to_string()),
+            json: Some(json!({ "tags": [] })),
+            ..Expect::default()
+        };
+        let failures = check(&expect, &response);
+        assert_eq!(failures.len(), 4, "{failures:?}");
+
+        // Any 2xx by default
+        assert!(check(&Expect::default(), &response).is_empty());
+        assert_eq!(check(&Expect::default(), &Response::new(500)).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_case_against_echo() {
+        let wasm = fs::read(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules/echo.wasm"),
+        )
+        .unwrap();
+        let suite: TestFile = serde_yaml::from_str(
+            r#"
+cases:
+  - name: echoes json
+    seed:
+      sql: CREATE TABLE t (id INTEGER);
+    request:
+      method: POST
+      path: /
+      json: { message: hello }
+    expect:
+      status: 200
+      json: { message: hello }
+  - name: wrong body
+    request:
+      method: POST
+      path: /
+      body: hi
+    expect:
+      body: bye
+"#,
+        )
+        .unwrap();
+
+        let failures = run_case(&wasm, None, &suite.cases[0]).await.unwrap();
+        assert!(failures.is_empty(), "{failures:?}");
+        let failures = run_case(&wasm, None, &suite.cases[1]).await.unwrap();
+        assert_eq!(failures, [r#"body: expected "bye", got "hi""#]);
+    }
+
+    #[test]
+    fn test_find_test_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("api")).unwrap();
+        fs::write(dir.path().join("api/orders.test.yaml"), "").unwrap();
+        fs::write(dir.path().join("health.test.json"), "").unwrap();
+        fs::write(dir.path().join("fixture.json"), "").unwrap();
+        fs::write(dir.path().join("integration.rs"), "").unwrap();
+
+        let files = find_test_files(dir.path()).unwrap();
+        assert_eq!(
+            files,
+            [
+                dir.path().join("api/orders.test.yaml"),
+                dir.path().join("health.test.json")
+            ]
+        );
+        assert!(
+            find_test_files(&dir.path().join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Metrics-driven configuration tuning.
+//!
+//! `mik tune` scrapes a running instance's `/metrics` endpoint twice, a
+//! window apart, and turns the counter deltas into concrete `[server]`
+//! recommendations:
+//!
+//! - Module cache hit rate while the cache is full -> `max_cache_mb` / `cache_size`
+//! - Requests waiting for a concurrency permit (instance pool exhausted) ->
+//!   `max_concurrent_requests`
+//! - Requests rejected by the per-module limit -> `max_per_module_requests`
+//! - Module loads that missed the AOT cache -> advisory only
+//!
+//! With `--write`, recommended values are saved to `mik.toml`.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::manifest::{Manifest, ServerConfig};
+
+/// Minimum module cache lookups in the window before judging hit rate.
+const MIN_CACHE_LOOKUPS: f64 = 50.0;
+
+/// Hit rate below which a full module cache is considered too small.
+const TARGET_HIT_RATE: f64 = 0.95;
+
+/// Fraction of capacity at which the module cache counts as full.
+const CACHE_FULL_RATIO: f64 = 0.9;
+
+/// Fraction of requests waiting for a permit that warrants a higher limit.
+const PERMIT_WAIT_RATIO: f64 = 0.01;
+
+/// Parsed Prometheus samples, keyed by `name` or `name{labels}`.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct MetricsSnapshot {
+    samples: HashMap<String, f64>,
+}
+
+impl MetricsSnapshot {
+    /// Parse Prometheus text exposition format.
+    ///
+    /// Comments, blank lines, and lines whose value is not a number are skipped.
+    fn parse(text: &str) -> Self {
+        let samples = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (key, value) = line.rsplit_once(' ')?;
+                Some((key.trim().to_string(), value.parse().ok()?))
+            })
+            .collect();
+        Self { samples }
+    }
+
+    fn get(&self, key: &str) -> f64 {
+        self.samples.get(key).copied().unwrap_or(0.0)
+    }
+
+    /// Values of a labelled metric, keyed by the given label.
+    fn by_label(&self, name: &str, label: &str) -> HashMap<String, f64> {
+        let prefix = format!("{name}{{{label}=\"");
+        self.samples
+            .iter()
+            .filter_map(|(key, value)| {
+                let rest = key.strip_prefix(&prefix)?;
+                let (label_value, _) = rest.split_once('"')?;
+                Some((label_value.to_string(), *value))
+            })
+            .collect()
+    }
+}
+
+/// Counter increase between two snapshots (handles restarts).
+fn delta(before: f64, after: f64) -> f64 {
+    if after >= before {
+        after - before
+    } else {
+        after
+    }
+}
+
+/// A `[server]` setting the report can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Setting {
+    MaxCacheMb,
+    CacheSize,
+    MaxConcurrentRequests,
+    MaxPerModuleRequests,
+}
+
+impl Setting {
+    const fn key(self) -> &'static str {
+        match self {
+            Self::MaxCacheMb => "max_cache_mb",
+            Self::CacheSize => "cache_size",
+            Self::MaxConcurrentRequests => "max_concurrent_requests",
+            Self::MaxPerModuleRequests => "max_per_module_requests",
+        }
+    }
+
+    fn apply(self, server: &mut ServerConfig, value: usize) {
+        match self {
+            Self::MaxCacheMb => server.max_cache_mb = value,
+            Self::CacheSize => server.cache_size = value,
+            Self::MaxConcurrentRequests => server.max_concurrent_requests = value,
+            Self::MaxPerModuleRequests => server.max_per_module_requests = value,
+        }
+    }
+}
+
+/// One finding from the analysis.
+#[derive(Debug, Clone, PartialEq)]
+struct Recommendation {
+    /// Setting to change and its (current, suggested) values; `None` for advice.
+    change: Option<(Setting, usize, usize)>,
+    reason: String,
+}
+
+/// Compare two scrapes taken `window` apart and suggest configuration changes.
+fn analyze(
+    before: &MetricsSnapshot,
+    after: &MetricsSnapshot,
+    window: Duration,
+) -> Vec<Recommendation> {
+    let mut recommendations = Vec::new();
+    let d = |name: &str| delta(before.get(name), after.get(name));
+    let window_secs = window.as_secs();
+
+    // Module cache
+    let hits = d("mik_module_cache_hits_total");
+    let misses = d("mik_module_cache_misses_total");
+    let lookups = hits + misses;
+    if lookups >= MIN_CACHE_LOOKUPS {
+        let hit_rate = hits / lookups;
+        let capacity_bytes = after.get("mik_cache_capacity_bytes");
+        let cache_bytes = after.get("mik_cache_bytes");
+        let capacity_entries = after.get("mik_cache_capacity_entries");
+        let entries = after.get("mik_cache_entries");
+
+        if hit_rate < TARGET_HIT_RATE {
+            let stats = format!(
+                "Module cache hit rate was {:.1}% over {window_secs}s ({misses:.0} misses)",
+                hit_rate * 100.0
+            );
+            if capacity_bytes > 0.0 && cache_bytes >= capacity_bytes * CACHE_FULL_RATIO {
+                let current_mb = (capacity_bytes / (1024.0 * 1024.0)).ceil() as usize;
+                recommendations.push(Recommendation {
+                    change: Some((Setting::MaxCacheMb, current_mb, (current_mb * 2).max(1))),
+                    reason: format!("{stats} with the cache at its byte limit"),
+                });
+            } else if capacity_entries > 0.0 && entries >= capacity_entries {
+                let current = capacity_entries as usize;
+                recommendations.push(Recommendation {
+                    change: Some((Setting::CacheSize, current, current * 2)),
+                    reason: format!("{stats} with the cache at its entry limit"),
+                });
+            } else {
+                recommendations.push(Recommendation {
+                    change: None,
+                    reason: format!(
+                        "{stats}, but the cache has headroom; misses are first loads, not evictions"
+                    ),
+                });
+            }
+        }
+    }
+
+    // Global concurrency / instance pool
+    let requests = d("mik_requests_total");
+    let waits = d("mik_request_permit_waits_total");
+    let max_concurrent = after.get("mik_max_concurrent_requests") as usize;
+    if waits > 0.0 && requests > 0.0 && waits / requests >= PERMIT_WAIT_RATIO && max_concurrent > 0
+    {
+        recommendations.push(Recommendation {
+            change: Some((
+                Setting::MaxConcurrentRequests,
+                max_concurrent,
+                max_concurrent * 2,
+            )),
+            reason: format!(
+                "{waits:.0} of {requests:.0} requests ({:.1}%) waited for a free instance slot",
+                waits / requests * 100.0
+            ),
+        });
+    }
+
+    // Per-module limits
+    let before_overloads = before.by_label("mik_module_overload_total", "module");
+    let mut overloaded: Vec<(String, f64)> = after
+        .by_label("mik_module_overload_total", "module")
+        .into_iter()
+        .map(|(module, count)| {
+            let previous = before_overloads.get(&module).copied().unwrap_or(0.0);
+            (module, delta(previous, count))
+        })
+        .filter(|(_, count)| *count > 0.0)
+        .collect();
+    let max_per_module = after.get("mik_max_per_module_requests") as usize;
+    if !overloaded.is_empty() && max_per_module > 0 {
+        overloaded.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let suggested = if max_concurrent > max_per_module {
+            (max_per_module * 2).min(max_concurrent)
+        } else {
+            max_per_module * 2
+        };
+        let modules = overloaded
+            .iter()
+            .map(|(module, count)| format!("{module} ({count:.0})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        recommendations.push(Recommendation {
+            change: Some((Setting::MaxPerModuleRequests, max_per_module, suggested)),
+            reason: format!("Requests rejected by the per-module limit: {modules}"),
+        });
+    }
+
+    // AOT cache
+    let aot_hits = d("mik_aot_cache_hits_total");
+    let aot_misses = d("mik_aot_cache_misses_total");
+    if aot_misses > 0.0 && aot_misses >= aot_hits {
+        recommendations.push(Recommendation {
+            change: None,
+            reason: format!(
+                "{aot_misses:.0} of {:.0} module loads were compiled from scratch; check \
+                 `mik cache info` and keep the AOT cache large enough to hold all modules",
+                aot_hits + aot_misses
+            ),
+        });
+    }
+
+    recommendations
+}
+
+/// Fetch and parse a metrics snapshot.
+async fn scrape(client: &reqwest::Client, url: &str) -> Result<MetricsSnapshot> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {url} - is the instance running?"))?
+        .error_for_status()
+        .with_context(|| format!("Metrics request to {url} failed"))?;
+    let body = response.text().await.context("Failed to read metrics")?;
+    Ok(MetricsSnapshot::parse(&body))
+}
+
+/// Execute the tune command.
+pub async fn execute(url: Option<String>, window_secs: u64, write: bool) -> Result<()> {
+    let url = url.unwrap_or_else(|| {
+        let port = Manifest::load_port().unwrap_or(3000);
+        format!("http://127.0.0.1:{port}/metrics")
+    });
+    let window = Duration::from_secs(window_secs.max(1));
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let before = scrape(&client, &url).await?;
+    let spinner = crate::ui::create_spinner(&format!(
+        "Collecting metrics from {url} for {}s...",
+        window.as_secs()
+    ));
+    tokio::time::sleep(window).await;
+    spinner.finish_and_clear();
+    let after = scrape(&client, &url).await?;
+
+    let recommendations = analyze(&before, &after, window);
+    if recommendations.is_empty() {
+        println!(
+            "No changes recommended over the {}s window.",
+            window.as_secs()
+        );
+        return Ok(());
+    }
+
+    println!("Recommendations");
+    println!("===============");
+    for rec in &recommendations {
+        match rec.change {
+            Some((setting, current, suggested)) => {
+                println!("  {}: {current} -> {suggested}", setting.key());
+            },
+            None => println!("  (advice)"),
+        }
+        println!("    {}", rec.reason);
+    }
+
+    let changes: Vec<(Setting, usize)> = recommendations
+        .iter()
+        .filter_map(|rec| {
+            rec.change
+                .map(|(setting, _, suggested)| (setting, suggested))
+        })
+        .collect();
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    if write {
+        let path = Path::new("mik.toml");
+        let mut manifest = Manifest::load_from(path)?;
+        for (setting, value) in &changes {
+            setting.apply(&mut manifest.server, *value);
+        }
+        manifest.save_to(path)?;
+        println!();
+        println!("Updated {} ({} settings)", path.display(), changes.len());
+    } else {
+        println!();
+        println!("Run with --write to apply these settings to mik.toml");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(text: &str) -> MetricsSnapshot {
+        MetricsSnapshot::parse(text)
+    }
+
+    #[test]
+    fn test_parse_prometheus_text() {
+        let snap = snapshot(
+            "# HELP mik_requests_total Total\n\
+             # TYPE mik_requests_total counter\n\
+             mik_requests_total 42\n\
+             \n\
+             mik_module_overload_total{module=\"orders\"} 7\n",
+        );
+        assert!((snap.get("mik_requests_total") - 42.0).abs() < f64::EPSILON);
+        assert!(snap.get("missing").abs() < f64::EPSILON);
+        let overloads = snap.by_label("mik_module_overload_total", "module");
+        assert!((overloads["orders"] - 7.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_full_cache_with_low_hit_rate_recommends_more_memory() {
+        let before = snapshot(
+            "mik_module_cache_hits_total 0\n\
+             mik_module_cache_misses_total 0\n",
+        );
+        let after = snapshot(
+            "mik_module_cache_hits_total 60\n\
+             mik_module_cache_misses_total 40\n\
+             mik_cache_bytes 268000000\n\
+             mik_cache_capacity_bytes 268435456\n",
+        );
+
+        let recs = analyze(&before, &after, Duration::from_secs(60));
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].change, Some((Setting::MaxCacheMb, 256, 512)));
+        assert!(recs[0].reason.contains("60.0%"));
+    }
+
+    #[test]
+    fn test_cache_with_headroom_only_gives_advice() {
+        let after = snapshot(
+            "mik_module_cache_hits_total 10\n\
+             mik_module_cache_misses_total 90\n\
+             mik_cache_bytes 1000\n\
+             mik_cache_capacity_bytes 268435456\n\
+             mik_cache_entries 3\n\
+             mik_cache_capacity_entries 100\n",
+        );
+
+        let recs = analyze(&MetricsSnapshot::default(), &after, Duration::from_secs(60));
+        assert_eq!(recs.len(), 1);
+        assert!(recs[0].change.is_none());
+    }
+
+    #[test]
+    fn test_permit_waits_and_module_overloads() {
+        let before = snapshot(
+            "mik_requests_total 1000\n\
+             mik_request_permit_waits_total 5\n\
+             mik_module_overload_total{module=\"orders\"} 10\n",
+        );
+        let after = snapshot(
+            "mik_requests_total 2000\n\
+             mik_request_permit_waits_total 105\n\
+             mik_max_concurrent_requests 64\n\
+             mik_max_per_module_requests 16\n\
+             mik_module_overload_total{module=\"orders\"} 30\n\
+             mik_module_overload_total{module=\"users\"} 3\n",
+        );
+
+        let recs = analyze(&before, &after, Duration::from_secs(60));
+        assert_eq!(recs.len(), 2);
+        assert_eq!(
+            recs[0].change,
+            Some((Setting::MaxConcurrentRequests, 64, 128))
+        );
+        assert_eq!(
+            recs[1].change,
+            Some((Setting::MaxPerModuleRequests, 16, 32))
+        );
+        assert!(recs[1].reason.contains("orders (20), users (3)"));
+    }
+
+    #[test]
+    fn test_quiet_window_has_no_recommendations() {
+        let snap = snapshot("mik_requests_total 5\nmik_module_cache_hits_total 5\n");
+        assert!(analyze(&snap, &snap, Duration::from_secs(60)).is_empty());
+    }
+
+    #[test]
+    fn test_aot_misses_give_advice() {
+        let after = snapshot("mik_aot_cache_hits_total 1\nmik_aot_cache_misses_total 4\n");
+        let recs = analyze(&MetricsSnapshot::default(), &after, Duration::from_secs(30));
+        assert_eq!(recs.len(), 1);
+        assert!(recs[0].change.is_none());
+        assert!(recs[0].reason.contains("4 of 5"));
+    }
+
+    #[test]
+    fn test_counter_reset_uses_current_value() {
+        assert!((delta(100.0, 30.0) - 30.0).abs() < f64::EPSILON);
+        assert!((delta(10.0, 30.0) - 20.0).abs() < f64::EPSILON);
+    }
+}
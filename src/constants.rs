@@ -14,6 +14,9 @@
 /// Prevents memory exhaustion from large uploads.
 pub const MAX_BODY_SIZE_BYTES: usize = 10 * 1024 * 1024;
 
+/// Maximum size of a module uploaded through the gateway (50 MB).
+pub const MAX_MODULE_SIZE_BYTES: usize = 50 * 1024 * 1024;
+
 /// Maximum path length for security validation (4096 bytes).
 pub const MAX_PATH_LENGTH: usize = 4096;
 
@@ -25,6 +28,10 @@ pub const MAX_WASM_TIMEOUT_SECS: u64 = 30;
 /// Trade-off: ~10-20% overhead but guarantees deterministic execution limits.
 pub const DEFAULT_FUEL_BUDGET: u64 = 1_000_000_000;
 
+/// Time a connection has to send its PROXY protocol header (5 seconds).
+/// Prevents idle connections from holding request permits.
+pub const PROXY_HEADER_TIMEOUT_SECS: u64 = 5;
+
 /// Minimum size for gzip compression (1 KB).
 /// Smaller responses don't benefit from compression overhead.
 pub const GZIP_MIN_SIZE: usize = 1024;
@@ -45,6 +52,16 @@ pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 1000;
 /// Default per-module concurrent request limit.
 pub const DEFAULT_MAX_PER_MODULE_REQUESTS: usize = 10;
 
+/// Default time a request waits in a module's queue (1 second).
+pub const DEFAULT_MODULE_QUEUE_TIMEOUT_MS: u64 = 1000;
+
+/// Default delay before retrying a transient execution failure (10ms).
+pub const DEFAULT_EXECUTION_RETRY_BACKOFF_MS: u64 = 10;
+
+/// Upper bound for the delay between execution retries.
+/// Rationale: retries share the request's deadline, so long waits only add latency.
+pub const EXECUTION_RETRY_MAX_BACKOFF_MS: u64 = 500;
+
 /// Circuit breaker failure threshold before opening.
 /// Rationale: 5 consecutive failures indicates a real problem, not transient.
 pub const CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 5;
@@ -78,6 +95,12 @@ pub const DEFAULT_MODULES_DIR: &str = "modules";
 /// Health check status indicating the service is ready.
 pub const HEALTH_STATUS_READY: &str = "ready";
 
+/// Health check status when the deep health check's canary fails.
+pub const HEALTH_STATUS_UNHEALTHY: &str = "unhealthy";
+
+/// Time the deep health check's canary may take (1 second).
+pub const CANARY_TIMEOUT_MS: u64 = 1000;
+
 // =============================================================================
 // AOT Cache
 // =============================================================================
@@ -0,0 +1,187 @@
+//! Backup archive creation.
+//!
+//! Packs selected entries of the data directory into a gzip-compressed tar
+//! archive. SQLite databases are snapshotted with `VACUUM INTO` so the archive
+//! holds a consistent copy even while the daemon is writing to them.
+
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Result of packing an archive.
+#[derive(Debug)]
+pub(crate) struct Archive {
+    /// Compressed archive bytes.
+    pub bytes: Vec<u8>,
+    /// Number of files written to the archive.
+    pub files: usize,
+}
+
+/// Pack `include` entries (relative to `data_dir`) into a `.tar.gz`.
+///
+/// Missing entries are skipped. Paths under `exclude` (relative to
+/// `data_dir`) are left out, which keeps previous archives stored in the
+/// local storage service from being backed up again.
+pub(crate) fn create_archive(
+    data_dir: &Path,
+    include: &[String],
+    exclude: Option<&Path>,
+) -> Result<Archive> {
+    let snapshot_dir = tempfile::tempdir().context("Failed to create snapshot directory")?;
+    let encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+    let mut files = 0;
+
+    for entry in include {
+        let relative = PathBuf::from(entry.trim_matches('/'));
+        let path = data_dir.join(&relative);
+        if !path.exists() {
+            tracing::debug!(path = %path.display(), "Backup entry not found, skipping");
+            continue;
+        }
+        files += append_path(
+            &mut tar,
+            data_dir,
+            &path,
+            exclude.map(|e| data_dir.join(e)).as_deref(),
+            snapshot_dir.path(),
+        )?;
+    }
+
+    let encoder = tar
+        .into_inner()
+        .context("Failed to finish backup archive")?;
+    let bytes = encoder
+        .finish()
+        .context("Failed to compress backup archive")?;
+    Ok(Archive { bytes, files })
+}
+
+/// Append a file or directory tree, returning the number of files added.
+fn append_path<W: std::io::Write>(
+    tar: &mut tar::Builder<W>,
+    data_dir: &Path,
+    path: &Path,
+    exclude: Option<&Path>,
+    snapshot_dir: &Path,
+) -> Result<usize> {
+    if exclude.is_some_and(|e| path.starts_with(e)) {
+        return Ok(0);
+    }
+
+    let name = path.strip_prefix(data_dir).unwrap_or(path);
+
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        entries.sort();
+
+        let mut files = 0;
+        for entry in entries {
+            files += append_path(tar, data_dir, &entry, exclude, snapshot_dir)?;
+        }
+        return Ok(files);
+    }
+
+    let source = if is_sqlite(path) {
+        snapshot_sqlite(path, snapshot_dir).unwrap_or_else(|e| {
+            tracing::warn!(
+                path = %path.display(),
+                error = %e,
+                "SQLite snapshot failed, copying file as-is"
+            );
+            path.to_path_buf()
+        })
+    } else {
+        path.to_path_buf()
+    };
+
+    let mut file =
+        File::open(&source).with_context(|| format!("Failed to open {}", source.display()))?;
+    tar.append_file(name, &mut file)
+        .with_context(|| format!("Failed to add {} to backup", path.display()))?;
+    Ok(1)
+}
+
+fn is_sqlite(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "db")
+}
+
+/// Write a consistent copy of a SQLite database into `snapshot_dir`.
+fn snapshot_sqlite(path: &Path, snapshot_dir: &Path) -> Result<PathBuf> {
+    let target = snapshot_dir.join(format!("{}.snapshot", uuid::Uuid::new_v4()));
+    let conn = rusqlite::Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("Failed to open {}", path.display()))?;
+    conn.execute("VACUUM INTO ?1", [target.to_string_lossy().as_ref()])
+        .with_context(|| format!("Failed to snapshot {}", path.display()))?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+
+    fn entries(bytes: &[u8]) -> Vec<String> {
+        let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+        let mut names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().display().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_archive_includes_files_and_skips_excluded() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("kv.redb"), b"kv").unwrap();
+        std::fs::create_dir_all(dir.path().join("storage/images")).unwrap();
+        std::fs::create_dir_all(dir.path().join("storage/backups")).unwrap();
+        std::fs::write(dir.path().join("storage/images/logo.png"), b"png").unwrap();
+        std::fs::write(dir.path().join("storage/backups/old.tar.gz"), b"old").unwrap();
+
+        let archive = create_archive(
+            dir.path(),
+            &["kv.redb".into(), "storage".into(), "missing.db".into()],
+            Some(Path::new("storage/backups")),
+        )
+        .unwrap();
+
+        assert_eq!(archive.files, 2);
+        assert_eq!(
+            entries(&archive.bytes),
+            vec!["kv.redb".to_string(), "storage/images/logo.png".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_sqlite_is_snapshotted() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("sql.db");
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch("CREATE TABLE t (v TEXT); INSERT INTO t VALUES ('kept');")
+            .unwrap();
+
+        let archive = create_archive(dir.path(), &["sql.db".into()], None).unwrap();
+        assert_eq!(entries(&archive.bytes), vec!["sql.db".to_string()]);
+
+        let restore = tempfile::tempdir().unwrap();
+        tar::Archive::new(GzDecoder::new(archive.bytes.as_slice()))
+            .unpack(restore.path())
+            .unwrap();
+        let restored = rusqlite::Connection::open(restore.path().join("sql.db")).unwrap();
+        let value: String = restored
+            .query_row("SELECT v FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(value, "kept");
+    }
+}
@@ -0,0 +1,417 @@
+//! Scheduled backups of daemon data.
+//!
+//! Backups run as cron jobs of type `backup`. Each run packs the daemon data
+//! directory (KV, SQL, storage, state) into a `.tar.gz`, uploads it to the
+//! embedded storage service or an S3 bucket, and prunes old archives
+//! according to the job's retention policy.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [[schedules]]
+//! name = "nightly-backup"
+//! type = "backup"
+//! cron = "0 0 3 * * * *"  # Daily at 03:00
+//!
+//! [schedules.backup.retention]
+//! keep_daily = 7
+//! keep_weekly = 4
+//!
+//! [schedules.backup.target]
+//! kind = "s3"
+//! bucket = "mik-backups"
+//! prefix = "prod"
+//! region = "eu-west-1"
+//! ```
+//!
+//! Without a `target`, archives are stored under `backups/` in the embedded
+//! storage service (which is itself excluded from the archive).
+//!
+//! Jobs of type `sql-backup` take point-in-time snapshots of the SQL
+//! database alone, with a simpler keep-the-newest-N retention; see
+//! [`SqlBackupConfig`].
+//!
+//! # Module Structure
+//!
+//! - `archive` - Archive creation (SQLite databases are snapshotted first)
+//! - `retention` - Archive naming and retention selection
+//! - `s3` - Minimal SigV4-signed S3 client
+//! - `sql` - SQL database snapshots
+
+mod archive;
+mod retention;
+pub(crate) mod s3;
+mod sql;
+
+pub use retention::RetentionPolicy;
+#[allow(unused_imports)] // SqlBackupTarget is public API for library users
+pub use sql::{SqlBackupConfig, SqlBackupTarget};
+
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::daemon::services::kv::KvStore;
+use crate::daemon::services::sql::SqlService;
+use crate::daemon::services::storage::StorageService;
+
+/// Content type of uploaded archives.
+const ARCHIVE_CONTENT_TYPE: &str = "application/gzip";
+
+/// Backup settings of a `backup` cron job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupJobConfig {
+    /// Entries of the data directory to include (default: all daemon data).
+    #[serde(default = "default_include")]
+    pub include: Vec<String>,
+    /// Retention policy (default: 7 daily, 4 weekly).
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    /// Upload target (default: embedded storage under `backups/`).
+    #[serde(default)]
+    pub target: BackupTarget,
+}
+
+impl Default for BackupJobConfig {
+    fn default() -> Self {
+        Self {
+            include: default_include(),
+            retention: RetentionPolicy::default(),
+            target: BackupTarget::default(),
+        }
+    }
+}
+
+/// Default data directory entries to back up.
+pub fn default_include() -> Vec<String> {
+    ["kv.redb", "sql.db", "sql-tenants", "storage", "state.redb"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Where backup archives are uploaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum BackupTarget {
+    /// Embedded storage service, under `prefix`.
+    Storage {
+        /// Object prefix (default: "backups").
+        #[serde(default = "default_prefix")]
+        prefix: String,
+    },
+    /// Remote S3 (or S3-compatible) bucket.
+    ///
+    /// Credentials are read from `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY`.
+    S3 {
+        /// Bucket name.
+        bucket: String,
+        /// Key prefix (default: "backups").
+        #[serde(default = "default_prefix")]
+        prefix: String,
+        /// Region used for signing (default: "us-east-1").
+        #[serde(default = "default_region")]
+        region: String,
+        /// Custom endpoint for S3-compatible services (e.g., `http://localhost:9000`).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        endpoint: Option<String>,
+    },
+}
+
+impl Default for BackupTarget {
+    fn default() -> Self {
+        Self::Storage {
+            prefix: default_prefix(),
+        }
+    }
+}
+
+fn default_prefix() -> String {
+    "backups".to_string()
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// Outcome of a successful backup run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupReport {
+    /// Key of the uploaded archive.
+    pub key: String,
+    /// Compressed archive size in bytes.
+    pub size_bytes: u64,
+    /// Number of files in the archive.
+    pub files: usize,
+    /// Number of expired archives deleted.
+    pub pruned: usize,
+}
+
+impl std::fmt::Display for BackupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "uploaded {} ({} bytes, {} files), pruned {}",
+            self.key, self.size_bytes, self.files, self.pruned
+        )
+    }
+}
+
+/// Runs backups against the daemon's data directory and services.
+#[derive(Clone)]
+pub struct BackupRunner {
+    data_dir: PathBuf,
+    storage: Option<StorageService>,
+    kv: Option<KvStore>,
+    sql: Option<SqlService>,
+}
+
+impl BackupRunner {
+    /// Creates a runner for `data_dir`.
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            storage: None,
+            kv: None,
+            sql: None,
+        }
+    }
+
+    /// Uses the embedded storage service as the `storage` upload target.
+    #[must_use]
+    pub fn with_storage(mut self, storage: StorageService) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Flushes the KV store before archiving (for write-behind caches).
+    #[must_use]
+    pub fn with_kv(mut self, kv: KvStore) -> Self {
+        self.kv = Some(kv);
+        self
+    }
+
+    /// Snapshots this SQL database in `sql-backup` jobs.
+    #[must_use]
+    pub fn with_sql(mut self, sql: SqlService) -> Self {
+        self.sql = Some(sql);
+        self
+    }
+
+    /// Runs a backup for `job`: archive, upload, then prune.
+    pub async fn run(&self, job: &str, config: &BackupJobConfig) -> Result<BackupReport> {
+        if let Some(kv) = &self.kv {
+            kv.flush().await.context("Failed to flush KV store")?;
+        }
+
+        let target = self.target(config)?;
+
+        // Keep earlier archives in the local storage service out of new ones
+        let exclude = match &config.target {
+            BackupTarget::Storage { prefix } => {
+                Some(Path::new("storage").join(prefix.trim_matches('/')))
+            },
+            BackupTarget::S3 { .. } => None,
+        };
+
+        let data_dir = self.data_dir.clone();
+        let include = config.include.clone();
+        let archive = tokio::task::spawn_blocking(move || {
+            archive::create_archive(&data_dir, &include, exclude.as_deref())
+        })
+        .await
+        .context("Backup task panicked")??;
+
+        let key = retention::archive_key(target.prefix(), job, Utc::now());
+        let size_bytes = archive.bytes.len() as u64;
+        target
+            .put(&key, archive.bytes)
+            .await
+            .with_context(|| format!("Failed to upload backup '{key}'"))?;
+
+        let job_prefix = retention::job_prefix(target.prefix(), job);
+        let archives: Vec<_> = target
+            .list(&job_prefix)
+            .await
+            .context("Failed to list existing backups")?
+            .into_iter()
+            .filter_map(|k| {
+                retention::parse_archive_key(target.prefix(), job, &k).map(|at| (k, at))
+            })
+            .collect();
+
+        let expired = retention::select_expired(&archives, config.retention);
+        for key in &expired {
+            target
+                .delete(key)
+                .await
+                .with_context(|| format!("Failed to delete expired backup '{key}'"))?;
+        }
+
+        Ok(BackupReport {
+            key,
+            size_bytes,
+            files: archive.files,
+            pruned: expired.len(),
+        })
+    }
+
+    fn target<'a>(&'a self, config: &'a BackupJobConfig) -> Result<Target<'a>> {
+        match &config.target {
+            BackupTarget::Storage { prefix } => {
+                let Some(storage) = &self.storage else {
+                    bail!("Storage service is disabled; configure an S3 backup target");
+                };
+                Ok(Target::Storage { storage, prefix })
+            },
+            BackupTarget::S3 {
+                bucket,
+                prefix,
+                region,
+                endpoint,
+            } => {
+                let credentials = s3::Credentials::from_env()?;
+                let client = s3::S3Client::new(bucket, region, endpoint.as_deref(), credentials)?;
+                Ok(Target::S3 { client, prefix })
+            },
+        }
+    }
+}
+
+/// Resolved upload target for a single run.
+enum Target<'a> {
+    Storage {
+        storage: &'a StorageService,
+        prefix: &'a str,
+    },
+    S3 {
+        client: s3::S3Client,
+        prefix: &'a str,
+    },
+}
+
+impl Target<'_> {
+    fn prefix(&self) -> &str {
+        match self {
+            Self::Storage { prefix, .. } | Self::S3 { prefix, .. } => prefix,
+        }
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<()> {
+        match self {
+            Self::Storage { storage, .. } => storage
+                .put_object(key, &bytes, Some(ARCHIVE_CONTENT_TYPE))
+                .await
+                .map(drop),
+            Self::S3 { client, .. } => client.put_object(key, bytes, ARCHIVE_CONTENT_TYPE).await,
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        match self {
+            Self::Storage { storage, .. } => Ok(storage
+                .list_objects(Some(prefix))
+                .await?
+                .into_iter()
+                .map(|meta| meta.path)
+                .collect()),
+            Self::S3 { client, .. } => client.list_keys(prefix).await,
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match self {
+            Self::Storage { storage, .. } => storage.delete_object(key).await.map(drop),
+            Self::S3 { client, .. } => client.delete_object(key).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[tokio::test]
+    async fn test_backup_uploads_and_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("state.redb"), b"state").unwrap();
+
+        let storage = StorageService::memory();
+        // 10 stale daily archives (Jan 22-31): the 6 newest days fill the
+        // daily slots next to the new archive, Jan 22-25 share a week with
+        // the kept Jan 26 archive and expire
+        let base = Utc.with_ymd_and_hms(2025, 1, 31, 3, 0, 0).unwrap();
+        for day in 0..10 {
+            let key = retention::archive_key("backups", "nightly", base - Duration::days(day));
+            storage.put_object(&key, b"old", None).await.unwrap();
+        }
+        storage
+            .put_object("backups/other-20250101T000000Z.tar.gz", b"x", None)
+            .await
+            .unwrap();
+
+        let runner = BackupRunner::new(dir.path()).with_storage(storage.clone());
+        let report = runner
+            .run("nightly", &BackupJobConfig::default())
+            .await
+            .unwrap();
+
+        assert!(report.key.starts_with("backups/nightly-"));
+        assert_eq!(report.files, 1);
+        assert!(report.size_bytes > 0);
+        assert_eq!(report.pruned, 4);
+
+        let remaining = storage
+            .list_objects(Some("backups/nightly-"))
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 7);
+        assert!(remaining.iter().any(|m| m.path == report.key));
+        // Other jobs' archives are never touched
+        assert!(
+            storage
+                .head_object("backups/other-20250101T000000Z.tar.gz")
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_storage_target_requires_storage_service() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = BackupRunner::new(dir.path())
+            .run("nightly", &BackupJobConfig::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Storage service is disabled"));
+    }
+
+    #[test]
+    fn test_config_defaults_from_toml() {
+        let config: BackupJobConfig = toml::from_str("").unwrap();
+        assert_eq!(config, BackupJobConfig::default());
+
+        let config: BackupJobConfig = toml::from_str(
+            r#"
+            include = ["kv.redb"]
+            [target]
+            kind = "s3"
+            bucket = "b"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.include, vec!["kv.redb"]);
+        assert_eq!(
+            config.target,
+            BackupTarget::S3 {
+                bucket: "b".into(),
+                prefix: "backups".into(),
+                region: "us-east-1".into(),
+                endpoint: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Archive naming and retention.
+//!
+//! Archives are named `{prefix}/{job}-{YYYYMMDDTHHMMSSZ}.tar.gz` (SQL
+//! snapshots end in `.db` instead), so the creation time can be recovered
+//! from the key without extra metadata. Retention keeps the newest archive
+//! of each of the most recent `keep_daily` days and `keep_weekly` ISO weeks;
+//! everything else expires. SQL snapshots simply keep the newest `keep`.
+
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Timestamp format embedded in archive names.
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Archive file extension.
+const ARCHIVE_EXTENSION: &str = ".tar.gz";
+
+/// SQL snapshot file extension.
+const SNAPSHOT_EXTENSION: &str = ".db";
+
+/// How many archives to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Keep the newest archive of each of this many recent days (default: 7).
+    #[serde(default = "default_keep_daily")]
+    pub keep_daily: usize,
+    /// Keep the newest archive of each of this many recent weeks (default: 4).
+    #[serde(default = "default_keep_weekly")]
+    pub keep_weekly: usize,
+}
+
+const fn default_keep_daily() -> usize {
+    7
+}
+
+const fn default_keep_weekly() -> usize {
+    4
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_daily: default_keep_daily(),
+            keep_weekly: default_keep_weekly(),
+        }
+    }
+}
+
+/// Prefix shared by all archives of a job (`{prefix}/{job}-`).
+pub(crate) fn job_prefix(prefix: &str, job: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        format!("{job}-")
+    } else {
+        format!("{prefix}/{job}-")
+    }
+}
+
+/// Object key for an archive created at `at`.
+pub(crate) fn archive_key(prefix: &str, job: &str, at: DateTime<Utc>) -> String {
+    key(prefix, job, at, ARCHIVE_EXTENSION)
+}
+
+/// Creation time of an archive, if `key` is one of this job's archives.
+pub(crate) fn parse_archive_key(prefix: &str, job: &str, key: &str) -> Option<DateTime<Utc>> {
+    parse_key(prefix, job, key, ARCHIVE_EXTENSION)
+}
+
+/// Object key (or file name, with an empty prefix) for a SQL snapshot.
+pub(crate) fn snapshot_key(prefix: &str, job: &str, at: DateTime<Utc>) -> String {
+    key(prefix, job, at, SNAPSHOT_EXTENSION)
+}
+
+/// Creation time of a SQL snapshot, if `key` is one of this job's snapshots.
+pub(crate) fn parse_snapshot_key(prefix: &str, job: &str, key: &str) -> Option<DateTime<Utc>> {
+    parse_key(prefix, job, key, SNAPSHOT_EXTENSION)
+}
+
+fn key(prefix: &str, job: &str, at: DateTime<Utc>, extension: &str) -> String {
+    format!(
+        "{}{}{extension}",
+        job_prefix(prefix, job),
+        at.format(TIMESTAMP_FORMAT)
+    )
+}
+
+fn parse_key(prefix: &str, job: &str, key: &str, extension: &str) -> Option<DateTime<Utc>> {
+    let stamp = key
+        .strip_prefix(&job_prefix(prefix, job))?
+        .strip_suffix(extension)?;
+    NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Select everything but the newest `keep` entries (at least one is kept).
+pub(crate) fn select_beyond_count(
+    archives: &[(String, DateTime<Utc>)],
+    keep: usize,
+) -> Vec<String> {
+    let mut sorted: Vec<&(String, DateTime<Utc>)> = archives.iter().collect();
+    sorted.sort_by_key(|(_, at)| std::cmp::Reverse(*at));
+    sorted
+        .into_iter()
+        .skip(keep.max(1))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+/// Select the archives that fall outside the retention policy.
+///
+/// The newest archive is always kept, even with a zero policy.
+pub(crate) fn select_expired(
+    archives: &[(String, DateTime<Utc>)],
+    policy: RetentionPolicy,
+) -> Vec<String> {
+    let mut sorted: Vec<&(String, DateTime<Utc>)> = archives.iter().collect();
+    sorted.sort_by_key(|(_, at)| std::cmp::Reverse(*at));
+
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    let mut expired = Vec::new();
+
+    for (i, (key, at)) in sorted.into_iter().enumerate() {
+        let day = at.date_naive();
+        let week = at.iso_week();
+        let week = (week.year(), week.week());
+
+        // Newest archive per day/week wins; older ones in the same bucket expire
+        let keep_day = days.len() < policy.keep_daily && days.insert(day);
+        let keep_week = weeks.len() < policy.keep_weekly && weeks.insert(week);
+
+        if !(i == 0 || keep_day || keep_week) {
+            expired.push(key.clone());
+        }
+    }
+
+    expired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Timelike};
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_archive_key_roundtrip() {
+        let created = at(2026, 10, 14, 3);
+        let key = archive_key("backups/", "nightly", created);
+        assert_eq!(key, "backups/nightly-20261014T030000Z.tar.gz");
+        assert_eq!(parse_archive_key("backups", "nightly", &key), Some(created));
+
+        assert_eq!(
+            archive_key("", "nightly", created),
+            "nightly-20261014T030000Z.tar.gz"
+        );
+        assert!(parse_archive_key("backups", "other", &key).is_none());
+        assert!(parse_archive_key("backups", "nightly", "backups/nightly-notes.txt").is_none());
+    }
+
+    #[test]
+    fn test_keeps_seven_daily_and_four_weekly() {
+        // Two archives a day for 60 days, ending on Wednesday 2026-10-14
+        let end = at(2026, 10, 14, 12);
+        let archives: Vec<(String, DateTime<Utc>)> = (0..120)
+            .map(|i| {
+                let created = end - Duration::hours(12 * i);
+                (archive_key("b", "job", created), created)
+            })
+            .collect();
+
+        let expired: HashSet<String> = select_expired(&archives, RetentionPolicy::default())
+            .into_iter()
+            .collect();
+        let kept: Vec<DateTime<Utc>> = archives
+            .iter()
+            .filter(|(key, _)| !expired.contains(key))
+            .map(|(_, created)| *created)
+            .collect();
+
+        // 7 daily (Oct 8-14, newest of each day) covering ISO weeks 41-42,
+        // plus the newest archive of weeks 40 and 39
+        assert_eq!(kept.len(), 9);
+        assert_eq!(kept[0], end);
+        assert!(kept[..7].iter().all(|t| t.hour() == 12));
+        assert_eq!(kept[7], at(2026, 10, 4, 12)); // Sunday ending week 40
+        assert_eq!(kept[8], at(2026, 9, 27, 12));
+    }
+
+    #[test]
+    fn test_snapshots_keep_newest_count() {
+        let end = at(2026, 10, 14, 12);
+        let snapshots: Vec<(String, DateTime<Utc>)> = (0..5)
+            .map(|i| {
+                let created = end - Duration::hours(i);
+                (snapshot_key("", "hourly", created), created)
+            })
+            .collect();
+        assert_eq!(snapshots[0].0, "hourly-20261014T120000Z.db");
+        assert_eq!(parse_snapshot_key("", "hourly", &snapshots[0].0), Some(end));
+        assert!(parse_archive_key("", "hourly", &snapshots[0].0).is_none());
+
+        let expired = select_beyond_count(&snapshots, 3);
+        assert_eq!(
+            expired,
+            vec![snapshots[3].0.clone(), snapshots[4].0.clone()]
+        );
+        assert_eq!(select_beyond_count(&snapshots, 0).len(), 4);
+    }
+
+    #[test]
+    fn test_newest_always_kept() {
+        let created = at(2026, 1, 1, 0);
+        let archives = vec![(archive_key("b", "job", created), created)];
+        let policy = RetentionPolicy {
+            keep_daily: 0,
+            keep_weekly: 0,
+        };
+        assert!(select_expired(&archives, policy).is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+//! Scheduled SQL snapshots.
+//!
+//! Cron jobs of type `sql-backup` copy the SQL database with
+//! [`SqlService::backup`](crate::daemon::services::sql::SqlService::backup)
+//! and keep the newest `keep` snapshots. Snapshots are plain SQLite files
+//! named `{job}-{YYYYMMDDTHHMMSSZ}.db`; to recover, stop the daemon and copy
+//! one over `sql.db`.
+//!
+//! ```toml
+//! [[schedules]]
+//! name = "sql-hourly"
+//! type = "sql-backup"
+//! cron = "0 0 * * * * *"  # Every hour
+//!
+//! [schedules.sql_backup]
+//! keep = 24
+//! target = { kind = "directory", path = "/var/backups/mik" }
+//! ```
+
+use super::{BackupReport, BackupRunner, retention};
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Content type of snapshots uploaded to the storage service.
+const SNAPSHOT_CONTENT_TYPE: &str = "application/vnd.sqlite3";
+
+/// Settings of a `sql-backup` cron job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SqlBackupConfig {
+    /// Number of snapshots to keep (default: 7).
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+    /// Where snapshots are written (default: `sql-backups/` in the data
+    /// directory).
+    #[serde(default)]
+    pub target: SqlBackupTarget,
+}
+
+impl Default for SqlBackupConfig {
+    fn default() -> Self {
+        Self {
+            keep: default_keep(),
+            target: SqlBackupTarget::default(),
+        }
+    }
+}
+
+const fn default_keep() -> usize {
+    7
+}
+
+/// Where SQL snapshots are written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SqlBackupTarget {
+    /// A local directory; relative paths are resolved against the data
+    /// directory.
+    Directory {
+        /// Directory path (default: "sql-backups").
+        #[serde(default = "default_path")]
+        path: PathBuf,
+    },
+    /// Embedded storage service, under `prefix`.
+    Storage {
+        /// Object prefix (default: "sql-backups").
+        #[serde(default = "default_prefix")]
+        prefix: String,
+    },
+}
+
+impl Default for SqlBackupTarget {
+    fn default() -> Self {
+        Self::Directory {
+            path: default_path(),
+        }
+    }
+}
+
+fn default_path() -> PathBuf {
+    PathBuf::from("sql-backups")
+}
+
+fn default_prefix() -> String {
+    "sql-backups".to_string()
+}
+
+impl BackupRunner {
+    /// Runs a SQL snapshot for `job`: back up, then prune.
+    pub async fn run_sql(&self, job: &str, config: &SqlBackupConfig) -> Result<BackupReport> {
+        let Some(sql) = &self.sql else {
+            bail!("SQL service is disabled");
+        };
+
+        match &config.target {
+            SqlBackupTarget::Directory { path } => {
+                let dir = self.data_dir.join(path);
+                let name = retention::snapshot_key("", job, Utc::now());
+                let file = dir.join(&name);
+                sql.backup(&file).await?;
+                let size_bytes = std::fs::metadata(&file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?
+                    .len();
+
+                let snapshots = list_snapshots(&dir, job)?;
+                let expired = retention::select_beyond_count(&snapshots, config.keep);
+                for name in &expired {
+                    let path = dir.join(name);
+                    std::fs::remove_file(&path).with_context(|| {
+                        format!("Failed to delete expired snapshot {}", path.display())
+                    })?;
+                }
+
+                Ok(BackupReport {
+                    key: file.display().to_string(),
+                    size_bytes,
+                    files: 1,
+                    pruned: expired.len(),
+                })
+            },
+            SqlBackupTarget::Storage { prefix } => {
+                let Some(storage) = &self.storage else {
+                    bail!("Storage service is disabled; configure a directory target");
+                };
+
+                let snapshot_dir =
+                    tempfile::tempdir().context("Failed to create snapshot directory")?;
+                let file = snapshot_dir.path().join("sql.db");
+                sql.backup(&file).await?;
+                let bytes = tokio::fs::read(&file)
+                    .await
+                    .context("Failed to read SQL snapshot")?;
+
+                let key = retention::snapshot_key(prefix, job, Utc::now());
+                let size_bytes = bytes.len() as u64;
+                storage
+                    .put_object(&key, &bytes, Some(SNAPSHOT_CONTENT_TYPE))
+                    .await
+                    .with_context(|| format!("Failed to upload snapshot '{key}'"))?;
+
+                let snapshots: Vec<_> = storage
+                    .list_objects(Some(&retention::job_prefix(prefix, job)))
+                    .await
+                    .context("Failed to list existing snapshots")?
+                    .into_iter()
+                    .filter_map(|meta| {
+                        retention::parse_snapshot_key(prefix, job, &meta.path)
+                            .map(|at| (meta.path, at))
+                    })
+                    .collect();
+                let expired = retention::select_beyond_count(&snapshots, config.keep);
+                for key in &expired {
+                    storage
+                        .delete_object(key)
+                        .await
+                        .with_context(|| format!("Failed to delete expired snapshot '{key}'"))?;
+                }
+
+                Ok(BackupReport {
+                    key,
+                    size_bytes,
+                    files: 1,
+                    pruned: expired.len(),
+                })
+            },
+        }
+    }
+}
+
+/// Snapshots of `job` in `dir`, by file name.
+fn list_snapshots(dir: &Path, job: &str) -> Result<Vec<(String, chrono::DateTime<Utc>)>> {
+    Ok(std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|name| retention::parse_snapshot_key("", job, &name).map(|at| (name, at)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::services::sql::SqlService;
+    use crate::daemon::services::storage::StorageService;
+
+    async fn seeded() -> SqlService {
+        let sql = SqlService::memory().unwrap();
+        sql.execute_batch("CREATE TABLE t (v TEXT); INSERT INTO t VALUES ('kept');")
+            .await
+            .unwrap();
+        sql
+    }
+
+    #[tokio::test]
+    async fn test_directory_snapshots_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("sql-backups")).unwrap();
+        for stamp in ["20250101T000000Z", "20250102T000000Z"] {
+            std::fs::write(
+                dir.path().join(format!("sql-backups/hourly-{stamp}.db")),
+                b"old",
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.path().join("sql-backups/notes.txt"), b"x").unwrap();
+
+        let runner = BackupRunner::new(dir.path()).with_sql(seeded().await);
+        let config = SqlBackupConfig {
+            keep: 2,
+            ..SqlBackupConfig::default()
+        };
+        let report = runner.run_sql("hourly", &config).await.unwrap();
+        assert_eq!(report.pruned, 1);
+        assert!(report.size_bytes > 0);
+
+        let path = PathBuf::from(&report.key);
+        assert!(path.starts_with(dir.path().join("sql-backups")));
+        let restored = SqlService::file(&path).unwrap();
+        let rows = restored.query("SELECT v FROM t", &[]).await.unwrap();
+        assert_eq!(rows.len(), 1);
+
+        assert!(
+            !dir.path()
+                .join("sql-backups/hourly-20250101T000000Z.db")
+                .exists()
+        );
+        assert!(
+            dir.path()
+                .join("sql-backups/hourly-20250102T000000Z.db")
+                .exists()
+        );
+        assert!(dir.path().join("sql-backups/notes.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_storage_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StorageService::memory();
+        let runner = BackupRunner::new(dir.path())
+            .with_sql(seeded().await)
+            .with_storage(storage.clone());
+        let config = SqlBackupConfig {
+            keep: 1,
+            target: SqlBackupTarget::Storage {
+                prefix: "snapshots".to_string(),
+            },
+        };
+
+        let report = runner.run_sql("daily", &config).await.unwrap();
+        assert!(retention::parse_snapshot_key("snapshots", "daily", &report.key).is_some());
+        assert!(storage.head_object(&report.key).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_requires_sql_service() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = BackupRunner::new(dir.path())
+            .run_sql("hourly", &SqlBackupConfig::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("SQL service is disabled"));
+    }
+
+    #[test]
+    fn test_config_from_toml() {
+        let config: SqlBackupConfig = toml::from_str("").unwrap();
+        assert_eq!(config, SqlBackupConfig::default());
+
+        let config: SqlBackupConfig = toml::from_str(
+            r#"
+            keep = 24
+            target = { kind = "storage" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.keep, 24);
+        assert_eq!(
+            config.target,
+            SqlBackupTarget::Storage {
+                prefix: "sql-backups".into(),
+            }
+        );
+    }
+}
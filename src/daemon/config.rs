@@ -10,15 +10,71 @@
 //! port = 9919
 //! max_auto_restarts = 10
 //! health_check_interval_secs = 10
+//! # Optional: restart instances whose RSS stays above 512 MB for 3 checks
+//! memory_ceiling_mb = 512
+//! memory_ceiling_samples = 3
+//! memory_ceiling_drain_secs = 30
+//!
+//! # Optional: daemons sharing cron jobs elect a leader that fires them
+//! [daemon.cron_leader]
+//! lease_secs = 15
+//! database = "/mnt/shared/mik/sql.db" # Default: the SQL service database
+//!
+//! # Optional: serve the daemon API over gRPC too (grpc feature)
+//! [daemon.grpc]
+//! port = 9920
+//!
+//! # Optional: ship instance logs to external sinks
+//! [daemon.log_shipping]
+//! batch_size = 500
+//!
+//! [[daemon.log_shipping.sinks]]
+//! type = "loki" # or "syslog", "http", "file"
+//! url = "http://loki:3100/loki/api/v1/push"
 //!
 //! [services]
 //! kv_enabled = true
 //! sql_enabled = true
 //! storage_enabled = true
+//! sql_tenancy = "shared" # or "database_per_tenant"
+//! storage_tenancy = "shared" # or "namespace_per_tenant"
+//! kv_tenancy = "shared" # or "namespace_per_tenant"
+//!
+//! # Optional: serve KV from memory and flush to disk in batches
+//! [services.kv_cache]
+//! max_entries = 10000
+//! flush_interval_ms = 1000
+//!
+//! # Optional: delete storage objects under tmp/ a day after their last write
+//! [services.storage_lifecycle]
+//! interval_secs = 3600
+//!
+//! [[services.storage_lifecycle.rules]]
+//! prefix = "tmp/"
+//! expire_after_hours = 24
+//!
+//! # Optional: storage quotas per tenant ("*" is the default)
+//! [services.storage_quotas]
+//! "*" = { max_bytes = 1073741824 }
+//! acme = { max_bytes = 10737418240, max_objects = 100000 }
+//!
+//! # Optional: KV key quotas per tenant ("*" is the default)
+//! [services.kv_quotas]
+//! "*" = { max_keys = 10000 }
+//!
+//! # Optional: serve storage over the S3 API (credentials from
+//! # MIK_S3_ACCESS_KEY_ID and MIK_S3_SECRET_ACCESS_KEY)
+//! [services.s3]
+//! port = 9000
+//! region = "us-east-1"
 //! ```
 
+use super::log_shipping::LogShippingSettings;
+use super::services::kv::{KvQuota, KvQuotas};
+use super::services::storage::{LifecycleRule, TenantQuota, TenantQuotas};
 use anyhow::{Context, Result};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Global daemon configuration loaded from `~/.mik/daemon.toml`.
@@ -39,8 +95,63 @@ pub struct DaemonSettings {
     pub port: u16,
     /// Maximum number of auto-restarts before giving up.
     pub max_auto_restarts: u32,
-    /// Health check interval in seconds.
+    /// Health check interval in seconds (also the resource sampling interval).
     pub health_check_interval_secs: u64,
+    /// Restart an instance when its RSS exceeds this many MB. Disabled when absent.
+    pub memory_ceiling_mb: Option<u64>,
+    /// Consecutive samples above the ceiling before restarting.
+    pub memory_ceiling_samples: u32,
+    /// Seconds an instance gets to drain in-flight requests before it is
+    /// killed for a memory restart.
+    pub memory_ceiling_drain_secs: u32,
+    /// Leader election for cron jobs shared between daemons. Disabled when
+    /// absent, in which case every daemon fires its own jobs.
+    pub cron_leader: Option<CronLeaderSettings>,
+    /// gRPC API, on its own port. Disabled when absent.
+    pub grpc: Option<GrpcSettings>,
+    /// Shipping of instance logs to external sinks. Disabled when absent.
+    pub log_shipping: Option<LogShippingSettings>,
+}
+
+/// Cron leader election settings.
+///
+/// See [`crate::daemon::cron::leader`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CronLeaderSettings {
+    /// Seconds the lease lasts without renewal; bounds failover time.
+    pub lease_secs: u64,
+    /// SQLite database shared by the daemons (default: the SQL service
+    /// database).
+    pub database: Option<PathBuf>,
+    /// This daemon's ID in the lease (default: host name and process ID).
+    pub node_id: Option<String>,
+}
+
+impl Default for CronLeaderSettings {
+    fn default() -> Self {
+        Self {
+            lease_secs: super::cron::leader::DEFAULT_LEASE.as_secs(),
+            database: None,
+            node_id: None,
+        }
+    }
+}
+
+/// gRPC API settings.
+///
+/// Requires mik to be built with the `grpc` feature.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GrpcSettings {
+    /// Port for the gRPC API.
+    pub port: u16,
+}
+
+impl Default for GrpcSettings {
+    fn default() -> Self {
+        Self { port: 9920 }
+    }
 }
 
 /// Embedded service enable/disable settings.
@@ -53,6 +164,150 @@ pub struct ServiceSettings {
     pub sql_enabled: bool,
     /// Enable Storage service.
     pub storage_enabled: bool,
+    /// How SQL data is separated between tenants.
+    pub sql_tenancy: SqlTenancy,
+    /// How storage objects are separated between tenants.
+    pub storage_tenancy: StorageTenancy,
+    /// Storage quotas by tenant ID, with `"*"` as the default.
+    pub storage_quotas: HashMap<String, TenantQuota>,
+    /// How KV keys are separated between tenants.
+    pub kv_tenancy: KvTenancy,
+    /// KV key quotas by tenant ID, with `"*"` as the default.
+    pub kv_quotas: HashMap<String, KvQuota>,
+    /// Write-behind memory cache for the KV store. Disabled when absent.
+    pub kv_cache: Option<KvCacheSettings>,
+    /// Expiry rules for storage objects.
+    pub storage_lifecycle: StorageLifecycleSettings,
+    /// S3-compatible API for the storage service. Disabled when absent.
+    pub s3: Option<S3Settings>,
+}
+
+/// S3-compatible storage API settings.
+///
+/// The API listens on its own port because S3 clients expect buckets at the
+/// root path. Buckets map to top-level storage prefixes.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct S3Settings {
+    /// Port for the S3 API.
+    pub port: u16,
+    /// Region clients must sign requests for.
+    pub region: String,
+}
+
+impl Default for S3Settings {
+    fn default() -> Self {
+        Self {
+            port: 9000,
+            region: "us-east-1".to_string(),
+        }
+    }
+}
+
+/// Storage lifecycle settings.
+///
+/// A background task deletes objects matching a rule once they are older
+/// than its `expire_after_hours`. No rules means nothing expires.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StorageLifecycleSettings {
+    /// Seconds between lifecycle sweeps.
+    pub interval_secs: u64,
+    /// Expiry rules; the longest matching prefix applies.
+    pub rules: Vec<LifecycleRule>,
+}
+
+impl Default for StorageLifecycleSettings {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3600,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// KV write-behind cache settings.
+///
+/// Writes are acknowledged once buffered in memory and committed to disk
+/// in a single transaction every `flush_interval_ms`, so a crash can lose
+/// up to one interval of writes.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct KvCacheSettings {
+    /// Maximum number of entries kept in memory.
+    pub max_entries: usize,
+    /// Interval between flushes to disk, in milliseconds.
+    pub flush_interval_ms: u64,
+}
+
+impl Default for KvCacheSettings {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            flush_interval_ms: 1000,
+        }
+    }
+}
+
+/// Tenant separation mode for the SQL service.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlTenancy {
+    /// All requests share a single database.
+    #[default]
+    Shared,
+    /// Each tenant gets its own database.
+    ///
+    /// SQL requests must carry an `X-Tenant-Id` header and are routed to
+    /// `sql-tenants/{tenant_id}.db` instead of the shared database.
+    DatabasePerTenant,
+}
+
+/// Tenant separation mode for the storage service.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageTenancy {
+    /// All requests share one object namespace.
+    #[default]
+    Shared,
+    /// Each tenant gets its own namespace.
+    ///
+    /// Storage requests must carry an `X-Tenant-Id` header; paths resolve
+    /// under `tenants/{tenant_id}/` and writes count against the tenant's
+    /// quota.
+    NamespacePerTenant,
+}
+
+/// Tenant separation mode for the KV service.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KvTenancy {
+    /// All requests share one keyspace.
+    #[default]
+    Shared,
+    /// Each tenant gets its own namespace.
+    ///
+    /// KV requests must carry an `X-Tenant-Id` header; keys resolve under
+    /// `tenants/{tenant_id}/` and new keys count against the tenant's quota.
+    NamespacePerTenant,
+}
+
+impl ServiceSettings {
+    /// Storage quotas keyed by tenant, split into the `"*"` default and
+    /// per-tenant overrides.
+    pub fn tenant_quotas(&self) -> TenantQuotas {
+        let mut tenants = self.storage_quotas.clone();
+        let default = tenants.remove("*").unwrap_or_default();
+        TenantQuotas { default, tenants }
+    }
+
+    /// KV key quotas keyed by tenant, split into the `"*"` default and
+    /// per-tenant overrides.
+    pub fn kv_tenant_quotas(&self) -> KvQuotas {
+        let mut tenants = self.kv_quotas.clone();
+        let default = tenants.remove("*").unwrap_or_default();
+        KvQuotas { default, tenants }
+    }
 }
 
 impl Default for DaemonSettings {
@@ -61,6 +316,12 @@ impl Default for DaemonSettings {
             port: 9919,
             max_auto_restarts: 10,
             health_check_interval_secs: 10,
+            memory_ceiling_mb: None,
+            memory_ceiling_samples: 3,
+            memory_ceiling_drain_secs: 30,
+            cron_leader: None,
+            grpc: None,
+            log_shipping: None,
         }
     }
 }
@@ -71,6 +332,14 @@ impl Default for ServiceSettings {
             kv_enabled: true,
             sql_enabled: true,
             storage_enabled: true,
+            sql_tenancy: SqlTenancy::Shared,
+            storage_tenancy: StorageTenancy::Shared,
+            storage_quotas: HashMap::new(),
+            kv_tenancy: KvTenancy::Shared,
+            kv_quotas: HashMap::new(),
+            kv_cache: None,
+            storage_lifecycle: StorageLifecycleSettings::default(),
+            s3: None,
         }
     }
 }
@@ -126,6 +395,7 @@ impl DaemonConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::daemon::log_shipping::{LogSink, SyslogProtocol};
 
     #[test]
     fn test_default_config() {
@@ -136,28 +406,128 @@ mod tests {
         assert!(config.services.kv_enabled);
         assert!(config.services.sql_enabled);
         assert!(config.services.storage_enabled);
+        assert_eq!(config.services.sql_tenancy, SqlTenancy::Shared);
+        assert_eq!(config.services.storage_tenancy, StorageTenancy::Shared);
+        assert!(config.services.kv_cache.is_none());
+        assert!(config.services.s3.is_none());
+        assert!(config.daemon.cron_leader.is_none());
+        assert!(config.daemon.grpc.is_none());
+        assert!(config.daemon.log_shipping.is_none());
     }
 
     #[test]
     fn test_parse_full_config() {
-        let toml = r"
+        let toml = r#"
 [daemon]
 port = 9090
 max_auto_restarts = 5
 health_check_interval_secs = 30
 
+[daemon.cron_leader]
+database = "/mnt/shared/sql.db"
+
+[daemon.grpc]
+
+[daemon.log_shipping]
+flush_interval_ms = 200
+
+[[daemon.log_shipping.sinks]]
+type = "syslog"
+address = "127.0.0.1:514"
+
+[[daemon.log_shipping.sinks]]
+type = "file"
+path = "/var/log/mik.ndjson"
+
 [services]
 kv_enabled = true
 sql_enabled = false
 storage_enabled = true
-";
+sql_tenancy = "database_per_tenant"
+storage_tenancy = "namespace_per_tenant"
+kv_tenancy = "namespace_per_tenant"
+
+[services.storage_quotas]
+"*" = { max_bytes = 1000 }
+acme = { max_objects = 10 }
+
+[services.kv_quotas]
+"*" = { max_keys = 100 }
+acme = {}
+
+[services.kv_cache]
+flush_interval_ms = 250
+
+[[services.storage_lifecycle.rules]]
+prefix = "tmp/"
+expire_after_hours = 24
+
+[services.s3]
+port = 9100
+"#;
         let config: DaemonConfig = toml::from_str(toml).unwrap();
         assert_eq!(config.daemon.port, 9090);
         assert_eq!(config.daemon.max_auto_restarts, 5);
         assert_eq!(config.daemon.health_check_interval_secs, 30);
+        let cron_leader = config.daemon.cron_leader.unwrap();
+        assert_eq!(cron_leader.lease_secs, 15);
+        assert_eq!(
+            cron_leader.database,
+            Some(PathBuf::from("/mnt/shared/sql.db"))
+        );
+        assert!(cron_leader.node_id.is_none());
+        assert_eq!(config.daemon.grpc.unwrap().port, 9920);
+        let log_shipping = config.daemon.log_shipping.unwrap();
+        assert_eq!(log_shipping.batch_size, 500);
+        assert_eq!(log_shipping.flush_interval_ms, 200);
+        assert_eq!(
+            log_shipping.sinks,
+            vec![
+                LogSink::Syslog {
+                    address: "127.0.0.1:514".to_string(),
+                    protocol: SyslogProtocol::Udp,
+                },
+                LogSink::File {
+                    path: PathBuf::from("/var/log/mik.ndjson"),
+                },
+            ]
+        );
         assert!(config.services.kv_enabled);
         assert!(!config.services.sql_enabled);
         assert!(config.services.storage_enabled);
+        assert_eq!(config.services.sql_tenancy, SqlTenancy::DatabasePerTenant);
+        assert_eq!(
+            config.services.storage_tenancy,
+            StorageTenancy::NamespacePerTenant
+        );
+        let quotas = config.services.tenant_quotas();
+        assert_eq!(quotas.get("other").max_bytes, Some(1000));
+        assert_eq!(
+            quotas.get("acme"),
+            TenantQuota {
+                max_bytes: None,
+                max_objects: Some(10),
+            }
+        );
+        assert_eq!(config.services.kv_tenancy, KvTenancy::NamespacePerTenant);
+        let kv_quotas = config.services.kv_tenant_quotas();
+        assert_eq!(kv_quotas.get("other").max_keys, Some(100));
+        assert_eq!(kv_quotas.get("acme").max_keys, None);
+        let kv_cache = config.services.kv_cache.unwrap();
+        assert_eq!(kv_cache.max_entries, 10_000);
+        assert_eq!(kv_cache.flush_interval_ms, 250);
+        let lifecycle = config.services.storage_lifecycle;
+        assert_eq!(lifecycle.interval_secs, 3600);
+        assert_eq!(
+            lifecycle.rules,
+            vec![LifecycleRule {
+                prefix: "tmp/".to_string(),
+                expire_after_hours: 24,
+            }]
+        );
+        let s3 = config.services.s3.unwrap();
+        assert_eq!(s3.port, 9100);
+        assert_eq!(s3.region, "us-east-1");
     }
 
     #[test]
@@ -1,6 +1,7 @@
 //! Job execution logic for the cron scheduler.
 //!
-//! Handles HTTP-based WASM module execution, health checks, and execution recording.
+//! Handles HTTP-based WASM module execution, backup jobs, health checks, and
+//! execution recording.
 
 use anyhow::{Context, Result};
 use chrono::Utc;
@@ -9,7 +10,8 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use super::types::{JobExecution, JobState, JobsMap, MAX_HISTORY_ENTRIES, ScheduleConfig};
+use super::types::{JobExecution, JobState, JobType, JobsMap, MAX_HISTORY_ENTRIES, ScheduleConfig};
+use crate::daemon::backup::BackupRunner;
 use crate::daemon::metrics;
 
 /// Waits for an instance to become healthy with retry and backoff.
@@ -156,11 +158,62 @@ pub(crate) async fn execute_wasm_module(config: &ScheduleConfig) -> Result<()> {
     Ok(())
 }
 
+/// Runs a backup job through the daemon's backup runner.
+///
+/// Returns a summary of the uploaded archive for the execution history.
+async fn execute_backup(config: &ScheduleConfig, backup: Option<&BackupRunner>) -> Result<String> {
+    let runner =
+        backup.ok_or_else(|| anyhow::anyhow!("Backups are not available in this daemon"))?;
+    let backup_config = config.backup.clone().unwrap_or_default();
+
+    match runner.run(&config.name, &backup_config).await {
+        Ok(report) => {
+            metrics::record_backup(&config.name, true, report.size_bytes, report.pruned);
+            Ok(report.to_string())
+        },
+        Err(e) => {
+            metrics::record_backup(&config.name, false, 0, 0);
+            Err(e)
+        },
+    }
+}
+
+/// Runs a `sql-backup` job through the daemon's backup runner.
+async fn execute_sql_backup(
+    config: &ScheduleConfig,
+    backup: Option<&BackupRunner>,
+) -> Result<String> {
+    let runner =
+        backup.ok_or_else(|| anyhow::anyhow!("Backups are not available in this daemon"))?;
+    let sql_config = config.sql_backup.clone().unwrap_or_default();
+
+    match runner.run_sql(&config.name, &sql_config).await {
+        Ok(report) => {
+            metrics::record_backup(&config.name, true, report.size_bytes, report.pruned);
+            Ok(report.to_string())
+        },
+        Err(e) => {
+            metrics::record_backup(&config.name, false, 0, 0);
+            Err(e)
+        },
+    }
+}
+
+/// Runs a job according to its type, returning an optional outcome summary.
+async fn run_job(config: &ScheduleConfig, backup: Option<&BackupRunner>) -> Result<Option<String>> {
+    match config.job_type {
+        JobType::Http => execute_wasm_module(config).await.map(|()| None),
+        JobType::Backup => execute_backup(config, backup).await.map(Some),
+        JobType::SqlBackup => execute_sql_backup(config, backup).await.map(Some),
+    }
+}
+
 /// Executes a job and records the result.
 ///
 /// This is the public execution method that records execution history.
 pub(crate) async fn execute_job(
     jobs: &Arc<RwLock<JobsMap>>,
+    backup: Option<&BackupRunner>,
     name: &str,
     manual: bool,
 ) -> JobExecution {
@@ -173,19 +226,19 @@ pub(crate) async fn execute_job(
         jobs_guard.get(name).map(|s| s.config.clone())
     };
 
-    let (success, error, duration_ms) = match config {
+    let (success, error, output, duration_ms) = match config {
         Some(config) => {
             let start = std::time::Instant::now();
-            let result = execute_wasm_module(&config).await;
+            let result = run_job(&config, backup).await;
             #[allow(clippy::cast_possible_truncation)]
             let duration = start.elapsed().as_millis() as u64;
 
             match result {
-                Ok(()) => (true, None, duration),
-                Err(e) => (false, Some(e.to_string()), duration),
+                Ok(output) => (true, None, output, duration),
+                Err(e) => (false, Some(format!("{e:#}")), None, duration),
             }
         },
-        None => (false, Some("Job not found".to_string()), 0),
+        None => (false, Some("Job not found".to_string()), None, 0),
     };
 
     let completed_at = Utc::now();
@@ -217,6 +270,7 @@ pub(crate) async fn execute_job(
         success,
         error,
         manual,
+        output,
     };
 
     // Record execution in history
@@ -228,36 +282,65 @@ pub(crate) async fn execute_job(
 /// Internal job execution (called by scheduler).
 ///
 /// This is used by the cron scheduler when jobs are triggered automatically.
+/// Applies the job's jitter and overlap policy; manual triggers bypass both.
 pub(crate) async fn execute_job_internal(
     jobs: &Arc<RwLock<HashMap<String, JobState>>>,
+    backup: Option<&BackupRunner>,
     name: &str,
 ) {
-    let execution_id = Uuid::new_v4().to_string();
-    let started_at = Utc::now();
-
-    // Get job config
-    let config = {
+    let jitter_secs = {
         let jobs_guard = jobs.read().await;
-        jobs_guard.get(name).map(|s| s.config.clone())
+        jobs_guard.get(name).and_then(|s| s.config.jitter_secs)
+    };
+    if let Some(secs) = jitter_secs.filter(|&secs| secs > 0) {
+        let delay_ms = fastrand::u64(0..=secs.saturating_mul(1000));
+        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+    }
+
+    let mut config = {
+        let mut jobs_guard = jobs.write().await;
+        let Some(state) = jobs_guard.get_mut(name) else {
+            return;
+        };
+        if !state.config.enabled || !state.begin_scheduled_run() {
+            return; // Disabled, skipped, or queued
+        }
+        state.config.clone()
     };
 
-    let (success, error, duration_ms) = match config {
-        Some(config) => {
-            if !config.enabled {
-                return; // Skip disabled jobs
-            }
+    loop {
+        execute_scheduled_run(jobs, backup, name, &config).await;
 
-            let start = std::time::Instant::now();
-            let result = execute_wasm_module(&config).await;
-            #[allow(clippy::cast_possible_truncation)]
-            let duration = start.elapsed().as_millis() as u64;
+        // Start the queued run, if any
+        let mut jobs_guard = jobs.write().await;
+        let Some(state) = jobs_guard.get_mut(name) else {
+            return;
+        };
+        if !state.finish_run() {
+            return;
+        }
+        config = state.config.clone();
+    }
+}
 
-            match result {
-                Ok(()) => (true, None, duration),
-                Err(e) => (false, Some(e.to_string()), duration),
-            }
-        },
-        None => return,
+/// Runs one admitted scheduled execution and records it.
+async fn execute_scheduled_run(
+    jobs: &Arc<RwLock<JobsMap>>,
+    backup: Option<&BackupRunner>,
+    name: &str,
+    config: &ScheduleConfig,
+) {
+    let execution_id = Uuid::new_v4().to_string();
+    let started_at = Utc::now();
+
+    let start = std::time::Instant::now();
+    let result = run_job(config, backup).await;
+    #[allow(clippy::cast_possible_truncation)]
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    let (success, error, output) = match result {
+        Ok(output) => (true, None, output),
+        Err(e) => (false, Some(format!("{e:#}")), None),
     };
 
     let completed_at = Utc::now();
@@ -271,6 +354,7 @@ pub(crate) async fn execute_job_internal(
         success,
         error: error.clone(),
         manual: false,
+        output,
     };
 
     // Record execution
@@ -0,0 +1,249 @@
+//! Leader election for daemons sharing cron jobs.
+//!
+//! When several daemons run the same schedules against shared services, only
+//! the one holding the leader lease fires scheduled jobs. The lease is a row
+//! in a SQLite database the daemons share (by default the SQL service
+//! database); SQLite's file locking makes acquiring it atomic across
+//! processes.
+//!
+//! The leader renews its lease every third of the lease duration. If it
+//! dies, its lease expires and the first follower to retry takes over, so
+//! failover takes at most about `lease_secs * 4 / 3`. Runs due during that
+//! window are missed rather than run twice. Node clocks must be roughly in
+//! sync, since expiry is compared against each node's wall clock.
+//!
+//! ```toml
+//! [daemon.cron_leader]
+//! lease_secs = 15
+//! database = "/mnt/shared/mik/sql.db"  # Default: the SQL service database
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use crate::daemon::services::sql::{SqlService, Value};
+
+/// Default lease duration.
+pub const DEFAULT_LEASE: Duration = Duration::from_secs(15);
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS _mik_cron_leader (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    holder TEXT NOT NULL,
+    expires_at INTEGER NOT NULL
+)";
+
+/// Takes the lease if it is free, expired, or already ours.
+const ACQUIRE: &str = "INSERT INTO _mik_cron_leader (id, holder, expires_at) VALUES (1, ?1, ?2)
+    ON CONFLICT (id) DO UPDATE SET holder = excluded.holder, expires_at = excluded.expires_at
+    WHERE _mik_cron_leader.holder = excluded.holder OR _mik_cron_leader.expires_at < ?3";
+
+const RELEASE: &str = "DELETE FROM _mik_cron_leader WHERE holder = ?1";
+
+/// This node's claim on the cron leader lease.
+pub struct LeaderLease {
+    sql: SqlService,
+    node_id: String,
+    lease: Duration,
+    /// Until when this node may act as leader, by its own monotonic clock.
+    held_until: Mutex<Option<Instant>>,
+}
+
+impl LeaderLease {
+    /// Creates a lease for `node_id` in `sql`, creating its table if needed.
+    pub async fn new(sql: SqlService, node_id: impl Into<String>, lease: Duration) -> Result<Self> {
+        sql.execute_batch(CREATE_TABLE)
+            .await
+            .context("Failed to create cron leader table")?;
+        Ok(Self {
+            sql,
+            node_id: node_id.into(),
+            lease,
+            held_until: Mutex::new(None),
+        })
+    }
+
+    /// Default node ID: host name and process ID.
+    pub fn default_node_id() -> String {
+        let host = sysinfo::System::host_name().unwrap_or_else(|| "mik".to_string());
+        format!("{host}-{}", std::process::id())
+    }
+
+    /// This node's ID.
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Whether this node currently holds the lease.
+    pub fn is_leader(&self) -> bool {
+        self.held_until
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Acquires or renews the lease, returning whether this node holds it.
+    ///
+    /// On error the current claim is left to run out, so a node that cannot
+    /// reach the database stops acting as leader once its lease expires.
+    pub async fn try_acquire(&self) -> Result<bool> {
+        // Measured before the write, so our view expires no later than the row
+        let started = Instant::now();
+        let now = Utc::now().timestamp_millis();
+        let lease_ms = i64::try_from(self.lease.as_millis()).unwrap_or(i64::MAX);
+        let acquired = self
+            .sql
+            .execute(
+                ACQUIRE,
+                &[
+                    Value::Text(self.node_id.clone()),
+                    Value::Integer(now.saturating_add(lease_ms)),
+                    Value::Integer(now),
+                ],
+            )
+            .await
+            .context("Failed to acquire cron leader lease")?
+            > 0;
+
+        let mut held_until = self
+            .held_until
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let was_leader = held_until.is_some_and(|until| Instant::now() < until);
+        *held_until = acquired.then(|| started + self.lease);
+        match (was_leader, acquired) {
+            (false, true) => tracing::info!(node = %self.node_id, "Acquired cron leadership"),
+            (true, false) => tracing::warn!(node = %self.node_id, "Lost cron leadership"),
+            _ => {},
+        }
+        Ok(acquired)
+    }
+
+    /// Gives up the lease so another node can take over immediately.
+    pub async fn release(&self) -> Result<()> {
+        *self
+            .held_until
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = None;
+        self.sql
+            .execute(RELEASE, &[Value::Text(self.node_id.clone())])
+            .await
+            .context("Failed to release cron leader lease")?;
+        Ok(())
+    }
+
+    /// ID of the node holding an unexpired lease, if any.
+    pub async fn current_leader(&self) -> Result<Option<String>> {
+        let rows = self
+            .sql
+            .query(
+                "SELECT holder FROM _mik_cron_leader WHERE expires_at >= ?1",
+                &[Value::Integer(Utc::now().timestamp_millis())],
+            )
+            .await
+            .context("Failed to read cron leader lease")?;
+        Ok(rows.first().and_then(|row| match row.get("holder") {
+            Some(Value::Text(holder)) => Some(holder.clone()),
+            _ => None,
+        }))
+    }
+}
+
+/// Keeps trying to acquire or renew `lease` while the scheduler is running,
+/// and releases it when the scheduler stops.
+pub(crate) async fn maintain(lease: Arc<LeaderLease>, mut running: watch::Receiver<bool>) {
+    let interval = lease.lease / 3;
+    loop {
+        if running.wait_for(|running| *running).await.is_err() {
+            return;
+        }
+
+        if let Err(e) = lease.try_acquire().await {
+            tracing::warn!(error = %format!("{e:#}"), "Cron leader election failed");
+        }
+
+        tokio::select! {
+            () = tokio::time::sleep(interval) => {},
+            changed = running.changed() => {
+                let stopped = changed.is_err() || !*running.borrow();
+                if stopped {
+                    if let Err(e) = lease.release().await {
+                        tracing::warn!(error = %format!("{e:#}"), "Failed to release cron leadership");
+                    }
+                    if changed.is_err() {
+                        return;
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn lease(sql: &SqlService, node: &str, lease: Duration) -> LeaderLease {
+        LeaderLease::new(sql.clone(), node, lease).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_single_leader() {
+        let sql = SqlService::memory().unwrap();
+        let a = lease(&sql, "a", DEFAULT_LEASE).await;
+        let b = lease(&sql, "b", DEFAULT_LEASE).await;
+
+        assert!(a.try_acquire().await.unwrap());
+        assert!(!b.try_acquire().await.unwrap());
+        assert!(a.is_leader());
+        assert!(!b.is_leader());
+
+        // Renewal keeps the lease
+        assert!(a.try_acquire().await.unwrap());
+        assert_eq!(a.current_leader().await.unwrap().as_deref(), Some("a"));
+    }
+
+    #[tokio::test]
+    async fn test_failover_after_expiry() {
+        let sql = SqlService::memory().unwrap();
+        let a = lease(&sql, "a", Duration::from_millis(50)).await;
+        let b = lease(&sql, "b", Duration::from_millis(50)).await;
+
+        assert!(a.try_acquire().await.unwrap());
+        assert!(!b.try_acquire().await.unwrap());
+
+        // "a" stops renewing
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert!(!a.is_leader());
+        assert!(b.try_acquire().await.unwrap());
+        assert!(b.is_leader());
+        assert!(!a.try_acquire().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_release_hands_over() {
+        let sql = SqlService::memory().unwrap();
+        let a = lease(&sql, "a", DEFAULT_LEASE).await;
+        let b = lease(&sql, "b", DEFAULT_LEASE).await;
+
+        assert!(a.try_acquire().await.unwrap());
+        a.release().await.unwrap();
+        assert!(!a.is_leader());
+        assert!(a.current_leader().await.unwrap().is_none());
+        assert!(b.try_acquire().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_shared_between_processes_via_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sql.db");
+        // Separate connections stand in for separate daemons
+        let a = lease(&SqlService::file(&path).unwrap(), "a", DEFAULT_LEASE).await;
+        let b = lease(&SqlService::file(&path).unwrap(), "b", DEFAULT_LEASE).await;
+
+        assert!(a.try_acquire().await.unwrap());
+        assert!(!b.try_acquire().await.unwrap());
+    }
+}
@@ -1,11 +1,14 @@
 //! Cron scheduler for scheduled WASM job execution.
 //!
-//! Provides cron-based scheduling for WASM modules using `tokio-cron-scheduler`.
+//! Provides cron-based scheduling for WASM modules.
 //! Jobs are defined in mik.toml and can be managed via HTTP API.
 //!
 //! # Cron Expression Format
 //!
-//! Uses 7-field format: `sec min hour day month weekday year`
+//! Uses 7-field format: `sec min hour day month weekday year`, plus
+//! nicknames such as `@monthly` and `L`/`#`/`W` day modifiers (see
+//! [`schedule`]). Expressions are evaluated in UTC unless the job sets an
+//! IANA `timezone`.
 //!
 //! # Example Configuration
 //!
@@ -19,8 +22,27 @@
 //! name = "sync"
 //! module = "modules/sync.wasm"
 //! cron = "0 */5 * * * * *"  # Every 5 minutes
+//! overlap = "skip"          # Drop runs while the previous one is in flight
+//! jitter_secs = 30          # Start up to 30s late to spread load
+//!
+//! [[schedules]]
+//! name = "nightly-backup"
+//! type = "backup"           # Back up daemon data instead of calling a module
+//! cron = "0 0 3 * * * *"    # Daily at 03:00
+//!
+//! [[schedules]]
+//! name = "month-end-report"
+//! module = "modules/report.wasm"
+//! cron = "0 0 18 L * * *"   # Last day of the month at 18:00...
+//! timezone = "Europe/Paris" # ...Paris time
 //! ```
 //!
+//! Backup jobs keep 7 daily and 4 weekly archives by default; see
+//! [`crate::daemon::backup`] for retention and upload targets.
+//!
+//! Daemons that share cron jobs can elect a leader so each run fires once;
+//! see [`leader`].
+//!
 //! # HTTP API
 //!
 //! - `GET /cron` - List all scheduled jobs
@@ -32,18 +54,22 @@
 //!
 //! - `types` - Type definitions (`ScheduleConfig`, `JobExecution`, `JobInfo`)
 //! - `manifest` - Manifest parsing for `[[schedules]]`
+//! - `schedule` - Cron expression parsing and next-run computation
+//! - `leader` - Leader election between daemons sharing cron jobs
 //! - `scheduler` - Core `CronScheduler` implementation
 //! - `execution` - Job execution and health check logic
 
 mod execution;
+pub mod leader;
 mod manifest;
+pub mod schedule;
 mod scheduler;
 mod types;
 
 // Re-export public API for convenience
 pub use manifest::parse_schedules_from_manifest;
 pub use scheduler::CronScheduler;
-pub use types::{JobExecution, ScheduleConfig};
+pub use types::{JobExecution, JobType, OverlapPolicy, ScheduleConfig};
 
 #[cfg(test)]
 mod tests {
@@ -52,19 +78,21 @@ mod tests {
 
     #[tokio::test]
     async fn test_scheduler_creation() {
-        let scheduler = CronScheduler::new().await.unwrap();
+        let scheduler = CronScheduler::new();
         assert!(scheduler.list_jobs().await.is_empty());
     }
 
     #[tokio::test]
     async fn test_add_and_list_job() {
-        let scheduler = CronScheduler::new().await.unwrap();
+        let scheduler = CronScheduler::new();
 
-        // tokio-cron-scheduler uses 7-field format: sec min hour day month weekday year
+        // 7-field format: sec min hour day month weekday year
         let config = ScheduleConfig {
             name: "test-job".to_string(),
+            job_type: JobType::Http,
             module: PathBuf::from("modules/test.wasm"),
             cron: "0 0 * * * * *".to_string(), // Every hour at :00
+            timezone: None,
             method: "GET".to_string(),
             path: "/".to_string(),
             enabled: true,
@@ -72,6 +100,10 @@ mod tests {
             body: None,
             headers: None,
             health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
         };
 
         scheduler.add_job(config).await.unwrap();
@@ -84,12 +116,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_remove_job() {
-        let scheduler = CronScheduler::new().await.unwrap();
+        let scheduler = CronScheduler::new();
 
         let config = ScheduleConfig {
             name: "to-remove".to_string(),
+            job_type: JobType::Http,
             module: PathBuf::from("modules/test.wasm"),
             cron: "0 0 * * * * *".to_string(), // 7-field format
+            timezone: None,
             method: "GET".to_string(),
             path: "/".to_string(),
             enabled: true,
@@ -97,6 +131,10 @@ mod tests {
             body: None,
             headers: None,
             health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
         };
 
         scheduler.add_job(config).await.unwrap();
@@ -109,12 +147,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_job() {
-        let scheduler = CronScheduler::new().await.unwrap();
+        let scheduler = CronScheduler::new();
 
         let config = ScheduleConfig {
             name: "my-job".to_string(),
+            job_type: JobType::Http,
             module: PathBuf::from("modules/test.wasm"),
             cron: "0 */5 * * * * *".to_string(), // Every 5 minutes (7-field)
+            timezone: None,
             method: "POST".to_string(),
             path: "/trigger".to_string(),
             enabled: true,
@@ -122,6 +162,10 @@ mod tests {
             body: None,
             headers: None,
             health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
         };
 
         scheduler.add_job(config).await.unwrap();
@@ -138,12 +182,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_invalid_cron_expression() {
-        let scheduler = CronScheduler::new().await.unwrap();
+        let scheduler = CronScheduler::new();
 
         let config = ScheduleConfig {
             name: "bad-cron".to_string(),
+            job_type: JobType::Http,
             module: PathBuf::from("modules/test.wasm"),
             cron: "invalid cron".to_string(),
+            timezone: None,
             method: "GET".to_string(),
             path: "/".to_string(),
             enabled: true,
@@ -151,22 +197,135 @@ mod tests {
             body: None,
             headers: None,
             health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
         };
 
         let result = scheduler.add_job(config).await;
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_job_timezone_and_next_run() {
+        let scheduler = CronScheduler::new();
+
+        let config = ScheduleConfig {
+            name: "month-end".to_string(),
+            job_type: JobType::Http,
+            module: PathBuf::from("modules/report.wasm"),
+            cron: "0 0 18 L * * *".to_string(), // Last day of the month
+            timezone: Some("America/New_York".to_string()),
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            enabled: true,
+            port: 3000,
+            body: None,
+            headers: None,
+            health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
+        };
+        scheduler.add_job(config.clone()).await.unwrap();
+
+        let job = scheduler.get_job("month-end").await.unwrap();
+        assert_eq!(job.timezone.as_deref(), Some("America/New_York"));
+        let next_run = job.next_run.expect("enabled job has a next run");
+        let local = next_run.with_timezone(&chrono_tz::America::New_York);
+        assert_eq!(local.format("%H:%M").to_string(), "18:00");
+        assert_ne!(
+            (local + chrono::Duration::days(1)).format("%m").to_string(),
+            local.format("%m").to_string()
+        );
+
+        // Paused jobs have no next run
+        scheduler.set_enabled("month-end", false).await;
+        assert!(
+            scheduler
+                .get_job("month-end")
+                .await
+                .unwrap()
+                .next_run
+                .is_none()
+        );
+
+        let result = scheduler
+            .add_job(ScheduleConfig {
+                name: "bad-timezone".to_string(),
+                timezone: Some("Mars/Olympus".to_string()),
+                ..config
+            })
+            .await;
+        assert!(result.unwrap_err().to_string().contains("Unknown timezone"));
+    }
+
+    #[tokio::test]
+    async fn test_started_scheduler_fires_jobs() {
+        let scheduler = CronScheduler::new();
+
+        let config = ScheduleConfig {
+            name: "every-second".to_string(),
+            job_type: JobType::Http,
+            module: PathBuf::from("modules/test.wasm"),
+            cron: "* * * * * * *".to_string(),
+            timezone: None,
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            enabled: true,
+            port: 39998, // Port unlikely to have a running server
+            body: None,
+            headers: None,
+            health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
+        };
+        scheduler.add_job(config).await.unwrap();
+
+        // Nothing fires before start
+        tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+        assert!(
+            scheduler
+                .get_history("every-second", None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        scheduler.start();
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while scheduler
+                .get_history("every-second", None)
+                .await
+                .unwrap()
+                .is_empty()
+            {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("job should fire once started");
+        assert!(!scheduler.get_history("every-second", None).await.unwrap()[0].manual);
+
+        scheduler.shutdown();
+    }
+
     #[tokio::test]
     async fn test_trigger_job_records_failure() {
         // This test verifies that triggering a job when no server is running
         // properly records the failure in the execution history.
-        let scheduler = CronScheduler::new().await.unwrap();
+        let scheduler = CronScheduler::new();
 
         let config = ScheduleConfig {
             name: "trigger-test".to_string(),
+            job_type: JobType::Http,
             module: PathBuf::from("modules/test.wasm"),
             cron: "0 0 0 * * * *".to_string(), // Daily at midnight (7-field)
+            timezone: None,
             method: "GET".to_string(),
             path: "/".to_string(),
             enabled: true,
@@ -174,6 +333,10 @@ mod tests {
             body: None,
             headers: None,
             health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
         };
 
         scheduler.add_job(config).await.unwrap();
@@ -195,19 +358,21 @@ mod tests {
 
     #[tokio::test]
     async fn test_trigger_nonexistent_job() {
-        let scheduler = CronScheduler::new().await.unwrap();
+        let scheduler = CronScheduler::new();
         let result = scheduler.trigger_job("nonexistent").await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_pause_resume_job() {
-        let scheduler = CronScheduler::new().await.unwrap();
+        let scheduler = CronScheduler::new();
 
         let config = ScheduleConfig {
             name: "pausable-job".to_string(),
+            job_type: JobType::Http,
             module: PathBuf::from("modules/test.wasm"),
             cron: "0 0 * * * * *".to_string(),
+            timezone: None,
             method: "GET".to_string(),
             path: "/".to_string(),
             enabled: true,
@@ -215,6 +380,10 @@ mod tests {
             body: None,
             headers: None,
             health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
         };
 
         scheduler.add_job(config).await.unwrap();
@@ -243,4 +412,115 @@ mod tests {
         let result = scheduler.set_enabled("nonexistent", false).await;
         assert_eq!(result, None);
     }
+
+    #[tokio::test]
+    async fn test_only_leader_fires_jobs() {
+        use crate::daemon::services::sql::SqlService;
+        use leader::{DEFAULT_LEASE, LeaderLease};
+
+        // Two daemons sharing one database
+        let sql = SqlService::memory().unwrap();
+        let mut nodes = Vec::new();
+        for node in ["a", "b"] {
+            let lease = LeaderLease::new(sql.clone(), node, DEFAULT_LEASE)
+                .await
+                .unwrap();
+            let scheduler = CronScheduler::new().with_leader_lease(lease);
+            // Backup jobs fail fast without a runner, which is enough here
+            let config: ScheduleConfig =
+                toml::from_str("name = \"tick\"\ntype = \"backup\"\ncron = \"* * * * * * *\"")
+                    .unwrap();
+            scheduler.add_job(config).await.unwrap();
+            scheduler.start();
+            // Let "a" win the election
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            nodes.push(scheduler);
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+        let history = |i: usize| nodes[i].get_history("tick", None);
+        assert!(!history(0).await.unwrap().is_empty());
+        assert!(history(1).await.unwrap().is_empty());
+        assert!(nodes[0].leader_lease().unwrap().is_leader());
+        assert!(!nodes[1].leader_lease().unwrap().is_leader());
+
+        // Stopping the leader releases the lease for the follower
+        nodes[0].shutdown();
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while !nodes[1].leader_lease().unwrap().is_leader() {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("follower should take over");
+    }
+
+    fn overlap_state(overlap: OverlapPolicy) -> types::JobState {
+        let config: ScheduleConfig = toml::from_str(&format!(
+            "name = \"slow\"\ncron = \"* * * * * * *\"\noverlap = \"{overlap}\"\njitter_secs = 30"
+        ))
+        .unwrap();
+        assert_eq!(config.overlap, overlap);
+        assert_eq!(config.jitter_secs, Some(30));
+        types::JobState::new(config, None)
+    }
+
+    #[test]
+    fn test_overlap_allow() {
+        let mut state = overlap_state(OverlapPolicy::Allow);
+        assert!(state.begin_scheduled_run());
+        assert!(state.begin_scheduled_run());
+        assert_eq!(state.running, 2);
+        assert!(!state.finish_run());
+        assert!(!state.finish_run());
+        assert!(state.last_skip.is_none());
+    }
+
+    #[test]
+    fn test_overlap_skip() {
+        let mut state = overlap_state(OverlapPolicy::Skip);
+        assert!(state.begin_scheduled_run());
+        assert!(!state.begin_scheduled_run());
+        let info = state.to_job_info();
+        assert!(info.running);
+        assert_eq!(info.overlap, OverlapPolicy::Skip);
+        assert_eq!(
+            info.last_skip.unwrap().reason,
+            "previous run still in progress"
+        );
+
+        assert!(!state.finish_run());
+        assert!(state.begin_scheduled_run());
+    }
+
+    #[test]
+    fn test_overlap_queue() {
+        let mut state = overlap_state(OverlapPolicy::Queue);
+        assert!(state.begin_scheduled_run());
+        // First overlapping run waits, further ones are dropped
+        assert!(!state.begin_scheduled_run());
+        assert!(state.queued);
+        assert!(!state.begin_scheduled_run());
+        assert_eq!(
+            state.last_skip.as_ref().unwrap().reason,
+            "a run is already queued"
+        );
+
+        // Finishing starts the queued run
+        assert!(state.finish_run());
+        assert_eq!(state.running, 1);
+        assert!(!state.queued);
+        assert!(!state.finish_run());
+        assert_eq!(state.running, 0);
+    }
+
+    #[test]
+    fn test_overlap_queue_dropped_when_paused() {
+        let mut state = overlap_state(OverlapPolicy::Queue);
+        assert!(state.begin_scheduled_run());
+        assert!(!state.begin_scheduled_run());
+        state.config.enabled = false;
+        assert!(!state.finish_run());
+        assert_eq!(state.running, 0);
+    }
 }
@@ -13,7 +13,9 @@ use proptest::prelude::*;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 
-use super::types::{JobExecution, JobState, MAX_HISTORY_ENTRIES, ScheduleConfig};
+use super::types::{
+    JobExecution, JobState, JobType, MAX_HISTORY_ENTRIES, OverlapPolicy, ScheduleConfig,
+};
 
 // ============================================================================
 // Test Helpers - Arbitrary Implementations
@@ -21,7 +23,7 @@ use super::types::{JobExecution, JobState, MAX_HISTORY_ENTRIES, ScheduleConfig};
 
 /// Strategy for generating valid cron expressions (7-field format).
 ///
-/// tokio-cron-scheduler uses 7 fields: sec min hour day month weekday year
+/// Cron expressions use 7 fields: sec min hour day month weekday year
 fn valid_cron_expression() -> impl Strategy<Value = String> {
     prop_oneof![
         // Every second
@@ -113,6 +115,7 @@ fn valid_schedule_config() -> impl Strategy<Value = ScheduleConfig> {
             name,
             module: PathBuf::from("modules/test.wasm"),
             cron,
+            timezone: None,
             method,
             path,
             enabled,
@@ -120,6 +123,10 @@ fn valid_schedule_config() -> impl Strategy<Value = ScheduleConfig> {
             body: None,
             headers: None,
             health_path: "/health".to_string(),
+            backup: None,
+            sql_backup: None,
+            overlap: OverlapPolicy::Allow,
+            jitter_secs: None,
         })
 }
 
@@ -147,6 +154,7 @@ fn job_execution_strategy() -> impl Strategy<Value = JobExecution> {
                     Some("Test error".to_string())
                 },
                 manual,
+                output: None,
             }
         })
 }
@@ -162,7 +170,7 @@ proptest! {
     /// by the underlying cron parser.
     #[test]
     fn valid_cron_expressions_are_parseable(cron in valid_cron_expression()) {
-        // tokio-cron-scheduler validates expressions when creating jobs
+        // The scheduler validates expressions when adding jobs
         // We test this by checking the expression format is correct
         let fields: Vec<&str> = cron.split_whitespace().collect();
         prop_assert_eq!(
@@ -345,6 +353,7 @@ proptest! {
                 success: true,
                 error: None,
                 manual: false,
+                output: None,
             };
 
             // Simulate the trim logic from record_execution
@@ -384,6 +393,7 @@ proptest! {
                 success: true,
                 error: None,
                 manual: false,
+                output: None,
             };
 
             if state.history.len() >= MAX_HISTORY_ENTRIES {
@@ -421,6 +431,7 @@ proptest! {
                 success: true,
                 error: None,
                 manual: false,
+                output: None,
             };
 
             if state.history.len() >= MAX_HISTORY_ENTRIES {
@@ -453,6 +464,7 @@ proptest! {
                 success: true,
                 error: None,
                 manual: false,
+                output: None,
             };
             state.history.push_back(execution);
         }
@@ -467,6 +479,7 @@ proptest! {
             success: true,
             error: None,
             manual: false,
+            output: None,
         };
 
         if state.history.len() >= MAX_HISTORY_ENTRIES {
@@ -544,6 +557,7 @@ proptest! {
                 success: i % 2 == 0,
                 error: if i % 2 == 0 { None } else { Some("error".to_string()) },
                 manual: false,
+                output: None,
             };
             state.execution_count += 1;
             if execution.success {
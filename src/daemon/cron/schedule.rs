@@ -0,0 +1,140 @@
+//! Cron expressions evaluated in a timezone.
+//!
+//! Expressions use the 7-field format `sec min hour day month weekday year`
+//! (the year may be omitted), plus:
+//!
+//! - nicknames: `@yearly`, `@monthly`, `@weekly`, `@daily`, `@hourly`
+//! - `L` for the last day of the month (`0 0 18 L * * *`) or, with `#`,
+//!   the last weekday of a kind (`0 0 9 * * FRI#L *`)
+//! - `#` for the nth weekday (`0 0 9 * * MON#1 *`, the first Monday)
+//! - `W` for the weekday closest to a day (`0 0 9 15W * * *`)
+//!
+//! Run times are computed in the job's IANA timezone (UTC by default), so a
+//! job at `0 0 9 * * * *` in `Europe/Paris` runs at 09:00 local time on both
+//! sides of a daylight-saving change.
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use croner::Cron;
+use croner::parser::{CronParser, Seconds};
+
+/// A parsed cron expression and the timezone it is evaluated in.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    cron: Cron,
+    timezone: Tz,
+}
+
+impl Schedule {
+    /// Parses `expression`, evaluated in `timezone` (`None` = UTC).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is invalid or the timezone is not
+    /// a known IANA name.
+    pub fn parse(expression: &str, timezone: Option<&str>) -> Result<Self> {
+        let cron = CronParser::builder()
+            .seconds(Seconds::Required)
+            .build()
+            .parse(expression)
+            .with_context(|| format!("Invalid cron expression: {expression}"))?;
+        let timezone = match timezone {
+            Some(name) => name
+                .parse::<Tz>()
+                .map_err(|_| anyhow!("Unknown timezone: {name}"))?,
+            None => Tz::UTC,
+        };
+        Ok(Self { cron, timezone })
+    }
+
+    /// Timezone the expression is evaluated in.
+    pub const fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// First run time strictly after `after`, or `None` if the expression
+    /// never matches again.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.cron
+            .find_next_occurrence(&after.with_timezone(&self.timezone), false)
+            .ok()
+            .map(|next| next.with_timezone(&Utc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_utc_by_default() {
+        let schedule = Schedule::parse("0 0 3 * * * *", None).unwrap();
+        assert_eq!(schedule.timezone(), Tz::UTC);
+        assert_eq!(
+            schedule.next_after(utc(2026, 10, 14, 12, 0)),
+            Some(utc(2026, 10, 15, 3, 0))
+        );
+    }
+
+    #[test]
+    fn test_timezone_follows_daylight_saving() {
+        let schedule = Schedule::parse("0 0 9 * * * *", Some("Europe/Paris")).unwrap();
+        // Summer time (UTC+2)
+        assert_eq!(
+            schedule.next_after(utc(2026, 10, 20, 0, 0)),
+            Some(utc(2026, 10, 20, 7, 0))
+        );
+        // Winter time (UTC+1), after the change on 2026-10-25
+        assert_eq!(
+            schedule.next_after(utc(2026, 10, 26, 0, 0)),
+            Some(utc(2026, 10, 26, 8, 0))
+        );
+    }
+
+    #[test]
+    fn test_calendar_expressions() {
+        let monthly = Schedule::parse("@monthly", None).unwrap();
+        assert_eq!(
+            monthly.next_after(utc(2026, 10, 14, 12, 0)),
+            Some(utc(2026, 11, 1, 0, 0))
+        );
+
+        let last_day = Schedule::parse("0 0 18 L * * *", None).unwrap();
+        assert_eq!(
+            last_day.next_after(utc(2026, 2, 1, 0, 0)),
+            Some(utc(2026, 2, 28, 18, 0))
+        );
+
+        let first_monday = Schedule::parse("0 0 9 * * MON#1 *", None).unwrap();
+        assert_eq!(
+            first_monday.next_after(utc(2026, 10, 14, 0, 0)),
+            Some(utc(2026, 11, 2, 9, 0))
+        );
+
+        let last_friday = Schedule::parse("0 0 9 * * FRI#L *", None).unwrap();
+        assert_eq!(
+            last_friday.next_after(utc(2026, 10, 14, 0, 0)),
+            Some(utc(2026, 10, 30, 9, 0))
+        );
+
+        // 2026-11-15 is a Sunday
+        let nearest_weekday = Schedule::parse("0 0 9 15W * * *", None).unwrap();
+        assert_eq!(
+            nearest_weekday.next_after(utc(2026, 11, 1, 0, 0)),
+            Some(utc(2026, 11, 16, 9, 0))
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(Schedule::parse("not a cron", None).is_err());
+        let err = Schedule::parse("0 0 * * * * *", Some("Mars/Olympus")).unwrap_err();
+        assert!(err.to_string().contains("Unknown timezone"));
+    }
+}
@@ -1,83 +1,121 @@
 //! Core cron scheduler implementation.
 //!
 //! Provides the `CronScheduler` service for managing scheduled WASM jobs.
+//!
+//! Each job is driven by its own task, which sleeps until the next run time
+//! computed by [`Schedule`] in the job's timezone. Jobs only fire while the
+//! scheduler is started and, with a [`LeaderLease`], while this node is the
+//! cron leader.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
+use chrono::Utc;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tokio_cron_scheduler::{Job, JobScheduler};
+use tokio::sync::{RwLock, watch};
 
 use super::execution::{execute_job, execute_job_internal};
+use super::leader::{self, LeaderLease};
+use super::schedule::Schedule;
 use super::types::{JobExecution, JobInfo, JobState, JobsMap, MAX_HISTORY_ENTRIES, ScheduleConfig};
+use crate::daemon::backup::BackupRunner;
 
 /// Cron scheduler service.
 pub struct CronScheduler {
-    scheduler: JobScheduler,
     jobs: Arc<RwLock<JobsMap>>,
+    backup: Option<Arc<BackupRunner>>,
+    leader: Option<Arc<LeaderLease>>,
+    /// Whether jobs fire; job tasks exit when this is dropped.
+    running: watch::Sender<bool>,
+}
+
+impl Default for CronScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CronScheduler {
     /// Creates a new cron scheduler.
-    pub async fn new() -> Result<Self> {
-        let scheduler = JobScheduler::new()
-            .await
-            .context("Failed to create job scheduler")?;
-
-        Ok(Self {
-            scheduler,
+    pub fn new() -> Self {
+        Self {
             jobs: Arc::new(RwLock::new(JobsMap::new())),
-        })
+            backup: None,
+            leader: None,
+            running: watch::Sender::new(false),
+        }
+    }
+
+    /// Sets the runner used by `backup` jobs.
+    ///
+    /// Must be called before jobs are added; without a runner, backup jobs fail.
+    #[must_use]
+    pub fn with_backup_runner(mut self, runner: BackupRunner) -> Self {
+        self.backup = Some(Arc::new(runner));
+        self
+    }
+
+    /// Only fires scheduled jobs while this node holds `lease`.
+    ///
+    /// Must be called before jobs are added. The lease is acquired and
+    /// renewed while the scheduler is running, and released when it stops.
+    /// Manual triggers run regardless.
+    #[must_use]
+    pub fn with_leader_lease(mut self, lease: LeaderLease) -> Self {
+        let lease = Arc::new(lease);
+        tokio::spawn(leader::maintain(
+            Arc::clone(&lease),
+            self.running.subscribe(),
+        ));
+        self.leader = Some(lease);
+        self
+    }
+
+    /// The leader lease, if leader election is enabled.
+    pub fn leader_lease(&self) -> Option<&LeaderLease> {
+        self.leader.as_deref()
     }
 
     /// Starts the scheduler.
-    pub async fn start(&self) -> Result<()> {
-        self.scheduler
-            .start()
-            .await
-            .context("Failed to start scheduler")?;
+    pub fn start(&self) {
+        self.running.send_replace(true);
         tracing::info!("Cron scheduler started");
-        Ok(())
     }
 
     /// Stops the scheduler.
-    pub async fn shutdown(&mut self) -> Result<()> {
-        self.scheduler
-            .shutdown()
-            .await
-            .context("Failed to shutdown scheduler")?;
+    pub fn shutdown(&self) {
+        self.running.send_replace(false);
         tracing::info!("Cron scheduler stopped");
-        Ok(())
     }
 
     /// Adds a scheduled job.
     pub async fn add_job(&self, config: ScheduleConfig) -> Result<()> {
         let name = config.name.clone();
-        let cron_expr = config.cron.clone();
-
-        // Validate cron expression by attempting to create a job
-        let jobs_clone = Arc::clone(&self.jobs);
-        let job_name = name.clone();
-
-        let job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
-            let jobs = Arc::clone(&jobs_clone);
-            let name = job_name.clone();
-            Box::pin(async move {
-                execute_job_internal(&jobs, &name).await;
-            })
-        })
-        .with_context(|| format!("Invalid cron expression: {cron_expr}"))?;
-
-        let job_id = self
-            .scheduler
-            .add(job)
-            .await
-            .context("Failed to add job to scheduler")?;
-
-        // Store job state
+        let schedule = Schedule::parse(&config.cron, config.timezone.as_deref())?;
+
+        let task = tokio::spawn(run_schedule(
+            schedule.clone(),
+            Arc::clone(&self.jobs),
+            self.backup.clone(),
+            self.leader.clone(),
+            name.clone(),
+            self.running.subscribe(),
+        ));
+
+        // Store job state, replacing any job with the same name
         let mut jobs = self.jobs.write().await;
-        jobs.insert(name.clone(), JobState::new(config, Some(job_id)));
+        if let Some(previous) = jobs.insert(
+            name.clone(),
+            JobState::new(config, Some(task.abort_handle())),
+        ) && let Some(task) = previous.task
+        {
+            task.abort();
+        }
 
-        tracing::info!(job = %name, cron = %cron_expr, "Scheduled job added");
+        tracing::info!(
+            job = %name,
+            cron = %jobs[&name].config.cron,
+            timezone = %schedule.timezone(),
+            "Scheduled job added"
+        );
         Ok(())
     }
 
@@ -86,11 +124,8 @@ impl CronScheduler {
         let mut jobs = self.jobs.write().await;
 
         if let Some(state) = jobs.remove(name) {
-            if let Some(job_id) = state.job_id {
-                self.scheduler
-                    .remove(&job_id)
-                    .await
-                    .context("Failed to remove job from scheduler")?;
+            if let Some(task) = state.task {
+                task.abort();
             }
             tracing::info!(job = %name, "Scheduled job removed");
             Ok(true)
@@ -147,7 +182,7 @@ impl CronScheduler {
     /// Manually triggers a job execution.
     pub async fn trigger_job(&self, name: &str) -> Result<JobExecution> {
         // Execute the job - execute_job handles the case where job doesn't exist
-        let execution = execute_job(&self.jobs, name, true).await;
+        let execution = execute_job(&self.jobs, self.backup.as_deref(), name, true).await;
 
         // Check if the job was actually found and executed
         if !execution.success && execution.error.as_deref() == Some("Job not found") {
@@ -157,3 +192,53 @@ impl CronScheduler {
         Ok(execution)
     }
 }
+
+/// Fires the job `name` at each run time of `schedule` while the scheduler
+/// is running (and this node is the leader, if there is a lease).
+///
+/// Runs are spawned so a slow run does not delay the next one.
+async fn run_schedule(
+    schedule: Schedule,
+    jobs: Arc<RwLock<JobsMap>>,
+    backup: Option<Arc<BackupRunner>>,
+    leader: Option<Arc<LeaderLease>>,
+    name: String,
+    mut running: watch::Receiver<bool>,
+) {
+    let mut last_run = Utc::now();
+    loop {
+        if running.wait_for(|running| *running).await.is_err() {
+            return;
+        }
+
+        // Never before the previous run, in case the timer fired early
+        let Some(next) = schedule.next_after(Utc::now().max(last_run)) else {
+            tracing::info!(job = %name, "Schedule has no further run times");
+            return;
+        };
+        let delay = (next - Utc::now()).to_std().unwrap_or_default();
+
+        tokio::select! {
+            () = tokio::time::sleep(delay) => {},
+            changed = running.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                continue;
+            },
+        }
+
+        last_run = next;
+        if leader.as_ref().is_some_and(|lease| !lease.is_leader()) {
+            tracing::debug!(job = %name, "Not the cron leader; leaving run to the leader");
+            continue;
+        }
+
+        let jobs = Arc::clone(&jobs);
+        let backup = backup.clone();
+        let name = name.clone();
+        tokio::spawn(async move {
+            execute_job_internal(&jobs, backup.as_deref(), &name).await;
+        });
+    }
+}
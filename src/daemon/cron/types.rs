@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 
+use super::schedule::Schedule;
+use crate::daemon::backup::{BackupJobConfig, SqlBackupConfig};
+
 /// Maximum number of history entries to keep per job.
 pub const MAX_HISTORY_ENTRIES: usize = 100;
 
@@ -15,10 +18,18 @@ pub const MAX_HISTORY_ENTRIES: usize = 100;
 pub struct ScheduleConfig {
     /// Unique name for this schedule.
     pub name: String,
+    /// Kind of job (default: http).
+    #[serde(default, rename = "type")]
+    pub job_type: JobType,
     /// Path to the WASM module to execute (module name, e.g., "cleanup").
+    /// Unused by `backup` and `sql-backup` jobs.
+    #[serde(default)]
     pub module: PathBuf,
-    /// Cron expression (e.g., "0 0 * * *" for daily at midnight).
+    /// Cron expression (e.g., "0 0 0 * * * *" for daily at midnight).
     pub cron: String,
+    /// IANA timezone the cron expression is evaluated in (default: UTC).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
     /// Optional HTTP method to use (default: GET).
     #[serde(default = "default_method")]
     pub method: String,
@@ -41,6 +52,57 @@ pub struct ScheduleConfig {
     /// Override this if your WASM module has a different health endpoint.
     #[serde(default = "default_health_path")]
     pub health_path: String,
+    /// Backup settings for `backup` jobs (defaults apply when omitted).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<BackupJobConfig>,
+    /// Snapshot settings for `sql-backup` jobs (defaults apply when omitted).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql_backup: Option<SqlBackupConfig>,
+    /// What to do when a run is due while the previous one is in flight
+    /// (default: allow).
+    #[serde(default)]
+    pub overlap: OverlapPolicy,
+    /// Random delay of up to this many seconds before each scheduled run,
+    /// to spread out jobs that fire at the same time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter_secs: Option<u64>,
+}
+
+/// What to do when a job is due while its previous run is still in flight.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlapPolicy {
+    /// Start another run alongside the previous one.
+    #[default]
+    Allow,
+    /// Drop the run.
+    Skip,
+    /// Run once the previous run finishes; at most one run waits.
+    Queue,
+}
+
+impl std::fmt::Display for OverlapPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Allow => "allow",
+            Self::Skip => "skip",
+            Self::Queue => "queue",
+        })
+    }
+}
+
+/// Kind of scheduled job.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobType {
+    /// Call a WASM module on a running mik instance.
+    #[default]
+    Http,
+    /// Back up daemon data (see [`crate::daemon::backup`]).
+    Backup,
+    /// Snapshot the SQL database (see [`crate::daemon::backup::SqlBackupConfig`]).
+    #[serde(rename = "sql-backup")]
+    SqlBackup,
 }
 
 /// Default port value (3000).
@@ -87,6 +149,18 @@ pub struct JobExecution {
     pub error: Option<String>,
     /// Whether this was a manual trigger.
     pub manual: bool,
+    /// Outcome summary (e.g., uploaded backup archive).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+/// A scheduled run that was dropped by the job's overlap policy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedRun {
+    /// When the run was due.
+    pub at: DateTime<Utc>,
+    /// Why it was dropped.
+    pub reason: String,
 }
 
 /// Information about a scheduled job.
@@ -96,6 +170,8 @@ pub struct JobInfo {
     pub name: String,
     /// Cron expression.
     pub cron: String,
+    /// IANA timezone of the cron expression (None = UTC).
+    pub timezone: Option<String>,
     /// Module path.
     pub module: String,
     /// HTTP method (GET, POST, etc.).
@@ -104,8 +180,16 @@ pub struct JobInfo {
     pub path: String,
     /// Whether the job is enabled.
     pub enabled: bool,
+    /// Overlap policy.
+    pub overlap: OverlapPolicy,
+    /// Maximum random delay before scheduled runs.
+    pub jitter_secs: Option<u64>,
+    /// Whether a run is in flight.
+    pub running: bool,
     /// Next scheduled run time.
     pub next_run: Option<DateTime<Utc>>,
+    /// Most recent run dropped by the overlap policy.
+    pub last_skip: Option<SkippedRun>,
     /// Last execution result.
     pub last_execution: Option<JobExecution>,
     /// Total number of executions.
@@ -119,9 +203,15 @@ pub struct JobInfo {
 /// Internal job state.
 pub(crate) struct JobState {
     pub config: ScheduleConfig,
-    pub job_id: Option<uuid::Uuid>,
+    /// Task that fires the job on schedule.
+    pub task: Option<tokio::task::AbortHandle>,
     /// Execution history stored as `VecDeque` for O(1) front removal.
     pub history: VecDeque<JobExecution>,
+    /// Runs in flight.
+    pub running: usize,
+    /// Whether a run waits for the in-flight one (`queue` policy).
+    pub queued: bool,
+    pub last_skip: Option<SkippedRun>,
     pub execution_count: u64,
     pub success_count: u64,
     pub failure_count: u64,
@@ -129,11 +219,14 @@ pub(crate) struct JobState {
 
 impl JobState {
     /// Creates a new `JobState` with the given configuration.
-    pub const fn new(config: ScheduleConfig, job_id: Option<uuid::Uuid>) -> Self {
+    pub const fn new(config: ScheduleConfig, task: Option<tokio::task::AbortHandle>) -> Self {
         Self {
             config,
-            job_id,
+            task,
             history: VecDeque::new(),
+            running: 0,
+            queued: false,
+            last_skip: None,
             execution_count: 0,
             success_count: 0,
             failure_count: 0,
@@ -145,17 +238,83 @@ impl JobState {
         JobInfo {
             name: self.config.name.clone(),
             cron: self.config.cron.clone(),
+            timezone: self.config.timezone.clone(),
             module: self.config.module.display().to_string(),
             method: self.config.method.clone(),
             path: self.config.path.clone(),
             enabled: self.config.enabled,
-            next_run: None, // Would need scheduler API to get this
+            overlap: self.config.overlap,
+            jitter_secs: self.config.jitter_secs,
+            running: self.running > 0,
+            next_run: self.next_run(),
+            last_skip: self.last_skip.clone(),
             last_execution: self.history.back().cloned(),
             execution_count: self.execution_count,
             success_count: self.success_count,
             failure_count: self.failure_count,
         }
     }
+
+    /// Admits a scheduled run according to the overlap policy.
+    ///
+    /// Returns true if the run should start now; it then counts as in
+    /// flight until [`finish_run`](Self::finish_run).
+    pub fn begin_scheduled_run(&mut self) -> bool {
+        if self.running > 0 {
+            match self.config.overlap {
+                OverlapPolicy::Allow => {},
+                OverlapPolicy::Skip => {
+                    self.skip("previous run still in progress");
+                    return false;
+                },
+                OverlapPolicy::Queue if !self.queued => {
+                    self.queued = true;
+                    return false;
+                },
+                OverlapPolicy::Queue => {
+                    self.skip("a run is already queued");
+                    return false;
+                },
+            }
+        }
+        self.running += 1;
+        true
+    }
+
+    /// Marks a run as finished.
+    ///
+    /// Returns true if a queued run should start now, in which case it
+    /// counts as in flight.
+    pub fn finish_run(&mut self) -> bool {
+        self.running = self.running.saturating_sub(1);
+        if self.queued && self.running == 0 {
+            self.queued = false;
+            if self.config.enabled {
+                self.running += 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn skip(&mut self, reason: &str) {
+        tracing::info!(job = %self.config.name, reason, "Scheduled run skipped");
+        crate::daemon::metrics::record_cron_skipped(&self.config.name);
+        self.last_skip = Some(SkippedRun {
+            at: Utc::now(),
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Next scheduled run, or None if the job is paused.
+    fn next_run(&self) -> Option<DateTime<Utc>> {
+        if !self.config.enabled {
+            return None;
+        }
+        Schedule::parse(&self.config.cron, self.config.timezone.as_deref())
+            .ok()?
+            .next_after(Utc::now())
+    }
 }
 
 /// Type alias for the jobs map used throughout the scheduler.
@@ -0,0 +1,547 @@
+//! Typed client for the daemon HTTP API.
+//!
+//! Uses the request and response types the server does ([`types`]), so
+//! callers don't hand-roll them:
+//!
+//! ```ignore
+//! let client = DaemonClient::new("http://127.0.0.1:9919")?.api_key_from_env();
+//! client.kv_set("greeting", "hello", None).await?;
+//! assert_eq!(client.kv_get("greeting").await?.as_deref(), Some("hello"));
+//! ```
+//!
+//! Errors carry the daemon's error message. Lookups of missing resources
+//! return `None` where the API answers 404.
+
+// Not every endpoint has a caller in the CLI yet.
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::{Method, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+use super::handlers::sql::TENANT_HEADER;
+use super::types::{
+    self, CronCreateRequest, CronCreateResponse, CronDeleteResponse, CronHistoryResponse,
+    CronJobResponse, CronTriggerResponse, CronUpdateRequest, CronUpdateResponse, ErrorResponse,
+    HealthResponse, InstanceResponse, KvGetResponse, KvListResponse, KvSetRequest,
+    ListCronJobsResponse, ListInstancesResponse, LogsResponse, SqlExecuteRequest,
+    SqlExecuteResponse, SqlQueryRequest, SqlQueryResponse, StartInstanceRequest,
+    StorageListResponse, VersionResponse,
+};
+
+/// Client for one daemon.
+#[derive(Debug, Clone)]
+pub struct DaemonClient {
+    http: reqwest::Client,
+    base: Url,
+    api_key: Option<String>,
+    tenant: Option<String>,
+}
+
+impl DaemonClient {
+    /// Client for the daemon at `base_url` (e.g. `http://127.0.0.1:9919`).
+    pub fn new(base_url: &str) -> Result<Self> {
+        let base =
+            Url::parse(base_url).with_context(|| format!("Invalid daemon URL: {base_url}"))?;
+        if base.cannot_be_a_base() {
+            anyhow::bail!("Invalid daemon URL: {base_url}");
+        }
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base,
+            api_key: None,
+            tenant: None,
+        })
+    }
+
+    /// Client for the local daemon on `port`.
+    pub fn local(port: u16) -> Self {
+        Self::new(&format!("http://127.0.0.1:{port}")).expect("local daemon URL is valid")
+    }
+
+    /// Send `key` as the `X-API-Key` header.
+    #[must_use]
+    pub fn api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Send the `MIK_API_KEY` environment variable as the API key, if set.
+    #[must_use]
+    pub fn api_key_from_env(self) -> Self {
+        match std::env::var("MIK_API_KEY") {
+            Ok(key) if !key.is_empty() => self.api_key(key),
+            _ => self,
+        }
+    }
+
+    /// Make SQL and storage requests on behalf of `tenant`.
+    #[must_use]
+    pub fn tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    /// Base URL of the daemon.
+    pub fn base_url(&self) -> &Url {
+        &self.base
+    }
+
+    // =========================================================================
+    // System
+    // =========================================================================
+
+    /// Whether the daemon answers its health check within `timeout`.
+    pub async fn is_healthy(&self, timeout: Duration) -> bool {
+        self.request(Method::GET, &["health"])
+            .timeout(timeout)
+            .send()
+            .await
+            .is_ok_and(|response| response.status().is_success())
+    }
+
+    pub async fn health(&self) -> Result<HealthResponse> {
+        self.json(self.request(Method::GET, &["health"])).await
+    }
+
+    pub async fn version(&self) -> Result<VersionResponse> {
+        self.json(self.request(Method::GET, &["version"])).await
+    }
+
+    /// The daemon's OpenAPI document.
+    pub async fn openapi(&self) -> Result<serde_json::Value> {
+        self.json(self.request(Method::GET, &["openapi.json"]))
+            .await
+    }
+
+    // =========================================================================
+    // Instances
+    // =========================================================================
+
+    pub async fn list_instances(&self) -> Result<Vec<InstanceResponse>> {
+        let response: ListInstancesResponse =
+            self.json(self.request(Method::GET, &["instances"])).await?;
+        Ok(response.instances)
+    }
+
+    pub async fn get_instance(&self, name: &str) -> Result<Option<InstanceResponse>> {
+        self.optional(self.request(Method::GET, &["instances", name]))
+            .await
+    }
+
+    pub async fn start_instance(&self, request: &StartInstanceRequest) -> Result<InstanceResponse> {
+        self.json(self.request(Method::POST, &["instances"]).json(request))
+            .await
+    }
+
+    pub async fn stop_instance(&self, name: &str) -> Result<InstanceResponse> {
+        self.json(self.request(Method::DELETE, &["instances", name]))
+            .await
+    }
+
+    pub async fn restart_instance(&self, name: &str) -> Result<InstanceResponse> {
+        self.json(self.request(Method::POST, &["instances", name, "restart"]))
+            .await
+    }
+
+    /// The last `lines` log lines of an instance.
+    pub async fn logs(&self, name: &str, lines: usize) -> Result<LogsResponse> {
+        let lines = lines.to_string();
+        let request = self.request_query(
+            Method::GET,
+            &["instances", name, "logs"],
+            &[("lines", &lines)],
+        );
+        self.json(request).await
+    }
+
+    /// The last `lines` log lines, then new lines as they are written.
+    ///
+    /// The response body is a Server-Sent Events stream with one `data:`
+    /// field per log line.
+    pub async fn follow_logs(&self, name: &str, lines: usize) -> Result<Response> {
+        let lines = lines.to_string();
+        let request = self.request_query(
+            Method::GET,
+            &["instances", name, "logs"],
+            &[("lines", &lines), ("follow", "true")],
+        );
+        self.send(request).await
+    }
+
+    // =========================================================================
+    // KV
+    // =========================================================================
+
+    pub async fn kv_get(&self, key: &str) -> Result<Option<String>> {
+        let response: Option<KvGetResponse> = self
+            .optional(self.request(Method::GET, &["kv", key]))
+            .await?;
+        Ok(response.map(|entry| entry.value))
+    }
+
+    /// Set `key`, expiring after `ttl` seconds if given.
+    pub async fn kv_set(&self, key: &str, value: &str, ttl: Option<u64>) -> Result<()> {
+        let body = KvSetRequest {
+            value: value.to_string(),
+            ttl,
+        };
+        self.send(self.request(Method::PUT, &["kv", key]).json(&body))
+            .await
+            .map(drop)
+    }
+
+    pub async fn kv_delete(&self, key: &str) -> Result<()> {
+        self.send(self.request(Method::DELETE, &["kv", key]))
+            .await
+            .map(drop)
+    }
+
+    /// Keys starting with `prefix` (all keys if empty).
+    pub async fn kv_list(&self, prefix: &str) -> Result<Vec<String>> {
+        let response: KvListResponse = self
+            .json(self.request_query(Method::GET, &["kv"], &[("prefix", prefix)]))
+            .await?;
+        Ok(response.keys)
+    }
+
+    // =========================================================================
+    // SQL
+    // =========================================================================
+
+    pub async fn sql_query(
+        &self,
+        sql: &str,
+        params: Vec<serde_json::Value>,
+    ) -> Result<SqlQueryResponse> {
+        let body = SqlQueryRequest {
+            sql: sql.to_string(),
+            params,
+        };
+        self.json(self.request(Method::POST, &["sql", "query"]).json(&body))
+            .await
+    }
+
+    /// Run a statement, returning the number of affected rows.
+    pub async fn sql_execute(&self, sql: &str, params: Vec<serde_json::Value>) -> Result<u64> {
+        let body = SqlExecuteRequest {
+            sql: sql.to_string(),
+            params,
+        };
+        let response: SqlExecuteResponse = self
+            .json(self.request(Method::POST, &["sql", "execute"]).json(&body))
+            .await?;
+        Ok(response.rows_affected)
+    }
+
+    /// Run statements in one transaction, returning affected rows per statement.
+    pub async fn sql_batch(&self, statements: Vec<SqlExecuteRequest>) -> Result<Vec<u64>> {
+        let body = types::SqlBatchRequest { statements };
+        let response: types::SqlBatchResponse = self
+            .json(self.request(Method::POST, &["sql", "batch"]).json(&body))
+            .await?;
+        Ok(response
+            .results
+            .into_iter()
+            .map(|r| r.rows_affected)
+            .collect())
+    }
+
+    // =========================================================================
+    // Storage
+    // =========================================================================
+
+    pub async fn storage_get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .request(Method::GET, &storage_path(path))
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach daemon at {}", self.base))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let bytes = check(response).await?.bytes().await?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    pub async fn storage_put(
+        &self,
+        path: &str,
+        data: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<()> {
+        let mut request = self.request(Method::PUT, &storage_path(path)).body(data);
+        if let Some(content_type) = content_type {
+            request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+        }
+        self.send(request).await.map(drop)
+    }
+
+    pub async fn storage_delete(&self, path: &str) -> Result<()> {
+        self.send(self.request(Method::DELETE, &storage_path(path)))
+            .await
+            .map(drop)
+    }
+
+    /// Objects under `prefix` (all objects if empty).
+    pub async fn storage_list(&self, prefix: &str) -> Result<StorageListResponse> {
+        self.json(self.request_query(Method::GET, &["storage"], &[("prefix", prefix)]))
+            .await
+    }
+
+    // =========================================================================
+    // Cron
+    // =========================================================================
+
+    pub async fn cron_list(&self) -> Result<ListCronJobsResponse> {
+        self.json(self.request(Method::GET, &["cron"])).await
+    }
+
+    pub async fn cron_get(&self, name: &str) -> Result<Option<CronJobResponse>> {
+        self.optional(self.request(Method::GET, &["cron", name]))
+            .await
+    }
+
+    pub async fn cron_create(&self, job: &CronCreateRequest) -> Result<CronCreateResponse> {
+        self.json(self.request(Method::POST, &["cron"]).json(job))
+            .await
+    }
+
+    /// Pause (`false`) or resume (`true`) a job.
+    pub async fn cron_set_enabled(&self, name: &str, enabled: bool) -> Result<CronUpdateResponse> {
+        let body = CronUpdateRequest {
+            enabled: Some(enabled),
+        };
+        self.json(self.request(Method::PATCH, &["cron", name]).json(&body))
+            .await
+    }
+
+    pub async fn cron_delete(&self, name: &str) -> Result<CronDeleteResponse> {
+        self.json(self.request(Method::DELETE, &["cron", name]))
+            .await
+    }
+
+    pub async fn cron_trigger(&self, name: &str) -> Result<CronTriggerResponse> {
+        self.json(self.request(Method::POST, &["cron", name, "trigger"]))
+            .await
+    }
+
+    pub async fn cron_history(&self, name: &str) -> Result<CronHistoryResponse> {
+        self.json(self.request(Method::GET, &["cron", name, "history"]))
+            .await
+    }
+
+    // =========================================================================
+    // Plumbing
+    // =========================================================================
+
+    /// Request to the URL made of `segments`, which are percent-encoded.
+    fn request(&self, method: Method, segments: &[&str]) -> RequestBuilder {
+        self.request_query(method, segments, &[])
+    }
+
+    /// Like [`request`](Self::request), with a query string.
+    fn request_query(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: &[(&str, &str)],
+    ) -> RequestBuilder {
+        let mut url = self.base.clone();
+        url.path_segments_mut()
+            .expect("checked in new()")
+            .pop_if_empty()
+            .extend(segments);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+
+        let mut request = self.http.request(method, url);
+        if let Some(key) = &self.api_key {
+            request = request.header("X-API-Key", key);
+        }
+        if let Some(tenant) = &self.tenant {
+            request = request.header(TENANT_HEADER, tenant);
+        }
+        request
+    }
+
+    /// Send `request`, failing on error statuses.
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach daemon at {}", self.base))?;
+        check(response).await
+    }
+
+    async fn json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        self.send(request)
+            .await?
+            .json()
+            .await
+            .context("Invalid response from daemon")
+    }
+
+    /// Like [`json`](Self::json), with 404 as `None`.
+    async fn optional<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<Option<T>> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach daemon at {}", self.base))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = check(response).await?.json().await;
+        body.map(Some).context("Invalid response from daemon")
+    }
+}
+
+/// URL segments of an object path, which may contain `/`.
+fn storage_path(path: &str) -> Vec<&str> {
+    std::iter::once("storage")
+        .chain(path.split('/').filter(|segment| !segment.is_empty()))
+        .collect()
+}
+
+/// Turn an error status into an error with the daemon's message.
+async fn check(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<ErrorResponse>(&body).map_or(body, |e| e.error);
+    anyhow::bail!("Daemon returned {}: {message}", status.as_u16())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::Router;
+    use axum::routing::{delete, get, post, put};
+    use dashmap::DashMap;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    use crate::daemon::config::DaemonConfig;
+    use crate::daemon::cron::CronScheduler;
+    use crate::daemon::http::AppState;
+    use crate::daemon::http::handlers::*;
+    use crate::daemon::services::kv::KvStore;
+    use crate::daemon::services::sql::SqlService;
+    use crate::daemon::services::storage::StorageService;
+    use crate::daemon::state::StateStore;
+
+    /// Serve the service routes on an ephemeral port.
+    async fn serve(dir: &std::path::Path) -> DaemonClient {
+        let state = Arc::new(RwLock::new(AppState {
+            store: StateStore::open(dir.join("state.redb")).unwrap(),
+            kv: Some(KvStore::memory()),
+            sql: Some(SqlService::memory().unwrap()),
+            sql_tenants: None,
+            sql_transactions: DashMap::new(),
+            instance_usage: Arc::default(),
+            storage: Some(StorageService::memory()),
+            cron: CronScheduler::new(),
+            config: DaemonConfig::default(),
+        }));
+        let app = Router::new()
+            .route("/kv", get(kv_list))
+            .route("/kv/{key}", get(kv_get).put(kv_set).delete(kv_delete))
+            .route("/sql/query", post(sql_query))
+            .route("/sql/execute", post(sql_execute))
+            .route("/sql/batch", post(sql_batch))
+            .route("/storage", get(storage_list))
+            .route("/storage/{*path}", get(storage_get))
+            .route("/storage/{*path}", put(storage_put))
+            .route("/storage/{*path}", delete(storage_delete))
+            .route("/instances/{name}", get(get_instance))
+            .route("/health", get(health))
+            .route("/version", get(version))
+            .with_state(state);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        DaemonClient::local(port)
+    }
+
+    #[tokio::test]
+    async fn test_client_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = serve(dir.path()).await;
+
+        assert!(client.is_healthy(Duration::from_secs(5)).await);
+        assert!(!client.version().await.unwrap().version.is_empty());
+
+        // KV, with a key that needs encoding
+        client.kv_set("a/b c", "1", None).await.unwrap();
+        assert_eq!(client.kv_get("a/b c").await.unwrap().as_deref(), Some("1"));
+        assert_eq!(client.kv_list("a/").await.unwrap(), ["a/b c"]);
+        client.kv_delete("a/b c").await.unwrap();
+        assert_eq!(client.kv_get("a/b c").await.unwrap(), None);
+
+        // SQL
+        client
+            .sql_execute("CREATE TABLE t (id INTEGER, name TEXT)", vec![])
+            .await
+            .unwrap();
+        let affected = client
+            .sql_execute("INSERT INTO t VALUES (?, ?)", vec![1.into(), "one".into()])
+            .await
+            .unwrap();
+        assert_eq!(affected, 1);
+        let rows = client.sql_query("SELECT * FROM t", vec![]).await.unwrap();
+        assert_eq!(rows.columns, ["id", "name"]);
+        assert_eq!(
+            rows.rows,
+            [vec![serde_json::json!(1), serde_json::json!("one")]]
+        );
+
+        // Storage
+        client
+            .storage_put("docs/readme.txt", b"hello".to_vec(), Some("text/plain"))
+            .await
+            .unwrap();
+        let data = client.storage_get("docs/readme.txt").await.unwrap();
+        assert_eq!(data.as_deref(), Some(&b"hello"[..]));
+        let listed = client.storage_list("docs/").await.unwrap();
+        assert_eq!(listed.objects[0].path, "docs/readme.txt");
+        client.storage_delete("docs/readme.txt").await.unwrap();
+        assert_eq!(client.storage_get("docs/readme.txt").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_client_surfaces_daemon_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = serve(dir.path()).await;
+
+        assert!(client.get_instance("missing").await.unwrap().is_none());
+
+        let err = client
+            .sql_query("SELECT * FROM missing", vec![])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Daemon returned "), "{err}");
+    }
+
+    #[test]
+    fn test_urls_are_encoded() {
+        let client = DaemonClient::new("http://daemon:9919/api/").unwrap();
+        let request = client
+            .request(Method::GET, &["kv", "a/b c"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "http://daemon:9919/api/kv/a%2Fb%20c"
+        );
+
+        assert_eq!(storage_path("/docs//a.txt"), ["storage", "docs", "a.txt"]);
+        assert!(DaemonClient::new("not a url").is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! `mik.v1.Instances`: instance management.
+//!
+//! Calls go through the HTTP handlers, so both APIs start, stop, and report
+//! instances the same way.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use tonic::{Request, Status};
+
+use crate::daemon::process::{RestartConfig, RestartPolicy};
+
+use super::super::SharedState;
+use super::super::handlers::{
+    get_instance, list_instances, restart_instance, start_instance, stop_instance,
+};
+use super::super::types::{self, InstanceResponse};
+use super::proto::{
+    Instance, InstanceRequest, InstanceResources, ListInstancesRequest, ListInstancesResponse,
+    StartInstanceRequest,
+};
+
+grpc_service!(InstancesServer, "mik.v1.Instances", {
+    unary "List" => list,
+    unary "Get" => get,
+    unary "Start" => start,
+    unary "Stop" => stop,
+    unary "Restart" => restart,
+});
+
+impl From<InstanceResponse> for Instance {
+    fn from(instance: InstanceResponse) -> Self {
+        Self {
+            name: instance.name,
+            port: instance.port.into(),
+            pid: instance.pid,
+            status: instance.status,
+            started_at: instance.started_at,
+            uptime: instance.uptime,
+            uptime_secs: instance.uptime_secs,
+            resources: instance.resources.map(|r| InstanceResources {
+                cpu_percent: r.cpu_percent,
+                rss_bytes: r.rss_bytes,
+                open_fds: r.open_fds,
+            }),
+            restart_policy: instance.restart.policy.to_string(),
+            restart_count: instance.restart_count,
+        }
+    }
+}
+
+async fn list(
+    state: SharedState,
+    _request: Request<ListInstancesRequest>,
+) -> Result<ListInstancesResponse, Status> {
+    let Json(response) = list_instances(State(state)).await?;
+    Ok(ListInstancesResponse {
+        instances: response.instances.into_iter().map(Instance::from).collect(),
+    })
+}
+
+async fn get(state: SharedState, request: Request<InstanceRequest>) -> Result<Instance, Status> {
+    let Json(instance) = get_instance(State(state), Path(request.into_inner().name)).await?;
+    Ok(instance.into())
+}
+
+async fn start(
+    state: SharedState,
+    request: Request<StartInstanceRequest>,
+) -> Result<Instance, Status> {
+    let req = request.into_inner();
+    let port = req
+        .port
+        .map(|port| {
+            u16::try_from(port)
+                .map_err(|_| Status::invalid_argument(format!("Invalid port: {port}")))
+        })
+        .transpose()?;
+    let restart = req
+        .restart_policy
+        .map(|policy| policy.parse::<RestartPolicy>())
+        .transpose()
+        .map_err(Status::invalid_argument)?
+        .map(|policy| RestartConfig {
+            max_retries: req.max_restarts,
+            ..RestartConfig::with_policy(policy)
+        });
+    let (_, Json(instance)) = start_instance(
+        State(state),
+        Json(types::StartInstanceRequest {
+            name: req.name,
+            port,
+            config: req.config,
+            working_dir: req.working_dir,
+            auto_restart: req.auto_restart,
+            restart,
+        }),
+    )
+    .await?;
+    Ok(instance.into())
+}
+
+async fn stop(state: SharedState, request: Request<InstanceRequest>) -> Result<Instance, Status> {
+    let Json(instance) = stop_instance(State(state), Path(request.into_inner().name)).await?;
+    Ok(instance.into())
+}
+
+async fn restart(
+    state: SharedState,
+    request: Request<InstanceRequest>,
+) -> Result<Instance, Status> {
+    let Json(instance) = restart_instance(State(state), Path(request.into_inner().name)).await?;
+    Ok(instance.into())
+}
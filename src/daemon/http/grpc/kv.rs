@@ -0,0 +1,113 @@
+//! `mik.v1.Kv`: the KV store.
+//!
+//! Values are raw bytes, unlike the HTTP API which requires UTF-8. With KV
+//! tenancy, calls carry the tenant in `x-tenant-id` metadata.
+
+use std::time::Duration;
+
+use tokio::sync::broadcast::error::RecvError;
+use tonic::{Request, Status};
+
+use crate::daemon::services::kv::KvEventKind;
+
+use super::super::handlers::kv::resolve_kv;
+use super::super::{AppError, SharedState, metrics};
+use super::proto::{
+    KvDeleteRequest, KvDeleteResponse, KvGetRequest, KvGetResponse, KvListRequest, KvListResponse,
+    KvSetRequest, KvSetResponse, KvWatchEvent, KvWatchRequest, kv_watch_event::Kind,
+};
+use super::{ResponseStream, headers, internal};
+
+grpc_service!(KvServer, "mik.v1.Kv", {
+    unary "Get" => get,
+    unary "Set" => set,
+    unary "Delete" => delete,
+    unary "List" => list,
+    streaming "Watch" => watch,
+});
+
+async fn get(state: SharedState, request: Request<KvGetRequest>) -> Result<KvGetResponse, Status> {
+    metrics::record_kv_operation("get");
+    let kv = resolve_kv(&state, &headers(&request)).await?;
+    let key = request.into_inner().key;
+    let value = kv
+        .get(&key)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| AppError::NotFound(format!("Key '{key}' not found")))?;
+    Ok(KvGetResponse { value })
+}
+
+async fn set(state: SharedState, request: Request<KvSetRequest>) -> Result<KvSetResponse, Status> {
+    metrics::record_kv_operation("set");
+    let kv = resolve_kv(&state, &headers(&request)).await?;
+    let req = request.into_inner();
+    kv.set(&req.key, &req.value, req.ttl_secs.map(Duration::from_secs))
+        .await
+        .map_err(AppError::from)?;
+    Ok(KvSetResponse {})
+}
+
+async fn delete(
+    state: SharedState,
+    request: Request<KvDeleteRequest>,
+) -> Result<KvDeleteResponse, Status> {
+    metrics::record_kv_operation("delete");
+    let kv = resolve_kv(&state, &headers(&request)).await?;
+    let deleted = kv
+        .delete(&request.into_inner().key)
+        .await
+        .map_err(internal)?;
+    Ok(KvDeleteResponse { deleted })
+}
+
+async fn list(
+    state: SharedState,
+    request: Request<KvListRequest>,
+) -> Result<KvListResponse, Status> {
+    metrics::record_kv_operation("list");
+    let kv = resolve_kv(&state, &headers(&request)).await?;
+    let keys = kv
+        .list_keys(request.into_inner().prefix.as_deref())
+        .await
+        .map_err(internal)?;
+    Ok(KvListResponse { keys })
+}
+
+/// Streams changes under the prefix until the client cancels the call.
+///
+/// A watcher that falls behind gets a `LAGGED` event with the number of
+/// changes it missed, then continues with newer ones.
+async fn watch(
+    state: SharedState,
+    request: Request<KvWatchRequest>,
+) -> Result<ResponseStream<KvWatchEvent>, Status> {
+    metrics::record_kv_operation("watch");
+    let kv = resolve_kv(&state, &headers(&request)).await?;
+    let watch = kv.watch(&request.into_inner().prefix);
+    Ok(Box::pin(futures::stream::unfold(
+        watch,
+        |mut watch| async move {
+            let event = match watch.recv().await {
+                Ok(change) => KvWatchEvent {
+                    kind: match change.kind {
+                        KvEventKind::Set => Kind::Set,
+                        KvEventKind::Delete => Kind::Delete,
+                    }
+                    .into(),
+                    key: change.key,
+                    value: change.value,
+                    time: change.time.to_rfc3339(),
+                    missed: 0,
+                },
+                Err(RecvError::Lagged(missed)) => KvWatchEvent {
+                    kind: Kind::Lagged.into(),
+                    missed,
+                    ..KvWatchEvent::default()
+                },
+                Err(RecvError::Closed) => return None,
+            };
+            Some((Ok(event), watch))
+        },
+    )))
+}
@@ -0,0 +1,431 @@
+//! gRPC API for the daemon services.
+//!
+//! With `[daemon.grpc]` configured and mik built with the `grpc` feature,
+//! the daemon also serves its API over gRPC on a separate port, giving
+//! sidecars and non-HTTP clients typed, streaming access. The services of
+//! package `mik.v1` (see `proto/mik/v1/daemon.proto`) are:
+//!
+//! - `Kv` - Get, Set, Delete, List, and Watch (streams changes)
+//! - `Sql` - Query (streams rows) and Execute
+//! - `Storage` - Get (streams content), Put, Delete, Head, and List
+//! - `Instances` - List, Get, Start, Stop, and Restart
+//!
+//! Calls run through the same code as the HTTP handlers. With `MIK_API_KEY`
+//! set, every call must carry the key as `x-api-key` metadata, and with
+//! tenant isolation `x-tenant-id` metadata selects the tenant like the
+//! `X-Tenant-Id` header. Messages are limited to [`MAX_MESSAGE_SIZE`], like
+//! HTTP request bodies.
+//!
+//! ```toml
+//! [daemon.grpc]
+//! port = 9920
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::http::HeaderMap;
+use futures::Stream;
+use tokio::net::TcpListener;
+use tonic::server::Grpc;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Request, Response, Status};
+use tonic_prost::ProstCodec;
+
+use super::{AppError, SharedState};
+
+/// Largest message accepted, the same as the HTTP API's body limit.
+pub const MAX_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Responses of a server-streaming method.
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Defines a gRPC service that routes `/{service}/{Method}` calls to
+/// handler functions, the plumbing `tonic-build` would generate for it.
+///
+/// Handlers take the shared state and the request, and return the response
+/// message (`unary`) or a [`ResponseStream`] of them (`streaming`).
+///
+/// # Usage
+///
+/// ```ignore
+/// grpc_service!(KvServer, "mik.v1.Kv", {
+///     unary "Get" => get,
+///     streaming "Watch" => watch,
+/// });
+/// ```
+macro_rules! grpc_service {
+    ($server:ident, $name:literal, { $($kind:ident $method:literal => $handler:path),+ $(,)? }) => {
+        #[doc = concat!("Server of the `", $name, "` gRPC service.")]
+        #[derive(Clone)]
+        pub(super) struct $server(super::super::SharedState);
+
+        impl $server {
+            pub(super) const fn new(state: super::super::SharedState) -> Self {
+                Self(state)
+            }
+        }
+
+        impl tonic::server::NamedService for $server {
+            const NAME: &'static str = $name;
+        }
+
+        impl tower::Service<axum::http::Request<tonic::body::Body>> for $server {
+            type Response = axum::http::Response<tonic::body::Body>;
+            type Error = std::convert::Infallible;
+            type Future = futures::future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+            fn poll_ready(
+                &mut self,
+                _cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<Result<(), Self::Error>> {
+                std::task::Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, request: axum::http::Request<tonic::body::Body>) -> Self::Future {
+                let state = std::sync::Arc::clone(&self.0);
+                Box::pin(async move {
+                    let path = request.uri().path().to_string();
+                    if let Err(status) = super::authorize(request.headers(), &path) {
+                        return Ok(status.into_http());
+                    }
+                    let method = path
+                        .strip_prefix(concat!("/", $name, "/"))
+                        .unwrap_or_default();
+                    Ok(match method {
+                        $($method => super::$kind(state, request, $handler).await,)+
+                        _ => tonic::Status::unimplemented(format!("Unknown method {path}"))
+                            .into_http(),
+                    })
+                })
+            }
+        }
+    };
+}
+
+mod instances;
+mod kv;
+mod proto;
+mod sql;
+mod storage;
+
+/// Serves the gRPC API on `listener` until `shutdown` completes.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    state: SharedState,
+    shutdown: impl Future<Output = ()>,
+) -> Result<()> {
+    tonic::transport::Server::builder()
+        .add_service(kv::KvServer::new(Arc::clone(&state)))
+        .add_service(sql::SqlServer::new(Arc::clone(&state)))
+        .add_service(storage::StorageServer::new(Arc::clone(&state)))
+        .add_service(instances::InstancesServer::new(state))
+        .serve_with_incoming_shutdown(TcpIncoming::from(listener), shutdown)
+        .await
+        .context("gRPC server error")
+}
+
+/// Checks the API key of a call, as the HTTP middleware does.
+fn authorize(headers: &HeaderMap, path: &str) -> Result<(), Status> {
+    let Some(expected_key) = super::API_KEY.as_ref() else {
+        return Ok(());
+    };
+    let provided_key = headers.get("x-api-key").and_then(|v| v.to_str().ok());
+    super::check_api_key(provided_key, expected_key, path).map_err(Status::unauthenticated)
+}
+
+impl From<AppError> for Status {
+    fn from(error: AppError) -> Self {
+        match error {
+            AppError::NotFound(msg) => Self::not_found(msg),
+            AppError::BadRequest(msg) => Self::invalid_argument(msg),
+            AppError::Conflict(msg) => Self::aborted(msg),
+            AppError::Internal(msg) => Self::internal(msg),
+            AppError::ServiceUnavailable(msg) => Self::unavailable(msg),
+            AppError::PayloadTooLarge(msg) | AppError::InsufficientStorage(msg) => {
+                Self::resource_exhausted(msg)
+            },
+            AppError::Forbidden(msg) => Self::permission_denied(msg),
+            AppError::PreconditionFailed(msg) => Self::failed_precondition(msg),
+        }
+    }
+}
+
+/// Metadata of a call as HTTP headers, for the handlers' tenant lookups.
+fn headers<M>(request: &Request<M>) -> HeaderMap {
+    request.metadata().clone().into_headers()
+}
+
+/// Reports a service error as `INTERNAL`, as the HTTP API reports it as 500.
+fn internal(error: anyhow::Error) -> Status {
+    AppError::from(error).into()
+}
+
+/// Codec for messages `M` in and `R` out, with the message size limit.
+fn grpc<R, M>() -> Grpc<ProstCodec<R, M>>
+where
+    R: prost::Message + Send + 'static,
+    M: prost::Message + Default + Send + 'static,
+{
+    Grpc::new(ProstCodec::default()).max_decoding_message_size(MAX_MESSAGE_SIZE)
+}
+
+/// Answers a unary call with `handler`.
+async fn unary<M, R, F, Fut>(
+    state: SharedState,
+    request: axum::http::Request<tonic::body::Body>,
+    handler: F,
+) -> axum::http::Response<tonic::body::Body>
+where
+    M: prost::Message + Default + Send + 'static,
+    R: prost::Message + Send + 'static,
+    F: Fn(SharedState, Request<M>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<R, Status>> + Send + 'static,
+{
+    let service = tower::service_fn(move |request| {
+        let call = handler(Arc::clone(&state), request);
+        async move { call.await.map(Response::new) }
+    });
+    grpc().unary(service, request).await
+}
+
+/// Answers a server-streaming call with `handler`.
+async fn streaming<M, R, F, Fut>(
+    state: SharedState,
+    request: axum::http::Request<tonic::body::Body>,
+    handler: F,
+) -> axum::http::Response<tonic::body::Body>
+where
+    M: prost::Message + Default + Send + 'static,
+    R: prost::Message + Send + 'static,
+    F: Fn(SharedState, Request<M>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<ResponseStream<R>, Status>> + Send + 'static,
+{
+    let service = tower::service_fn(move |request| {
+        let call = handler(Arc::clone(&state), request);
+        async move { call.await.map(Response::new) }
+    });
+    grpc().server_streaming(service, request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::proto::*;
+    use super::*;
+    use crate::daemon::config::DaemonConfig;
+    use crate::daemon::cron::CronScheduler;
+    use crate::daemon::http::AppState;
+    use crate::daemon::services::kv::KvStore;
+    use crate::daemon::services::sql::SqlService;
+    use crate::daemon::services::storage::StorageService;
+    use crate::daemon::state::StateStore;
+    use bytes::{Buf, BufMut, Bytes, BytesMut};
+    use dashmap::DashMap;
+    use http_body_util::BodyExt;
+    use tokio::sync::RwLock;
+    use tonic::Code;
+    use tower::{Service, ServiceExt};
+
+    fn test_state(dir: &tempfile::TempDir, kv: Option<KvStore>) -> SharedState {
+        Arc::new(RwLock::new(AppState {
+            store: StateStore::open(dir.path().join("state.redb")).unwrap(),
+            kv,
+            sql: Some(SqlService::memory().unwrap()),
+            sql_tenants: None,
+            sql_transactions: DashMap::new(),
+            instance_usage: Arc::default(),
+            storage: Some(StorageService::memory()),
+            cron: CronScheduler::new(),
+            config: DaemonConfig::default(),
+        }))
+    }
+
+    /// Sends one framed message to `path` and decodes the response messages.
+    async fn call<S, M, R>(server: S, path: &str, message: &M) -> Result<Vec<R>, Status>
+    where
+        S: Service<
+                axum::http::Request<tonic::body::Body>,
+                Response = axum::http::Response<tonic::body::Body>,
+                Error = std::convert::Infallible,
+            >,
+        M: prost::Message,
+        R: prost::Message + Default,
+    {
+        let mut frame = BytesMut::new();
+        frame.put_u8(0);
+        frame.put_u32(u32::try_from(message.encoded_len()).unwrap());
+        message.encode(&mut frame).unwrap();
+        let request = axum::http::Request::post(path)
+            .header("content-type", "application/grpc")
+            .body(tonic::body::Body::new(http_body_util::Full::new(
+                frame.freeze(),
+            )))
+            .unwrap();
+
+        let response = server.oneshot(request).await.unwrap();
+        let headers = response.headers().clone();
+        let body = response.into_body().collect().await.unwrap();
+        let trailers = body.trailers().cloned().unwrap_or_default();
+        if let Some(status) =
+            Status::from_header_map(&trailers).or_else(|| Status::from_header_map(&headers))
+            && status.code() != Code::Ok
+        {
+            return Err(status);
+        }
+
+        let mut data: Bytes = body.to_bytes();
+        let mut messages = Vec::new();
+        while data.has_remaining() {
+            assert_eq!(data.get_u8(), 0, "compressed frame");
+            let len = data.get_u32() as usize;
+            messages.push(R::decode(data.split_to(len)).unwrap());
+        }
+        Ok(messages)
+    }
+
+    #[tokio::test]
+    async fn test_kv_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let kv = kv::KvServer::new(test_state(&dir, Some(KvStore::memory())));
+
+        let set = KvSetRequest {
+            key: "greeting".into(),
+            value: vec![0xff, 0x00],
+            ttl_secs: None,
+        };
+        call::<_, _, KvSetResponse>(kv.clone(), "/mik.v1.Kv/Set", &set)
+            .await
+            .unwrap();
+
+        let get = KvGetRequest {
+            key: "greeting".into(),
+        };
+        let got: Vec<KvGetResponse> = call(kv.clone(), "/mik.v1.Kv/Get", &get).await.unwrap();
+        assert_eq!(got[0].value, [0xff, 0x00]);
+
+        let list: Vec<KvListResponse> =
+            call(kv.clone(), "/mik.v1.Kv/List", &KvListRequest::default())
+                .await
+                .unwrap();
+        assert_eq!(list[0].keys, ["greeting"]);
+
+        let deleted: Vec<KvDeleteResponse> = call(
+            kv.clone(),
+            "/mik.v1.Kv/Delete",
+            &KvDeleteRequest {
+                key: "greeting".into(),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(deleted[0].deleted);
+
+        let missing = call::<_, _, KvGetResponse>(kv, "/mik.v1.Kv/Get", &get)
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_sql_query_streams_columns_then_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let sql = sql::SqlServer::new(test_state(&dir, None));
+
+        for statement in [
+            "CREATE TABLE t (id INTEGER, name TEXT)",
+            "INSERT INTO t VALUES (1, 'a'), (2, NULL)",
+        ] {
+            let request = SqlExecuteRequest {
+                sql: statement.into(),
+                params: Vec::new(),
+            };
+            call::<_, _, SqlExecuteResponse>(sql.clone(), "/mik.v1.Sql/Execute", &request)
+                .await
+                .unwrap();
+        }
+
+        let request = SqlQueryRequest {
+            sql: "SELECT id, name FROM t WHERE id >= ? ORDER BY id".into(),
+            params: vec![SqlValue {
+                kind: Some(sql_value::Kind::Integer(1)),
+            }],
+        };
+        let messages: Vec<SqlQueryResponse> = call(sql.clone(), "/mik.v1.Sql/Query", &request)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].columns, ["id", "name"]);
+        assert_eq!(
+            messages[1].values[1].kind,
+            Some(sql_value::Kind::Text("a".into()))
+        );
+        assert_eq!(messages[2].values[1].kind, None);
+
+        let invalid = SqlQueryRequest {
+            sql: "SELECT * FROM missing".into(),
+            params: Vec::new(),
+        };
+        let err = call::<_, _, SqlQueryResponse>(sql, "/mik.v1.Sql/Query", &invalid)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::Internal);
+    }
+
+    #[tokio::test]
+    async fn test_storage_get_streams_metadata_then_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = storage::StorageServer::new(test_state(&dir, None));
+
+        let put = StoragePutRequest {
+            path: "docs/readme.txt".into(),
+            data: b"hello".to_vec(),
+            content_type: Some("text/plain".into()),
+        };
+        let stored: Vec<StorageObject> = call(storage.clone(), "/mik.v1.Storage/Put", &put)
+            .await
+            .unwrap();
+        assert_eq!(stored[0].size, 5);
+
+        let get = StoragePathRequest {
+            path: "docs/readme.txt".into(),
+        };
+        let messages: Vec<StorageGetResponse> = call(storage.clone(), "/mik.v1.Storage/Get", &get)
+            .await
+            .unwrap();
+        let object = messages[0].object.clone().unwrap();
+        assert_eq!(object.content_type, "text/plain");
+        assert_eq!(object.etag, stored[0].etag);
+        let content: Vec<u8> = messages[1..]
+            .iter()
+            .flat_map(|message| message.data.to_vec())
+            .collect();
+        assert_eq!(content, b"hello");
+
+        let missing = StoragePathRequest {
+            path: "docs/missing.txt".into(),
+        };
+        let err = call::<_, _, StorageObject>(storage, "/mik.v1.Storage/Head", &missing)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_service_and_unknown_method() {
+        let dir = tempfile::tempdir().unwrap();
+        let kv = kv::KvServer::new(test_state(&dir, None));
+
+        let get = KvGetRequest { key: "k".into() };
+        let err = call::<_, _, KvGetResponse>(kv.clone(), "/mik.v1.Kv/Get", &get)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::Unavailable);
+
+        let err = call::<_, _, KvGetResponse>(kv, "/mik.v1.Kv/Frobnicate", &get)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::Unimplemented);
+    }
+}
@@ -0,0 +1,293 @@
+//! Messages of the `mik.v1` package.
+//!
+//! Written by hand to match `proto/mik/v1/daemon.proto`, so building mik
+//! needs no `protoc`. Keep field tags in sync with the `.proto` file.
+
+/// Request of `Kv/Get`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvGetRequest {
+    #[prost(string, tag = "1")]
+    pub key: String,
+}
+
+/// Response of `Kv/Get`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvGetResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub value: Vec<u8>,
+}
+
+/// Request of `Kv/Set`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvSetRequest {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+    #[prost(uint64, optional, tag = "3")]
+    pub ttl_secs: Option<u64>,
+}
+
+/// Response of `Kv/Set`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvSetResponse {}
+
+/// Request of `Kv/Delete`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvDeleteRequest {
+    #[prost(string, tag = "1")]
+    pub key: String,
+}
+
+/// Response of `Kv/Delete`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvDeleteResponse {
+    #[prost(bool, tag = "1")]
+    pub deleted: bool,
+}
+
+/// Request of `Kv/List`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvListRequest {
+    #[prost(string, optional, tag = "1")]
+    pub prefix: Option<String>,
+}
+
+/// Response of `Kv/List`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvListResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub keys: Vec<String>,
+}
+
+/// Request of `Kv/Watch`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvWatchRequest {
+    #[prost(string, tag = "1")]
+    pub prefix: String,
+}
+
+/// A message of the `Kv/Watch` stream.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KvWatchEvent {
+    #[prost(enumeration = "kv_watch_event::Kind", tag = "1")]
+    pub kind: i32,
+    #[prost(string, tag = "2")]
+    pub key: String,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub value: Option<Vec<u8>>,
+    #[prost(string, tag = "4")]
+    pub time: String,
+    #[prost(uint64, tag = "5")]
+    pub missed: u64,
+}
+
+/// Nested types of [`KvWatchEvent`].
+pub mod kv_watch_event {
+    /// What a watch event reports.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Kind {
+        Set = 0,
+        Delete = 1,
+        Lagged = 2,
+    }
+}
+
+/// A SQL value; NULL when `kind` is empty.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SqlValue {
+    #[prost(oneof = "sql_value::Kind", tags = "1, 2, 3, 4")]
+    pub kind: Option<sql_value::Kind>,
+}
+
+/// Nested types of [`SqlValue`].
+pub mod sql_value {
+    /// Value of a non-NULL [`SqlValue`](super::SqlValue).
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Kind {
+        #[prost(int64, tag = "1")]
+        Integer(i64),
+        #[prost(double, tag = "2")]
+        Real(f64),
+        #[prost(string, tag = "3")]
+        Text(String),
+        #[prost(bytes = "vec", tag = "4")]
+        Blob(Vec<u8>),
+    }
+}
+
+/// Request of `Sql/Query`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SqlQueryRequest {
+    #[prost(string, tag = "1")]
+    pub sql: String,
+    #[prost(message, repeated, tag = "2")]
+    pub params: Vec<SqlValue>,
+}
+
+/// A message of the `Sql/Query` stream: column names first, then one row
+/// per message.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SqlQueryResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub columns: Vec<String>,
+    #[prost(message, repeated, tag = "2")]
+    pub values: Vec<SqlValue>,
+}
+
+/// Request of `Sql/Execute`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SqlExecuteRequest {
+    #[prost(string, tag = "1")]
+    pub sql: String,
+    #[prost(message, repeated, tag = "2")]
+    pub params: Vec<SqlValue>,
+}
+
+/// Response of `Sql/Execute`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SqlExecuteResponse {
+    #[prost(uint64, tag = "1")]
+    pub rows_affected: u64,
+}
+
+/// Metadata of a storage object.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StorageObject {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(uint64, tag = "2")]
+    pub size: u64,
+    #[prost(string, tag = "3")]
+    pub content_type: String,
+    #[prost(string, tag = "4")]
+    pub created_at: String,
+    #[prost(string, tag = "5")]
+    pub updated_at: String,
+    #[prost(string, optional, tag = "6")]
+    pub expires_at: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub etag: Option<String>,
+}
+
+/// Request naming a storage object.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StoragePathRequest {
+    #[prost(string, tag = "1")]
+    pub path: String,
+}
+
+/// A message of the `Storage/Get` stream: metadata first, then content.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StorageGetResponse {
+    #[prost(message, optional, tag = "1")]
+    pub object: Option<StorageObject>,
+    #[prost(bytes = "bytes", tag = "2")]
+    pub data: ::prost::bytes::Bytes,
+}
+
+/// Request of `Storage/Put`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StoragePutRequest {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: Vec<u8>,
+    #[prost(string, optional, tag = "3")]
+    pub content_type: Option<String>,
+}
+
+/// Response of `Storage/Delete`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StorageDeleteResponse {
+    #[prost(bool, tag = "1")]
+    pub deleted: bool,
+}
+
+/// Request of `Storage/List`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StorageListRequest {
+    #[prost(string, optional, tag = "1")]
+    pub prefix: Option<String>,
+}
+
+/// Response of `Storage/List`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StorageListResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub objects: Vec<StorageObject>,
+}
+
+/// A managed instance.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Instance {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(uint32, tag = "2")]
+    pub port: u32,
+    #[prost(uint32, tag = "3")]
+    pub pid: u32,
+    #[prost(string, tag = "4")]
+    pub status: String,
+    #[prost(string, optional, tag = "5")]
+    pub started_at: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub uptime: Option<String>,
+    #[prost(uint64, optional, tag = "7")]
+    pub uptime_secs: Option<u64>,
+    #[prost(message, optional, tag = "8")]
+    pub resources: Option<InstanceResources>,
+    #[prost(string, tag = "9")]
+    pub restart_policy: String,
+    #[prost(uint32, tag = "10")]
+    pub restart_count: u32,
+}
+
+/// Resource usage of an instance process.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InstanceResources {
+    #[prost(float, tag = "1")]
+    pub cpu_percent: f32,
+    #[prost(uint64, tag = "2")]
+    pub rss_bytes: u64,
+    #[prost(uint64, optional, tag = "3")]
+    pub open_fds: Option<u64>,
+}
+
+/// Request naming an instance.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InstanceRequest {
+    #[prost(string, tag = "1")]
+    pub name: String,
+}
+
+/// Request of `Instances/List`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListInstancesRequest {}
+
+/// Response of `Instances/List`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListInstancesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub instances: Vec<Instance>,
+}
+
+/// Request of `Instances/Start`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StartInstanceRequest {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(uint32, optional, tag = "2")]
+    pub port: Option<u32>,
+    #[prost(string, optional, tag = "3")]
+    pub config: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub working_dir: Option<String>,
+    #[prost(bool, tag = "5")]
+    pub auto_restart: bool,
+    #[prost(string, optional, tag = "6")]
+    pub restart_policy: Option<String>,
+    #[prost(uint32, optional, tag = "7")]
+    pub max_restarts: Option<u32>,
+}
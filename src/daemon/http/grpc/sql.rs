@@ -0,0 +1,101 @@
+//! `mik.v1.Sql`: the SQL database.
+//!
+//! Queries are streamed, so large results are never held in memory.
+
+use std::time::Instant;
+
+use futures::StreamExt;
+use tonic::{Request, Status};
+
+use crate::daemon::services::sql::Value;
+
+use super::super::handlers::sql::resolve_sql;
+use super::super::{SharedState, metrics};
+use super::proto::{
+    SqlExecuteRequest, SqlExecuteResponse, SqlQueryRequest, SqlQueryResponse, SqlValue, sql_value,
+};
+use super::{ResponseStream, headers, internal};
+
+grpc_service!(SqlServer, "mik.v1.Sql", {
+    streaming "Query" => query,
+    unary "Execute" => execute,
+});
+
+impl From<SqlValue> for Value {
+    fn from(value: SqlValue) -> Self {
+        match value.kind {
+            None => Self::Null,
+            Some(sql_value::Kind::Integer(i)) => Self::Integer(i),
+            Some(sql_value::Kind::Real(r)) => Self::Real(r),
+            Some(sql_value::Kind::Text(s)) => Self::Text(s),
+            Some(sql_value::Kind::Blob(b)) => Self::Blob(b),
+        }
+    }
+}
+
+impl From<Value> for SqlValue {
+    fn from(value: Value) -> Self {
+        let kind = match value {
+            Value::Null => None,
+            Value::Integer(i) => Some(sql_value::Kind::Integer(i)),
+            Value::Real(r) => Some(sql_value::Kind::Real(r)),
+            Value::Text(s) => Some(sql_value::Kind::Text(s)),
+            Value::Blob(b) => Some(sql_value::Kind::Blob(b)),
+        };
+        Self { kind }
+    }
+}
+
+fn params(params: Vec<SqlValue>) -> Vec<Value> {
+    params.into_iter().map(Value::from).collect()
+}
+
+/// Streams the column names, then one message per row.
+async fn query(
+    state: SharedState,
+    request: Request<SqlQueryRequest>,
+) -> Result<ResponseStream<SqlQueryResponse>, Status> {
+    let start = Instant::now();
+    let sql = resolve_sql(&state, &headers(&request)).await?;
+    let req = request.into_inner();
+
+    let rows = sql
+        .query_stream(&req.sql, &params(req.params))
+        .await
+        .map_err(internal)?;
+    metrics::record_sql_query("stream", start.elapsed().as_secs_f64());
+
+    let columns = SqlQueryResponse {
+        columns: rows.columns().to_vec(),
+        values: Vec::new(),
+    };
+    let rows = rows.map(|row| {
+        row.map(|row| SqlQueryResponse {
+            columns: Vec::new(),
+            values: row.values.into_iter().map(SqlValue::from).collect(),
+        })
+        .map_err(internal)
+    });
+    Ok(Box::pin(
+        futures::stream::once(async { Ok(columns) }).chain(rows),
+    ))
+}
+
+async fn execute(
+    state: SharedState,
+    request: Request<SqlExecuteRequest>,
+) -> Result<SqlExecuteResponse, Status> {
+    let start = Instant::now();
+    let sql = resolve_sql(&state, &headers(&request)).await?;
+    let req = request.into_inner();
+
+    let rows_affected = sql
+        .execute(&req.sql, &params(req.params))
+        .await
+        .map_err(internal)?;
+    metrics::record_sql_query("execute", start.elapsed().as_secs_f64());
+
+    Ok(SqlExecuteResponse {
+        rows_affected: rows_affected as u64,
+    })
+}
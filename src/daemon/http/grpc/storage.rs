@@ -0,0 +1,132 @@
+//! `mik.v1.Storage`: object storage.
+//!
+//! Downloads are streamed in chunks. Uploads must fit in one message
+//! ([`MAX_MESSAGE_SIZE`](super::MAX_MESSAGE_SIZE)); larger objects go
+//! through the HTTP or S3 API, which stream them.
+
+use futures::StreamExt;
+use tonic::{Request, Status};
+
+use crate::daemon::services::storage::StorageQuotaExceeded;
+
+use super::super::handlers::storage::resolve_storage;
+use super::super::types::StorageObjectInfo;
+use super::super::{AppError, SharedState, metrics};
+use super::proto::{
+    StorageDeleteResponse, StorageGetResponse, StorageListRequest, StorageListResponse,
+    StorageObject, StoragePathRequest, StoragePutRequest,
+};
+use super::{ResponseStream, headers, internal};
+
+grpc_service!(StorageServer, "mik.v1.Storage", {
+    streaming "Get" => get,
+    unary "Put" => put,
+    unary "Delete" => delete,
+    unary "Head" => head,
+    unary "List" => list,
+});
+
+impl From<StorageObjectInfo> for StorageObject {
+    fn from(info: StorageObjectInfo) -> Self {
+        Self {
+            path: info.path,
+            size: info.size,
+            content_type: info.content_type,
+            created_at: info.created_at,
+            updated_at: info.updated_at,
+            expires_at: info.expires_at,
+            etag: info.etag,
+        }
+    }
+}
+
+/// Streams the object's metadata, then its content.
+async fn get(
+    state: SharedState,
+    request: Request<StoragePathRequest>,
+) -> Result<ResponseStream<StorageGetResponse>, Status> {
+    let storage = resolve_storage(&state, &headers(&request)).await?;
+    let path = request.into_inner().path;
+    let (content, meta) = storage
+        .get_object_stream(&path)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| AppError::NotFound(format!("Object '{path}' not found")))?;
+    metrics::record_storage_operation("get", Some(meta.size));
+
+    let object = StorageGetResponse {
+        object: Some(StorageObjectInfo::from(meta).into()),
+        data: prost::bytes::Bytes::new(),
+    };
+    let chunks = content.map(|chunk| {
+        chunk
+            .map(|data| StorageGetResponse { object: None, data })
+            .map_err(|e| Status::internal(e.to_string()))
+    });
+    Ok(Box::pin(
+        futures::stream::once(async { Ok(object) }).chain(chunks),
+    ))
+}
+
+async fn put(
+    state: SharedState,
+    request: Request<StoragePutRequest>,
+) -> Result<StorageObject, Status> {
+    let storage = resolve_storage(&state, &headers(&request)).await?;
+    let req = request.into_inner();
+    let meta = storage
+        .put_object(&req.path, &req.data, req.content_type.as_deref())
+        .await
+        .map_err(|e| match e.downcast_ref::<StorageQuotaExceeded>() {
+            Some(exceeded) => AppError::InsufficientStorage(exceeded.to_string()).into(),
+            None => internal(e),
+        })?;
+    metrics::record_storage_operation("put", Some(meta.size));
+    Ok(StorageObjectInfo::from(meta).into())
+}
+
+async fn delete(
+    state: SharedState,
+    request: Request<StoragePathRequest>,
+) -> Result<StorageDeleteResponse, Status> {
+    metrics::record_storage_operation("delete", None);
+    let storage = resolve_storage(&state, &headers(&request)).await?;
+    let deleted = storage
+        .delete_object(&request.into_inner().path)
+        .await
+        .map_err(internal)?;
+    Ok(StorageDeleteResponse { deleted })
+}
+
+async fn head(
+    state: SharedState,
+    request: Request<StoragePathRequest>,
+) -> Result<StorageObject, Status> {
+    metrics::record_storage_operation("head", None);
+    let storage = resolve_storage(&state, &headers(&request)).await?;
+    let path = request.into_inner().path;
+    let meta = storage
+        .head_object(&path)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| AppError::NotFound(format!("Object '{path}' not found")))?;
+    Ok(StorageObjectInfo::from(meta).into())
+}
+
+async fn list(
+    state: SharedState,
+    request: Request<StorageListRequest>,
+) -> Result<StorageListResponse, Status> {
+    metrics::record_storage_operation("list", None);
+    let storage = resolve_storage(&state, &headers(&request)).await?;
+    let prefix = request.into_inner().prefix;
+    let objects = storage
+        .list_objects(prefix.as_deref())
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .filter(|meta| meta.path.starts_with(prefix.as_deref().unwrap_or_default()))
+        .map(|meta| StorageObjectInfo::from(meta).into())
+        .collect();
+    Ok(StorageListResponse { objects })
+}
@@ -10,11 +10,11 @@ use axum::{
 
 use super::super::types::{
     CronCreateRequest, CronCreateResponse, CronDeleteResponse, CronExecutionInfo,
-    CronHistoryResponse, CronJobResponse, CronTriggerResponse, CronUpdateRequest,
-    CronUpdateResponse, ListCronJobsResponse,
+    CronHistoryResponse, CronJobResponse, CronLeaderResponse, CronTriggerResponse,
+    CronUpdateRequest, CronUpdateResponse, ListCronJobsResponse,
 };
 use super::super::{AppError, SharedState};
-use crate::daemon::cron::ScheduleConfig;
+use crate::daemon::cron::{JobType, ScheduleConfig};
 
 /// GET /cron - List all scheduled jobs.
 pub(crate) async fn cron_list(
@@ -26,8 +26,9 @@ pub(crate) async fn cron_list(
     let responses: Vec<CronJobResponse> = jobs
         .iter()
         .map(|job| {
-            // Determine status based on last execution
+            // Determine status based on in-flight and last execution
             let status_str = match &job.last_execution {
+                _ if job.running => "running",
                 Some(exec) if exec.success => "idle",
                 Some(_) => "failed",
                 None => "idle",
@@ -36,6 +37,7 @@ pub(crate) async fn cron_list(
             CronJobResponse {
                 name: job.name.clone(),
                 cron: job.cron.clone(),
+                timezone: job.timezone.clone(),
                 module: job.module.clone(),
                 method: job.method.clone(),
                 path: job.path.clone(),
@@ -48,11 +50,33 @@ pub(crate) async fn cron_list(
                 next_run: job
                     .next_run
                     .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+                overlap: job.overlap,
+                jitter_secs: job.jitter_secs,
+                last_skip_at: job
+                    .last_skip
+                    .as_ref()
+                    .map(|skip| skip.at.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+                last_skip_reason: job.last_skip.as_ref().map(|skip| skip.reason.clone()),
             }
         })
         .collect();
 
-    Ok(Json(ListCronJobsResponse { jobs: responses }))
+    let leader = match state.cron.leader_lease() {
+        Some(lease) => Some(CronLeaderResponse {
+            node_id: lease.node_id().to_string(),
+            is_leader: lease.is_leader(),
+            current_leader: lease
+                .current_leader()
+                .await
+                .map_err(|e| AppError::Internal(format!("{e:#}")))?,
+        }),
+        None => None,
+    };
+
+    Ok(Json(ListCronJobsResponse {
+        jobs: responses,
+        leader,
+    }))
 }
 
 /// POST /cron - Create a new cron job.
@@ -64,11 +88,19 @@ pub(crate) async fn cron_create(
 
     let state = state.read().await;
 
+    if req.job_type == JobType::Http && req.module.is_empty() {
+        return Err(AppError::BadRequest(
+            "Field 'module' is required for http jobs".to_string(),
+        ));
+    }
+
     // Create schedule config
     let config = ScheduleConfig {
         name: req.name.clone(),
+        job_type: req.job_type,
         module: PathBuf::from(&req.module),
         cron: req.cron,
+        timezone: req.timezone,
         method: req.method,
         path: req.path,
         enabled: req.enabled,
@@ -76,6 +108,10 @@ pub(crate) async fn cron_create(
         body: req.body,
         headers: req.headers,
         health_path: req.health_path,
+        backup: req.backup,
+        sql_backup: req.sql_backup,
+        overlap: req.overlap,
+        jitter_secs: req.jitter_secs,
     };
 
     // Add the job to scheduler
@@ -112,8 +148,9 @@ pub(crate) async fn cron_get(
         .await
         .ok_or_else(|| AppError::NotFound(format!("Cron job '{name}' not found")))?;
 
-    // Determine status based on last execution
+    // Determine status based on in-flight and last execution
     let status_str = match &job.last_execution {
+        _ if job.running => "running",
         Some(exec) if exec.success => "idle",
         Some(_) => "failed",
         None => "idle",
@@ -122,6 +159,7 @@ pub(crate) async fn cron_get(
     Ok(Json(CronJobResponse {
         name: job.name.clone(),
         cron: job.cron.clone(),
+        timezone: job.timezone.clone(),
         module: job.module.clone(),
         method: "GET".to_string(),
         path: "/".to_string(),
@@ -134,6 +172,13 @@ pub(crate) async fn cron_get(
         next_run: job
             .next_run
             .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+        overlap: job.overlap,
+        jitter_secs: job.jitter_secs,
+        last_skip_at: job
+            .last_skip
+            .as_ref()
+            .map(|skip| skip.at.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+        last_skip_reason: job.last_skip.as_ref().map(|skip| skip.reason.clone()),
     }))
 }
 
@@ -284,6 +329,7 @@ pub(crate) async fn cron_history(
             success: exec.success,
             error: exec.error.clone(),
             manual: exec.manual,
+            output: exec.output.clone(),
         })
         .collect();
 
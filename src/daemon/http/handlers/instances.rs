@@ -3,21 +3,31 @@
 //! Handlers for managing WASM instances (list, start, stop, restart, logs).
 
 use std::path::PathBuf;
+use std::sync::Arc;
+
+use std::convert::Infallible;
+use std::time::Duration;
 
 use axum::{
     Json,
     extract::{Path, Query, State},
     http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
+use dashmap::DashMap;
+use futures::{Stream, StreamExt};
 
 use super::super::types::{
-    HealthResponse, InstanceResponse, ListInstancesResponse, LogsQuery, LogsResponse,
-    StartInstanceRequest, VersionResponse, validate_instance_name,
+    HealthResponse, InstanceResources, InstanceResponse, ListInstancesResponse, LogsQuery,
+    LogsResponse, StartInstanceRequest, VersionResponse, validate_instance_name,
 };
-use super::super::{AppError, SharedState};
+use super::super::{AppError, SharedState, spawn_config_for};
 use crate::daemon::cron::parse_schedules_from_manifest;
 use crate::daemon::metrics;
-use crate::daemon::process::{self, SpawnConfig};
+use crate::daemon::process::{self, RestartConfig, RestartPolicy, SpawnConfig};
 use crate::daemon::state::{Instance, Status};
 
 /// GET /instances - List all instances.
@@ -25,9 +35,9 @@ pub(crate) async fn list_instances(
     State(state): State<SharedState>,
 ) -> Result<Json<ListInstancesResponse>, AppError> {
     // Clone store and drop lock before async operation
-    let store = {
+    let (store, usage) = {
         let state = state.read().await;
-        state.store.clone()
+        (state.store.clone(), Arc::clone(&state.instance_usage))
     };
 
     let instances = store.list_instances_async().await?;
@@ -45,13 +55,13 @@ pub(crate) async fn list_instances(
             instance.status == Status::Running && process::is_running(instance.pid)?;
 
         if instance.status == Status::Running && !is_actually_running {
-            response.status = "crashed".to_string();
-            response.uptime = None;
+            response.mark_crashed();
             crashed_count += 1;
         } else {
             match &instance.status {
                 Status::Running => {
                     running_count += 1;
+                    response.resources = sampled_usage(&usage, instance);
                     // Record uptime for running instances
                     #[allow(clippy::cast_precision_loss)] // Uptime in seconds is safe
                     let uptime = chrono::Utc::now()
@@ -122,6 +132,13 @@ pub(crate) async fn start_instance(
     };
 
     let port = req.port.unwrap_or(3000);
+    let restart = req.restart.unwrap_or_else(|| {
+        RestartConfig::with_policy(if req.auto_restart {
+            RestartPolicy::Always
+        } else {
+            RestartPolicy::Never
+        })
+    });
 
     let spawn_config = SpawnConfig {
         name: req.name.clone(),
@@ -129,6 +146,10 @@ pub(crate) async fn start_instance(
         config_path: config_path.clone(),
         working_dir: working_dir.clone(),
         hot_reload: false,
+        build_error_file: None,
+        dev_proxy: None,
+        dev_dashboard: false,
+        restart,
     };
 
     let info = process::spawn_instance(&spawn_config)?;
@@ -142,9 +163,12 @@ pub(crate) async fn start_instance(
         config: config_path,
         started_at: chrono::Utc::now(),
         modules: vec![],
-        auto_restart: req.auto_restart,
+        auto_restart: restart.policy != RestartPolicy::Never,
         restart_count: 0,
         last_restart_at: None,
+        memory_restarts: 0,
+        restart: Some(spawn_config.restart),
+        restart_history: Vec::new(),
     };
 
     // Save instance asynchronously (non-blocking)
@@ -216,9 +240,9 @@ pub(crate) async fn get_instance(
     Path(name): Path<String>,
 ) -> Result<Json<InstanceResponse>, AppError> {
     // Clone store and drop lock before async operation
-    let store = {
+    let (store, usage) = {
         let state = state.read().await;
-        state.store.clone()
+        (state.store.clone(), Arc::clone(&state.instance_usage))
     };
 
     let instance = store
@@ -229,14 +253,28 @@ pub(crate) async fn get_instance(
     let mut response = InstanceResponse::from(&instance);
 
     // Check actual running status
-    if instance.status == Status::Running && !process::is_running(instance.pid)? {
-        response.status = "crashed".to_string();
-        response.uptime = None;
+    if instance.status == Status::Running {
+        if process::is_running(instance.pid)? {
+            response.resources = sampled_usage(&usage, &instance);
+        } else {
+            response.mark_crashed();
+        }
     }
 
     Ok(Json(response))
 }
 
+/// The health check task's latest sample of `instance`, unless it was
+/// taken from a previous process.
+fn sampled_usage(
+    usage: &DashMap<String, (u32, process::ResourceSample)>,
+    instance: &Instance,
+) -> Option<InstanceResources> {
+    let entry = usage.get(&instance.name)?;
+    let (pid, sample) = *entry;
+    (pid == instance.pid).then(|| sample.into())
+}
+
 /// DELETE /instances/:name - Stop an instance.
 pub(crate) async fn stop_instance(
     State(state): State<SharedState>,
@@ -335,32 +373,14 @@ pub(crate) async fn restart_instance(
     drop(state_guard);
 
     // Restart with same config
-    let spawn_config = SpawnConfig {
-        name: instance.name.clone(),
-        port: instance.port,
-        config_path: instance.config.clone(),
-        working_dir: instance
-            .config
-            .parent()
-            .unwrap_or_else(|| std::path::Path::new("."))
-            .to_path_buf(),
-        hot_reload: false,
-    };
+    let info = process::spawn_instance(&spawn_config_for(&instance))?;
 
-    let info = process::spawn_instance(&spawn_config)?;
-
-    // Update instance state (preserve auto_restart settings)
+    // Update instance state (preserve restart policy and history)
     let updated = Instance {
-        name: instance.name.clone(),
-        port: instance.port,
         pid: info.pid,
         status: Status::Running,
-        config: instance.config.clone(),
         started_at: chrono::Utc::now(),
-        modules: instance.modules.clone(),
-        auto_restart: instance.auto_restart,
-        restart_count: instance.restart_count,
-        last_restart_at: instance.last_restart_at,
+        ..instance.clone()
     };
 
     // Save asynchronously
@@ -388,24 +408,66 @@ pub(crate) async fn restart_instance(
 }
 
 /// GET /instances/:name/logs - Get instance logs.
+///
+/// With `?follow=true`, responds with a Server-Sent Events stream: the last
+/// `lines` lines, then new lines as they are written (across log rotation).
 pub(crate) async fn get_logs(
     Path(name): Path<String>,
     Query(query): Query<LogsQuery>,
-) -> Result<Json<LogsResponse>, AppError> {
+) -> Result<Response, AppError> {
     let log_path =
         crate::daemon::paths::get_log_path(&name).map_err(|e| AppError::Internal(e.to_string()))?;
 
-    if !log_path.exists() {
-        return Ok(Json(LogsResponse {
-            name,
-            lines: vec![],
-        }));
+    let lines_count = query.lines.unwrap_or(50);
+    let lines = if log_path.exists() {
+        process::tail_log(&log_path, lines_count)?
+    } else {
+        vec![]
+    };
+
+    if query.follow {
+        let follower = process::LogFollower::from_end(log_path);
+        return Ok(Sse::new(follow_log_stream(lines, follower))
+            .keep_alive(KeepAlive::default())
+            .into_response());
     }
 
-    let lines_count = query.lines.unwrap_or(50);
-    let lines = process::tail_log(&log_path, lines_count)?;
+    Ok(Json(LogsResponse { name, lines }).into_response())
+}
+
+/// How often a followed log is checked for new lines.
+const LOG_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// SSE stream of `initial` lines followed by lines appended to the log.
+///
+/// Each line is sent as one `data:` event. The stream ends when the client
+/// disconnects or the log becomes unreadable.
+fn follow_log_stream(
+    initial: Vec<String>,
+    follower: process::LogFollower,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    let appended = futures::stream::unfold(follower, |mut follower| async move {
+        loop {
+            tokio::time::sleep(LOG_POLL_INTERVAL).await;
+            match follower.poll() {
+                Ok(lines) if lines.is_empty() => {},
+                Ok(lines) => return Some((futures::stream::iter(lines), follower)),
+                Err(e) => {
+                    tracing::warn!(
+                        path = %follower.path().display(),
+                        error = %e,
+                        "Stopped following log"
+                    );
+                    return None;
+                },
+            }
+        }
+    })
+    .flatten();
 
-    Ok(Json(LogsResponse { name, lines }))
+    futures::stream::iter(initial)
+        .chain(appended)
+        .map(|line| Ok(Event::default().data(line)))
 }
 
 /// GET /health - Health check.
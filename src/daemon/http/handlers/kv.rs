@@ -2,40 +2,179 @@
 //!
 //! Handlers for key-value store operations including listing keys,
 //! getting values, setting values with optional TTL, and deleting keys.
+//!
+//! `GET /kv?watch=true` streams key changes as Server-Sent Events, with the
+//! event type `set` or `delete` and a JSON [`KvWatchEvent`] as data.
+//!
+//! `POST /kv` applies several writes atomically, guarded by the values the
+//! caller expects keys to hold (`409 Conflict` if any has changed).
+//!
+//! `POST /kv-batch/{get,set,delete}` read, write, or delete up to
+//! [`MAX_BATCH_SIZE`] keys in one round trip.
+//!
+//! With `kv_tenancy = "namespace_per_tenant"`, requests must send an
+//! `X-Tenant-Id` header and see only that tenant's keys. Writes that would
+//! take the tenant past its key quota fail with `507 Insufficient Storage`.
 
+use std::convert::Infallible;
 use std::time::Duration;
 
 use axum::{
     Json,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
+use futures::Stream;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::daemon::config::KvTenancy;
+use crate::daemon::services::kv::{KvCommit, KvStore, KvWatch, KvWrite};
 
-use super::super::types::{KvGetResponse, KvListQuery, KvListResponse, KvSetRequest};
+use super::super::types::{
+    KvBatchDeleteResponse, KvBatchGetResponse, KvBatchKeysRequest, KvBatchSetRequest,
+    KvGetResponse, KvListQuery, KvListResponse, KvSetRequest, KvTransactionRequest, KvWatchEvent,
+};
 use super::super::{AppError, SharedState, metrics};
 use super::get_service;
+use super::sql::TENANT_HEADER;
 
 // Generate the get_kv helper using the shared macro
-get_service!(get_kv, kv, crate::daemon::services::kv::KvStore, "KV");
+get_service!(get_kv, kv, KvStore, "KV");
+
+/// Resolve the KV store a request runs against.
+///
+/// With tenant namespaces enabled, the `X-Tenant-Id` header is required and
+/// scopes the request to that tenant.
+pub(crate) async fn resolve_kv(
+    state: &SharedState,
+    headers: &HeaderMap,
+) -> Result<KvStore, AppError> {
+    let kv = get_kv(state).await?;
+    if state.read().await.config.services.kv_tenancy == KvTenancy::Shared {
+        return Ok(kv);
+    }
+
+    let tenant_id = headers
+        .get(TENANT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "{TENANT_HEADER} header is required when KV tenancy is namespace_per_tenant"
+            ))
+        })?;
+    kv.tenant(tenant_id)
+        .map_err(|e| AppError::BadRequest(e.to_string()))
+}
+
+/// Default page size for paginated listings.
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Maximum page size for paginated listings.
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// Maximum number of keys in one batch request.
+const MAX_BATCH_SIZE: usize = 1000;
 
 /// GET /kv - List all keys with optional prefix filter.
+///
+/// With `limit`, `cursor`, or `values`, returns one page of keys in key
+/// order, with a `cursor` for the next one. With `watch`, streams changes
+/// under the prefix until the client disconnects.
 pub(crate) async fn kv_list(
     State(state): State<SharedState>,
+    headers: HeaderMap,
     Query(query): Query<KvListQuery>,
-) -> Result<Json<KvListResponse>, AppError> {
+) -> Result<Response, AppError> {
+    let kv = resolve_kv(&state, &headers).await?;
+    if query.watch {
+        metrics::record_kv_operation("watch");
+        let watch = kv.watch(query.prefix.as_deref().unwrap_or_default());
+        return Ok(Sse::new(watch_stream(watch))
+            .keep_alive(KeepAlive::default())
+            .into_response());
+    }
+
     metrics::record_kv_operation("list");
-    let kv = get_kv(&state).await?;
-    let keys = kv.list_keys(query.prefix.as_deref()).await?;
-    Ok(Json(KvListResponse { keys }))
+    if query.limit.is_none() && query.cursor.is_none() && !query.values {
+        let keys = kv.list_keys(query.prefix.as_deref()).await?;
+        return Ok(Json(KvListResponse {
+            keys,
+            entries: None,
+            cursor: None,
+        })
+        .into_response());
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let page = kv
+        .scan(query.prefix.as_deref(), query.cursor.as_deref(), limit)
+        .await?;
+    let keys = page.entries.iter().map(|(key, _)| key.clone()).collect();
+    let entries = if query.values {
+        let entries = page
+            .entries
+            .into_iter()
+            .map(|(key, bytes)| {
+                let value = String::from_utf8(bytes).map_err(|_| {
+                    AppError::Internal(format!("Value of key '{key}' is not valid UTF-8"))
+                })?;
+                Ok(KvGetResponse { key, value })
+            })
+            .collect::<Result<_, AppError>>()?;
+        Some(entries)
+    } else {
+        None
+    };
+
+    Ok(Json(KvListResponse {
+        keys,
+        entries,
+        cursor: page.cursor,
+    })
+    .into_response())
+}
+
+/// SSE stream of key changes.
+///
+/// A watcher that falls behind gets a `lagged` event with the number of
+/// changes it missed, then continues with newer ones.
+fn watch_stream(watch: KvWatch) -> impl Stream<Item = Result<Event, Infallible>> {
+    futures::stream::unfold(watch, |mut watch| async move {
+        let event = match watch.recv().await {
+            Ok(change) => Event::default()
+                .event(change.kind.as_str())
+                .json_data(KvWatchEvent {
+                    key: change.key,
+                    value: change
+                        .value
+                        .map(|value| String::from_utf8_lossy(&value).into_owned()),
+                    time: change.time.to_rfc3339(),
+                })
+                .unwrap_or_else(|_| Event::default().comment("unserializable event")),
+            Err(RecvError::Lagged(missed)) => {
+                Event::default().event("lagged").data(missed.to_string())
+            },
+            Err(RecvError::Closed) => return None,
+        };
+        Some((Ok(event), watch))
+    })
 }
 
 /// GET /kv/:key - Get a value by key.
 pub(crate) async fn kv_get(
     State(state): State<SharedState>,
+    headers: HeaderMap,
     Path(key): Path<String>,
 ) -> Result<Json<KvGetResponse>, AppError> {
     metrics::record_kv_operation("get");
-    let kv = get_kv(&state).await?;
+    let kv = resolve_kv(&state, &headers).await?;
     let bytes = kv
         .get(&key)
         .await?
@@ -50,11 +189,12 @@ pub(crate) async fn kv_get(
 /// PUT /kv/:key - Set a value with optional TTL.
 pub(crate) async fn kv_set(
     State(state): State<SharedState>,
+    headers: HeaderMap,
     Path(key): Path<String>,
     Json(req): Json<KvSetRequest>,
 ) -> Result<StatusCode, AppError> {
     metrics::record_kv_operation("set");
-    let kv = get_kv(&state).await?;
+    let kv = resolve_kv(&state, &headers).await?;
     let value_bytes = req.value.into_bytes();
     let ttl = req.ttl.map(Duration::from_secs);
 
@@ -66,10 +206,123 @@ pub(crate) async fn kv_set(
 /// DELETE /kv/:key - Delete a key.
 pub(crate) async fn kv_delete(
     State(state): State<SharedState>,
+    headers: HeaderMap,
     Path(key): Path<String>,
 ) -> Result<StatusCode, AppError> {
     metrics::record_kv_operation("delete");
-    let kv = get_kv(&state).await?;
+    let kv = resolve_kv(&state, &headers).await?;
     kv.delete(&key).await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// POST /kv - Apply a transaction.
+pub(crate) async fn kv_transaction(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(req): Json<KvTransactionRequest>,
+) -> Result<StatusCode, AppError> {
+    metrics::record_kv_operation("transaction");
+    let kv = resolve_kv(&state, &headers).await?;
+    let commit = KvCommit {
+        reads: req
+            .expect
+            .into_iter()
+            .map(|(key, value)| (key, value.map(String::into_bytes)))
+            .collect(),
+        writes: req
+            .writes
+            .into_iter()
+            .map(|write| {
+                let op = match write.value {
+                    Some(value) => KvWrite::Set {
+                        value: value.into_bytes(),
+                        ttl: write.ttl.map(Duration::from_secs),
+                    },
+                    None => KvWrite::Delete,
+                };
+                (write.key, op)
+            })
+            .collect(),
+    };
+
+    if kv.commit(commit).await? {
+        Ok(StatusCode::OK)
+    } else {
+        Err(AppError::Conflict(
+            "Transaction conflict: an expected value has changed".to_string(),
+        ))
+    }
+}
+
+/// Rejects batches over [`MAX_BATCH_SIZE`] keys.
+fn check_batch_size(len: usize) -> Result<(), AppError> {
+    if len > MAX_BATCH_SIZE {
+        return Err(AppError::BadRequest(format!(
+            "Batch of {len} keys exceeds the maximum of {MAX_BATCH_SIZE}"
+        )));
+    }
+    Ok(())
+}
+
+/// POST /kv-batch/get - Get several values.
+pub(crate) async fn kv_batch_get(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(req): Json<KvBatchKeysRequest>,
+) -> Result<Json<KvBatchGetResponse>, AppError> {
+    metrics::record_kv_operation("batch_get");
+    check_batch_size(req.keys.len())?;
+    let kv = resolve_kv(&state, &headers).await?;
+    let values = kv.get_many(&req.keys).await?;
+
+    let values = req
+        .keys
+        .into_iter()
+        .zip(values)
+        .map(|(key, bytes)| {
+            let value = bytes.map(String::from_utf8).transpose().map_err(|_| {
+                AppError::Internal(format!("Value of key '{key}' is not valid UTF-8"))
+            })?;
+            Ok((key, value))
+        })
+        .collect::<Result<_, AppError>>()?;
+
+    Ok(Json(KvBatchGetResponse { values }))
+}
+
+/// POST /kv-batch/set - Set several values atomically.
+pub(crate) async fn kv_batch_set(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(req): Json<KvBatchSetRequest>,
+) -> Result<StatusCode, AppError> {
+    metrics::record_kv_operation("batch_set");
+    check_batch_size(req.entries.len())?;
+    let kv = resolve_kv(&state, &headers).await?;
+    let entries = req
+        .entries
+        .into_iter()
+        .map(|(key, value)| (key, value.into_bytes()))
+        .collect();
+
+    kv.set_many(entries, req.ttl.map(Duration::from_secs))
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// POST /kv-batch/delete - Delete several keys.
+pub(crate) async fn kv_batch_delete(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(req): Json<KvBatchKeysRequest>,
+) -> Result<Json<KvBatchDeleteResponse>, AppError> {
+    metrics::record_kv_operation("batch_delete");
+    check_batch_size(req.keys.len())?;
+    let kv = resolve_kv(&state, &headers).await?;
+    let deleted = kv.delete_many(&req.keys).await?;
+
+    Ok(Json(KvBatchDeleteResponse {
+        deleted: deleted.into_iter().filter(|deleted| *deleted).count(),
+    }))
+}
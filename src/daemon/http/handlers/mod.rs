@@ -14,9 +14,17 @@ pub(crate) use instances::{
     get_instance, get_logs, health, list_instances, restart_instance, start_instance,
     stop_instance, version,
 };
-pub(crate) use kv::{kv_delete, kv_get, kv_list, kv_set};
-pub(crate) use sql::{sql_batch, sql_execute, sql_query};
-pub(crate) use storage::{storage_delete, storage_get, storage_head, storage_list, storage_put};
+pub(crate) use kv::{
+    kv_batch_delete, kv_batch_get, kv_batch_set, kv_delete, kv_get, kv_list, kv_set, kv_transaction,
+};
+pub(crate) use sql::{
+    OpenSqlTransaction, sql_batch, sql_begin, sql_commit, sql_execute, sql_query, sql_rollback,
+    sql_stream, sql_transaction_execute, sql_transaction_query,
+};
+pub(crate) use storage::{
+    storage_delete, storage_get, storage_head, storage_list, storage_presign, storage_put,
+    storage_usage,
+};
 
 /// Macro to generate service availability helper functions.
 ///
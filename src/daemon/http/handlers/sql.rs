@@ -1,19 +1,39 @@
 //! SQL service handlers.
 //!
 //! Handlers for SQL database operations including queries, executes, and batch operations.
+//!
+//! `POST /sql/stream` runs a query like `/sql/query` but streams the result
+//! as NDJSON: a `{"columns": [...]}` line, then one JSON array of values per
+//! row. An error after the first row ends the stream with an
+//! `{"error": "..."}` line.
+//!
+//! `POST /sql/transactions` opens an explicit transaction and returns its
+//! ID. Statements sent to `/sql/transactions/:id/query` and `/execute` run
+//! inside it until `/commit` or `/rollback`. A transaction left idle for
+//! [`DEFAULT_IDLE_TIMEOUT`](crate::daemon::services::sql::DEFAULT_IDLE_TIMEOUT)
+//! is rolled back. With tenant isolation, only
+//! requests for the tenant that opened a transaction can use it.
 
+use std::convert::Infallible;
 use std::time::Instant;
 
-use axum::{Json, extract::State};
+use axum::{
+    Json,
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
 
 use crate::daemon::metrics;
-use crate::daemon::services::sql::{SqlService, Value as SqlValue};
+use crate::daemon::services::sql::{SqlService, SqlTransaction, Value as SqlValue};
 
 use super::super::{
     AppError, SharedState,
     types::{
         SqlBatchRequest, SqlBatchResponse, SqlExecuteRequest, SqlExecuteResponse, SqlQueryRequest,
-        SqlQueryResponse,
+        SqlQueryResponse, SqlTransactionResponse,
     },
 };
 use super::get_service;
@@ -21,6 +41,71 @@ use super::get_service;
 // Generate the get_sql helper using the shared macro
 get_service!(get_sql, sql, SqlService, "SQL");
 
+/// Header identifying the tenant a SQL request is issued for.
+pub(crate) const TENANT_HEADER: &str = "X-Tenant-Id";
+
+/// Resolve the SQL service a request should run against.
+///
+/// With tenant isolation enabled, the `X-Tenant-Id` header is required and
+/// selects the tenant's own database. Otherwise the shared database is used.
+pub(crate) async fn resolve_sql(
+    state: &SharedState,
+    headers: &HeaderMap,
+) -> Result<SqlService, AppError> {
+    let tenants = state.read().await.sql_tenants.clone();
+    let Some(tenants) = tenants else {
+        return get_sql(state).await;
+    };
+
+    let tenant_id = headers
+        .get(TENANT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "{TENANT_HEADER} header is required when SQL tenancy is database_per_tenant"
+            ))
+        })?;
+
+    tenants
+        .for_tenant(tenant_id)
+        .map_err(|e| AppError::BadRequest(e.to_string()))
+}
+
+/// Maximum number of explicit transactions open at once.
+const MAX_OPEN_TRANSACTIONS: usize = 64;
+
+/// An explicit transaction opened over HTTP.
+pub(crate) struct OpenSqlTransaction {
+    /// Tenant the transaction belongs to, when tenant isolation is enabled.
+    tenant_id: Option<String>,
+    txn: SqlTransaction,
+}
+
+/// Tenant a request is issued for, when tenant isolation is enabled.
+async fn request_tenant(state: &SharedState, headers: &HeaderMap) -> Option<String> {
+    state.read().await.sql_tenants.as_ref()?;
+    headers
+        .get(TENANT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Look up an open transaction the request is allowed to use.
+async fn open_transaction(
+    state: &SharedState,
+    headers: &HeaderMap,
+    id: &str,
+) -> Result<SqlTransaction, AppError> {
+    let tenant_id = request_tenant(state, headers).await;
+    let state = state.read().await;
+    let txn = state
+        .sql_transactions
+        .get(id)
+        .filter(|open| open.tenant_id == tenant_id && !open.txn.is_finished())
+        .map(|open| open.txn.clone());
+    txn.ok_or_else(|| AppError::NotFound(format!("SQL transaction '{id}' not found")))
+}
+
 // =============================================================================
 // Conversion Helpers
 // =============================================================================
@@ -68,10 +153,11 @@ fn sql_to_json_value(v: &SqlValue) -> serde_json::Value {
 /// POST /sql/query - Execute a SELECT query.
 pub(crate) async fn sql_query(
     State(state): State<SharedState>,
+    headers: HeaderMap,
     Json(req): Json<SqlQueryRequest>,
 ) -> Result<Json<SqlQueryResponse>, AppError> {
     let start = Instant::now();
-    let sql = get_sql(&state).await?;
+    let sql = resolve_sql(&state, &headers).await?;
     let params: Vec<SqlValue> = req.params.iter().map(json_to_sql_value).collect();
 
     let rows = sql.query(&req.sql, &params).await?;
@@ -91,13 +177,52 @@ pub(crate) async fn sql_query(
     }))
 }
 
+/// POST /sql/stream - Execute a SELECT query, streaming rows as NDJSON.
+pub(crate) async fn sql_stream(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(req): Json<SqlQueryRequest>,
+) -> Result<Response, AppError> {
+    let start = Instant::now();
+    let sql = resolve_sql(&state, &headers).await?;
+    let params: Vec<SqlValue> = req.params.iter().map(json_to_sql_value).collect();
+
+    let rows = sql.query_stream(&req.sql, &params).await?;
+    metrics::record_sql_query("stream", start.elapsed().as_secs_f64());
+
+    let columns = ndjson_line(&serde_json::json!({ "columns": rows.columns() }));
+    let lines = rows.map(|row| match row {
+        Ok(row) => ndjson_line(&serde_json::Value::Array(
+            row.values.iter().map(sql_to_json_value).collect(),
+        )),
+        Err(e) => ndjson_line(&serde_json::json!({ "error": e.to_string() })),
+    });
+    let body = futures::stream::once(async { columns })
+        .chain(lines)
+        .map(Ok::<_, Infallible>);
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(body),
+    )
+        .into_response())
+}
+
+/// Serialize one NDJSON line.
+fn ndjson_line(value: &serde_json::Value) -> Bytes {
+    let mut line = value.to_string().into_bytes();
+    line.push(b'\n');
+    Bytes::from(line)
+}
+
 /// POST /sql/execute - Execute an INSERT/UPDATE/DELETE statement.
 pub(crate) async fn sql_execute(
     State(state): State<SharedState>,
+    headers: HeaderMap,
     Json(req): Json<SqlExecuteRequest>,
 ) -> Result<Json<SqlExecuteResponse>, AppError> {
     let start = Instant::now();
-    let sql = get_sql(&state).await?;
+    let sql = resolve_sql(&state, &headers).await?;
     let params: Vec<SqlValue> = req.params.iter().map(json_to_sql_value).collect();
 
     let rows_affected = sql.execute(&req.sql, &params).await?;
@@ -114,10 +239,11 @@ pub(crate) async fn sql_execute(
 /// all changes are rolled back. This ensures data consistency for related operations.
 pub(crate) async fn sql_batch(
     State(state): State<SharedState>,
+    headers: HeaderMap,
     Json(req): Json<SqlBatchRequest>,
 ) -> Result<Json<SqlBatchResponse>, AppError> {
     let start = Instant::now();
-    let sql = get_sql(&state).await?;
+    let sql = resolve_sql(&state, &headers).await?;
 
     // Convert statements to (sql, params) tuples for atomic execution
     let statements: Vec<(String, Vec<SqlValue>)> = req
@@ -142,3 +268,102 @@ pub(crate) async fn sql_batch(
 
     Ok(Json(SqlBatchResponse { results }))
 }
+
+/// POST /sql/transactions - Begin an explicit transaction.
+pub(crate) async fn sql_begin(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<SqlTransactionResponse>), AppError> {
+    let sql = resolve_sql(&state, &headers).await?;
+    let tenant_id = request_tenant(&state, &headers).await;
+    {
+        let state = state.read().await;
+        state
+            .sql_transactions
+            .retain(|_, open| !open.txn.is_finished());
+        if state.sql_transactions.len() >= MAX_OPEN_TRANSACTIONS {
+            return Err(AppError::ServiceUnavailable(format!(
+                "Too many open SQL transactions (maximum {MAX_OPEN_TRANSACTIONS})"
+            )));
+        }
+    }
+
+    let txn = sql.begin().await?;
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    state
+        .read()
+        .await
+        .sql_transactions
+        .insert(id.clone(), OpenSqlTransaction { tenant_id, txn });
+
+    Ok((StatusCode::CREATED, Json(SqlTransactionResponse { id })))
+}
+
+/// POST /sql/transactions/:id/query - Execute a SELECT query in a transaction.
+pub(crate) async fn sql_transaction_query(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<SqlQueryRequest>,
+) -> Result<Json<SqlQueryResponse>, AppError> {
+    let start = Instant::now();
+    let txn = open_transaction(&state, &headers, &id).await?;
+    let params: Vec<SqlValue> = req.params.iter().map(json_to_sql_value).collect();
+
+    let rows = txn.query(&req.sql, &params).await?;
+    metrics::record_sql_query("query", start.elapsed().as_secs_f64());
+
+    let columns = rows.first().map(|r| r.columns.clone()).unwrap_or_default();
+    let json_rows = rows
+        .iter()
+        .map(|row| row.values.iter().map(sql_to_json_value).collect())
+        .collect();
+
+    Ok(Json(SqlQueryResponse {
+        columns,
+        rows: json_rows,
+    }))
+}
+
+/// POST /sql/transactions/:id/execute - Execute a statement in a transaction.
+pub(crate) async fn sql_transaction_execute(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<SqlExecuteRequest>,
+) -> Result<Json<SqlExecuteResponse>, AppError> {
+    let start = Instant::now();
+    let txn = open_transaction(&state, &headers, &id).await?;
+    let params: Vec<SqlValue> = req.params.iter().map(json_to_sql_value).collect();
+
+    let rows_affected = txn.execute(&req.sql, &params).await?;
+    metrics::record_sql_query("execute", start.elapsed().as_secs_f64());
+
+    Ok(Json(SqlExecuteResponse {
+        rows_affected: rows_affected as u64,
+    }))
+}
+
+/// POST /sql/transactions/:id/commit - Commit a transaction.
+pub(crate) async fn sql_commit(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AppError> {
+    let txn = open_transaction(&state, &headers, &id).await?;
+    state.read().await.sql_transactions.remove(&id);
+    txn.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /sql/transactions/:id/rollback - Roll a transaction back.
+pub(crate) async fn sql_rollback(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AppError> {
+    let txn = open_transaction(&state, &headers, &id).await?;
+    state.read().await.sql_transactions.remove(&id);
+    txn.rollback().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
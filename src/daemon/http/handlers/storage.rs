@@ -1,35 +1,165 @@
 //! Storage service handlers.
 //!
-//! Handlers for object storage operations.
+//! Handlers for object storage operations. Object bodies are streamed in
+//! both directions, so transfers never hold a whole object in memory.
+//!
+//! `GET /storage?watch=true` streams object changes as Server-Sent Events,
+//! with the S3-style event type (`ObjectCreated:Put`, `ObjectUpdated:Put`,
+//! `ObjectRemoved:Delete`) and a JSON `StorageEvent` as data.
+//!
+//! GET and PUT also accept presigned URLs (see `POST /presign/*path`), which
+//! are authorized by their signature instead of the API key.
+//!
+//! Responses carry the object's `ETag`. GET and HEAD honor `If-None-Match`
+//! (`304 Not Modified`) and `If-Match`; PUT honors both for optimistic
+//! concurrency (`412 Precondition Failed` if the object changed).
+//!
+//! With `storage_tenancy = "namespace_per_tenant"`, requests must send an
+//! `X-Tenant-Id` header and see only that tenant's objects. Writes beyond
+//! the tenant's quota fail with `507 Insufficient Storage`, including
+//! presigned uploads to a tenant's objects.
 
 use axum::{
     Json,
-    body::Bytes,
+    body::Body,
     extract::{Path, Query, State},
-    http::{StatusCode, header},
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
 };
+use chrono::Utc;
+use futures::{Stream, StreamExt};
+use std::convert::Infallible;
+use std::io;
+use std::sync::LazyLock;
+use tokio::sync::broadcast::error::RecvError;
 
-use crate::daemon::services::storage::StorageService;
+use crate::daemon::config::StorageTenancy;
+use crate::daemon::services::storage::{
+    ObjectMeta, ObjectStream, PreconditionFailed, Preconditions, PresignMethod, Presigner,
+    ReadPrecondition, StorageQuotaExceeded, StorageService, StorageWatch, TENANTS_PREFIX,
+};
 
-use super::super::types::{StorageListQuery, StorageListResponse, StorageObjectInfo};
+use super::super::types::{
+    PresignQuery, PresignRequest, PresignResponse, StorageListQuery, StorageListResponse,
+    StorageObjectInfo, StorageUsageResponse, TenantStorageUsage,
+};
 use super::super::{AppError, SharedState, metrics};
 use super::get_service;
+use super::sql::TENANT_HEADER;
 
 // Generate the get_storage helper using the shared macro
 get_service!(get_storage, storage, StorageService, "Storage");
 
+/// Resolve the storage a request runs against.
+///
+/// With tenant namespaces enabled, the `X-Tenant-Id` header is required and
+/// scopes the request to that tenant.
+pub(crate) async fn resolve_storage(
+    state: &SharedState,
+    headers: &HeaderMap,
+) -> Result<StorageService, AppError> {
+    let storage = get_storage(state).await?;
+    let tenancy = state.read().await.config.services.storage_tenancy;
+    if tenancy == StorageTenancy::Shared {
+        return Ok(storage);
+    }
+
+    let tenant_id = headers
+        .get(TENANT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "{TENANT_HEADER} header is required when storage tenancy is namespace_per_tenant"
+            ))
+        })?;
+    storage
+        .tenant(tenant_id)
+        .map_err(|e| AppError::BadRequest(e.to_string()))
+}
+
+/// Resolve the storage and path a GET or PUT runs against.
+///
+/// Presigned requests were signed for a full path and carry no tenant
+/// header. With tenant namespaces enabled, a path under `tenants/{id}/` runs
+/// against that tenant's view, so its quota applies to signed uploads too.
+async fn resolve_object(
+    state: &SharedState,
+    headers: &HeaderMap,
+    presigned: bool,
+    path: String,
+) -> Result<(StorageService, String), AppError> {
+    if !presigned {
+        return Ok((resolve_storage(state, headers).await?, path));
+    }
+    let storage = get_storage(state).await?;
+    let tenancy = state.read().await.config.services.storage_tenancy;
+    let tenant_path = path
+        .strip_prefix(TENANTS_PREFIX)
+        .and_then(|rest| rest.split_once('/'));
+    match tenant_path {
+        Some((tenant_id, relative)) if tenancy == StorageTenancy::NamespacePerTenant => {
+            let storage = storage
+                .tenant(tenant_id)
+                .map_err(|e| AppError::BadRequest(e.to_string()))?;
+            Ok((storage, relative.to_string()))
+        },
+        _ => Ok((storage, path)),
+    }
+}
+
+/// Maximum size of an uploaded object.
+///
+/// Uploads are streamed, so this is independent of the API body limit.
+pub(crate) const MAX_OBJECT_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Default lifetime of presigned URLs (15 minutes).
+const DEFAULT_PRESIGN_SECS: u64 = 15 * 60;
+
+/// Maximum lifetime of presigned URLs (7 days).
+const MAX_PRESIGN_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Signer for presigned URLs.
+///
+/// Keyed by `MIK_STORAGE_SIGNING_KEY`. Without it a random key is used, and
+/// links stop working when the daemon restarts.
+static PRESIGNER: LazyLock<Presigner> =
+    LazyLock::new(|| match std::env::var("MIK_STORAGE_SIGNING_KEY") {
+        Ok(key) if !key.is_empty() => Presigner::new(key),
+        _ => Presigner::random(),
+    });
+
 /// GET /storage - List objects with optional prefix.
+///
+/// With `?expired=true`, lists only objects that lifecycle rules would delete
+/// now, without deleting them. With `?watch=true`, streams changes under the
+/// prefix until the client disconnects.
 pub(crate) async fn storage_list(
     State(state): State<SharedState>,
     Query(query): Query<StorageListQuery>,
-) -> Result<Json<StorageListResponse>, AppError> {
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let storage = resolve_storage(&state, &headers).await?;
+    if query.watch {
+        metrics::record_storage_operation("watch", None);
+        let watch = storage.watch(query.prefix.as_deref().unwrap_or_default());
+        return Ok(Sse::new(watch_stream(watch))
+            .keep_alive(KeepAlive::default())
+            .into_response());
+    }
+
     metrics::record_storage_operation("list", None);
-    let storage = get_storage(&state).await?;
-    let mut objects: Vec<StorageObjectInfo> = storage
-        .list_objects(query.prefix.as_deref())
-        .await?
+    let listed = if query.expired {
+        storage.expired_objects(chrono::Utc::now()).await?
+    } else {
+        storage.list_objects(query.prefix.as_deref()).await?
+    };
+    let prefix = query.prefix.as_deref().unwrap_or_default();
+    let mut objects: Vec<StorageObjectInfo> = listed
         .into_iter()
+        .filter(|meta| meta.path.starts_with(prefix))
         .map(StorageObjectInfo::from)
         .collect();
 
@@ -38,55 +168,252 @@ pub(crate) async fn storage_list(
         objects.truncate(limit);
     }
 
-    Ok(Json(StorageListResponse { objects }))
+    Ok(Json(StorageListResponse { objects }).into_response())
+}
+
+/// Server-Sent Events for the changes seen by `watch`.
+///
+/// A watcher that falls behind gets a `lagged` event with the number of
+/// changes it missed.
+fn watch_stream(watch: StorageWatch) -> impl Stream<Item = Result<Event, Infallible>> {
+    futures::stream::unfold(watch, |mut watch| async move {
+        let event = match watch.recv().await {
+            Ok(change) => Event::default()
+                .event(change.kind.event_name())
+                .json_data(&change)
+                .unwrap_or_else(|_| Event::default().comment("unserializable event")),
+            Err(RecvError::Lagged(missed)) => {
+                Event::default().event("lagged").data(missed.to_string())
+            },
+            Err(RecvError::Closed) => return None,
+        };
+        Some((Ok(event), watch))
+    })
 }
 
 /// GET /storage/*path - Get an object.
 pub(crate) async fn storage_get(
     State(state): State<SharedState>,
     Path(path): Path<String>,
-) -> Result<impl IntoResponse, AppError> {
-    let storage = get_storage(&state).await?;
-    let (data, meta) = storage
-        .get_object(&path)
+    Query(presign): Query<PresignQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    check_presigned(&presign, PresignMethod::Get, &path)?;
+    let (storage, path) =
+        resolve_object(&state, &headers, presign.signature.is_some(), path).await?;
+    let (stream, meta) = storage
+        .get_object_stream(&path)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Object '{path}' not found")))?;
+    if let Some(response) = check_read(&headers, &meta)? {
+        return Ok(response);
+    }
 
-    metrics::record_storage_operation("get", Some(data.len() as u64));
+    metrics::record_storage_operation("get", Some(meta.size));
     Ok((
         StatusCode::OK,
-        [(header::CONTENT_TYPE, meta.content_type)],
-        data,
-    ))
+        object_headers(&meta),
+        Body::from_stream(stream),
+    )
+        .into_response())
 }
 
 /// PUT /storage/*path - Store an object.
 pub(crate) async fn storage_put(
     State(state): State<SharedState>,
     Path(path): Path<String>,
-    headers: axum::http::HeaderMap,
-    body: Bytes,
-) -> Result<StatusCode, AppError> {
-    metrics::record_storage_operation("put", Some(body.len() as u64));
-    let storage = get_storage(&state).await?;
+    Query(presign): Query<PresignQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Response, AppError> {
+    check_presigned(&presign, PresignMethod::Put, &path)?;
+    let (storage, path) =
+        resolve_object(&state, &headers, presign.signature.is_some(), path).await?;
+    let conditions = preconditions(&headers);
     let content_type = headers
         .get(header::CONTENT_TYPE)
         .and_then(|v| v.to_str().ok())
         .map(String::from);
 
-    storage
-        .put_object(&path, &body, content_type.as_deref())
-        .await?;
-    Ok(StatusCode::CREATED)
+    let declared_size = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared_size.is_some_and(|size| size > MAX_OBJECT_SIZE) {
+        return Err(too_large());
+    }
+
+    let meta = storage
+        .put_object_stream_if(
+            &path,
+            limited_body(body),
+            content_type.as_deref(),
+            &conditions,
+        )
+        .await
+        .map_err(|e| {
+            if let Some(failed) = e.downcast_ref::<PreconditionFailed>() {
+                return AppError::PreconditionFailed(failed.to_string());
+            }
+            if let Some(exceeded) = e.downcast_ref::<StorageQuotaExceeded>() {
+                return AppError::InsufficientStorage(exceeded.to_string());
+            }
+            let exceeded = e
+                .chain()
+                .filter_map(|cause| cause.downcast_ref::<io::Error>())
+                .any(|cause| cause.kind() == io::ErrorKind::FileTooLarge);
+            if exceeded { too_large() } else { e.into() }
+        })?;
+
+    metrics::record_storage_operation("put", Some(meta.size));
+    Ok((StatusCode::CREATED, etag_headers(&meta)).into_response())
+}
+
+/// Preconditions from the `If-Match` / `If-None-Match` headers.
+pub(crate) fn preconditions(headers: &HeaderMap) -> Preconditions {
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    Preconditions::from_headers(header(header::IF_MATCH), header(header::IF_NONE_MATCH))
+}
+
+/// Quoted `ETag` header value of an object, if its content hash is known.
+pub(crate) fn etag_header(meta: &ObjectMeta) -> Option<String> {
+    meta.etag.as_ref().map(|etag| format!("\"{etag}\""))
+}
+
+/// Evaluates read preconditions, returning the response to send instead of
+/// the object if they short-circuit the request.
+fn check_read(headers: &HeaderMap, meta: &ObjectMeta) -> Result<Option<Response>, AppError> {
+    match preconditions(headers).check_read(meta) {
+        ReadPrecondition::Proceed => Ok(None),
+        ReadPrecondition::NotModified => Ok(Some(
+            (StatusCode::NOT_MODIFIED, etag_headers(meta)).into_response(),
+        )),
+        ReadPrecondition::Failed => Err(AppError::PreconditionFailed(format!(
+            "Object '{}' does not match If-Match",
+            meta.path
+        ))),
+    }
+}
+
+/// The `ETag` header of an object, if it has one.
+fn etag_headers(meta: &ObjectMeta) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(Ok(etag)) = etag_header(meta).map(|etag| etag.parse()) {
+        headers.insert(header::ETAG, etag);
+    }
+    headers
+}
+
+/// Content type, length, and ETag headers of an object.
+fn object_headers(meta: &ObjectMeta) -> HeaderMap {
+    let mut headers = etag_headers(meta);
+    if let Ok(content_type) = meta.content_type.parse() {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(header::CONTENT_LENGTH, meta.size.into());
+    headers
+}
+
+/// Request body as an object stream that fails once it exceeds
+/// [`MAX_OBJECT_SIZE`] (for chunked uploads without a length).
+pub(crate) fn limited_body(body: Body) -> ObjectStream {
+    let mut received = 0u64;
+    Box::pin(body.into_data_stream().map(move |chunk| {
+        let chunk = chunk.map_err(io::Error::other)?;
+        received += chunk.len() as u64;
+        if received > MAX_OBJECT_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::FileTooLarge,
+                "object exceeds maximum size",
+            ));
+        }
+        Ok(chunk)
+    }))
+}
+
+fn too_large() -> AppError {
+    AppError::PayloadTooLarge(format!(
+        "Object exceeds maximum size of {MAX_OBJECT_SIZE} bytes"
+    ))
+}
+
+/// POST /presign/*path - Create a presigned GET or PUT URL for an object.
+///
+/// Tenant links are signed for the object's full path under `tenants/`.
+pub(crate) async fn storage_presign(
+    State(state): State<SharedState>,
+    Path(path): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<PresignRequest>,
+) -> Result<Json<PresignResponse>, AppError> {
+    metrics::record_storage_operation("presign", None);
+    // A link is useless if the storage service is disabled
+    let storage = resolve_storage(&state, &headers).await?;
+    let path = match storage.tenant_id() {
+        Some(tenant_id) => format!("{TENANTS_PREFIX}{tenant_id}/{path}"),
+        None => path,
+    };
+
+    let lifetime = request.expires_in_secs.unwrap_or(DEFAULT_PRESIGN_SECS);
+    if lifetime == 0 || lifetime > MAX_PRESIGN_SECS {
+        return Err(AppError::BadRequest(format!(
+            "expires_in_secs must be between 1 and {MAX_PRESIGN_SECS}"
+        )));
+    }
+
+    #[allow(clippy::cast_possible_wrap)] // bounded by MAX_PRESIGN_SECS
+    let expires_at = Utc::now() + chrono::Duration::seconds(lifetime as i64);
+    let expires = expires_at.timestamp();
+    let signature = PRESIGNER.sign(request.method, &path, expires);
+
+    Ok(Json(PresignResponse {
+        url: presigned_url(&path, expires, &signature),
+        method: request.method,
+        expires_at: expires_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    }))
+}
+
+/// Verifies presigned URL parameters, if the request carries any.
+///
+/// Signed requests skip API key authentication, so a present but invalid
+/// signature is always rejected.
+fn check_presigned(
+    query: &PresignQuery,
+    method: PresignMethod,
+    path: &str,
+) -> Result<(), AppError> {
+    let Some(signature) = &query.signature else {
+        return Ok(());
+    };
+    let expires = query
+        .expires
+        .ok_or_else(|| AppError::Forbidden("Presigned URL is missing 'expires'".to_string()))?;
+    PRESIGNER
+        .verify(method, path, expires, signature, Utc::now().timestamp())
+        .map_err(|e| AppError::Forbidden(e.to_string()))
+}
+
+/// Relative URL of a presigned object link, with each path segment encoded.
+fn presigned_url(path: &str, expires: i64, signature: &str) -> String {
+    let mut url = url::Url::parse("http://localhost/storage/").expect("static URL is valid");
+    url.path_segments_mut()
+        .expect("http URLs have path segments")
+        .pop_if_empty()
+        .extend(path.split('/'));
+    url.query_pairs_mut()
+        .append_pair("expires", &expires.to_string())
+        .append_pair("signature", signature);
+    format!("{}?{}", url.path(), url.query().unwrap_or_default())
 }
 
 /// DELETE /storage/*path - Delete an object.
 pub(crate) async fn storage_delete(
     State(state): State<SharedState>,
     Path(path): Path<String>,
+    headers: HeaderMap,
 ) -> Result<StatusCode, AppError> {
     metrics::record_storage_operation("delete", None);
-    let storage = get_storage(&state).await?;
+    let storage = resolve_storage(&state, &headers).await?;
     storage.delete_object(&path).await?;
     Ok(StatusCode::NO_CONTENT)
 }
@@ -95,19 +422,46 @@ pub(crate) async fn storage_delete(
 pub(crate) async fn storage_head(
     State(state): State<SharedState>,
     Path(path): Path<String>,
-) -> Result<impl IntoResponse, AppError> {
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
     metrics::record_storage_operation("head", None);
-    let storage = get_storage(&state).await?;
+    let storage = resolve_storage(&state, &headers).await?;
     let meta = storage
         .head_object(&path)
         .await?
         .ok_or_else(|| AppError::NotFound(format!("Object '{path}' not found")))?;
+    if let Some(response) = check_read(&headers, &meta)? {
+        return Ok(response);
+    }
 
-    Ok((
-        StatusCode::OK,
-        [
-            (header::CONTENT_TYPE, meta.content_type),
-            (header::CONTENT_LENGTH, meta.size.to_string()),
-        ],
-    ))
+    Ok((StatusCode::OK, object_headers(&meta)).into_response())
+}
+
+/// GET /storage-usage - Objects, bytes, and quota per tenant.
+///
+/// With an `X-Tenant-Id` header, only that tenant is reported.
+pub(crate) async fn storage_usage(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<Json<StorageUsageResponse>, AppError> {
+    metrics::record_storage_operation("usage", None);
+    let mut storage = get_storage(&state).await?;
+    if let Some(tenant_id) = headers.get(TENANT_HEADER).and_then(|v| v.to_str().ok()) {
+        storage = storage
+            .tenant(tenant_id)
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    }
+
+    let tenants = storage
+        .tenant_usage()
+        .await?
+        .into_iter()
+        .map(|(tenant_id, usage)| TenantStorageUsage {
+            quota: storage.tenant_quota(&tenant_id),
+            tenant_id,
+            objects: usage.objects,
+            bytes: usage.bytes,
+        })
+        .collect();
+    Ok(Json(StorageUsageResponse { tenants }))
 }
@@ -11,25 +11,53 @@
 //! - `GET /instances/:name` - Get instance details
 //! - `DELETE /instances/:name` - Stop instance
 //! - `POST /instances/:name/restart` - Restart instance
-//! - `GET /instances/:name/logs` - Get instance logs
+//! - `GET /instances/:name/logs` - Get instance logs (`?follow=true` streams SSE)
 //!
 //! ### KV Service (`/kv`)
 //! - `GET /kv/:key` - Get value
 //! - `PUT /kv/:key` - Set value (with optional TTL)
 //! - `DELETE /kv/:key` - Delete key
-//! - `GET /kv` - List keys (with optional prefix)
+//! - `GET /kv` - List keys (with optional prefix; `?watch=true` streams
+//!   changes as SSE)
+//! - `POST /kv` - Apply several writes atomically
+//! - `POST /kv-batch/get` - Get several values
+//! - `POST /kv-batch/set` - Set several values atomically (with optional TTL)
+//! - `POST /kv-batch/delete` - Delete several keys
+//!
+//! With `kv_tenancy = "namespace_per_tenant"`, KV requests must send an
+//! `X-Tenant-Id` header and are confined to `tenants/{tenant_id}/`.
 //!
 //! ### SQL Service (`/sql`)
 //! - `POST /sql/query` - Execute SELECT query
+//! - `POST /sql/stream` - Execute SELECT query, streaming rows as NDJSON
 //! - `POST /sql/execute` - Execute INSERT/UPDATE/DELETE
 //! - `POST /sql/batch` - Execute batch of statements
+//! - `POST /sql/transactions` - Begin a transaction (returns its ID)
+//! - `POST /sql/transactions/:id/query` - Execute SELECT query in a transaction
+//! - `POST /sql/transactions/:id/execute` - Execute statement in a transaction
+//! - `POST /sql/transactions/:id/commit` - Commit a transaction
+//! - `POST /sql/transactions/:id/rollback` - Roll back a transaction
+//!
+//! With `sql_tenancy = "database_per_tenant"`, SQL requests must send an
+//! `X-Tenant-Id` header and run against that tenant's own database.
 //!
 //! ### Storage Service (`/storage`)
 //! - `GET /storage/*path` - Get object
 //! - `PUT /storage/*path` - Put object
 //! - `DELETE /storage/*path` - Delete object
 //! - `HEAD /storage/*path` - Get object metadata
-//! - `GET /storage` - List objects (with optional prefix)
+//! - `GET /storage` - List objects (with optional prefix; `?watch=true`
+//!   streams changes as SSE)
+//! - `POST /presign/*path` - Create a presigned GET/PUT URL for an object
+//! - `GET /storage-usage` - Objects, bytes, and quota per tenant
+//!
+//! With `storage_tenancy = "namespace_per_tenant"`, storage requests must
+//! send an `X-Tenant-Id` header and are confined to `tenants/{tenant_id}/`.
+//! The S3 API is not tenant-scoped.
+//!
+//! With `[services.s3]` configured, storage is also served over an
+//! S3-compatible API on a separate port (see [`s3`]). With `[daemon.grpc]`
+//! configured, the API is also served over gRPC (`grpc` feature).
 //!
 //! ### Cron Scheduler (`/cron`)
 //! - `GET /cron` - List all scheduled jobs
@@ -46,12 +74,15 @@
 //! ### System
 //! - `GET /health` - Health check
 //! - `GET /version` - Version info
+//! - `GET /openapi.json` - OpenAPI document of this API (see [`openapi`])
+//!
+//! [`client::DaemonClient`] is a typed client for the API.
 //!
 //! ## Authentication
 //!
 //! API key authentication is optional. To enable it, set the `MIK_API_KEY`
-//! environment variable. When enabled, all requests (except `/health` and
-//! `/metrics`) must include an `X-API-Key` header with the matching key.
+//! environment variable. When enabled, all requests (except `/health`,
+//! `/metrics`, and `/openapi.json`) must include an `X-API-Key` header with the matching key.
 //!
 //! ```bash
 //! # Enable API key authentication
@@ -65,34 +96,48 @@
 //! Exempt endpoints (for monitoring/health checks):
 //! - `/health` - Always accessible
 //! - `/metrics` - Always accessible for Prometheus scraping
+//! - `/openapi.json` - Always accessible for API tooling
 
 use anyhow::{Context, Result};
 use axum::{
     Json, Router,
     extract::{DefaultBodyLimit, Request},
-    http::{StatusCode, header},
+    http::{Method, StatusCode, header},
     middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::{delete, get, head, post, put},
 };
+use dashmap::DashMap;
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use subtle::ConstantTimeEq;
 use tokio::sync::RwLock;
 
-use crate::daemon::config::DaemonConfig;
+use crate::daemon::backup::BackupRunner;
+use crate::daemon::config::{DaemonConfig, KvTenancy, SqlTenancy, StorageTenancy};
 use crate::daemon::cron::CronScheduler;
+use crate::daemon::cron::leader::LeaderLease;
+use crate::daemon::log_shipping::LogShipper;
 use crate::daemon::metrics;
 #[cfg(feature = "otlp")]
 use crate::daemon::otlp;
 use crate::daemon::process::{self, SpawnConfig};
-use crate::daemon::services::{kv::KvStore, sql::SqlService, storage::StorageService};
-use crate::daemon::state::{Instance, StateStore, Status};
+use crate::daemon::services::{
+    kv::{CacheConfig, KvQuotaExceeded, KvStore},
+    sql::{SqlService, TenantSqlService},
+    storage::StorageService,
+};
+use crate::daemon::state::{Instance, RestartEvent, RestartReason, StateStore, Status};
 
 pub mod audit;
+pub mod client;
+#[cfg(feature = "grpc")]
+mod grpc;
 pub mod handlers;
+pub mod openapi;
+mod s3;
 pub mod types;
 
 // Re-export types for convenience
@@ -100,6 +145,8 @@ pub use types::*;
 
 // Use handlers from the handlers module
 use handlers::{
+    // SQL
+    OpenSqlTransaction,
     // Cron
     cron_create,
     cron_delete,
@@ -113,16 +160,25 @@ use handlers::{
     get_logs,
     health,
     // KV
+    kv_batch_delete,
+    kv_batch_get,
+    kv_batch_set,
     kv_delete,
     kv_get,
     kv_list,
     kv_set,
+    kv_transaction,
     list_instances,
     restart_instance,
-    // SQL
     sql_batch,
+    sql_begin,
+    sql_commit,
     sql_execute,
     sql_query,
+    sql_rollback,
+    sql_stream,
+    sql_transaction_execute,
+    sql_transaction_query,
     start_instance,
     stop_instance,
     // Storage
@@ -130,7 +186,9 @@ use handlers::{
     storage_get,
     storage_head,
     storage_list,
+    storage_presign,
     storage_put,
+    storage_usage,
     version,
 };
 
@@ -143,6 +201,13 @@ pub(crate) struct AppState {
     store: StateStore,
     kv: Option<KvStore>,
     sql: Option<SqlService>,
+    /// Per-tenant SQL databases, set when `sql_tenancy` is `database_per_tenant`.
+    sql_tenants: Option<TenantSqlService>,
+    /// Explicit SQL transactions opened over HTTP, by ID.
+    sql_transactions: DashMap<String, OpenSqlTransaction>,
+    /// Latest resource sample of each running instance, by name, with the
+    /// PID it was taken from. Updated by the health check task.
+    instance_usage: Arc<DashMap<String, (u32, process::ResourceSample)>>,
     storage: Option<StorageService>,
     cron: CronScheduler,
     config: DaemonConfig,
@@ -183,7 +248,31 @@ pub async fn serve(port: u16, state_path: PathBuf, config: DaemonConfig) -> Resu
 
     // Initialize embedded services (conditionally based on config)
     let kv = if config.services.kv_enabled {
-        Some(KvStore::file(data_dir.join("kv.redb")).context("Failed to open KV store")?)
+        let kv_path = data_dir.join("kv.redb");
+        let kv = match &config.services.kv_cache {
+            Some(cache) => {
+                tracing::info!(
+                    max_entries = cache.max_entries,
+                    flush_interval_ms = cache.flush_interval_ms,
+                    "KV write-behind cache enabled"
+                );
+                KvStore::file_cached(
+                    kv_path,
+                    CacheConfig {
+                        max_entries: cache.max_entries,
+                        flush_interval: Duration::from_millis(cache.flush_interval_ms),
+                    },
+                )
+            },
+            None => KvStore::file(kv_path),
+        };
+        if config.services.kv_tenancy == KvTenancy::NamespacePerTenant {
+            tracing::info!("KV tenant isolation enabled (one namespace per tenant)");
+        }
+        Some(
+            kv.context("Failed to open KV store")?
+                .with_tenant_quotas(config.services.kv_tenant_quotas()),
+        )
     } else {
         tracing::info!("KV service disabled by configuration");
         None
@@ -196,20 +285,97 @@ pub async fn serve(port: u16, state_path: PathBuf, config: DaemonConfig) -> Resu
         None
     };
 
+    let sql_tenants = if config.services.sql_enabled
+        && config.services.sql_tenancy == SqlTenancy::DatabasePerTenant
+    {
+        tracing::info!("SQL tenant isolation enabled (one database per tenant)");
+        Some(
+            TenantSqlService::directory(data_dir.join("sql-tenants"))
+                .context("Failed to open tenant SQL directory")?,
+        )
+    } else {
+        None
+    };
+
     let storage = if config.services.storage_enabled {
+        if config.services.storage_tenancy == StorageTenancy::NamespacePerTenant {
+            tracing::info!("Storage tenant isolation enabled (one namespace per tenant)");
+        }
         Some(
             StorageService::file(data_dir.join("storage"))
-                .context("Failed to open storage service")?,
+                .context("Failed to open storage service")?
+                .with_lifecycle(config.services.storage_lifecycle.rules.clone())
+                .with_tenant_quotas(config.services.tenant_quotas()),
         )
     } else {
         tracing::info!("Storage service disabled by configuration");
         None
     };
 
+    // Backups archive the data directory; the storage service doubles as
+    // the default upload target
+    let mut backup = BackupRunner::new(&data_dir);
+    if let Some(storage) = &storage {
+        backup = backup.with_storage(storage.clone());
+    }
+    if let Some(kv) = &kv {
+        backup = backup.with_kv(kv.clone());
+    }
+    if let Some(sql) = &sql {
+        backup = backup.with_sql(sql.clone());
+    }
+
+    // The S3 API serves the storage service on its own port
+    let s3_api = match (&config.services.s3, &storage) {
+        (Some(settings), Some(storage)) => {
+            let verifier = s3::Verifier::from_env(&settings.region)?;
+            Some((settings.port, s3::router(storage.clone(), verifier)))
+        },
+        (Some(_), None) => {
+            anyhow::bail!("[services.s3] requires storage_enabled = true");
+        },
+        (None, _) => None,
+    };
+
+    // The gRPC API is served on its own port too
+    let grpc_port = config.daemon.grpc.as_ref().map(|settings| settings.port);
+    if grpc_port.is_some() && !cfg!(feature = "grpc") {
+        anyhow::bail!("[daemon.grpc] requires mik to be built with the grpc feature");
+    }
+
+    let log_shipper = match &config.daemon.log_shipping {
+        Some(settings) if !settings.sinks.is_empty() => Some(
+            LogShipper::new(settings.clone(), crate::daemon::paths::get_logs_dir()?)
+                .context("Invalid [daemon.log_shipping] settings")?,
+        ),
+        _ => None,
+    };
+
     // Initialize cron scheduler
-    let cron = CronScheduler::new()
-        .await
-        .context("Failed to create cron scheduler")?;
+    let mut cron = CronScheduler::new().with_backup_runner(backup);
+    if let Some(settings) = &config.daemon.cron_leader {
+        let lease_sql = match (&settings.database, &sql) {
+            (Some(path), _) => SqlService::file(path).with_context(|| {
+                format!("Failed to open cron leader database {}", path.display())
+            })?,
+            (None, Some(sql)) => sql.clone(),
+            (None, None) => {
+                anyhow::bail!("[daemon.cron_leader] requires sql_enabled = true or a database path")
+            },
+        };
+        let node_id = settings
+            .node_id
+            .clone()
+            .unwrap_or_else(LeaderLease::default_node_id);
+        tracing::info!(node = %node_id, "Cron leader election enabled");
+        let lease = LeaderLease::new(
+            lease_sql,
+            node_id,
+            Duration::from_secs(settings.lease_secs.max(1)),
+        )
+        .await?;
+        cron = cron.with_leader_lease(lease);
+    }
 
     // Load persisted cron jobs from database
     let persisted_jobs = store
@@ -224,15 +390,15 @@ pub async fn serve(port: u16, state_path: PathBuf, config: DaemonConfig) -> Resu
         }
     }
 
-    cron.start()
-        .await
-        .context("Failed to start cron scheduler")?;
-    tracing::info!("Cron scheduler started");
+    cron.start();
 
     let app_state = Arc::new(RwLock::new(AppState {
         store,
         kv,
         sql,
+        sql_tenants,
+        sql_transactions: DashMap::new(),
+        instance_usage: Arc::default(),
         storage,
         cron,
         config,
@@ -249,21 +415,7 @@ pub async fn serve(port: u16, state_path: PathBuf, config: DaemonConfig) -> Resu
         .route("/instances/{name}", delete(stop_instance))
         .route("/instances/{name}/restart", post(restart_instance))
         .route("/instances/{name}/logs", get(get_logs))
-        // KV service
-        .route("/kv", get(kv_list))
-        .route("/kv/{key}", get(kv_get))
-        .route("/kv/{key}", put(kv_set))
-        .route("/kv/{key}", delete(kv_delete))
-        // SQL service
-        .route("/sql/query", post(sql_query))
-        .route("/sql/execute", post(sql_execute))
-        .route("/sql/batch", post(sql_batch))
-        // Storage service
-        .route("/storage", get(storage_list))
-        .route("/storage/{*path}", get(storage_get))
-        .route("/storage/{*path}", put(storage_put))
-        .route("/storage/{*path}", delete(storage_delete))
-        .route("/storage/{*path}", head(storage_head))
+        .merge(service_routes())
         // Cron scheduler
         .route("/cron", get(cron_list).post(cron_create))
         .route(
@@ -277,6 +429,7 @@ pub async fn serve(port: u16, state_path: PathBuf, config: DaemonConfig) -> Resu
         // System endpoints
         .route("/health", get(health))
         .route("/version", get(version))
+        .route("/openapi.json", get(openapi::openapi_json))
         .with_state(app_state)
         // Request body size limit (10MB) - prevents DoS via large payloads
         .layer(DefaultBodyLimit::max(10 * 1024 * 1024))
@@ -302,25 +455,176 @@ pub async fn serve(port: u16, state_path: PathBuf, config: DaemonConfig) -> Resu
         instance_health_check_task(health_check_state, health_check_rx).await;
     });
 
+    // Spawn storage lifecycle task when expiry rules are configured
+    let (lifecycle_tx, lifecycle_rx) = tokio::sync::oneshot::channel::<()>();
+    let lifecycle_handle = {
+        let state = shutdown_state.read().await;
+        state
+            .storage
+            .clone()
+            .filter(StorageService::has_lifecycle)
+            .map(|storage| {
+                let interval =
+                    Duration::from_secs(state.config.services.storage_lifecycle.interval_secs);
+                tokio::spawn(storage_lifecycle_task(storage, interval, lifecycle_rx))
+            })
+    };
+
+    // Spawn log shipping when sinks are configured
+    let (log_shipping_tx, log_shipping_rx) = tokio::sync::oneshot::channel::<()>();
+    let log_shipping_handle = log_shipper.map(|shipper| tokio::spawn(shipper.run(log_shipping_rx)));
+
     let listener = tokio::net::TcpListener::bind(addr)
         .await
         .with_context(|| format!("Failed to bind to {addr}"))?;
 
+    // Spawn the S3 API server, stopped after the main server
+    let (s3_tx, s3_rx) = tokio::sync::oneshot::channel::<()>();
+    let s3_handle = match s3_api {
+        Some((s3_port, router)) => {
+            let s3_addr = SocketAddr::new(addr.ip(), s3_port);
+            let s3_listener = tokio::net::TcpListener::bind(s3_addr)
+                .await
+                .with_context(|| format!("Failed to bind S3 API to {s3_addr}"))?;
+            tracing::info!("Starting S3-compatible storage API on {}", s3_addr);
+            Some(tokio::spawn(async move {
+                let shutdown = async {
+                    let _ = s3_rx.await;
+                };
+                if let Err(e) = axum::serve(s3_listener, router)
+                    .with_graceful_shutdown(shutdown)
+                    .await
+                {
+                    tracing::error!(error = %e, "S3 API server error");
+                }
+            }))
+        },
+        None => None,
+    };
+
+    // Spawn the gRPC API server, stopped after the main server
+    #[cfg(feature = "grpc")]
+    let (grpc_tx, grpc_rx) = tokio::sync::oneshot::channel::<()>();
+    #[cfg(feature = "grpc")]
+    let grpc_handle = match grpc_port {
+        Some(grpc_port) => {
+            let grpc_addr = SocketAddr::new(addr.ip(), grpc_port);
+            let grpc_listener = tokio::net::TcpListener::bind(grpc_addr)
+                .await
+                .with_context(|| format!("Failed to bind gRPC API to {grpc_addr}"))?;
+            tracing::info!("Starting gRPC API on {}", grpc_addr);
+            let grpc_state = Arc::clone(&shutdown_state);
+            Some(tokio::spawn(async move {
+                let shutdown = async {
+                    let _ = grpc_rx.await;
+                };
+                if let Err(e) = grpc::serve(grpc_listener, grpc_state, shutdown).await {
+                    tracing::error!(error = %format!("{e:#}"), "gRPC API server error");
+                }
+            }))
+        },
+        None => None,
+    };
+
     axum::serve(listener, app)
         .with_graceful_shutdown(shutdown_signal())
         .await
         .context("HTTP server error")?;
 
-    // Signal health check task to stop
+    let _ = s3_tx.send(());
+    if let Some(handle) = s3_handle {
+        let _ = handle.await;
+    }
+    #[cfg(feature = "grpc")]
+    {
+        let _ = grpc_tx.send(());
+        if let Some(handle) = grpc_handle {
+            let _ = handle.await;
+        }
+    }
+
+    // Signal background tasks to stop
     let _ = health_check_tx.send(());
     let _ = health_check_handle.await;
+    let _ = lifecycle_tx.send(());
+    if let Some(handle) = lifecycle_handle {
+        let _ = handle.await;
+    }
 
     // Graceful shutdown: stop all running instances
     graceful_shutdown(shutdown_state).await;
 
+    // Stop log shipping last so it ships what instances wrote while stopping
+    let _ = log_shipping_tx.send(());
+    if let Some(handle) = log_shipping_handle {
+        let _ = handle.await;
+    }
+
     Ok(())
 }
 
+/// KV, SQL and storage endpoints.
+fn service_routes() -> Router<SharedState> {
+    Router::new()
+        // KV service
+        .route("/kv", get(kv_list).post(kv_transaction))
+        .route("/kv/{key}", get(kv_get))
+        .route("/kv/{key}", put(kv_set))
+        .route("/kv/{key}", delete(kv_delete))
+        .route("/kv-batch/get", post(kv_batch_get))
+        .route("/kv-batch/set", post(kv_batch_set))
+        .route("/kv-batch/delete", post(kv_batch_delete))
+        // SQL service
+        .route("/sql/query", post(sql_query))
+        .route("/sql/stream", post(sql_stream))
+        .route("/sql/execute", post(sql_execute))
+        .route("/sql/batch", post(sql_batch))
+        .route("/sql/transactions", post(sql_begin))
+        .route("/sql/transactions/{id}/query", post(sql_transaction_query))
+        .route(
+            "/sql/transactions/{id}/execute",
+            post(sql_transaction_execute),
+        )
+        .route("/sql/transactions/{id}/commit", post(sql_commit))
+        .route("/sql/transactions/{id}/rollback", post(sql_rollback))
+        // Storage service
+        .route("/storage", get(storage_list))
+        .route("/storage/{*path}", get(storage_get))
+        .route("/storage/{*path}", put(storage_put))
+        .route("/storage/{*path}", delete(storage_delete))
+        .route("/storage/{*path}", head(storage_head))
+        .route("/presign/{*path}", post(storage_presign))
+        .route("/storage-usage", get(storage_usage))
+}
+
+/// Router serving only the KV, SQL and storage endpoints from the given
+/// services, without authentication or metrics.
+///
+/// Used by `mik::testing` to give components under test their own services.
+/// `state_path` is the instance state database the handlers require.
+#[allow(dead_code)] // Only used by the library's `testing` feature
+pub fn services_router(
+    kv: KvStore,
+    sql: SqlService,
+    storage: StorageService,
+    state_path: &Path,
+) -> Result<Router> {
+    let state = AppState {
+        store: StateStore::open(state_path).context("Failed to open state database")?,
+        kv: Some(kv),
+        sql: Some(sql),
+        sql_tenants: None,
+        sql_transactions: DashMap::new(),
+        instance_usage: Arc::default(),
+        storage: Some(storage),
+        cron: CronScheduler::new(),
+        config: DaemonConfig::default(),
+    };
+    Ok(service_routes()
+        .with_state(Arc::new(RwLock::new(state)))
+        .layer(DefaultBodyLimit::max(10 * 1024 * 1024)))
+}
+
 /// Graceful shutdown signal handler.
 async fn shutdown_signal() {
     let ctrl_c = async {
@@ -337,7 +641,11 @@ async fn shutdown_signal() {
             .await;
     };
 
-    #[cfg(not(unix))]
+    // `mik stop` asks through a stop event, there being no SIGTERM
+    #[cfg(windows)]
+    let terminate = process::stop_requested();
+
+    #[cfg(not(any(unix, windows)))]
     let terminate = std::future::pending::<()>();
 
     tokio::select! {
@@ -422,14 +730,17 @@ async fn graceful_shutdown(state: SharedState) {
         tracing::info!(count = stopped_count, "Stopped running instances");
     }
 
-    // Stop the cron scheduler
+    // Commit any KV writes still buffered in memory
+    let kv = state.read().await.kv.clone();
+    if let Some(kv) = kv
+        && let Err(e) = kv.flush().await
     {
-        let mut state = state.write().await;
-        if let Err(e) = state.cron.shutdown().await {
-            tracing::warn!(error = %e, "Failed to stop cron scheduler");
-        }
+        tracing::error!(error = %e, "Failed to flush KV store");
     }
 
+    // Stop the cron scheduler
+    state.read().await.cron.shutdown();
+
     // Flush OTLP traces before exit
     #[cfg(feature = "otlp")]
     otlp::shutdown();
@@ -437,13 +748,8 @@ async fn graceful_shutdown(state: SharedState) {
     tracing::info!("Graceful shutdown complete");
 }
 
-/// Base backoff delay in seconds for auto-restart.
-const BASE_BACKOFF_SECS: u64 = 5;
-
-/// Maximum backoff delay in seconds.
-const MAX_BACKOFF_SECS: u64 = 300; // 5 minutes
-
-/// Background task that monitors instance health and auto-restarts crashed instances.
+/// Background task that monitors instance health and applies restart
+/// policies to instances whose process exited (the instance supervisor).
 async fn instance_health_check_task(
     state: SharedState,
     mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
@@ -458,11 +764,12 @@ async fn instance_health_check_task(
 
     let mut interval =
         tokio::time::interval(std::time::Duration::from_secs(health_check_interval_secs));
+    let mut monitor = process::ResourceMonitor::new();
 
     loop {
         tokio::select! {
             _ = interval.tick() => {
-                check_and_recover_instances(&state).await;
+                check_and_recover_instances(&state, &mut monitor).await;
             }
             _ = &mut shutdown_rx => {
                 tracing::info!("Health check task shutting down");
@@ -472,12 +779,56 @@ async fn instance_health_check_task(
     }
 }
 
-/// Checks all running instances and attempts to recover crashed ones.
-async fn check_and_recover_instances(state: &SharedState) {
+/// Background task that deletes storage objects expired by lifecycle rules.
+async fn storage_lifecycle_task(
+    storage: StorageService,
+    every: Duration,
+    mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+) {
+    tracing::info!(
+        interval_secs = every.as_secs(),
+        "Storage lifecycle task started"
+    );
+
+    let mut interval = tokio::time::interval(every.max(Duration::from_secs(1)));
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                match storage.apply_lifecycle(chrono::Utc::now()).await {
+                    Ok(deleted) if !deleted.is_empty() => {
+                        tracing::info!(count = deleted.len(), "Deleted expired storage objects");
+                        metrics::record_storage_lifecycle_deletions(deleted.len() as u64);
+                    },
+                    Ok(_) => {},
+                    Err(e) => tracing::warn!(error = %e, "Storage lifecycle sweep failed"),
+                }
+            }
+            _ = &mut shutdown_rx => {
+                tracing::info!("Storage lifecycle task shutting down");
+                break;
+            }
+        }
+    }
+}
+
+/// Checks all running instances, enforces the memory ceiling, and attempts
+/// to recover crashed ones.
+async fn check_and_recover_instances(state: &SharedState, monitor: &mut process::ResourceMonitor) {
     // Extract the store and config first to avoid holding the lock during blocking operations
-    let (store, max_auto_restarts) = {
+    let (store, usage, max_auto_restarts, ceiling) = {
         let state = state.read().await;
-        (state.store.clone(), state.config.daemon.max_auto_restarts)
+        let daemon = &state.config.daemon;
+        let ceiling = daemon.memory_ceiling_mb.map(|mb| MemoryCeiling {
+            bytes: mb * 1024 * 1024,
+            samples: daemon.memory_ceiling_samples,
+            drain_timeout: Duration::from_secs(u64::from(daemon.memory_ceiling_drain_secs)),
+        });
+        (
+            state.store.clone(),
+            Arc::clone(&state.instance_usage),
+            daemon.max_auto_restarts,
+            ceiling,
+        )
     };
 
     // Get all instances using async method
@@ -489,107 +840,294 @@ async fn check_and_recover_instances(state: &SharedState) {
         },
     };
 
+    // Samples of instances that are gone or no longer running are dropped
+    usage.retain(|name, _| {
+        instances
+            .iter()
+            .any(|i| &i.name == name && i.status == Status::Running)
+    });
+
     for instance in instances {
+        if instance.status == Status::Running
+            && monitor_instance_resources(&store, monitor, &usage, &instance, ceiling.as_ref())
+                .await
+        {
+            continue; // Restarted for memory, state already updated
+        }
         check_and_recover_single_instance(&store, instance, max_auto_restarts).await;
     }
 }
 
-/// Check a single instance and attempt recovery if crashed.
-async fn check_and_recover_single_instance(
+/// Memory ceiling enforcement settings.
+struct MemoryCeiling {
+    bytes: u64,
+    samples: u32,
+    drain_timeout: Duration,
+}
+
+/// Sample an instance's resources into `usage` and restart it if it stayed
+/// above the memory ceiling. Returns `true` if the instance was restarted.
+async fn monitor_instance_resources(
     store: &StateStore,
-    instance: Instance,
-    max_auto_restarts: u32,
-) {
-    // Only check instances marked as Running
-    if instance.status != Status::Running {
+    monitor: &mut process::ResourceMonitor,
+    usage: &DashMap<String, (u32, process::ResourceSample)>,
+    instance: &Instance,
+    ceiling: Option<&MemoryCeiling>,
+) -> bool {
+    let Some(sample) = monitor.sample(instance.pid) else {
+        monitor.forget(&instance.name);
+        usage.remove(&instance.name);
+        return false;
+    };
+    metrics::set_instance_resources(&instance.name, sample.rss_bytes, sample.cpu_percent);
+    usage.insert(instance.name.clone(), (instance.pid, sample));
+
+    let Some(ceiling) = ceiling else {
+        return false;
+    };
+    if !monitor.exceeds_ceiling(
+        &instance.name,
+        sample.rss_bytes,
+        ceiling.bytes,
+        ceiling.samples,
+    ) {
+        return false;
+    }
+
+    tracing::warn!(
+        instance = %instance.name,
+        pid = instance.pid,
+        rss_mb = sample.rss_bytes / (1024 * 1024),
+        ceiling_mb = ceiling.bytes / (1024 * 1024),
+        samples = ceiling.samples,
+        "Instance exceeded memory ceiling, restarting"
+    );
+    usage.remove(&instance.name);
+    restart_for_memory(store, instance, ceiling.drain_timeout).await;
+    true
+}
+
+/// Drain and restart an instance that exceeded the memory ceiling.
+///
+/// SIGTERM makes the instance stop accepting and finish in-flight requests;
+/// it is killed if still running after `drain_timeout`.
+async fn restart_for_memory(store: &StateStore, instance: &Instance, drain_timeout: Duration) {
+    let pid = instance.pid;
+    let stopped =
+        tokio::task::spawn_blocking(move || process::kill_instance_with_grace(pid, drain_timeout))
+            .await;
+    if let Ok(Err(e)) | Err(e) = stopped.map_err(anyhow::Error::from) {
+        tracing::error!(
+            instance = %instance.name,
+            error = %e,
+            "Failed to stop instance for memory restart"
+        );
         return;
     }
 
-    // Check if process is actually running
-    let is_running = match process::is_running(instance.pid) {
-        Ok(running) => running,
+    metrics::record_instance_memory_restart(&instance.name);
+
+    match process::spawn_instance(&spawn_config_for(instance)) {
+        Ok(info) => {
+            let now = chrono::Utc::now();
+            let mut restarted = Instance {
+                pid: info.pid,
+                status: Status::Running,
+                started_at: now,
+                last_restart_at: Some(now),
+                memory_restarts: instance.memory_restarts + 1,
+                ..instance.clone()
+            };
+            restarted.record_restart_event(RestartEvent {
+                at: now,
+                reason: RestartReason::MemoryCeiling,
+                exit: None,
+                restarted_at: Some(now),
+            });
+            if let Err(e) = store.save_instance_async(restarted).await {
+                tracing::error!(
+                    instance = %instance.name,
+                    error = %e,
+                    "Failed to save restarted instance state"
+                );
+            } else {
+                tracing::info!(
+                    instance = %instance.name,
+                    pid = info.pid,
+                    memory_restarts = instance.memory_restarts + 1,
+                    "Instance restarted after exceeding memory ceiling"
+                );
+            }
+        },
         Err(e) => {
-            tracing::warn!(
+            // Recorded as crashed so the restart policy takes over
+            let mut crashed = instance.clone();
+            crashed.status = Status::Crashed { exit_code: -1 };
+            crashed.record_restart_event(RestartEvent {
+                at: chrono::Utc::now(),
+                reason: RestartReason::MemoryCeiling,
+                exit: None,
+                restarted_at: None,
+            });
+            let _ = store.save_instance_async(crashed).await;
+            tracing::error!(
                 instance = %instance.name,
-                pid = instance.pid,
                 error = %e,
-                "Failed to check process status"
+                "Failed to restart instance after exceeding memory ceiling"
             );
+        },
+    }
+}
+
+/// Spawn configuration that restarts `instance` with its original settings.
+fn spawn_config_for(instance: &Instance) -> SpawnConfig {
+    SpawnConfig {
+        name: instance.name.clone(),
+        port: instance.port,
+        config_path: instance.config.clone(),
+        working_dir: instance
+            .config
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .to_path_buf(),
+        hot_reload: false,
+        build_error_file: None,
+        dev_proxy: None,
+        dev_dashboard: false,
+        restart: instance.restart_config(),
+    }
+}
+
+/// Check a single instance and apply its restart policy if its process
+/// exited.
+async fn check_and_recover_single_instance(
+    store: &StateStore,
+    instance: Instance,
+    max_auto_restarts: u32,
+) {
+    match instance.status {
+        Status::Running => {},
+        // May be waiting out the backoff before a restart
+        Status::Crashed { .. } => {
+            attempt_auto_restart(store, instance, max_auto_restarts).await;
             return;
         },
-    };
+        Status::Stopped => {
+            // Reap a stopped child so it doesn't linger as a zombie
+            process::try_reap(instance.pid);
+            return;
+        },
+    }
 
-    if is_running {
-        return; // Instance is healthy
+    // A child of the daemon is reaped here, which also tells how it exited
+    let exit = process::try_reap(instance.pid);
+    if exit.is_none() {
+        match process::is_running(instance.pid) {
+            Ok(true) => return, // Instance is healthy
+            Ok(false) => {},
+            Err(e) => {
+                tracing::warn!(
+                    instance = %instance.name,
+                    pid = instance.pid,
+                    error = %e,
+                    "Failed to check process status"
+                );
+                return;
+            },
+        }
     }
 
-    // Instance crashed - handle recovery
-    handle_crashed_instance(store, instance, max_auto_restarts).await;
+    handle_exited_instance(store, instance, exit, max_auto_restarts).await;
 }
 
-/// Handle a crashed instance: update status and attempt auto-restart if enabled.
-async fn handle_crashed_instance(store: &StateStore, instance: Instance, max_auto_restarts: u32) {
+/// Handle an instance whose process exited: record the exit and restart it
+/// if its policy says so.
+async fn handle_exited_instance(
+    store: &StateStore,
+    instance: Instance,
+    exit: Option<process::InstanceExit>,
+    max_auto_restarts: u32,
+) {
+    let restart = instance.restart_config();
+    let restarts = restart.restarts_after(exit);
     tracing::warn!(
         instance = %instance.name,
         pid = instance.pid,
-        "Instance crashed (process not running)"
+        exit = %exit.map_or_else(|| "unknown".to_string(), |e| e.to_string()),
+        policy = %restart.policy,
+        "Instance process exited"
     );
 
-    // Update status to Crashed using async method
-    let mut crashed_instance = instance.clone();
-    crashed_instance.status = Status::Crashed { exit_code: -1 };
+    let mut exited = instance;
+    exited.status = match exit {
+        // A clean exit the policy accepts is a stop, not a crash
+        Some(exit) if exit.is_success() && !restarts => Status::Stopped,
+        Some(exit) => Status::Crashed {
+            exit_code: exit.exit_code(),
+        },
+        None => Status::Crashed { exit_code: -1 },
+    };
+    exited.record_restart_event(RestartEvent {
+        at: chrono::Utc::now(),
+        reason: RestartReason::Exited,
+        exit,
+        restarted_at: None,
+    });
 
-    if let Err(e) = store.save_instance_async(crashed_instance).await {
+    if let Err(e) = store.save_instance_async(exited.clone()).await {
         tracing::error!(
-            instance = %instance.name,
+            instance = %exited.name,
             error = %e,
-            "Failed to update instance status to crashed"
+            "Failed to update status of exited instance"
         );
         return;
     }
 
-    // Check if auto-restart is enabled
-    if !instance.auto_restart {
+    if !restarts {
         tracing::info!(
-            instance = %instance.name,
-            "Auto-restart disabled, not recovering"
+            instance = %exited.name,
+            policy = %restart.policy,
+            "Restart policy does not restart this exit"
         );
         return;
     }
 
-    // Attempt auto-restart with backoff
-    attempt_auto_restart(store, &instance, max_auto_restarts).await;
-}
-
-/// Attempt to auto-restart a crashed instance with exponential backoff.
-async fn attempt_auto_restart(store: &StateStore, instance: &Instance, max_auto_restarts: u32) {
-    // Check if we've exceeded max restarts
-    if instance.restart_count >= max_auto_restarts {
+    let max_retries = restart.max_retries.unwrap_or(max_auto_restarts);
+    if exited.restart_count >= max_retries {
         tracing::error!(
-            instance = %instance.name,
-            restart_count = instance.restart_count,
-            "Max auto-restarts ({}) exceeded, giving up",
-            max_auto_restarts
+            instance = %exited.name,
+            restart_count = exited.restart_count,
+            "Max restarts ({max_retries}) exceeded, giving up"
         );
         return;
     }
 
-    // Calculate backoff delay (exponential with cap)
-    let backoff_secs = std::cmp::min(
-        BASE_BACKOFF_SECS * 2u64.pow(instance.restart_count),
-        MAX_BACKOFF_SECS,
-    );
+    attempt_auto_restart(store, exited, max_auto_restarts).await;
+}
+
+/// Restart an exited instance its policy wants back, once the backoff since
+/// its previous restart has passed.
+async fn attempt_auto_restart(store: &StateStore, instance: Instance, max_auto_restarts: u32) {
+    let restart = instance.restart_config();
+    let Some(last) = instance.restart_history.last() else {
+        return; // Exited before restart history was kept
+    };
+    if last.restarted_at.is_some() || !restart.restarts_after(last.exit) {
+        return;
+    }
+    if instance.restart_count >= restart.max_retries.unwrap_or(max_auto_restarts) {
+        return;
+    }
 
-    // Check if enough time has passed since last restart
-    if !should_restart_now(instance, backoff_secs) {
+    let backoff = restart.backoff(instance.restart_count);
+    if !should_restart_now(&instance, backoff) {
         return;
     }
 
-    // Attempt restart
     tracing::info!(
         instance = %instance.name,
         restart_count = instance.restart_count + 1,
-        backoff_secs = backoff_secs,
+        backoff_secs = backoff.as_secs(),
         "Attempting auto-restart"
     );
 
@@ -597,17 +1135,14 @@ async fn attempt_auto_restart(store: &StateStore, instance: &Instance, max_auto_
 }
 
 /// Check if enough time has passed since the last restart attempt.
-fn should_restart_now(instance: &Instance, backoff_secs: u64) -> bool {
+fn should_restart_now(instance: &Instance, backoff: Duration) -> bool {
     if let Some(last_restart) = instance.last_restart_at {
-        let elapsed = chrono::Utc::now()
-            .signed_duration_since(last_restart)
-            .num_seconds();
-        #[allow(clippy::cast_possible_wrap)]
-        let backoff_secs_i64 = backoff_secs as i64;
-        if elapsed < backoff_secs_i64 {
+        let elapsed = chrono::Utc::now().signed_duration_since(last_restart);
+        let backoff = chrono::Duration::from_std(backoff).unwrap_or(chrono::Duration::MAX);
+        if elapsed < backoff {
             tracing::debug!(
                 instance = %instance.name,
-                backoff_remaining = backoff_secs_i64 - elapsed,
+                backoff_remaining_secs = (backoff - elapsed).num_seconds(),
                 "Waiting for backoff before restart"
             );
             return false;
@@ -617,44 +1152,31 @@ fn should_restart_now(instance: &Instance, backoff_secs: u64) -> bool {
 }
 
 /// Spawn a new instance process and save the updated state.
-async fn spawn_and_save_restarted_instance(store: &StateStore, instance: &Instance) {
-    let spawn_config = SpawnConfig {
-        name: instance.name.clone(),
-        port: instance.port,
-        config_path: instance.config.clone(),
-        working_dir: instance
-            .config
-            .parent()
-            .unwrap_or_else(|| std::path::Path::new("."))
-            .to_path_buf(),
-        hot_reload: false,
-    };
-
-    match process::spawn_instance(&spawn_config) {
+async fn spawn_and_save_restarted_instance(store: &StateStore, instance: Instance) {
+    match process::spawn_instance(&spawn_config_for(&instance)) {
         Ok(info) => {
-            let restarted = Instance {
-                name: instance.name.clone(),
-                port: instance.port,
+            let now = chrono::Utc::now();
+            let mut restarted = Instance {
                 pid: info.pid,
                 status: Status::Running,
-                config: instance.config.clone(),
-                started_at: chrono::Utc::now(),
-                modules: instance.modules.clone(),
-                auto_restart: true,
+                started_at: now,
                 restart_count: instance.restart_count + 1,
-                last_restart_at: Some(chrono::Utc::now()),
+                last_restart_at: Some(now),
+                ..instance
             };
+            if let Some(last) = restarted.restart_history.last_mut() {
+                last.restarted_at = Some(now);
+            }
 
-            // Use async method
             if let Err(e) = store.save_instance_async(restarted.clone()).await {
                 tracing::error!(
-                    instance = %instance.name,
+                    instance = %restarted.name,
                     error = %e,
                     "Failed to save restarted instance state"
                 );
             } else {
                 tracing::info!(
-                    instance = %instance.name,
+                    instance = %restarted.name,
                     pid = info.pid,
                     restart_count = restarted.restart_count,
                     "Instance auto-restarted successfully"
@@ -662,8 +1184,13 @@ async fn spawn_and_save_restarted_instance(store: &StateStore, instance: &Instan
             }
         },
         Err(e) => {
+            // Stays crashed; the next check tries again after the backoff
+            let mut failed = instance;
+            failed.last_restart_at = Some(chrono::Utc::now());
+            failed.restart_count += 1;
+            let _ = store.save_instance_async(failed.clone()).await;
             tracing::error!(
-                instance = %instance.name,
+                instance = %failed.name,
                 error = %e,
                 "Failed to auto-restart instance"
             );
@@ -684,6 +1211,7 @@ static API_KEY: std::sync::LazyLock<Option<String>> =
 /// a matching `X-API-Key` header. The following endpoints are exempt:
 /// - `/health` - Health checks (for load balancers/monitoring)
 /// - `/metrics` - Prometheus metrics (for scraping)
+/// - `/openapi.json` - API description (contains no data)
 ///
 /// If `MIK_API_KEY` is not set, all requests pass through (backwards compatible).
 ///
@@ -698,9 +1226,14 @@ async fn api_key_auth_middleware(request: Request, next: Next) -> Response {
         return next.run(request).await;
     };
 
-    // Exempt health and metrics endpoints from auth
+    // Exempt health, metrics, and API description endpoints from auth
     let path = request.uri().path();
-    if path == "/health" || path == "/metrics" {
+    if matches!(path, "/health" | "/metrics" | "/openapi.json") {
+        return next.run(request).await;
+    }
+
+    // Presigned storage URLs are verified by the storage handlers instead
+    if is_presigned_storage_request(&request) {
         return next.run(request).await;
     }
 
@@ -710,39 +1243,19 @@ async fn api_key_auth_middleware(request: Request, next: Next) -> Response {
         .get("X-API-Key")
         .and_then(|v| v.to_str().ok());
 
-    if let Some(key) = provided_key {
-        // Constant-time comparison prevents timing attacks:
-        // An attacker cannot determine which characters are correct by
-        // measuring response time differences.
-        let key_bytes = key.as_bytes();
-        let expected_bytes = expected_key.as_bytes();
-
-        // Only perform constant-time comparison if lengths match
-        // (length comparison is not constant-time, but revealing length
-        // is acceptable for API keys and avoids panic on slice comparison)
-        let is_valid =
-            key_bytes.len() == expected_bytes.len() && bool::from(key_bytes.ct_eq(expected_bytes));
-
-        if is_valid {
-            // Log successful auth for audit trail
-            audit::log_audit_event(audit::AuditEvent::AuthSuccess {
-                remote_addr: "[redacted]"
-                    .parse()
-                    .unwrap_or_else(|_| std::net::SocketAddr::from(([0, 0, 0, 0], 0))),
-            });
-            next.run(request).await
-        } else {
-            // Log failed auth attempt for security monitoring
-            audit::log_audit_event(audit::AuditEvent::AuthFailure {
-                remote_addr: "[redacted]"
-                    .parse()
-                    .unwrap_or_else(|_| std::net::SocketAddr::from(([0, 0, 0, 0], 0))),
-                reason: "Invalid API key".to_string(),
-            });
-            tracing::warn!(path = %path, "API key authentication failed: invalid key");
-            (StatusCode::UNAUTHORIZED, "Invalid API key").into_response()
-        }
-    } else {
+    match check_api_key(provided_key, expected_key, path) {
+        Ok(()) => next.run(request).await,
+        Err(message) => (StatusCode::UNAUTHORIZED, message).into_response(),
+    }
+}
+
+/// Checks a provided API key against the expected one, recording the
+/// outcome in the audit log.
+///
+/// Returns the reason to report to the client when the key is missing or
+/// wrong.
+fn check_api_key(provided: Option<&str>, expected: &str, path: &str) -> Result<(), &'static str> {
+    let Some(key) = provided else {
         // Log missing header for security monitoring
         audit::log_audit_event(audit::AuditEvent::AuthFailure {
             remote_addr: "[redacted]"
@@ -751,10 +1264,52 @@ async fn api_key_auth_middleware(request: Request, next: Next) -> Response {
             reason: "Missing X-API-Key header".to_string(),
         });
         tracing::warn!(path = %path, "API key authentication failed: missing header");
-        (StatusCode::UNAUTHORIZED, "Missing X-API-Key header").into_response()
+        return Err("Missing X-API-Key header");
+    };
+
+    // Constant-time comparison prevents timing attacks:
+    // An attacker cannot determine which characters are correct by
+    // measuring response time differences.
+    let key_bytes = key.as_bytes();
+    let expected_bytes = expected.as_bytes();
+
+    // Only perform constant-time comparison if lengths match
+    // (length comparison is not constant-time, but revealing length
+    // is acceptable for API keys and avoids panic on slice comparison)
+    let is_valid =
+        key_bytes.len() == expected_bytes.len() && bool::from(key_bytes.ct_eq(expected_bytes));
+
+    if is_valid {
+        // Log successful auth for audit trail
+        audit::log_audit_event(audit::AuditEvent::AuthSuccess {
+            remote_addr: "[redacted]"
+                .parse()
+                .unwrap_or_else(|_| std::net::SocketAddr::from(([0, 0, 0, 0], 0))),
+        });
+        Ok(())
+    } else {
+        // Log failed auth attempt for security monitoring
+        audit::log_audit_event(audit::AuditEvent::AuthFailure {
+            remote_addr: "[redacted]"
+                .parse()
+                .unwrap_or_else(|_| std::net::SocketAddr::from(([0, 0, 0, 0], 0))),
+            reason: "Invalid API key".to_string(),
+        });
+        tracing::warn!(path = %path, "API key authentication failed: invalid key");
+        Err("Invalid API key")
     }
 }
 
+/// Whether a request is a GET/PUT on a storage object carrying a presigned
+/// URL signature.
+fn is_presigned_storage_request(request: &Request) -> bool {
+    matches!(*request.method(), Method::GET | Method::PUT)
+        && request.uri().path().starts_with("/storage/")
+        && request.uri().query().is_some_and(|query| {
+            url::form_urlencoded::parse(query.as_bytes()).any(|(key, _)| key == "signature")
+        })
+}
+
 /// Middleware to record HTTP request metrics.
 async fn metrics_middleware(request: Request, next: Next) -> Response {
     let start = Instant::now();
@@ -800,6 +1355,10 @@ pub(crate) enum AppError {
     Conflict(String),
     Internal(String),
     ServiceUnavailable(String),
+    PayloadTooLarge(String),
+    Forbidden(String),
+    PreconditionFailed(String),
+    InsufficientStorage(String),
 }
 
 impl IntoResponse for AppError {
@@ -810,14 +1369,31 @@ impl IntoResponse for AppError {
             Self::Conflict(msg) => (StatusCode::CONFLICT, msg),
             Self::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             Self::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            Self::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg),
+            Self::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            Self::PreconditionFailed(msg) => (StatusCode::PRECONDITION_FAILED, msg),
+            Self::InsufficientStorage(msg) => (StatusCode::INSUFFICIENT_STORAGE, msg),
         };
-
-        (status, Json(ErrorResponse { error: message })).into_response()
+        // Insufficient storage is only reported for tenant quotas
+        let code =
+            (status == StatusCode::INSUFFICIENT_STORAGE).then(|| "quota_exceeded".to_string());
+
+        (
+            status,
+            Json(ErrorResponse {
+                error: message,
+                code,
+            }),
+        )
+            .into_response()
     }
 }
 
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
+        if let Some(exceeded) = err.downcast_ref::<KvQuotaExceeded>() {
+            return Self::InsufficientStorage(exceeded.to_string());
+        }
         Self::Internal(err.to_string())
     }
 }
@@ -834,6 +1410,7 @@ impl From<std::io::Error> for AppError {
 
 #[cfg(test)]
 mod tests {
+    use super::types::PresignResponse;
     use super::*;
     use axum::body::Body;
     use axum::http::{Method, Request};
@@ -842,7 +1419,7 @@ mod tests {
     // Note: format_duration tests are in utils.rs
 
     /// Create a test router with all service routes.
-    async fn create_test_app() -> Router {
+    fn create_test_app() -> Router {
         let temp_dir = tempfile::tempdir().unwrap();
         let data_dir = temp_dir.path().to_path_buf();
 
@@ -854,12 +1431,15 @@ mod tests {
         let kv = Some(KvStore::file(data_dir.join("kv.redb")).unwrap());
         let sql = Some(SqlService::file(data_dir.join("sql.db")).unwrap());
         let storage = Some(StorageService::file(data_dir.join("storage")).unwrap());
-        let cron = CronScheduler::new().await.unwrap();
+        let cron = CronScheduler::new();
 
         let app_state = Arc::new(RwLock::new(AppState {
             store,
             kv,
             sql,
+            sql_tenants: None,
+            sql_transactions: DashMap::new(),
+            instance_usage: Arc::default(),
             storage,
             cron,
             config: DaemonConfig::default(),
@@ -867,10 +1447,13 @@ mod tests {
 
         Router::new()
             // KV service
-            .route("/kv", get(kv_list))
+            .route("/kv", get(kv_list).post(kv_transaction))
             .route("/kv/{key}", get(kv_get))
             .route("/kv/{key}", put(kv_set))
             .route("/kv/{key}", delete(kv_delete))
+            .route("/kv-batch/get", post(kv_batch_get))
+            .route("/kv-batch/set", post(kv_batch_set))
+            .route("/kv-batch/delete", post(kv_batch_delete))
             // SQL service
             .route("/sql/query", post(sql_query))
             .route("/sql/execute", post(sql_execute))
@@ -881,6 +1464,7 @@ mod tests {
             .route("/storage/{*path}", put(storage_put))
             .route("/storage/{*path}", delete(storage_delete))
             .route("/storage/{*path}", head(storage_head))
+            .route("/presign/{*path}", post(storage_presign))
             // System endpoints
             .route("/health", get(health))
             .route("/version", get(version))
@@ -893,7 +1477,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_health_endpoint() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let response = app
             .oneshot(
@@ -916,7 +1500,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_version_endpoint() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let response = app
             .oneshot(
@@ -943,7 +1527,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_kv_set_and_get() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         // Set a value
         let set_request = Request::builder()
@@ -973,9 +1557,218 @@ mod tests {
         assert_eq!(kv_response.value, "test-value");
     }
 
+    #[tokio::test]
+    async fn test_kv_transaction() {
+        let app = create_test_app();
+
+        let transaction = |body: &'static str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/kv")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(transaction(
+                r#"{"expect": {"a": null}, "writes": [{"key": "a", "value": "1"}, {"key": "b", "value": "2"}]}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // `a` exists now, so the same guard conflicts
+        let response = app
+            .clone()
+            .oneshot(transaction(
+                r#"{"expect": {"a": null}, "writes": [{"key": "b"}]}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = app
+            .oneshot(Request::builder().uri("/kv/b").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_kv_list_pages() {
+        let app = create_test_app();
+
+        for key in ["a", "b", "c"] {
+            let request = Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/kv/{key}"))
+                .header("content-type", "application/json")
+                .body(Body::from(format!(r#"{{"value": "{key}!"}}"#)))
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let list = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<KvListResponse>(&body).unwrap()
+            }
+        };
+
+        let first = list("/kv?limit=2&values=true").await;
+        assert_eq!(first.keys, vec!["a", "b"]);
+        assert_eq!(first.entries.unwrap()[1].value, "b!");
+        assert_eq!(first.cursor.as_deref(), Some("b"));
+
+        let second = list("/kv?limit=2&cursor=b").await;
+        assert_eq!(second.keys, vec!["c"]);
+        assert!(second.entries.is_none());
+        assert!(second.cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_kv_watch_streams_changes() {
+        use futures::StreamExt;
+
+        let app = create_test_app();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/kv?watch=true&prefix=config:")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut events = response.into_body().into_data_stream();
+
+        for key in ["other", "config:mode"] {
+            let request = Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/kv/{key}"))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"value": "fast"}"#))
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let frame = tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let frame = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(frame.starts_with("event: set\n"), "{frame}");
+        assert!(frame.contains(r#""key":"config:mode""#), "{frame}");
+        assert!(frame.contains(r#""value":"fast""#), "{frame}");
+    }
+
+    #[tokio::test]
+    async fn test_storage_watch_streams_changes() {
+        use futures::StreamExt;
+
+        let app = create_test_app();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/storage?watch=true&prefix=uploads/")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let mut events = response.into_body().into_data_stream();
+
+        for path in ["other/a.txt", "uploads/b.txt"] {
+            let request = Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/storage/{path}"))
+                .body(Body::from("data"))
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let frame = tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let frame = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(frame.starts_with("event: ObjectCreated:Put\n"), "{frame}");
+        assert!(frame.contains(r#""path":"uploads/b.txt""#), "{frame}");
+        assert!(frame.contains(r#""size":4"#), "{frame}");
+    }
+
+    #[tokio::test]
+    async fn test_kv_batch() {
+        let app = create_test_app();
+
+        let batch = |op: &str, body: &'static str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/kv-batch/{op}"))
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(batch("set", r#"{"entries": {"a": "1", "b": "2"}}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(batch("get", r#"{"keys": ["a", "b", "c"]}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let got: types::KvBatchGetResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(got.values["a"].as_deref(), Some("1"));
+        assert_eq!(got.values["b"].as_deref(), Some("2"));
+        assert_eq!(got.values["c"], None);
+
+        let response = app
+            .clone()
+            .oneshot(batch("delete", r#"{"keys": ["a", "c"]}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let deleted: types::KvBatchDeleteResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(deleted.deleted, 1);
+
+        let response = app
+            .oneshot(Request::builder().uri("/kv/a").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn test_kv_get_not_found() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let request = Request::builder()
             .uri("/kv/nonexistent-key")
@@ -988,7 +1781,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_kv_delete() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         // First set a value
         let set_request = Request::builder()
@@ -1023,7 +1816,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_kv_list_keys() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         // Set multiple keys
         for key in ["prefix:a", "prefix:b", "other:c"] {
@@ -1067,7 +1860,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_kv_set_with_ttl() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let set_request = Request::builder()
             .method(Method::PUT)
@@ -1098,7 +1891,7 @@ mod tests {
     #[tokio::test]
     #[ignore = "requires local SQLite setup"]
     async fn test_sql_execute_create_table() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let request = Request::builder()
             .method(Method::POST)
@@ -1118,7 +1911,7 @@ mod tests {
     #[tokio::test]
     #[ignore = "requires local SQLite setup"]
     async fn test_sql_execute_insert_and_query() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         // Create table
         let create_request = Request::builder()
@@ -1183,7 +1976,7 @@ mod tests {
     #[tokio::test]
     #[ignore = "requires local SQLite setup"]
     async fn test_sql_batch_execute() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         // Create table first
         let create_request = Request::builder()
@@ -1234,7 +2027,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_storage_put_and_get() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let content = b"Hello, World!";
 
@@ -1268,9 +2061,239 @@ mod tests {
         assert_eq!(&body[..], content);
     }
 
+    #[tokio::test]
+    async fn test_storage_streams_objects_above_body_limit() {
+        let app = create_test_app();
+
+        // 12 MiB in 1 MiB chunks, without a content length
+        let chunk = bytes::Bytes::from(vec![7u8; 1024 * 1024]);
+        let chunks =
+            futures::stream::iter((0..12).map(move |_| Ok::<_, std::io::Error>(chunk.clone())));
+        let put_request = Request::builder()
+            .method(Method::PUT)
+            .uri("/storage/large/blob.bin")
+            .body(Body::from_stream(chunks))
+            .unwrap();
+        let response = app.clone().oneshot(put_request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let get_request = Request::builder()
+            .uri("/storage/large/blob.bin")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(get_request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("content-length").unwrap(),
+            &(12 * 1024 * 1024).to_string()
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body.len(), 12 * 1024 * 1024);
+        assert!(body.iter().all(|&b| b == 7));
+
+        // Declared sizes over the object limit are rejected up front
+        let put_request = Request::builder()
+            .method(Method::PUT)
+            .uri("/storage/large/huge.bin")
+            .header("content-length", (6u64 * 1024 * 1024 * 1024).to_string())
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(put_request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_storage_presigned_urls() {
+        let app = create_test_app();
+
+        let presign = |method: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/presign/shared/report%20v1.txt")
+                .header("content-type", "application/json")
+                .body(Body::from(format!(r#"{{"method":"{method}"}}"#)))
+                .unwrap()
+        };
+        let url_of = |body: &[u8]| serde_json::from_slice::<PresignResponse>(body).unwrap().url;
+
+        let response = app.clone().oneshot(presign("PUT")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let put_url = url_of(&body);
+        assert!(put_url.starts_with("/storage/shared/report%20v1.txt?expires="));
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(&put_url)
+                    .body(Body::from("signed upload"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // A PUT link does not grant GET
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(&put_url)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app.clone().oneshot(presign("GET")).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let get_url = url_of(&body);
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(&get_url)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"signed upload");
+
+        // Links cannot be moved to another object
+        let other = get_url.replace("report%20v1.txt", "other.txt");
+        let response = app
+            .oneshot(Request::builder().uri(&other).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_storage_presigned_put_respects_tenant_quota() {
+        use crate::daemon::services::storage::{TenantQuota, TenantQuotas};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let data_dir: &'static std::path::Path = Box::leak(Box::new(temp_dir.keep()));
+        let mut config = DaemonConfig::default();
+        config.services.storage_tenancy = StorageTenancy::NamespacePerTenant;
+        let storage = StorageService::memory().with_tenant_quotas(TenantQuotas {
+            default: TenantQuota {
+                max_bytes: None,
+                max_objects: Some(1),
+            },
+            tenants: std::collections::HashMap::new(),
+        });
+        let app_state = Arc::new(RwLock::new(AppState {
+            store: StateStore::open(data_dir.join("state.redb")).unwrap(),
+            kv: None,
+            sql: None,
+            sql_tenants: None,
+            sql_transactions: DashMap::new(),
+            instance_usage: Arc::default(),
+            storage: Some(storage),
+            cron: CronScheduler::new(),
+            config,
+        }));
+        let app = Router::new()
+            .route("/storage", get(storage_list))
+            .route("/storage/{*path}", put(storage_put))
+            .route("/presign/{*path}", post(storage_presign))
+            .with_state(app_state);
+
+        let signed_put = |name: &str| {
+            let app = app.clone();
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(format!("/presign/{name}"))
+                .header("content-type", "application/json")
+                .header("X-Tenant-Id", "acme")
+                .body(Body::from(r#"{"method":"PUT"}"#))
+                .unwrap();
+            async move {
+                let response = app.clone().oneshot(request).await.unwrap();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let url = serde_json::from_slice::<PresignResponse>(&body)
+                    .unwrap()
+                    .url;
+                assert!(url.starts_with("/storage/tenants/acme/"), "{url}");
+                let request = Request::builder()
+                    .method(Method::PUT)
+                    .uri(&url)
+                    .body(Body::from("signed upload"))
+                    .unwrap();
+                app.oneshot(request).await.unwrap()
+            }
+        };
+
+        assert_eq!(signed_put("a.txt").await.status(), StatusCode::CREATED);
+        // Replacing the object through its link stays within the quota
+        assert_eq!(signed_put("a.txt").await.status(), StatusCode::CREATED);
+        assert_eq!(
+            signed_put("b.txt").await.status(),
+            StatusCode::INSUFFICIENT_STORAGE
+        );
+
+        let request = Request::builder()
+            .uri("/storage")
+            .header("X-Tenant-Id", "acme")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: StorageListResponse = serde_json::from_slice(&body).unwrap();
+        let paths: Vec<_> = list.objects.iter().map(|o| o.path.as_str()).collect();
+        assert_eq!(paths, ["a.txt"]);
+    }
+
+    #[test]
+    fn test_presigned_requests_detected() {
+        let request = |method: Method, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        assert!(is_presigned_storage_request(&request(
+            Method::GET,
+            "/storage/a.txt?expires=1&signature=ab"
+        )));
+        assert!(!is_presigned_storage_request(&request(
+            Method::DELETE,
+            "/storage/a.txt?expires=1&signature=ab"
+        )));
+        assert!(!is_presigned_storage_request(&request(
+            Method::GET,
+            "/kv/a?signature=ab"
+        )));
+        assert!(!is_presigned_storage_request(&request(
+            Method::GET,
+            "/storage/a.txt"
+        )));
+    }
+
     #[tokio::test]
     async fn test_storage_get_not_found() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let request = Request::builder()
             .uri("/storage/nonexistent/file.txt")
@@ -1283,7 +2306,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_storage_head() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let content = b"Test content for head request";
 
@@ -1316,9 +2339,49 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_storage_conditional_requests() {
+        let app = create_test_app();
+        let put = |body: &'static str, condition: Option<(&'static str, String)>| {
+            let mut request = Request::builder()
+                .method(Method::PUT)
+                .uri("/storage/config.json");
+            if let Some((name, value)) = condition {
+                request = request.header(name, value);
+            }
+            request.body(Body::from(body)).unwrap()
+        };
+
+        let response = app.clone().oneshot(put("v1", None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let etag = response.headers()["etag"].to_str().unwrap().to_string();
+
+        // A client holding the current version gets 304
+        let request = Request::builder()
+            .uri("/storage/config.json")
+            .header("if-none-match", &etag)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()["etag"], etag.as_str());
+
+        // Overwrite succeeds once with the seen ETag, then fails with it
+        let if_match = || Some(("if-match", etag.clone()));
+        let response = app.clone().oneshot(put("v2", if_match())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let response = app.clone().oneshot(put("v3", if_match())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+        // Create-only writes fail when the object exists
+        let create_only = Some(("if-none-match", "*".to_string()));
+        let response = app.oneshot(put("v4", create_only)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    }
+
     #[tokio::test]
     async fn test_storage_delete() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         // Put an object
         let put_request = Request::builder()
@@ -1352,7 +2415,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_storage_list() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         // Put multiple objects
         for path in ["docs/a.txt", "docs/b.txt", "images/c.png"] {
@@ -1397,6 +2460,294 @@ mod tests {
         assert_eq!(list_response.objects.len(), 2);
     }
 
+    // =========================================================================
+    // SQL Tenant Isolation Tests
+    // =========================================================================
+
+    /// Create a test app with SQL tenant isolation enabled.
+    fn create_tenant_sql_app() -> Router {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let data_dir: &'static std::path::Path = Box::leak(Box::new(temp_dir.keep()));
+
+        let app_state = Arc::new(RwLock::new(AppState {
+            store: StateStore::open(data_dir.join("state.redb")).unwrap(),
+            kv: None,
+            sql: Some(SqlService::memory().unwrap()),
+            sql_tenants: Some(TenantSqlService::memory()),
+            sql_transactions: DashMap::new(),
+            instance_usage: Arc::default(),
+            storage: None,
+            cron: CronScheduler::new(),
+            config: DaemonConfig::default(),
+        }));
+
+        Router::new()
+            .route("/sql/query", post(sql_query))
+            .route("/sql/stream", post(sql_stream))
+            .route("/sql/execute", post(sql_execute))
+            .route("/sql/transactions", post(sql_begin))
+            .route("/sql/transactions/{id}/query", post(sql_transaction_query))
+            .route(
+                "/sql/transactions/{id}/execute",
+                post(sql_transaction_execute),
+            )
+            .route("/sql/transactions/{id}/commit", post(sql_commit))
+            .with_state(app_state)
+    }
+
+    fn tenant_sql_request(uri: &str, tenant: Option<&str>, body: &str) -> Request<Body> {
+        let mut builder = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(tenant) = tenant {
+            builder = builder.header("X-Tenant-Id", tenant);
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sql_tenant_header_required() {
+        let app = create_tenant_sql_app();
+
+        let request = tenant_sql_request("/sql/query", None, r#"{"sql": "SELECT 1"}"#);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let request = tenant_sql_request("/sql/query", Some("../acme"), r#"{"sql": "SELECT 1"}"#);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_sql_tenants_cannot_see_each_other() {
+        let app = create_tenant_sql_app();
+
+        let request = tenant_sql_request(
+            "/sql/execute",
+            Some("acme"),
+            r#"{"sql": "CREATE TABLE orders (id INTEGER PRIMARY KEY)"}"#,
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = tenant_sql_request(
+            "/sql/query",
+            Some("acme"),
+            r#"{"sql": "SELECT * FROM orders"}"#,
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Another tenant's database has no orders table at all
+        let request = tenant_sql_request(
+            "/sql/query",
+            Some("globex"),
+            r#"{"sql": "SELECT * FROM orders"}"#,
+        );
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_sql_stream_ndjson() {
+        let app = create_tenant_sql_app();
+
+        let request = tenant_sql_request(
+            "/sql/execute",
+            Some("acme"),
+            r#"{"sql": "CREATE TABLE items AS SELECT 1 AS id, 'a' AS name UNION ALL SELECT 2, 'b'"}"#,
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = tenant_sql_request(
+            "/sql/stream",
+            Some("acme"),
+            r#"{"sql": "SELECT id, name FROM items ORDER BY id"}"#,
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/x-ndjson"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let lines: Vec<serde_json::Value> = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                serde_json::json!({"columns": ["id", "name"]}),
+                serde_json::json!([1, "a"]),
+                serde_json::json!([2, "b"]),
+            ]
+        );
+
+        // Errors before the first row are reported with a status code
+        let request = tenant_sql_request(
+            "/sql/stream",
+            Some("acme"),
+            r#"{"sql": "SELECT * FROM missing"}"#,
+        );
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_sql_transaction_endpoints() {
+        let app = create_tenant_sql_app();
+        let send = |uri: String, tenant: &str, body: &str| {
+            app.clone()
+                .oneshot(tenant_sql_request(&uri, Some(tenant), body))
+        };
+
+        let response = send("/sql/transactions".to_string(), "acme", "")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let id = serde_json::from_slice::<types::SqlTransactionResponse>(&body)
+            .unwrap()
+            .id;
+
+        let response = send(
+            format!("/sql/transactions/{id}/execute"),
+            "acme",
+            r#"{"sql": "CREATE TABLE orders (id INTEGER PRIMARY KEY)"}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(
+            format!("/sql/transactions/{id}/query"),
+            "acme",
+            r#"{"sql": "SELECT * FROM orders"}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Another tenant cannot use the transaction
+        let response = send(
+            format!("/sql/transactions/{id}/query"),
+            "globex",
+            r#"{"sql": "SELECT 1"}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = send(format!("/sql/transactions/{id}/commit"), "acme", "")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = send(format!("/sql/transactions/{id}/commit"), "acme", "")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = send(
+            "/sql/query".to_string(),
+            "acme",
+            r#"{"sql": "SELECT * FROM orders"}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // =========================================================================
+    // KV Tenant Isolation Tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_kv_tenant_namespaces_and_quota() {
+        use crate::daemon::config::KvTenancy;
+        use crate::daemon::services::kv::{KvQuota, KvQuotas};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let data_dir: &'static std::path::Path = Box::leak(Box::new(temp_dir.keep()));
+        let mut config = DaemonConfig::default();
+        config.services.kv_tenancy = KvTenancy::NamespacePerTenant;
+        let kv = KvStore::memory().with_tenant_quotas(KvQuotas {
+            default: KvQuota { max_keys: Some(1) },
+            tenants: std::collections::HashMap::new(),
+        });
+        let app_state = Arc::new(RwLock::new(AppState {
+            store: StateStore::open(data_dir.join("state.redb")).unwrap(),
+            kv: Some(kv),
+            sql: None,
+            sql_tenants: None,
+            sql_transactions: DashMap::new(),
+            instance_usage: Arc::default(),
+            storage: None,
+            cron: CronScheduler::new(),
+            config,
+        }));
+        let app = Router::new()
+            .route("/kv", get(kv_list))
+            .route("/kv/{key}", put(kv_set))
+            .with_state(app_state);
+
+        let set = |key: &str, tenant: Option<&str>| {
+            let mut builder = Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/kv/{key}"))
+                .header("content-type", "application/json");
+            if let Some(tenant) = tenant {
+                builder = builder.header("X-Tenant-Id", tenant);
+            }
+            app.clone()
+                .oneshot(builder.body(Body::from(r#"{"value": "v"}"#)).unwrap())
+        };
+
+        assert_eq!(
+            set("a", None).await.unwrap().status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            set("a", Some("acme")).await.unwrap().status(),
+            StatusCode::OK
+        );
+        // Overwriting an existing key does not count against the quota
+        assert_eq!(
+            set("a", Some("acme")).await.unwrap().status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            set("a", Some("globex")).await.unwrap().status(),
+            StatusCode::OK
+        );
+
+        let response = set("b", Some("acme")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INSUFFICIENT_STORAGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code.as_deref(), Some("quota_exceeded"));
+
+        let request = Request::builder()
+            .uri("/kv")
+            .header("X-Tenant-Id", "acme")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: KvListResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list.keys, vec!["a"]);
+    }
+
     // =========================================================================
     // Error Handling Tests
     // =========================================================================
@@ -1404,7 +2755,7 @@ mod tests {
     #[tokio::test]
     #[ignore = "requires local SQLite setup"]
     async fn test_sql_invalid_query() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let request = Request::builder()
             .method(Method::POST)
@@ -1417,9 +2768,117 @@ mod tests {
         assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    #[tokio::test]
+    async fn test_instances_report_sampled_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StateStore::open(dir.path().join("state.redb")).unwrap();
+        let pid = std::process::id();
+        store
+            .save_instance(&Instance::new("app", 3000, pid, "mik.toml".into()))
+            .unwrap();
+        store
+            .save_instance(&Instance::new("restarted", 3001, pid, "mik.toml".into()))
+            .unwrap();
+
+        let sample = process::ResourceSample {
+            rss_bytes: 4096,
+            cpu_percent: 12.5,
+            open_fds: Some(7),
+        };
+        let usage = Arc::new(DashMap::new());
+        usage.insert("app".to_string(), (pid, sample));
+        // Sampled from the process before a restart
+        usage.insert("restarted".to_string(), (pid + 1, sample));
+
+        let app_state = Arc::new(RwLock::new(AppState {
+            store,
+            kv: None,
+            sql: None,
+            sql_tenants: None,
+            sql_transactions: DashMap::new(),
+            instance_usage: usage,
+            storage: None,
+            cron: CronScheduler::new(),
+            config: DaemonConfig::default(),
+        }));
+        let app = Router::new()
+            .route("/instances", get(list_instances))
+            .route("/instances/{name}", get(get_instance))
+            .with_state(app_state);
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/instances/app").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let instance: InstanceResponse = serde_json::from_slice(&body).unwrap();
+        let resources = instance.resources.unwrap();
+        assert_eq!(resources.rss_bytes, 4096);
+        assert_eq!(resources.open_fds, Some(7));
+        assert!(instance.uptime_secs.is_some());
+
+        let response = app
+            .oneshot(Request::get("/instances").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: ListInstancesResponse = serde_json::from_slice(&body).unwrap();
+        let restarted = list.instances.iter().find(|i| i.name == "restarted");
+        assert!(restarted.unwrap().resources.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_exited_instances_follow_restart_policy() {
+        use crate::daemon::process::{InstanceExit, RestartConfig, RestartPolicy};
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = StateStore::open(dir.path().join("state.redb")).unwrap();
+        let instance = |name: &str, restart: RestartConfig| Instance {
+            restart: Some(restart),
+            ..Instance::new(name, 3000, 1, "mik.toml".into())
+        };
+
+        // A clean exit under on-failure is a stop
+        let clean = instance(
+            "clean",
+            RestartConfig::with_policy(RestartPolicy::OnFailure),
+        );
+        handle_exited_instance(&store, clean, Some(InstanceExit::Code(0)), 5).await;
+        let clean = store.get_instance("clean").unwrap().unwrap();
+        assert_eq!(clean.status, Status::Stopped);
+        assert_eq!(clean.restart_history.len(), 1);
+        assert_eq!(clean.restart_history[0].exit, Some(InstanceExit::Code(0)));
+        assert!(clean.restart_history[0].restarted_at.is_none());
+
+        // A failure under never is recorded but left down
+        let never = instance("never", RestartConfig::default());
+        handle_exited_instance(&store, never, Some(InstanceExit::Signal(9)), 5).await;
+        let never = store.get_instance("never").unwrap().unwrap();
+        assert_eq!(never.status, Status::Crashed { exit_code: 137 });
+        assert_eq!(never.restart_count, 0);
+
+        // Out of retries under always
+        let exhausted = instance(
+            "exhausted",
+            RestartConfig {
+                max_retries: Some(0),
+                ..RestartConfig::with_policy(RestartPolicy::Always)
+            },
+        );
+        handle_exited_instance(&store, exhausted, Some(InstanceExit::Code(1)), 5).await;
+        let exhausted = store.get_instance("exhausted").unwrap().unwrap();
+        assert_eq!(exhausted.status, Status::Crashed { exit_code: 1 });
+        assert!(exhausted.restart_history[0].restarted_at.is_none());
+    }
+
     #[tokio::test]
     async fn test_kv_invalid_json() {
-        let app = create_test_app().await;
+        let app = create_test_app();
 
         let request = Request::builder()
             .method(Method::PUT)
@@ -1438,7 +2897,7 @@ mod tests {
     // =========================================================================
 
     /// Create a test app with the API key auth middleware layer.
-    async fn create_test_app_with_auth() -> Router {
+    fn create_test_app_with_auth() -> Router {
         let temp_dir = tempfile::tempdir().unwrap();
         let data_dir = temp_dir.path().to_path_buf();
         let data_dir: &'static std::path::Path = Box::leak(Box::new(data_dir));
@@ -1447,12 +2906,15 @@ mod tests {
         let kv = Some(KvStore::file(data_dir.join("kv.redb")).unwrap());
         let sql = Some(SqlService::file(data_dir.join("sql.db")).unwrap());
         let storage = Some(StorageService::file(data_dir.join("storage")).unwrap());
-        let cron = CronScheduler::new().await.unwrap();
+        let cron = CronScheduler::new();
 
         let app_state = Arc::new(RwLock::new(AppState {
             store,
             kv,
             sql,
+            sql_tenants: None,
+            sql_transactions: DashMap::new(),
+            instance_usage: Arc::default(),
             storage,
             cron,
             config: DaemonConfig::default(),
@@ -1469,7 +2931,7 @@ mod tests {
     #[tokio::test]
     async fn test_auth_health_endpoint_always_accessible() {
         // Health endpoint should always be accessible, even with auth enabled
-        let app = create_test_app_with_auth().await;
+        let app = create_test_app_with_auth();
 
         let request = Request::builder()
             .uri("/health")
@@ -1484,7 +2946,7 @@ mod tests {
     #[tokio::test]
     async fn test_auth_metrics_endpoint_always_accessible() {
         // Metrics endpoint should always be accessible for Prometheus scraping
-        let app = create_test_app_with_auth().await;
+        let app = create_test_app_with_auth();
 
         let request = Request::builder()
             .uri("/metrics")
@@ -0,0 +1,859 @@
+//! OpenAPI description of the daemon HTTP API.
+//!
+//! `GET /openapi.json` serves an OpenAPI 3.1 document covering every route
+//! in [`serve`](super::serve), for client generators and API explorers. It
+//! is written by hand next to the request and response types in
+//! [`types`](super::types); when adding a route or a field, update both.
+//!
+//! Like `/health` and `/metrics`, the document is served without an API key.
+
+use std::sync::LazyLock;
+
+use axum::Json;
+use serde_json::{Map, Value, json};
+
+/// The document, built once.
+static DOCUMENT: LazyLock<Value> = LazyLock::new(document);
+
+/// GET /openapi.json - OpenAPI document of this API.
+pub(crate) async fn openapi_json() -> Json<Value> {
+    Json(DOCUMENT.clone())
+}
+
+/// Reference to a schema in `components.schemas`.
+fn schema(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+/// Object schema with `properties`, of which `required` must be present.
+fn object(required: &[&str], properties: Value) -> Value {
+    let mut schema = json!({ "type": "object", "required": required });
+    schema["properties"] = properties;
+    schema
+}
+
+/// Nullable variant of a schema.
+fn nullable(schema: Value) -> Value {
+    let one_of = Value::Array(vec![schema, json!({ "type": "null" })]);
+    Value::Object(Map::from_iter([("oneOf".to_string(), one_of)]))
+}
+
+fn array(items: Value) -> Value {
+    let mut schema = json!({ "type": "array" });
+    schema["items"] = items;
+    schema
+}
+
+fn json_body(name: &str) -> Value {
+    json!({ "required": true, "content": { "application/json": { "schema": schema(name) } } })
+}
+
+fn json_response(description: &str, name: &str) -> Value {
+    json!({ "description": description, "content": { "application/json": { "schema": schema(name) } } })
+}
+
+fn empty_response(description: &str) -> Value {
+    json!({ "description": description })
+}
+
+fn path_param(name: &str, description: &str) -> Value {
+    json!({ "name": name, "in": "path", "required": true, "description": description, "schema": { "type": "string" } })
+}
+
+fn query_param(name: &str, kind: &str, description: &str) -> Value {
+    json!({ "name": name, "in": "query", "description": description, "schema": { "type": kind } })
+}
+
+fn tenant_header() -> Value {
+    json!({
+        "name": "X-Tenant-Id",
+        "in": "header",
+        "description": "Tenant the request is for; required with tenant isolation",
+        "schema": { "type": "string" },
+    })
+}
+
+/// An operation. `responses` get the shared error responses added.
+fn operation(tag: &str, id: &str, summary: &str, mut spec: Value) -> Value {
+    let fields = spec.as_object_mut().expect("operation spec is an object");
+    fields.insert("tags".into(), json!([tag]));
+    fields.insert("operationId".into(), json!(id));
+    fields.insert("summary".into(), json!(summary));
+    let responses = fields
+        .entry("responses")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .expect("responses is an object");
+    responses.insert(
+        "default".into(),
+        json!({ "$ref": "#/components/responses/Error" }),
+    );
+    spec
+}
+
+/// The OpenAPI document.
+#[allow(clippy::too_many_lines)] // One declarative table of every route
+pub fn document() -> Value {
+    let name = || path_param("name", "Instance name");
+    let key = || path_param("key", "Key");
+    let object_path = || path_param("path", "Object path (may contain `/`)");
+    let transaction = || path_param("id", "Transaction ID");
+    let job = || path_param("name", "Job name");
+
+    // In sections, to stay under the `json!` recursion limit
+    let sections = [
+        // Instances
+        json!({
+        "/instances": {
+            "get": operation("instances", "listInstances", "List instances", json!({
+                "responses": { "200": json_response("Instances", "ListInstancesResponse") },
+            })),
+            "post": operation("instances", "startInstance", "Start an instance", json!({
+                "requestBody": json_body("StartInstanceRequest"),
+                "responses": { "201": json_response("Started instance", "InstanceResponse") },
+            })),
+        },
+        "/instances/{name}": {
+            "get": operation("instances", "getInstance", "Get an instance", json!({
+                "parameters": [name()],
+                "responses": { "200": json_response("Instance", "InstanceResponse") },
+            })),
+            "delete": operation("instances", "stopInstance", "Stop an instance", json!({
+                "parameters": [name()],
+                "responses": { "200": json_response("Stopped instance", "InstanceResponse") },
+            })),
+        },
+        "/instances/{name}/restart": {
+            "post": operation("instances", "restartInstance", "Restart an instance", json!({
+                "parameters": [name()],
+                "responses": { "200": json_response("Restarted instance", "InstanceResponse") },
+            })),
+        },
+        "/instances/{name}/logs": {
+            "get": operation("instances", "getLogs", "Tail instance logs", json!({
+                "description": "With `follow=true`, streams new lines as Server-Sent Events (one `data:` line per log line) after the tail.",
+                "parameters": [
+                    name(),
+                    query_param("lines", "integer", "Number of lines (default: 50)"),
+                    query_param("follow", "boolean", "Stream new lines as Server-Sent Events"),
+                ],
+                "responses": { "200": {
+                    "description": "Log lines",
+                    "content": {
+                        "application/json": { "schema": schema("LogsResponse") },
+                        "text/event-stream": { "schema": { "type": "string" } },
+                    },
+                } },
+            })),
+        },
+        }),
+        // KV
+        json!({
+        "/kv": {
+            "get": operation("kv", "listKeys", "List keys", json!({
+                "description": "With `limit`, `cursor`, or `values`, returns one page in key order. With `watch=true`, streams changes as Server-Sent Events (`set`/`delete` events with a `KvWatchEvent` as data).",
+                "parameters": [
+                    query_param("prefix", "string", "Only keys starting with this prefix"),
+                    query_param("cursor", "string", "Continue after the page that returned this cursor"),
+                    query_param("limit", "integer", "Page size (default: 100, at most 1000)"),
+                    query_param("values", "boolean", "Include values in `entries`"),
+                    query_param("watch", "boolean", "Stream changes under `prefix`"),
+                    tenant_header(),
+                ],
+                "responses": { "200": {
+                    "description": "Keys",
+                    "content": {
+                        "application/json": { "schema": schema("KvListResponse") },
+                        "text/event-stream": { "schema": { "type": "string" } },
+                    },
+                } },
+            })),
+            "post": operation("kv", "kvTransaction", "Apply writes atomically", json!({
+                "parameters": [tenant_header()],
+                "requestBody": json_body("KvTransactionRequest"),
+                "responses": {
+                    "200": empty_response("Applied"),
+                    "409": { "$ref": "#/components/responses/Error" },
+                    "507": { "$ref": "#/components/responses/Error" },
+                },
+            })),
+        },
+        "/kv/{key}": {
+            "get": operation("kv", "getKey", "Get a value", json!({
+                "parameters": [key(), tenant_header()],
+                "responses": { "200": json_response("Value", "KvGetResponse") },
+            })),
+            "put": operation("kv", "setKey", "Set a value", json!({
+                "parameters": [key(), tenant_header()],
+                "requestBody": json_body("KvSetRequest"),
+                "responses": {
+                    "200": empty_response("Set"),
+                    "507": { "$ref": "#/components/responses/Error" },
+                },
+            })),
+            "delete": operation("kv", "deleteKey", "Delete a key", json!({
+                "parameters": [key(), tenant_header()],
+                "responses": { "204": empty_response("Deleted") },
+            })),
+        },
+        "/kv-batch/get": {
+            "post": operation("kv", "batchGet", "Get several values", json!({
+                "parameters": [tenant_header()],
+                "requestBody": json_body("KvBatchKeysRequest"),
+                "responses": { "200": json_response("Values", "KvBatchGetResponse") },
+            })),
+        },
+        "/kv-batch/set": {
+            "post": operation("kv", "batchSet", "Set several values atomically", json!({
+                "parameters": [tenant_header()],
+                "requestBody": json_body("KvBatchSetRequest"),
+                "responses": {
+                    "200": empty_response("Set"),
+                    "507": { "$ref": "#/components/responses/Error" },
+                },
+            })),
+        },
+        "/kv-batch/delete": {
+            "post": operation("kv", "batchDelete", "Delete several keys", json!({
+                "parameters": [tenant_header()],
+                "requestBody": json_body("KvBatchKeysRequest"),
+                "responses": { "200": json_response("Deleted keys", "KvBatchDeleteResponse") },
+            })),
+        },
+        }),
+        // SQL
+        json!({
+        "/sql/query": {
+            "post": operation("sql", "sqlQuery", "Run a query", json!({
+                "parameters": [tenant_header()],
+                "requestBody": json_body("SqlQueryRequest"),
+                "responses": { "200": json_response("Rows", "SqlQueryResponse") },
+            })),
+        },
+        "/sql/stream": {
+            "post": operation("sql", "sqlStream", "Run a query, streaming rows", json!({
+                "description": "NDJSON: a `{\"columns\": [...]}` line, then one array of values per row. An error after the first row ends the stream with an `{\"error\": \"...\"}` line.",
+                "parameters": [tenant_header()],
+                "requestBody": json_body("SqlQueryRequest"),
+                "responses": { "200": {
+                    "description": "Rows as NDJSON",
+                    "content": { "application/x-ndjson": { "schema": { "type": "string" } } },
+                } },
+            })),
+        },
+        "/sql/execute": {
+            "post": operation("sql", "sqlExecute", "Run a statement", json!({
+                "parameters": [tenant_header()],
+                "requestBody": json_body("SqlExecuteRequest"),
+                "responses": { "200": json_response("Affected rows", "SqlExecuteResponse") },
+            })),
+        },
+        "/sql/batch": {
+            "post": operation("sql", "sqlBatch", "Run statements atomically", json!({
+                "parameters": [tenant_header()],
+                "requestBody": json_body("SqlBatchRequest"),
+                "responses": { "200": json_response("Affected rows per statement", "SqlBatchResponse") },
+            })),
+        },
+        "/sql/transactions": {
+            "post": operation("sql", "sqlBegin", "Begin a transaction", json!({
+                "parameters": [tenant_header()],
+                "responses": { "201": json_response("Transaction", "SqlTransactionResponse") },
+            })),
+        },
+        "/sql/transactions/{id}/query": {
+            "post": operation("sql", "sqlTransactionQuery", "Run a query in a transaction", json!({
+                "parameters": [transaction(), tenant_header()],
+                "requestBody": json_body("SqlQueryRequest"),
+                "responses": { "200": json_response("Rows", "SqlQueryResponse") },
+            })),
+        },
+        "/sql/transactions/{id}/execute": {
+            "post": operation("sql", "sqlTransactionExecute", "Run a statement in a transaction", json!({
+                "parameters": [transaction(), tenant_header()],
+                "requestBody": json_body("SqlExecuteRequest"),
+                "responses": { "200": json_response("Affected rows", "SqlExecuteResponse") },
+            })),
+        },
+        "/sql/transactions/{id}/commit": {
+            "post": operation("sql", "sqlCommit", "Commit a transaction", json!({
+                "parameters": [transaction(), tenant_header()],
+                "responses": { "204": empty_response("Committed") },
+            })),
+        },
+        "/sql/transactions/{id}/rollback": {
+            "post": operation("sql", "sqlRollback", "Roll back a transaction", json!({
+                "parameters": [transaction(), tenant_header()],
+                "responses": { "204": empty_response("Rolled back") },
+            })),
+        },
+        }),
+        // Storage
+        json!({
+        "/storage": {
+            "get": operation("storage", "listObjects", "List objects", json!({
+                "description": "With `watch=true`, streams changes as Server-Sent Events (`ObjectCreated:Put`/`ObjectUpdated:Put`/`ObjectRemoved:Delete` events with a `StorageEvent` as data).",
+                "parameters": [
+                    query_param("prefix", "string", "Only objects under this prefix"),
+                    query_param("limit", "integer", "Maximum number of objects"),
+                    query_param("expired", "boolean", "Only objects lifecycle rules would delete now"),
+                    query_param("watch", "boolean", "Stream changes under `prefix`"),
+                    tenant_header(),
+                ],
+                "responses": { "200": {
+                    "description": "Objects",
+                    "content": {
+                        "application/json": { "schema": schema("StorageListResponse") },
+                        "text/event-stream": { "schema": { "type": "string" } },
+                    },
+                } },
+            })),
+        },
+        "/storage/{path}": {
+            "get": operation("storage", "getObject", "Download an object", json!({
+                "parameters": [
+                    object_path(),
+                    tenant_header(),
+                    query_param("expires", "integer", "Presigned URL expiry (Unix seconds)"),
+                    query_param("signature", "string", "Presigned URL signature"),
+                ],
+                "responses": {
+                    "200": {
+                        "description": "Object content",
+                        "content": { "*/*": { "schema": { "type": "string", "format": "binary" } } },
+                    },
+                    "304": empty_response("Not modified (If-None-Match)"),
+                },
+            })),
+            "put": operation("storage", "putObject", "Upload an object", json!({
+                "parameters": [
+                    object_path(),
+                    tenant_header(),
+                    query_param("expires", "integer", "Presigned URL expiry (Unix seconds)"),
+                    query_param("signature", "string", "Presigned URL signature"),
+                ],
+                "requestBody": {
+                    "required": true,
+                    "content": { "*/*": { "schema": { "type": "string", "format": "binary" } } },
+                },
+                "responses": { "201": empty_response("Stored; the ETag header identifies the content") },
+            })),
+            "delete": operation("storage", "deleteObject", "Delete an object", json!({
+                "parameters": [object_path(), tenant_header()],
+                "responses": { "204": empty_response("Deleted") },
+            })),
+            "head": operation("storage", "headObject", "Get object metadata", json!({
+                "parameters": [object_path(), tenant_header()],
+                "responses": { "200": empty_response("Content type, length, and ETag headers") },
+            })),
+        },
+        "/presign/{path}": {
+            "post": operation("storage", "presignObject", "Create a presigned URL", json!({
+                "parameters": [object_path(), tenant_header()],
+                "requestBody": json_body("PresignRequest"),
+                "responses": { "200": json_response("Presigned URL", "PresignResponse") },
+            })),
+        },
+        "/storage-usage": {
+            "get": operation("storage", "storageUsage", "Storage usage per tenant", json!({
+                "parameters": [tenant_header()],
+                "responses": { "200": json_response("Usage", "StorageUsageResponse") },
+            })),
+        },
+        }),
+        // Cron
+        json!({
+        "/cron": {
+            "get": operation("cron", "listCronJobs", "List cron jobs", json!({
+                "responses": { "200": json_response("Jobs", "ListCronJobsResponse") },
+            })),
+            "post": operation("cron", "createCronJob", "Create a cron job", json!({
+                "requestBody": json_body("CronCreateRequest"),
+                "responses": { "200": json_response("Created job", "CronCreateResponse") },
+            })),
+        },
+        "/cron/{name}": {
+            "get": operation("cron", "getCronJob", "Get a cron job", json!({
+                "parameters": [job()],
+                "responses": { "200": json_response("Job", "CronJobResponse") },
+            })),
+            "patch": operation("cron", "updateCronJob", "Pause or resume a cron job", json!({
+                "parameters": [job()],
+                "requestBody": json_body("CronUpdateRequest"),
+                "responses": { "200": json_response("Updated job", "CronUpdateResponse") },
+            })),
+            "delete": operation("cron", "deleteCronJob", "Delete a cron job", json!({
+                "parameters": [job()],
+                "responses": { "200": json_response("Deleted job", "CronDeleteResponse") },
+            })),
+        },
+        "/cron/{name}/trigger": {
+            "post": operation("cron", "triggerCronJob", "Run a cron job now", json!({
+                "parameters": [job()],
+                "responses": { "200": json_response("Triggered run", "CronTriggerResponse") },
+            })),
+        },
+        "/cron/{name}/history": {
+            "get": operation("cron", "cronHistory", "Execution history of a cron job", json!({
+                "parameters": [job()],
+                "responses": { "200": json_response("Executions", "CronHistoryResponse") },
+            })),
+        },
+        }),
+        // System
+        json!({
+        "/health": {
+            "get": operation("system", "health", "Health check", json!({
+                "security": [],
+                "responses": { "200": json_response("Healthy", "HealthResponse") },
+            })),
+        },
+        "/version": {
+            "get": operation("system", "version", "Daemon version", json!({
+                "responses": { "200": json_response("Version", "VersionResponse") },
+            })),
+        },
+        "/metrics": {
+            "get": operation("system", "metrics", "Prometheus metrics", json!({
+                "security": [],
+                "responses": { "200": {
+                    "description": "Metrics in the Prometheus text format",
+                    "content": { "text/plain": { "schema": { "type": "string" } } },
+                } },
+            })),
+        },
+        "/openapi.json": {
+            "get": operation("system", "openapi", "This document", json!({
+                "security": [],
+                "responses": { "200": {
+                    "description": "OpenAPI document",
+                    "content": { "application/json": { "schema": { "type": "object" } } },
+                } },
+            })),
+        },
+        }),
+    ];
+    let paths: Map<String, Value> = sections
+        .into_iter()
+        .filter_map(|section| match section {
+            Value::Object(paths) => Some(paths),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "mik daemon API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Instance management and embedded services (KV, SQL, storage, cron) of the mik daemon.",
+        },
+        "servers": [{ "url": "http://127.0.0.1:9919" }],
+        "security": [{ "apiKey": [] }],
+        "tags": [
+            { "name": "instances" },
+            { "name": "kv" },
+            { "name": "sql" },
+            { "name": "storage" },
+            { "name": "cron" },
+            { "name": "system" },
+        ],
+        "paths": paths,
+        "components": {
+            "securitySchemes": {
+                "apiKey": {
+                    "type": "apiKey",
+                    "in": "header",
+                    "name": "X-API-Key",
+                    "description": "Required when the daemon runs with MIK_API_KEY set",
+                },
+            },
+            "responses": {
+                "Error": json_response("Error", "ErrorResponse"),
+            },
+            "schemas": schemas(),
+        },
+    })
+}
+
+/// Schemas of the request and response types.
+#[allow(clippy::too_many_lines)] // One schema per type in `types`
+fn schemas() -> Map<String, Value> {
+    let string = || json!({ "type": "string" });
+    let integer = || json!({ "type": "integer" });
+    let boolean = || json!({ "type": "boolean" });
+    let any = || json!({});
+    let sql_value = || json!({ "type": ["string", "number", "integer", "boolean", "null"] });
+    let string_map = |values: Value| json!({ "type": "object", "additionalProperties": values });
+    let overlap = || json!({ "type": "string", "enum": ["allow", "skip", "queue"] });
+
+    // In sections, to stay under the `json!` recursion limit
+    [
+        json!({
+            "ErrorResponse": object(&["error"], json!({ "error": string(), "code": string() })),
+            "HealthResponse": object(&["status", "uptime"], json!({ "status": string(), "uptime": string() })),
+            "VersionResponse": object(&["version", "build"], json!({ "version": string(), "build": string() })),
+        }),
+        json!({
+            "StartInstanceRequest": object(&["name"], json!({
+                "name": string(),
+                "port": integer(),
+                "config": string(),
+                "working_dir": string(),
+                "auto_restart": boolean(),
+                "restart": schema("RestartConfig"),
+            })),
+            "InstanceResponse": object(&["name", "port", "pid", "status", "restart", "restart_count"], json!({
+                "name": string(),
+                "port": integer(),
+                "pid": integer(),
+                "status": string(),
+                "started_at": string(),
+                "uptime": string(),
+                "uptime_secs": integer(),
+                "resources": schema("InstanceResources"),
+                "restart": schema("RestartConfig"),
+                "restart_count": integer(),
+                "restart_history": array(schema("RestartEvent")),
+            })),
+            "RestartConfig": object(&["policy", "backoff_secs", "max_backoff_secs"], json!({
+                "policy": { "type": "string", "enum": ["never", "on-failure", "always"] },
+                "max_retries": integer(),
+                "backoff_secs": integer(),
+                "max_backoff_secs": integer(),
+            })),
+            "RestartEvent": object(&["at", "reason"], json!({
+                "at": string(),
+                "reason": { "type": "string", "enum": ["exited", "memory_ceiling"] },
+                "exit": {
+                    "type": "object",
+                    "description": "`{\"code\": N}` or `{\"signal\": N}`",
+                    "properties": { "code": integer(), "signal": integer() },
+                },
+                "restarted_at": string(),
+            })),
+            "InstanceResources": object(&["cpu_percent", "rss_bytes"], json!({
+                "cpu_percent": { "type": "number" },
+                "rss_bytes": integer(),
+                "open_fds": integer(),
+            })),
+            "ListInstancesResponse": object(&["instances"], json!({
+                "instances": array(schema("InstanceResponse")),
+            })),
+            "LogsResponse": object(&["name", "lines"], json!({
+                "name": string(),
+                "lines": array(string()),
+            })),
+        }),
+        json!({
+            "KvSetRequest": object(&["value"], json!({
+                "value": string(),
+                "ttl": integer(),
+            })),
+            "KvGetResponse": object(&["key", "value"], json!({ "key": string(), "value": string() })),
+            "KvListResponse": object(&["keys"], json!({
+                "keys": array(string()),
+                "entries": array(schema("KvGetResponse")),
+                "cursor": string(),
+            })),
+            "KvWatchEvent": object(&["key", "time"], json!({
+                "key": string(),
+                "value": string(),
+                "time": string(),
+            })),
+            "KvBatchKeysRequest": object(&["keys"], json!({ "keys": array(string()) })),
+            "KvBatchGetResponse": object(&["values"], json!({ "values": string_map(nullable(string())) })),
+            "KvBatchSetRequest": object(&["entries"], json!({
+                "entries": string_map(string()),
+                "ttl": integer(),
+            })),
+            "KvBatchDeleteResponse": object(&["deleted"], json!({ "deleted": integer() })),
+            "KvTransactionRequest": object(&["writes"], json!({
+                "expect": string_map(nullable(string())),
+                "writes": array(schema("KvTransactionWrite")),
+            })),
+            "KvTransactionWrite": object(&["key"], json!({
+                "key": string(),
+                "value": nullable(string()),
+                "ttl": integer(),
+            })),
+        }),
+        json!({
+            "SqlQueryRequest": object(&["sql"], json!({
+                "sql": string(),
+                "params": array(sql_value()),
+            })),
+            "SqlExecuteRequest": object(&["sql"], json!({
+                "sql": string(),
+                "params": array(sql_value()),
+            })),
+            "SqlQueryResponse": object(&["columns", "rows"], json!({
+                "columns": array(string()),
+                "rows": array(array(sql_value())),
+            })),
+            "SqlExecuteResponse": object(&["rows_affected"], json!({ "rows_affected": integer() })),
+            "SqlBatchRequest": object(&["statements"], json!({
+                "statements": array(schema("SqlExecuteRequest")),
+            })),
+            "SqlBatchResponse": object(&["results"], json!({
+                "results": array(schema("SqlExecuteResponse")),
+            })),
+            "SqlTransactionResponse": object(&["id"], json!({ "id": string() })),
+        }),
+        json!({
+            "StorageObjectInfo": object(&["path", "size", "content_type", "created_at", "updated_at"], json!({
+                "path": string(),
+                "size": integer(),
+                "content_type": string(),
+                "created_at": string(),
+                "updated_at": string(),
+                "expires_at": string(),
+                "etag": string(),
+            })),
+            "StorageListResponse": object(&["objects"], json!({
+                "objects": array(schema("StorageObjectInfo")),
+            })),
+            "StorageEvent": object(&["kind", "path", "size", "time"], json!({
+                "kind": { "type": "string", "enum": ["created", "updated", "deleted"] },
+                "path": string(),
+                "size": integer(),
+                "etag": string(),
+                "time": string(),
+            })),
+            "PresignRequest": object(&["method"], json!({
+                "method": { "type": "string", "enum": ["GET", "PUT"] },
+                "expires_in_secs": integer(),
+            })),
+            "PresignResponse": object(&["url", "method", "expires_at"], json!({
+                "url": string(),
+                "method": { "type": "string", "enum": ["GET", "PUT"] },
+                "expires_at": string(),
+            })),
+            "TenantStorageUsage": object(&["tenant_id", "objects", "bytes"], json!({
+                "tenant_id": string(),
+                "objects": integer(),
+                "bytes": integer(),
+                "max_bytes": integer(),
+                "max_objects": integer(),
+            })),
+            "StorageUsageResponse": object(&["tenants"], json!({
+                "tenants": array(schema("TenantStorageUsage")),
+            })),
+        }),
+        json!({
+            "CronCreateRequest": object(&["name", "cron"], json!({
+                "name": string(),
+                "type": { "type": "string", "enum": ["http", "backup", "sql-backup"] },
+                "module": string(),
+                "cron": string(),
+                "timezone": string(),
+                "method": string(),
+                "path": string(),
+                "enabled": boolean(),
+                "port": integer(),
+                "body": any(),
+                "headers": string_map(string()),
+                "health_path": string(),
+                "backup": { "type": "object" },
+                "sql_backup": { "type": "object" },
+                "overlap": overlap(),
+                "jitter_secs": integer(),
+            })),
+            "CronCreateResponse": object(&["name", "created"], json!({ "name": string(), "created": boolean() })),
+            "CronUpdateRequest": object(&[], json!({ "enabled": boolean() })),
+            "CronUpdateResponse": object(&["name", "enabled", "updated"], json!({
+                "name": string(),
+                "enabled": boolean(),
+                "updated": boolean(),
+            })),
+            "CronDeleteResponse": object(&["name", "deleted"], json!({ "name": string(), "deleted": boolean() })),
+            "CronJobResponse": object(
+                &["name", "cron", "module", "method", "path", "enabled", "status", "last_run", "next_run", "overlap"],
+                json!({
+                    "name": string(),
+                    "cron": string(),
+                    "timezone": string(),
+                    "module": string(),
+                    "method": string(),
+                    "path": string(),
+                    "enabled": boolean(),
+                    "status": string(),
+                    "last_run": nullable(string()),
+                    "next_run": nullable(string()),
+                    "overlap": overlap(),
+                    "jitter_secs": integer(),
+                    "last_skip_at": string(),
+                    "last_skip_reason": string(),
+                }),
+            ),
+            "CronLeaderResponse": object(&["node_id", "is_leader", "current_leader"], json!({
+                "node_id": string(),
+                "is_leader": boolean(),
+                "current_leader": nullable(string()),
+            })),
+            "ListCronJobsResponse": object(&["jobs"], json!({
+                "jobs": array(schema("CronJobResponse")),
+                "leader": schema("CronLeaderResponse"),
+            })),
+            "CronExecutionInfo": object(
+                &["id", "started_at", "completed_at", "duration_ms", "success", "error", "manual"],
+                json!({
+                    "id": string(),
+                    "started_at": string(),
+                    "completed_at": nullable(string()),
+                    "duration_ms": nullable(integer()),
+                    "success": boolean(),
+                    "error": nullable(string()),
+                    "manual": boolean(),
+                    "output": string(),
+                }),
+            ),
+            "CronHistoryResponse": object(&["job_name", "executions"], json!({
+                "job_name": string(),
+                "executions": array(schema("CronExecutionInfo")),
+            })),
+            "CronTriggerResponse": object(&["job_name", "execution_id", "triggered"], json!({
+                "job_name": string(),
+                "execution_id": string(),
+                "triggered": boolean(),
+            })),
+        }),
+    ]
+    .into_iter()
+    .filter_map(|section| match section {
+        Value::Object(schemas) => Some(schemas),
+        _ => None,
+    })
+    .flatten()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Every `$ref` in `value`.
+    fn refs<'a>(value: &'a Value, found: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(target)) = map.get("$ref") {
+                    found.push(target);
+                }
+                map.values().for_each(|v| refs(v, found));
+            },
+            Value::Array(items) => items.iter().for_each(|v| refs(v, found)),
+            _ => {},
+        }
+    }
+
+    #[test]
+    fn test_references_resolve() {
+        let document = document();
+        let mut found = Vec::new();
+        refs(&document, &mut found);
+        assert!(!found.is_empty());
+        for target in found {
+            let pointer = target.strip_prefix('#').unwrap();
+            assert!(
+                document.pointer(pointer).is_some(),
+                "unresolved reference {target}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_operation_ids_are_unique() {
+        let document = document();
+        let mut ids = HashSet::new();
+        for methods in document["paths"].as_object().unwrap().values() {
+            for operation in methods.as_object().unwrap().values() {
+                let id = operation["operationId"].as_str().unwrap();
+                assert!(ids.insert(id), "duplicate operationId {id}");
+            }
+        }
+        assert!(ids.contains("listInstances"));
+        assert!(ids.contains("createCronJob"));
+    }
+
+    #[test]
+    fn test_schemas_match_serialized_types() {
+        use std::path::PathBuf;
+
+        use crate::daemon::http::types::{CronExecutionInfo, InstanceResponse, StorageObjectInfo};
+        use crate::daemon::process::{InstanceExit, RestartConfig, RestartPolicy};
+        use crate::daemon::state::{Instance, RestartEvent, RestartReason};
+
+        // Required fields are the ones the types always serialize
+        let schemas = schemas();
+        let check = |name: &str, value: Value| {
+            let required = schemas[name]["required"].as_array().unwrap();
+            let properties = schemas[name]["properties"].as_object().unwrap();
+            let serialized = value.as_object().unwrap();
+            for field in required {
+                assert!(
+                    serialized.contains_key(field.as_str().unwrap()),
+                    "{name}.{field}"
+                );
+            }
+            for field in serialized.keys() {
+                assert!(
+                    properties.contains_key(field),
+                    "{name}.{field} undocumented"
+                );
+            }
+        };
+
+        check(
+            "CronExecutionInfo",
+            serde_json::to_value(CronExecutionInfo {
+                id: "1".into(),
+                started_at: "2026-10-14T00:00:00Z".into(),
+                completed_at: None,
+                duration_ms: None,
+                success: true,
+                error: None,
+                manual: false,
+                output: Some("ok".into()),
+            })
+            .unwrap(),
+        );
+        check(
+            "StorageObjectInfo",
+            serde_json::to_value(StorageObjectInfo {
+                path: "a.txt".into(),
+                size: 1,
+                content_type: "text/plain".into(),
+                created_at: "2026-10-14T00:00:00Z".into(),
+                updated_at: "2026-10-14T00:00:00Z".into(),
+                expires_at: Some("2026-10-15T00:00:00Z".into()),
+                etag: Some("abc".into()),
+            })
+            .unwrap(),
+        );
+        check(
+            "RestartConfig",
+            serde_json::to_value(RestartConfig {
+                max_retries: Some(3),
+                ..RestartConfig::with_policy(RestartPolicy::OnFailure)
+            })
+            .unwrap(),
+        );
+        check(
+            "RestartEvent",
+            serde_json::to_value(RestartEvent {
+                at: chrono::Utc::now(),
+                reason: RestartReason::Exited,
+                exit: Some(InstanceExit::Code(1)),
+                restarted_at: Some(chrono::Utc::now()),
+            })
+            .unwrap(),
+        );
+        let instance = Instance::new("default", 3000, 1, PathBuf::from("mik.toml"));
+        check(
+            "InstanceResponse",
+            serde_json::to_value(InstanceResponse::from(&instance)).unwrap(),
+        );
+    }
+}
@@ -0,0 +1,842 @@
+//! S3-compatible API for the storage service.
+//!
+//! Lets S3 SDKs and tools such as rclone or the AWS CLI use the embedded
+//! storage. The API is served on its own port with path-style addressing:
+//!
+//! - `GET /` - ListBuckets
+//! - `GET /:bucket?list-type=2` - ListObjectsV2 (ListObjects v1 without `list-type`)
+//! - `HEAD /:bucket`, `PUT /:bucket` - HeadBucket, CreateBucket (no-ops)
+//! - `GET /:bucket/*key` - GetObject
+//! - `PUT /:bucket/*key` - PutObject
+//! - `DELETE /:bucket/*key` - DeleteObject
+//! - `HEAD /:bucket/*key` - HeadObject
+//!
+//! Buckets are not stored separately: bucket `photos` is the `photos/`
+//! prefix of the storage service, so objects written over S3 are visible at
+//! `/storage/photos/...` and vice versa. Every request must be signed with
+//! AWS Signature Version 4 using the `MIK_S3_ACCESS_KEY_ID` and
+//! `MIK_S3_SECRET_ACCESS_KEY` key pair.
+//!
+//! `If-Match` and `If-None-Match` are honored on GetObject, HeadObject, and
+//! PutObject. Multipart uploads, copies, ranges, versioning, ACLs, and chunked
+//! (`aws-chunked`) payload signing are not supported.
+
+mod sigv4;
+mod xml;
+
+use axum::{
+    Extension, Router,
+    body::Body,
+    extract::{Path, Query, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use percent_encoding::utf8_percent_encode;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::sync::Arc;
+
+use crate::daemon::backup::s3::URI_ENCODE;
+use crate::daemon::metrics;
+use crate::daemon::services::storage::{
+    ObjectMeta, ObjectStream, PreconditionFailed, ReadPrecondition, StorageService,
+};
+
+use super::handlers::storage::{MAX_OBJECT_SIZE, etag_header, limited_body, preconditions};
+use sigv4::PayloadHash;
+pub use sigv4::Verifier;
+use xml::XmlWriter;
+
+/// Most keys a list request returns, as in S3.
+const MAX_LIST_KEYS: usize = 1000;
+
+/// Query parameters selecting S3 features this API does not implement.
+const UNSUPPORTED_SUBRESOURCES: &[&str] = &[
+    "acl",
+    "cors",
+    "lifecycle",
+    "policy",
+    "tagging",
+    "uploadId",
+    "uploads",
+    "versionId",
+    "versioning",
+    "versions",
+];
+
+type S3State = Arc<S3Context>;
+
+/// State shared by the S3 handlers.
+struct S3Context {
+    storage: StorageService,
+    verifier: Verifier,
+}
+
+/// Builds the S3 API router over a storage service.
+pub(crate) fn router(storage: StorageService, verifier: Verifier) -> Router {
+    let state: S3State = Arc::new(S3Context { storage, verifier });
+    let bucket = get(list_objects)
+        .head(head_bucket)
+        .put(create_bucket)
+        .fallback(unsupported);
+
+    Router::new()
+        .route("/", get(list_buckets).fallback(unsupported))
+        .route("/{bucket}", bucket.clone())
+        .route("/{bucket}/", bucket)
+        .route(
+            "/{bucket}/{*key}",
+            get(get_object)
+                .put(put_object)
+                .delete(delete_object)
+                .head(head_object)
+                .fallback(unsupported),
+        )
+        .fallback(unsupported)
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            sigv4_auth_middleware,
+        ))
+        .layer(middleware::from_fn(super::metrics_middleware))
+        .with_state(state)
+}
+
+// =============================================================================
+// Errors
+// =============================================================================
+
+/// An S3 error response (`<Error><Code>...</Code>...</Error>`).
+#[derive(Debug)]
+pub struct S3Error {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+impl S3Error {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn access_denied(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "AccessDenied", message)
+    }
+
+    fn malformed_authorization(message: impl Into<String>) -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "AuthorizationHeaderMalformed",
+            message,
+        )
+    }
+
+    fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "InvalidRequest", message)
+    }
+
+    fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "InvalidArgument", message)
+    }
+
+    fn not_implemented(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_IMPLEMENTED, "NotImplemented", message)
+    }
+
+    fn no_such_key(key: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "NoSuchKey",
+            format!("The specified key '{key}' does not exist"),
+        )
+    }
+
+    fn precondition_failed() -> Self {
+        Self::new(
+            StatusCode::PRECONDITION_FAILED,
+            "PreconditionFailed",
+            "At least one of the preconditions you specified did not hold",
+        )
+    }
+
+    fn entity_too_large() -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "EntityTooLarge",
+            format!("Object exceeds maximum size of {MAX_OBJECT_SIZE} bytes"),
+        )
+    }
+}
+
+impl IntoResponse for S3Error {
+    fn into_response(self) -> Response {
+        let mut xml = XmlWriter::new("Error");
+        xml.field("Code", self.code).field("Message", &self.message);
+        (
+            self.status,
+            [(header::CONTENT_TYPE, "application/xml")],
+            xml.finish(),
+        )
+            .into_response()
+    }
+}
+
+impl From<anyhow::Error> for S3Error {
+    fn from(err: anyhow::Error) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "InternalError",
+            err.to_string(),
+        )
+    }
+}
+
+// =============================================================================
+// Authentication
+// =============================================================================
+
+/// Rejects requests without a valid SigV4 signature.
+///
+/// The payload hash the client signed is passed on to the handlers as a
+/// request extension, so uploads can be checked against it.
+async fn sigv4_auth_middleware(
+    State(state): State<S3State>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match state.verifier.verify(
+        request.method(),
+        request.uri(),
+        request.headers(),
+        Utc::now(),
+    ) {
+        Ok(payload) => {
+            request.extensions_mut().insert(payload);
+            next.run(request).await
+        },
+        Err(err) => {
+            tracing::warn!(
+                code = err.code,
+                path = %request.uri().path(),
+                "Rejected S3 request"
+            );
+            err.into_response()
+        },
+    }
+}
+
+// =============================================================================
+// Buckets
+// =============================================================================
+
+/// GET / - List buckets (top-level storage prefixes).
+async fn list_buckets(State(state): State<S3State>) -> Result<Response, S3Error> {
+    metrics::record_storage_operation("list", None);
+    let mut buckets: BTreeMap<String, DateTime<Utc>> = BTreeMap::new();
+    for meta in state.storage.list_objects(None).await? {
+        let Some((bucket, _)) = meta.path.split_once('/') else {
+            continue;
+        };
+        if is_valid_bucket(bucket) {
+            let created = buckets.entry(bucket.to_string()).or_insert(meta.created_at);
+            *created = (*created).min(meta.created_at);
+        }
+    }
+
+    let mut xml = XmlWriter::new("ListAllMyBucketsResult");
+    xml.open("Owner")
+        .field("ID", "mik")
+        .field("DisplayName", "mik")
+        .close("Owner")
+        .open("Buckets");
+    for (name, created) in &buckets {
+        xml.open("Bucket")
+            .field("Name", name)
+            .field("CreationDate", iso_timestamp(*created))
+            .close("Bucket");
+    }
+    xml.close("Buckets");
+    Ok(xml_response(xml))
+}
+
+/// HEAD /:bucket - Buckets always exist once their name is valid.
+async fn head_bucket(
+    State(state): State<S3State>,
+    Path(bucket): Path<String>,
+) -> Result<Response, S3Error> {
+    check_bucket(&bucket)?;
+    Ok((
+        StatusCode::OK,
+        [("x-amz-bucket-region", state.verifier.region().to_string())],
+    )
+        .into_response())
+}
+
+/// PUT /:bucket - Accepted without effect, buckets are implicit.
+async fn create_bucket(Path(bucket): Path<String>) -> Result<Response, S3Error> {
+    check_bucket(&bucket)?;
+    Ok((StatusCode::OK, [(header::LOCATION, format!("/{bucket}"))]).into_response())
+}
+
+/// GET /:bucket - ListObjectsV2, or ListObjects with no `list-type`.
+///
+/// `?location` answers GetBucketLocation with the configured region.
+async fn list_objects(
+    State(state): State<S3State>,
+    Path(bucket): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Response, S3Error> {
+    check_bucket(&bucket)?;
+    check_subresources(&params)?;
+    if params.contains_key("location") {
+        let mut xml = XmlWriter::new("LocationConstraint");
+        xml.text(state.verifier.region());
+        return Ok(xml_response(xml));
+    }
+
+    metrics::record_storage_operation("list", None);
+    let param = |name: &str| params.get(name).map(String::as_str);
+    let v2 = param("list-type") == Some("2");
+    let prefix = param("prefix").unwrap_or_default();
+    let delimiter = param("delimiter").unwrap_or_default();
+    let url_encoded = param("encoding-type") == Some("url");
+    let max_keys = match param("max-keys") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| S3Error::invalid_argument("max-keys must be a non-negative integer"))?
+            .min(MAX_LIST_KEYS),
+        None => MAX_LIST_KEYS,
+    };
+    let after = if v2 {
+        match param("continuation-token") {
+            Some(token) => Some(decode_token(token)?),
+            None => param("start-after").map(String::from),
+        }
+    } else {
+        param("marker").map(String::from)
+    };
+
+    let bucket_prefix = format!("{bucket}/");
+    let mut entries: Vec<(String, ObjectMeta)> = state
+        .storage
+        .list_objects(Some(&format!("{bucket_prefix}{prefix}")))
+        .await?
+        .into_iter()
+        .filter_map(|meta| {
+            let key = meta.path.strip_prefix(&bucket_prefix)?.to_string();
+            key.starts_with(prefix).then_some((key, meta))
+        })
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let page = paginate(entries, prefix, delimiter, max_keys, after.as_deref());
+
+    let encode = |value: &str| {
+        if url_encoded {
+            utf8_percent_encode(value, URI_ENCODE).to_string()
+        } else {
+            value.to_string()
+        }
+    };
+    let mut xml = XmlWriter::new("ListBucketResult");
+    xml.field("Name", &bucket)
+        .field("Prefix", encode(prefix))
+        .field("MaxKeys", max_keys)
+        .field("IsTruncated", page.next.is_some());
+    if !delimiter.is_empty() {
+        xml.field("Delimiter", encode(delimiter));
+    }
+    if url_encoded {
+        xml.field("EncodingType", "url");
+    }
+    if v2 {
+        xml.field("KeyCount", page.contents.len() + page.common_prefixes.len());
+        if let Some(token) = param("continuation-token") {
+            xml.field("ContinuationToken", token);
+        }
+        if let Some(start_after) = param("start-after") {
+            xml.field("StartAfter", encode(start_after));
+        }
+        if let Some(next) = &page.next {
+            xml.field("NextContinuationToken", hex::encode(next));
+        }
+    } else {
+        xml.field("Marker", encode(param("marker").unwrap_or_default()));
+        if let Some(next) = &page.next {
+            xml.field("NextMarker", encode(next));
+        }
+    }
+    for (key, meta) in &page.contents {
+        xml.open("Contents")
+            .field("Key", encode(key))
+            .field("LastModified", iso_timestamp(meta.modified_at))
+            .field("ETag", etag(meta))
+            .field("Size", meta.size)
+            .field("StorageClass", "STANDARD")
+            .close("Contents");
+    }
+    for common_prefix in &page.common_prefixes {
+        xml.open("CommonPrefixes")
+            .field("Prefix", encode(common_prefix))
+            .close("CommonPrefixes");
+    }
+    Ok(xml_response(xml))
+}
+
+/// One page of a bucket listing.
+struct ListPage {
+    contents: Vec<(String, ObjectMeta)>,
+    common_prefixes: Vec<String>,
+    /// Last key or common prefix returned, when more entries follow.
+    next: Option<String>,
+}
+
+/// Groups sorted `entries` by `delimiter` and returns at most `max_keys`
+/// keys and common prefixes following `after`.
+fn paginate(
+    entries: Vec<(String, ObjectMeta)>,
+    prefix: &str,
+    delimiter: &str,
+    max_keys: usize,
+    after: Option<&str>,
+) -> ListPage {
+    let mut page = ListPage {
+        contents: Vec::new(),
+        common_prefixes: Vec::new(),
+        next: None,
+    };
+    let mut last = None;
+
+    for (key, meta) in entries {
+        if let Some(after) = after {
+            // A common prefix token also skips every key under that prefix
+            let under_token =
+                !delimiter.is_empty() && after.ends_with(delimiter) && key.starts_with(after);
+            if key.as_str() <= after || under_token {
+                continue;
+            }
+        }
+
+        let common_prefix = (!delimiter.is_empty())
+            .then(|| key[prefix.len()..].find(delimiter))
+            .flatten()
+            .map(|index| key[..prefix.len() + index + delimiter.len()].to_string());
+        if common_prefix.is_some() && page.common_prefixes.last() == common_prefix.as_ref() {
+            continue;
+        }
+
+        if page.contents.len() + page.common_prefixes.len() == max_keys {
+            page.next = last;
+            break;
+        }
+        if let Some(common_prefix) = common_prefix {
+            last = Some(common_prefix.clone());
+            page.common_prefixes.push(common_prefix);
+        } else {
+            last = Some(key.clone());
+            page.contents.push((key, meta));
+        }
+    }
+    page
+}
+
+fn decode_token(token: &str) -> Result<String, S3Error> {
+    hex::decode(token)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| S3Error::invalid_argument("The continuation token is not valid"))
+}
+
+// =============================================================================
+// Objects
+// =============================================================================
+
+/// GET /:bucket/*key - Download an object.
+async fn get_object(
+    State(state): State<S3State>,
+    Path((bucket, key)): Path<(String, String)>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Result<Response, S3Error> {
+    let path = object_path(&bucket, &key, &params)?;
+    let (stream, meta) = state
+        .storage
+        .get_object_stream(&path)
+        .await?
+        .ok_or_else(|| S3Error::no_such_key(&key))?;
+    if let Some(response) = check_read(&headers, &meta)? {
+        return Ok(response);
+    }
+
+    metrics::record_storage_operation("get", Some(meta.size));
+    let mut response = Body::from_stream(stream).into_response();
+    set_object_headers(response.headers_mut(), &meta);
+    Ok(response)
+}
+
+/// PUT /:bucket/*key - Upload an object.
+async fn put_object(
+    State(state): State<S3State>,
+    Path((bucket, key)): Path<(String, String)>,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(payload): Extension<PayloadHash>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Response, S3Error> {
+    let path = object_path(&bucket, &key, &params)?;
+    if headers.contains_key("x-amz-copy-source") {
+        return Err(S3Error::not_implemented("CopyObject is not supported"));
+    }
+    let declared_size = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared_size.is_some_and(|size| size > MAX_OBJECT_SIZE) {
+        return Err(S3Error::entity_too_large());
+    }
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+
+    let mut stream = limited_body(body);
+    if let PayloadHash::Sha256(expected) = payload {
+        stream = verified_body(stream, expected);
+    }
+
+    let meta = state
+        .storage
+        .put_object_stream_if(&path, stream, content_type, &preconditions(&headers))
+        .await
+        .map_err(|e| {
+            if e.downcast_ref::<PreconditionFailed>().is_some() {
+                return S3Error::precondition_failed();
+            }
+            let kind = e
+                .chain()
+                .filter_map(|cause| cause.downcast_ref::<io::Error>())
+                .map(io::Error::kind)
+                .next();
+            match kind {
+                Some(io::ErrorKind::FileTooLarge) => S3Error::entity_too_large(),
+                Some(io::ErrorKind::InvalidData) => S3Error::new(
+                    StatusCode::BAD_REQUEST,
+                    "XAmzContentSHA256Mismatch",
+                    "The body does not match x-amz-content-sha256",
+                ),
+                _ => e.into(),
+            }
+        })?;
+
+    metrics::record_storage_operation("put", Some(meta.size));
+    Ok((StatusCode::OK, [(header::ETAG, etag(&meta))]).into_response())
+}
+
+/// DELETE /:bucket/*key - Delete an object (succeeds if it does not exist).
+async fn delete_object(
+    State(state): State<S3State>,
+    Path((bucket, key)): Path<(String, String)>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<StatusCode, S3Error> {
+    let path = object_path(&bucket, &key, &params)?;
+    metrics::record_storage_operation("delete", None);
+    state.storage.delete_object(&path).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// HEAD /:bucket/*key - Object metadata.
+async fn head_object(
+    State(state): State<S3State>,
+    Path((bucket, key)): Path<(String, String)>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Result<Response, S3Error> {
+    let path = object_path(&bucket, &key, &params)?;
+    metrics::record_storage_operation("head", None);
+    let meta = state
+        .storage
+        .head_object(&path)
+        .await?
+        .ok_or_else(|| S3Error::no_such_key(&key))?;
+    if let Some(response) = check_read(&headers, &meta)? {
+        return Ok(response);
+    }
+
+    let mut response = StatusCode::OK.into_response();
+    set_object_headers(response.headers_mut(), &meta);
+    Ok(response)
+}
+
+/// Evaluates `If-Match` / `If-None-Match`, returning the response to send
+/// instead of the object if they short-circuit the request.
+fn check_read(headers: &HeaderMap, meta: &ObjectMeta) -> Result<Option<Response>, S3Error> {
+    match preconditions(headers).check_read(meta) {
+        ReadPrecondition::Proceed => Ok(None),
+        ReadPrecondition::NotModified => Ok(Some(
+            (StatusCode::NOT_MODIFIED, [(header::ETAG, etag(meta))]).into_response(),
+        )),
+        ReadPrecondition::Failed => Err(S3Error::precondition_failed()),
+    }
+}
+
+/// Any operation without a handler.
+async fn unsupported() -> S3Error {
+    S3Error::not_implemented("This S3 operation is not supported")
+}
+
+/// Object stream that fails with `InvalidData` if the body does not hash
+/// to `expected`.
+fn verified_body(body: ObjectStream, expected: [u8; 32]) -> ObjectStream {
+    Box::pin(futures::stream::unfold(
+        (body, Some(Sha256::new())),
+        move |(mut body, hasher)| async move {
+            let mut hasher = hasher?;
+            match body.next().await {
+                Some(Ok(chunk)) => {
+                    hasher.update(&chunk);
+                    Some((Ok(chunk), (body, Some(hasher))))
+                },
+                Some(Err(e)) => Some((Err(e), (body, None))),
+                None if <[u8; 32]>::from(hasher.finalize()) == expected => None,
+                None => Some((
+                    Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "body does not match x-amz-content-sha256",
+                    )),
+                    (body, None),
+                )),
+            }
+        },
+    ))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Whether `name` follows the S3 bucket naming rules.
+fn is_valid_bucket(name: &str) -> bool {
+    (3..=63).contains(&name.len())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'.' || b == b'-')
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+}
+
+fn check_bucket(bucket: &str) -> Result<(), S3Error> {
+    if is_valid_bucket(bucket) {
+        Ok(())
+    } else {
+        Err(S3Error::new(
+            StatusCode::BAD_REQUEST,
+            "InvalidBucketName",
+            format!("The bucket name '{bucket}' is not valid"),
+        ))
+    }
+}
+
+fn check_subresources(params: &HashMap<String, String>) -> Result<(), S3Error> {
+    match UNSUPPORTED_SUBRESOURCES
+        .iter()
+        .find(|name| params.contains_key(**name))
+    {
+        Some(name) => Err(S3Error::not_implemented(format!(
+            "The '{name}' subresource is not supported"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Storage path of `key` in `bucket`.
+fn object_path(
+    bucket: &str,
+    key: &str,
+    params: &HashMap<String, String>,
+) -> Result<String, S3Error> {
+    check_bucket(bucket)?;
+    check_subresources(params)?;
+    Ok(format!("{bucket}/{key}"))
+}
+
+/// Entity tag identifying an object version.
+///
+/// This is the content hash, a BLAKE3 digest rather than the MD5 clients
+/// may expect, so they do not verify downloads against it. Objects without
+/// a stored hash get one derived from the path, size, and modification
+/// time; its `-1` suffix marks it as not being an MD5 either.
+fn etag(meta: &ObjectMeta) -> String {
+    if let Some(etag) = etag_header(meta) {
+        return etag;
+    }
+    let version = format!(
+        "{}:{}:{}",
+        meta.path,
+        meta.size,
+        meta.modified_at.timestamp_nanos_opt().unwrap_or_default()
+    );
+    let digest = hex::encode(Sha256::digest(version.as_bytes()));
+    format!("\"{}-1\"", &digest[..32])
+}
+
+fn set_object_headers(headers: &mut HeaderMap, meta: &ObjectMeta) {
+    let values = [
+        (header::CONTENT_TYPE, meta.content_type.clone()),
+        (header::CONTENT_LENGTH, meta.size.to_string()),
+        (header::ETAG, etag(meta)),
+        (
+            header::LAST_MODIFIED,
+            meta.modified_at
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string(),
+        ),
+    ];
+    for (name, value) in values {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    }
+}
+
+fn iso_timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn xml_response(xml: XmlWriter) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/xml")],
+        xml.finish(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{Method, Request, Uri};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    fn verifier() -> Verifier {
+        let credentials = crate::daemon::backup::s3::Credentials {
+            access_key: "test-key".to_string(),
+            secret_key: "test-secret".to_string(),
+            session_token: None,
+        };
+        Verifier::new(credentials, "us-east-1")
+    }
+
+    async fn send(app: &Router, method: Method, uri: &str, body: &'static [u8]) -> Response {
+        let uri: Uri = uri.parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_static("localhost:9000"));
+        let payload_hash = hex::encode(Sha256::digest(body));
+        sigv4::tests::sign_request(
+            &verifier(),
+            &method,
+            &uri,
+            &mut headers,
+            &payload_hash,
+            Utc::now(),
+        );
+        let mut request = Request::builder().method(method).uri(uri);
+        *request.headers_mut().unwrap() = headers;
+        app.clone()
+            .oneshot(request.body(Body::from(body)).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn text(response: Response) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_put_get_list_delete() {
+        let storage = StorageService::memory();
+        let app = router(storage.clone(), verifier());
+
+        let response = send(&app, Method::PUT, "/photos/2024/cat.jpg", b"meow").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key(header::ETAG));
+        send(&app, Method::PUT, "/photos/2024/dog.jpg", b"woof").await;
+        send(&app, Method::PUT, "/photos/readme.txt", b"hi").await;
+
+        // Objects land under the bucket prefix of the storage service
+        let (data, _) = storage
+            .get_object("photos/2024/cat.jpg")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(data, b"meow");
+
+        let response = send(&app, Method::GET, "/photos/2024/cat.jpg", b"").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(text(response).await, "meow");
+
+        let response = send(&app, Method::GET, "/photos?list-type=2&delimiter=%2F", b"").await;
+        let listing = text(response).await;
+        assert!(listing.contains("<KeyCount>2</KeyCount>"));
+        assert!(listing.contains("<Key>readme.txt</Key>"));
+        assert!(listing.contains("<CommonPrefixes><Prefix>2024/</Prefix></CommonPrefixes>"));
+
+        let response = send(&app, Method::GET, "/photos?list-type=2&max-keys=2", b"").await;
+        let listing = text(response).await;
+        assert!(listing.contains("<IsTruncated>true</IsTruncated>"));
+        let token = hex::encode("2024/dog.jpg");
+        assert!(listing.contains(&format!("<NextContinuationToken>{token}<")));
+        let uri = format!("/photos?list-type=2&continuation-token={token}");
+        let listing = text(send(&app, Method::GET, &uri, b"").await).await;
+        assert!(listing.contains("<KeyCount>1</KeyCount>"));
+        assert!(listing.contains("<Key>readme.txt</Key>"));
+
+        let response = send(&app, Method::DELETE, "/photos/2024/cat.jpg", b"").await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = send(&app, Method::HEAD, "/photos/2024/cat.jpg", b"").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsigned_and_mismatched_requests() {
+        let app = router(StorageService::memory(), verifier());
+
+        let request = Request::builder()
+            .uri("/photos/cat.jpg")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(text(response).await.contains("<Code>AccessDenied</Code>"));
+
+        // Signed for an empty body, but sent with one
+        let uri: Uri = "/photos/cat.jpg".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, HeaderValue::from_static("localhost:9000"));
+        sigv4::tests::sign_request(
+            &verifier(),
+            &Method::PUT,
+            &uri,
+            &mut headers,
+            EMPTY_SHA256,
+            Utc::now(),
+        );
+        let mut request = Request::builder().method(Method::PUT).uri(uri);
+        *request.headers_mut().unwrap() = headers;
+        let response = app
+            .oneshot(request.body(Body::from("meow")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(text(response).await.contains("XAmzContentSHA256Mismatch"));
+    }
+}
@@ -0,0 +1,69 @@
+//! Minimal XML writer for S3 response documents.
+
+use std::fmt::{Display, Write};
+
+/// Namespace of S3 API responses.
+const S3_NAMESPACE: &str = "http://s3.amazonaws.com/doc/2006-03-01/";
+
+/// Builds an S3 XML document element by element.
+pub(super) struct XmlWriter {
+    xml: String,
+    root: &'static str,
+}
+
+impl XmlWriter {
+    /// Starts a document with the given root element.
+    pub(super) fn new(root: &'static str) -> Self {
+        Self {
+            xml: format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><{root} xmlns="{S3_NAMESPACE}">"#
+            ),
+            root,
+        }
+    }
+
+    /// Opens a nested element.
+    pub(super) fn open(&mut self, name: &str) -> &mut Self {
+        let _ = write!(self.xml, "<{name}>");
+        self
+    }
+
+    /// Closes a nested element.
+    pub(super) fn close(&mut self, name: &str) -> &mut Self {
+        let _ = write!(self.xml, "</{name}>");
+        self
+    }
+
+    /// Writes an element with escaped text content.
+    pub(super) fn field(&mut self, name: &str, value: impl Display) -> &mut Self {
+        let _ = write!(self.xml, "<{name}>{}</{name}>", escape(&value.to_string()));
+        self
+    }
+
+    /// Writes escaped text content.
+    pub(super) fn text(&mut self, value: impl Display) -> &mut Self {
+        self.xml.push_str(&escape(&value.to_string()));
+        self
+    }
+
+    /// Closes the root element and returns the document.
+    pub(super) fn finish(mut self) -> String {
+        let _ = write!(self.xml, "</{}>", self.root);
+        self.xml
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -22,12 +22,13 @@ async fn create_test_app() -> Router {
     let kv = KvStore::open(data_dir.join("kv.redb")).unwrap();
     let sql = SqlService::open(data_dir.join("sql.db")).unwrap();
     let storage = StorageService::open(data_dir.join("storage")).unwrap();
-    let cron = CronScheduler::new().await.unwrap();
+    let cron = CronScheduler::new();
 
     let app_state = Arc::new(RwLock::new(AppState {
         store,
         kv: Some(kv),
         sql: Some(sql),
+        sql_tenants: None,
         storage: Some(storage),
         cron,
         config: DaemonConfig::default(),
@@ -611,12 +612,13 @@ async fn create_test_app_with_auth() -> Router {
     let kv = KvStore::open(data_dir.join("kv.redb")).unwrap();
     let sql = SqlService::open(data_dir.join("sql.db")).unwrap();
     let storage = StorageService::open(data_dir.join("storage")).unwrap();
-    let cron = CronScheduler::new().await.unwrap();
+    let cron = CronScheduler::new();
 
     let app_state = Arc::new(RwLock::new(AppState {
         store,
         kv: Some(kv),
         sql: Some(sql),
+        sql_tenants: None,
         storage: Some(storage),
         cron,
         config: DaemonConfig::default(),
@@ -2,8 +2,9 @@
 //!
 //! This module contains all the request/response types used by the daemon HTTP API handlers.
 
-use crate::daemon::services::storage::ObjectMeta;
-use crate::daemon::state::{Instance, Status};
+use crate::daemon::process::{ResourceSample, RestartConfig};
+use crate::daemon::services::storage::{ObjectMeta, PresignMethod, TenantQuota};
+use crate::daemon::state::{Instance, RestartEvent, Status};
 use serde::{Deserialize, Serialize};
 
 // =============================================================================
@@ -11,7 +12,7 @@ use serde::{Deserialize, Serialize};
 // =============================================================================
 
 /// Request to start a new instance.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct StartInstanceRequest {
     /// Instance name (required)
     pub name: String,
@@ -21,9 +22,13 @@ pub struct StartInstanceRequest {
     pub config: Option<String>,
     /// Working directory (default: current directory)
     pub working_dir: Option<String>,
-    /// Enable auto-restart on crash (default: false)
+    /// Enable auto-restart on crash (default: false). Shorthand for the
+    /// `always` restart policy; ignored when `restart` is given.
     #[serde(default)]
     pub auto_restart: bool,
+    /// Restart policy, retry limit, and backoff.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<RestartConfig>,
 }
 
 /// Validates an instance name to prevent path traversal and other issues.
@@ -55,7 +60,7 @@ pub fn validate_instance_name(name: &str) -> Result<(), String> {
 }
 
 /// Response for instance operations.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct InstanceResponse {
     pub name: String,
     pub port: u16,
@@ -65,6 +70,52 @@ pub struct InstanceResponse {
     pub started_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub uptime: Option<String>,
+    /// Seconds since start, while running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uptime_secs: Option<u64>,
+    /// Resource usage, while running and once sampled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<InstanceResources>,
+    /// Restart policy applied when the process exits.
+    pub restart: RestartConfig,
+    /// Restarts so far.
+    #[serde(default)]
+    pub restart_count: u32,
+    /// Recent exits and restarts, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restart_history: Vec<RestartEvent>,
+}
+
+/// Resource usage of an instance process.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct InstanceResources {
+    /// CPU usage in percent of one core, averaged since the previous sample.
+    pub cpu_percent: f32,
+    /// Resident set size in bytes.
+    pub rss_bytes: u64,
+    /// Open file descriptors, where the platform reports them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_fds: Option<u64>,
+}
+
+impl From<ResourceSample> for InstanceResources {
+    fn from(sample: ResourceSample) -> Self {
+        Self {
+            cpu_percent: sample.cpu_percent,
+            rss_bytes: sample.rss_bytes,
+            open_fds: sample.open_fds,
+        }
+    }
+}
+
+impl InstanceResponse {
+    /// Marks a recorded-running instance whose process is gone as crashed.
+    pub fn mark_crashed(&mut self) {
+        self.status = "crashed".to_string();
+        self.uptime = None;
+        self.uptime_secs = None;
+        self.resources = None;
+    }
 }
 
 impl From<&Instance> for InstanceResponse {
@@ -75,12 +126,10 @@ impl From<&Instance> for InstanceResponse {
             Status::Crashed { exit_code } => format!("crashed (exit: {exit_code})"),
         };
 
-        let uptime = if instance.status == Status::Running {
-            let duration = chrono::Utc::now().signed_duration_since(instance.started_at);
-            Some(format_duration(duration))
-        } else {
-            None
-        };
+        let running = (instance.status == Status::Running)
+            .then(|| chrono::Utc::now().signed_duration_since(instance.started_at));
+        let uptime = running.map(format_duration);
+        let uptime_secs = running.map(|duration| duration.num_seconds().max(0).unsigned_abs());
 
         Self {
             name: instance.name.clone(),
@@ -89,12 +138,17 @@ impl From<&Instance> for InstanceResponse {
             status,
             started_at: Some(instance.started_at.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
             uptime,
+            uptime_secs,
+            resources: None,
+            restart: instance.restart_config(),
+            restart_count: instance.restart_count,
+            restart_history: instance.restart_history.clone(),
         }
     }
 }
 
 /// Response containing list of instances.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ListInstancesResponse {
     pub instances: Vec<InstanceResponse>,
 }
@@ -104,10 +158,13 @@ pub struct ListInstancesResponse {
 pub struct LogsQuery {
     /// Number of lines to return (default: 50)
     pub lines: Option<usize>,
+    /// Stream new lines as Server-Sent Events after the initial tail.
+    #[serde(default)]
+    pub follow: bool,
 }
 
 /// Logs response.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LogsResponse {
     pub name: String,
     pub lines: Vec<String>,
@@ -132,9 +189,12 @@ pub struct VersionResponse {
 }
 
 /// Error response.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: String,
+    /// Machine-readable error kind, e.g. `quota_exceeded`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 // =============================================================================
@@ -142,7 +202,7 @@ pub struct ErrorResponse {
 // =============================================================================
 
 /// Request to set a KV value.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct KvSetRequest {
     pub value: String,
     /// TTL in seconds (optional)
@@ -157,15 +217,91 @@ pub struct KvGetResponse {
 }
 
 /// Query parameters for KV list.
+///
+/// With `limit` or `cursor`, keys are returned one page at a time.
 #[derive(Debug, Deserialize)]
 pub struct KvListQuery {
     pub prefix: Option<String>,
+    /// Continue after the page that returned this cursor.
+    pub cursor: Option<String>,
+    /// Page size (default 100, at most 1000).
+    pub limit: Option<usize>,
+    /// Include values in `entries`.
+    #[serde(default)]
+    pub values: bool,
+    /// Stream changes under `prefix` as Server-Sent Events instead.
+    #[serde(default)]
+    pub watch: bool,
 }
 
 /// Response for KV list operation.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct KvListResponse {
     pub keys: Vec<String>,
+    /// Keys with values, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entries: Option<Vec<KvGetResponse>>,
+    /// Cursor for the next page; absent on the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// A key change, as sent to `GET /kv?watch=true` subscribers.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KvWatchEvent {
+    pub key: String,
+    /// New value for sets (lossy UTF-8).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Time of the change (RFC 3339).
+    pub time: String,
+}
+
+/// Keys for `POST /kv-batch/get` and `POST /kv-batch/delete`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KvBatchKeysRequest {
+    pub keys: Vec<String>,
+}
+
+/// Response for `POST /kv-batch/get`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KvBatchGetResponse {
+    /// Value per requested key (`null` if the key does not exist).
+    pub values: std::collections::BTreeMap<String, Option<String>>,
+}
+
+/// Request for `POST /kv-batch/set`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KvBatchSetRequest {
+    pub entries: std::collections::BTreeMap<String, String>,
+    /// TTL in seconds for every entry (optional)
+    pub ttl: Option<u64>,
+}
+
+/// Response for `POST /kv-batch/delete`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KvBatchDeleteResponse {
+    /// Number of keys that existed.
+    pub deleted: usize,
+}
+
+/// Request to apply several KV writes atomically.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KvTransactionRequest {
+    /// Values the keys must still have (`null`: the key must not exist).
+    #[serde(default)]
+    pub expect: std::collections::BTreeMap<String, Option<String>>,
+    pub writes: Vec<KvTransactionWrite>,
+}
+
+/// One write in a KV transaction.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KvTransactionWrite {
+    pub key: String,
+    /// New value; absent or `null` deletes the key.
+    pub value: Option<String>,
+    /// TTL in seconds (optional)
+    pub ttl: Option<u64>,
 }
 
 // =============================================================================
@@ -173,7 +309,7 @@ pub struct KvListResponse {
 // =============================================================================
 
 /// Request for SQL query.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SqlQueryRequest {
     pub sql: String,
     #[serde(default)]
@@ -181,7 +317,7 @@ pub struct SqlQueryRequest {
 }
 
 /// Request for SQL execute.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SqlExecuteRequest {
     pub sql: String,
     #[serde(default)]
@@ -189,11 +325,18 @@ pub struct SqlExecuteRequest {
 }
 
 /// Request for SQL batch execution.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SqlBatchRequest {
     pub statements: Vec<SqlExecuteRequest>,
 }
 
+/// Response for `POST /sql/transactions`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SqlTransactionResponse {
+    /// Transaction ID for the `/sql/transactions/:id/...` endpoints.
+    pub id: String,
+}
+
 /// Response for SQL query.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SqlQueryResponse {
@@ -222,6 +365,40 @@ pub struct SqlBatchResponse {
 pub struct StorageListQuery {
     pub prefix: Option<String>,
     pub limit: Option<usize>,
+    /// Only list objects that lifecycle rules would delete now (dry run).
+    #[serde(default)]
+    pub expired: bool,
+    /// Stream changes under `prefix` as Server-Sent Events instead.
+    #[serde(default)]
+    pub watch: bool,
+}
+
+/// Presigned URL parameters on storage GET/PUT requests.
+#[derive(Debug, Default, Deserialize)]
+pub struct PresignQuery {
+    /// Expiry time (Unix seconds).
+    pub expires: Option<i64>,
+    /// Hex HMAC signature.
+    pub signature: Option<String>,
+}
+
+/// Request to create a presigned storage URL.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresignRequest {
+    /// Method the URL grants (`GET` or `PUT`).
+    pub method: PresignMethod,
+    /// Lifetime of the URL in seconds (default: 900).
+    pub expires_in_secs: Option<u64>,
+}
+
+/// Response with a presigned storage URL.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PresignResponse {
+    /// Path and query of the presigned URL, relative to the daemon address.
+    pub url: String,
+    pub method: PresignMethod,
+    /// Expiry time (RFC 3339).
+    pub expires_at: String,
 }
 
 /// Response for storage list operation.
@@ -238,6 +415,10 @@ pub struct StorageObjectInfo {
     pub content_type: String,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
 }
 
 impl From<ObjectMeta> for StorageObjectInfo {
@@ -248,10 +429,30 @@ impl From<ObjectMeta> for StorageObjectInfo {
             content_type: meta.content_type,
             created_at: meta.created_at.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
             updated_at: meta.modified_at.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            expires_at: meta
+                .expires_at
+                .map(|at| at.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            etag: meta.etag,
         }
     }
 }
 
+/// Response for the storage usage report.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StorageUsageResponse {
+    pub tenants: Vec<TenantStorageUsage>,
+}
+
+/// Objects and bytes stored by one tenant, with its quota.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TenantStorageUsage {
+    pub tenant_id: String,
+    pub objects: u64,
+    pub bytes: u64,
+    #[serde(flatten)]
+    pub quota: TenantQuota,
+}
+
 // =============================================================================
 // Cron Service Types
 // =============================================================================
@@ -261,6 +462,8 @@ impl From<ObjectMeta> for StorageObjectInfo {
 pub struct CronJobResponse {
     pub name: String,
     pub cron: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
     pub module: String,
     pub method: String,
     pub path: String,
@@ -268,12 +471,36 @@ pub struct CronJobResponse {
     pub status: String,
     pub last_run: Option<String>,
     pub next_run: Option<String>,
+    /// Overlap policy applied to scheduled runs.
+    pub overlap: crate::daemon::cron::OverlapPolicy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jitter_secs: Option<u64>,
+    /// When the last run dropped by the overlap policy was due.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_skip_at: Option<String>,
+    /// Why that run was dropped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_skip_reason: Option<String>,
 }
 
 /// Response for listing cron jobs.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ListCronJobsResponse {
     pub jobs: Vec<CronJobResponse>,
+    /// Leader election state, when enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leader: Option<CronLeaderResponse>,
+}
+
+/// Cron leader election state of this daemon.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CronLeaderResponse {
+    /// This daemon's node ID.
+    pub node_id: String,
+    /// Whether this daemon fires scheduled jobs.
+    pub is_leader: bool,
+    /// Node holding the lease, if any.
+    pub current_leader: Option<String>,
 }
 
 /// Response for cron job execution history.
@@ -293,6 +520,8 @@ pub struct CronExecutionInfo {
     pub success: bool,
     pub error: Option<String>,
     pub manual: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
 }
 
 /// Response for triggering a job.
@@ -304,11 +533,18 @@ pub struct CronTriggerResponse {
 }
 
 /// Request to create a cron job.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CronCreateRequest {
     pub name: String,
+    /// Kind of job (default: http).
+    #[serde(default, rename = "type")]
+    pub job_type: crate::daemon::cron::JobType,
+    #[serde(default)]
     pub module: String,
     pub cron: String,
+    /// IANA timezone of the cron expression (default: UTC).
+    #[serde(default)]
+    pub timezone: Option<String>,
     #[serde(default = "default_method")]
     pub method: String,
     #[serde(default = "default_path")]
@@ -326,6 +562,18 @@ pub struct CronCreateRequest {
     /// Health check path (default: /health).
     #[serde(default = "default_health_path")]
     pub health_path: String,
+    /// Backup settings for `backup` jobs.
+    #[serde(default)]
+    pub backup: Option<crate::daemon::backup::BackupJobConfig>,
+    /// Snapshot settings for `sql-backup` jobs.
+    #[serde(default)]
+    pub sql_backup: Option<crate::daemon::backup::SqlBackupConfig>,
+    /// Overlap policy (default: allow).
+    #[serde(default)]
+    pub overlap: crate::daemon::cron::OverlapPolicy,
+    /// Maximum random delay in seconds before each scheduled run.
+    #[serde(default)]
+    pub jitter_secs: Option<u64>,
 }
 
 fn default_method() -> String {
@@ -349,28 +597,28 @@ const fn default_port() -> u16 {
 }
 
 /// Response for creating a cron job.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CronCreateResponse {
     pub name: String,
     pub created: bool,
 }
 
 /// Response for deleting a cron job.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CronDeleteResponse {
     pub name: String,
     pub deleted: bool,
 }
 
 /// Request to update a cron job (pause/resume).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CronUpdateRequest {
     /// Set to false to pause, true to resume.
     pub enabled: Option<bool>,
 }
 
 /// Response for updating a cron job.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CronUpdateResponse {
     pub name: String,
     pub enabled: bool,
@@ -0,0 +1,650 @@
+//! Log shipping to external sinks.
+//!
+//! Follows every instance log in `~/.mik/logs/` and sends new lines, in
+//! batches, to the sinks in `[daemon.log_shipping]`: syslog (RFC 5424 over
+//! UDP or TCP), a Loki push endpoint, an HTTP endpoint taking NDJSON, or a
+//! local NDJSON file.
+//!
+//! Each sink has a bounded buffer. When one fills up because its sink is slow
+//! or down, reading pauses until it drains; the lines wait in the log files
+//! meanwhile, so only lines rotated away before they are read can be lost. A
+//! batch that still fails after [`MAX_ATTEMPTS`] is dropped and counted in
+//! `mik_log_lines_dropped_total`.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [daemon.log_shipping]
+//! batch_size = 500
+//! flush_interval_ms = 1000
+//! buffer_lines = 10000
+//!
+//! [[daemon.log_shipping.sinks]]
+//! type = "loki"
+//! url = "http://loki:3100/loki/api/v1/push"
+//! labels = { env = "prod" }
+//!
+//! [[daemon.log_shipping.sinks]]
+//! type = "syslog"
+//! address = "logs.internal:514"
+//! protocol = "tcp"
+//! ```
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, oneshot};
+
+use super::metrics;
+use super::process::LogFollower;
+
+/// Delivery attempts per batch before it is dropped.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// How often instance logs are checked for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Wait before the second delivery attempt; doubles with each one after.
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Time sinks get to flush buffered lines on shutdown.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The daemon's own log, which is not shipped.
+const DAEMON_LOG: &str = "daemon";
+
+/// Log shipping settings (`[daemon.log_shipping]`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LogShippingSettings {
+    /// Maximum lines per batch.
+    pub batch_size: usize,
+    /// Longest a line waits for its batch to fill, in milliseconds.
+    pub flush_interval_ms: u64,
+    /// Lines buffered per sink before reading pauses.
+    pub buffer_lines: usize,
+    /// Where lines are sent.
+    pub sinks: Vec<LogSink>,
+}
+
+impl Default for LogShippingSettings {
+    fn default() -> Self {
+        Self {
+            batch_size: 500,
+            flush_interval_ms: 1000,
+            buffer_lines: 10_000,
+            sinks: Vec::new(),
+        }
+    }
+}
+
+/// A log destination.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LogSink {
+    /// RFC 5424 syslog messages, with the instance as the app name.
+    Syslog {
+        /// `host:port` of the syslog server.
+        address: String,
+        #[serde(default)]
+        protocol: SyslogProtocol,
+    },
+    /// Loki push API, with one stream per instance.
+    Loki {
+        /// Push endpoint, e.g. `http://loki:3100/loki/api/v1/push`.
+        url: String,
+        /// Labels added to every stream, besides `instance` and `host`.
+        #[serde(default)]
+        labels: BTreeMap<String, String>,
+    },
+    /// NDJSON records POSTed to an HTTP endpoint.
+    Http {
+        url: String,
+        /// Headers sent with every request, e.g. `Authorization`.
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// NDJSON records appended to a local file.
+    File { path: PathBuf },
+}
+
+impl LogSink {
+    /// Sink type, as used in metric labels.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Syslog { .. } => "syslog",
+            Self::Loki { .. } => "loki",
+            Self::Http { .. } => "http",
+            Self::File { .. } => "file",
+        }
+    }
+}
+
+/// Transport for syslog messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogProtocol {
+    /// One datagram per message.
+    #[default]
+    Udp,
+    /// Octet-counted framing (RFC 6587).
+    Tcp,
+}
+
+/// A shipped log line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogRecord {
+    /// When the line was read from the log.
+    pub time: DateTime<Utc>,
+    /// Instance that wrote the line.
+    pub instance: String,
+    pub line: String,
+}
+
+/// Follows instance logs and feeds new lines to the sink workers.
+pub struct LogShipper {
+    log_dir: PathBuf,
+    settings: LogShippingSettings,
+    host: String,
+}
+
+impl LogShipper {
+    /// Creates a shipper for the logs in `log_dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a sink is misconfigured.
+    pub fn new(settings: LogShippingSettings, log_dir: impl Into<PathBuf>) -> Result<Self> {
+        if settings.batch_size == 0 || settings.buffer_lines == 0 {
+            bail!("[daemon.log_shipping] batch_size and buffer_lines must be positive");
+        }
+        for sink in &settings.sinks {
+            match sink {
+                LogSink::Loki { url, .. } | LogSink::Http { url, .. } => {
+                    reqwest::Url::parse(url)
+                        .with_context(|| format!("Invalid {} sink URL: {url}", sink.kind()))?;
+                },
+                LogSink::Syslog { address, .. } if address.rsplit_once(':').is_none() => {
+                    bail!("Invalid syslog sink address (expected host:port): {address}");
+                },
+                LogSink::Syslog { .. } | LogSink::File { .. } => {},
+            }
+        }
+        Ok(Self {
+            log_dir: log_dir.into(),
+            settings,
+            host: sysinfo::System::host_name().unwrap_or_else(|| "mik".to_string()),
+        })
+    }
+
+    /// Ships log lines until `shutdown` fires, then flushes what is buffered.
+    pub async fn run(self, mut shutdown: oneshot::Receiver<()>) {
+        tracing::info!(
+            sinks = self.settings.sinks.len(),
+            log_dir = %self.log_dir.display(),
+            "Log shipping started"
+        );
+
+        let flush_interval = Duration::from_millis(self.settings.flush_interval_ms);
+        let (senders, workers): (Vec<_>, Vec<_>) = self
+            .settings
+            .sinks
+            .iter()
+            .map(|sink| {
+                let (tx, rx) = mpsc::channel(self.settings.buffer_lines);
+                let writer = SinkWriter::new(sink.clone(), self.host.clone());
+                let worker = deliver(writer, rx, self.settings.batch_size, flush_interval);
+                (tx, tokio::spawn(worker))
+            })
+            .unzip();
+
+        // Logs present at startup are followed from their end, so a restart
+        // does not ship them again; logs created later from their start
+        let mut followers = HashMap::new();
+        self.discover(&mut followers, LogFollower::from_end);
+
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    self.discover(&mut followers, LogFollower::from_start);
+                    forward(&mut followers, &senders).await;
+                }
+                _ = &mut shutdown => {
+                    // Pick up what instances wrote while stopping
+                    forward(&mut followers, &senders).await;
+                    break;
+                }
+            }
+        }
+
+        drop(senders);
+        let flushed = tokio::time::timeout(SHUTDOWN_TIMEOUT, async {
+            for worker in workers {
+                let _ = worker.await;
+            }
+        });
+        if flushed.await.is_err() {
+            tracing::warn!("Log sinks did not flush before shutdown");
+        }
+        tracing::info!("Log shipping shutting down");
+    }
+
+    /// Adds a follower for every instance log not followed yet.
+    fn discover(
+        &self,
+        followers: &mut HashMap<String, LogFollower>,
+        follow: fn(PathBuf) -> LogFollower,
+    ) {
+        let Ok(entries) = std::fs::read_dir(&self.log_dir) else {
+            return;
+        };
+        for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+            if let Some(instance) = instance_name(&path) {
+                followers
+                    .entry(instance.to_string())
+                    .or_insert_with(|| follow(path));
+            }
+        }
+    }
+}
+
+/// Sends batches from `rx` to one sink until every sender is gone.
+async fn deliver(
+    mut writer: SinkWriter,
+    mut rx: mpsc::Receiver<LogRecord>,
+    batch_size: usize,
+    flush_interval: Duration,
+) {
+    let mut batch = Vec::with_capacity(batch_size);
+    while rx.recv_many(&mut batch, batch_size).await > 0 {
+        // Give a partial batch until the flush interval to fill up
+        let deadline = tokio::time::Instant::now() + flush_interval;
+        while batch.len() < batch_size {
+            let room = batch_size - batch.len();
+            let more = rx.recv_many(&mut batch, room);
+            match tokio::time::timeout_at(deadline, more).await {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {},
+            }
+        }
+        writer.send_with_retry(&batch).await;
+        batch.clear();
+    }
+}
+
+/// Polls every follower and hands new lines to each sink, waiting while a
+/// sink's buffer is full.
+async fn forward(
+    followers: &mut HashMap<String, LogFollower>,
+    senders: &[mpsc::Sender<LogRecord>],
+) {
+    for (instance, follower) in followers.iter_mut() {
+        let lines = match follower.poll() {
+            Ok(lines) => lines,
+            Err(e) => {
+                tracing::debug!(instance = %instance, error = %e, "Failed to read instance log");
+                continue;
+            },
+        };
+        let time = Utc::now();
+        for line in lines {
+            let record = LogRecord {
+                time,
+                instance: instance.clone(),
+                line,
+            };
+            for sender in senders {
+                // Only fails once the worker is gone, which happens on shutdown
+                let _ = sender.send(record.clone()).await;
+            }
+        }
+    }
+}
+
+/// Instance name of a log path: `{name}.log`, excluding rotated logs and the
+/// daemon's own log.
+fn instance_name(path: &Path) -> Option<&str> {
+    if path.extension()? != "log" {
+        return None;
+    }
+    path.file_stem()?
+        .to_str()
+        .filter(|name| *name != DAEMON_LOG)
+}
+
+/// Writes batches to one sink, keeping its connection between batches.
+struct SinkWriter {
+    sink: LogSink,
+    host: String,
+    http: reqwest::Client,
+    tcp: Option<tokio::net::TcpStream>,
+    udp: Option<tokio::net::UdpSocket>,
+}
+
+impl SinkWriter {
+    fn new(sink: LogSink, host: String) -> Self {
+        Self {
+            sink,
+            host,
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+            tcp: None,
+            udp: None,
+        }
+    }
+
+    /// Sends a batch, retrying with backoff, and records the outcome.
+    async fn send_with_retry(&mut self, batch: &[LogRecord]) {
+        let kind = self.sink.kind();
+        let mut backoff = RETRY_BACKOFF;
+        for attempt in 1..=MAX_ATTEMPTS {
+            match self.send(batch).await {
+                Ok(()) => {
+                    metrics::record_log_lines_shipped(kind, batch.len() as u64);
+                    return;
+                },
+                Err(e) if attempt < MAX_ATTEMPTS => {
+                    tracing::debug!(sink = kind, attempt, error = %format!("{e:#}"), "Log batch failed, retrying");
+                    // Reconnect on the next attempt
+                    self.tcp = None;
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                },
+                Err(e) => {
+                    tracing::warn!(
+                        sink = kind,
+                        lines = batch.len(),
+                        error = %format!("{e:#}"),
+                        "Dropping log batch after {MAX_ATTEMPTS} attempts"
+                    );
+                    metrics::record_log_lines_dropped(kind, batch.len() as u64);
+                },
+            }
+        }
+    }
+
+    async fn send(&mut self, batch: &[LogRecord]) -> Result<()> {
+        match &self.sink {
+            LogSink::Syslog { address, protocol } => {
+                let messages = batch.iter().map(|r| syslog_message(r, &self.host));
+                match protocol {
+                    SyslogProtocol::Udp => {
+                        if self.udp.is_none() {
+                            let socket = tokio::net::UdpSocket::bind("0.0.0.0:0")
+                                .await
+                                .context("Failed to bind syslog socket")?;
+                            socket
+                                .connect(address)
+                                .await
+                                .with_context(|| format!("Failed to resolve {address}"))?;
+                            self.udp = Some(socket);
+                        }
+                        let socket = self.udp.as_ref().expect("connected above");
+                        for message in messages {
+                            socket
+                                .send(message.as_bytes())
+                                .await
+                                .with_context(|| format!("Failed to send syslog to {address}"))?;
+                        }
+                    },
+                    SyslogProtocol::Tcp => {
+                        let mut framed = String::new();
+                        for message in messages {
+                            framed.push_str(&message.len().to_string());
+                            framed.push(' ');
+                            framed.push_str(&message);
+                        }
+                        if self.tcp.is_none() {
+                            let stream = tokio::net::TcpStream::connect(address)
+                                .await
+                                .with_context(|| format!("Failed to connect to {address}"))?;
+                            self.tcp = Some(stream);
+                        }
+                        let stream = self.tcp.as_mut().expect("connected above");
+                        stream
+                            .write_all(framed.as_bytes())
+                            .await
+                            .with_context(|| format!("Failed to send syslog to {address}"))?;
+                    },
+                }
+            },
+            LogSink::Loki { url, labels } => {
+                let body = loki_push_body(batch, &self.host, labels);
+                let response = self
+                    .http
+                    .post(url)
+                    .json(&body)
+                    .send()
+                    .await
+                    .with_context(|| format!("Loki push to {url} failed"))?;
+                check_status(response, url).await?;
+            },
+            LogSink::Http { url, headers } => {
+                let mut request = self
+                    .http
+                    .post(url)
+                    .header("content-type", "application/x-ndjson")
+                    .body(ndjson(batch));
+                for (name, value) in headers {
+                    request = request.header(name, value);
+                }
+                let response = request
+                    .send()
+                    .await
+                    .with_context(|| format!("Log push to {url} failed"))?;
+                check_status(response, url).await?;
+            },
+            LogSink::File { path } => {
+                let mut file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await
+                    .with_context(|| format!("Failed to open {}", path.display()))?;
+                file.write_all(ndjson(batch).as_bytes())
+                    .await
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            },
+        }
+        Ok(())
+    }
+}
+
+async fn check_status(response: reqwest::Response, url: &str) -> Result<()> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let message: String = body.chars().take(200).collect();
+        bail!("{url} returned status {}: {message}", status.as_u16());
+    }
+    Ok(())
+}
+
+/// One JSON record per line.
+fn ndjson(batch: &[LogRecord]) -> String {
+    batch
+        .iter()
+        .filter_map(|record| serde_json::to_string(record).ok())
+        .map(|json| json + "\n")
+        .collect()
+}
+
+/// RFC 5424 message at facility `user`, severity `info`.
+fn syslog_message(record: &LogRecord, host: &str) -> String {
+    format!(
+        "<14>1 {} {host} {} - - - {}",
+        record.time.to_rfc3339_opts(SecondsFormat::Millis, true),
+        record.instance,
+        record.line
+    )
+}
+
+/// Loki push request with one stream per instance.
+fn loki_push_body(
+    batch: &[LogRecord],
+    host: &str,
+    labels: &BTreeMap<String, String>,
+) -> serde_json::Value {
+    let mut streams: BTreeMap<&str, Vec<[String; 2]>> = BTreeMap::new();
+    for record in batch {
+        let nanos = record.time.timestamp_nanos_opt().unwrap_or_default();
+        streams
+            .entry(&record.instance)
+            .or_default()
+            .push([nanos.to_string(), record.line.clone()]);
+    }
+    let streams: Vec<_> = streams
+        .into_iter()
+        .map(|(instance, values)| {
+            let mut stream = labels.clone();
+            stream.insert("instance".to_string(), instance.to_string());
+            stream.insert("host".to_string(), host.to_string());
+            serde_json::json!({ "stream": stream, "values": values })
+        })
+        .collect();
+    serde_json::json!({ "streams": streams })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn record(instance: &str, line: &str) -> LogRecord {
+        LogRecord {
+            time: "2026-10-14T12:00:00.5Z".parse().unwrap(),
+            instance: instance.to_string(),
+            line: line.to_string(),
+        }
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_formats() {
+        assert_eq!(
+            syslog_message(&record("api", "GET /health 200"), "node1"),
+            "<14>1 2026-10-14T12:00:00.500Z node1 api - - - GET /health 200"
+        );
+
+        let labels = BTreeMap::from([("env".to_string(), "prod".to_string())]);
+        let body = loki_push_body(
+            &[record("api", "a"), record("web", "b"), record("api", "c")],
+            "node1",
+            &labels,
+        );
+        let streams = body["streams"].as_array().unwrap();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[0]["stream"]["instance"], "api");
+        assert_eq!(streams[0]["stream"]["env"], "prod");
+        assert_eq!(streams[0]["values"][1][1], "c");
+        assert_eq!(streams[0]["values"][0][0], "1791979200500000000");
+
+        let line = ndjson(&[record("api", "a")]);
+        assert_eq!(
+            line,
+            "{\"time\":\"2026-10-14T12:00:00.500Z\",\"instance\":\"api\",\"line\":\"a\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_instance_names() {
+        assert_eq!(instance_name(Path::new("/logs/api.log")), Some("api"));
+        assert_eq!(instance_name(Path::new("/logs/daemon.log")), None);
+        assert_eq!(
+            instance_name(Path::new("/logs/api.log.20260101-000000")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_sinks() {
+        let settings = |sink: LogSink| LogShippingSettings {
+            sinks: vec![sink],
+            ..LogShippingSettings::default()
+        };
+        let loki = LogSink::Loki {
+            url: "not a url".to_string(),
+            labels: BTreeMap::new(),
+        };
+        assert!(LogShipper::new(settings(loki), "/tmp").is_err());
+        let syslog = LogSink::Syslog {
+            address: "localhost".to_string(),
+            protocol: SyslogProtocol::Udp,
+        };
+        assert!(LogShipper::new(settings(syslog), "/tmp").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ships_new_lines_to_file_and_syslog() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = dir.path().join("logs");
+        std::fs::create_dir(&logs).unwrap();
+        append(&logs.join("api.log"), "before start\n");
+        append(&logs.join("daemon.log"), "daemon line\n");
+
+        let syslog = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let output = dir.path().join("shipped.ndjson");
+        let settings = LogShippingSettings {
+            flush_interval_ms: 10,
+            sinks: vec![
+                LogSink::File {
+                    path: output.clone(),
+                },
+                LogSink::Syslog {
+                    address: syslog.local_addr().unwrap().to_string(),
+                    protocol: SyslogProtocol::Udp,
+                },
+            ],
+            ..LogShippingSettings::default()
+        };
+        let shipper = LogShipper::new(settings, &logs).unwrap();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let handle = tokio::spawn(shipper.run(shutdown_rx));
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+        append(&logs.join("api.log"), "after start\n");
+        append(&logs.join("web.log"), "new instance\n");
+        append(&logs.join("daemon.log"), "daemon line\n");
+
+        let mut buf = [0; 1024];
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let len = tokio::time::timeout(Duration::from_secs(5), syslog.recv(&mut buf))
+                .await
+                .unwrap()
+                .unwrap();
+            received.push(String::from_utf8_lossy(&buf[..len]).to_string());
+        }
+        received.sort();
+        assert!(received[0].ends_with(" api - - - after start"));
+        assert!(received[1].ends_with(" web - - - new instance"));
+
+        shutdown_tx.send(()).unwrap();
+        handle.await.unwrap();
+
+        let mut records: Vec<serde_json::Value> = std::fs::read_to_string(&output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        records.sort_by_key(|r| r["instance"].as_str().unwrap().to_string());
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["line"], "after start");
+        assert_eq!(records[1]["instance"], "web");
+    }
+}
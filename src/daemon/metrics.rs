@@ -13,15 +13,30 @@
 //! ## Instance Metrics
 //! - `mik_instances_total` - Total instances by status (labels: status)
 //! - `mik_instance_uptime_seconds` - Instance uptime gauge
+//! - `mik_instance_memory_bytes` - Instance RSS (labels: instance)
+//! - `mik_instance_cpu_percent` - Instance CPU usage (labels: instance)
+//! - `mik_instance_memory_restarts_total` - Restarts due to the memory ceiling (labels: instance)
 //!
 //! ## Service Metrics
 //! - `mik_kv_operations_total` - KV operations (labels: operation)
 //! - `mik_sql_queries_total` - SQL queries (labels: type)
+//! - `mik_sql_statement_cache_total` - Prepared statement cache lookups (labels: result)
 //! - `mik_storage_operations_total` - Storage operations (labels: operation)
 //!
 //! ## Scheduler Metrics
 //! - `mik_cron_executions_total` - Cron job executions (labels: job, success)
+//! - `mik_cron_skipped_total` - Cron runs dropped by the overlap policy (labels: job)
 //! - `mik_cron_execution_duration_seconds` - Cron job duration histogram
+//!
+//! ## Backup Metrics
+//! - `mik_backups_total` - Backup runs (labels: job, success)
+//! - `mik_backup_size_bytes` - Size of the last successful archive (labels: job)
+//! - `mik_backup_last_success_timestamp_seconds` - Time of the last successful backup (labels: job)
+//! - `mik_backups_pruned_total` - Expired archives deleted by retention (labels: job)
+//!
+//! ## Log Shipping Metrics
+//! - `mik_log_lines_shipped_total` - Lines delivered to a sink (labels: sink)
+//! - `mik_log_lines_dropped_total` - Lines dropped after failed deliveries (labels: sink)
 
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
@@ -65,6 +80,18 @@ fn register_metrics() {
     // Instance metrics
     describe_gauge!("mik_instances_total", "Total number of instances by status");
     describe_gauge!("mik_instance_uptime_seconds", "Instance uptime in seconds");
+    describe_gauge!(
+        "mik_instance_memory_bytes",
+        "Instance resident set size in bytes"
+    );
+    describe_gauge!(
+        "mik_instance_cpu_percent",
+        "Instance CPU usage in percent of one core"
+    );
+    describe_counter!(
+        "mik_instance_memory_restarts_total",
+        "Total instance restarts triggered by the memory ceiling"
+    );
 
     // KV metrics
     describe_counter!("mik_kv_operations_total", "Total KV store operations");
@@ -75,6 +102,10 @@ fn register_metrics() {
         "mik_sql_query_duration_seconds",
         "SQL query duration in seconds"
     );
+    describe_counter!(
+        "mik_sql_statement_cache_total",
+        "SQLite prepared statement cache lookups (hit/miss)"
+    );
 
     // Storage metrics
     describe_counter!("mik_storage_operations_total", "Total storage operations");
@@ -82,13 +113,46 @@ fn register_metrics() {
         "mik_storage_bytes_total",
         "Total bytes transferred (read/write)"
     );
+    describe_counter!(
+        "mik_storage_lifecycle_deleted_total",
+        "Objects deleted by storage lifecycle rules"
+    );
 
     // Cron metrics
     describe_counter!("mik_cron_executions_total", "Total cron job executions");
+    describe_counter!(
+        "mik_cron_skipped_total",
+        "Cron runs dropped by the overlap policy"
+    );
     describe_histogram!(
         "mik_cron_execution_duration_seconds",
         "Cron job execution duration in seconds"
     );
+
+    // Backup metrics
+    describe_counter!("mik_backups_total", "Total backup runs");
+    describe_gauge!(
+        "mik_backup_size_bytes",
+        "Size of the last successful backup archive in bytes"
+    );
+    describe_gauge!(
+        "mik_backup_last_success_timestamp_seconds",
+        "Unix time of the last successful backup"
+    );
+    describe_counter!(
+        "mik_backups_pruned_total",
+        "Total expired backup archives deleted"
+    );
+
+    // Log shipping metrics
+    describe_counter!(
+        "mik_log_lines_shipped_total",
+        "Log lines delivered to a shipping sink"
+    );
+    describe_counter!(
+        "mik_log_lines_dropped_total",
+        "Log lines dropped after failed deliveries to a shipping sink"
+    );
 }
 
 // =============================================================================
@@ -161,6 +225,31 @@ pub fn set_instance_uptime(name: &str, uptime_secs: f64) {
     .set(uptime_secs);
 }
 
+/// Records an instance resource sample.
+#[allow(clippy::cast_precision_loss)] // RSS in bytes fits in f64
+pub fn set_instance_resources(name: &str, rss_bytes: u64, cpu_percent: f32) {
+    gauge!(
+        "mik_instance_memory_bytes",
+        "instance" => name.to_string()
+    )
+    .set(rss_bytes as f64);
+
+    gauge!(
+        "mik_instance_cpu_percent",
+        "instance" => name.to_string()
+    )
+    .set(f64::from(cpu_percent));
+}
+
+/// Records a restart triggered by the memory ceiling.
+pub fn record_instance_memory_restart(name: &str) {
+    counter!(
+        "mik_instance_memory_restarts_total",
+        "instance" => name.to_string()
+    )
+    .increment(1);
+}
+
 // =============================================================================
 // KV Metrics
 // =============================================================================
@@ -193,6 +282,15 @@ pub fn record_sql_query(query_type: &str, duration_secs: f64) {
     .record(duration_secs);
 }
 
+/// Records a prepared statement cache lookup.
+pub fn record_sql_statement_cache(hit: bool) {
+    counter!(
+        "mik_sql_statement_cache_total",
+        "result" => if hit { "hit" } else { "miss" }
+    )
+    .increment(1);
+}
+
 // =============================================================================
 // Storage Metrics
 // =============================================================================
@@ -214,6 +312,11 @@ pub fn record_storage_operation(operation: &str, bytes: Option<u64>) {
     }
 }
 
+/// Record objects deleted by storage lifecycle rules.
+pub fn record_storage_lifecycle_deletions(count: u64) {
+    counter!("mik_storage_lifecycle_deleted_total").increment(count);
+}
+
 // =============================================================================
 // Cron Metrics
 // =============================================================================
@@ -236,6 +339,52 @@ pub fn record_cron_execution(job: &str, success: bool, duration_secs: f64) {
     .record(duration_secs);
 }
 
+/// Records a cron run dropped by the job's overlap policy.
+pub fn record_cron_skipped(job: &str) {
+    counter!("mik_cron_skipped_total", "job" => job.to_string()).increment(1);
+}
+
+// =============================================================================
+// Backup Metrics
+// =============================================================================
+
+/// Records a backup run.
+#[allow(clippy::cast_precision_loss)] // Archive sizes and timestamps fit in f64
+pub fn record_backup(job: &str, success: bool, size_bytes: u64, pruned: usize) {
+    let success_str = if success { "true" } else { "false" };
+
+    counter!(
+        "mik_backups_total",
+        "job" => job.to_string(),
+        "success" => success_str.to_string()
+    )
+    .increment(1);
+
+    if success {
+        gauge!("mik_backup_size_bytes", "job" => job.to_string()).set(size_bytes as f64);
+        gauge!(
+            "mik_backup_last_success_timestamp_seconds",
+            "job" => job.to_string()
+        )
+        .set(chrono::Utc::now().timestamp() as f64);
+        counter!("mik_backups_pruned_total", "job" => job.to_string()).increment(pruned as u64);
+    }
+}
+
+// =============================================================================
+// Log Shipping Metrics
+// =============================================================================
+
+/// Records log lines delivered to a sink.
+pub fn record_log_lines_shipped(sink: &'static str, lines: u64) {
+    counter!("mik_log_lines_shipped_total", "sink" => sink).increment(lines);
+}
+
+/// Records log lines dropped after a sink kept failing.
+pub fn record_log_lines_dropped(sink: &'static str, lines: u64) {
+    counter!("mik_log_lines_dropped_total", "sink" => sink).increment(lines);
+}
+
 // =============================================================================
 // Metrics Rendering
 // =============================================================================
@@ -3,9 +3,11 @@
 //! Provides persistent state storage, process management, and HTTP API
 //! for Docker-like instance lifecycle management.
 
+pub mod backup;
 pub mod config;
 pub mod cron;
 pub mod http;
+pub mod log_shipping;
 pub mod metrics;
 #[cfg(feature = "otlp")]
 pub mod otlp;
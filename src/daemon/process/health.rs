@@ -9,6 +9,8 @@ use std::io::{BufRead, BufReader};
 use std::path::Path;
 use sysinfo::{Pid, ProcessesToUpdate, System};
 
+use super::types::InstanceExit;
+
 /// Checks if a process with the given PID is currently running.
 ///
 /// Uses sysinfo to query the system's process table and verify the process
@@ -35,6 +37,33 @@ pub fn is_running(pid: u32) -> Result<bool> {
     Ok(system.process(Pid::from(pid as usize)).is_some())
 }
 
+/// Reaps `pid` if it is a child of this process that has exited, returning
+/// how it exited.
+///
+/// Instances the daemon spawns are its children, and stay in the process
+/// table (so [`is_running`] reports them) until reaped. Returns `None` while
+/// the process runs, and for processes that are not children of this one,
+/// whose exit status cannot be known.
+pub fn try_reap(pid: u32) -> Option<InstanceExit> {
+    #[cfg(unix)]
+    {
+        use nix::sys::wait::{WaitPidFlag, WaitStatus, waitpid};
+
+        let pid = nix::unistd::Pid::from_raw(i32::try_from(pid).ok()?);
+        match waitpid(pid, Some(WaitPidFlag::WNOHANG)) {
+            Ok(WaitStatus::Exited(_, code)) => Some(InstanceExit::Code(code)),
+            Ok(WaitStatus::Signaled(_, signal, _)) => Some(InstanceExit::Signal(signal as i32)),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
 /// Reads the last N lines from a log file.
 ///
 /// Useful for implementing `mik logs` command to show recent log entries.
@@ -68,6 +97,41 @@ mod tests {
     use std::io::Write;
     use tempfile::TempDir;
 
+    #[cfg(unix)]
+    #[test]
+    #[allow(clippy::zombie_processes)] // Reaped by try_reap
+    fn test_try_reap_reports_exit() {
+        /// Polls until the child is reaped.
+        fn reap(pid: u32) -> InstanceExit {
+            for _ in 0..500 {
+                if let Some(exit) = try_reap(pid) {
+                    return exit;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            panic!("process {pid} was not reaped");
+        }
+
+        let exited = std::process::Command::new("sh")
+            .args(["-c", "exit 3"])
+            .spawn()
+            .unwrap();
+        assert_eq!(reap(exited.id()), InstanceExit::Code(3));
+
+        let mut killed = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let pid = killed.id();
+        assert_eq!(try_reap(pid), None); // Still running
+        killed.kill().unwrap();
+        assert_eq!(reap(pid), InstanceExit::Signal(9));
+        assert!(!is_running(pid).unwrap());
+
+        // Not a child of this process
+        assert_eq!(try_reap(1), None);
+    }
+
     #[test]
     fn test_tail_log() {
         let temp_dir = TempDir::new().unwrap();
@@ -14,8 +14,11 @@ use super::log_rotation::rotate_log_if_needed;
 use super::types::{InstanceInfo, LogRotationConfig, SpawnConfig};
 use super::utils::get_log_dir;
 
-#[cfg(unix)]
 use super::health::is_running;
+#[cfg(unix)]
+use super::health::try_reap;
+#[cfg(windows)]
+use super::windows;
 
 /// Spawns a new mik server instance as a background process.
 ///
@@ -69,6 +72,9 @@ pub fn spawn_instance(config: &SpawnConfig) -> Result<InstanceInfo> {
 /// - `setsid()` is async-signal-safe according to `POSIX`
 /// - No memory allocation or locking occurs in the `pre_exec` closure
 /// - The closure only calls libc functions that are safe in this context
+///
+/// On Windows the process is detached from the console instead, and put in
+/// its own Job Object so it can be killed along with its children.
 #[allow(unsafe_code)] // SAFETY: Unix pre_exec/setsid for process group detachment
 pub fn spawn_instance_with_rotation(
     config: &SpawnConfig,
@@ -112,6 +118,15 @@ pub fn spawn_instance_with_rotation(
     if config.hot_reload {
         cmd.env("MIK_HOT_RELOAD", "1");
     }
+    if let Some(file) = &config.build_error_file {
+        cmd.env("MIK_BUILD_ERROR_FILE", file);
+    }
+    if let Some(url) = &config.dev_proxy {
+        cmd.env("MIK_DEV_PROXY", url);
+    }
+    if config.dev_dashboard {
+        cmd.env("MIK_DEV_DASHBOARD", &log_path);
+    }
 
     // Platform-specific process detachment
     #[cfg(unix)]
@@ -131,20 +146,22 @@ pub fn spawn_instance_with_rotation(
         }
     }
 
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-
-        // On Windows, use CREATE_NEW_PROCESS_GROUP to detach
-        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
-        const DETACHED_PROCESS: u32 = 0x0000_0008;
-
-        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS);
-    }
-
     // Spawn the process
+    #[cfg(not(windows))]
     let child = cmd.spawn().context("Failed to spawn mik server process")?;
 
+    #[cfg(windows)]
+    let child = {
+        let child =
+            windows::spawn_detached(&mut cmd).context("Failed to spawn mik server process")?;
+        // Without a job, stopping still works but misses the instance's
+        // own child processes
+        if let Err(e) = windows::assign_job(&child) {
+            tracing::warn!(error = %format!("{e:#}"), "Failed to assign instance to a job object");
+        }
+        child
+    };
+
     // Get PID before releasing the handle
     let pid = child.id();
 
@@ -171,8 +188,9 @@ pub fn spawn_instance_with_rotation(
 
 /// Kills a running instance by sending a termination signal.
 ///
-/// Attempts graceful shutdown first (SIGTERM on Unix, equivalent on Windows),
-/// and falls back to forceful termination if the process doesn't exit.
+/// Attempts graceful shutdown first (SIGTERM on Unix, the instance's stop
+/// event on Windows), and falls back to forceful termination if the process
+/// doesn't exit.
 ///
 /// # Arguments
 ///
@@ -182,6 +200,20 @@ pub fn spawn_instance_with_rotation(
 ///
 /// Returns an error if the process cannot be killed or doesn't exist.
 pub fn kill_instance(pid: u32) -> Result<()> {
+    kill_instance_with_grace(pid, std::time::Duration::from_secs(2))
+}
+
+/// Kills a running instance, giving it up to `grace` to exit on its own.
+///
+/// On Unix the instance gets SIGTERM, which makes it stop accepting and drain
+/// in-flight requests; SIGKILL follows only if it is still running after
+/// `grace`. On Windows the stop event plays the part of SIGTERM, and
+/// terminating the instance's Job Object that of SIGKILL.
+///
+/// # Errors
+///
+/// Returns an error if the process cannot be killed or doesn't exist.
+pub fn kill_instance_with_grace(pid: u32, grace: std::time::Duration) -> Result<()> {
     #[cfg(unix)]
     {
         use nix::sys::signal::{self, Signal};
@@ -195,11 +227,16 @@ pub fn kill_instance(pid: u32) -> Result<()> {
         signal::kill(nix_pid, Signal::SIGTERM)
             .with_context(|| format!("Failed to send SIGTERM to process {pid}"))?;
 
-        // Wait briefly for graceful shutdown
-        std::thread::sleep(std::time::Duration::from_secs(2));
+        // Wait for graceful shutdown. Instances spawned by this process must
+        // be reaped, or they linger as zombies that look running.
+        let running = || -> Result<bool> { Ok(try_reap(pid).is_none() && is_running(pid)?) };
+        let deadline = std::time::Instant::now() + grace;
+        while std::time::Instant::now() < deadline && running()? {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
 
         // Check if process is still running
-        if is_running(pid)? {
+        if running()? {
             tracing::warn!(
                 pid = pid,
                 "Process didn't respond to SIGTERM, sending SIGKILL"
@@ -213,27 +250,21 @@ pub fn kill_instance(pid: u32) -> Result<()> {
 
     #[cfg(windows)]
     {
-        use std::os::windows::process::CommandExt;
-
-        // On Windows, use taskkill for graceful shutdown
-        let status = Command::new("taskkill")
-            .args(["/PID", &pid.to_string(), "/T"])
-            .creation_flags(0x0800_0000) // CREATE_NO_WINDOW
-            .status()
-            .with_context(|| format!("Failed to kill process {pid}"))?;
-
-        if !status.success() {
-            // Try forceful kill
-            let force_status = Command::new("taskkill")
-                .args(["/PID", &pid.to_string(), "/T", "/F"])
-                .creation_flags(0x0800_0000) // CREATE_NO_WINDOW
-                .status()
-                .with_context(|| format!("Failed to force kill process {pid}"))?;
-
-            if !force_status.success() {
-                anyhow::bail!("Failed to kill process {pid}");
+        // Processes that don't listen for stop requests are terminated at once
+        if windows::request_stop(pid) {
+            let deadline = std::time::Instant::now() + grace;
+            while std::time::Instant::now() < deadline && is_running(pid)? {
+                std::thread::sleep(std::time::Duration::from_millis(100));
             }
         }
+
+        if is_running(pid)? {
+            tracing::warn!(
+                pid = pid,
+                "Process didn't stop gracefully, terminating its job"
+            );
+            windows::terminate(pid)?;
+        }
     }
 
     tracing::info!(pid = pid, "Killed instance");
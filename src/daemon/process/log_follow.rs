@@ -0,0 +1,187 @@
+//! Incremental log reading for `tail -f` style following.
+//!
+//! [`LogFollower`] remembers its position in an instance log and returns only
+//! lines appended since the last poll. When the log is rotated (renamed and
+//! recreated) or truncated, it reopens the path and continues from the start
+//! of the new file, so followers survive instance restarts.
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Upper bound on bytes read per poll, so a burst cannot stall the caller.
+const MAX_READ_PER_POLL: u64 = 1024 * 1024;
+
+/// Follows a log file across appends, truncation, and rotation.
+pub struct LogFollower {
+    path: PathBuf,
+    file: Option<File>,
+    position: u64,
+    /// Bytes of an incomplete trailing line, kept until its newline arrives.
+    partial: Vec<u8>,
+}
+
+impl LogFollower {
+    /// Start following `path` from its current end.
+    ///
+    /// The file does not need to exist yet.
+    pub fn from_end(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let file = File::open(&path).ok();
+        let position = file
+            .as_ref()
+            .and_then(|f| f.metadata().ok())
+            .map_or(0, |m| m.len());
+        Self {
+            path,
+            file,
+            position,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Start following `path` from its beginning.
+    pub fn from_start(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let file = File::open(&path).ok();
+        Self {
+            path,
+            file,
+            position: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Path being followed.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return complete lines appended since the last poll.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log cannot be read.
+    pub fn poll(&mut self) -> Result<Vec<String>> {
+        if self.replaced() {
+            self.file = File::open(&self.path).ok();
+            self.position = 0;
+            self.partial.clear();
+        }
+
+        let Some(file) = &mut self.file else {
+            return Ok(Vec::new());
+        };
+
+        file.seek(SeekFrom::Start(self.position))
+            .with_context(|| format!("Failed to seek {}", self.path.display()))?;
+        let mut chunk = Vec::new();
+        let read = file
+            .take(MAX_READ_PER_POLL)
+            .read_to_end(&mut chunk)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        self.position += read as u64;
+
+        self.partial.extend_from_slice(&chunk);
+        let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.partial.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+
+        Ok(String::from_utf8_lossy(&complete)
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    /// Whether the open file no longer matches the path (rotated, truncated,
+    /// or created since we started).
+    fn replaced(&self) -> bool {
+        let Ok(current) = std::fs::metadata(&self.path) else {
+            // Rotated away and not yet recreated: keep draining the old handle
+            return false;
+        };
+        let Some(open) = self.file.as_ref().and_then(|f| f.metadata().ok()) else {
+            return true;
+        };
+
+        if current.len() < self.position {
+            return true;
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            current.ino() != open.ino() || current.dev() != open.dev()
+        }
+        #[cfg(not(unix))]
+        {
+            // Without inode numbers only truncation (checked above) is detected
+            let _ = open;
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn append(path: &Path, text: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_follow_appends_and_partial_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("app.log");
+        append(&log, "old line\n");
+
+        let mut follower = LogFollower::from_end(&log);
+        assert!(follower.poll().unwrap().is_empty());
+
+        append(&log, "first\nsec");
+        assert_eq!(follower.poll().unwrap(), vec!["first"]);
+        append(&log, "ond\n");
+        assert_eq!(follower.poll().unwrap(), vec!["second"]);
+    }
+
+    #[test]
+    fn test_follow_across_rotation_and_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("app.log");
+        append(&log, "before\n");
+        let mut follower = LogFollower::from_end(&log);
+
+        // Rotation: rename and recreate
+        append(&log, "last of old\n");
+        std::fs::rename(&log, dir.path().join("app.log.20260101-000000")).unwrap();
+        assert_eq!(follower.poll().unwrap(), vec!["last of old"]);
+        append(&log, "new file\n");
+        assert_eq!(follower.poll().unwrap(), vec!["new file"]);
+
+        // Truncation in place
+        std::fs::write(&log, "").unwrap();
+        append(&log, "x\n");
+        assert_eq!(follower.poll().unwrap(), vec!["x"]);
+    }
+
+    #[test]
+    fn test_follow_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("later.log");
+        let mut follower = LogFollower::from_end(&log);
+        assert!(follower.poll().unwrap().is_empty());
+
+        append(&log, "hello\n");
+        assert_eq!(follower.poll().unwrap(), vec!["hello"]);
+    }
+}
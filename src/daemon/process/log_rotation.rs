@@ -90,7 +90,7 @@ pub(crate) fn cleanup_old_logs(log_path: &Path, max_files: usize) {
     }
 
     // Sort by modification time (newest first)
-    rotated_files.sort_by(|a, b| b.1.cmp(&a.1));
+    rotated_files.sort_by_key(|f| std::cmp::Reverse(f.1));
 
     // Delete files beyond the limit
     for (path, _) in rotated_files.iter().skip(max_files) {
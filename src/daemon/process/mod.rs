@@ -11,20 +11,32 @@
 //!
 //! ## Module Structure
 //!
-//! - [`types`]: Configuration and information types
+//! - [`types`]: Configuration and information types, including restart policies
 //! - [`lifecycle`]: Process spawning and termination
 //! - [`health`]: Health checking and log reading
+//! - [`log_follow`]: Incremental log following across rotation
+//! - [`resources`]: RSS/CPU/file descriptor sampling and memory ceiling tracking
 //! - [`log_rotation`]: Log file rotation
 //! - [`utils`]: Shared utility functions
+//! - `windows`: Job Objects and stop events standing in for Unix process
+//!   groups and signals (Windows only)
 
 mod health;
 mod lifecycle;
+mod log_follow;
 mod log_rotation;
+mod resources;
 mod types;
 mod utils;
+#[cfg(windows)]
+mod windows;
 
 // Re-export public API
 // Process management functions are used by HTTP handlers for instance lifecycle.
-pub use health::{is_running, tail_log};
-pub use lifecycle::{kill_instance, spawn_instance};
-pub use types::SpawnConfig;
+pub use health::{is_running, tail_log, try_reap};
+pub use lifecycle::{kill_instance, kill_instance_with_grace, spawn_instance};
+pub use log_follow::LogFollower;
+pub use resources::{ResourceMonitor, ResourceSample, sample_usage};
+pub use types::{InstanceExit, RestartConfig, RestartPolicy, SpawnConfig};
+#[cfg(windows)]
+pub use windows::{spawn_detached, stop_requested};
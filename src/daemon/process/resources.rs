@@ -0,0 +1,160 @@
+//! Resource sampling for spawned instances.
+//!
+//! [`ResourceMonitor`] samples the RSS, CPU usage, and open file
+//! descriptors of instance processes and tracks how many consecutive samples
+//! each instance has spent above a memory ceiling, so a single spike does not
+//! trigger a restart. [`sample_usage`] takes a one-off reading for commands
+//! that don't keep a monitor around, like `mik ps`.
+
+use std::collections::HashMap;
+use sysinfo::{MINIMUM_CPU_UPDATE_INTERVAL, Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// A single resource sample of a process.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceSample {
+    /// Resident set size in bytes.
+    pub rss_bytes: u64,
+    /// CPU usage since the previous sample, in percent of one core.
+    ///
+    /// Zero on a process's first sample.
+    pub cpu_percent: f32,
+    /// Open file descriptors, where the platform reports them.
+    pub open_fds: Option<u64>,
+}
+
+impl ResourceSample {
+    fn of(process: &sysinfo::Process) -> Self {
+        Self {
+            rss_bytes: process.memory(),
+            cpu_percent: process.cpu_usage(),
+            open_fds: process.open_files().map(|n| n as u64),
+        }
+    }
+}
+
+/// Samples `pids` twice, [`MINIMUM_CPU_UPDATE_INTERVAL`] apart, so CPU usage
+/// is meaningful. Processes that are not running are left out.
+///
+/// Blocks for the interval.
+pub fn sample_usage(pids: &[u32]) -> HashMap<u32, ResourceSample> {
+    let pids: Vec<Pid> = pids.iter().copied().map(Pid::from_u32).collect();
+    let refresh = ProcessRefreshKind::nothing().with_memory().with_cpu();
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids), true, refresh);
+    std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids), true, refresh);
+
+    pids.iter()
+        .filter_map(|pid| {
+            let process = system.process(*pid)?;
+            Some((pid.as_u32(), ResourceSample::of(process)))
+        })
+        .collect()
+}
+
+/// Samples instance processes and tracks memory ceiling breaches.
+pub struct ResourceMonitor {
+    system: System,
+    /// Consecutive samples above the ceiling, by instance name.
+    breaches: HashMap<String, u32>,
+}
+
+impl ResourceMonitor {
+    /// Creates a monitor with no samples yet.
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            breaches: HashMap::new(),
+        }
+    }
+
+    /// Samples a process, or `None` if it is not running.
+    pub fn sample(&mut self, pid: u32) -> Option<ResourceSample> {
+        let pid = Pid::from_u32(pid);
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing().with_memory().with_cpu(),
+        );
+        self.system.process(pid).map(ResourceSample::of)
+    }
+
+    /// Records a sample against the ceiling for `instance`.
+    ///
+    /// Returns `true` once the instance has been above `ceiling_bytes` for
+    /// `required_samples` consecutive samples; the count then starts over.
+    pub fn exceeds_ceiling(
+        &mut self,
+        instance: &str,
+        rss_bytes: u64,
+        ceiling_bytes: u64,
+        required_samples: u32,
+    ) -> bool {
+        if rss_bytes <= ceiling_bytes {
+            self.breaches.remove(instance);
+            return false;
+        }
+
+        let count = self.breaches.entry(instance.to_string()).or_insert(0);
+        *count += 1;
+        if *count >= required_samples.max(1) {
+            self.breaches.remove(instance);
+            return true;
+        }
+        false
+    }
+
+    /// Drops tracking state for an instance (stopped or restarted).
+    pub fn forget(&mut self, instance: &str) {
+        self.breaches.remove(instance);
+    }
+}
+
+impl Default for ResourceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ceiling_requires_consecutive_samples() {
+        let mut monitor = ResourceMonitor::new();
+        let ceiling = 100;
+
+        assert!(!monitor.exceeds_ceiling("app", 150, ceiling, 3));
+        assert!(!monitor.exceeds_ceiling("app", 150, ceiling, 3));
+        // Dropping below resets the streak
+        assert!(!monitor.exceeds_ceiling("app", 50, ceiling, 3));
+        assert!(!monitor.breaches.contains_key("app"));
+
+        assert!(!monitor.exceeds_ceiling("app", 150, ceiling, 3));
+        assert!(!monitor.exceeds_ceiling("app", 150, ceiling, 3));
+        assert!(monitor.exceeds_ceiling("app", 150, ceiling, 3));
+        assert!(!monitor.breaches.contains_key("app"));
+
+        // Other instances are tracked separately
+        assert!(monitor.exceeds_ceiling("other", 150, ceiling, 1));
+    }
+
+    #[test]
+    fn test_sample_current_process() {
+        let mut monitor = ResourceMonitor::new();
+        let sample = monitor.sample(std::process::id()).unwrap();
+        assert!(sample.rss_bytes > 0);
+        #[cfg(target_os = "linux")]
+        assert!(sample.open_fds.is_some_and(|n| n > 0));
+        assert!(monitor.sample(u32::MAX).is_none());
+    }
+
+    #[test]
+    fn test_sample_usage_skips_missing_processes() {
+        let own = std::process::id();
+        let usage = sample_usage(&[own, u32::MAX]);
+        assert_eq!(usage.len(), 1);
+        assert!(usage[&own].rss_bytes > 0);
+    }
+}
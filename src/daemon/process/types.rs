@@ -4,6 +4,9 @@
 //! the process management subsystem.
 
 use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
 
 /// Default maximum log file size before rotation (10 MB).
 pub const DEFAULT_MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
@@ -67,6 +70,153 @@ pub struct SpawnConfig {
     pub working_dir: PathBuf,
     /// Enable hot-reload mode (bypasses AOT cache).
     pub hot_reload: bool,
+    /// File holding the last build error, served on module routes while it
+    /// exists (`mik dev`).
+    pub build_error_file: Option<PathBuf>,
+    /// Frontend dev server for paths the runtime does not serve (`mik dev
+    /// --proxy`).
+    pub dev_proxy: Option<String>,
+    /// Serve the development dashboard at `/_dev/` (`mik dev`).
+    pub dev_dashboard: bool,
+    /// What the daemon's supervisor does when the process exits.
+    pub restart: RestartConfig,
+}
+
+/// Default delay between the first two restarts of an instance.
+pub const DEFAULT_RESTART_BACKOFF_SECS: u64 = 5;
+
+/// Default cap on the delay between restarts.
+pub const DEFAULT_MAX_RESTART_BACKOFF_SECS: u64 = 300;
+
+/// When the supervisor restarts an instance whose process exited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Leave it down.
+    #[default]
+    Never,
+    /// Restart unless it exited with status 0.
+    OnFailure,
+    /// Restart however it exited.
+    Always,
+}
+
+impl std::fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Never => "never",
+            Self::OnFailure => "on-failure",
+            Self::Always => "always",
+        })
+    }
+}
+
+impl std::str::FromStr for RestartPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(Self::Never),
+            "on-failure" => Ok(Self::OnFailure),
+            "always" => Ok(Self::Always),
+            _ => Err(format!(
+                "Invalid restart policy '{s}' (expected never, on-failure, or always)"
+            )),
+        }
+    }
+}
+
+/// Restart policy of an instance, with its retry limit and backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartConfig {
+    pub policy: RestartPolicy,
+    /// Restarts before giving up. `None` uses the daemon's `max_auto_restarts`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+    /// Minimum time between the first two restarts; doubles with each
+    /// further restart.
+    pub backoff_secs: u64,
+    /// Cap on the time between restarts.
+    pub max_backoff_secs: u64,
+}
+
+impl Default for RestartConfig {
+    fn default() -> Self {
+        Self {
+            policy: RestartPolicy::Never,
+            max_retries: None,
+            backoff_secs: DEFAULT_RESTART_BACKOFF_SECS,
+            max_backoff_secs: DEFAULT_MAX_RESTART_BACKOFF_SECS,
+        }
+    }
+}
+
+impl RestartConfig {
+    /// `policy` with the default retry limit and backoff.
+    pub fn with_policy(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the policy restarts after `exit`.
+    ///
+    /// An unknown exit, from a process the daemon did not spawn itself,
+    /// counts as a failure.
+    pub fn restarts_after(&self, exit: Option<InstanceExit>) -> bool {
+        match self.policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => !exit.is_some_and(InstanceExit::is_success),
+            RestartPolicy::Always => true,
+        }
+    }
+
+    /// Minimum time since the previous restart before restarting again,
+    /// after `restarts` restarts so far.
+    pub fn backoff(&self, restarts: u32) -> Duration {
+        let factor = 2u64.saturating_pow(restarts.saturating_sub(1));
+        Duration::from_secs(
+            self.backoff_secs
+                .saturating_mul(factor)
+                .min(self.max_backoff_secs),
+        )
+    }
+}
+
+/// How an instance process exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceExit {
+    /// Exited with a status code.
+    Code(i32),
+    /// Killed by a signal.
+    Signal(i32),
+}
+
+impl InstanceExit {
+    /// Whether the process exited with status 0.
+    pub const fn is_success(self) -> bool {
+        matches!(self, Self::Code(0))
+    }
+
+    /// Shell-style exit code: the status, or 128 plus the signal number.
+    pub const fn exit_code(self) -> i32 {
+        match self {
+            Self::Code(code) => code,
+            Self::Signal(signal) => 128 + signal,
+        }
+    }
+}
+
+impl std::fmt::Display for InstanceExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Code(code) => write!(f, "exit code {code}"),
+            Self::Signal(signal) => write!(f, "signal {signal}"),
+        }
+    }
 }
 
 /// Information about a running instance.
@@ -77,3 +227,56 @@ pub struct InstanceInfo {
     /// Path to the log file.
     pub log_path: PathBuf,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_policies() {
+        let exits = [
+            None,
+            Some(InstanceExit::Code(0)),
+            Some(InstanceExit::Code(1)),
+            Some(InstanceExit::Signal(9)),
+        ];
+        let restarts =
+            |policy| exits.map(|exit| RestartConfig::with_policy(policy).restarts_after(exit));
+
+        assert_eq!(restarts(RestartPolicy::Never), [false; 4]);
+        assert_eq!(
+            restarts(RestartPolicy::OnFailure),
+            [true, false, true, true]
+        );
+        assert_eq!(restarts(RestartPolicy::Always), [true; 4]);
+    }
+
+    #[test]
+    fn test_restart_backoff_doubles_up_to_cap() {
+        let config = RestartConfig {
+            backoff_secs: 5,
+            max_backoff_secs: 30,
+            ..RestartConfig::default()
+        };
+        let secs: Vec<u64> = (1..=5).map(|n| config.backoff(n).as_secs()).collect();
+        assert_eq!(secs, [5, 10, 20, 30, 30]);
+        assert_eq!(config.backoff(u32::MAX).as_secs(), 30);
+    }
+
+    #[test]
+    fn test_restart_config_serde() {
+        let config: RestartConfig =
+            serde_json::from_str(r#"{"policy": "on-failure", "max_retries": 3}"#).unwrap();
+        assert_eq!(config.policy, RestartPolicy::OnFailure);
+        assert_eq!(config.max_retries, Some(3));
+        assert_eq!(config.backoff_secs, DEFAULT_RESTART_BACKOFF_SECS);
+
+        assert_eq!("always".parse::<RestartPolicy>(), Ok(RestartPolicy::Always));
+        assert!("sometimes".parse::<RestartPolicy>().is_err());
+        assert_eq!(
+            serde_json::to_value(InstanceExit::Signal(9)).unwrap(),
+            serde_json::json!({"signal": 9})
+        );
+        assert_eq!(InstanceExit::Signal(9).exit_code(), 137);
+    }
+}
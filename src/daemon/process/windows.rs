@@ -0,0 +1,186 @@
+//! Windows process control for mik daemon instances.
+//!
+//! Windows has no signals, so instances are managed through two named kernel
+//! objects keyed by the instance pid:
+//!
+//! - A Job Object (`Local\mik-instance-{pid}`) the instance is assigned to
+//!   right after spawning. Terminating the job kills the instance together
+//!   with any processes it started, like killing a Unix process group.
+//! - A stop event (`Local\mik-stop-{pid}`) the instance creates and waits on.
+//!   Setting it is the equivalent of SIGTERM: the instance stops accepting
+//!   and drains in-flight requests.
+//!
+//! Both are opened by name, so `mik stop` works from any process in the
+//! session, not just the one that spawned the instance.
+
+#![allow(unsafe_code)] // SAFETY: Win32 FFI; every handle is checked and closed
+
+use anyhow::{Context, Result, bail};
+use std::os::windows::io::AsRawHandle;
+use std::os::windows::process::CommandExt;
+use std::process::{Child, Command};
+
+use windows_sys::Win32::Foundation::{CloseHandle, FALSE, HANDLE, TRUE, WAIT_OBJECT_0};
+use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, OpenJobObjectW, TerminateJobObject,
+};
+use windows_sys::Win32::System::Threading::{
+    CREATE_BREAKAWAY_FROM_JOB, CREATE_NEW_PROCESS_GROUP, CreateEventW, DETACHED_PROCESS,
+    EVENT_MODIFY_STATE, INFINITE, OpenEventW, OpenProcess, PROCESS_TERMINATE, SetEvent,
+    TerminateProcess, WaitForSingleObject,
+};
+
+/// Win32 `JOB_OBJECT_TERMINATE` access right (defined in `SystemServices`).
+const JOB_OBJECT_TERMINATE: u32 = 0x0008;
+
+/// Exit code of instances terminated with their job.
+const TERMINATED_EXIT_CODE: u32 = 1;
+
+/// Win32 `ERROR_ACCESS_DENIED`, returned when breaking away from the
+/// parent's job is not allowed.
+const ERROR_ACCESS_DENIED: i32 = 5;
+
+/// Spawns `cmd` detached from the caller's console and process group.
+///
+/// The child breaks away from the caller's job when allowed, so closing the
+/// terminal or a job-managed shell doesn't take it down with the caller.
+pub fn spawn_detached(cmd: &mut Command) -> std::io::Result<Child> {
+    let flags = DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP;
+    match cmd
+        .creation_flags(flags | CREATE_BREAKAWAY_FROM_JOB)
+        .spawn()
+    {
+        Err(e) if e.raw_os_error() == Some(ERROR_ACCESS_DENIED) => {
+            cmd.creation_flags(flags).spawn()
+        },
+        result => result,
+    }
+}
+
+/// Assigns a freshly spawned instance to its own named Job Object.
+///
+/// The job is created without `KILL_ON_JOB_CLOSE` and outlives the handle
+/// closed here for as long as the instance runs.
+pub fn assign_job(child: &Child) -> Result<()> {
+    let name = wide(&job_name(child.id()));
+    // SAFETY: `name` is NUL-terminated and outlives the call; null
+    // attributes request the default security descriptor.
+    let job = unsafe { CreateJobObjectW(std::ptr::null(), name.as_ptr()) };
+    if job.is_null() {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to create job object for process {}", child.id()));
+    }
+    // SAFETY: `job` is a valid job handle and the child handle stays open
+    // while `child` is borrowed.
+    let assigned = unsafe { AssignProcessToJobObject(job, child.as_raw_handle() as HANDLE) };
+    let error = std::io::Error::last_os_error();
+    close(job);
+    if assigned == FALSE {
+        return Err(error)
+            .with_context(|| format!("Failed to assign process {} to its job", child.id()));
+    }
+    Ok(())
+}
+
+/// Asks an instance to stop gracefully by setting its stop event.
+///
+/// Returns whether the event was set; `false` means the process is not a
+/// mik instance listening for stop requests.
+pub fn request_stop(pid: u32) -> bool {
+    let name = wide(&stop_event_name(pid));
+    // SAFETY: `name` is NUL-terminated and outlives the call.
+    let event = unsafe { OpenEventW(EVENT_MODIFY_STATE, FALSE, name.as_ptr()) };
+    if event.is_null() {
+        return false;
+    }
+    // SAFETY: `event` was opened with EVENT_MODIFY_STATE above.
+    let set = unsafe { SetEvent(event) } != FALSE;
+    close(event);
+    set
+}
+
+/// Forcefully terminates an instance and every process in its job.
+///
+/// Falls back to terminating just the process for processes without a job,
+/// such as instances spawned by an older mik.
+pub fn terminate(pid: u32) -> Result<()> {
+    let name = wide(&job_name(pid));
+    // SAFETY: `name` is NUL-terminated and outlives the call.
+    let job = unsafe { OpenJobObjectW(JOB_OBJECT_TERMINATE, FALSE, name.as_ptr()) };
+    if !job.is_null() {
+        // SAFETY: `job` was opened with JOB_OBJECT_TERMINATE above.
+        let terminated = unsafe { TerminateJobObject(job, TERMINATED_EXIT_CODE) } != FALSE;
+        let error = std::io::Error::last_os_error();
+        close(job);
+        if terminated {
+            return Ok(());
+        }
+        return Err(error).with_context(|| format!("Failed to terminate job of process {pid}"));
+    }
+
+    // SAFETY: plain FFI call; the result is checked before use.
+    let process = unsafe { OpenProcess(PROCESS_TERMINATE, FALSE, pid) };
+    if process.is_null() {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to open process {pid}"));
+    }
+    // SAFETY: `process` was opened with PROCESS_TERMINATE above.
+    let terminated = unsafe { TerminateProcess(process, TERMINATED_EXIT_CODE) } != FALSE;
+    let error = std::io::Error::last_os_error();
+    close(process);
+    if !terminated {
+        bail!("Failed to terminate process {pid}: {error}");
+    }
+    Ok(())
+}
+
+/// Completes when another process asks this one to stop with
+/// [`request_stop`].
+///
+/// Never completes if the stop event cannot be created.
+pub async fn stop_requested() {
+    let name = wide(&stop_event_name(std::process::id()));
+    // SAFETY: `name` is NUL-terminated and outlives the call. The event is
+    // manual-reset and starts unset.
+    let event = unsafe { CreateEventW(std::ptr::null(), TRUE, FALSE, name.as_ptr()) };
+    if event.is_null() {
+        tracing::warn!(
+            error = %std::io::Error::last_os_error(),
+            "Failed to create stop event; `mik stop` will terminate this process"
+        );
+        return std::future::pending().await;
+    }
+
+    // Handles are not Send; pass the address to the blocking thread
+    let event_addr = event as usize;
+    let waited = tokio::task::spawn_blocking(move || {
+        // SAFETY: the handle stays open until this wait returns. It is
+        // never closed, as the process exits after a stop request.
+        unsafe { WaitForSingleObject(event_addr as HANDLE, INFINITE) }
+    })
+    .await;
+    if !matches!(waited, Ok(WAIT_OBJECT_0)) {
+        tracing::warn!("Waiting for the stop event failed");
+        std::future::pending::<()>().await;
+    }
+}
+
+fn job_name(pid: u32) -> String {
+    format!("Local\\mik-instance-{pid}")
+}
+
+fn stop_event_name(pid: u32) -> String {
+    format!("Local\\mik-stop-{pid}")
+}
+
+/// NUL-terminated UTF-16 for Win32 `W` functions.
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+fn close(handle: HANDLE) {
+    // SAFETY: callers pass handles they opened and no longer use.
+    unsafe {
+        CloseHandle(handle);
+    }
+}
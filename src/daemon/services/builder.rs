@@ -3,7 +3,10 @@
 //! Provides a fluent API for configuring KV, SQL, and Storage services
 //! with custom backends or default file-based implementations.
 
-use super::kv::{KvBackend, KvStore, MemoryBackend as KvMemoryBackend, RedbBackend};
+use super::kv::{
+    CacheConfig, CachedRedbBackend, KvBackend, KvStore, MemoryBackend as KvMemoryBackend,
+    RedbBackend,
+};
 use super::sql::{MemorySqlBackend, SqlBackend, SqlService, SqliteBackend};
 use super::storage::{FilesystemBackend, MemoryStorageBackend, StorageBackend, StorageService};
 use anyhow::{Context, Result};
@@ -124,6 +127,18 @@ impl ServicesBuilder {
         Ok(self)
     }
 
+    /// Configures KV with a file-based redb backend behind a write-behind cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened.
+    pub fn kv_file_cached<P: AsRef<Path>>(mut self, path: P, config: CacheConfig) -> Result<Self> {
+        let backend = CachedRedbBackend::open(path, config)?;
+        self.kv = Some(Box::new(backend));
+        self.kv_disabled = false;
+        Ok(self)
+    }
+
     /// Configures KV with an in-memory backend.
     #[must_use]
     pub fn kv_memory(mut self) -> Self {
@@ -3,6 +3,8 @@
 //! Defines the interface that all KV storage backends must implement,
 //! enabling pluggable storage (redb, memory, Redis, etc.).
 
+use super::transaction::{KvCommit, KvWrite};
+use super::types::KvScan;
 use anyhow::Result;
 use async_trait::async_trait;
 use std::time::Duration;
@@ -68,6 +70,132 @@ pub trait KvBackend: Send + Sync + 'static {
     /// Returns an error if the underlying storage operation fails.
     async fn list(&self, prefix: Option<&str>) -> Result<Vec<String>>;
 
+    /// Stores `new` only if the current value equals `expected`.
+    ///
+    /// `expected: None` means the key must not exist (or have expired).
+    /// Returns `Ok(true)` if the value was swapped. The comparison and the
+    /// write must be atomic with respect to every other write to the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<&[u8]>,
+        new: Vec<u8>,
+        ttl: Option<Duration>,
+    ) -> Result<bool>;
+
+    /// Atomically adds `delta` to the integer stored at `key`, returning the
+    /// new value.
+    ///
+    /// Counters are stored as decimal text. A missing key counts from zero;
+    /// an existing key keeps its expiry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current value is not an integer, the result
+    /// overflows `i64`, or the underlying storage operation fails.
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64>;
+
+    /// Applies `commit.writes` if every key in `commit.reads` still has the
+    /// value recorded there.
+    ///
+    /// Returns `Ok(false)` without writing anything on conflict. The check
+    /// and all writes must be atomic: other operations see either none or
+    /// all of the writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    async fn commit(&self, commit: KvCommit) -> Result<bool>;
+
+    /// Retrieves several values at once, in the order of `keys`.
+    ///
+    /// The default implementation calls [`get`](Self::get) per key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
+    }
+
+    /// Stores several key-value pairs atomically, all with the same TTL.
+    ///
+    /// The default implementation applies them as one [`commit`](Self::commit).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    async fn set_many(&self, entries: Vec<(String, Vec<u8>)>, ttl: Option<Duration>) -> Result<()> {
+        let commit = KvCommit {
+            reads: std::collections::BTreeMap::new(),
+            writes: entries
+                .into_iter()
+                .map(|(key, value)| (key, KvWrite::Set { value, ttl }))
+                .collect(),
+        };
+        self.commit(commit).await?;
+        Ok(())
+    }
+
+    /// Deletes several keys, returning whether each one existed.
+    ///
+    /// The default implementation calls [`delete`](Self::delete) per key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    async fn delete_many(&self, keys: &[String]) -> Result<Vec<bool>> {
+        let mut deleted = Vec::with_capacity(keys.len());
+        for key in keys {
+            deleted.push(self.delete(key).await?);
+        }
+        Ok(deleted)
+    }
+
+    /// Returns up to `limit` keys matching `prefix`, with their values, in
+    /// key order, starting after `cursor`.
+    ///
+    /// The cursor is the one returned by the previous page. Pages are read
+    /// independently, so writes between them may or may not be seen.
+    ///
+    /// The default implementation lists and sorts every matching key, then
+    /// reads one page; ordered backends should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    async fn scan(
+        &self,
+        prefix: Option<&str>,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<KvScan> {
+        let mut keys = self.list(prefix).await?;
+        keys.sort_unstable();
+        let mut entries = Vec::new();
+        for key in keys
+            .into_iter()
+            .filter(|key| cursor.is_none_or(|cursor| key.as_str() > cursor))
+        {
+            if entries.len() > limit {
+                break;
+            }
+            // Skip keys deleted since they were listed
+            if let Some(value) = self.get(&key).await? {
+                entries.push((key, value));
+            }
+        }
+        Ok(KvScan::page(entries, limit))
+    }
+
     /// Checks if a key exists and has not expired.
     ///
     /// Default implementation uses `get()`, but backends may override
@@ -79,4 +207,15 @@ pub trait KvBackend: Send + Sync + 'static {
     async fn exists(&self, key: &str) -> Result<bool> {
         Ok(self.get(key).await?.is_some())
     }
+
+    /// Commits any buffered writes to durable storage.
+    ///
+    /// Backends that write through on every call need not override this.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffered writes cannot be committed.
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
 }
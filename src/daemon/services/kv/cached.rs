@@ -0,0 +1,635 @@
+//! Write-behind memory cache over the redb backend.
+//!
+//! Keeps recently read entries in memory and buffers writes, committing
+//! them to redb in one transaction per flush. Reads and writes that hit the
+//! cache never touch the database or the blocking thread pool.
+
+use super::backend::KvBackend;
+use super::redb::{RedbBackend, increment_entry, swap_entry, write_entry};
+use super::transaction::{KvCommit, KvWrite};
+use super::types::{KvEntry, KvScan};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use moka::sync::Cache;
+use parking_lot::RwLock;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+/// Default number of entries kept in the hot cache.
+const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// Default interval between write-behind flushes.
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration for [`CachedRedbBackend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Maximum number of entries held in the hot cache.
+    ///
+    /// Also bounds the write buffer: reaching this many unflushed writes
+    /// triggers an immediate flush.
+    pub max_entries: usize,
+    /// How often buffered writes are committed to redb.
+    pub flush_interval: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+        }
+    }
+}
+
+/// Result of looking a key up in memory only.
+enum Lookup {
+    /// The key has a live value in memory.
+    Found(Vec<u8>),
+    /// The key is known to be absent (pending delete or expired write).
+    Absent,
+    /// Memory has nothing for the key; redb must be consulted.
+    Miss,
+}
+
+/// Shared state between the backend handles and the flush task.
+struct CacheInner {
+    redb: RedbBackend,
+    /// Entries known to match what is committed in redb.
+    hot: Cache<String, KvEntry>,
+    /// Writes not yet committed. `None` is a pending delete.
+    dirty: DashMap<String, Option<KvEntry>>,
+    /// Held exclusively while a flush commits and publishes its batch, so a
+    /// concurrent cache fill cannot insert a value the flush just replaced.
+    publish: RwLock<()>,
+    /// Held exclusively while a transaction checks and buffers its writes,
+    /// and shared by every other access to the write buffer, so nothing
+    /// observes a partially buffered transaction.
+    commit: RwLock<()>,
+    max_dirty: usize,
+}
+
+impl CacheInner {
+    /// Answers a read from memory, if possible.
+    fn get_cached(&self, key: &str) -> Result<Lookup> {
+        let _shared = self.commit.read();
+        if let Some(pending) = self.dirty.get(key) {
+            return match pending.value() {
+                Some(entry) if !entry.is_expired()? => Ok(Lookup::Found(entry.value.clone())),
+                _ => Ok(Lookup::Absent),
+            };
+        }
+
+        if let Some(entry) = self.hot.get(key) {
+            if !entry.is_expired()? {
+                return Ok(Lookup::Found(entry.value));
+            }
+            // Fall through so redb removes the expired entry
+            self.hot.invalidate(key);
+        }
+
+        Ok(Lookup::Miss)
+    }
+
+    fn get_sync(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match self.get_cached(key)? {
+            Lookup::Found(value) => return Ok(Some(value)),
+            Lookup::Absent => return Ok(None),
+            Lookup::Miss => {},
+        }
+
+        let _guard = self.publish.read();
+        let entry = self.redb.get_entry_sync(key)?;
+        if let Some(entry) = &entry {
+            self.hot.insert(key.to_string(), entry.clone());
+        }
+        Ok(entry.map(|entry| entry.value))
+    }
+
+    fn list_sync(&self, prefix: Option<&str>) -> Result<Vec<String>> {
+        let _shared = self.commit.read();
+        let _guard = self.publish.read();
+        let mut keys: BTreeSet<String> = self.redb.list_sync(prefix)?.into_iter().collect();
+
+        for pending in &self.dirty {
+            let key = pending.key();
+            if prefix.is_some_and(|p| !key.starts_with(p)) {
+                continue;
+            }
+            match pending.value() {
+                Some(entry) if !entry.is_expired()? => {
+                    keys.insert(key.clone());
+                },
+                _ => {
+                    keys.remove(key);
+                },
+            }
+        }
+
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Reads a key and buffers a write to it atomically.
+    ///
+    /// Holds the key's write-buffer slot for the whole read-modify-write,
+    /// so concurrent writes to the key wait, and blocks flushes so the
+    /// committed value cannot change underneath. Returns the result and
+    /// whether the buffer is now full.
+    fn update_sync<T>(
+        &self,
+        key: &str,
+        update: impl FnOnce(Option<KvEntry>) -> Result<(Option<KvEntry>, T)>,
+    ) -> Result<(T, bool)> {
+        // Lock order: commit lock, publish lock, then write buffer
+        let _shared = self.commit.read();
+        let _guard = self.publish.read();
+        let slot = self.dirty.entry(key.to_string());
+        let current = match &slot {
+            dashmap::mapref::entry::Entry::Occupied(pending) => pending.get().clone(),
+            dashmap::mapref::entry::Entry::Vacant(_) => self.committed_entry(key)?,
+        };
+        let current = live(current)?;
+
+        let (entry, result) = update(current)?;
+        let Some(entry) = entry else {
+            return Ok((result, false));
+        };
+        drop(slot.insert(Some(entry)));
+        Ok((result, self.dirty.len() >= self.max_dirty))
+    }
+
+    /// The committed entry for a key: from the hot cache, else from redb.
+    ///
+    /// Callers hold the publish lock, so no flush changes it meanwhile.
+    fn committed_entry(&self, key: &str) -> Result<Option<KvEntry>> {
+        match self.hot.get(key) {
+            Some(entry) => Ok(Some(entry)),
+            None => self.redb.get_entry_sync(key),
+        }
+    }
+
+    /// Checks a transaction's reads and buffers its writes as one step.
+    ///
+    /// Returns whether it committed and whether the buffer is now full. The
+    /// writes reach redb in the same flush, since a flush commits every
+    /// buffered write in one transaction.
+    fn commit_sync(&self, commit: KvCommit) -> Result<(bool, bool)> {
+        let _exclusive = self.commit.write();
+        let _guard = self.publish.read();
+
+        for (key, expected) in &commit.reads {
+            let pending = self.dirty.get(key).map(|pending| pending.value().clone());
+            let current = match pending {
+                Some(pending) => pending,
+                None => self.committed_entry(key)?,
+            };
+            let current = live(current)?;
+            if current.as_ref().map(|entry| entry.value.as_slice()) != expected.as_deref() {
+                return Ok((false, false));
+            }
+        }
+
+        for (key, write) in commit.writes {
+            let entry = match write {
+                KvWrite::Set { value, ttl } => Some(write_entry(value, ttl)?),
+                KvWrite::Delete => None,
+            };
+            self.dirty.insert(key, entry);
+        }
+        Ok((true, self.dirty.len() >= self.max_dirty))
+    }
+
+    /// Reads a page of entries, overlaying buffered writes on redb.
+    fn scan_sync(
+        &self,
+        prefix: Option<&str>,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<KvScan> {
+        let _shared = self.commit.read();
+        let _guard = self.publish.read();
+
+        let in_range =
+            |key: &str| prefix.is_none_or(|p| key.starts_with(p)) && cursor.is_none_or(|c| key > c);
+        let pending: BTreeMap<String, Option<KvEntry>> = self
+            .dirty
+            .iter()
+            .filter(|pending| in_range(pending.key()))
+            .map(|pending| (pending.key().clone(), pending.value().clone()))
+            .collect();
+
+        // Buffered writes can override at most `pending.len()` committed
+        // entries, so reading that many more fills the page
+        let mut merged: BTreeMap<String, Vec<u8>> = self
+            .redb
+            .scan_sync(prefix, cursor, limit.saturating_add(1 + pending.len()))?
+            .into_iter()
+            .filter(|(key, _)| !pending.contains_key(key))
+            .map(|(key, entry)| (key, entry.value))
+            .collect();
+        for (key, entry) in pending {
+            if let Some(entry) = live(entry)? {
+                merged.insert(key, entry.value);
+            }
+        }
+
+        Ok(KvScan::page(
+            merged.into_iter().take(limit.saturating_add(1)).collect(),
+            limit,
+        ))
+    }
+
+    /// Buffers a write, returning `true` if the buffer is now full.
+    fn buffer(&self, key: &str, entry: Option<KvEntry>) -> bool {
+        let _shared = self.commit.read();
+        self.dirty.insert(key.to_string(), entry);
+        self.dirty.len() >= self.max_dirty
+    }
+
+    /// Commits all buffered writes to redb in a single transaction.
+    ///
+    /// Returns the number of keys written. Writes that arrive while the
+    /// batch is being committed stay buffered for the next flush.
+    fn flush_sync(&self) -> Result<usize> {
+        let _guard = self.publish.write();
+
+        let batch: Vec<(String, Option<KvEntry>)> = self
+            .dirty
+            .iter()
+            .map(|pending| (pending.key().clone(), pending.value().clone()))
+            .collect();
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        self.redb
+            .write_batch_sync(&batch)
+            .context("Failed to flush KV write buffer")?;
+
+        for (key, entry) in &batch {
+            match entry {
+                Some(entry) => self.hot.insert(key.clone(), entry.clone()),
+                None => self.hot.invalidate(key),
+            }
+            self.dirty.remove_if(key, |_, pending| pending == entry);
+        }
+
+        Ok(batch.len())
+    }
+}
+
+/// `entry` unless it has expired.
+fn live(entry: Option<KvEntry>) -> Result<Option<KvEntry>> {
+    match entry {
+        Some(entry) if !entry.is_expired()? => Ok(Some(entry)),
+        _ => Ok(None),
+    }
+}
+
+impl Drop for CacheInner {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush_sync() {
+            tracing::error!(
+                error = %e,
+                pending = self.dirty.len(),
+                "Failed to flush KV write buffer on shutdown"
+            );
+        }
+    }
+}
+
+/// Redb backend with a hot in-memory cache and write-behind buffering.
+///
+/// Reads are served from memory when the key was recently read or written.
+/// Writes return as soon as they are buffered and are committed to redb on
+/// a fixed interval, when the buffer fills up, on [`KvBackend::flush`], or
+/// when the last handle is dropped.
+///
+/// # Durability
+///
+/// Each flush is a single redb transaction, so the database always holds a
+/// consistent snapshot as of some flush. A crash loses at most the writes
+/// buffered since the last flush; it never leaves a partial batch on disk.
+///
+/// The periodic flush task is only started when the backend is created
+/// inside a tokio runtime. Outside one, writes are committed when the buffer
+/// fills, on explicit flush, or on drop.
+///
+/// # Example
+///
+/// ```ignore
+/// use mik::daemon::services::kv::{CacheConfig, CachedRedbBackend, KvStore};
+///
+/// let backend = CachedRedbBackend::open("~/.mik/kv.redb", CacheConfig::default())?;
+/// let store = KvStore::custom(backend);
+/// ```
+#[derive(Clone)]
+pub struct CachedRedbBackend {
+    inner: Arc<CacheInner>,
+}
+
+impl CachedRedbBackend {
+    /// Opens or creates a redb database at `path` with a cache in front.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying database cannot be opened.
+    pub fn open<P: AsRef<Path>>(path: P, config: CacheConfig) -> Result<Self> {
+        Ok(Self::new(RedbBackend::open(path)?, config))
+    }
+
+    /// Wraps an already opened redb backend with a cache.
+    pub fn new(redb: RedbBackend, config: CacheConfig) -> Self {
+        let max_entries = config.max_entries.max(1);
+        let inner = Arc::new(CacheInner {
+            redb,
+            hot: Cache::new(u64::try_from(max_entries).unwrap_or(u64::MAX)),
+            dirty: DashMap::new(),
+            publish: RwLock::new(()),
+            commit: RwLock::new(()),
+            max_dirty: max_entries,
+        });
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(flush_task(Arc::downgrade(&inner), config.flush_interval));
+            },
+            Err(_) => {
+                tracing::debug!("No tokio runtime, KV write buffer flushes only on demand");
+            },
+        }
+
+        Self { inner }
+    }
+
+    /// Returns the number of writes waiting to be flushed.
+    pub fn pending_writes(&self) -> usize {
+        self.inner.dirty.len()
+    }
+
+    async fn flush_blocking(&self) -> Result<usize> {
+        let inner = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || inner.flush_sync())
+            .await
+            .context("Task join error")?
+    }
+}
+
+/// Periodically flushes the write buffer until the backend is dropped.
+async fn flush_task(inner: Weak<CacheInner>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // The first tick completes immediately
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        let Some(inner) = inner.upgrade() else {
+            break;
+        };
+        if inner.dirty.is_empty() {
+            continue;
+        }
+
+        match tokio::task::spawn_blocking(move || inner.flush_sync()).await {
+            Ok(Ok(count)) => tracing::trace!(count, "Flushed KV write buffer"),
+            Ok(Err(e)) => tracing::warn!(error = %e, "Failed to flush KV write buffer"),
+            Err(e) => tracing::warn!(error = %e, "KV flush task panicked"),
+        }
+    }
+}
+
+#[async_trait]
+impl KvBackend for CachedRedbBackend {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match self.inner.get_cached(key)? {
+            Lookup::Found(value) => return Ok(Some(value)),
+            Lookup::Absent => return Ok(None),
+            Lookup::Miss => {},
+        }
+
+        let inner = Arc::clone(&self.inner);
+        let key = key.to_string();
+        tokio::task::spawn_blocking(move || inner.get_sync(&key))
+            .await
+            .context("Task join error")?
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let entry = if let Some(ttl) = ttl {
+            KvEntry::with_ttl(value, ttl.as_secs())?
+        } else {
+            KvEntry::new(value)
+        };
+
+        if self.inner.buffer(key, Some(entry)) {
+            self.flush_blocking().await?;
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool> {
+        let existed = self.get(key).await?.is_some();
+        if self.inner.buffer(key, None) {
+            self.flush_blocking().await?;
+        }
+        Ok(existed)
+    }
+
+    async fn list(&self, prefix: Option<&str>) -> Result<Vec<String>> {
+        let inner = Arc::clone(&self.inner);
+        let prefix = prefix.map(std::string::ToString::to_string);
+        tokio::task::spawn_blocking(move || inner.list_sync(prefix.as_deref()))
+            .await
+            .context("Task join error")?
+    }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<&[u8]>,
+        new: Vec<u8>,
+        ttl: Option<Duration>,
+    ) -> Result<bool> {
+        let inner = Arc::clone(&self.inner);
+        let key = key.to_string();
+        let expected = expected.map(<[u8]>::to_vec);
+        let (swapped, full) = tokio::task::spawn_blocking(move || {
+            inner.update_sync(&key, |current| {
+                swap_entry(current.as_ref(), expected.as_deref(), new, ttl)
+            })
+        })
+        .await
+        .context("Task join error")??;
+        if full {
+            self.flush_blocking().await?;
+        }
+        Ok(swapped)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64> {
+        let inner = Arc::clone(&self.inner);
+        let key = key.to_string();
+        let (value, full) = tokio::task::spawn_blocking(move || {
+            inner.update_sync(&key, |current| increment_entry(&key, current, delta))
+        })
+        .await
+        .context("Task join error")??;
+        if full {
+            self.flush_blocking().await?;
+        }
+        Ok(value)
+    }
+
+    async fn scan(
+        &self,
+        prefix: Option<&str>,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<KvScan> {
+        let inner = Arc::clone(&self.inner);
+        let prefix = prefix.map(str::to_string);
+        let cursor = cursor.map(str::to_string);
+        tokio::task::spawn_blocking(move || {
+            inner.scan_sync(prefix.as_deref(), cursor.as_deref(), limit)
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    async fn commit(&self, commit: KvCommit) -> Result<bool> {
+        let inner = Arc::clone(&self.inner);
+        let (committed, full) = tokio::task::spawn_blocking(move || inner.commit_sync(commit))
+            .await
+            .context("Task join error")??;
+        if full {
+            self.flush_blocking().await?;
+        }
+        Ok(committed)
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.flush_blocking().await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Config that never flushes on its own during a test.
+    fn manual_flush() -> CacheConfig {
+        CacheConfig {
+            max_entries: 100,
+            flush_interval: Duration::from_secs(3600),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_are_buffered_until_flush() {
+        let tmp = TempDir::new().unwrap();
+        let backend = CachedRedbBackend::open(tmp.path().join("kv.redb"), manual_flush()).unwrap();
+
+        backend.set("key", b"value".to_vec(), None).await.unwrap();
+        assert_eq!(backend.get("key").await.unwrap().unwrap(), b"value");
+        assert_eq!(backend.pending_writes(), 1);
+        assert!(backend.inner.redb.get_entry_sync("key").unwrap().is_none());
+
+        backend.flush().await.unwrap();
+        assert_eq!(backend.pending_writes(), 0);
+        let stored = backend.inner.redb.get_entry_sync("key").unwrap().unwrap();
+        assert_eq!(stored.value, b"value");
+        assert_eq!(backend.get("key").await.unwrap().unwrap(), b"value");
+    }
+
+    #[tokio::test]
+    async fn test_pending_delete_hides_committed_value() {
+        let tmp = TempDir::new().unwrap();
+        let backend = CachedRedbBackend::open(tmp.path().join("kv.redb"), manual_flush()).unwrap();
+
+        backend.set("user:1", b"a".to_vec(), None).await.unwrap();
+        backend.set("user:2", b"b".to_vec(), None).await.unwrap();
+        backend.flush().await.unwrap();
+
+        assert!(backend.delete("user:1").await.unwrap());
+        assert!(!backend.delete("user:missing").await.unwrap());
+        backend.set("user:3", b"c".to_vec(), None).await.unwrap();
+
+        assert!(backend.get("user:1").await.unwrap().is_none());
+        assert!(
+            backend
+                .inner
+                .redb
+                .get_entry_sync("user:1")
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            backend.list(Some("user:")).await.unwrap(),
+            vec!["user:2".to_string(), "user:3".to_string()]
+        );
+
+        backend.flush().await.unwrap();
+        assert!(
+            backend
+                .inner
+                .redb
+                .get_entry_sync("user:1")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_buffer_flushes_immediately() {
+        let tmp = TempDir::new().unwrap();
+        let config = CacheConfig {
+            max_entries: 2,
+            ..manual_flush()
+        };
+        let backend = CachedRedbBackend::open(tmp.path().join("kv.redb"), config).unwrap();
+
+        backend.set("a", b"1".to_vec(), None).await.unwrap();
+        assert_eq!(backend.pending_writes(), 1);
+        backend.set("b", b"2".to_vec(), None).await.unwrap();
+        assert_eq!(backend.pending_writes(), 0);
+        assert!(backend.inner.redb.get_entry_sync("a").unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_periodic_flush() {
+        let tmp = TempDir::new().unwrap();
+        let config = CacheConfig {
+            max_entries: 100,
+            flush_interval: Duration::from_millis(20),
+        };
+        let backend = CachedRedbBackend::open(tmp.path().join("kv.redb"), config).unwrap();
+
+        backend.set("key", b"value".to_vec(), None).await.unwrap();
+        for _ in 0..50 {
+            if backend.pending_writes() == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(backend.pending_writes(), 0);
+        assert!(backend.inner.redb.get_entry_sync("key").unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_drop_flushes_pending_writes() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("kv.redb");
+
+        let backend = CachedRedbBackend::open(&path, manual_flush()).unwrap();
+        backend.set("key", b"value".to_vec(), None).await.unwrap();
+        drop(backend);
+
+        let reopened = RedbBackend::open(&path).unwrap();
+        assert_eq!(reopened.get("key").await.unwrap().unwrap(), b"value");
+    }
+}
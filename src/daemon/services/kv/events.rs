@@ -0,0 +1,113 @@
+//! Key change notifications.
+//!
+//! [`KvStore::watch`](super::KvStore::watch) subscribes to [`KvEvent`]s for
+//! keys under a prefix. Events are emitted for writes made through the
+//! store: sets (including compare-and-swap, increments, and transaction
+//! writes) and deletes. Keys that expire through their TTL are not
+//! reported.
+//!
+//! A watcher that falls more than [`WATCH_CAPACITY`] events behind skips
+//! ahead (`RecvError::Lagged`).
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Events buffered per watcher.
+pub const WATCH_CAPACITY: usize = 1024;
+
+/// What happened to a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KvEventKind {
+    /// The key was written.
+    Set,
+    /// The key was deleted.
+    Delete,
+}
+
+impl KvEventKind {
+    /// Name of the event, as used for SSE event types.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Set => "set",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// A change to one key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KvEvent {
+    /// What happened.
+    pub kind: KvEventKind,
+    /// The key.
+    pub key: String,
+    /// The new value, for sets.
+    pub value: Option<Vec<u8>>,
+    /// When the change happened.
+    pub time: DateTime<Utc>,
+}
+
+impl KvEvent {
+    pub(crate) fn set(key: &str, value: Vec<u8>) -> Self {
+        Self {
+            kind: KvEventKind::Set,
+            key: key.to_string(),
+            value: Some(value),
+            time: Utc::now(),
+        }
+    }
+
+    pub(crate) fn delete(key: &str) -> Self {
+        Self {
+            kind: KvEventKind::Delete,
+            key: key.to_string(),
+            value: None,
+            time: Utc::now(),
+        }
+    }
+}
+
+/// A subscription to changes under a key prefix.
+pub struct KvWatch {
+    receiver: broadcast::Receiver<KvEvent>,
+    /// Full key prefix to report.
+    prefix: String,
+    /// Length of the tenant namespace stripped from reported keys.
+    namespace_len: usize,
+}
+
+impl KvWatch {
+    /// Watches `prefix` within `namespace` (empty for the whole store),
+    /// reporting keys relative to the namespace.
+    pub(crate) fn new(
+        receiver: broadcast::Receiver<KvEvent>,
+        namespace: &str,
+        prefix: &str,
+    ) -> Self {
+        Self {
+            receiver,
+            prefix: format!("{namespace}{prefix}"),
+            namespace_len: namespace.len(),
+        }
+    }
+
+    /// Waits for the next change to a key under the prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RecvError::Lagged` if events were skipped because this
+    /// watcher fell behind (the next call continues with newer events), or
+    /// `RecvError::Closed` once the store is gone.
+    pub async fn recv(&mut self) -> Result<KvEvent, RecvError> {
+        loop {
+            let mut event = self.receiver.recv().await?;
+            if event.key.starts_with(&self.prefix) {
+                event.key.drain(..self.namespace_len);
+                return Ok(event);
+            }
+        }
+    }
+}
@@ -4,9 +4,14 @@
 //! concurrent access. Ideal for testing, development, and embedded use cases.
 
 use super::backend::KvBackend;
+use super::transaction::{KvCommit, KvWrite};
+use super::types::add_to_counter;
 use anyhow::Result;
 use async_trait::async_trait;
 use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use parking_lot::RwLock;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Entry stored in the memory backend with optional expiration.
@@ -40,7 +45,8 @@ impl MemoryEntry {
 /// # Thread Safety
 ///
 /// `MemoryBackend` is `Clone` and uses `DashMap` internally for
-/// lock-free concurrent access.
+/// lock-free concurrent access. Transactions take an exclusive lock that
+/// other operations share, so they never observe a partial commit.
 ///
 /// # Example
 ///
@@ -53,6 +59,7 @@ impl MemoryEntry {
 #[derive(Clone, Default)]
 pub struct MemoryBackend {
     data: DashMap<String, MemoryEntry>,
+    commit_lock: Arc<RwLock<()>>,
 }
 
 impl MemoryBackend {
@@ -79,12 +86,14 @@ impl MemoryBackend {
     /// entries. Otherwise, expired entries are cleaned up lazily on access.
     #[allow(dead_code)]
     pub fn cleanup_expired(&self) {
+        let _shared = self.commit_lock.read();
         self.data.retain(|_, entry| !entry.is_expired());
     }
 
     /// Clears all entries from the store.
     #[allow(dead_code)]
     pub fn clear(&self) {
+        let _shared = self.commit_lock.read();
         self.data.clear();
     }
 }
@@ -92,6 +101,7 @@ impl MemoryBackend {
 #[async_trait]
 impl KvBackend for MemoryBackend {
     async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let _shared = self.commit_lock.read();
         if let Some(entry) = self.data.get(key) {
             if entry.is_expired() {
                 drop(entry);
@@ -106,16 +116,19 @@ impl KvBackend for MemoryBackend {
     }
 
     async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
+        let _shared = self.commit_lock.read();
         let entry = MemoryEntry::new(value, ttl);
         self.data.insert(key.to_string(), entry);
         Ok(())
     }
 
     async fn delete(&self, key: &str) -> Result<bool> {
+        let _shared = self.commit_lock.read();
         Ok(self.data.remove(key).is_some())
     }
 
     async fn list(&self, prefix: Option<&str>) -> Result<Vec<String>> {
+        let _shared = self.commit_lock.read();
         let mut keys = Vec::new();
         let mut expired_keys = Vec::new();
 
@@ -144,7 +157,68 @@ impl KvBackend for MemoryBackend {
         Ok(keys)
     }
 
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<&[u8]>,
+        new: Vec<u8>,
+        ttl: Option<Duration>,
+    ) -> Result<bool> {
+        let _shared = self.commit_lock.read();
+        // The entry holds the shard lock until the swap is done
+        let entry = self.data.entry(key.to_string());
+        let current = match &entry {
+            Entry::Occupied(occupied) if !occupied.get().is_expired() => {
+                Some(occupied.get().value.as_slice())
+            },
+            _ => None,
+        };
+        if current != expected {
+            return Ok(false);
+        }
+        entry.insert(MemoryEntry::new(new, ttl));
+        Ok(true)
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64> {
+        let _shared = self.commit_lock.read();
+        let entry = self.data.entry(key.to_string());
+        let current = match &entry {
+            Entry::Occupied(occupied) if !occupied.get().is_expired() => Some(occupied.get()),
+            _ => None,
+        };
+        let value = add_to_counter(key, current.map(|entry| entry.value.as_slice()), delta)?;
+        let expires_at = current.and_then(|entry| entry.expires_at);
+        entry.insert(MemoryEntry {
+            value: value.to_string().into_bytes(),
+            expires_at,
+        });
+        Ok(value)
+    }
+
+    async fn commit(&self, commit: KvCommit) -> Result<bool> {
+        let _exclusive = self.commit_lock.write();
+        for (key, expected) in &commit.reads {
+            let current = self.data.get(key).filter(|entry| !entry.is_expired());
+            if current.as_ref().map(|entry| entry.value.as_slice()) != expected.as_deref() {
+                return Ok(false);
+            }
+        }
+        for (key, write) in commit.writes {
+            match write {
+                KvWrite::Set { value, ttl } => {
+                    self.data.insert(key, MemoryEntry::new(value, ttl));
+                },
+                KvWrite::Delete => {
+                    self.data.remove(&key);
+                },
+            }
+        }
+        Ok(true)
+    }
+
     async fn exists(&self, key: &str) -> Result<bool> {
+        let _shared = self.commit_lock.read();
         if let Some(entry) = self.data.get(key) {
             if entry.is_expired() {
                 drop(entry);
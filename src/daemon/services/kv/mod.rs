@@ -5,11 +5,13 @@
 //!
 //! - **RedbBackend**: Persistent storage with ACID guarantees (default for CLI)
 //! - **MemoryBackend**: Fast, non-persistent storage (ideal for testing/embedding)
+//! - **CachedRedbBackend**: Redb with a hot memory cache and write-behind
+//!   flushing (lower latency for KV-heavy handlers)
 //!
 //! # Example
 //!
 //! ```ignore
-//! use mik::daemon::services::kv::{KvStore, MemoryBackend, RedbBackend};
+//! use mik::daemon::services::kv::{CacheConfig, KvStore};
 //!
 //! // In-memory (testing/embedding)
 //! let store = KvStore::memory();
@@ -18,8 +20,37 @@
 //! // Persistent (production)
 //! let store = KvStore::file("~/.mik/kv.redb")?;
 //! store.set("key", b"value", None).await?;
+//!
+//! // Persistent with a write-behind cache
+//! let store = KvStore::file_cached("~/.mik/kv.redb", CacheConfig::default())?;
+//! store.set("key", b"value", None).await?;
+//! store.flush().await?;
+//! ```
+//!
+//! # Atomic Operations
+//!
+//! [`KvStore::compare_and_swap`] and [`KvStore::increment`] read and write a
+//! key atomically, for optimistic locking and counters shared by concurrent
+//! handlers:
+//!
+//! ```ignore
+//! let hits = store.increment("hits:/home", 1).await?;
+//! let acquired = store.compare_and_swap("lock:job", None, b"worker-1", None).await?;
 //! ```
 //!
+//! [`KvStore::transaction`] updates several keys at once: the writes are
+//! applied together, or not at all if a key it read has changed since.
+//!
+//! # Watching Keys
+//!
+//! [`KvStore::watch`] streams a [`KvEvent`] for every set and delete under
+//! a prefix, so configuration stored in KV can be reloaded when it changes.
+//!
+//! # Tenant Namespaces
+//!
+//! [`KvStore::tenant`] scopes the store to `tenants/{tenant_id}/` and
+//! enforces that tenant's [`KvQuota`] on writes that create keys.
+//!
 //! # Custom Backends
 //!
 //! Implement the `KvBackend` trait to use custom storage:
@@ -34,9 +65,13 @@
 //! ```
 
 mod backend;
+mod cached;
+mod events;
 mod memory;
 mod redb;
 mod store;
+mod tenant;
+mod transaction;
 mod types;
 
 #[cfg(test)]
@@ -46,6 +81,11 @@ mod tests;
 
 // Re-export the public API
 pub use backend::KvBackend;
+pub use cached::{CacheConfig, CachedRedbBackend};
+pub use events::{KvEvent, KvEventKind, KvWatch, WATCH_CAPACITY};
 pub use memory::MemoryBackend;
 pub use redb::RedbBackend;
 pub use store::KvStore;
+pub use tenant::{KvQuota, KvQuotaExceeded, KvQuotas, TENANTS_PREFIX};
+pub use transaction::{KvCommit, KvTransaction, KvWrite};
+pub use types::KvScan;
@@ -3,10 +3,12 @@
 //! Provides persistent key-value storage using redb with ACID guarantees.
 
 use super::backend::KvBackend;
-use super::types::KvEntry;
+use super::transaction::{KvCommit, KvWrite};
+use super::types::{KvEntry, KvScan, add_to_counter};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use redb::{Database, ReadableDatabase, ReadableTable, TableDefinition};
+use std::ops::Bound;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
@@ -71,6 +73,11 @@ impl RedbBackend {
 
     /// Internal helper to get a value synchronously.
     fn get_sync(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.get_entry_sync(key)?.map(|entry| entry.value))
+    }
+
+    /// Reads the full entry for a key, removing it if it has expired.
+    pub(super) fn get_entry_sync(&self, key: &str) -> Result<Option<KvEntry>> {
         let read_txn = self
             .db
             .begin_read()
@@ -93,6 +100,7 @@ impl RedbBackend {
                 // Check expiration
                 if entry.is_expired()? {
                     // Drop read transaction before starting write
+                    drop(guard);
                     drop(table);
                     drop(read_txn);
 
@@ -100,13 +108,132 @@ impl RedbBackend {
                     self.delete_sync(key)?;
                     Ok(None)
                 } else {
-                    Ok(Some(entry.value))
+                    Ok(Some(entry))
                 }
             },
             None => Ok(None),
         }
     }
 
+    /// Applies a batch of writes in a single transaction.
+    ///
+    /// `Some(entry)` upserts the key and `None` removes it. Either every
+    /// change in the batch is committed or none is.
+    pub(super) fn write_batch_sync(&self, batch: &[(String, Option<KvEntry>)]) -> Result<()> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .context("Failed to begin write transaction")?;
+
+        {
+            let mut table = write_txn
+                .open_table(KV_TABLE)
+                .context("Failed to open KV table")?;
+
+            for (key, entry) in batch {
+                if let Some(entry) = entry {
+                    let json =
+                        serde_json::to_vec(entry).context("Failed to serialize entry to JSON")?;
+                    table
+                        .insert(key.as_str(), json.as_slice())
+                        .with_context(|| format!("Failed to insert key '{key}'"))?;
+                } else {
+                    table
+                        .remove(key.as_str())
+                        .with_context(|| format!("Failed to remove key '{key}'"))?;
+                }
+            }
+        }
+
+        write_txn
+            .commit()
+            .context("Failed to commit batch transaction")?;
+
+        Ok(())
+    }
+
+    /// Reads a key and writes it back in one write transaction.
+    ///
+    /// `update` sees the live entry (`None` if missing or expired) and
+    /// returns the entry to store, if any. redb serializes write
+    /// transactions, so no other write can land in between.
+    pub(super) fn update_sync<T>(
+        &self,
+        key: &str,
+        update: impl FnOnce(Option<KvEntry>) -> Result<(Option<KvEntry>, T)>,
+    ) -> Result<T> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .context("Failed to begin write transaction")?;
+
+        let result = {
+            let mut table = write_txn
+                .open_table(KV_TABLE)
+                .context("Failed to open KV table")?;
+
+            let (entry, result) = update(live_entry(&table, key)?)?;
+            if let Some(entry) = entry {
+                let json =
+                    serde_json::to_vec(&entry).context("Failed to serialize entry to JSON")?;
+                table
+                    .insert(key, json.as_slice())
+                    .with_context(|| format!("Failed to insert key '{key}'"))?;
+            }
+            result
+        };
+
+        write_txn
+            .commit()
+            .context("Failed to commit update transaction")?;
+
+        Ok(result)
+    }
+
+    /// Checks and applies a transaction in one write transaction.
+    pub(super) fn commit_sync(&self, commit: &KvCommit) -> Result<bool> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .context("Failed to begin write transaction")?;
+
+        {
+            let mut table = write_txn
+                .open_table(KV_TABLE)
+                .context("Failed to open KV table")?;
+
+            for (key, expected) in &commit.reads {
+                let current = live_entry(&table, key)?;
+                if current.as_ref().map(|entry| entry.value.as_slice()) != expected.as_deref() {
+                    // Dropping the transaction aborts it
+                    return Ok(false);
+                }
+            }
+
+            for (key, write) in &commit.writes {
+                match write {
+                    KvWrite::Set { value, ttl } => {
+                        let entry = write_entry(value.clone(), *ttl)?;
+                        let json = serde_json::to_vec(&entry)
+                            .context("Failed to serialize entry to JSON")?;
+                        table
+                            .insert(key.as_str(), json.as_slice())
+                            .with_context(|| format!("Failed to insert key '{key}'"))?;
+                    },
+                    KvWrite::Delete => {
+                        table
+                            .remove(key.as_str())
+                            .with_context(|| format!("Failed to remove key '{key}'"))?;
+                    },
+                }
+            }
+        }
+
+        write_txn.commit().context("Failed to commit transaction")?;
+
+        Ok(true)
+    }
+
     /// Internal helper to set a value synchronously.
     fn set_sync(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> Result<()> {
         let entry = if let Some(ttl) = ttl {
@@ -164,8 +291,102 @@ impl RedbBackend {
         Ok(removed)
     }
 
+    /// Reads several keys in one read transaction.
+    fn get_many_sync(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .context("Failed to begin read transaction")?;
+
+        let table = read_txn
+            .open_table(KV_TABLE)
+            .context("Failed to open KV table")?;
+
+        keys.iter()
+            .map(|key| Ok(live_entry(&table, key)?.map(|entry| entry.value)))
+            .collect()
+    }
+
+    /// Removes several keys in one write transaction, returning whether
+    /// each one held a live entry.
+    fn delete_many_sync(&self, keys: &[String]) -> Result<Vec<bool>> {
+        let write_txn = self
+            .db
+            .begin_write()
+            .context("Failed to begin write transaction")?;
+
+        let deleted = {
+            let mut table = write_txn
+                .open_table(KV_TABLE)
+                .context("Failed to open KV table")?;
+
+            let mut deleted = Vec::with_capacity(keys.len());
+            for key in keys {
+                let live = live_entry(&table, key)?.is_some();
+                table
+                    .remove(key.as_str())
+                    .with_context(|| format!("Failed to remove key '{key}'"))?;
+                deleted.push(live);
+            }
+            deleted
+        };
+
+        write_txn
+            .commit()
+            .context("Failed to commit delete transaction")?;
+
+        Ok(deleted)
+    }
+
+    /// Reads up to `limit` live entries under `prefix` after `cursor`, in
+    /// key order.
+    pub(super) fn scan_sync(
+        &self,
+        prefix: Option<&str>,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<(String, KvEntry)>> {
+        let read_txn = self
+            .db
+            .begin_read()
+            .context("Failed to begin read transaction")?;
+
+        let table = read_txn
+            .open_table(KV_TABLE)
+            .context("Failed to open KV table")?;
+
+        let prefix = prefix.unwrap_or_default();
+        let start = match cursor {
+            Some(cursor) if cursor >= prefix => Bound::Excluded(cursor),
+            _ => Bound::Included(prefix),
+        };
+
+        let mut entries = Vec::new();
+        for item in table
+            .range::<&str>((start, Bound::Unbounded))
+            .context("Failed to iterate KV table")?
+        {
+            if entries.len() >= limit {
+                break;
+            }
+            let (key, value) = item.context("Failed to read KV entry")?;
+            let key = key.value();
+            if !key.starts_with(prefix) {
+                break;
+            }
+            let entry: KvEntry = serde_json::from_slice(value.value())
+                .with_context(|| format!("Failed to deserialize entry for key '{key}'"))?;
+            // Expired entries are left for `get` and `list` to clean up
+            if !entry.is_expired()? {
+                entries.push((key.to_string(), entry));
+            }
+        }
+
+        Ok(entries)
+    }
+
     /// Internal helper to list keys synchronously.
-    fn list_sync(&self, prefix: Option<&str>) -> Result<Vec<String>> {
+    pub(super) fn list_sync(&self, prefix: Option<&str>) -> Result<Vec<String>> {
         let read_txn = self
             .db
             .begin_read()
@@ -252,4 +473,150 @@ impl KvBackend for RedbBackend {
             .await
             .context("Task join error")?
     }
+
+    async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<&[u8]>,
+        new: Vec<u8>,
+        ttl: Option<Duration>,
+    ) -> Result<bool> {
+        let backend = self.clone();
+        let key = key.to_string();
+        let expected = expected.map(<[u8]>::to_vec);
+        tokio::task::spawn_blocking(move || {
+            backend.update_sync(&key, |current| {
+                swap_entry(current.as_ref(), expected.as_deref(), new, ttl)
+            })
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64> {
+        let backend = self.clone();
+        let key = key.to_string();
+        tokio::task::spawn_blocking(move || {
+            backend.update_sync(&key, |current| increment_entry(&key, current, delta))
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        let backend = self.clone();
+        let keys = keys.to_vec();
+        tokio::task::spawn_blocking(move || backend.get_many_sync(&keys))
+            .await
+            .context("Task join error")?
+    }
+
+    async fn set_many(&self, entries: Vec<(String, Vec<u8>)>, ttl: Option<Duration>) -> Result<()> {
+        let backend = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let batch = entries
+                .into_iter()
+                .map(|(key, value)| Ok((key, Some(write_entry(value, ttl)?))))
+                .collect::<Result<Vec<_>>>()?;
+            backend.write_batch_sync(&batch)
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    async fn delete_many(&self, keys: &[String]) -> Result<Vec<bool>> {
+        let backend = self.clone();
+        let keys = keys.to_vec();
+        tokio::task::spawn_blocking(move || backend.delete_many_sync(&keys))
+            .await
+            .context("Task join error")?
+    }
+
+    async fn scan(
+        &self,
+        prefix: Option<&str>,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<KvScan> {
+        let backend = self.clone();
+        let prefix = prefix.map(str::to_string);
+        let cursor = cursor.map(str::to_string);
+        let entries = tokio::task::spawn_blocking(move || {
+            backend.scan_sync(
+                prefix.as_deref(),
+                cursor.as_deref(),
+                limit.saturating_add(1),
+            )
+        })
+        .await
+        .context("Task join error")??;
+        let entries = entries
+            .into_iter()
+            .map(|(key, entry)| (key, entry.value))
+            .collect();
+        Ok(KvScan::page(entries, limit))
+    }
+
+    async fn commit(&self, commit: KvCommit) -> Result<bool> {
+        let backend = self.clone();
+        tokio::task::spawn_blocking(move || backend.commit_sync(&commit))
+            .await
+            .context("Task join error")?
+    }
+}
+
+/// Reads the entry for `key` from an open table, treating expired entries
+/// as missing.
+fn live_entry(
+    table: &impl ReadableTable<&'static str, &'static [u8]>,
+    key: &str,
+) -> Result<Option<KvEntry>> {
+    let Some(guard) = table
+        .get(key)
+        .with_context(|| format!("Failed to read key '{key}'"))?
+    else {
+        return Ok(None);
+    };
+    let entry: KvEntry = serde_json::from_slice(guard.value())
+        .with_context(|| format!("Failed to deserialize entry for key '{key}'"))?;
+    Ok((!entry.is_expired()?).then_some(entry))
+}
+
+/// A new entry holding `value`, expiring after `ttl`.
+pub(super) fn write_entry(value: Vec<u8>, ttl: Option<Duration>) -> Result<KvEntry> {
+    match ttl {
+        Some(ttl) => KvEntry::with_ttl(value, ttl.as_secs()),
+        None => Ok(KvEntry::new(value)),
+    }
+}
+
+/// The entry [`KvBackend::compare_and_swap`] stores, and whether it swapped.
+pub(super) fn swap_entry(
+    current: Option<&KvEntry>,
+    expected: Option<&[u8]>,
+    new: Vec<u8>,
+    ttl: Option<Duration>,
+) -> Result<(Option<KvEntry>, bool)> {
+    if current.map(|entry| entry.value.as_slice()) != expected {
+        return Ok((None, false));
+    }
+    Ok((Some(write_entry(new, ttl)?), true))
+}
+
+/// The entry [`KvBackend::increment`] stores, and the new count.
+pub(super) fn increment_entry(
+    key: &str,
+    current: Option<KvEntry>,
+    delta: i64,
+) -> Result<(Option<KvEntry>, i64)> {
+    let value = add_to_counter(
+        key,
+        current.as_ref().map(|entry| entry.value.as_slice()),
+        delta,
+    )?;
+    let entry = KvEntry {
+        value: value.to_string().into_bytes(),
+        expires_at: current.and_then(|entry| entry.expires_at),
+    };
+    Ok((Some(entry), value))
 }
@@ -3,12 +3,22 @@
 //! Provides a convenient API that wraps any `KvBackend` implementation.
 
 use super::backend::KvBackend;
+use super::cached::{CacheConfig, CachedRedbBackend};
+use super::events::{KvEvent, KvWatch, WATCH_CAPACITY};
 use super::memory::MemoryBackend;
 use super::redb::RedbBackend;
+use super::tenant::{KvQuota, KvQuotaExceeded, KvQuotas, Namespace};
+use super::transaction::{KvCommit, KvTransaction, KvWrite};
+use super::types::KvScan;
+use crate::daemon::services::sql::validate_tenant_id;
 use anyhow::Result;
+use dashmap::DashMap;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::{Mutex, OwnedMutexGuard, broadcast};
 
 /// High-level key-value store interface.
 ///
@@ -40,6 +50,12 @@ use std::time::Duration;
 #[derive(Clone)]
 pub struct KvStore {
     backend: Arc<dyn KvBackend>,
+    events: broadcast::Sender<KvEvent>,
+    /// Set on [`tenant`](Self::tenant) views.
+    namespace: Option<Arc<Namespace>>,
+    quotas: Arc<KvQuotas>,
+    /// Serializes quota-checked writes per tenant.
+    quota_locks: Arc<DashMap<String, Arc<Mutex<()>>>>,
 }
 
 impl KvStore {
@@ -58,9 +74,20 @@ impl KvStore {
     /// ```
     pub fn file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let backend = RedbBackend::open(path)?;
-        Ok(Self {
-            backend: Arc::new(backend),
-        })
+        Ok(Self::from_arc(Arc::new(backend)))
+    }
+
+    /// Creates a new `KvStore` backed by redb with a write-behind memory cache.
+    ///
+    /// Trades a bounded window of unflushed writes for lower latency on
+    /// KV-heavy workloads. See [`CachedRedbBackend`] for durability details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or created.
+    pub fn file_cached<P: AsRef<Path>>(path: P, config: CacheConfig) -> Result<Self> {
+        let backend = CachedRedbBackend::open(path, config)?;
+        Ok(Self::from_arc(Arc::new(backend)))
     }
 
     /// Creates a new `KvStore` backed by an in-memory store.
@@ -74,9 +101,7 @@ impl KvStore {
     /// let store = KvStore::memory();
     /// ```
     pub fn memory() -> Self {
-        Self {
-            backend: Arc::new(MemoryBackend::new()),
-        }
+        Self::from_arc(Arc::new(MemoryBackend::new()))
     }
 
     /// Creates a new `KvStore` with a custom backend.
@@ -92,17 +117,144 @@ impl KvStore {
     /// let store = KvStore::custom(RedisBackend::new());
     /// ```
     pub fn custom<B: KvBackend>(backend: B) -> Self {
-        Self {
-            backend: Arc::new(backend),
-        }
+        Self::from_arc(Arc::new(backend))
     }
 
     /// Creates a new `KvStore` from a boxed backend.
     ///
     /// Useful when working with trait objects directly.
     pub fn from_boxed(backend: Box<dyn KvBackend>) -> Self {
+        Self::from_arc(Arc::from(backend))
+    }
+
+    fn from_arc(backend: Arc<dyn KvBackend>) -> Self {
         Self {
-            backend: Arc::from(backend),
+            backend,
+            events: broadcast::channel(WATCH_CAPACITY).0,
+            namespace: None,
+            quotas: Arc::default(),
+            quota_locks: Arc::default(),
+        }
+    }
+
+    /// Sets the key quotas enforced by [`tenant`](Self::tenant) views.
+    #[must_use]
+    pub fn with_tenant_quotas(mut self, quotas: KvQuotas) -> Self {
+        self.quotas = Arc::new(quotas);
+        self
+    }
+
+    /// Returns a view of the store scoped to `tenant_id`'s namespace.
+    ///
+    /// The view shares the backend and watchers with this store. Keys are
+    /// relative to `tenants/{tenant_id}/`, including in the view's own
+    /// watches; watches on the whole store see the full keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tenant ID is invalid.
+    pub fn tenant(&self, tenant_id: &str) -> Result<Self> {
+        validate_tenant_id(tenant_id)?;
+        let quota = self.quotas.get(tenant_id);
+        Ok(Self {
+            namespace: Some(Arc::new(Namespace::new(tenant_id, quota))),
+            ..self.clone()
+        })
+    }
+
+    /// Tenant this view is scoped to, if any.
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.namespace.as_ref().map(|ns| ns.tenant_id.as_str())
+    }
+
+    /// Key quota that applies to `tenant_id`.
+    pub fn tenant_quota(&self, tenant_id: &str) -> KvQuota {
+        self.quotas.get(tenant_id)
+    }
+
+    /// Maps a caller's key (or prefix) into the namespace.
+    fn full_key<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        match &self.namespace {
+            Some(ns) => Cow::Owned(format!("{}{key}", ns.prefix)),
+            None => Cow::Borrowed(key),
+        }
+    }
+
+    fn full_keys<'a>(&self, keys: &'a [String]) -> Cow<'a, [String]> {
+        if self.namespace.is_none() {
+            return Cow::Borrowed(keys);
+        }
+        Cow::Owned(
+            keys.iter()
+                .map(|key| self.full_key(key).into_owned())
+                .collect(),
+        )
+    }
+
+    /// Makes a full key relative to the namespace.
+    fn relative(&self, key: String) -> String {
+        match &self.namespace {
+            Some(ns) => key
+                .strip_prefix(&ns.prefix)
+                .map_or(key.clone(), str::to_string),
+            None => key,
+        }
+    }
+
+    /// Checks a write that may create `keys` (full keys) against the
+    /// namespace quota.
+    ///
+    /// Returns `None` when no quota applies. Otherwise the guard holds the
+    /// tenant's quota lock until it is dropped.
+    async fn reserve<'k>(
+        &self,
+        keys: impl IntoIterator<Item = &'k str>,
+    ) -> Result<Option<OwnedMutexGuard<()>>> {
+        let Some(ns) = self.namespace.as_deref() else {
+            return Ok(None);
+        };
+        let Some(max_keys) = ns.quota.max_keys else {
+            return Ok(None);
+        };
+        let lock = self
+            .quota_locks
+            .entry(ns.tenant_id.clone())
+            .or_default()
+            .clone();
+        let guard = lock.lock_owned().await;
+
+        let existing: BTreeSet<String> = self
+            .backend
+            .list(Some(&ns.prefix))
+            .await?
+            .into_iter()
+            .collect();
+        let created: BTreeSet<&str> = keys
+            .into_iter()
+            .filter(|key| !existing.contains(*key))
+            .collect();
+        if !created.is_empty() && (existing.len() + created.len()) as u64 > max_keys {
+            return Err(KvQuotaExceeded {
+                tenant_id: ns.tenant_id.clone(),
+                max_keys,
+            }
+            .into());
+        }
+        Ok(Some(guard))
+    }
+
+    /// Subscribes to changes of keys under `prefix` (see [`KvWatch`]).
+    ///
+    /// Only writes made through this store (or its clones) are reported.
+    pub fn watch(&self, prefix: &str) -> KvWatch {
+        let namespace = self.namespace.as_ref().map_or("", |ns| ns.prefix.as_str());
+        KvWatch::new(self.events.subscribe(), namespace, prefix)
+    }
+
+    fn publish(&self, event: impl FnOnce() -> KvEvent) {
+        // Skip building the event (and copying the value) without watchers
+        if self.events.receiver_count() > 0 {
+            let _ = self.events.send(event());
         }
     }
 
@@ -114,7 +266,7 @@ impl KvStore {
     ///
     /// Returns an error if the underlying storage operation fails.
     pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
-        self.backend.get(key).await
+        self.backend.get(&self.full_key(key)).await
     }
 
     /// Stores a key-value pair with an optional TTL.
@@ -124,9 +276,14 @@ impl KvStore {
     ///
     /// # Errors
     ///
-    /// Returns an error if the underlying storage operation fails.
+    /// Returns an error if the underlying storage operation fails, or
+    /// [`KvQuotaExceeded`] if a new key would exceed the tenant's quota.
     pub async fn set(&self, key: &str, value: &[u8], ttl: Option<Duration>) -> Result<()> {
-        self.backend.set(key, value.to_vec(), ttl).await
+        let key = self.full_key(key);
+        let _reservation = self.reserve([&*key]).await?;
+        self.backend.set(&key, value.to_vec(), ttl).await?;
+        self.publish(|| KvEvent::set(&key, value.to_vec()));
+        Ok(())
     }
 
     /// Deletes a key-value pair.
@@ -137,7 +294,179 @@ impl KvStore {
     ///
     /// Returns an error if the underlying storage operation fails.
     pub async fn delete(&self, key: &str) -> Result<bool> {
-        self.backend.delete(key).await
+        let key = self.full_key(key);
+        let deleted = self.backend.delete(&key).await?;
+        if deleted {
+            self.publish(|| KvEvent::delete(&key));
+        }
+        Ok(deleted)
+    }
+
+    /// Stores `new` only if the current value equals `expected`.
+    ///
+    /// `expected: None` means the key must not exist, which makes this a
+    /// set-if-absent. Returns `Ok(true)` if the value was swapped, for
+    /// optimistic locking without racing a separate get and set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails, or
+    /// [`KvQuotaExceeded`] if a new key would exceed the tenant's quota.
+    pub async fn compare_and_swap(
+        &self,
+        key: &str,
+        expected: Option<&[u8]>,
+        new: &[u8],
+        ttl: Option<Duration>,
+    ) -> Result<bool> {
+        let key = self.full_key(key);
+        let _reservation = match expected {
+            None => self.reserve([&*key]).await?,
+            Some(_) => None,
+        };
+        let swapped = self
+            .backend
+            .compare_and_swap(&key, expected, new.to_vec(), ttl)
+            .await?;
+        if swapped {
+            self.publish(|| KvEvent::set(&key, new.to_vec()));
+        }
+        Ok(swapped)
+    }
+
+    /// Atomically adds `delta` (which may be negative) to a counter and
+    /// returns the new value.
+    ///
+    /// Counters are stored as decimal text, so `get` returns e.g. `b"42"`.
+    /// A missing key starts from zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key holds a non-integer value, the counter
+    /// overflows, a new key would exceed the tenant's quota, or the
+    /// underlying storage operation fails.
+    pub async fn increment(&self, key: &str, delta: i64) -> Result<i64> {
+        let key = self.full_key(key);
+        let _reservation = self.reserve([&*key]).await?;
+        let value = self.backend.increment(&key, delta).await?;
+        self.publish(|| KvEvent::set(&key, value.to_string().into_bytes()));
+        Ok(value)
+    }
+
+    /// Begins a multi-key transaction (see [`KvTransaction`]).
+    pub fn transaction(&self) -> KvTransaction {
+        KvTransaction::new(self.clone())
+    }
+
+    /// Applies a prepared commit atomically, as [`KvTransaction::commit`]
+    /// does.
+    ///
+    /// Returns `Ok(false)` if a key in `commit.reads` has changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails, or
+    /// [`KvQuotaExceeded`] if new keys would exceed the tenant's quota.
+    pub async fn commit(&self, commit: KvCommit) -> Result<bool> {
+        let commit = if self.namespace.is_some() {
+            KvCommit {
+                reads: commit
+                    .reads
+                    .into_iter()
+                    .map(|(key, value)| (self.full_key(&key).into_owned(), value))
+                    .collect(),
+                writes: commit
+                    .writes
+                    .into_iter()
+                    .map(|(key, write)| (self.full_key(&key).into_owned(), write))
+                    .collect(),
+            }
+        } else {
+            commit
+        };
+        let _reservation = self
+            .reserve(
+                commit
+                    .writes
+                    .iter()
+                    .filter(|(_, write)| matches!(write, KvWrite::Set { .. }))
+                    .map(|(key, _)| key.as_str()),
+            )
+            .await?;
+        if self.events.receiver_count() == 0 {
+            return self.backend.commit(commit).await;
+        }
+        let writes = commit.writes.clone();
+        let committed = self.backend.commit(commit).await?;
+        if committed {
+            for (key, write) in writes {
+                self.publish(|| match write {
+                    KvWrite::Set { value, .. } => KvEvent::set(&key, value),
+                    KvWrite::Delete => KvEvent::delete(&key),
+                });
+            }
+        }
+        Ok(committed)
+    }
+
+    /// Retrieves several values in one round trip, in the order of `keys`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    pub async fn get_many(&self, keys: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        self.backend.get_many(&self.full_keys(keys)).await
+    }
+
+    /// Stores several key-value pairs atomically, all with the same TTL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails, or
+    /// [`KvQuotaExceeded`] if new keys would exceed the tenant's quota.
+    pub async fn set_many(
+        &self,
+        entries: Vec<(String, Vec<u8>)>,
+        ttl: Option<Duration>,
+    ) -> Result<()> {
+        let entries: Vec<(String, Vec<u8>)> = if self.namespace.is_some() {
+            entries
+                .into_iter()
+                .map(|(key, value)| (self.full_key(&key).into_owned(), value))
+                .collect()
+        } else {
+            entries
+        };
+        let _reservation = self
+            .reserve(entries.iter().map(|(key, _)| key.as_str()))
+            .await?;
+        let events: Vec<KvEvent> = if self.events.receiver_count() > 0 {
+            entries
+                .iter()
+                .map(|(key, value)| KvEvent::set(key, value.clone()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.backend.set_many(entries, ttl).await?;
+        for event in events {
+            self.publish(|| event);
+        }
+        Ok(())
+    }
+
+    /// Deletes several keys, returning whether each one existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    pub async fn delete_many(&self, keys: &[String]) -> Result<Vec<bool>> {
+        let keys = self.full_keys(keys);
+        let deleted = self.backend.delete_many(&keys).await?;
+        for (key, _) in keys.iter().zip(&deleted).filter(|(_, deleted)| **deleted) {
+            self.publish(|| KvEvent::delete(key));
+        }
+        Ok(deleted)
     }
 
     /// Lists all keys matching an optional prefix.
@@ -146,7 +475,47 @@ impl KvStore {
     ///
     /// Returns an error if the underlying storage operation fails.
     pub async fn list_keys(&self, prefix: Option<&str>) -> Result<Vec<String>> {
-        self.backend.list(prefix).await
+        if self.namespace.is_none() {
+            return self.backend.list(prefix).await;
+        }
+        let prefix = self.full_key(prefix.unwrap_or_default());
+        let keys = self.backend.list(Some(&prefix)).await?;
+        Ok(keys.into_iter().map(|key| self.relative(key)).collect())
+    }
+
+    /// Reads one page of up to `limit` keys under `prefix`, with values,
+    /// continuing after `cursor`.
+    ///
+    /// Start with `cursor: None` and pass each page's `cursor` to the next
+    /// call until it comes back `None`, to walk a large keyspace without
+    /// loading it at once. A `limit` of zero is treated as one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    pub async fn scan(
+        &self,
+        prefix: Option<&str>,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> Result<KvScan> {
+        if self.namespace.is_none() {
+            return self.backend.scan(prefix, cursor, limit.max(1)).await;
+        }
+        let prefix = self.full_key(prefix.unwrap_or_default());
+        let cursor = cursor.map(|cursor| self.full_key(cursor));
+        let page = self
+            .backend
+            .scan(Some(&prefix), cursor.as_deref(), limit.max(1))
+            .await?;
+        Ok(KvScan {
+            entries: page
+                .entries
+                .into_iter()
+                .map(|(key, value)| (self.relative(key), value))
+                .collect(),
+            cursor: page.cursor.map(|cursor| self.relative(cursor)),
+        })
     }
 
     /// Checks if a key exists and has not expired.
@@ -155,6 +524,15 @@ impl KvStore {
     ///
     /// Returns an error if the underlying storage operation fails.
     pub async fn exists(&self, key: &str) -> Result<bool> {
-        self.backend.exists(key).await
+        self.backend.exists(&self.full_key(key)).await
+    }
+
+    /// Commits any writes the backend has buffered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffered writes cannot be committed.
+    pub async fn flush(&self) -> Result<()> {
+        self.backend.flush().await
     }
 }
@@ -0,0 +1,70 @@
+//! Tenant KV namespaces and key quotas.
+//!
+//! [`KvStore::tenant`](super::KvStore::tenant) returns a view of the store
+//! rooted at `tenants/{tenant_id}/`. Keys passed to and returned from the
+//! view are relative to that prefix, so a tenant cannot name another
+//! tenant's keys. Writes through the view that create keys are checked
+//! against the tenant's [`KvQuota`].
+//!
+//! Usage is counted from the keys under the tenant prefix, and a tenant's
+//! writes are serialized while a quota applies, so concurrent writes cannot
+//! overshoot it together. Deleting keys frees quota immediately.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Prefix under which tenant namespaces live.
+pub const TENANTS_PREFIX: &str = "tenants/";
+
+/// Limits on one tenant's keys. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KvQuota {
+    /// Number of live keys.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_keys: Option<u64>,
+}
+
+/// Key quotas for all tenants: a default plus per-tenant overrides.
+#[derive(Debug, Clone, Default)]
+pub struct KvQuotas {
+    /// Quota for tenants without an override.
+    pub default: KvQuota,
+    /// Quotas for specific tenants.
+    pub tenants: HashMap<String, KvQuota>,
+}
+
+impl KvQuotas {
+    /// Quota that applies to `tenant_id`.
+    pub fn get(&self, tenant_id: &str) -> KvQuota {
+        self.tenants.get(tenant_id).copied().unwrap_or(self.default)
+    }
+}
+
+/// A write was rejected because it would exceed the tenant's key quota.
+#[derive(Debug, thiserror::Error)]
+#[error("KV quota exceeded for tenant '{tenant_id}': key limit of {max_keys} reached")]
+pub struct KvQuotaExceeded {
+    /// Tenant whose quota was hit.
+    pub tenant_id: String,
+    /// The tenant's key limit.
+    pub max_keys: u64,
+}
+
+/// The tenant a [`KvStore`](super::KvStore) view is scoped to.
+#[derive(Debug, Clone)]
+pub(crate) struct Namespace {
+    pub(crate) tenant_id: String,
+    /// `tenants/{tenant_id}/`
+    pub(crate) prefix: String,
+    pub(crate) quota: KvQuota,
+}
+
+impl Namespace {
+    pub(crate) fn new(tenant_id: &str, quota: KvQuota) -> Self {
+        Self {
+            tenant_id: tenant_id.to_string(),
+            prefix: format!("{TENANTS_PREFIX}{tenant_id}/"),
+            quota,
+        }
+    }
+}
@@ -277,3 +277,296 @@ async fn test_memory_backend() {
     let store2 = KvStore::memory();
     assert!(store2.get("key1").await.unwrap().is_none());
 }
+
+#[tokio::test]
+async fn test_compare_and_swap_and_increment() {
+    let tmp = TempDir::new().unwrap();
+    let stores = [
+        KvStore::memory(),
+        KvStore::file(tmp.path().join("kv.redb")).unwrap(),
+        KvStore::file_cached(tmp.path().join("cached.redb"), CacheConfig::default()).unwrap(),
+    ];
+
+    for store in stores {
+        // Set-if-absent
+        assert!(
+            store
+                .compare_and_swap("lock", None, b"a", None)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !store
+                .compare_and_swap("lock", None, b"b", None)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !store
+                .compare_and_swap("lock", Some(b"stale"), b"b", None)
+                .await
+                .unwrap()
+        );
+        assert!(
+            store
+                .compare_and_swap("lock", Some(b"a"), b"b", None)
+                .await
+                .unwrap()
+        );
+        assert_eq!(store.get("lock").await.unwrap().unwrap(), b"b");
+
+        assert_eq!(store.increment("hits", 1).await.unwrap(), 1);
+        assert_eq!(store.increment("hits", 5).await.unwrap(), 6);
+        assert_eq!(store.increment("hits", -10).await.unwrap(), -4);
+        assert_eq!(store.get("hits").await.unwrap().unwrap(), b"-4");
+        assert!(store.increment("lock", 1).await.is_err());
+
+        store
+            .set("max", i64::MAX.to_string().as_bytes(), None)
+            .await
+            .unwrap();
+        assert!(store.increment("max", 1).await.is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_concurrent_increments() {
+    let tmp = TempDir::new().unwrap();
+    let stores = [
+        KvStore::memory(),
+        KvStore::file(tmp.path().join("kv.redb")).unwrap(),
+        KvStore::file_cached(tmp.path().join("cached.redb"), CacheConfig::default()).unwrap(),
+    ];
+
+    for store in stores {
+        let tasks: Vec<_> = (0..20)
+            .map(|_| {
+                let store = store.clone();
+                tokio::spawn(async move { store.increment("counter", 1).await.unwrap() })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(store.get("counter").await.unwrap().unwrap(), b"20");
+    }
+}
+
+#[tokio::test]
+async fn test_transactions() {
+    let tmp = TempDir::new().unwrap();
+    let stores = [
+        KvStore::memory(),
+        KvStore::file(tmp.path().join("kv.redb")).unwrap(),
+        KvStore::file_cached(tmp.path().join("cached.redb"), CacheConfig::default()).unwrap(),
+    ];
+
+    for store in stores {
+        store.set("todo", b"a,b", None).await.unwrap();
+
+        // Move an item between two lists
+        let mut txn = store.transaction();
+        assert_eq!(txn.get("todo").await.unwrap().unwrap(), b"a,b");
+        assert!(txn.get("done").await.unwrap().is_none());
+        txn.set("todo", b"b", None);
+        txn.set("done", b"a", None);
+        assert_eq!(txn.get("done").await.unwrap().unwrap(), b"a");
+        assert!(txn.commit().await.unwrap());
+        assert_eq!(store.get("todo").await.unwrap().unwrap(), b"b");
+        assert_eq!(store.get("done").await.unwrap().unwrap(), b"a");
+
+        // A concurrent change to a key read aborts every write
+        let mut txn = store.transaction();
+        txn.get("todo").await.unwrap();
+        txn.delete("todo");
+        txn.set("done", b"a,b", None);
+        store.set("todo", b"b,c", None).await.unwrap();
+        assert!(!txn.commit().await.unwrap());
+        assert_eq!(store.get("todo").await.unwrap().unwrap(), b"b,c");
+        assert_eq!(store.get("done").await.unwrap().unwrap(), b"a");
+
+        let mut txn = store.transaction();
+        txn.delete("todo");
+        assert!(txn.commit().await.unwrap());
+        assert!(!store.exists("todo").await.unwrap());
+    }
+}
+
+#[tokio::test]
+async fn test_batch_operations() {
+    let tmp = TempDir::new().unwrap();
+    let stores = [
+        KvStore::memory(),
+        KvStore::file(tmp.path().join("kv.redb")).unwrap(),
+        KvStore::file_cached(tmp.path().join("cached.redb"), CacheConfig::default()).unwrap(),
+    ];
+    let keys = |names: &[&str]| names.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    for store in stores {
+        store
+            .set_many(
+                vec![
+                    ("a".to_string(), b"1".to_vec()),
+                    ("b".to_string(), b"2".to_vec()),
+                ],
+                None,
+            )
+            .await
+            .unwrap();
+        store
+            .set_many(
+                vec![("tmp".to_string(), b"3".to_vec())],
+                Some(Duration::from_secs(1)),
+            )
+            .await
+            .unwrap();
+
+        let values = store
+            .get_many(&keys(&["b", "missing", "a", "tmp"]))
+            .await
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                Some(b"2".to_vec()),
+                None,
+                Some(b"1".to_vec()),
+                Some(b"3".to_vec())
+            ]
+        );
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(store.get_many(&keys(&["tmp"])).await.unwrap(), vec![None]);
+
+        let deleted = store
+            .delete_many(&keys(&["a", "missing", "tmp"]))
+            .await
+            .unwrap();
+        assert_eq!(deleted, vec![true, false, false]);
+        assert_eq!(
+            store.get_many(&keys(&["a", "b"])).await.unwrap(),
+            vec![None, Some(b"2".to_vec())]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_scan_pages() {
+    let tmp = TempDir::new().unwrap();
+    let config = CacheConfig {
+        max_entries: 100,
+        flush_interval: Duration::from_secs(3600),
+    };
+    let stores = [
+        KvStore::memory(),
+        KvStore::file(tmp.path().join("kv.redb")).unwrap(),
+        KvStore::file_cached(tmp.path().join("cached.redb"), config).unwrap(),
+    ];
+
+    for store in stores {
+        for i in 0..5 {
+            store.set(&format!("user:{i}"), b"v", None).await.unwrap();
+        }
+        store.set("other", b"v", None).await.unwrap();
+        store.flush().await.unwrap();
+        // Buffered in the cached store, overlaid on what was flushed
+        store.delete("user:1").await.unwrap();
+        store.set("user:5", b"new", None).await.unwrap();
+
+        let mut keys = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = store
+                .scan(Some("user:"), cursor.as_deref(), 2)
+                .await
+                .unwrap();
+            assert!(page.entries.len() <= 2);
+            keys.extend(page.entries.into_iter().map(|(key, _)| key));
+            cursor = page.cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(keys, vec!["user:0", "user:2", "user:3", "user:4", "user:5"]);
+
+        let page = store.scan(None, Some("user:4"), 10).await.unwrap();
+        assert_eq!(page.entries, vec![("user:5".to_string(), b"new".to_vec())]);
+        assert!(page.cursor.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_watch_prefix() {
+    let store = KvStore::memory();
+    let mut config = store.watch("config:");
+
+    store.set("other", b"x", None).await.unwrap();
+    store.set("config:mode", b"fast", None).await.unwrap();
+    assert!(
+        store
+            .compare_and_swap("config:mode", Some(b"fast"), b"safe", None)
+            .await
+            .unwrap()
+    );
+    store.increment("config:version", 1).await.unwrap();
+    assert!(!store.delete("config:missing").await.unwrap());
+    let mut txn = store.transaction();
+    txn.delete("config:mode");
+    assert!(txn.commit().await.unwrap());
+
+    let mut next = async || config.recv().await.unwrap();
+    let set = next().await;
+    assert_eq!(set.kind, KvEventKind::Set);
+    assert_eq!(set.key, "config:mode");
+    assert_eq!(set.value.as_deref(), Some(b"fast".as_slice()));
+    assert_eq!(next().await.value.as_deref(), Some(b"safe".as_slice()));
+    assert_eq!(next().await.value.as_deref(), Some(b"1".as_slice()));
+    let deleted = next().await;
+    assert_eq!(deleted.kind, KvEventKind::Delete);
+    assert_eq!(deleted.key, "config:mode");
+}
+
+#[tokio::test]
+async fn test_tenant_namespaces_and_quotas() {
+    let store = KvStore::memory().with_tenant_quotas(KvQuotas {
+        default: KvQuota { max_keys: Some(2) },
+        tenants: std::collections::HashMap::from([("big".to_string(), KvQuota::default())]),
+    });
+    let acme = store.tenant("acme").unwrap();
+    let other = store.tenant("other").unwrap();
+    assert!(store.tenant("../acme").is_err());
+    let mut watch = acme.watch("");
+
+    acme.set("a", b"1", None).await.unwrap();
+    assert_eq!(store.get("tenants/acme/a").await.unwrap().unwrap(), b"1");
+    assert!(other.get("a").await.unwrap().is_none());
+    assert_eq!(watch.recv().await.unwrap().key, "a");
+
+    // The quota counts new keys; overwrites and deletes never fail
+    acme.increment("b", 1).await.unwrap();
+    acme.set("a", b"2", None).await.unwrap();
+    let err = acme.set("c", b"3", None).await.unwrap_err();
+    assert!(err.downcast_ref::<KvQuotaExceeded>().is_some());
+    let mut txn = acme.transaction();
+    txn.set("c", b"3", None);
+    assert!(txn.commit().await.is_err());
+    assert!(
+        acme.set_many(vec![("c".to_string(), b"3".to_vec())], None)
+            .await
+            .is_err()
+    );
+    assert!(acme.delete("b").await.unwrap());
+    acme.set("c", b"3", None).await.unwrap();
+
+    let mut keys = acme.list_keys(None).await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "c"]);
+    let page = acme.scan(None, Some("a"), 1).await.unwrap();
+    assert_eq!(page.entries, vec![("c".to_string(), b"3".to_vec())]);
+
+    // Per-tenant overrides replace the default
+    let big = store.tenant("big").unwrap();
+    for key in ["a", "b", "c"] {
+        big.set(key, b"x", None).await.unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+//! Multi-key transactions.
+//!
+//! [`KvStore::transaction`] begins an optimistic transaction: reads go to
+//! the store and are remembered, writes are buffered, and
+//! [`KvTransaction::commit`] applies every write atomically if none of the
+//! keys read has changed in the meantime. On conflict nothing is written
+//! and the caller retries with a fresh transaction.
+//!
+//! Conflicts are detected by comparing values, so a key changed and then
+//! changed back does not conflict.
+
+use super::store::KvStore;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// A buffered write to one key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KvWrite {
+    /// Store a value with an optional TTL.
+    Set {
+        value: Vec<u8>,
+        ttl: Option<Duration>,
+    },
+    /// Remove the key.
+    Delete,
+}
+
+/// What a backend checks and applies on commit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KvCommit {
+    /// Values the transaction read, by key (`None` if the key was absent).
+    /// The commit fails unless every key still has this value.
+    pub reads: BTreeMap<String, Option<Vec<u8>>>,
+    /// Writes to apply, by key.
+    pub writes: BTreeMap<String, KvWrite>,
+}
+
+/// An optimistic transaction over several keys.
+///
+/// # Example
+///
+/// ```ignore
+/// // Move an item from one list to another
+/// loop {
+///     let mut txn = store.transaction();
+///     let todo = txn.get("list:todo").await?.unwrap_or_default();
+///     let done = txn.get("list:done").await?.unwrap_or_default();
+///     let (todo, done) = move_item(todo, done);
+///     txn.set("list:todo", &todo, None);
+///     txn.set("list:done", &done, None);
+///     if txn.commit().await? {
+///         break;
+///     }
+/// }
+/// ```
+pub struct KvTransaction {
+    store: KvStore,
+    commit: KvCommit,
+}
+
+impl KvTransaction {
+    pub(super) fn new(store: KvStore) -> Self {
+        Self {
+            store,
+            commit: KvCommit::default(),
+        }
+    }
+
+    /// Reads a key, seeing this transaction's own writes.
+    ///
+    /// The first value read from the store is checked again on commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    pub async fn get(&mut self, key: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(write) = self.commit.writes.get(key) {
+            return Ok(match write {
+                KvWrite::Set { value, .. } => Some(value.clone()),
+                KvWrite::Delete => None,
+            });
+        }
+        if let Some(read) = self.commit.reads.get(key) {
+            return Ok(read.clone());
+        }
+        let value = self.store.get(key).await?;
+        self.commit.reads.insert(key.to_string(), value.clone());
+        Ok(value)
+    }
+
+    /// Buffers a write of `value` to `key`.
+    pub fn set(&mut self, key: &str, value: &[u8], ttl: Option<Duration>) {
+        self.commit.writes.insert(
+            key.to_string(),
+            KvWrite::Set {
+                value: value.to_vec(),
+                ttl,
+            },
+        );
+    }
+
+    /// Buffers a delete of `key`.
+    pub fn delete(&mut self, key: &str) {
+        self.commit.writes.insert(key.to_string(), KvWrite::Delete);
+    }
+
+    /// Applies the buffered writes if no key read has changed.
+    ///
+    /// Returns `Ok(false)` on conflict, in which case nothing was written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying storage operation fails.
+    pub async fn commit(self) -> Result<bool> {
+        self.store.commit(self.commit).await
+    }
+}
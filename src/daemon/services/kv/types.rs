@@ -3,15 +3,39 @@
 //! Contains the internal entry structure used for storing values with
 //! optional expiration metadata.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// A page of entries from [`KvStore::scan`](super::KvStore::scan).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KvScan {
+    /// Keys and values, in key order.
+    pub entries: Vec<(String, Vec<u8>)>,
+    /// Pass to the next scan to continue after this page. `None` when
+    /// there are no more keys.
+    pub cursor: Option<String>,
+}
+
+impl KvScan {
+    /// Builds a page from up to `limit + 1` sorted entries; the extra entry
+    /// only signals that there is more.
+    pub(crate) fn page(mut entries: Vec<(String, Vec<u8>)>, limit: usize) -> Self {
+        let cursor = if entries.len() > limit {
+            entries.truncate(limit);
+            entries.last().map(|(key, _)| key.clone())
+        } else {
+            None
+        };
+        Self { entries, cursor }
+    }
+}
+
 /// Internal structure for storing values with optional expiration.
 ///
 /// Serialized to JSON for compatibility with debugging tools and future
 /// schema evolution. The expiration timestamp is in Unix epoch seconds.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct KvEntry {
     /// The actual value bytes (could be any binary data)
     pub value: Vec<u8>,
@@ -55,3 +79,20 @@ impl KvEntry {
         }
     }
 }
+
+/// Adds `delta` to a counter value as stored by `increment`.
+///
+/// A missing value counts as zero.
+pub(crate) fn add_to_counter(key: &str, current: Option<&[u8]>, delta: i64) -> Result<i64> {
+    let current = match current {
+        Some(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|text| text.trim().parse::<i64>().ok())
+            .with_context(|| format!("Value of key '{key}' is not an integer"))?,
+        None => 0,
+    };
+    let Some(value) = current.checked_add(delta) else {
+        bail!("Incrementing key '{key}' overflows");
+    };
+    Ok(value)
+}
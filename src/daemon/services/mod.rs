@@ -67,7 +67,10 @@ use std::path::PathBuf;
 pub use builder::{Services, ServicesBuilder};
 
 // Re-export commonly used types for convenience
-pub use kv::{KvBackend, KvStore, MemoryBackend as KvMemoryBackend, RedbBackend};
+pub use kv::{
+    CacheConfig, CachedRedbBackend, KvBackend, KvStore, MemoryBackend as KvMemoryBackend,
+    RedbBackend,
+};
 pub use sql::{MemorySqlBackend, Row, SqlBackend, SqlService, SqliteBackend, Value};
 pub use storage::{
     FilesystemBackend, MemoryStorageBackend, ObjectMeta, StorageBackend, StorageService,
@@ -3,9 +3,13 @@
 //! Defines the interface that all SQL storage backends must implement,
 //! enabling pluggable storage (SQLite, in-memory, PostgreSQL, etc.).
 
+use super::stream::RowStream;
+use super::transaction::SqlTransaction;
 use super::types::{Row, Value};
-use anyhow::Result;
+use anyhow::{Result, bail};
 use async_trait::async_trait;
+use std::path::Path;
+use std::time::Duration;
 
 /// Backend trait for SQL storage.
 ///
@@ -37,6 +41,20 @@ pub trait SqlBackend: Send + Sync + 'static {
     /// - Query execution or result fetching fails
     async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>>;
 
+    /// Executes a SELECT query and yields rows as they are read.
+    ///
+    /// The default implementation runs [`query`](Self::query) and streams
+    /// the collected rows; backends override it to avoid holding the full
+    /// result set in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query cannot be prepared or started. Errors
+    /// while reading rows are yielded by the stream.
+    async fn query_stream(&self, sql: &str, params: &[Value]) -> Result<RowStream> {
+        Ok(RowStream::from_rows(self.query(sql, params).await?))
+    }
+
     /// Executes an INSERT, UPDATE, or DELETE statement.
     ///
     /// Returns the number of rows affected. Accepts parameterized queries
@@ -70,4 +88,28 @@ pub trait SqlBackend: Send + Sync + 'static {
         &self,
         statements: Vec<(String, Vec<Value>)>,
     ) -> Result<Vec<usize>>;
+
+    /// Begins an explicit transaction spanning several calls.
+    ///
+    /// The transaction is rolled back if it is dropped or stays idle for
+    /// longer than `idle_timeout`. The default implementation reports that
+    /// the backend does not support explicit transactions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started.
+    async fn begin(&self, _idle_timeout: Duration) -> Result<SqlTransaction> {
+        bail!("This SQL backend does not support explicit transactions")
+    }
+
+    /// Writes a consistent copy of the database to `path`, replacing any
+    /// existing file. The default implementation reports that the backend
+    /// does not support backups.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the copy cannot be written.
+    async fn backup(&self, _path: &Path) -> Result<()> {
+        bail!("This SQL backend does not support backups")
+    }
 }
@@ -0,0 +1,54 @@
+//! Online backups.
+//!
+//! [`SqlService::backup`](super::SqlService::backup) copies the live
+//! database to a file with SQLite's backup API, giving a consistent
+//! point-in-time snapshot without stopping the daemon. Other callers on the
+//! same database wait while the copy runs.
+//!
+//! The copy is written next to the target and renamed into place, so an
+//! interrupted backup never leaves a partial file behind.
+
+use anyhow::{Context, Result, anyhow};
+use rusqlite::{Connection, MAIN_DB};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Copies the database on `conn` to `path`, replacing any existing file.
+pub(crate) async fn backup(conn: Arc<Mutex<Connection>>, path: &Path) -> Result<()> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || backup_sync(&conn, &path))
+        .await
+        .context("Task join error")?
+}
+
+fn backup_sync(conn: &Mutex<Connection>, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create backup directory: {}", parent.display()))?;
+    }
+
+    let partial = partial_path(path);
+    let result = conn
+        .lock()
+        .map_err(|e| anyhow!("Failed to acquire database lock: {e}"))
+        .and_then(|conn| {
+            conn.backup(MAIN_DB, &partial, None)
+                .with_context(|| format!("Failed to back up database to {}", path.display()))
+        })
+        .and_then(|()| {
+            std::fs::rename(&partial, path)
+                .with_context(|| format!("Failed to move backup into place: {}", path.display()))
+        });
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    result
+}
+
+/// `{path}.partial`, where the copy is written before it is renamed.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".partial");
+    PathBuf::from(name)
+}
@@ -4,11 +4,41 @@
 //! Ideal for testing, development, and embedded use cases.
 
 use super::backend::SqlBackend;
+use super::backup;
+use super::stream::{self, RowStream};
+use super::transaction::SqlTransaction;
 use super::types::{Row, Value};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use rusqlite::{Connection, params_from_iter};
+use rusqlite::{Connection, MAIN_DB, params_from_iter};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Point-in-time copy of a [`MemorySqlBackend`] database.
+///
+/// Holds the serialized database image. Cheap to clone and can be restored
+/// any number of times, so a test harness can take one snapshot after
+/// seeding and reset to it before every case.
+#[derive(Clone)]
+pub struct SqlSnapshot {
+    image: Arc<Vec<u8>>,
+}
+
+impl SqlSnapshot {
+    /// Size of the serialized database image in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.image.len()
+    }
+}
+
+impl std::fmt::Debug for SqlSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqlSnapshot")
+            .field("size_bytes", &self.image.len())
+            .finish()
+    }
+}
 
 /// In-memory SQL storage backend using SQLite's `:memory:` mode.
 ///
@@ -30,6 +60,21 @@ use std::sync::{Arc, Mutex};
 /// let backend = MemorySqlBackend::new()?;
 /// backend.execute_batch("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").await?;
 /// ```
+///
+/// # Snapshots
+///
+/// Clones share the same database, so a harness can keep a handle for
+/// snapshotting while handing another to `SqlService::custom`:
+///
+/// ```ignore
+/// let backend = MemorySqlBackend::new()?;
+/// backend.load_fixtures_dir("tests/fixtures/sql").await?;
+/// let seeded = backend.snapshot().await?;
+///
+/// let service = SqlService::custom(backend.clone());
+/// // ... run a test case against `service` ...
+/// backend.restore(&seeded).await?; // back to the seeded state
+/// ```
 #[derive(Clone)]
 pub struct MemorySqlBackend {
     conn: Arc<Mutex<Connection>>,
@@ -54,6 +99,114 @@ impl MemorySqlBackend {
         })
     }
 
+    /// Captures the current database state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be serialized.
+    pub async fn snapshot(&self) -> Result<SqlSnapshot> {
+        let backend = self.clone();
+        tokio::task::spawn_blocking(move || backend.snapshot_sync())
+            .await
+            .context("Task join error")?
+    }
+
+    /// Replaces the database with a previously captured snapshot.
+    ///
+    /// All tables, rows, and schema changes made since the snapshot are
+    /// discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot cannot be loaded.
+    pub async fn restore(&self, snapshot: &SqlSnapshot) -> Result<()> {
+        let backend = self.clone();
+        let snapshot = snapshot.clone();
+        tokio::task::spawn_blocking(move || backend.restore_sync(&snapshot))
+            .await
+            .context("Task join error")?
+    }
+
+    /// Executes a seed SQL file against the database.
+    ///
+    /// The file may contain multiple statements separated by semicolons.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or any statement fails.
+    pub async fn load_fixture<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let sql = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read SQL fixture: {}", path.display()))?;
+        self.execute_batch(&sql)
+            .await
+            .with_context(|| format!("Failed to load SQL fixture: {}", path.display()))
+    }
+
+    /// Executes every `*.sql` file in a directory, in file name order.
+    ///
+    /// Prefix files with numbers (`001_schema.sql`, `002_users.sql`) to
+    /// control ordering. Returns the files that were loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read or any fixture fails.
+    pub async fn load_fixtures_dir<P: AsRef<Path>>(&self, dir: P) -> Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        let mut entries = tokio::fs::read_dir(dir)
+            .await
+            .with_context(|| format!("Failed to read fixture directory: {}", dir.display()))?;
+
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "sql") {
+                files.push(path);
+            }
+        }
+        files.sort();
+
+        for file in &files {
+            self.load_fixture(file).await?;
+        }
+        Ok(files)
+    }
+
+    /// Internal helper for synchronous snapshot.
+    fn snapshot_sync(&self) -> Result<SqlSnapshot> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to acquire database lock: {e}"))?;
+
+        let data = conn
+            .serialize(MAIN_DB)
+            .context("Failed to serialize database")?;
+
+        Ok(SqlSnapshot {
+            image: Arc::new(data.to_vec()),
+        })
+    }
+
+    /// Internal helper for synchronous restore.
+    fn restore_sync(&self, snapshot: &SqlSnapshot) -> Result<()> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to acquire database lock: {e}"))?;
+
+        conn.deserialize_read_exact(
+            MAIN_DB,
+            snapshot.image.as_slice(),
+            snapshot.image.len(),
+            false,
+        )
+        .context("Failed to restore database snapshot")?;
+
+        Ok(())
+    }
+
     /// Internal helper for synchronous query execution.
     fn query_sync(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>> {
         let conn = self
@@ -165,6 +318,10 @@ impl SqlBackend for MemorySqlBackend {
             .context("Task join error")?
     }
 
+    async fn query_stream(&self, sql: &str, params: &[Value]) -> Result<RowStream> {
+        stream::query_stream(Arc::clone(&self.conn), sql.to_string(), params.to_vec()).await
+    }
+
     async fn execute(&self, sql: &str, params: &[Value]) -> Result<usize> {
         let backend = self.clone();
         let sql = sql.to_string();
@@ -191,11 +348,20 @@ impl SqlBackend for MemorySqlBackend {
             .await
             .context("Task join error")?
     }
+
+    async fn begin(&self, idle_timeout: Duration) -> Result<SqlTransaction> {
+        SqlTransaction::begin(Arc::clone(&self.conn), idle_timeout).await
+    }
+
+    async fn backup(&self, path: &Path) -> Result<()> {
+        backup::backup(Arc::clone(&self.conn), path).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::daemon::services::sql::SqlService;
 
     #[tokio::test]
     async fn test_create_table_and_insert() {
@@ -287,4 +453,268 @@ mod tests {
         let rows = backend.query("SELECT * FROM users", &[]).await.unwrap();
         assert_eq!(rows.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_query_stream() {
+        use futures::StreamExt;
+
+        let backend = MemorySqlBackend::new().unwrap();
+        backend
+            .execute_batch(
+                "CREATE TABLE events (id INTEGER PRIMARY KEY, name TEXT);
+                 WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000)
+                 INSERT INTO events SELECT i, 'event ' || i FROM n;",
+            )
+            .await
+            .unwrap();
+
+        // More rows than the read-ahead buffer
+        let mut rows = backend
+            .query_stream("SELECT id, name FROM events ORDER BY id", &[])
+            .await
+            .unwrap();
+        assert_eq!(rows.columns(), ["id", "name"]);
+        let mut count = 0;
+        while let Some(row) = rows.next().await {
+            count += 1;
+            assert_eq!(row.unwrap().values[0], Value::Integer(count));
+        }
+        assert_eq!(count, 1000);
+
+        let rows = backend
+            .query_stream("SELECT * FROM events WHERE id > ?", &[Value::Integer(5000)])
+            .await
+            .unwrap();
+        assert_eq!(rows.columns(), ["id", "name"]);
+        assert_eq!(rows.count().await, 0);
+
+        assert!(
+            backend
+                .query_stream("SELECT * FROM missing", &[])
+                .await
+                .is_err()
+        );
+
+        // Dropping a stream early releases the connection
+        let mut rows = backend
+            .query_stream("SELECT * FROM events", &[])
+            .await
+            .unwrap();
+        rows.next().await.unwrap().unwrap();
+        drop(rows);
+        let rows = backend
+            .query("SELECT COUNT(*) FROM events", &[])
+            .await
+            .unwrap();
+        assert_eq!(rows[0].values[0], Value::Integer(1000));
+    }
+
+    #[tokio::test]
+    async fn test_explicit_transactions() {
+        let service = SqlService::memory().unwrap();
+        service
+            .execute_batch("CREATE TABLE accounts (id INTEGER PRIMARY KEY, balance INTEGER)")
+            .await
+            .unwrap();
+        service
+            .execute("INSERT INTO accounts VALUES (1, 100), (2, 0)", &[])
+            .await
+            .unwrap();
+        let balance = |id: i64| {
+            let service = service.clone();
+            async move {
+                let rows = service
+                    .query(
+                        "SELECT balance FROM accounts WHERE id = ?",
+                        &[Value::Integer(id)],
+                    )
+                    .await
+                    .unwrap();
+                rows[0].values[0].clone()
+            }
+        };
+
+        // Changes are visible inside the transaction and applied on commit
+        let txn = service.begin().await.unwrap();
+        txn.execute(
+            "UPDATE accounts SET balance = balance - 10 WHERE id = 1",
+            &[],
+        )
+        .await
+        .unwrap();
+        let rows = txn
+            .query("SELECT balance FROM accounts WHERE id = 1", &[])
+            .await
+            .unwrap();
+        assert_eq!(rows[0].values[0], Value::Integer(90));
+        // A failed statement leaves the transaction usable
+        assert!(txn.execute("UPDATE missing SET x = 1", &[]).await.is_err());
+        txn.execute(
+            "UPDATE accounts SET balance = balance + 10 WHERE id = 2",
+            &[],
+        )
+        .await
+        .unwrap();
+        txn.commit().await.unwrap();
+        assert!(txn.query("SELECT 1", &[]).await.is_err());
+        assert_eq!(balance(1).await, Value::Integer(90));
+        assert_eq!(balance(2).await, Value::Integer(10));
+
+        // Rollback, drop, and idle timeout all discard changes
+        let txn = service.begin().await.unwrap();
+        txn.execute("DELETE FROM accounts", &[]).await.unwrap();
+        txn.rollback().await.unwrap();
+
+        let txn = service.begin().await.unwrap();
+        txn.execute("DELETE FROM accounts", &[]).await.unwrap();
+        drop(txn);
+
+        let txn = service
+            .begin_with_idle_timeout(Duration::from_millis(50))
+            .await
+            .unwrap();
+        txn.execute("DELETE FROM accounts", &[]).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(txn.execute("DELETE FROM accounts", &[]).await.is_err());
+        assert!(txn.is_finished());
+
+        assert_eq!(balance(1).await, Value::Integer(90));
+    }
+
+    #[tokio::test]
+    async fn test_with_transaction() {
+        let service = SqlService::memory().unwrap();
+        service
+            .execute_batch("CREATE TABLE items (name TEXT NOT NULL)")
+            .await
+            .unwrap();
+
+        let inserted = service
+            .with_transaction(|txn| async move {
+                txn.execute("INSERT INTO items VALUES ('a')", &[]).await?;
+                txn.execute("INSERT INTO items VALUES ('b')", &[]).await
+            })
+            .await
+            .unwrap();
+        assert_eq!(inserted, 1);
+
+        let result: Result<()> = service
+            .with_transaction(|txn| async move {
+                txn.execute("INSERT INTO items VALUES ('c')", &[]).await?;
+                txn.execute("INSERT INTO items VALUES (NULL)", &[]).await?;
+                Ok(())
+            })
+            .await;
+        assert!(result.is_err());
+
+        let rows = service.query("SELECT * FROM items", &[]).await.unwrap();
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_backup_to_file() {
+        let service = SqlService::memory().unwrap();
+        service
+            .execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+                 INSERT INTO users (name) VALUES ('Alice');",
+            )
+            .await
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/backup.db");
+        service.backup(&path).await.unwrap();
+
+        // Later writes do not reach the snapshot, and backups overwrite
+        service
+            .execute("INSERT INTO users (name) VALUES ('Bob')", &[])
+            .await
+            .unwrap();
+        let restored = SqlService::file(&path).unwrap();
+        let rows = restored.query("SELECT name FROM users", &[]).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        drop(restored);
+
+        service.backup(&path).await.unwrap();
+        let restored = SqlService::file(&path).unwrap();
+        let rows = restored.query("SELECT name FROM users", &[]).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(!dir.path().join("nested/backup.db.partial").exists());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_restore() {
+        let backend = MemorySqlBackend::new().unwrap();
+
+        backend
+            .execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+                 INSERT INTO users (name) VALUES ('Alice');",
+            )
+            .await
+            .unwrap();
+
+        let snapshot = backend.snapshot().await.unwrap();
+        assert!(snapshot.size_bytes() > 0);
+
+        backend
+            .execute_batch(
+                "INSERT INTO users (name) VALUES ('Bob');
+                 CREATE TABLE orders (id INTEGER PRIMARY KEY);",
+            )
+            .await
+            .unwrap();
+
+        backend.restore(&snapshot).await.unwrap();
+
+        let rows = backend.query("SELECT name FROM users", &[]).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].values[0], Value::Text("Alice".to_string()));
+        assert!(backend.query("SELECT * FROM orders", &[]).await.is_err());
+
+        // Snapshots can be restored repeatedly and remain writable
+        backend
+            .execute("INSERT INTO users (name) VALUES ('Carol')", &[])
+            .await
+            .unwrap();
+        backend.restore(&snapshot).await.unwrap();
+        let rows = backend.query("SELECT * FROM users", &[]).await.unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_fixtures_dir_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("002_users.sql"),
+            "INSERT INTO users (name) VALUES ('Alice'); INSERT INTO users (name) VALUES ('Bob');",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("001_schema.sql"),
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "not sql").unwrap();
+
+        let backend = MemorySqlBackend::new().unwrap();
+        let loaded = backend.load_fixtures_dir(dir.path()).await.unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded[0].ends_with("001_schema.sql"));
+
+        let rows = backend.query("SELECT * FROM users", &[]).await.unwrap();
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_load_fixture_reports_file_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.sql");
+        std::fs::write(&path, "CREATE TABLE").unwrap();
+
+        let backend = MemorySqlBackend::new().unwrap();
+        let err = backend.load_fixture(&path).await.unwrap_err();
+        assert!(err.to_string().contains("broken.sql"));
+    }
 }
@@ -23,6 +23,10 @@
 //!
 //! // Query data
 //! let rows = service.query("SELECT * FROM users WHERE id = ?", &[Value::Integer(1)]).await?;
+//!
+//! // Stream a large result set row by row
+//! let mut rows = service.query_stream("SELECT * FROM users", &[]).await?;
+//! while let Some(row) = rows.next().await { /* ... */ }
 //! ```
 //!
 //! # Custom Backends
@@ -37,16 +41,60 @@
 //!
 //! let service = SqlService::custom(PostgresBackend::new());
 //! ```
+//!
+//! # Transactions
+//!
+//! `SqlService::begin` opens a transaction that spans several calls, and
+//! `SqlService::with_transaction` commits or rolls back around a closure:
+//!
+//! ```ignore
+//! service
+//!     .with_transaction(|txn| async move {
+//!         txn.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1", &[]).await?;
+//!         txn.execute("UPDATE accounts SET balance = balance + 10 WHERE id = 2", &[]).await?;
+//!         Ok(())
+//!     })
+//!     .await?;
+//! ```
+//!
+//! # Backups
+//!
+//! `SqlService::backup` writes a consistent copy of the live database to a
+//! file using SQLite's online backup API:
+//!
+//! ```ignore
+//! service.backup("/var/backups/mik/sql-20250131.db").await?;
+//! ```
+//!
+//! `sql-backup` cron jobs take these snapshots on a schedule; see
+//! [`crate::daemon::backup`].
+//!
+//! # Tenant Isolation
+//!
+//! `TenantSqlService` gives each tenant its own database so queries issued
+//! on behalf of one tenant can never read another tenant's rows:
+//!
+//! ```ignore
+//! let tenants = TenantSqlService::directory("~/.mik/sql-tenants")?;
+//! let rows = tenants.for_tenant("acme")?.query("SELECT * FROM orders", &[]).await?;
+//! ```
 
 mod backend;
+mod backup;
 mod memory;
 mod service;
 mod sqlite;
+mod stream;
+mod tenant;
+mod transaction;
 mod types;
 
 // Re-export the public API
 pub use backend::SqlBackend;
-pub use memory::MemorySqlBackend;
+pub use memory::{MemorySqlBackend, SqlSnapshot};
 pub use service::SqlService;
-pub use sqlite::SqliteBackend;
+pub use sqlite::{STATEMENT_CACHE_CAPACITY, SqliteBackend, StatementCacheStats};
+pub use stream::{RowStream, STREAM_BUFFER};
+pub use tenant::{TenantSqlService, validate_tenant_id};
+pub use transaction::{DEFAULT_IDLE_TIMEOUT, SqlTransaction};
 pub use types::{Row, Value};
@@ -5,10 +5,13 @@
 use super::backend::SqlBackend;
 use super::memory::MemorySqlBackend;
 use super::sqlite::SqliteBackend;
+use super::stream::RowStream;
+use super::transaction::{DEFAULT_IDLE_TIMEOUT, SqlTransaction};
 use super::types::{Row, Value};
 use anyhow::Result;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// High-level SQL service interface.
 ///
@@ -93,6 +96,11 @@ impl SqlService {
         }
     }
 
+    /// Returns a shared handle to the underlying backend.
+    pub(crate) fn backend(&self) -> Arc<dyn SqlBackend> {
+        Arc::clone(&self.backend)
+    }
+
     /// Executes a SELECT query and returns matching rows.
     ///
     /// Accepts parameterized queries to prevent SQL injection.
@@ -104,6 +112,18 @@ impl SqlService {
         self.backend.query(sql, params).await
     }
 
+    /// Executes a SELECT query and yields rows as they are read.
+    ///
+    /// Use this instead of [`query`](Self::query) for large result sets. The
+    /// query holds the database until the stream is exhausted or dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query cannot be prepared or started.
+    pub async fn query_stream(&self, sql: &str, params: &[Value]) -> Result<RowStream> {
+        self.backend.query_stream(sql, params).await
+    }
+
     /// Executes an INSERT, UPDATE, or DELETE statement.
     ///
     /// Returns the number of rows affected.
@@ -139,4 +159,78 @@ impl SqlService {
     ) -> Result<Vec<usize>> {
         self.backend.execute_batch_atomic(statements).await
     }
+
+    /// Begins an explicit transaction, rolled back if it is dropped or idle
+    /// for longer than [`DEFAULT_IDLE_TIMEOUT`].
+    ///
+    /// The transaction holds the database until it ends; other callers wait.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started.
+    pub async fn begin(&self) -> Result<SqlTransaction> {
+        self.backend.begin(DEFAULT_IDLE_TIMEOUT).await
+    }
+
+    /// Begins an explicit transaction with a custom idle timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started.
+    pub async fn begin_with_idle_timeout(&self, idle_timeout: Duration) -> Result<SqlTransaction> {
+        self.backend.begin(idle_timeout).await
+    }
+
+    /// Writes a consistent copy of the live database to `path`, replacing
+    /// any existing file.
+    ///
+    /// Uses SQLite's online backup API, so the daemon keeps running; other
+    /// callers wait until the copy is done. The backup is an ordinary SQLite
+    /// database that can be opened with [`SqlService::file`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend does not support backups or the copy
+    /// cannot be written.
+    pub async fn backup(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.backend.backup(path.as_ref()).await
+    }
+
+    /// Runs `f` inside a transaction, committing if it returns `Ok` and
+    /// rolling back otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let order_id = service
+    ///     .with_transaction(|txn| async move {
+    ///         txn.execute("INSERT INTO orders (customer) VALUES (?)", &[Value::Integer(7)]).await?;
+    ///         let rows = txn.query("SELECT last_insert_rowid() AS id", &[]).await?;
+    ///         txn.execute("UPDATE stock SET count = count - 1 WHERE item = ?", &[Value::Integer(3)]).await?;
+    ///         Ok(rows[0].get("id").cloned())
+    ///     })
+    ///     .await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from `f`, or an error if the transaction cannot be
+    /// started or committed.
+    pub async fn with_transaction<T, F, Fut>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(SqlTransaction) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let txn = self.begin().await?;
+        match f(txn.clone()).await {
+            Ok(value) => {
+                txn.commit().await?;
+                Ok(value)
+            },
+            Err(e) => {
+                let _ = txn.rollback().await;
+                Err(e)
+            },
+        }
+    }
 }
@@ -1,14 +1,104 @@
 //! SQLite-backed SQL storage backend.
 //!
 //! Provides persistent SQL storage using rusqlite with ACID guarantees.
+//!
+//! Statements are prepared once per connection and reused, keyed by SQL
+//! text, so hot queries skip parsing. Lookups are counted in
+//! `mik_sql_statement_cache_total` and [`SqliteBackend::statement_cache_stats`].
 
 use super::backend::SqlBackend;
+use super::backup;
+use super::stream::{self, RowStream};
+use super::transaction::SqlTransaction;
 use super::types::{Row, Value};
+use crate::daemon::metrics;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use rusqlite::{Connection, params_from_iter};
+use std::collections::VecDeque;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Default number of prepared statements kept per connection.
+pub const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+/// Hit and miss counts of a prepared statement cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatementCacheStats {
+    /// Lookups that reused a prepared statement.
+    pub hits: u64,
+    /// Lookups that had to prepare the statement.
+    pub misses: u64,
+}
+
+impl StatementCacheStats {
+    /// Fraction of lookups that were hits (0 when there were none).
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+/// Tracks which statements the connection's LRU cache holds, to count
+/// hits. rusqlite does not report them itself.
+struct StatementCache {
+    capacity: usize,
+    /// SQL texts, most recently used first.
+    recent: parking_lot::Mutex<VecDeque<String>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl StatementCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            recent: parking_lot::Mutex::new(VecDeque::with_capacity(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Records a lookup of `sql`, mirroring the connection cache's eviction.
+    fn record(&self, sql: &str) {
+        // rusqlite keys its cache by the trimmed SQL text
+        let sql = sql.trim();
+        let hit = {
+            let mut recent = self.recent.lock();
+            if let Some(pos) = recent.iter().position(|cached| cached == sql) {
+                if let Some(cached) = recent.remove(pos) {
+                    recent.push_front(cached);
+                }
+                true
+            } else {
+                recent.push_front(sql.to_string());
+                recent.truncate(self.capacity);
+                false
+            }
+        };
+
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        metrics::record_sql_statement_cache(hit);
+    }
+
+    fn stats(&self) -> StatementCacheStats {
+        StatementCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
 
 /// SQLite-backed SQL storage backend.
 ///
@@ -22,6 +112,7 @@ use std::sync::{Arc, Mutex};
 #[derive(Clone)]
 pub struct SqliteBackend {
     conn: Arc<Mutex<Connection>>,
+    statements: Arc<StatementCache>,
 }
 
 impl SqliteBackend {
@@ -52,12 +143,33 @@ impl SqliteBackend {
         // Enable foreign keys by default for referential integrity
         conn.execute("PRAGMA foreign_keys = ON", [])
             .context("Failed to enable foreign keys")?;
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            statements: Arc::new(StatementCache::new(STATEMENT_CACHE_CAPACITY)),
         })
     }
 
+    /// Sets how many prepared statements are kept (0 disables caching).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection lock is poisoned.
+    pub fn with_statement_cache_capacity(mut self, capacity: usize) -> Result<Self> {
+        self.conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to acquire database lock: {e}"))?
+            .set_prepared_statement_cache_capacity(capacity);
+        self.statements = Arc::new(StatementCache::new(capacity));
+        Ok(self)
+    }
+
+    /// Hit and miss counts of the prepared statement cache.
+    pub fn statement_cache_stats(&self) -> StatementCacheStats {
+        self.statements.stats()
+    }
+
     /// Internal helper for synchronous query execution.
     fn query_sync(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>> {
         let conn = self
@@ -66,8 +178,9 @@ impl SqliteBackend {
             .map_err(|e| anyhow::anyhow!("Failed to acquire database lock: {e}"))?;
 
         let mut stmt = conn
-            .prepare(sql)
+            .prepare_cached(sql)
             .with_context(|| format!("Failed to prepare query: {sql}"))?;
+        self.statements.record(sql);
 
         let rusqlite_params: Vec<rusqlite::types::Value> =
             params.iter().map(Value::to_rusqlite).collect();
@@ -103,8 +216,13 @@ impl SqliteBackend {
         let rusqlite_params: Vec<rusqlite::types::Value> =
             params.iter().map(Value::to_rusqlite).collect();
 
-        let affected = conn
-            .execute(sql, params_from_iter(rusqlite_params.iter()))
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .with_context(|| format!("Failed to prepare statement: {sql}"))?;
+        self.statements.record(sql);
+
+        let affected = stmt
+            .execute(params_from_iter(rusqlite_params.iter()))
             .with_context(|| format!("Failed to execute statement: {sql}"))?;
 
         Ok(affected)
@@ -140,7 +258,12 @@ impl SqliteBackend {
             let rusqlite_params: Vec<rusqlite::types::Value> =
                 params.iter().map(Value::to_rusqlite).collect();
 
-            match conn.execute(sql, params_from_iter(rusqlite_params.iter())) {
+            let result = conn.prepare_cached(sql).and_then(|mut stmt| {
+                self.statements.record(sql);
+                stmt.execute(params_from_iter(rusqlite_params.iter()))
+            });
+
+            match result {
                 Ok(affected) => results.push(affected),
                 Err(e) => {
                     // Rollback on error
@@ -169,6 +292,10 @@ impl SqlBackend for SqliteBackend {
             .context("Task join error")?
     }
 
+    async fn query_stream(&self, sql: &str, params: &[Value]) -> Result<RowStream> {
+        stream::query_stream(Arc::clone(&self.conn), sql.to_string(), params.to_vec()).await
+    }
+
     async fn execute(&self, sql: &str, params: &[Value]) -> Result<usize> {
         let backend = self.clone();
         let sql = sql.to_string();
@@ -195,4 +322,54 @@ impl SqlBackend for SqliteBackend {
             .await
             .context("Task join error")?
     }
+
+    async fn begin(&self, idle_timeout: Duration) -> Result<SqlTransaction> {
+        SqlTransaction::begin(Arc::clone(&self.conn), idle_timeout).await
+    }
+
+    async fn backup(&self, path: &Path) -> Result<()> {
+        backup::backup(Arc::clone(&self.conn), path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_statement_cache_hits() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = SqliteBackend::open(dir.path().join("cache.db"))
+            .unwrap()
+            .with_statement_cache_capacity(2)
+            .unwrap();
+
+        backend
+            .execute_batch("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)")
+            .await
+            .unwrap();
+        let insert = "INSERT INTO items (name) VALUES (?)";
+        for name in ["a", "b", "c"] {
+            backend
+                .execute(insert, &[Value::Text(name.to_string())])
+                .await
+                .unwrap();
+        }
+        let rows = backend.query("SELECT * FROM items", &[]).await.unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            backend.statement_cache_stats(),
+            StatementCacheStats { hits: 2, misses: 2 }
+        );
+
+        // A third statement evicts the least recently used one (the insert)
+        backend.query("SELECT 1", &[]).await.unwrap();
+        backend
+            .execute(insert, &[Value::Text("d".to_string())])
+            .await
+            .unwrap();
+        let stats = backend.statement_cache_stats();
+        assert_eq!(stats, StatementCacheStats { hits: 2, misses: 4 });
+        assert!((stats.hit_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+    }
 }
@@ -0,0 +1,153 @@
+//! Streaming query results.
+//!
+//! [`SqlService::query_stream`](super::SqlService::query_stream) returns a
+//! [`RowStream`] that yields rows as SQLite produces them, so a SELECT over
+//! hundreds of thousands of rows never materializes a full `Vec<Row>`.
+//!
+//! At most [`STREAM_BUFFER`] rows are read ahead of the consumer. The
+//! statement holds the database connection until the stream is exhausted
+//! or dropped, so other callers on the same database wait meanwhile.
+
+use super::types::{Row, Value};
+use anyhow::{Context, Result, anyhow};
+use futures::Stream;
+use rusqlite::{Connection, params_from_iter};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll};
+use tokio::sync::{mpsc, oneshot};
+
+/// Rows read ahead of a slow consumer.
+pub const STREAM_BUFFER: usize = 256;
+
+/// Rows of a query, yielded one at a time.
+///
+/// The stream ends after the last row, or after the first error.
+///
+/// # Example
+///
+/// ```ignore
+/// use futures::StreamExt;
+///
+/// let mut rows = service.query_stream("SELECT * FROM events", &[]).await?;
+/// while let Some(row) = rows.next().await {
+///     process(row?);
+/// }
+/// ```
+pub struct RowStream {
+    columns: Vec<String>,
+    rows: Pin<Box<dyn Stream<Item = Result<Row>> + Send>>,
+}
+
+impl RowStream {
+    /// Streams rows that are already in memory.
+    ///
+    /// Column names are taken from the first row, so they are empty when
+    /// there are no rows.
+    pub fn from_rows(rows: Vec<Row>) -> Self {
+        Self {
+            columns: rows
+                .first()
+                .map(|row| row.columns.clone())
+                .unwrap_or_default(),
+            rows: Box::pin(futures::stream::iter(rows.into_iter().map(Ok))),
+        }
+    }
+
+    /// Column names of the result set.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+}
+
+impl Stream for RowStream {
+    type Item = Result<Row>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().rows.as_mut().poll_next(cx)
+    }
+}
+
+/// Runs `sql` on `conn` in a blocking task, sending rows as they are read.
+///
+/// Returns once the statement is prepared, so syntax errors are reported
+/// here rather than as the first item.
+pub(crate) async fn query_stream(
+    conn: Arc<Mutex<Connection>>,
+    sql: String,
+    params: Vec<Value>,
+) -> Result<RowStream> {
+    let (ready, started) = oneshot::channel();
+    let (rows, mut receiver) = mpsc::channel(STREAM_BUFFER);
+
+    tokio::task::spawn_blocking(move || {
+        let conn = match conn.lock() {
+            Ok(conn) => conn,
+            Err(e) => {
+                let _ = ready.send(Err(anyhow!("Failed to acquire database lock: {e}")));
+                return;
+            },
+        };
+
+        let mut stmt = match conn
+            .prepare(&sql)
+            .with_context(|| format!("Failed to prepare query: {sql}"))
+        {
+            Ok(stmt) => stmt,
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            },
+        };
+
+        let column_names: Vec<String> = (0..stmt.column_count())
+            .map(|i| stmt.column_name(i).unwrap_or("unknown").to_string())
+            .collect();
+        let rusqlite_params: Vec<rusqlite::types::Value> =
+            params.iter().map(Value::to_rusqlite).collect();
+
+        let mut results = match stmt
+            .query(params_from_iter(rusqlite_params.iter()))
+            .with_context(|| format!("Failed to execute query: {sql}"))
+        {
+            Ok(results) => results,
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            },
+        };
+
+        if ready.send(Ok(column_names.clone())).is_err() {
+            return;
+        }
+
+        loop {
+            let item = match results.next() {
+                Ok(Some(row)) => read_row(row, &column_names),
+                Ok(None) => return,
+                Err(e) => Err(anyhow::Error::from(e).context("Failed to fetch query results")),
+            };
+            let failed = item.is_err();
+            // Stop when the consumer has gone away or after an error
+            if rows.blocking_send(item).is_err() || failed {
+                return;
+            }
+        }
+    });
+
+    let columns = started.await.context("Query task exited")??;
+
+    Ok(RowStream {
+        columns,
+        rows: Box::pin(futures::stream::poll_fn(move |cx| receiver.poll_recv(cx))),
+    })
+}
+
+fn read_row(row: &rusqlite::Row<'_>, column_names: &[String]) -> Result<Row> {
+    let mut values = Vec::with_capacity(column_names.len());
+    for i in 0..column_names.len() {
+        let value_ref = row.get_ref(i).context("Failed to fetch query results")?;
+        values.push(Value::from(value_ref));
+    }
+    Ok(Row::new(column_names.to_vec(), values))
+}
@@ -0,0 +1,332 @@
+//! Tenant-scoped SQL access.
+//!
+//! Isolates tenants by giving each one its own database. A handler that
+//! forgets a `WHERE tenant_id = ?` clause still only sees its own tenant's
+//! rows, because other tenants' tables are not reachable from its connection.
+
+use super::backend::SqlBackend;
+use super::service::SqlService;
+use super::stream::RowStream;
+use super::transaction::SqlTransaction;
+use super::types::{Row, Value};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum length of a tenant ID.
+const MAX_TENANT_ID_LEN: usize = 64;
+
+/// SQL keywords that would let a statement escape its tenant database.
+///
+/// `ATTACH` can open another tenant's file. `VACUUM INTO` is checked
+/// separately since plain `VACUUM` is harmless.
+const CROSS_DATABASE_KEYWORDS: &[&str] = &["ATTACH", "DETACH"];
+
+/// Validates a tenant ID before it is used to derive a database location.
+///
+/// Tenant IDs must:
+/// - Be 1-64 characters long
+/// - Contain only alphanumeric characters, hyphens, and underscores
+/// - Not start with a hyphen or underscore
+///
+/// # Errors
+///
+/// Returns an error describing the first rule the ID violates.
+pub fn validate_tenant_id(tenant_id: &str) -> Result<()> {
+    if tenant_id.is_empty() {
+        bail!("Tenant ID cannot be empty");
+    }
+    if tenant_id.len() > MAX_TENANT_ID_LEN {
+        bail!("Tenant ID must be {MAX_TENANT_ID_LEN} characters or less");
+    }
+    if !tenant_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("Tenant ID can only contain alphanumeric characters, hyphens, and underscores");
+    }
+    if tenant_id.starts_with('-') || tenant_id.starts_with('_') {
+        bail!("Tenant ID cannot start with a hyphen or underscore");
+    }
+    Ok(())
+}
+
+/// Rejects statements that could reach outside the tenant's database.
+///
+/// Keywords are matched on word boundaries outside of string literals, so a
+/// value like `'attachment'` or a column named `attached_at` is allowed.
+fn check_tenant_statement(sql: &str) -> Result<()> {
+    let mut in_literal: Option<char> = None;
+    let mut word = String::new();
+    let mut words: Vec<String> = Vec::new();
+
+    for c in sql.chars() {
+        if let Some(quote) = in_literal {
+            if c == quote {
+                in_literal = None;
+            }
+            continue;
+        }
+        if c == '\'' || c == '"' || c == '`' {
+            in_literal = Some(c);
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c.to_ascii_uppercase());
+            continue;
+        }
+        if !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    for (i, w) in words.iter().enumerate() {
+        if CROSS_DATABASE_KEYWORDS.contains(&w.as_str()) {
+            bail!("{w} is not allowed in tenant-scoped SQL");
+        }
+        if w == "VACUUM" && words.get(i + 1).is_some_and(|next| next == "INTO") {
+            bail!("VACUUM INTO is not allowed in tenant-scoped SQL");
+        }
+    }
+    Ok(())
+}
+
+/// Backend wrapper that enforces tenant statement rules before delegating.
+struct TenantGuardBackend {
+    inner: Arc<dyn SqlBackend>,
+}
+
+#[async_trait]
+impl SqlBackend for TenantGuardBackend {
+    async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>> {
+        check_tenant_statement(sql)?;
+        self.inner.query(sql, params).await
+    }
+
+    async fn query_stream(&self, sql: &str, params: &[Value]) -> Result<RowStream> {
+        check_tenant_statement(sql)?;
+        self.inner.query_stream(sql, params).await
+    }
+
+    async fn execute(&self, sql: &str, params: &[Value]) -> Result<usize> {
+        check_tenant_statement(sql)?;
+        self.inner.execute(sql, params).await
+    }
+
+    async fn execute_batch(&self, sql: &str) -> Result<()> {
+        check_tenant_statement(sql)?;
+        self.inner.execute_batch(sql).await
+    }
+
+    async fn execute_batch_atomic(
+        &self,
+        statements: Vec<(String, Vec<Value>)>,
+    ) -> Result<Vec<usize>> {
+        for (sql, _) in &statements {
+            check_tenant_statement(sql)?;
+        }
+        self.inner.execute_batch_atomic(statements).await
+    }
+
+    async fn begin(&self, idle_timeout: Duration) -> Result<SqlTransaction> {
+        let txn = self.inner.begin(idle_timeout).await?;
+        Ok(txn.with_guard(check_tenant_statement))
+    }
+
+    async fn backup(&self, path: &Path) -> Result<()> {
+        self.inner.backup(path).await
+    }
+}
+
+/// Where tenant databases live.
+#[derive(Clone)]
+enum TenantLocation {
+    /// One SQLite file per tenant: `{dir}/{tenant_id}.db`.
+    Directory(PathBuf),
+    /// One in-memory database per tenant (testing/embedding).
+    Memory,
+}
+
+/// SQL service that routes each tenant to its own database.
+///
+/// Databases are opened lazily on first use and cached for the lifetime of
+/// the service. Every returned [`SqlService`] rejects `ATTACH`, `DETACH`, and
+/// `VACUUM INTO` so a handler cannot open another tenant's file.
+///
+/// # Example
+///
+/// ```ignore
+/// use mik::daemon::services::sql::TenantSqlService;
+///
+/// let tenants = TenantSqlService::directory("~/.mik/sql-tenants")?;
+///
+/// let acme = tenants.for_tenant("acme")?;
+/// acme.execute_batch("CREATE TABLE orders (id INTEGER PRIMARY KEY)").await?;
+///
+/// // "globex" has its own database and cannot see acme's orders table
+/// let globex = tenants.for_tenant("globex")?;
+/// ```
+#[derive(Clone)]
+pub struct TenantSqlService {
+    location: TenantLocation,
+    services: Arc<DashMap<String, SqlService>>,
+}
+
+impl TenantSqlService {
+    /// Creates a tenant service storing one SQLite file per tenant in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created.
+    pub fn directory<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).with_context(|| {
+            format!(
+                "Failed to create tenant database directory: {}",
+                dir.display()
+            )
+        })?;
+        Ok(Self {
+            location: TenantLocation::Directory(dir.to_path_buf()),
+            services: Arc::new(DashMap::new()),
+        })
+    }
+
+    /// Creates a tenant service with one in-memory database per tenant.
+    pub fn memory() -> Self {
+        Self {
+            location: TenantLocation::Memory,
+            services: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Returns the SQL service for a tenant, opening its database if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tenant ID is invalid or the database cannot
+    /// be opened.
+    pub fn for_tenant(&self, tenant_id: &str) -> Result<SqlService> {
+        validate_tenant_id(tenant_id)?;
+
+        if let Some(service) = self.services.get(tenant_id) {
+            return Ok(service.clone());
+        }
+
+        let entry = self.services.entry(tenant_id.to_string());
+        let service = match entry {
+            dashmap::mapref::entry::Entry::Occupied(existing) => existing.get().clone(),
+            dashmap::mapref::entry::Entry::Vacant(vacant) => {
+                let inner = match &self.location {
+                    TenantLocation::Directory(dir) => {
+                        SqlService::file(dir.join(format!("{tenant_id}.db")))?
+                    },
+                    TenantLocation::Memory => SqlService::memory()?,
+                };
+                let guarded = SqlService::custom(TenantGuardBackend {
+                    inner: inner.backend(),
+                });
+                vacant.insert(guarded).clone()
+            },
+        };
+
+        Ok(service)
+    }
+
+    /// Returns the number of tenant databases opened so far.
+    pub fn tenant_count(&self) -> usize {
+        self.services.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_tenant_id() {
+        assert!(validate_tenant_id("acme").is_ok());
+        assert!(validate_tenant_id("tenant-42_a").is_ok());
+        assert!(validate_tenant_id("").is_err());
+        assert!(validate_tenant_id("../other").is_err());
+        assert!(validate_tenant_id("a/b").is_err());
+        assert!(validate_tenant_id("-acme").is_err());
+        assert!(validate_tenant_id(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_check_tenant_statement() {
+        assert!(check_tenant_statement("SELECT * FROM orders").is_ok());
+        assert!(check_tenant_statement("SELECT attached_at FROM files").is_ok());
+        assert!(check_tenant_statement("INSERT INTO t VALUES ('attach me')").is_ok());
+        assert!(check_tenant_statement("VACUUM").is_ok());
+        assert!(check_tenant_statement("ATTACH DATABASE 'x.db' AS x").is_err());
+        assert!(check_tenant_statement("select 1; attach 'x.db' as x").is_err());
+        assert!(check_tenant_statement("DETACH x").is_err());
+        assert!(check_tenant_statement("VACUUM INTO '/tmp/copy.db'").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tenants_are_isolated() {
+        let tenants = TenantSqlService::memory();
+
+        let acme = tenants.for_tenant("acme").unwrap();
+        acme.execute_batch("CREATE TABLE orders (id INTEGER PRIMARY KEY, item TEXT)")
+            .await
+            .unwrap();
+        acme.execute(
+            "INSERT INTO orders (item) VALUES (?)",
+            &[Value::Text("anvil".to_string())],
+        )
+        .await
+        .unwrap();
+
+        let globex = tenants.for_tenant("globex").unwrap();
+        assert!(globex.query("SELECT * FROM orders", &[]).await.is_err());
+
+        // Same tenant gets the same database back
+        let acme_again = tenants.for_tenant("acme").unwrap();
+        let rows = acme_again.query("SELECT * FROM orders", &[]).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(tenants.tenant_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_transactions_are_guarded() {
+        let tenants = TenantSqlService::memory();
+        let txn = tenants.for_tenant("acme").unwrap().begin().await.unwrap();
+        assert!(
+            txn.execute("ATTACH DATABASE 'x.db' AS x", &[])
+                .await
+                .is_err()
+        );
+        assert!(txn.query("SELECT 1", &[]).await.is_ok());
+        txn.rollback().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_directory_tenants_use_separate_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let tenants = TenantSqlService::directory(dir.path()).unwrap();
+
+        let acme = tenants.for_tenant("acme").unwrap();
+        acme.execute_batch("CREATE TABLE t (id INTEGER)")
+            .await
+            .unwrap();
+
+        assert!(dir.path().join("acme.db").exists());
+        assert!(!dir.path().join("globex.db").exists());
+
+        let result = acme
+            .execute_batch(&format!(
+                "ATTACH DATABASE '{}' AS other",
+                dir.path().join("globex.db").display()
+            ))
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Explicit transactions spanning several calls.
+//!
+//! [`SqlService::begin`](super::SqlService::begin) starts a transaction that
+//! holds the database connection from `BEGIN` until it is committed or
+//! rolled back, so other callers on the same database wait meanwhile and
+//! transactions should be kept short. The connection is driven by a
+//! dedicated thread, which lets the transaction be used across `.await`
+//! points.
+//!
+//! A transaction that is dropped, or left idle for longer than its idle
+//! timeout, is rolled back.
+
+use super::types::{Row, Value};
+use anyhow::{Context, Result, anyhow};
+use rusqlite::{Connection, params_from_iter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// How long a transaction may sit between statements before it is rolled
+/// back.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Work sent to the thread that owns the connection.
+enum Command {
+    Query {
+        sql: String,
+        params: Vec<Value>,
+        reply: oneshot::Sender<Result<Vec<Row>>>,
+    },
+    Execute {
+        sql: String,
+        params: Vec<Value>,
+        reply: oneshot::Sender<Result<usize>>,
+    },
+    Finish {
+        commit: bool,
+        reply: oneshot::Sender<Result<()>>,
+    },
+}
+
+/// An open transaction.
+///
+/// Clones refer to the same transaction. It ends when [`commit`](Self::commit)
+/// or [`rollback`](Self::rollback) is called on any clone, after which every
+/// call fails.
+///
+/// # Example
+///
+/// ```ignore
+/// let txn = service.begin().await?;
+/// let rows = txn.query("SELECT balance FROM accounts WHERE id = ?", &[Value::Integer(1)]).await?;
+/// txn.execute("UPDATE accounts SET balance = balance - 10 WHERE id = ?", &[Value::Integer(1)]).await?;
+/// txn.execute("UPDATE accounts SET balance = balance + 10 WHERE id = ?", &[Value::Integer(2)]).await?;
+/// txn.commit().await?;
+/// ```
+#[derive(Clone)]
+pub struct SqlTransaction {
+    commands: mpsc::Sender<Command>,
+    /// Statement check applied before anything is sent to the connection.
+    guard: Option<fn(&str) -> Result<()>>,
+    finished: Arc<AtomicBool>,
+}
+
+impl SqlTransaction {
+    /// Begins a transaction on `conn`, waiting for the connection if it is
+    /// in use.
+    pub(crate) async fn begin(
+        conn: Arc<Mutex<Connection>>,
+        idle_timeout: Duration,
+    ) -> Result<Self> {
+        let (commands, receiver) = mpsc::channel();
+        let (ready, started) = oneshot::channel();
+        let finished = Arc::new(AtomicBool::new(false));
+
+        let done = Arc::clone(&finished);
+        std::thread::Builder::new()
+            .name("mik-sql-txn".to_string())
+            .spawn(move || {
+                run(&conn, &receiver, idle_timeout, ready);
+                done.store(true, Ordering::Release);
+            })
+            .context("Failed to spawn transaction thread")?;
+
+        started.await.context("Transaction thread exited")??;
+
+        Ok(Self {
+            commands,
+            guard: None,
+            finished,
+        })
+    }
+
+    /// Checks every statement with `guard` before running it.
+    pub(crate) fn with_guard(mut self, guard: fn(&str) -> Result<()>) -> Self {
+        self.guard = Some(guard);
+        self
+    }
+
+    /// Whether the transaction has been committed, rolled back, or timed out.
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+
+    /// Executes a SELECT query inside the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails or the transaction has ended.
+    pub async fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Row>> {
+        self.check(sql)?;
+        let (reply, result) = oneshot::channel();
+        self.send(Command::Query {
+            sql: sql.to_string(),
+            params: params.to_vec(),
+            reply,
+        })?;
+        result.await.map_err(|_| ended())?
+    }
+
+    /// Executes an INSERT, UPDATE, or DELETE statement inside the
+    /// transaction, returning the number of rows affected.
+    ///
+    /// A failed statement leaves the transaction open.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement fails or the transaction has ended.
+    pub async fn execute(&self, sql: &str, params: &[Value]) -> Result<usize> {
+        self.check(sql)?;
+        let (reply, result) = oneshot::channel();
+        self.send(Command::Execute {
+            sql: sql.to_string(),
+            params: params.to_vec(),
+            reply,
+        })?;
+        result.await.map_err(|_| ended())?
+    }
+
+    /// Commits the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the commit fails, in which case the transaction
+    /// is rolled back, or if the transaction has already ended.
+    pub async fn commit(&self) -> Result<()> {
+        self.finish(true).await
+    }
+
+    /// Rolls the transaction back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction has already ended.
+    pub async fn rollback(&self) -> Result<()> {
+        self.finish(false).await
+    }
+
+    async fn finish(&self, commit: bool) -> Result<()> {
+        let (reply, result) = oneshot::channel();
+        self.send(Command::Finish { commit, reply })?;
+        result.await.map_err(|_| ended())?
+    }
+
+    fn check(&self, sql: &str) -> Result<()> {
+        self.guard.map_or(Ok(()), |guard| guard(sql))
+    }
+
+    fn send(&self, command: Command) -> Result<()> {
+        self.commands.send(command).map_err(|_| ended())
+    }
+}
+
+fn ended() -> anyhow::Error {
+    anyhow!("Transaction is no longer active")
+}
+
+/// Owns the connection for the lifetime of one transaction.
+fn run(
+    conn: &Mutex<Connection>,
+    commands: &mpsc::Receiver<Command>,
+    idle_timeout: Duration,
+    ready: oneshot::Sender<Result<()>>,
+) {
+    let conn = match conn.lock() {
+        Ok(conn) => conn,
+        Err(e) => {
+            let _ = ready.send(Err(anyhow!("Failed to acquire database lock: {e}")));
+            return;
+        },
+    };
+
+    if let Err(e) = conn
+        .execute_batch("BEGIN TRANSACTION")
+        .context("Failed to begin transaction")
+    {
+        let _ = ready.send(Err(e));
+        return;
+    }
+    if ready.send(Ok(())).is_err() {
+        rollback(&conn);
+        return;
+    }
+
+    loop {
+        match commands.recv_timeout(idle_timeout) {
+            Ok(Command::Query { sql, params, reply }) => {
+                let _ = reply.send(query(&conn, &sql, &params));
+            },
+            Ok(Command::Execute { sql, params, reply }) => {
+                let _ = reply.send(execute(&conn, &sql, &params));
+            },
+            Ok(Command::Finish { commit, reply }) => {
+                let result = if commit {
+                    conn.execute_batch("COMMIT")
+                        .context("Failed to commit transaction")
+                } else {
+                    conn.execute_batch("ROLLBACK")
+                        .context("Failed to roll back transaction")
+                };
+                rollback(&conn);
+                let _ = reply.send(result);
+                return;
+            },
+            Err(RecvTimeoutError::Timeout) => {
+                tracing::warn!(
+                    idle_secs = idle_timeout.as_secs(),
+                    "Rolling back idle SQL transaction"
+                );
+                rollback(&conn);
+                return;
+            },
+            Err(RecvTimeoutError::Disconnected) => {
+                rollback(&conn);
+                return;
+            },
+        }
+    }
+}
+
+/// Rolls back whatever is still open on the connection.
+fn rollback(conn: &Connection) {
+    if !conn.is_autocommit() {
+        let _ = conn.execute_batch("ROLLBACK");
+    }
+}
+
+fn query(conn: &Connection, sql: &str, params: &[Value]) -> Result<Vec<Row>> {
+    let mut stmt = conn
+        .prepare(sql)
+        .with_context(|| format!("Failed to prepare query: {sql}"))?;
+
+    let rusqlite_params: Vec<rusqlite::types::Value> =
+        params.iter().map(Value::to_rusqlite).collect();
+
+    let column_count = stmt.column_count();
+    let column_names: Vec<String> = (0..column_count)
+        .map(|i| stmt.column_name(i).unwrap_or("unknown").to_string())
+        .collect();
+
+    let rows = stmt
+        .query_map(params_from_iter(rusqlite_params.iter()), |row| {
+            let mut values = Vec::with_capacity(column_count);
+            for i in 0..column_count {
+                values.push(Value::from(row.get_ref(i)?));
+            }
+            Ok(Row::new(column_names.clone(), values))
+        })
+        .with_context(|| format!("Failed to execute query: {sql}"))?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to fetch query results")?;
+
+    Ok(rows)
+}
+
+fn execute(conn: &Connection, sql: &str, params: &[Value]) -> Result<usize> {
+    let rusqlite_params: Vec<rusqlite::types::Value> =
+        params.iter().map(Value::to_rusqlite).collect();
+
+    conn.execute(sql, params_from_iter(rusqlite_params.iter()))
+        .with_context(|| format!("Failed to execute statement: {sql}"))
+}
@@ -3,9 +3,11 @@
 //! Defines the interface that all storage backends must implement,
 //! enabling pluggable storage (filesystem, memory, S3, etc.).
 
-use super::types::ObjectMeta;
-use anyhow::Result;
+use super::types::{ObjectMeta, ObjectStream};
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
 
 /// Backend trait for object storage.
 ///
@@ -52,6 +54,50 @@ pub trait StorageBackend: Send + Sync + 'static {
     /// Returns an error if the path is invalid or the read operation fails.
     async fn get(&self, path: &str) -> Result<Option<(Vec<u8>, ObjectMeta)>>;
 
+    /// Stores an object read from a stream of chunks.
+    ///
+    /// The object only becomes visible once the stream has been fully
+    /// consumed. The default implementation buffers the stream and calls
+    /// [`put`](Self::put); backends should override it to avoid holding the
+    /// whole object in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is invalid, the stream yields an error,
+    /// or the storage operation fails.
+    async fn put_stream(
+        &self,
+        path: &str,
+        mut data: ObjectStream,
+        content_type: Option<&str>,
+    ) -> Result<ObjectMeta> {
+        let mut buffer = Vec::new();
+        while let Some(chunk) = data.next().await {
+            buffer.extend_from_slice(&chunk.context("Failed to read object data")?);
+        }
+        self.put(path, &buffer, content_type).await
+    }
+
+    /// Retrieves an object as a stream of chunks, with its metadata.
+    ///
+    /// The default implementation loads the object with [`get`](Self::get)
+    /// and yields it as a single chunk.
+    ///
+    /// # Returns
+    /// * `Ok(Some((stream, meta)))` - Object found
+    /// * `Ok(None)` - Object not found
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is invalid or the object cannot be opened.
+    async fn get_stream(&self, path: &str) -> Result<Option<(ObjectStream, ObjectMeta)>> {
+        Ok(self.get(path).await?.map(|(data, meta)| {
+            let stream: ObjectStream =
+                Box::pin(futures::stream::once(async move { Ok(Bytes::from(data)) }));
+            (stream, meta)
+        }))
+    }
+
     /// Deletes an object.
     ///
     /// # Returns
@@ -0,0 +1,181 @@
+//! Conditional requests on object ETags.
+//!
+//! [`Preconditions`] carries the `If-Match` / `If-None-Match` headers of a
+//! request. Reads use them to answer `304 Not Modified`, writes to only
+//! replace the version of an object the client last saw:
+//!
+//! ```text
+//! PUT /storage/config.json
+//! If-Match: "<etag from the last GET>"
+//! ```
+
+use super::types::ObjectMeta;
+
+/// An `If-Match` or `If-None-Match` header value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ETagMatch {
+    /// `*`: matches any existing object.
+    Any,
+    /// A list of entity tags, without quotes.
+    Tags(Vec<String>),
+}
+
+impl ETagMatch {
+    /// Parses `*` or a comma-separated list of quoted tags.
+    ///
+    /// Weak tags (`W/"..."`) compare like strong ones, since every ETag is
+    /// a content hash.
+    pub fn parse(value: &str) -> Self {
+        if value.trim() == "*" {
+            return Self::Any;
+        }
+        Self::Tags(
+            value
+                .split(',')
+                .map(|tag| {
+                    let tag = tag.trim();
+                    let tag = tag.strip_prefix("W/").unwrap_or(tag);
+                    tag.trim_matches('"').to_string()
+                })
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+        )
+    }
+
+    fn matches(&self, current: Option<&ObjectMeta>) -> bool {
+        match self {
+            Self::Any => current.is_some(),
+            Self::Tags(tags) => current
+                .and_then(|meta| meta.etag.as_deref())
+                .is_some_and(|etag| tags.iter().any(|tag| tag == etag)),
+        }
+    }
+}
+
+/// How a conditional read should be answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadPrecondition {
+    /// Return the object.
+    Proceed,
+    /// The client's copy is current (`304 Not Modified`).
+    NotModified,
+    /// `If-Match` did not match (`412 Precondition Failed`).
+    Failed,
+}
+
+/// Conditions a request places on the current version of an object.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preconditions {
+    /// Proceed only if the object matches.
+    pub if_match: Option<ETagMatch>,
+    /// Proceed only if the object does not match.
+    pub if_none_match: Option<ETagMatch>,
+}
+
+impl Preconditions {
+    /// Builds preconditions from raw header values.
+    pub fn from_headers(if_match: Option<&str>, if_none_match: Option<&str>) -> Self {
+        Self {
+            if_match: if_match.map(ETagMatch::parse),
+            if_none_match: if_none_match.map(ETagMatch::parse),
+        }
+    }
+
+    /// Whether the request carries no conditions.
+    pub fn is_empty(&self) -> bool {
+        self.if_match.is_none() && self.if_none_match.is_none()
+    }
+
+    /// Evaluates the conditions for reading `current`.
+    pub fn check_read(&self, current: &ObjectMeta) -> ReadPrecondition {
+        if self
+            .if_match
+            .as_ref()
+            .is_some_and(|condition| !condition.matches(Some(current)))
+        {
+            return ReadPrecondition::Failed;
+        }
+        if self
+            .if_none_match
+            .as_ref()
+            .is_some_and(|condition| condition.matches(Some(current)))
+        {
+            return ReadPrecondition::NotModified;
+        }
+        ReadPrecondition::Proceed
+    }
+
+    /// Whether a write may replace `current` (`None` if the object does
+    /// not exist).
+    pub fn allows_write(&self, current: Option<&ObjectMeta>) -> bool {
+        let if_match = self
+            .if_match
+            .as_ref()
+            .is_none_or(|condition| condition.matches(current));
+        let if_none_match = self
+            .if_none_match
+            .as_ref()
+            .is_none_or(|condition| !condition.matches(current));
+        if_match && if_none_match
+    }
+}
+
+/// A conditional write was rejected because the object changed.
+#[derive(Debug, thiserror::Error)]
+#[error("Precondition failed: object '{path}' does not match the request's conditions")]
+pub struct PreconditionFailed {
+    /// Path of the object.
+    pub path: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn meta(etag: &str) -> ObjectMeta {
+        ObjectMeta {
+            path: "a.txt".to_string(),
+            size: 1,
+            content_type: "text/plain".to_string(),
+            created_at: Utc::now(),
+            modified_at: Utc::now(),
+            expires_at: None,
+            etag: Some(etag.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_preconditions() {
+        let current = meta("abc");
+        assert_eq!(
+            ETagMatch::parse(r#""abc", W/"def""#),
+            ETagMatch::Tags(vec!["abc".to_string(), "def".to_string()])
+        );
+
+        let not_modified = Preconditions::from_headers(None, Some(r#""abc""#));
+        assert_eq!(
+            not_modified.check_read(&current),
+            ReadPrecondition::NotModified
+        );
+        assert_eq!(
+            Preconditions::from_headers(Some(r#""old""#), None).check_read(&current),
+            ReadPrecondition::Failed
+        );
+        assert_eq!(
+            Preconditions::default().check_read(&current),
+            ReadPrecondition::Proceed
+        );
+
+        // Optimistic concurrency: replace only the version the client saw
+        let if_match = Preconditions::from_headers(Some(r#""abc""#), None);
+        assert!(if_match.allows_write(Some(&current)));
+        assert!(!if_match.allows_write(Some(&meta("newer"))));
+        assert!(!if_match.allows_write(None));
+
+        // Create only if absent
+        let create = Preconditions::from_headers(None, Some("*"));
+        assert!(create.allows_write(None));
+        assert!(!create.allows_write(Some(&current)));
+    }
+}
@@ -0,0 +1,123 @@
+//! Object change notifications.
+//!
+//! [`StorageService::watch`](super::StorageService::watch) subscribes to
+//! [`StorageEvent`]s for every write, overwrite, and delete under a prefix,
+//! in the spirit of S3 event notifications. Events are dropped when nobody
+//! is watching, and a watcher that falls more than [`EVENT_CAPACITY`]
+//! events behind skips ahead (`RecvError::Lagged`).
+
+use super::types::ObjectMeta;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Events buffered per subscriber.
+pub const EVENT_CAPACITY: usize = 1024;
+
+/// What happened to an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageEventKind {
+    /// A new object was stored.
+    Created,
+    /// An existing object was overwritten.
+    Updated,
+    /// An object was deleted (including lifecycle expiry).
+    Deleted,
+}
+
+impl StorageEventKind {
+    /// Event name in S3 notification style, e.g. `ObjectCreated:Put`.
+    pub const fn event_name(self) -> &'static str {
+        match self {
+            Self::Created => "ObjectCreated:Put",
+            Self::Updated => "ObjectUpdated:Put",
+            Self::Deleted => "ObjectRemoved:Delete",
+        }
+    }
+}
+
+/// A change to one object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageEvent {
+    /// What happened.
+    pub kind: StorageEventKind,
+    /// Object path.
+    pub path: String,
+    /// Size in bytes (0 for deletes).
+    pub size: u64,
+    /// Content ETag after the write.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// When the change happened.
+    pub time: DateTime<Utc>,
+}
+
+impl StorageEvent {
+    pub(crate) fn written(meta: &ObjectMeta, existed: bool) -> Self {
+        Self {
+            kind: if existed {
+                StorageEventKind::Updated
+            } else {
+                StorageEventKind::Created
+            },
+            path: meta.path.clone(),
+            size: meta.size,
+            etag: meta.etag.clone(),
+            time: meta.modified_at,
+        }
+    }
+
+    pub(crate) fn deleted(path: &str) -> Self {
+        Self {
+            kind: StorageEventKind::Deleted,
+            path: path.to_string(),
+            size: 0,
+            etag: None,
+            time: Utc::now(),
+        }
+    }
+}
+
+/// A subscription to changes under a path prefix.
+pub struct StorageWatch {
+    receiver: broadcast::Receiver<StorageEvent>,
+    /// Full path prefix to report.
+    prefix: String,
+    /// Length of the tenant namespace stripped from reported paths.
+    namespace_len: usize,
+}
+
+impl StorageWatch {
+    /// Watches `prefix` within `namespace` (empty for the whole store),
+    /// reporting paths relative to the namespace.
+    pub(crate) fn new(
+        receiver: broadcast::Receiver<StorageEvent>,
+        namespace: &str,
+        prefix: &str,
+    ) -> Self {
+        Self {
+            receiver,
+            prefix: format!("{namespace}{prefix}"),
+            namespace_len: namespace.len(),
+        }
+    }
+
+    /// Waits for the next change to an object under the prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RecvError::Lagged` if events were skipped because this
+    /// watcher fell behind (the next call continues with newer events), or
+    /// `RecvError::Closed` once the service is gone.
+    pub async fn recv(&mut self) -> Result<StorageEvent, RecvError> {
+        loop {
+            let mut event = self.receiver.recv().await?;
+            if event.path.starts_with(&self.prefix) {
+                event.path.drain(..self.namespace_len);
+                return Ok(event);
+            }
+        }
+    }
+}
@@ -5,15 +5,21 @@
 
 use super::backend::StorageBackend;
 use super::metadata::{load_metadata, reconcile, remove_metadata, save_metadata};
-use super::types::{OBJECTS_TABLE, ObjectMeta};
+use super::types::{OBJECTS_TABLE, ObjectMeta, ObjectStream, UPLOADS_DIR, content_etag};
 use super::validation::object_path;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use bytes::BytesMut;
 use chrono::Utc;
+use futures::StreamExt;
 use redb::Database;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Chunk size for streamed object reads.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Filesystem-backed object storage backend.
 ///
@@ -77,6 +83,17 @@ impl FilesystemBackend {
             db: Arc::new(db),
         };
 
+        // Uploads interrupted by a previous shutdown are never completed
+        let uploads_dir = backend.base_dir.join(UPLOADS_DIR);
+        if uploads_dir.exists() {
+            fs::remove_dir_all(&uploads_dir).with_context(|| {
+                format!(
+                    "Failed to clear interrupted uploads: {}",
+                    uploads_dir.display()
+                )
+            })?;
+        }
+
         // Reconcile metadata with filesystem on startup
         backend.reconcile_sync()?;
 
@@ -119,6 +136,8 @@ impl FilesystemBackend {
             content_type,
             created_at: now,
             modified_at: now,
+            expires_at: None,
+            etag: Some(content_etag(data)),
         };
 
         // Store metadata in database
@@ -127,6 +146,86 @@ impl FilesystemBackend {
         Ok(meta)
     }
 
+    /// Writes a streamed object to a temporary file, then moves it into place
+    /// so readers never observe a partial object.
+    async fn put_stream_inner(
+        &self,
+        path: &str,
+        mut data: ObjectStream,
+        content_type: Option<&str>,
+    ) -> Result<ObjectMeta> {
+        let file_path = object_path(&self.base_dir, path)?;
+        let uploads_dir = self.base_dir.join(UPLOADS_DIR);
+        tokio::fs::create_dir_all(&uploads_dir)
+            .await
+            .context("Failed to create uploads directory")?;
+        if let Some(parent) = file_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create parent directories for: {path}"))?;
+        }
+
+        let temp_path = uploads_dir.join(uuid::Uuid::new_v4().to_string());
+        let written = async {
+            let mut file = tokio::fs::File::create(&temp_path)
+                .await
+                .with_context(|| format!("Failed to create upload file for: {path}"))?;
+            let mut size = 0u64;
+            let mut hasher = blake3::Hasher::new();
+            while let Some(chunk) = data.next().await {
+                let chunk = chunk.context("Failed to read object data")?;
+                file.write_all(&chunk)
+                    .await
+                    .with_context(|| format!("Failed to write object: {path}"))?;
+                hasher.update(&chunk);
+                size += chunk.len() as u64;
+            }
+            file.sync_all()
+                .await
+                .with_context(|| format!("Failed to write object: {path}"))?;
+            tokio::fs::rename(&temp_path, &file_path)
+                .await
+                .with_context(|| format!("Failed to store object: {path}"))?;
+            anyhow::Ok((size, hasher.finalize().to_hex().to_string()))
+        }
+        .await;
+        let (size, etag) = match written {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                return Err(e);
+            },
+        };
+
+        let content_type = content_type
+            .map(std::string::ToString::to_string)
+            .or_else(|| {
+                mime_guess::from_path(&file_path)
+                    .first()
+                    .map(|mime| mime.to_string())
+            })
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        let now = Utc::now();
+        let meta = ObjectMeta {
+            path: path.to_string(),
+            size,
+            content_type,
+            created_at: now,
+            modified_at: now,
+            expires_at: None,
+            etag: Some(etag),
+        };
+
+        let db = self.db.clone();
+        let saved = meta.clone();
+        tokio::task::spawn_blocking(move || save_metadata(&db, &saved))
+            .await
+            .context("Task join error")??;
+
+        Ok(meta)
+    }
+
     /// Internal helper for synchronous get.
     fn get_sync(&self, path: &str) -> Result<Option<(Vec<u8>, ObjectMeta)>> {
         let file_path = object_path(&self.base_dir, path)?;
@@ -160,6 +259,8 @@ impl FilesystemBackend {
                 content_type,
                 created_at: now,
                 modified_at: now,
+                expires_at: None,
+                etag: Some(content_etag(&data)),
             }
         };
 
@@ -216,6 +317,8 @@ impl FilesystemBackend {
             content_type,
             created_at: now,
             modified_at: now,
+            expires_at: None,
+            etag: None,
         }))
     }
 
@@ -277,6 +380,38 @@ impl StorageBackend for FilesystemBackend {
             .context("Task join error")?
     }
 
+    async fn put_stream(
+        &self,
+        path: &str,
+        data: ObjectStream,
+        content_type: Option<&str>,
+    ) -> Result<ObjectMeta> {
+        self.put_stream_inner(path, data, content_type).await
+    }
+
+    async fn get_stream(&self, path: &str) -> Result<Option<(ObjectStream, ObjectMeta)>> {
+        let file_path = object_path(&self.base_dir, path)?;
+        // Opened before reading metadata, so a concurrent overwrite (which
+        // replaces the file) cannot hand out a mix of old and new content
+        let file = match tokio::fs::File::open(&file_path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read object: {path}"));
+            },
+        };
+        let Some(meta) = self.head(path).await? else {
+            return Ok(None);
+        };
+
+        let stream: ObjectStream = Box::pin(futures::stream::try_unfold(file, |mut file| async {
+            let mut chunk = BytesMut::with_capacity(STREAM_CHUNK_SIZE);
+            let read = file.read_buf(&mut chunk).await?;
+            Ok((read > 0).then(|| (chunk.freeze(), file)))
+        }));
+        Ok(Some((stream, meta)))
+    }
+
     async fn delete(&self, path: &str) -> Result<bool> {
         let backend = self.clone();
         let path = path.to_string();
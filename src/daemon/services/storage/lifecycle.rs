@@ -0,0 +1,113 @@
+//! Lifecycle rules that expire objects by path prefix.
+//!
+//! A rule expires every object under its prefix a fixed time after the
+//! object was last modified:
+//!
+//! ```toml
+//! [[services.storage_lifecycle.rules]]
+//! prefix = "tmp/"
+//! expire_after_hours = 24
+//! ```
+//!
+//! When several rules match a path, the one with the longest prefix wins, so
+//! `tmp/keep/` can be given a longer lifetime than the rest of `tmp/`.
+
+use super::types::ObjectMeta;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+/// Expire objects under `prefix` after `expire_after_hours`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LifecycleRule {
+    /// Path prefix the rule applies to (`""` matches every object).
+    pub prefix: String,
+    /// Hours after the last modification at which objects expire.
+    pub expire_after_hours: u64,
+}
+
+/// A set of lifecycle rules.
+#[derive(Debug, Clone, Default)]
+pub struct Lifecycle {
+    /// Rules sorted by descending prefix length, so the first match is the
+    /// most specific.
+    rules: Vec<LifecycleRule>,
+}
+
+impl Lifecycle {
+    /// Creates a lifecycle from rules.
+    pub fn new(mut rules: Vec<LifecycleRule>) -> Self {
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.prefix.len()));
+        Self { rules }
+    }
+
+    /// Whether no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Most specific rule matching `path`.
+    pub fn rule_for(&self, path: &str) -> Option<&LifecycleRule> {
+        self.rules
+            .iter()
+            .find(|rule| path.starts_with(&rule.prefix))
+    }
+
+    /// Expiry time of an object, or `None` if no rule matches it.
+    pub fn expires_at(&self, meta: &ObjectMeta) -> Option<DateTime<Utc>> {
+        let rule = self.rule_for(&meta.path)?;
+        let hours = i64::try_from(rule.expire_after_hours).unwrap_or(i64::MAX);
+        let ttl = Duration::try_hours(hours).unwrap_or(Duration::MAX);
+        Some(
+            meta.modified_at
+                .checked_add_signed(ttl)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        )
+    }
+
+    /// Sets `meta.expires_at` from the rules.
+    pub fn annotate(&self, meta: &mut ObjectMeta) {
+        meta.expires_at = self.expires_at(meta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(path: &str, modified_at: DateTime<Utc>) -> ObjectMeta {
+        ObjectMeta {
+            path: path.to_string(),
+            size: 0,
+            content_type: "text/plain".to_string(),
+            created_at: modified_at,
+            modified_at,
+            expires_at: None,
+            etag: None,
+        }
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let lifecycle = Lifecycle::new(vec![
+            LifecycleRule {
+                prefix: "tmp/".to_string(),
+                expire_after_hours: 24,
+            },
+            LifecycleRule {
+                prefix: "tmp/keep/".to_string(),
+                expire_after_hours: 24 * 30,
+            },
+        ]);
+        let now = Utc::now();
+
+        assert_eq!(
+            lifecycle.expires_at(&meta("tmp/a.txt", now)),
+            Some(now + Duration::hours(24))
+        );
+        assert_eq!(
+            lifecycle.expires_at(&meta("tmp/keep/a.txt", now)),
+            Some(now + Duration::hours(24 * 30))
+        );
+        assert_eq!(lifecycle.expires_at(&meta("images/a.png", now)), None);
+    }
+}
@@ -4,7 +4,7 @@
 //! concurrent access. Ideal for testing, development, and embedded use cases.
 
 use super::backend::StorageBackend;
-use super::types::ObjectMeta;
+use super::types::{ObjectMeta, content_etag};
 use super::validation::validate_path;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -95,6 +95,8 @@ impl StorageBackend for MemoryStorageBackend {
             content_type,
             created_at: now,
             modified_at: now,
+            expires_at: None,
+            etag: Some(content_etag(data)),
         };
 
         // Store object
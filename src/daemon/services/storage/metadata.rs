@@ -10,7 +10,7 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
-use super::types::{OBJECTS_TABLE, ObjectMeta};
+use super::types::{OBJECTS_TABLE, ObjectMeta, UPLOADS_DIR};
 
 /// Saves object metadata to the database.
 pub(crate) fn save_metadata(db: &Database, meta: &ObjectMeta) -> Result<()> {
@@ -170,6 +170,8 @@ pub(crate) fn reconcile(db: &Database, base_dir: &Path) -> Result<()> {
                     content_type,
                     created_at: now,
                     modified_at: now,
+                    expires_at: None,
+                    etag: None,
                 };
                 save_metadata(db, &meta)?;
             }
@@ -186,6 +188,8 @@ pub(crate) fn reconcile(db: &Database, base_dir: &Path) -> Result<()> {
             if let Some(mut meta) = load_metadata(db, path)? {
                 meta.size = *actual_size;
                 meta.modified_at = Utc::now();
+                // The content changed outside the service
+                meta.etag = None;
                 save_metadata(db, &meta)?;
             }
         }
@@ -226,6 +230,10 @@ fn scan_directory(base_dir: &Path, dir: &Path, files: &mut HashSet<String>) -> R
         if path.extension().is_some_and(|e| e == "lock") {
             continue;
         }
+        // Skip in-progress streamed uploads
+        if dir == base_dir && path.file_name().is_some_and(|n| n == UPLOADS_DIR) {
+            continue;
+        }
 
         if path.is_dir() {
             scan_directory(base_dir, &path, files)?;
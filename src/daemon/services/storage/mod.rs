@@ -19,6 +19,11 @@
 //!
 //! // List objects
 //! let images = storage.list_objects(Some("images/")).await?;
+//!
+//! // Stream large objects without buffering them in memory
+//! if let Some((stream, meta)) = storage.get_object_stream("videos/intro.mp4").await? {
+//!     // ...
+//! }
 //! ```
 //!
 //! # Custom Backends
@@ -34,6 +39,37 @@
 //! let storage = StorageService::custom(S3Backend::new());
 //! ```
 //!
+//! # Presigned URLs
+//!
+//! [`Presigner`] signs expiring GET/PUT links for single objects, so clients
+//! can transfer objects directly without holding an API key.
+//!
+//! # Lifecycle Rules
+//!
+//! [`LifecycleRule`]s expire objects under a prefix a fixed time after their
+//! last modification (e.g. `tmp/` after 24 hours). Metadata returned by the
+//! service carries the resulting `expires_at`, and
+//! [`StorageService::apply_lifecycle`] deletes objects that are due.
+//!
+//! # ETags and Conditional Requests
+//!
+//! Writes record a BLAKE3 hash of the content as the object's `etag`.
+//! [`Preconditions`] evaluates `If-Match` / `If-None-Match` against it, and
+//! [`StorageService::put_object_stream_if`] uses it for optimistic
+//! concurrency on overwrite.
+//!
+//! # Change Events
+//!
+//! [`StorageService::watch`] streams a [`StorageEvent`] for every created,
+//! overwritten, and deleted object under a prefix, for notifiers that fan
+//! changes out to workers.
+//!
+//! # Tenant Namespaces
+//!
+//! [`StorageService::tenant`] scopes the store to `tenants/{tenant_id}/`
+//! and enforces that tenant's [`TenantQuota`] on writes;
+//! [`StorageService::tenant_usage`] reports what each tenant stores.
+//!
 //! # Security
 //!
 //! All object paths are normalized and validated to prevent directory
@@ -41,16 +77,26 @@
 //! suspicious components are rejected.
 
 mod backend;
+mod conditions;
+mod events;
 mod filesystem;
+mod lifecycle;
 mod memory;
 mod metadata;
+mod presign;
 mod service;
+mod tenant;
 mod types;
 mod validation;
 
 // Re-export the public API
 pub use backend::StorageBackend;
+pub use conditions::{ETagMatch, PreconditionFailed, Preconditions, ReadPrecondition};
+pub use events::{EVENT_CAPACITY, StorageEvent, StorageEventKind, StorageWatch};
 pub use filesystem::FilesystemBackend;
+pub use lifecycle::{Lifecycle, LifecycleRule};
 pub use memory::MemoryStorageBackend;
+pub use presign::{PresignError, PresignMethod, Presigner};
 pub use service::StorageService;
-pub use types::ObjectMeta;
+pub use tenant::{StorageQuotaExceeded, TENANTS_PREFIX, TenantQuota, TenantQuotas, TenantUsage};
+pub use types::{ObjectMeta, ObjectStream};
@@ -0,0 +1,162 @@
+//! Presigned access tokens for storage objects.
+//!
+//! A presigned URL grants one method (GET or PUT) on one object path until an
+//! expiry time, without an API key:
+//!
+//! ```text
+//! /storage/images/logo.png?expires=1767225600&signature=<hex>
+//! ```
+//!
+//! The signature is an HMAC-SHA256 over the method, object path, and expiry,
+//! so none of them can be changed without invalidating the link.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Method a presigned URL grants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PresignMethod {
+    /// Download the object.
+    Get,
+    /// Upload (create or replace) the object.
+    Put,
+}
+
+impl PresignMethod {
+    /// HTTP method name.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Put => "PUT",
+        }
+    }
+}
+
+/// Reasons a presigned request is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PresignError {
+    /// The link's expiry time has passed.
+    #[error("presigned URL has expired")]
+    Expired,
+    /// The signature does not match the method, path, and expiry.
+    #[error("invalid presigned URL signature")]
+    InvalidSignature,
+}
+
+/// Signs and verifies presigned storage URLs.
+#[derive(Clone)]
+pub struct Presigner {
+    key: Vec<u8>,
+}
+
+impl Presigner {
+    /// Creates a presigner with a secret key.
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: key.as_ref().to_vec(),
+        }
+    }
+
+    /// Creates a presigner with a random key.
+    ///
+    /// URLs signed by it stop working when the process exits.
+    pub fn random() -> Self {
+        let mut key = Vec::with_capacity(32);
+        key.extend_from_slice(uuid::Uuid::new_v4().as_bytes());
+        key.extend_from_slice(uuid::Uuid::new_v4().as_bytes());
+        Self { key }
+    }
+
+    /// Hex signature granting `method` on `path` until `expires` (Unix seconds).
+    pub fn sign(&self, method: PresignMethod, path: &str, expires: i64) -> String {
+        hex::encode(self.mac(method, path, expires).finalize().into_bytes())
+    }
+
+    /// Checks a presigned request at time `now` (Unix seconds).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PresignError::InvalidSignature`] if the signature does not
+    /// match, or [`PresignError::Expired`] if it does but `expires` has passed.
+    pub fn verify(
+        &self,
+        method: PresignMethod,
+        path: &str,
+        expires: i64,
+        signature: &str,
+        now: i64,
+    ) -> Result<(), PresignError> {
+        let expected = self.sign(method, path, expires);
+        let valid = signature.len() == expected.len()
+            && bool::from(signature.as_bytes().ct_eq(expected.as_bytes()));
+        if !valid {
+            return Err(PresignError::InvalidSignature);
+        }
+        if now > expires {
+            return Err(PresignError::Expired);
+        }
+        Ok(())
+    }
+
+    fn mac(&self, method: PresignMethod, path: &str, expires: i64) -> HmacSha256 {
+        // HMAC accepts keys of any length
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(format!("{}\n{path}\n{expires}", method.as_str()).as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = Presigner::new("secret");
+        let signature = signer.sign(PresignMethod::Get, "images/logo.png", 1000);
+
+        assert_eq!(
+            signer.verify(PresignMethod::Get, "images/logo.png", 1000, &signature, 999),
+            Ok(())
+        );
+        assert_eq!(
+            signer.verify(
+                PresignMethod::Get,
+                "images/logo.png",
+                1000,
+                &signature,
+                1001
+            ),
+            Err(PresignError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_signature_binds_method_path_and_expiry() {
+        let signer = Presigner::new("secret");
+        let signature = signer.sign(PresignMethod::Get, "a.txt", 1000);
+        let invalid = Err(PresignError::InvalidSignature);
+
+        assert_eq!(
+            signer.verify(PresignMethod::Put, "a.txt", 1000, &signature, 0),
+            invalid
+        );
+        assert_eq!(
+            signer.verify(PresignMethod::Get, "b.txt", 1000, &signature, 0),
+            invalid
+        );
+        assert_eq!(
+            signer.verify(PresignMethod::Get, "a.txt", 2000, &signature, 0),
+            invalid
+        );
+        assert_eq!(
+            Presigner::new("other").verify(PresignMethod::Get, "a.txt", 1000, &signature, 0),
+            invalid
+        );
+    }
+}
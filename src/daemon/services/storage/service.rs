@@ -3,12 +3,24 @@
 //! Provides a convenient API that wraps any `StorageBackend` implementation.
 
 use super::backend::StorageBackend;
+use super::conditions::{PreconditionFailed, Preconditions};
+use super::events::{EVENT_CAPACITY, StorageEvent, StorageWatch};
 use super::filesystem::FilesystemBackend;
+use super::lifecycle::{Lifecycle, LifecycleRule};
 use super::memory::MemoryStorageBackend;
-use super::types::ObjectMeta;
+use super::tenant::{Namespace, TENANTS_PREFIX, TenantQuota, TenantQuotas, TenantUsage};
+use super::types::{ObjectMeta, ObjectStream};
+use crate::daemon::services::sql::validate_tenant_id;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use futures::StreamExt;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{Mutex, OwnedMutexGuard, broadcast};
 
 /// High-level object storage service interface.
 ///
@@ -39,6 +51,46 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct StorageService {
     backend: Arc<dyn StorageBackend>,
+    lifecycle: Arc<Lifecycle>,
+    writes: Arc<PathLocks>,
+    events: broadcast::Sender<StorageEvent>,
+    /// Set on [`tenant`](Self::tenant) views.
+    namespace: Option<Arc<Namespace>>,
+    quotas: Arc<TenantQuotas>,
+    /// Serializes quota-checked writes per tenant.
+    quota_locks: Arc<PathLocks>,
+}
+
+/// Serializes writes to the same path, so a conditional write sees the
+/// object it replaces.
+#[derive(Default)]
+struct PathLocks(DashMap<String, Arc<Mutex<()>>>);
+
+impl PathLocks {
+    async fn lock(&self, path: &str) -> PathGuard<'_> {
+        let lock = Arc::clone(self.0.entry(path.to_string()).or_default().value());
+        PathGuard {
+            locks: self,
+            path: path.to_string(),
+            guard: Some(lock.lock_owned().await),
+        }
+    }
+}
+
+struct PathGuard<'a> {
+    locks: &'a PathLocks,
+    path: String,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for PathGuard<'_> {
+    fn drop(&mut self) {
+        self.guard.take();
+        // Forget the lock once no other writer holds or waits for it
+        self.locks
+            .0
+            .remove_if(&self.path, |_, lock| Arc::strong_count(lock) == 1);
+    }
 }
 
 impl StorageService {
@@ -51,9 +103,7 @@ impl StorageService {
     /// Returns an error if the storage directory cannot be created or opened.
     pub fn file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let backend = FilesystemBackend::open(path)?;
-        Ok(Self {
-            backend: Arc::new(backend),
-        })
+        Ok(Self::from_arc(Arc::new(backend)))
     }
 
     /// Creates a new `StorageService` backed by an in-memory store.
@@ -61,27 +111,189 @@ impl StorageService {
     /// Ideal for testing, development, and embedded applications.
     /// All data is lost when the process exits.
     pub fn memory() -> Self {
-        Self {
-            backend: Arc::new(MemoryStorageBackend::new()),
-        }
+        Self::from_arc(Arc::new(MemoryStorageBackend::new()))
     }
 
     /// Creates a new `StorageService` with a custom backend.
     ///
     /// Use this to integrate custom storage backends like S3, etc.
     pub fn custom<B: StorageBackend>(backend: B) -> Self {
-        Self {
-            backend: Arc::new(backend),
-        }
+        Self::from_arc(Arc::new(backend))
     }
 
     /// Creates a new `StorageService` from a boxed backend.
     ///
     /// Useful when working with trait objects directly.
     pub fn from_boxed(backend: Box<dyn StorageBackend>) -> Self {
+        Self::from_arc(Arc::from(backend))
+    }
+
+    fn from_arc(backend: Arc<dyn StorageBackend>) -> Self {
         Self {
-            backend: Arc::from(backend),
+            backend,
+            lifecycle: Arc::default(),
+            writes: Arc::default(),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            namespace: None,
+            quotas: Arc::default(),
+            quota_locks: Arc::default(),
+        }
+    }
+
+    /// Applies lifecycle rules to this service.
+    ///
+    /// Returned metadata carries the expiry from the matching rule; expired
+    /// objects are removed by [`apply_lifecycle`](Self::apply_lifecycle).
+    #[must_use]
+    pub fn with_lifecycle(mut self, rules: Vec<LifecycleRule>) -> Self {
+        self.lifecycle = Arc::new(Lifecycle::new(rules));
+        self
+    }
+
+    /// Whether any lifecycle rules are configured.
+    pub fn has_lifecycle(&self) -> bool {
+        !self.lifecycle.is_empty()
+    }
+
+    /// Sets the quotas enforced by [`tenant`](Self::tenant) views.
+    #[must_use]
+    pub fn with_tenant_quotas(mut self, quotas: TenantQuotas) -> Self {
+        self.quotas = Arc::new(quotas);
+        self
+    }
+
+    /// Returns a view of the store scoped to `tenant_id`'s namespace.
+    ///
+    /// The view shares the backend, lifecycle rules, and subscribers with
+    /// this service. Paths are relative to `tenants/{tenant_id}/`, and
+    /// watchers of the whole store see the full path of tenant objects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tenant ID is invalid.
+    pub fn tenant(&self, tenant_id: &str) -> Result<Self> {
+        validate_tenant_id(tenant_id)?;
+        let quota = self.quotas.get(tenant_id);
+        Ok(Self {
+            namespace: Some(Arc::new(Namespace::new(tenant_id, quota))),
+            ..self.clone()
+        })
+    }
+
+    /// Tenant this view is scoped to, if any.
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.namespace.as_ref().map(|ns| ns.tenant_id.as_str())
+    }
+
+    /// Quota that applies to `tenant_id`.
+    pub fn tenant_quota(&self, tenant_id: &str) -> TenantQuota {
+        self.quotas.get(tenant_id)
+    }
+
+    /// Objects and bytes stored per tenant.
+    ///
+    /// On a [`tenant`](Self::tenant) view, only that tenant is reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if listing fails.
+    pub async fn tenant_usage(&self) -> Result<BTreeMap<String, TenantUsage>> {
+        let prefix = self
+            .namespace
+            .as_ref()
+            .map_or(TENANTS_PREFIX, |ns| ns.prefix.as_str());
+        let mut usage = BTreeMap::<String, TenantUsage>::new();
+        for meta in self.backend.list(Some(prefix)).await? {
+            let Some((tenant_id, _)) = meta
+                .path
+                .strip_prefix(TENANTS_PREFIX)
+                .and_then(|rest| rest.split_once('/'))
+            else {
+                continue;
+            };
+            let entry = usage.entry(tenant_id.to_string()).or_default();
+            entry.objects += 1;
+            entry.bytes += meta.size;
+        }
+        Ok(usage)
+    }
+
+    /// Maps a caller's path into the namespace.
+    fn full_path<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        match &self.namespace {
+            Some(ns) => Cow::Owned(format!("{}{path}", ns.prefix)),
+            None => Cow::Borrowed(path),
+        }
+    }
+
+    /// Annotates lifecycle expiry and makes the path relative to the
+    /// namespace. Lifecycle rules match full paths.
+    fn present(&self, meta: &mut ObjectMeta) {
+        self.lifecycle.annotate(meta);
+        if let Some(ns) = &self.namespace
+            && let Some(relative) = meta.path.strip_prefix(&ns.prefix)
+        {
+            meta.path = relative.to_string();
+        }
+    }
+
+    /// Checks a write to `path` (a full path) against the namespace quota.
+    ///
+    /// Returns `None` when no quota applies. Otherwise the reservation holds
+    /// the tenant's quota lock until it is dropped.
+    async fn reserve(&self, path: &str) -> Result<Option<Reservation<'_>>> {
+        let Some(ns) = self
+            .namespace
+            .as_deref()
+            .filter(|ns| !ns.quota.is_unlimited())
+        else {
+            return Ok(None);
+        };
+        let guard = self.quota_locks.lock(&ns.tenant_id).await;
+        let objects = self.backend.list(Some(&ns.prefix)).await?;
+        let used: u64 = objects.iter().map(|meta| meta.size).sum();
+        let replaced = objects.iter().find(|meta| meta.path == path);
+        if let Some(max) = ns.quota.max_objects
+            && replaced.is_none()
+            && objects.len() as u64 >= max
+        {
+            return Err(ns.exceeded(format!("object limit of {max} reached")).into());
         }
+        let remaining = ns
+            .quota
+            .max_bytes
+            .map(|max| max.saturating_sub(used - replaced.map_or(0, |meta| meta.size)));
+        Ok(Some(Reservation {
+            ns,
+            remaining,
+            _guard: guard,
+        }))
+    }
+
+    /// Subscribes to changes of objects under `prefix` (see
+    /// [`StorageWatch`]).
+    ///
+    /// On a [`tenant`](Self::tenant) view, only that tenant's objects are
+    /// reported, with paths relative to its namespace.
+    pub fn watch(&self, prefix: &str) -> StorageWatch {
+        let namespace = self.namespace.as_ref().map_or("", |ns| ns.prefix.as_str());
+        StorageWatch::new(self.events.subscribe(), namespace, prefix)
+    }
+
+    /// Whether the object at `path` exists, if anyone needs to know.
+    ///
+    /// Distinguishing creates from overwrites costs a metadata lookup, so it
+    /// is skipped without subscribers.
+    async fn existed_for_event(&self, path: &str) -> Result<bool> {
+        if self.events.receiver_count() == 0 {
+            return Ok(false);
+        }
+        Ok(self.backend.head(path).await?.is_some())
+    }
+
+    fn publish(&self, event: StorageEvent) {
+        // Fails only without subscribers
+        let _ = self.events.send(event);
     }
 
     /// Stores an object with metadata.
@@ -95,7 +307,16 @@ impl StorageService {
         data: &[u8],
         content_type: Option<&str>,
     ) -> Result<ObjectMeta> {
-        self.backend.put(path, data, content_type).await
+        let path = self.full_path(path);
+        let _write = self.writes.lock(&path).await;
+        if let Some(reservation) = self.reserve(&path).await? {
+            reservation.check(data.len() as u64)?;
+        }
+        let existed = self.existed_for_event(&path).await?;
+        let mut meta = self.backend.put(&path, data, content_type).await?;
+        self.publish(StorageEvent::written(&meta, existed));
+        self.present(&mut meta);
+        Ok(meta)
     }
 
     /// Retrieves an object and its metadata.
@@ -106,7 +327,102 @@ impl StorageService {
     ///
     /// Returns an error if the path is invalid or retrieval fails.
     pub async fn get_object(&self, path: &str) -> Result<Option<(Vec<u8>, ObjectMeta)>> {
-        self.backend.get(path).await
+        let mut object = self.backend.get(&self.full_path(path)).await?;
+        if let Some((_, meta)) = &mut object {
+            self.present(meta);
+        }
+        Ok(object)
+    }
+
+    /// Stores an object from a stream of chunks without buffering it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is invalid, the stream fails, or storage
+    /// fails.
+    pub async fn put_object_stream(
+        &self,
+        path: &str,
+        data: ObjectStream,
+        content_type: Option<&str>,
+    ) -> Result<ObjectMeta> {
+        self.put_object_stream_if(path, data, content_type, &Preconditions::default())
+            .await
+    }
+
+    /// Stores a streamed object if `conditions` hold for the current version.
+    ///
+    /// The check and the write happen under a per-path lock, so an
+    /// `If-Match` write cannot overwrite a version it has not seen.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PreconditionFailed`] error if the conditions do not hold,
+    /// or an error if the path is invalid, the stream fails, or storage fails.
+    pub async fn put_object_stream_if(
+        &self,
+        path: &str,
+        data: ObjectStream,
+        content_type: Option<&str>,
+        conditions: &Preconditions,
+    ) -> Result<ObjectMeta> {
+        let full_path = self.full_path(path);
+        let _write = self.writes.lock(&full_path).await;
+        let existed = if conditions.is_empty() {
+            self.existed_for_event(&full_path).await?
+        } else {
+            let current = self.backend.head(&full_path).await?;
+            if !conditions.allows_write(current.as_ref()) {
+                return Err(PreconditionFailed {
+                    path: path.to_string(),
+                }
+                .into());
+            }
+            current.is_some()
+        };
+        let reservation = self.reserve(&full_path).await?;
+        let exceeded = Arc::new(AtomicBool::new(false));
+        let data = match reservation.as_ref().and_then(|r| r.remaining) {
+            Some(remaining) => limit_stream(data, remaining, Arc::clone(&exceeded)),
+            None => data,
+        };
+        let mut meta = match self
+            .backend
+            .put_stream(&full_path, data, content_type)
+            .await
+        {
+            Ok(meta) => meta,
+            Err(e) => {
+                return match reservation {
+                    Some(reservation) if exceeded.load(Ordering::Relaxed) => {
+                        Err(reservation.over_bytes().into())
+                    },
+                    _ => Err(e),
+                };
+            },
+        };
+        drop(reservation);
+        self.publish(StorageEvent::written(&meta, existed));
+        self.present(&mut meta);
+        Ok(meta)
+    }
+
+    /// Retrieves an object as a stream of chunks, with its metadata.
+    ///
+    /// Returns `Ok(None)` if the object doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is invalid or the object cannot be opened.
+    pub async fn get_object_stream(
+        &self,
+        path: &str,
+    ) -> Result<Option<(ObjectStream, ObjectMeta)>> {
+        let mut object = self.backend.get_stream(&self.full_path(path)).await?;
+        if let Some((_, meta)) = &mut object {
+            self.present(meta);
+        }
+        Ok(object)
     }
 
     /// Deletes an object.
@@ -117,7 +433,13 @@ impl StorageService {
     ///
     /// Returns an error if the path is invalid or deletion fails.
     pub async fn delete_object(&self, path: &str) -> Result<bool> {
-        self.backend.delete(path).await
+        let path = self.full_path(path);
+        let _write = self.writes.lock(&path).await;
+        let deleted = self.backend.delete(&path).await?;
+        if deleted {
+            self.publish(StorageEvent::deleted(&path));
+        }
+        Ok(deleted)
     }
 
     /// Retrieves object metadata without downloading the object.
@@ -128,7 +450,11 @@ impl StorageService {
     ///
     /// Returns an error if the path is invalid or metadata cannot be read.
     pub async fn head_object(&self, path: &str) -> Result<Option<ObjectMeta>> {
-        self.backend.head(path).await
+        let mut meta = self.backend.head(&self.full_path(path)).await?;
+        if let Some(meta) = &mut meta {
+            self.present(meta);
+        }
+        Ok(meta)
     }
 
     /// Lists all objects, optionally filtered by prefix.
@@ -137,6 +463,303 @@ impl StorageService {
     ///
     /// Returns an error if listing fails.
     pub async fn list_objects(&self, prefix: Option<&str>) -> Result<Vec<ObjectMeta>> {
-        self.backend.list(prefix).await
+        let prefix = match &self.namespace {
+            Some(ns) => Some(Cow::Owned(format!("{}{}", ns.prefix, prefix.unwrap_or("")))),
+            None => prefix.map(Cow::Borrowed),
+        };
+        let mut objects = self.backend.list(prefix.as_deref()).await?;
+        for meta in &mut objects {
+            self.present(meta);
+        }
+        Ok(objects)
+    }
+
+    /// Lists objects whose lifecycle expiry is at or before `now`.
+    ///
+    /// This is what [`apply_lifecycle`](Self::apply_lifecycle) would delete,
+    /// so it doubles as a dry run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if listing fails.
+    pub async fn expired_objects(&self, now: DateTime<Utc>) -> Result<Vec<ObjectMeta>> {
+        if self.lifecycle.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut objects = self.list_objects(None).await?;
+        objects.retain(|meta| meta.expires_at.is_some_and(|expires_at| expires_at <= now));
+        Ok(objects)
+    }
+
+    /// Deletes objects whose lifecycle expiry is at or before `now`.
+    ///
+    /// Returns the deleted objects. An object that fails to delete is logged
+    /// and retried on the next run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if listing fails.
+    pub async fn apply_lifecycle(&self, now: DateTime<Utc>) -> Result<Vec<ObjectMeta>> {
+        let mut deleted = Vec::new();
+        for meta in self.expired_objects(now).await? {
+            let path = self.full_path(&meta.path);
+            match self.backend.delete(&path).await {
+                Ok(true) => {
+                    self.publish(StorageEvent::deleted(&path));
+                    deleted.push(meta);
+                },
+                Ok(false) => {},
+                Err(e) => {
+                    tracing::warn!(path = %meta.path, error = %e, "Failed to delete expired object");
+                },
+            }
+        }
+        Ok(deleted)
+    }
+}
+
+/// A quota-checked write in progress.
+struct Reservation<'a> {
+    ns: &'a Namespace,
+    /// Bytes the written object may take, if bytes are limited.
+    remaining: Option<u64>,
+    _guard: PathGuard<'a>,
+}
+
+impl Reservation<'_> {
+    fn check(&self, size: u64) -> Result<()> {
+        match self.remaining {
+            Some(remaining) if size > remaining => Err(self.over_bytes().into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn over_bytes(&self) -> super::StorageQuotaExceeded {
+        let max = self.ns.quota.max_bytes.unwrap_or_default();
+        self.ns.exceeded(format!("byte limit of {max} reached"))
+    }
+}
+
+/// Fails `data` once it yields more than `max` bytes, setting `exceeded`.
+fn limit_stream(data: ObjectStream, max: u64, exceeded: Arc<AtomicBool>) -> ObjectStream {
+    let mut seen = 0u64;
+    Box::pin(data.map(move |chunk| {
+        let chunk = chunk?;
+        seen += chunk.len() as u64;
+        if seen > max {
+            exceeded.store(true, Ordering::Relaxed);
+            return Err(std::io::Error::other("storage quota exceeded"));
+        }
+        Ok(chunk)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::services::storage::StorageEventKind;
+    use bytes::Bytes;
+    use chrono::Duration;
+
+    fn body(data: &'static [u8]) -> ObjectStream {
+        Box::pin(futures::stream::once(async move { Ok(Bytes::from(data)) }))
+    }
+
+    #[tokio::test]
+    async fn test_conditional_put() {
+        let storage = StorageService::memory();
+        let v1 = storage.put_object("a.txt", b"one", None).await.unwrap();
+        let etag = v1.etag.clone().unwrap();
+        assert_eq!(etag, blake3::hash(b"one").to_hex().as_str());
+
+        let if_match = Preconditions::from_headers(Some(&format!("\"{etag}\"")), None);
+        let v2 = storage
+            .put_object_stream_if("a.txt", body(b"two"), None, &if_match)
+            .await
+            .unwrap();
+        assert_ne!(v2.etag, v1.etag);
+
+        // A second writer holding the old ETag loses
+        let err = storage
+            .put_object_stream_if("a.txt", body(b"three"), None, &if_match)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<PreconditionFailed>().is_some());
+        let (data, _) = storage.get_object("a.txt").await.unwrap().unwrap();
+        assert_eq!(data, b"two");
+        assert!(storage.writes.0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_change_events() {
+        let storage = StorageService::memory();
+        let mut events = storage.watch("");
+
+        storage.put_object("a.txt", b"one", None).await.unwrap();
+        storage
+            .put_object_stream("a.txt", body(b"two"), None)
+            .await
+            .unwrap();
+        assert!(storage.delete_object("a.txt").await.unwrap());
+        assert!(!storage.delete_object("a.txt").await.unwrap());
+        storage.put_object("b.txt", b"three", None).await.unwrap();
+
+        let created = events.recv().await.unwrap();
+        assert_eq!(created.kind, StorageEventKind::Created);
+        assert_eq!(created.size, 3);
+        let updated = events.recv().await.unwrap();
+        assert_eq!(updated.kind, StorageEventKind::Updated);
+        assert_eq!(
+            updated.etag.as_deref(),
+            Some(blake3::hash(b"two").to_hex().as_str())
+        );
+        let deleted = events.recv().await.unwrap();
+        assert_eq!(deleted.kind, StorageEventKind::Deleted);
+        assert_eq!(deleted.path, "a.txt");
+        // Deleting a missing object is not an event
+        assert_eq!(events.recv().await.unwrap().path, "b.txt");
+    }
+
+    #[tokio::test]
+    async fn test_watch_prefixes_and_tenants() {
+        let storage = StorageService::memory();
+        let acme = storage.tenant("acme").unwrap();
+        let mut all = storage.watch("");
+        let mut acme_images = acme.watch("images/");
+
+        storage
+            .put_object("images/shared.png", b"1", None)
+            .await
+            .unwrap();
+        storage
+            .tenant("other")
+            .unwrap()
+            .put_object("images/other.png", b"2", None)
+            .await
+            .unwrap();
+        acme.put_object("docs/a.txt", b"3", None).await.unwrap();
+        acme.put_object("images/logo.png", b"4", None)
+            .await
+            .unwrap();
+
+        // Tenant watchers only see their namespace, with relative paths
+        assert_eq!(acme_images.recv().await.unwrap().path, "images/logo.png");
+
+        let mut paths = Vec::new();
+        for _ in 0..4 {
+            paths.push(all.recv().await.unwrap().path);
+        }
+        assert_eq!(
+            paths,
+            [
+                "images/shared.png",
+                "tenants/other/images/other.png",
+                "tenants/acme/docs/a.txt",
+                "tenants/acme/images/logo.png",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tenant_namespaces_and_quotas() {
+        let quotas = TenantQuotas {
+            default: TenantQuota {
+                max_bytes: Some(10),
+                max_objects: Some(2),
+            },
+            tenants: std::collections::HashMap::from([("big".to_string(), TenantQuota::default())]),
+        };
+        let storage = StorageService::memory().with_tenant_quotas(quotas);
+        let acme = storage.tenant("acme").unwrap();
+        let other = storage.tenant("other").unwrap();
+        assert!(storage.tenant("../acme").is_err());
+
+        let meta = acme.put_object("a.txt", b"12345", None).await.unwrap();
+        assert_eq!(meta.path, "a.txt");
+        assert!(other.get_object("a.txt").await.unwrap().is_none());
+        assert!(
+            storage
+                .head_object("tenants/acme/a.txt")
+                .await
+                .unwrap()
+                .is_some()
+        );
+        let listed = acme.list_objects(None).await.unwrap();
+        assert_eq!(listed[0].path, "a.txt");
+
+        // Overwrites only count the difference
+        acme.put_object("a.txt", b"1234567890", None).await.unwrap();
+        let err = acme
+            .put_object_stream("b.txt", body(b"x"), None)
+            .await
+            .unwrap_err();
+        assert!(
+            err.downcast_ref::<super::super::StorageQuotaExceeded>()
+                .is_some()
+        );
+        assert!(acme.head_object("b.txt").await.unwrap().is_none());
+
+        acme.put_object("a.txt", b"1", None).await.unwrap();
+        acme.put_object("b.txt", b"2", None).await.unwrap();
+        let err = acme.put_object("c.txt", b"3", None).await.unwrap_err();
+        assert!(err.to_string().contains("object limit of 2"));
+
+        // Per-tenant overrides replace the default
+        let big = storage.tenant("big").unwrap();
+        big.put_object("large.bin", &[0; 64], None).await.unwrap();
+
+        let usage = storage.tenant_usage().await.unwrap();
+        assert_eq!(
+            usage["acme"],
+            TenantUsage {
+                objects: 2,
+                bytes: 2
+            }
+        );
+        assert_eq!(usage["big"].bytes, 64);
+        assert_eq!(acme.tenant_usage().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_expiry_and_dry_run() {
+        let storage = StorageService::memory().with_lifecycle(vec![LifecycleRule {
+            prefix: "tmp/".to_string(),
+            expire_after_hours: 24,
+        }]);
+        let meta = storage.put_object("tmp/a.txt", b"a", None).await.unwrap();
+        storage
+            .put_object("images/b.png", b"b", None)
+            .await
+            .unwrap();
+
+        let expires_at = meta.expires_at.unwrap();
+        assert_eq!(expires_at, meta.modified_at + Duration::hours(24));
+        let listed = storage.list_objects(None).await.unwrap();
+        assert!(
+            listed
+                .iter()
+                .any(|m| m.path == "images/b.png" && m.expires_at.is_none())
+        );
+
+        // Nothing is due yet
+        assert!(
+            storage
+                .apply_lifecycle(Utc::now())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let later = expires_at + Duration::seconds(1);
+        let due = storage.expired_objects(later).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].path, "tmp/a.txt");
+        // Dry run leaves the object in place
+        assert!(storage.head_object("tmp/a.txt").await.unwrap().is_some());
+
+        let deleted = storage.apply_lifecycle(later).await.unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert!(storage.head_object("tmp/a.txt").await.unwrap().is_none());
+        assert!(storage.head_object("images/b.png").await.unwrap().is_some());
     }
 }
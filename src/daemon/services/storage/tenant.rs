@@ -0,0 +1,96 @@
+//! Tenant storage namespaces and quotas.
+//!
+//! [`StorageService::tenant`](super::StorageService::tenant) returns a view
+//! of the store rooted at `tenants/{tenant_id}/`. Paths passed to and
+//! returned from the view are relative to that prefix, so a tenant cannot
+//! name another tenant's objects. Writes through the view are checked
+//! against the tenant's [`TenantQuota`].
+//!
+//! Usage is computed from the object listing under the tenant prefix, and a
+//! tenant's writes are serialized while a quota applies, so concurrent
+//! uploads cannot overshoot it together.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Prefix under which tenant namespaces live.
+pub const TENANTS_PREFIX: &str = "tenants/";
+
+/// Limits on one tenant's objects. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantQuota {
+    /// Total bytes across the tenant's objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+    /// Number of objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_objects: Option<u64>,
+}
+
+impl TenantQuota {
+    /// Whether the quota limits anything.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_bytes.is_none() && self.max_objects.is_none()
+    }
+}
+
+/// Quotas for all tenants: a default plus per-tenant overrides.
+#[derive(Debug, Clone, Default)]
+pub struct TenantQuotas {
+    /// Quota for tenants without an override.
+    pub default: TenantQuota,
+    /// Quotas for specific tenants.
+    pub tenants: HashMap<String, TenantQuota>,
+}
+
+impl TenantQuotas {
+    /// Quota that applies to `tenant_id`.
+    pub fn get(&self, tenant_id: &str) -> TenantQuota {
+        self.tenants.get(tenant_id).copied().unwrap_or(self.default)
+    }
+}
+
+/// Objects and bytes stored by one tenant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TenantUsage {
+    /// Number of objects.
+    pub objects: u64,
+    /// Total size in bytes.
+    pub bytes: u64,
+}
+
+/// A write was rejected because it would exceed the tenant's quota.
+#[derive(Debug, thiserror::Error)]
+#[error("Storage quota exceeded for tenant '{tenant_id}': {reason}")]
+pub struct StorageQuotaExceeded {
+    /// Tenant whose quota was hit.
+    pub tenant_id: String,
+    /// Which limit was hit.
+    pub reason: String,
+}
+
+/// The tenant a [`StorageService`](super::StorageService) view is scoped to.
+#[derive(Debug, Clone)]
+pub(crate) struct Namespace {
+    pub(crate) tenant_id: String,
+    /// `tenants/{tenant_id}/`
+    pub(crate) prefix: String,
+    pub(crate) quota: TenantQuota,
+}
+
+impl Namespace {
+    pub(crate) fn new(tenant_id: &str, quota: TenantQuota) -> Self {
+        Self {
+            tenant_id: tenant_id.to_string(),
+            prefix: format!("{TENANTS_PREFIX}{tenant_id}/"),
+            quota,
+        }
+    }
+
+    pub(crate) fn exceeded(&self, reason: String) -> StorageQuotaExceeded {
+        StorageQuotaExceeded {
+            tenant_id: self.tenant_id.clone(),
+            reason,
+        }
+    }
+}
@@ -1,13 +1,22 @@
 //! Types and constants for the storage service.
 
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
+use futures::Stream;
 use redb::TableDefinition;
 use serde::{Deserialize, Serialize};
+use std::pin::Pin;
 
 /// Table for object metadata storage
 pub(crate) const OBJECTS_TABLE: TableDefinition<'static, &'static str, &'static [u8]> =
     TableDefinition::new("objects");
 
+/// Directory under the storage root holding in-progress streamed uploads
+pub(crate) const UPLOADS_DIR: &str = ".uploads";
+
+/// Stream of object data chunks
+pub type ObjectStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>;
+
 /// Metadata for a stored object
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ObjectMeta {
@@ -21,4 +30,18 @@ pub struct ObjectMeta {
     pub created_at: DateTime<Utc>,
     /// Timestamp when object was last modified
     pub modified_at: DateTime<Utc>,
+    /// When a lifecycle rule will delete the object, if one applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// BLAKE3 hash of the content (hex), if known
+    ///
+    /// Objects stored before hashing was added, or found on disk without
+    /// metadata, have no ETag until they are written again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+}
+
+/// ETag of object content: its BLAKE3 hash in hex.
+pub(crate) fn content_etag(data: &[u8]) -> String {
+    blake3::hash(data).to_hex().to_string()
 }
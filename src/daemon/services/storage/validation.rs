@@ -6,6 +6,8 @@
 use anyhow::{Result, bail};
 use std::path::{Component, Path, PathBuf};
 
+use super::types::UPLOADS_DIR;
+
 /// Validates and normalizes an object path to prevent directory traversal.
 ///
 /// # Security
@@ -64,7 +66,12 @@ pub(crate) fn validate_path(path: &str) -> Result<PathBuf> {
 }
 
 /// Returns the filesystem path for an object given a base directory and object path.
+///
+/// Paths inside the reserved uploads directory are rejected.
 pub(crate) fn object_path(base_dir: &Path, path: &str) -> Result<PathBuf> {
     let normalized = validate_path(path)?;
+    if normalized.starts_with(UPLOADS_DIR) {
+        bail!("Object path uses reserved directory '{UPLOADS_DIR}': {path}");
+    }
     Ok(base_dir.join(normalized))
 }
@@ -5,8 +5,10 @@
 //! - `is_daemon_running()` - Check if daemon is healthy
 
 use anyhow::{Context, Result};
+use std::path::Path;
 use std::time::Duration;
 
+use super::http::client::DaemonClient;
 use super::paths::{get_daemon_pid_path, get_logs_dir, get_state_path};
 
 /// Default daemon port.
@@ -14,12 +16,9 @@ pub const DAEMON_PORT: u16 = 9919;
 
 /// Check if daemon is running by trying to connect to health endpoint.
 pub async fn is_daemon_running(port: u16) -> bool {
-    reqwest::Client::new()
-        .get(format!("http://127.0.0.1:{port}/health"))
-        .timeout(Duration::from_millis(500))
-        .send()
+    DaemonClient::local(port)
+        .is_healthy(Duration::from_millis(500))
         .await
-        .is_ok()
 }
 
 /// Ensure daemon is running, starting it if necessary.
@@ -48,12 +47,7 @@ pub async fn ensure_daemon_running_with_message(print_message: bool) -> Result<(
 
     let log_file = std::fs::File::create(&daemon_log)?;
 
-    let child = std::process::Command::new(&mik_exe)
-        .args(["daemon", "--port", &DAEMON_PORT.to_string()])
-        .stdout(log_file.try_clone()?)
-        .stderr(log_file)
-        .spawn()
-        .context("Failed to start daemon")?;
+    let child = spawn_daemon(&mik_exe, log_file)?;
 
     // Save daemon PID
     let daemon_pid_path = get_daemon_pid_path()?;
@@ -73,6 +67,23 @@ pub async fn ensure_daemon_running_with_message(print_message: bool) -> Result<(
     anyhow::bail!("Daemon failed to start within 5 seconds")
 }
 
+/// Spawn the daemon in the background, logging to `log_file`.
+fn spawn_daemon(mik_exe: &Path, log_file: std::fs::File) -> Result<std::process::Child> {
+    let mut command = std::process::Command::new(mik_exe);
+    command
+        .args(["daemon", "--port", &DAEMON_PORT.to_string()])
+        .stdout(log_file.try_clone()?)
+        .stderr(log_file);
+
+    // Detach from the console, or closing the terminal stops the daemon
+    #[cfg(windows)]
+    let child = crate::daemon::process::spawn_detached(&mut command);
+    #[cfg(not(windows))]
+    let child = command.spawn();
+
+    child.context("Failed to start daemon")
+}
+
 /// Ensure daemon is running for services (prints services message).
 ///
 /// Used by `mik dev` to start daemon with services information.
@@ -90,12 +101,7 @@ pub async fn ensure_daemon_running_for_services() -> Result<()> {
 
     let log_file = std::fs::File::create(&daemon_log)?;
 
-    let child = std::process::Command::new(&mik_exe)
-        .args(["daemon", "--port", &DAEMON_PORT.to_string()])
-        .stdout(log_file.try_clone()?)
-        .stderr(log_file)
-        .spawn()
-        .context("Failed to start daemon")?;
+    let child = spawn_daemon(&mik_exe, log_file)?;
 
     // Save daemon PID
     let daemon_pid_path = get_daemon_pid_path()?;
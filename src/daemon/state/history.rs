@@ -78,7 +78,7 @@ impl StateStore {
         }
 
         // Sort by started_at descending (most recent first)
-        executions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        executions.sort_by_key(|e| std::cmp::Reverse(e.started_at));
 
         // Apply limit
         executions.truncate(limit);
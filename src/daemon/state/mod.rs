@@ -22,7 +22,7 @@ mod history;
 mod instances;
 mod types;
 
-pub use types::{Instance, Status};
+pub use types::{Instance, RestartEvent, RestartReason, Status};
 
 use anyhow::{Context, Result};
 use redb::{Database, TableDefinition};
@@ -129,6 +129,9 @@ mod tests {
             auto_restart: false,
             restart_count: 0,
             last_restart_at: None,
+            memory_restarts: 0,
+            restart: None,
+            restart_history: Vec::new(),
         }
     }
 
@@ -298,6 +301,7 @@ mod tests {
                 Some("Test error".to_string())
             },
             manual: false,
+            output: None,
         }
     }
 
@@ -15,7 +15,7 @@ use tempfile::TempDir;
 use chrono::{DateTime, TimeZone, Utc};
 
 use super::{Instance, StateStore, Status};
-use crate::daemon::cron::{JobExecution, ScheduleConfig};
+use crate::daemon::cron::{JobExecution, JobType, OverlapPolicy, ScheduleConfig};
 
 // ============================================================================
 // Arbitrary Implementations for Test Data Generation
@@ -109,6 +109,9 @@ fn instance_strategy() -> impl Strategy<Value = Instance> {
                     auto_restart,
                     restart_count,
                     last_restart_at,
+                    memory_restarts: 0,
+                    restart: None,
+                    restart_history: Vec::new(),
                 }
             },
         )
@@ -190,6 +193,7 @@ fn schedule_config_strategy() -> impl Strategy<Value = ScheduleConfig> {
                     name,
                     module,
                     cron,
+                    timezone: None,
                     method,
                     path,
                     enabled,
@@ -197,6 +201,10 @@ fn schedule_config_strategy() -> impl Strategy<Value = ScheduleConfig> {
                     body,
                     headers,
                     health_path,
+                    backup: None,
+                    sql_backup: None,
+                    overlap: OverlapPolicy::Allow,
+                    jitter_secs: None,
                 }
             },
         )
@@ -230,6 +238,7 @@ fn job_execution_strategy() -> impl Strategy<Value = JobExecution> {
                     success,
                     error,
                     manual,
+                    output: None,
                 }
             },
         )
@@ -546,6 +555,9 @@ proptest! {
                             auto_restart: false,
                             restart_count: 0,
                             last_restart_at: None,
+                            memory_restarts: 0,
+                            restart: None,
+                            restart_history: Vec::new(),
                         };
                         store.save_instance(&instance).unwrap();
                     }
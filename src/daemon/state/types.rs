@@ -3,11 +3,17 @@
 //! Contains the core data structures used by the state store:
 //! - `Status` - Runtime status of WASM instances
 //! - `Instance` - WASM instance metadata
+//! - `RestartEvent` - An exit of an instance process, for restart history
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::daemon::process::{InstanceExit, RestartConfig, RestartPolicy};
+
+/// Restart events kept per instance.
+pub const MAX_RESTART_HISTORY: usize = 20;
+
 /// Runtime status of a WASM instance
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Status {
@@ -45,6 +51,41 @@ pub struct Instance {
     /// Timestamp of last auto-restart (for backoff calculation)
     #[serde(default)]
     pub last_restart_at: Option<DateTime<Utc>>,
+    /// Number of restarts triggered by exceeding the memory ceiling
+    #[serde(default)]
+    pub memory_restarts: u32,
+    /// Restart policy. Instances saved before policies existed have none and
+    /// follow `auto_restart` (see [`Instance::restart_config`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<RestartConfig>,
+    /// Recent exits and restarts, oldest first (at most [`MAX_RESTART_HISTORY`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restart_history: Vec<RestartEvent>,
+}
+
+/// Why an instance went down.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartReason {
+    /// The process exited on its own.
+    Exited,
+    /// The daemon restarted it for exceeding the memory ceiling.
+    MemoryCeiling,
+}
+
+/// An exit of an instance process and whether the supervisor restarted it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RestartEvent {
+    /// When the exit was noticed.
+    pub at: DateTime<Utc>,
+    pub reason: RestartReason,
+    /// How the process exited, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<InstanceExit>,
+    /// When it was restarted. `None` while waiting out the backoff, or if
+    /// the policy or retry limit kept it down.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restarted_at: Option<DateTime<Utc>>,
 }
 
 impl Instance {
@@ -64,6 +105,32 @@ impl Instance {
             auto_restart: false,
             restart_count: 0,
             last_restart_at: None,
+            memory_restarts: 0,
+            restart: None,
+            restart_history: Vec::new(),
         }
     }
+
+    /// Restart policy, with `auto_restart` standing in for instances saved
+    /// without one (it restarted on any exit).
+    pub fn restart_config(&self) -> RestartConfig {
+        self.restart.unwrap_or_else(|| {
+            RestartConfig::with_policy(if self.auto_restart {
+                RestartPolicy::Always
+            } else {
+                RestartPolicy::Never
+            })
+        })
+    }
+
+    /// Appends to the restart history, dropping the oldest events beyond
+    /// [`MAX_RESTART_HISTORY`].
+    pub fn record_restart_event(&mut self, event: RestartEvent) {
+        self.restart_history.push(event);
+        let excess = self
+            .restart_history
+            .len()
+            .saturating_sub(MAX_RESTART_HISTORY);
+        self.restart_history.drain(..excess);
+    }
 }
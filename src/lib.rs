@@ -3,7 +3,9 @@
 // =============================================================================
 
 // Safety: deny unsafe by default, allow only where documented
-// (wasmtime AOT cache in runtime/mod.rs, Unix setsid in daemon/process.rs)
+// (wasmtime AOT cache in runtime/mod.rs, Unix setsid in daemon/process.rs,
+// Win32 job objects in daemon/process/windows.rs, inherited listener fds in
+// runtime/handoff.rs)
 #![deny(unsafe_code)]
 // Correctness: Must handle all fallible operations
 #![deny(unused_must_use)]
@@ -50,6 +52,9 @@
 //! - [`runtime::Request`] / [`runtime::Response`] - Framework-agnostic HTTP types
 //! - [`runtime::Cluster`] / [`runtime::ClusterBuilder`] - Multi-worker orchestration
 //!
+//! With the `testing` feature, `mik::testing::TestRuntime` wraps a [`runtime::Runtime`]
+//! for component integration tests.
+//!
 //! # Example
 //!
 //! ```
@@ -96,6 +101,13 @@
 #[path = "runtime/mod.rs"]
 pub mod runtime;
 
+/// Test fixture running components on the real executor (`testing` feature).
+///
+/// - [`testing::TestRuntime`] - Runtime over a temporary modules directory
+/// - [`testing::TestResponse`] - Status, header, JSON, and span assertions
+#[cfg(feature = "testing")]
+pub mod testing;
+
 /// Security utilities for input sanitization and path traversal prevention.
 ///
 /// This module provides functions to validate and sanitize untrusted input
@@ -92,6 +92,9 @@ enum Commands {
     ///   mik dev                    # Watch mode on port 3000
     ///   mik dev --port 8080        # Custom port
     ///   mik dev --no-services      # Skip embedded services
+    ///   mik dev --no-build         # Only reload when modules/ changes
+    ///   mik dev --env-file .env.local
+    ///   mik dev --proxy http://localhost:5173  # Frontend dev server for other paths
     Dev {
         /// Port for the HTTP server (default: 3000)
         #[arg(short, long, default_value = "3000")]
@@ -99,6 +102,17 @@ enum Commands {
         /// Skip starting embedded services (KV, SQL, etc.)
         #[arg(long)]
         no_services: bool,
+        /// Don't build the project; only reload when modules/ changes
+        #[arg(long)]
+        no_build: bool,
+        /// Load environment variables from this file, after `.env` and
+        /// `.env.<profile>`
+        #[arg(long, value_name = "PATH")]
+        env_file: Option<String>,
+        /// Forward paths outside /run/ and /static/ to this frontend dev
+        /// server (Vite, Next, ...), WebSockets included for hot reload
+        #[arg(long, value_name = "URL")]
+        proxy: Option<String>,
     },
     // =========================================================================
     // Instance Management
@@ -125,7 +139,8 @@ enum Commands {
     },
     /// List running WASM instances
     ///
-    /// Shows all tracked instances with their status, port, and uptime.
+    /// Shows all tracked instances with their status, port, CPU, memory,
+    /// open file descriptors, and uptime.
     ///
     /// Examples:
     ///   mik ps                     # List all instances
@@ -150,6 +165,7 @@ enum Commands {
     /// Examples:
     ///   mik logs                   # Show logs for "default" instance
     ///   mik logs dev -f            # Follow logs for named instance
+    ///   mik logs dev -f --daemon http://host:9919   # Follow via remote daemon
     Logs {
         /// Instance name (default: "default")
         #[arg(default_value = "default")]
@@ -160,15 +176,23 @@ enum Commands {
         /// Number of lines to show (default: 50)
         #[arg(short = 'n', long, default_value = "50")]
         lines: usize,
+        /// Read logs through a daemon's HTTP API instead of local files
+        /// (default: $MIK_DAEMON_URL; sends $MIK_API_KEY if set)
+        #[arg(long)]
+        daemon: Option<String>,
     },
-    /// Show detailed instance information
+    /// Show detailed instance or component information
     ///
-    /// Displays full details including configuration and loaded modules.
+    /// For an instance, displays full details including configuration and
+    /// loaded modules. For a .wasm file, displays the component's world,
+    /// imports (flagging those mik cannot satisfy), exports, custom sections,
+    /// embedded OpenAPI and size breakdown.
     ///
     /// Examples:
-    ///   mik inspect default        # Inspect default instance
+    ///   mik inspect default                 # Inspect default instance
+    ///   mik inspect modules/api.wasm        # Inspect a component
     Inspect {
-        /// Instance name to inspect
+        /// Instance name, or path to a .wasm component
         name: String,
     },
     /// Remove stopped instances
@@ -191,6 +215,21 @@ enum Commands {
         #[command(subcommand)]
         action: CacheAction,
     },
+    /// Inspect and validate the resolved configuration
+    ///
+    /// Shows the settings `mik run` would use and where each value comes
+    /// from: mik.toml, the active profile, .env files, auto-detection or
+    /// MIK_* overrides. Secret-like values are masked.
+    ///
+    /// Examples:
+    ///   mik config show                      # Effective settings and sources
+    ///   mik config show --profile production # With a profile applied
+    ///   mik config show --toml               # Resolved mik.toml
+    ///   mik config check                     # Validate the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
     /// Generate shell completions
     ///
     /// Outputs shell completion script to stdout.
@@ -220,6 +259,8 @@ enum Commands {
     ///   mik new my-service -y                 # Use defaults (Rust + basic)
     ///   mik new my-service --lang typescript  # `TypeScript` project
     ///   mik new my-api --lang rust --template rest-api
+    ///   mik new my-api --template crud         # SQL-backed API with tests
+    ///   mik new my-api --services kv,sql,cron  # Basic handler + service examples
     ///   mik new my-service --template github:user/repo
     New {
         /// Project name (creates directory with this name)
@@ -227,9 +268,14 @@ enum Commands {
         /// Target language: rust, typescript (ts)
         #[arg(long, short = 'l')]
         lang: Option<String>,
-        /// Template: basic (default), rest-api (Rust only), or github:user/repo
+        /// Template: basic (default), rest-api, crud, worker, cron, auth
+        /// (the last four Rust only), or github:user/repo
         #[arg(long, short = 't')]
         template: Option<String>,
+        /// Services to generate examples and mik.toml grants for (Rust only):
+        /// kv, sql, storage, queue, cron, http
+        #[arg(long, short = 's')]
+        services: Option<String>,
         /// Skip interactive prompts, use defaults
         #[arg(long, short = 'y')]
         yes: bool,
@@ -286,7 +332,8 @@ enum Commands {
     /// Build the component with cargo-component
     ///
     /// Compiles to WASM component targeting `wasm32-wasip2`.
-    /// Supports multiple languages: Rust (default) and `TypeScript`.
+    /// Supports multiple languages: Rust (default), `TypeScript`, Go (TinyGo)
+    /// and Python (componentize-py), configured in [build.go] / [build.python].
     /// Optionally composes all dependencies using WAC.
     /// Extracts OpenAPI schema if handler uses mik-sdk routes! macro.
     ///
@@ -295,7 +342,9 @@ enum Commands {
     ///   mik build --release         # Optimized build
     ///   mik build -rc               # Release + compose dependencies
     ///   mik build --lang ts         # Build `TypeScript` project
+    ///   mik build --lang go         # Build Go project with TinyGo
     ///   mik build --no-schema       # Skip schema extraction
+    ///   mik build --watch           # Rebuild on source changes
     Build {
         /// Build in release mode
         #[arg(short, long)]
@@ -303,12 +352,62 @@ enum Commands {
         /// Compose all dependencies after build
         #[arg(short, long)]
         compose: bool,
-        /// Language override: rust, typescript (ts)
-        #[arg(long, short = 'l', value_parser = ["rust", "rs", "typescript", "ts"])]
+        /// Language override: rust, typescript (ts), go, python (py)
+        #[arg(long, short = 'l', value_parser = ["rust", "rs", "typescript", "ts", "go", "golang", "python", "py"])]
         lang: Option<String>,
         /// Skip OpenAPI schema extraction
         #[arg(long)]
         no_schema: bool,
+        /// Rebuild whenever a source file changes
+        #[arg(short, long)]
+        watch: bool,
+    },
+    /// Run declarative tests against the built component
+    ///
+    /// Sends each case's request to an ephemeral runtime (no port is bound)
+    /// and checks the response. Cases live in *.test.yaml / *.test.json
+    /// files and can seed KV and SQL state. Exits non-zero if any case fails.
+    ///
+    /// Examples:
+    ///   mik test                            # Run tests/**/*.test.{yaml,yml,json}
+    ///   mik test tests/orders.test.yaml     # Run one file
+    ///   mik test --filter orders            # Only cases whose name contains "orders"
+    ///   mik test --component dist/app.wasm  # Test a specific component
+    Test {
+        /// Test files or directories (default: tests/)
+        paths: Vec<String>,
+        /// Component to test (default: built component in dist/)
+        #[arg(long)]
+        component: Option<String>,
+        /// Only run cases whose name contains this string
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Replay recorded traffic and diff the responses
+    ///
+    /// Sends the requests recorded by `mik run --record <dir>` to a running
+    /// server, in order, and compares status, content-type and body with the
+    /// recording. JSON bodies are compared field by field. Exits non-zero if
+    /// any response differs. Requests have their side effects again: replay
+    /// against a disposable environment.
+    ///
+    /// Examples:
+    ///   mik replay recordings/                          # Against localhost:3000
+    ///   mik replay recordings/ --target http://staging:3000
+    ///   mik replay recordings/ --module orders          # Only one module
+    ///   mik replay recordings/ --ignore timestamp --ignore id
+    Replay {
+        /// Directory of recordings
+        dir: String,
+        /// Server to replay against (default: http://127.0.0.1:3000)
+        #[arg(long)]
+        target: Option<String>,
+        /// Only replay requests to this module
+        #[arg(long)]
+        module: Option<String>,
+        /// JSON object key to skip when comparing bodies (repeatable)
+        #[arg(long, value_name = "KEY")]
+        ignore: Vec<String>,
     },
     /// Run the component with local development server
     ///
@@ -319,7 +418,10 @@ enum Commands {
     ///   mik run                          # Foreground, auto-detect component
     ///   mik run --detach                 # Background with services
     ///   mik run --detach --name prod     # Named background instance
+    ///   mik run --detach --restart on-failure --max-restarts 5
     ///   mik run --workers 4 --lb         # Multi-worker with load balancer
+    ///   mik run --profile production     # Apply [profiles.production] from mik.toml
+    ///   mik run --env-file .env.staging  # Load extra environment variables
     Run {
         /// Path to component (default: auto-detect)
         component: Option<String>,
@@ -333,6 +435,16 @@ enum Commands {
         #[arg(short, long, default_value = "default")]
         name: String,
 
+        /// Restart a detached instance when it exits: never, on-failure, or
+        /// always (default: never). Restarts back off exponentially.
+        #[arg(long, value_name = "POLICY", requires = "detach")]
+        restart: Option<daemon::process::RestartPolicy>,
+
+        /// Restarts before giving up (default: `max_auto_restarts` from
+        /// ~/.mik/daemon.toml)
+        #[arg(long, value_name = "N", requires = "restart")]
+        max_restarts: Option<u32>,
+
         /// Number of worker processes for horizontal scaling.
         /// Use 0 for auto-detect (one worker per CPU core).
         /// Each worker runs on a separate port (`base_port`+1, `base_port`+2, ...).
@@ -355,6 +467,22 @@ enum Commands {
         /// to workers using round-robin with health checks.
         #[arg(long)]
         lb: bool,
+
+        /// Manifest profile to apply (`[profiles.<name>]` in mik.toml).
+        /// Defaults to the `MIK_PROFILE` environment variable.
+        #[arg(long, conflicts_with = "detach")]
+        profile: Option<String>,
+
+        /// Record every module request and response to this directory, for
+        /// `mik replay`. Credentials and cookies are redacted.
+        #[arg(long, value_name = "DIR", conflicts_with = "detach")]
+        record: Option<String>,
+
+        /// Load environment variables from this file, after `.env` and
+        /// `.env.<profile>`. Components see the variables listed in
+        /// `[env].expose`.
+        #[arg(long, value_name = "PATH", conflicts_with = "detach")]
+        env_file: Option<String>,
     },
     /// Synchronize dependencies from OCI registries
     ///
@@ -387,6 +515,8 @@ enum Commands {
     /// Examples:
     ///   mik strip component.wasm                    # Strip all, output to component.stripped.wasm
     ///   mik strip component.wasm -o slim.wasm       # Custom output path
+    ///   mik strip component.wasm -O z               # Also optimize with wasm-opt
+    ///   mik strip component.wasm -O s --max-size-kb 512
     Strip {
         /// Input WASM component file
         input: String,
@@ -396,6 +526,70 @@ enum Commands {
         /// Only remove debug info (.debug* sections)
         #[arg(long)]
         debug_only: bool,
+        /// Also optimize with wasm-opt: 0-4, s or z
+        #[arg(short = 'O', long, value_parser = ["0", "1", "2", "3", "4", "s", "z"])]
+        opt_level: Option<String>,
+        /// Fail if the output is larger than this many KiB
+        #[arg(long)]
+        max_size_kb: Option<u64>,
+    },
+    /// Package the project for deployment
+    ///
+    /// Writes a Docker build context with mik.toml, modules, static files
+    /// and scripts, and a Dockerfile for a distroless image running
+    /// `mik run` with a healthcheck.
+    ///
+    /// Examples:
+    ///   mik package --docker                     # Context in dist/docker
+    ///   mik package --docker -t orders:1.0       # Also run docker build
+    Package {
+        /// Generate a Docker image
+        #[arg(long)]
+        docker: bool,
+        /// Output directory (default: dist/docker)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Build the image with this tag (requires docker)
+        #[arg(short, long)]
+        tag: Option<String>,
+    },
+    /// Check that a local instance is healthy
+    ///
+    /// Requests /health and exits with status 1 unless it returns 200.
+    /// Used as the healthcheck of images from `mik package --docker`.
+    ///
+    /// Examples:
+    ///   mik health                 # Port from mik.toml
+    ///   mik health --port 8080
+    Health {
+        /// Port of the instance (default: from mik.toml, or 3000)
+        #[arg(short, long)]
+        port: Option<u16>,
+        /// Timeout in seconds
+        #[arg(long, default_value = "5")]
+        timeout: u64,
+    },
+    /// Recommend cache and concurrency settings from live metrics
+    ///
+    /// Samples a running instance's /metrics endpoint over a window and
+    /// suggests [server] changes based on cache hit rates, permit waits,
+    /// per-module rejections, and AOT cache misses.
+    ///
+    /// Examples:
+    ///   mik tune                                   # Sample local instance for 60s
+    ///   mik tune --window 300                      # Longer sampling window
+    ///   mik tune --url http://10.0.0.5:3000/metrics
+    ///   mik tune --write                           # Apply to mik.toml
+    Tune {
+        /// Metrics URL (default: http://127.0.0.1:<port>/metrics from mik.toml)
+        #[arg(long)]
+        url: Option<String>,
+        /// Sampling window in seconds
+        #[arg(short, long, default_value = "60")]
+        window: u64,
+        /// Write recommended values to mik.toml
+        #[arg(long)]
+        write: bool,
     },
 }
 
@@ -419,6 +613,37 @@ enum CacheAction {
     Clear,
 }
 
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the effective settings and the source of each value
+    ///
+    /// Applies the active profile, interpolates environment variables and
+    /// applies MIK_* overrides the way `mik run` does, masking values of
+    /// secret-like variables and keys.
+    Show {
+        /// Profile to apply (overrides MIK_PROFILE)
+        #[arg(long)]
+        profile: Option<String>,
+        /// Port override, as passed to `mik run --port`
+        #[arg(long)]
+        port: Option<u16>,
+        /// Print the resolved mik.toml instead
+        #[arg(long)]
+        toml: bool,
+    },
+    /// Validate mik.toml and the effective settings
+    ///
+    /// Reports every problem and exits with an error if there is one.
+    Check {
+        /// Profile to apply (overrides MIK_PROFILE)
+        #[arg(long)]
+        profile: Option<String>,
+        /// Port override, as passed to `mik run --port`
+        #[arg(long)]
+        port: Option<u16>,
+    },
+}
+
 fn print_completions<G: Generator>(generator: G, cmd: &mut clap::Command) {
     clap_complete::generate(
         generator,
@@ -470,8 +695,21 @@ async fn main() -> Result<()> {
 
     match command {
         // Development
-        Commands::Dev { port, no_services } => {
-            commands::dev::execute(port, no_services).await?;
+        Commands::Dev {
+            port,
+            no_services,
+            no_build,
+            env_file,
+            proxy,
+        } => {
+            commands::dev::execute(
+                port,
+                no_services,
+                no_build,
+                env_file.as_deref(),
+                proxy.as_deref(),
+            )
+            .await?;
         },
 
         // Instance management
@@ -491,11 +729,21 @@ async fn main() -> Result<()> {
             name,
             follow,
             lines,
+            daemon,
         } => {
-            commands::daemon::logs(&name, follow, lines).await?;
+            let daemon = daemon.or_else(|| std::env::var("MIK_DAEMON_URL").ok());
+            if let Some(url) = daemon {
+                commands::daemon::remote_logs(&url, &name, follow, lines).await?;
+            } else {
+                commands::daemon::logs(&name, follow, lines).await?;
+            }
         },
         Commands::Inspect { name } => {
-            commands::daemon::inspect(&name)?;
+            if name.ends_with(".wasm") || std::path::Path::new(&name).is_file() {
+                commands::inspect::execute(&name).await?;
+            } else {
+                commands::daemon::inspect(&name)?;
+            }
         },
         Commands::Prune => {
             commands::daemon::prune()?;
@@ -503,6 +751,9 @@ async fn main() -> Result<()> {
         Commands::Cache { action } => {
             commands::cache::execute(action)?;
         },
+        Commands::Config { action } => {
+            commands::config::execute(action)?;
+        },
         Commands::Completions { shell } => {
             print_completions(shell, &mut Cli::command());
         },
@@ -512,9 +763,10 @@ async fn main() -> Result<()> {
             name,
             lang,
             template,
+            services,
             yes,
         } => {
-            use commands::new::{Language, NewOptions, Template};
+            use commands::new::{Language, NewOptions, Template, parse_services};
 
             // Parse language
             let lang = lang
@@ -524,14 +776,17 @@ async fn main() -> Result<()> {
 
             // Determine GitHub template:
             // - Explicit github:user/repo or user/repo → use that
-            // - Explicit embedded template (basic, rest-api) → None
-            // - No template specified → default GitHub template based on language
+            // - Explicit embedded template (basic, rest-api, crud...) → None
+            // - No template specified → default GitHub template based on language,
+            //   or basic when services are picked
             let github_template = if let Some(ref t) = template {
                 if t.starts_with("github:") || t.contains('/') {
                     Some(t.clone())
                 } else {
                     None // Explicit embedded template
                 }
+            } else if services.is_some() {
+                None
             } else {
                 // Default to GitHub template based on language
                 Some(match lang {
@@ -544,16 +799,26 @@ async fn main() -> Result<()> {
             let template = if github_template.is_none() {
                 template
                     .as_deref()
-                    .and_then(|s| Template::from_str(s).ok())
+                    .map(Template::from_str)
+                    .transpose()
+                    .map_err(|e| anyhow::anyhow!(e))?
                     .unwrap_or_default()
             } else {
                 Template::default()
             };
 
+            let services = services
+                .as_deref()
+                .map(parse_services)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!(e))?
+                .unwrap_or_default();
+
             let options = NewOptions {
                 name,
                 lang,
                 template,
+                services,
                 yes,
                 github_template,
             };
@@ -588,24 +853,70 @@ async fn main() -> Result<()> {
             compose,
             lang,
             no_schema,
+            watch,
         } => {
-            commands::build::execute(release, compose, lang, no_schema).await?;
+            if watch {
+                let options = commands::build::BuildOptions {
+                    release,
+                    compose,
+                    lang,
+                    no_schema,
+                };
+                let debounce = commands::build_watch::debounce();
+                commands::build_watch::watch(options, debounce, |_| {}).await?;
+            } else {
+                commands::build::execute(release, compose, lang, no_schema).await?;
+            }
+        },
+        Commands::Test {
+            paths,
+            component,
+            filter,
+        } => {
+            commands::test::execute(&paths, component.as_deref(), filter.as_deref()).await?;
+        },
+        Commands::Replay {
+            dir,
+            target,
+            module,
+            ignore,
+        } => {
+            commands::replay::execute(&dir, target.as_deref(), module.as_deref(), &ignore).await?;
         },
         Commands::Run {
             component,
             detach,
             name,
+            restart,
+            max_restarts,
             workers,
             port,
             local,
             lb,
+            profile,
+            record,
+            env_file,
         } => {
             if detach {
                 // Background mode with daemon services
-                commands::daemon::run_detached(&name, port.unwrap_or(3000)).await?;
+                let restart = daemon::process::RestartConfig {
+                    max_retries: max_restarts,
+                    ..daemon::process::RestartConfig::with_policy(restart.unwrap_or_default())
+                };
+                commands::daemon::run_detached(&name, port.unwrap_or(3000), restart).await?;
             } else {
                 // Foreground mode
-                commands::run::execute(component.as_deref(), workers, port, local, lb).await?;
+                commands::run::execute(
+                    component.as_deref(),
+                    workers,
+                    port,
+                    local,
+                    lb,
+                    profile.as_deref(),
+                    record.as_deref(),
+                    env_file.as_deref(),
+                )
+                .await?;
             }
         },
         #[cfg(feature = "registry")]
@@ -619,15 +930,35 @@ async fn main() -> Result<()> {
             input,
             output,
             debug_only,
+            opt_level,
+            max_size_kb,
         } => {
             let options = commands::strip::StripOptions {
                 all: !debug_only,
                 debug: debug_only,
                 output,
+                opt_level: opt_level
+                    .map(|level| level.parse())
+                    .transpose()
+                    .map_err(anyhow::Error::msg)?,
+                max_size_kb,
                 ..Default::default()
             };
             commands::strip::execute(&input, options)?;
         },
+        Commands::Package {
+            docker,
+            output,
+            tag,
+        } => {
+            commands::package::execute(docker, output.as_deref(), tag.as_deref())?;
+        },
+        Commands::Health { port, timeout } => {
+            commands::health::execute(port, timeout).await?;
+        },
+        Commands::Tune { url, window, write } => {
+            commands::tune::execute(url, window, write).await?;
+        },
     }
 
     Ok(())
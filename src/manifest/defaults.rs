@@ -41,6 +41,11 @@ pub const fn default_max_body_size_mb() -> usize {
     10
 }
 
+/// Default max uploaded module size in MB (50).
+pub const fn default_max_module_size_mb() -> usize {
+    50
+}
+
 /// Default execution timeout in seconds (30).
 pub const fn default_execution_timeout() -> u64 {
     30
@@ -51,6 +56,16 @@ pub const fn default_shutdown_timeout() -> u64 {
     30
 }
 
+/// Default time a request may wait in a module's queue in milliseconds (1000).
+pub const fn default_module_queue_timeout_ms() -> u64 {
+    1000
+}
+
+/// Default delay before the first execution retry in milliseconds (10).
+pub const fn default_execution_retry_backoff_ms() -> u64 {
+    10
+}
+
 /// Default log max size in MB (10).
 pub const fn default_log_max_size_mb() -> usize {
     10
@@ -139,6 +154,40 @@ pub const fn default_http2_only() -> bool {
     false
 }
 
+/// Default percentage of requests mirrored when a mirror backend is set (100).
+pub const fn default_mirror_percent() -> u8 {
+    100
+}
+
+// =============================================================================
+// Build Defaults
+// =============================================================================
+
+/// Default Go package to build (".").
+pub fn default_go_package() -> String {
+    ".".to_string()
+}
+
+/// Default TinyGo target ("wasip2").
+pub fn default_go_target() -> String {
+    "wasip2".to_string()
+}
+
+/// Default Python module defining the exports ("app").
+pub fn default_python_module() -> String {
+    "app".to_string()
+}
+
+/// Default WIT directory ("wit").
+pub fn default_wit_dir() -> String {
+    "wit".to_string()
+}
+
+/// Default world to target ("proxy", the WASI HTTP world).
+pub fn default_wit_world() -> String {
+    "proxy".to_string()
+}
+
 // =============================================================================
 // Project Defaults
 // =============================================================================
@@ -3,18 +3,26 @@
 //! Similar to Cargo.toml, pyproject.toml, package.json but for WASI components.
 
 mod defaults;
+mod env;
+mod profiles;
 mod types;
 mod validation;
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 // Re-export all public types
 // Note: Some re-exports may appear unused but are part of the public API
 #[allow(unused_imports)]
 pub use defaults::*;
+pub use env::{
+    ENV_FILE_ENV, EnvFiles, MASK, is_secret_name, mask_secret_keys, mask_secret_vars,
+    variable_references,
+};
+pub use profiles::{PROFILE_ENV, active_profile, apply_profile};
 pub use types::*;
 #[allow(unused_imports)]
 pub use validation::ValidationError;
@@ -42,6 +50,26 @@ impl Manifest {
         Ok(manifest)
     }
 
+    /// Load manifest from mik.toml with the active profile applied.
+    ///
+    /// Use this for running, not for editing: saving the result would bake
+    /// the profile into the base configuration.
+    pub fn load_resolved() -> Result<Self> {
+        Self::load_resolved_from(Path::new("mik.toml"))
+    }
+
+    /// Load manifest from a specific path with the active profile applied.
+    pub fn load_resolved_from(path: &Path) -> Result<Self> {
+        let (table, _) = read_with_active_profile(path)?;
+        let manifest: Self = table
+            .try_into()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        manifest.validate(path)?;
+
+        Ok(manifest)
+    }
+
     /// Save manifest to mik.toml in current directory.
     pub fn save(&self) -> Result<()> {
         self.save_to(Path::new("mik.toml"))
@@ -86,9 +114,9 @@ impl Manifest {
             return Ok(ServerConfig::default());
         }
 
-        let content = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read {}", path.display()))?;
-        let partial: Partial = toml::from_str(&content)
+        let (table, _) = read_with_active_profile(path)?;
+        let partial: Partial = table
+            .try_into()
             .with_context(|| format!("Failed to parse server config from {}", path.display()))?;
         Ok(partial.server)
     }
@@ -112,9 +140,9 @@ impl Manifest {
             return Ok(TracingConfig::default());
         }
 
-        let content = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read {}", path.display()))?;
-        let partial: Partial = toml::from_str(&content)
+        let (table, _) = read_with_active_profile(path)?;
+        let partial: Partial = table
+            .try_into()
             .with_context(|| format!("Failed to parse tracing config from {}", path.display()))?;
         Ok(partial.tracing)
     }
@@ -132,6 +160,63 @@ impl Manifest {
     }
 }
 
+/// Read a manifest file as TOML with the active profile applied.
+///
+/// Returns the merged table and the name of the applied profile.
+pub(crate) fn read_with_active_profile(path: &Path) -> Result<(toml::Table, Option<String>)> {
+    let resolved = read_resolved(path, false)?;
+    Ok((resolved.table, resolved.profile))
+}
+
+/// A manifest with its profile applied and environment variables resolved.
+pub(crate) struct Resolved {
+    pub table: toml::Table,
+    pub profile: Option<String>,
+    /// `.env` files that were loaded, in order.
+    #[allow(dead_code)] // Only read by `mik config`
+    pub env_files: Vec<PathBuf>,
+    /// Variables exposed to components by `[env].expose`, or `None` when
+    /// components inherit the process environment.
+    pub component_env: Option<BTreeMap<String, String>>,
+}
+
+/// Read a manifest file, apply the active profile, and interpolate `${VAR}`
+/// references from the environment and `.env` files.
+///
+/// With `mask_secrets`, secret-like variables are substituted as [`env::MASK`].
+/// Fails listing every missing variable, including `[env].required` ones.
+pub(crate) fn read_resolved(path: &Path, mask_secrets: bool) -> Result<Resolved> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut table: toml::Table =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let profile = active_profile();
+    apply_profile(&mut table, profile.as_deref())
+        .with_context(|| format!("Invalid profile in {}", path.display()))?;
+
+    let env_config: EnvConfig = table
+        .get("env")
+        .cloned()
+        .map(toml::Value::try_into)
+        .transpose()
+        .with_context(|| format!("Invalid [env] section in {}", path.display()))?
+        .unwrap_or_default();
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let env = EnvFiles::load(dir, profile.as_deref())?;
+    env::interpolate(&mut table, &env, &env_config.required, mask_secrets)
+        .with_context(|| format!("Failed to resolve environment for {}", path.display()))?;
+
+    let component_env = (!env_config.expose.is_empty()).then(|| env.matching(&env_config.expose));
+
+    Ok(Resolved {
+        table,
+        profile,
+        env_files: env.files().to_vec(),
+        component_env,
+    })
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -211,6 +296,60 @@ version = "0.1.0"
         assert!(manifest.composition.bridge.is_none());
     }
 
+    #[test]
+    fn test_parse_build_config() {
+        let toml = r#"
+[project]
+name = "my-app"
+language = "go"
+
+[build.go]
+package = "./cmd/handler"
+tags = ["purego"]
+
+[build.python]
+python_path = ["src"]
+"#;
+        let manifest: Manifest = toml::from_str(toml).unwrap();
+        assert_eq!(manifest.build.go.package, "./cmd/handler");
+        assert_eq!(manifest.build.go.tags, ["purego"]);
+        assert_eq!(manifest.build.go.target, "wasip2");
+        assert_eq!(manifest.build.python.module, "app");
+        assert_eq!(manifest.build.python.world, "proxy");
+        assert_eq!(manifest.build.python.python_path, ["src"]);
+        assert_eq!(manifest.build.opt_level, OptLevel::Z);
+        assert_eq!(manifest.build.max_size_kb, None);
+
+        // Default build settings are not written back
+        let manifest = Manifest::default();
+        assert!(manifest.build.is_empty());
+        assert!(!toml::to_string(&manifest).unwrap().contains("[build"));
+    }
+
+    #[test]
+    fn test_parse_build_budget() {
+        let toml = r#"
+[project]
+name = "my-app"
+
+[build]
+opt_level = "s"
+max_size_kb = 512
+"#;
+        let manifest: Manifest = toml::from_str(toml).unwrap();
+        assert_eq!(manifest.build.opt_level, OptLevel::S);
+        assert_eq!(manifest.build.opt_level.flag(), "-Os");
+        assert_eq!(manifest.build.max_size_kb, Some(512));
+        assert!(!manifest.build.is_empty());
+
+        assert!(
+            toml::from_str::<Manifest>("[project]\nname = \"x\"\n[build]\nopt_level = \"9\"")
+                .is_err()
+        );
+        assert_eq!("3".parse::<OptLevel>(), Ok(OptLevel::O3));
+        assert!("fast".parse::<OptLevel>().is_err());
+    }
+
     #[test]
     fn test_composition_disabled() {
         let toml = r#"
@@ -286,6 +425,110 @@ version = "0.1.0"
         assert!(error.contains("Project name is required"));
     }
 
+    #[test]
+    fn test_validate_profile_sections() {
+        let toml = r#"
+[project]
+name = "my-app"
+version = "0.1.0"
+
+[profiles.production.server]
+port = 8080
+
+[profiles.production.dependencies]
+router = "1.0"
+"#;
+        let manifest: Manifest = toml::from_str(toml).unwrap();
+        assert_eq!(manifest.profiles.len(), 1);
+        let error = manifest
+            .validate(Path::new("mik.toml"))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Profile 'production' cannot override [dependencies]"));
+    }
+
+    #[test]
+    fn test_parse_and_validate_aliases() {
+        let toml = r#"
+[project]
+name = "my-app"
+version = "0.1.0"
+
+[server.aliases]
+v1 = { module = "orders-1.4.wasm", deprecated = true, successor = "v2" }
+v2 = { module = "orders-2.0" }
+"#;
+        let manifest: Manifest = toml::from_str(toml).unwrap();
+        assert!(manifest.validate(Path::new("mik.toml")).is_ok());
+        let v1 = &manifest.server.aliases["v1"];
+        assert_eq!(v1.module_name(), "orders-1.4");
+        assert!(v1.deprecated);
+        assert_eq!(manifest.server.aliases["v2"].module_name(), "orders-2.0");
+
+        let toml = r#"
+[project]
+name = "my-app"
+version = "0.1.0"
+
+[server.aliases]
+v1 = { module = "../secret", successor = "v3" }
+"#;
+        let manifest: Manifest = toml::from_str(toml).unwrap();
+        let error = manifest
+            .validate(Path::new("mik.toml"))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Invalid alias 'v1'"));
+    }
+
+    #[test]
+    fn test_profiles_survive_save() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mik.toml");
+        std::fs::write(
+            &path,
+            r#"
+[project]
+name = "my-app"
+
+[profiles.production.server]
+port = 8080
+"#,
+        )
+        .unwrap();
+
+        let manifest = Manifest::load_from(&path).unwrap();
+        manifest.save_to(&path).unwrap();
+        let reloaded = Manifest::load_from(&path).unwrap();
+        assert_eq!(reloaded.server.port, 3000);
+        assert_eq!(
+            reloaded.profiles["production"]["server"]["port"].as_integer(),
+            Some(8080)
+        );
+    }
+
+    #[test]
+    fn test_read_resolved_exposes_env() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mik.toml");
+        fs::write(
+            dir.path().join(".env"),
+            "MIK_TEST_EXPOSED=yes\nMIK_TEST_HIDDEN=no\n",
+        )
+        .unwrap();
+        fs::write(&path, "[project]\nname = \"app\"\n").unwrap();
+        assert!(read_resolved(&path, false).unwrap().component_env.is_none());
+
+        fs::write(
+            &path,
+            "[project]\nname = \"app\"\n\n[env]\nexpose = [\"MIK_TEST_EXPOSED\"]\n",
+        )
+        .unwrap();
+        let vars = read_resolved(&path, false).unwrap().component_env.unwrap();
+        assert_eq!(vars.len(), 1);
+        assert_eq!(vars["MIK_TEST_EXPOSED"], "yes");
+    }
+
     #[test]
     fn test_validate_invalid_name() {
         let toml = r#"
@@ -0,0 +1,173 @@
+//! Named environment profiles.
+//!
+//! A manifest can declare `[profiles.<name>]` sections that override parts of
+//! the base configuration for one environment:
+//!
+//! ```toml
+//! [server]
+//! port = 3000
+//! logging = true
+//!
+//! [profiles.production.server]
+//! port = 8080
+//! max_concurrent_requests = 2000
+//! http_allowed = ["api.example.com"]
+//!
+//! [profiles.production.tracing]
+//! otlp_endpoint = "http://tempo:4317"
+//! ```
+//!
+//! The active profile is selected with `mik run --profile <name>` or the
+//! `MIK_PROFILE` environment variable. Tables are merged key by key, any other
+//! value (including arrays) replaces the base value.
+
+use anyhow::{Result, bail};
+use toml::{Table, Value};
+
+/// Environment variable selecting the active profile.
+pub const PROFILE_ENV: &str = "MIK_PROFILE";
+
+/// Sections a profile may override.
+pub const PROFILE_SECTIONS: &[&str] = &["server", "tracing", "lb"];
+
+/// Name of the active profile (from `MIK_PROFILE`), if any.
+///
+/// `mik run --profile` sets the variable, so worker processes inherit it.
+pub fn active_profile() -> Option<String> {
+    std::env::var(PROFILE_ENV)
+        .ok()
+        .filter(|name| !name.is_empty())
+}
+
+/// Remove the `[profiles]` table from a parsed manifest and merge the
+/// `profile` section over the base configuration.
+///
+/// # Errors
+///
+/// Returns an error if the profile does not exist or overrides a section
+/// that profiles cannot change.
+pub fn apply_profile(manifest: &mut Table, profile: Option<&str>) -> Result<()> {
+    let profiles = match manifest.remove("profiles") {
+        Some(Value::Table(profiles)) => profiles,
+        Some(_) => bail!("[profiles] must be a table"),
+        None => Table::new(),
+    };
+
+    let Some(name) = profile else {
+        return Ok(());
+    };
+    let Some(overrides) = profiles.get(name) else {
+        let available: Vec<_> = profiles.keys().map(String::as_str).collect();
+        if available.is_empty() {
+            bail!("Profile '{name}' not found: mik.toml defines no [profiles]");
+        }
+        bail!(
+            "Profile '{name}' not found. Available profiles: {}",
+            available.join(", ")
+        );
+    };
+    let Value::Table(overrides) = overrides else {
+        bail!("[profiles.{name}] must be a table");
+    };
+
+    if let Some(section) = invalid_section(overrides) {
+        bail!(
+            "Profile '{name}' cannot override [{section}]. Profiles may only override: {}",
+            PROFILE_SECTIONS.join(", ")
+        );
+    }
+
+    merge(manifest, overrides.clone());
+    Ok(())
+}
+
+/// First section of `overrides` that profiles may not change.
+pub(crate) fn invalid_section(overrides: &Table) -> Option<&str> {
+    overrides
+        .keys()
+        .map(String::as_str)
+        .find(|section| !PROFILE_SECTIONS.contains(section))
+}
+
+/// Deep-merge `overrides` into `base`.
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[project]
+name = "app"
+
+[server]
+port = 3000
+logging = true
+http_allowed = ["*"]
+
+[profiles.production.server]
+port = 8080
+http_allowed = ["api.example.com"]
+
+[profiles.production.tracing]
+service_name = "app-prod"
+
+[profiles.staging.server]
+port = 4000
+"#;
+
+    #[test]
+    fn test_apply_profile_merges_sections() {
+        let mut manifest: Table = toml::from_str(MANIFEST).unwrap();
+        apply_profile(&mut manifest, Some("production")).unwrap();
+
+        assert!(!manifest.contains_key("profiles"));
+        let server = manifest["server"].as_table().unwrap();
+        assert_eq!(server["port"].as_integer(), Some(8080));
+        // Untouched keys keep their base value, arrays are replaced
+        assert_eq!(server["logging"].as_bool(), Some(true));
+        assert_eq!(
+            server["http_allowed"].as_array().unwrap(),
+            &vec![Value::from("api.example.com")]
+        );
+        assert_eq!(
+            manifest["tracing"]["service_name"].as_str(),
+            Some("app-prod")
+        );
+    }
+
+    #[test]
+    fn test_apply_no_profile_strips_profiles() {
+        let mut manifest: Table = toml::from_str(MANIFEST).unwrap();
+        apply_profile(&mut manifest, None).unwrap();
+
+        assert!(!manifest.contains_key("profiles"));
+        assert_eq!(manifest["server"]["port"].as_integer(), Some(3000));
+    }
+
+    #[test]
+    fn test_apply_profile_errors() {
+        let mut manifest: Table = toml::from_str(MANIFEST).unwrap();
+        let err = apply_profile(&mut manifest, Some("dev")).unwrap_err();
+        assert!(err.to_string().contains("production, staging"));
+
+        let mut manifest: Table = toml::from_str(
+            r#"
+[profiles.production.project]
+name = "other"
+"#,
+        )
+        .unwrap();
+        let err = apply_profile(&mut manifest, Some("production")).unwrap_err();
+        assert!(err.to_string().contains("cannot override [project]"));
+    }
+}
@@ -7,14 +7,17 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 use super::defaults::{
-    default_auto, default_execution_timeout, default_health_check_interval_ms,
+    default_auto, default_execution_retry_backoff_ms, default_execution_timeout,
+    default_go_package, default_go_target, default_health_check_interval_ms,
     default_health_check_path, default_health_check_timeout_ms, default_health_check_type,
     default_healthy_threshold, default_http_handler, default_http2_only, default_lb_enabled,
     default_log_max_files, default_log_max_size_mb, default_max_body_size_mb,
-    default_max_connections_per_backend, default_modules_dir, default_pool_idle_timeout_secs,
-    default_port, default_request_timeout_secs, default_service_name, default_shutdown_timeout,
-    default_tcp_keepalive_secs, default_tracing_enabled, default_unhealthy_threshold,
-    default_version, default_watch_debounce_ms,
+    default_max_connections_per_backend, default_max_module_size_mb, default_mirror_percent,
+    default_module_queue_timeout_ms, default_modules_dir, default_pool_idle_timeout_secs,
+    default_port, default_python_module, default_request_timeout_secs, default_service_name,
+    default_shutdown_timeout, default_tcp_keepalive_secs, default_tracing_enabled,
+    default_unhealthy_threshold, default_version, default_watch_debounce_ms, default_wit_dir,
+    default_wit_world,
 };
 
 // =============================================================================
@@ -31,12 +34,19 @@ pub struct Manifest {
     pub tracing: TracingConfig,
     #[serde(default)]
     pub composition: CompositionConfig,
+    #[serde(default, skip_serializing_if = "BuildConfig::is_empty")]
+    pub build: BuildConfig,
     #[serde(default)]
     pub lb: Option<LbConfig>,
     #[serde(default)]
     pub dependencies: BTreeMap<String, Dependency>,
     #[serde(default, rename = "dev-dependencies")]
     pub dev_dependencies: BTreeMap<String, Dependency>,
+    /// Named environment profiles (`[profiles.<name>]`), kept unmerged.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, toml::Table>,
+    #[serde(default, skip_serializing_if = "EnvConfig::is_empty")]
+    pub env: EnvConfig,
 }
 
 impl Default for Manifest {
@@ -52,13 +62,51 @@ impl Default for Manifest {
             server: ServerConfig::default(),
             tracing: TracingConfig::default(),
             composition: CompositionConfig::default(),
+            build: BuildConfig::default(),
             lb: None,
             dependencies: BTreeMap::new(),
             dev_dependencies: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            env: EnvConfig::default(),
         }
     }
 }
 
+// =============================================================================
+// Environment Configuration
+// =============================================================================
+
+/// Environment variable requirements.
+///
+/// Variables are read from the process environment and from `.env` /
+/// `.env.<profile>` next to mik.toml, and can be referenced in manifest
+/// values as `${VAR}` or `${VAR:-default}`.
+///
+/// # Example
+///
+/// ```toml
+/// [env]
+/// required = ["DATABASE_URL", "API_TOKEN"]
+/// expose = ["API_TOKEN", "APP_*"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnvConfig {
+    /// Variables that must be set (and non-empty) for `mik run` to start.
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Variables components can read (exact names, or prefixes ending in
+    /// `*`). When empty, components inherit the whole process environment.
+    #[serde(default)]
+    pub expose: Vec<String>,
+}
+
+impl EnvConfig {
+    /// Whether nothing is declared.
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.expose.is_empty()
+    }
+}
+
 // =============================================================================
 // Tracing Configuration
 // =============================================================================
@@ -111,6 +159,7 @@ impl Default for TracingConfig {
 
 /// Server configuration for the host runtime.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)] // Independent [server] switches
 pub struct ServerConfig {
     /// Auto-configure based on system resources (default: true).
     ///
@@ -122,6 +171,10 @@ pub struct ServerConfig {
     /// Port to listen on (default: 3000)
     #[serde(default = "default_port")]
     pub port: u16,
+    /// Additional addresses to serve on: `host:port` or `unix:/path`
+    /// (default: empty, only `port`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub listen: Vec<String>,
     /// Directory containing WASM modules (default: "modules/")
     #[serde(default = "default_modules_dir")]
     pub modules: String,
@@ -146,15 +199,54 @@ pub struct ServerConfig {
     /// Maximum request body size in MB (default: 10)
     #[serde(default = "default_max_body_size_mb")]
     pub max_body_size_mb: usize,
+    /// Max size of a tenant module uploaded through the gateway in MB
+    /// (default: 50)
+    #[serde(default = "default_max_module_size_mb")]
+    pub max_module_size_mb: usize,
     /// WASM execution timeout in seconds (default: 30)
     #[serde(default = "default_execution_timeout")]
     pub execution_timeout_secs: u64,
+    /// Maximum timeout a request may ask for via `X-Request-Timeout` or
+    /// `grpc-timeout` (0 = `execution_timeout_secs`, requests can only shorten it).
+    #[serde(default)]
+    pub max_request_timeout_secs: u64,
+    /// Timeout for a component's `init`/`warmup` export in seconds
+    /// (0 = `execution_timeout_secs`).
+    #[serde(default)]
+    pub init_timeout_secs: u64,
+    /// Retries for executions that fail before the handler runs because
+    /// instances are exhausted (default: 0, no retries).
+    #[serde(default)]
+    pub execution_retries: u32,
+    /// Delay before the first execution retry in milliseconds, doubled with
+    /// jitter for each further retry (default: 10).
+    #[serde(default = "default_execution_retry_backoff_ms")]
+    pub execution_retry_backoff_ms: u64,
     /// Maximum concurrent requests (0 = auto-detect based on CPU cores).
     #[serde(default)]
     pub max_concurrent_requests: usize,
     /// Maximum concurrent requests per module (0 = auto-detect).
     #[serde(default)]
     pub max_per_module_requests: usize,
+    /// Maximum concurrent requests per tenant across all of its modules
+    /// under `/tenant/<tenant-id>/` (0 = unlimited).
+    #[serde(default)]
+    pub max_per_tenant_requests: usize,
+    /// Requests that may wait for a busy module before being shed with 429
+    /// (0 = reject immediately).
+    #[serde(default)]
+    pub module_queue_depth: usize,
+    /// Maximum time a request waits in a module's queue in milliseconds
+    /// (default: 1000).
+    #[serde(default = "default_module_queue_timeout_ms")]
+    pub module_queue_timeout_ms: u64,
+    /// Adjust each module's concurrency limit to its latency and errors,
+    /// with `max_per_module_requests` as the ceiling (default: false).
+    #[serde(default)]
+    pub adaptive_concurrency: bool,
+    /// Lowest limit adaptive concurrency may set for a module (default: 1).
+    #[serde(default)]
+    pub adaptive_concurrency_min: usize,
     /// Graceful shutdown drain timeout in seconds (default: 30)
     #[serde(default = "default_shutdown_timeout")]
     pub shutdown_timeout_secs: u64,
@@ -167,6 +259,10 @@ pub struct ServerConfig {
     /// File watch debounce duration in milliseconds (default: 300)
     #[serde(default = "default_watch_debounce_ms")]
     pub watch_debounce_ms: u64,
+    /// Log filter for the server, in `RUST_LOG` syntax (e.g. `info` or
+    /// `mik=debug,wasmtime=warn`). `RUST_LOG` takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
     /// Enable wasi:logging for WASM modules (default: false).
     #[serde(default)]
     pub logging: bool,
@@ -177,6 +273,265 @@ pub struct ServerConfig {
     /// - `["api.example.com", "*.supabase.co"]` = specific hosts only
     #[serde(default)]
     pub http_allowed: Vec<String>,
+    /// Proxies (IPs or CIDR ranges) trusted to set `X-Forwarded-For` and
+    /// `X-Client-Cert-Subject`, as reported by `mik:request-info`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_proxies: Vec<String>,
+    /// Expect a PROXY protocol v1/v2 header from the load balancer on every
+    /// connection and use its client address (default: false).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub proxy_protocol: bool,
+    /// Enable `PUT`/`DELETE /_mik/tenants/{id}/modules/{module}`, which
+    /// upload and delete tenant modules with the API key (default: false).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tenant_uploads: bool,
+    /// URL notified with a `POST` when modules are added, removed or
+    /// updated, so a gateway can update its routing without polling
+    /// `/_mik/handlers` (default: none).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handlers_webhook: Option<String>,
+    /// Versioned route aliases: `/run/<alias>/*` is served by another module.
+    ///
+    /// ```toml
+    /// [server.aliases]
+    /// v1 = { module = "orders-1.4.wasm", deprecated = true, successor = "v2" }
+    /// v2 = { module = "orders-2.0.wasm" }
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, ModuleAlias>,
+    /// Per-module quotas on outgoing HTTP traffic; `"*"` applies to modules
+    /// without their own entry.
+    ///
+    /// ```toml
+    /// [server.egress_quotas]
+    /// "*" = { monthly_mb = 1024 }
+    /// crawler = { daily_mb = 50, monthly_mb = 500 }
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub egress_quotas: BTreeMap<String, EgressQuota>,
+    /// Circuit breaker settings per module; `"*"` replaces the defaults for
+    /// modules without their own entry. Omitted fields keep the defaults.
+    ///
+    /// ```toml
+    /// [server.circuit_breakers]
+    /// payments = { failure_threshold = 10, recovery_timeout_secs = 5 }
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub circuit_breakers: BTreeMap<String, CircuitBreakerPolicy>,
+    /// Faults injected into requests per module, for resilience testing;
+    /// `"*"` applies to modules without their own entry. Never enable in
+    /// production.
+    ///
+    /// ```toml
+    /// [server.chaos]
+    /// payments = { latency_ms = 500, latency_probability = 0.2, trap_probability = 0.05 }
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub chaos: BTreeMap<String, ChaosPolicy>,
+    /// Limits per tenant under `/tenant/<tenant-id>/`; `"*"` applies to
+    /// tenants without their own entry.
+    ///
+    /// ```toml
+    /// [server.tenant_limits]
+    /// "*" = { requests_per_second = 50, max_storage_mb = 100 }
+    /// acme = { requests_per_second = 500, max_concurrent_requests = 64 }
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tenant_limits: BTreeMap<String, TenantLimits>,
+    /// Modules allowed to query the SQL database through the `mik:sql`
+    /// host interface (`["*"]` = all modules, default: none).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sql_modules: Vec<String>,
+    /// Daemon services each orchestration script may use, keyed by script
+    /// name (`"*"` = default for other scripts, default: none).
+    ///
+    /// ```toml
+    /// [server.script_capabilities]
+    /// checkout = { kv = true, storage = true }
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub script_capabilities: BTreeMap<String, ScriptCapabilities>,
+    /// Host directories each module sees through WASI filesystem preopens,
+    /// keyed by module name (`"*"` = default for other modules, default:
+    /// none). Tenant modules never get preopens.
+    ///
+    /// ```toml
+    /// [server.preopens]
+    /// thumbnails = [
+    ///     { path = "assets/fonts", guest = "/fonts" },
+    ///     { path = "data/out", guest = "/out", writable = true },
+    /// ]
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub preopens: BTreeMap<String, Vec<Preopen>>,
+    /// Models served through `wasi-nn` (`nn` feature), keyed by the name
+    /// guests pass to `load-by-name`. Each directory holds a `model.onnx`.
+    ///
+    /// ```toml
+    /// [server.nn_models]
+    /// sentiment = "models/sentiment"
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub nn_models: BTreeMap<String, String>,
+    /// Models each module may load, keyed by module name (`"*"` = default
+    /// for other modules, default: none). Tenant modules get no models.
+    ///
+    /// ```toml
+    /// [server.nn_modules]
+    /// classifier = ["sentiment"]
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub nn_modules: BTreeMap<String, Vec<String>>,
+    /// Modules pulled from OCI registries on first request when they are
+    /// not in `modules` (`registry` feature), keyed by module name.
+    ///
+    /// ```toml
+    /// [server.oci_modules]
+    /// handler = "oci://ghcr.io/org/handler:1.2"
+    /// ```
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub oci_modules: BTreeMap<String, String>,
+}
+
+/// A route alias for a module (see [`ServerConfig::aliases`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleAlias {
+    /// Module serving the alias (`orders-1.4` or `orders-1.4.wasm`).
+    pub module: String,
+    /// Mark the alias as deprecated (adds a `Deprecation` response header).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Date after which the alias may be removed, sent as the `Sunset` header
+    /// (HTTP date, e.g. `"Wed, 01 Jul 2026 00:00:00 GMT"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunset: Option<String>,
+    /// Alias that replaces this one, advertised in a `Link` header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub successor: Option<String>,
+}
+
+impl ModuleAlias {
+    /// Alias for `module` with no deprecation.
+    #[allow(dead_code)] // Public API for embedders (`RuntimeBuilder::alias`)
+    pub fn new(module: impl Into<String>) -> Self {
+        Self {
+            module: module.into(),
+            deprecated: false,
+            sunset: None,
+            successor: None,
+        }
+    }
+
+    /// Module name without the `.wasm` extension.
+    pub fn module_name(&self) -> &str {
+        self.module.strip_suffix(".wasm").unwrap_or(&self.module)
+    }
+}
+
+/// Outgoing HTTP traffic allowed per module (see [`ServerConfig::egress_quotas`]).
+///
+/// Windows are UTC calendar days and months.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EgressQuota {
+    /// Megabytes per day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_mb: Option<u64>,
+    /// Megabytes per month.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_mb: Option<u64>,
+}
+
+/// Circuit breaker settings for a module (see [`ServerConfig::circuit_breakers`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitBreakerPolicy {
+    /// Consecutive failures before the circuit opens (default: 5).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_threshold: Option<u32>,
+    /// Seconds an open circuit waits before letting a probe through (default: 30).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery_timeout_secs: Option<u64>,
+    /// Seconds a probe may run before another one is allowed (default: 30).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probe_timeout_secs: Option<u64>,
+}
+
+/// Faults injected into a module's requests (see [`ServerConfig::chaos`]).
+///
+/// Probabilities are between 0.0 and 1.0 and rolled independently on every
+/// request. For resilience testing only.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChaosPolicy {
+    /// Delay added before the handler runs, in milliseconds. It counts
+    /// against the execution timeout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Probability of adding `latency_ms`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_probability: Option<f64>,
+    /// Probability of running the handler and then dropping its response:
+    /// the connection is closed without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drop_probability: Option<f64>,
+    /// Probability of failing as if the handler trapped, without running it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trap_probability: Option<f64>,
+    /// Probability of running the handler without fuel, so it traps as
+    /// soon as it starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel_starvation_probability: Option<f64>,
+}
+
+impl ChaosPolicy {
+    /// The probabilities that are set, by field name.
+    pub fn probabilities(&self) -> [(&'static str, Option<f64>); 4] {
+        [
+            ("latency_probability", self.latency_probability),
+            ("drop_probability", self.drop_probability),
+            ("trap_probability", self.trap_probability),
+            (
+                "fuel_starvation_probability",
+                self.fuel_starvation_probability,
+            ),
+        ]
+    }
+}
+
+/// Limits for one tenant (see [`ServerConfig::tenant_limits`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantLimits {
+    /// Requests per second across the tenant's modules, with bursts of up
+    /// to one second's worth.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_second: Option<u32>,
+    /// Concurrent requests (overrides `max_per_tenant_requests`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_requests: Option<usize>,
+    /// Megabytes of modules uploaded through the gateway.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_storage_mb: Option<u64>,
+}
+
+/// A host directory granted to a module (see [`ServerConfig::preopens`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preopen {
+    /// Directory on the host, relative to the working directory.
+    pub path: String,
+    /// Absolute path the module opens it at, e.g. `/assets`.
+    pub guest: String,
+    /// Allow creating, writing and removing files (default: read-only).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub writable: bool,
+}
+
+/// Daemon services a script may use (see [`ServerConfig::script_capabilities`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptCapabilities {
+    /// `host.kv.*`: the daemon KV store.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub kv: bool,
+    /// `host.storage.*`: the daemon object storage.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub storage: bool,
 }
 
 impl Default for ServerConfig {
@@ -184,6 +539,7 @@ impl Default for ServerConfig {
         Self {
             auto: default_auto(),
             port: default_port(),
+            listen: Vec::new(),
             modules: default_modules_dir(),
             user_modules: None,
             scripts: None,
@@ -191,15 +547,41 @@ impl Default for ServerConfig {
             cache_size: 0,   // 0 = auto-detect
             max_cache_mb: 0, // 0 = auto-detect
             max_body_size_mb: default_max_body_size_mb(),
+            max_module_size_mb: default_max_module_size_mb(),
             execution_timeout_secs: default_execution_timeout(),
+            max_request_timeout_secs: 0,
+            init_timeout_secs: 0,
+            execution_retries: 0,
+            execution_retry_backoff_ms: default_execution_retry_backoff_ms(),
             max_concurrent_requests: 0, // 0 = auto-detect
             max_per_module_requests: 0, // 0 = auto-detect
+            max_per_tenant_requests: 0,
+            module_queue_depth: 0,
+            module_queue_timeout_ms: default_module_queue_timeout_ms(),
+            adaptive_concurrency: false,
+            adaptive_concurrency_min: 0,
             shutdown_timeout_secs: default_shutdown_timeout(),
             log_max_size_mb: default_log_max_size_mb(),
             log_max_files: default_log_max_files(),
             watch_debounce_ms: default_watch_debounce_ms(),
+            log_level: None,
             logging: false,
             http_allowed: Vec::new(),
+            trusted_proxies: Vec::new(),
+            proxy_protocol: false,
+            tenant_uploads: false,
+            handlers_webhook: None,
+            aliases: BTreeMap::new(),
+            egress_quotas: BTreeMap::new(),
+            circuit_breakers: BTreeMap::new(),
+            chaos: BTreeMap::new(),
+            tenant_limits: BTreeMap::new(),
+            sql_modules: Vec::new(),
+            script_capabilities: BTreeMap::new(),
+            preopens: BTreeMap::new(),
+            nn_models: BTreeMap::new(),
+            nn_modules: BTreeMap::new(),
+            oci_modules: BTreeMap::new(),
         }
     }
 }
@@ -257,6 +639,181 @@ impl Default for CompositionConfig {
     }
 }
 
+// =============================================================================
+// Build Configuration
+// =============================================================================
+
+/// Build settings for `mik build`: release optimization and per-language
+/// toolchain settings.
+///
+/// # Example
+///
+/// ```toml
+/// [build]
+/// opt_level = "s"
+/// max_size_kb = 2048
+///
+/// [build.go]
+/// package = "./cmd/handler"
+/// world = "proxy"
+///
+/// [build.python]
+/// module = "app"
+/// python_path = ["src"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildConfig {
+    /// wasm-opt level for release builds (default: "z").
+    #[serde(default)]
+    pub opt_level: OptLevel,
+    /// Largest allowed release artifact, in KiB. Release builds over it fail.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size_kb: Option<u64>,
+    /// Go components, built with TinyGo.
+    #[serde(default)]
+    pub go: GoBuildConfig,
+    /// Python components, built with componentize-py.
+    #[serde(default)]
+    pub python: PythonBuildConfig,
+}
+
+impl BuildConfig {
+    /// Whether no build settings are configured.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// wasm-opt optimization level.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptLevel {
+    /// No optimization (`-O0`).
+    #[serde(rename = "0")]
+    O0,
+    /// Quick optimizations (`-O1`).
+    #[serde(rename = "1")]
+    O1,
+    /// Most optimizations (`-O2`).
+    #[serde(rename = "2")]
+    O2,
+    /// All optimizations, slower to run (`-O3`).
+    #[serde(rename = "3")]
+    O3,
+    /// Speed, with more aggressive inlining (`-O4`).
+    #[serde(rename = "4")]
+    O4,
+    /// Size (`-Os`).
+    #[serde(rename = "s")]
+    S,
+    /// Size, aggressively (`-Oz`).
+    #[default]
+    #[serde(rename = "z")]
+    Z,
+}
+
+impl OptLevel {
+    /// The wasm-opt flag for this level.
+    pub const fn flag(self) -> &'static str {
+        match self {
+            Self::O0 => "-O0",
+            Self::O1 => "-O1",
+            Self::O2 => "-O2",
+            Self::O3 => "-O3",
+            Self::O4 => "-O4",
+            Self::S => "-Os",
+            Self::Z => "-Oz",
+        }
+    }
+}
+
+impl std::str::FromStr for OptLevel {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level {
+            "0" => Ok(Self::O0),
+            "1" => Ok(Self::O1),
+            "2" => Ok(Self::O2),
+            "3" => Ok(Self::O3),
+            "4" => Ok(Self::O4),
+            "s" => Ok(Self::S),
+            "z" => Ok(Self::Z),
+            _ => Err(format!(
+                "invalid optimization level '{level}' (expected 0-4, s or z)"
+            )),
+        }
+    }
+}
+
+/// TinyGo settings (`[build.go]`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoBuildConfig {
+    /// Go package to build (default: ".").
+    #[serde(default = "default_go_package")]
+    pub package: String,
+    /// WIT directory (default: "wit").
+    #[serde(default = "default_wit_dir")]
+    pub wit: String,
+    /// World to target (default: "proxy").
+    #[serde(default = "default_wit_world")]
+    pub world: String,
+    /// TinyGo target: "wasip2" (default) builds a component, "wasip1" a
+    /// core module that is adapted to a component after the build.
+    #[serde(default = "default_go_target")]
+    pub target: String,
+    /// Go build tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Extra flags passed to `tinygo build`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
+impl Default for GoBuildConfig {
+    fn default() -> Self {
+        Self {
+            package: default_go_package(),
+            wit: default_wit_dir(),
+            world: default_wit_world(),
+            target: default_go_target(),
+            tags: Vec::new(),
+            args: Vec::new(),
+        }
+    }
+}
+
+/// componentize-py settings (`[build.python]`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PythonBuildConfig {
+    /// Module defining the exports (default: "app").
+    #[serde(default = "default_python_module")]
+    pub module: String,
+    /// WIT directory (default: "wit").
+    #[serde(default = "default_wit_dir")]
+    pub wit: String,
+    /// World to target (default: "proxy").
+    #[serde(default = "default_wit_world")]
+    pub world: String,
+    /// Extra directories searched for Python modules (`-p`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub python_path: Vec<String>,
+    /// Extra flags passed to `componentize-py componentize`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
+impl Default for PythonBuildConfig {
+    fn default() -> Self {
+        Self {
+            module: default_python_module(),
+            wit: default_wit_dir(),
+            world: default_wit_world(),
+            python_path: Vec::new(),
+            args: Vec::new(),
+        }
+    }
+}
+
 // =============================================================================
 // Load Balancer Configuration
 // =============================================================================
@@ -324,6 +881,23 @@ pub struct LbConfig {
     /// Enable this when all backends support HTTP/2 for better performance.
     #[serde(default = "default_http2_only")]
     pub http2_only: bool,
+    /// Shadow backend that receives a copy of sampled requests (optional).
+    /// Shadow responses are discarded and never reach the client.
+    #[serde(default)]
+    pub mirror_backend: Option<String>,
+    /// Percentage of requests to mirror to `mirror_backend` (default: 100).
+    #[serde(default = "default_mirror_percent")]
+    pub mirror_percent: u8,
+    /// PEM CA bundle for `https://` backends (optional).
+    /// When set, only these CAs are trusted for backend certificates.
+    #[serde(default)]
+    pub backend_ca_cert: Option<String>,
+    /// PEM client certificate presented to `https://` backends (optional).
+    #[serde(default)]
+    pub backend_client_cert: Option<String>,
+    /// PEM private key for `backend_client_cert` (optional).
+    #[serde(default)]
+    pub backend_client_key: Option<String>,
 }
 
 impl Default for LbConfig {
@@ -341,6 +915,11 @@ impl Default for LbConfig {
             pool_idle_timeout_secs: default_pool_idle_timeout_secs(),
             tcp_keepalive_secs: default_tcp_keepalive_secs(),
             http2_only: default_http2_only(),
+            mirror_backend: None,
+            mirror_percent: default_mirror_percent(),
+            backend_ca_cert: None,
+            backend_client_cert: None,
+            backend_client_key: None,
         }
     }
 }
@@ -359,7 +938,7 @@ pub struct Project {
     pub description: Option<String>,
     #[serde(default)]
     pub authors: Vec<Author>,
-    /// Project language: rust (default), typescript, python, c
+    /// Project language: rust (default), typescript, go, python
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
 }
@@ -7,6 +7,7 @@ use std::path::Path;
 use thiserror::Error;
 use url::Url;
 
+use super::profiles::{PROFILE_SECTIONS, invalid_section};
 use super::types::{Dependency, DependencyDetail, Manifest};
 
 // =============================================================================
@@ -52,6 +53,19 @@ pub enum ValidationError {
 
     #[error("{0}")]
     DependencyError(String),
+
+    #[error(
+        "Profile '{profile}' cannot override [{section}]\n  \
+         Profiles may only override: {allowed}"
+    )]
+    InvalidProfileSection {
+        profile: String,
+        section: String,
+        allowed: String,
+    },
+
+    #[error("Invalid alias '{alias}' in [server.aliases]: {reason}")]
+    InvalidAlias { alias: String, reason: String },
 }
 
 // =============================================================================
@@ -68,6 +82,7 @@ impl Manifest {
     /// - Server configuration is sensible
     /// - Component references exist (for path dependencies)
     /// - Dependencies have valid specifications
+    /// - Profiles only override supported sections
     ///
     /// # Errors
     ///
@@ -121,6 +136,38 @@ impl Manifest {
             }
         }
 
+        // 6. Validate profiles
+        for (profile, overrides) in &self.profiles {
+            if let Some(section) = invalid_section(overrides) {
+                errors.push(ValidationError::InvalidProfileSection {
+                    profile: profile.clone(),
+                    section: section.to_string(),
+                    allowed: PROFILE_SECTIONS.join(", "),
+                });
+            }
+        }
+
+        // 7. Validate route aliases
+        for (alias, target) in &self.server.aliases {
+            let reason = if !Self::is_valid_name(alias) {
+                Some("alias names follow the same rules as module names".to_string())
+            } else if !is_safe_module_file(target.module_name()) {
+                Some(format!("invalid module '{}'", target.module))
+            } else {
+                target
+                    .successor
+                    .as_ref()
+                    .filter(|successor| !self.server.aliases.contains_key(*successor))
+                    .map(|successor| format!("successor '{successor}' is not a defined alias"))
+            };
+            if let Some(reason) = reason {
+                errors.push(ValidationError::InvalidAlias {
+                    alias: alias.clone(),
+                    reason,
+                });
+            }
+        }
+
         // If there are errors, format them nicely and return
         if !errors.is_empty() {
             let error_list = errors
@@ -437,3 +484,14 @@ fn is_valid_git_url(git: &str) -> bool {
         matches!(scheme, "http" | "https" | "git" | "ssh") && url.host().is_some()
     })
 }
+
+/// Check that a module name is a plain file stem inside the modules directory.
+fn is_safe_module_file(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.contains("..")
+}
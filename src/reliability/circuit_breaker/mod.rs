@@ -42,10 +42,12 @@ mod property_tests;
 // Re-export public types for convenience
 pub use config::CircuitBreakerConfig;
 pub use error::{CircuitOpenError, CircuitOpenReason};
-pub use state::CircuitState;
+pub use state::{CircuitSnapshot, CircuitState};
 
 use moka::ops::compute::Op;
 use moka::sync::Cache as MokaCache;
+use parking_lot::RwLock;
+use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Instant;
@@ -58,7 +60,15 @@ use tracing::{info, warn};
 #[derive(Clone)]
 pub struct CircuitBreaker {
     states: MokaCache<Arc<str>, CircuitState>,
-    config: CircuitBreakerConfig,
+    /// Thresholds for all keys and for individual keys, replaced by
+    /// [`reconfigure`](Self::reconfigure).
+    configs: Arc<RwLock<Configs>>,
+}
+
+/// The breaker's configuration and its overrides for individual keys.
+struct Configs {
+    default: CircuitBreakerConfig,
+    keys: HashMap<String, CircuitBreakerConfig>,
 }
 
 impl CircuitBreaker {
@@ -74,7 +84,46 @@ impl CircuitBreaker {
             .time_to_idle(config.idle_timeout)
             .build();
 
-        Self { states, config }
+        Self {
+            states,
+            configs: Arc::new(RwLock::new(Configs {
+                default: config,
+                keys: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Use `config` for `key` instead of the breaker's configuration.
+    ///
+    /// Only `failure_threshold`, `timeout` and `probe_timeout` apply per key;
+    /// `max_tracked_keys` and `idle_timeout` are shared by all keys.
+    #[must_use]
+    pub fn with_key_config(self, key: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        self.configs.write().keys.insert(key.into(), config);
+        self
+    }
+
+    /// Replace the configuration and the per-key overrides, keeping the
+    /// state of every circuit.
+    ///
+    /// As with [`with_key_config`](Self::with_key_config), only
+    /// `failure_threshold`, `timeout` and `probe_timeout` change:
+    /// `max_tracked_keys` and `idle_timeout` stay as created.
+    pub fn reconfigure(
+        &self,
+        config: CircuitBreakerConfig,
+        keys: HashMap<String, CircuitBreakerConfig>,
+    ) {
+        *self.configs.write() = Configs {
+            default: config,
+            keys,
+        };
+    }
+
+    /// Configuration applied to `key`.
+    pub fn config_for(&self, key: &str) -> CircuitBreakerConfig {
+        let configs = self.configs.read();
+        configs.keys.get(key).unwrap_or(&configs.default).clone()
     }
 
     /// Check if a request should be allowed.
@@ -85,8 +134,9 @@ impl CircuitBreaker {
     /// In `HalfOpen` state, only ONE probe request is allowed. Subsequent requests
     /// are rejected until the probe completes (via `record_success` or `record_failure`).
     pub fn check_request(&self, key: &str) -> Result<(), CircuitOpenError> {
-        let timeout = self.config.timeout;
-        let probe_timeout = self.config.probe_timeout;
+        let config = self.config_for(key);
+        let timeout = config.timeout;
+        let probe_timeout = config.probe_timeout;
 
         // Use atomic compute to check and potentially transition state
         let mut allowed = true;
@@ -217,7 +267,7 @@ impl CircuitBreaker {
     /// - `HalfOpen`: Reopens the circuit (recovery failed)
     /// - Open: Extends the open period
     pub fn record_failure(&self, key: &str) {
-        let threshold = self.config.failure_threshold;
+        let threshold = self.config_for(key).failure_threshold;
         let cache_key: Arc<str> = Arc::from(key);
 
         self.states
@@ -282,7 +332,6 @@ impl CircuitBreaker {
     }
 
     /// Get the current state for a key.
-    #[allow(dead_code)] // Inspection method for debugging/monitoring
     pub fn get_state(&self, key: &str) -> CircuitState {
         self.states.get(key).unwrap_or_default()
     }
@@ -299,7 +348,6 @@ impl CircuitBreaker {
     }
 
     /// Reset circuit for a key.
-    #[allow(dead_code)] // Inspection method for debugging/monitoring
     pub fn reset(&self, key: &str) {
         let cache_key: Arc<str> = Arc::from(key);
         self.states
@@ -314,6 +362,30 @@ impl CircuitBreaker {
             });
     }
 
+    /// Open the circuit for a key, whatever its current state.
+    ///
+    /// Requests are rejected until the key's recovery timeout elapses, after
+    /// which a probe is let through as usual. Failures counted so far are kept.
+    pub fn force_open(&self, key: &str) {
+        let cache_key: Arc<str> = Arc::from(key);
+        self.states
+            .entry_by_ref(&cache_key)
+            .and_compute_with(|entry| {
+                let failure_count = match entry.map(moka::Entry::into_value) {
+                    Some(
+                        CircuitState::Closed { failure_count }
+                        | CircuitState::Open { failure_count, .. },
+                    ) => failure_count,
+                    Some(CircuitState::HalfOpen { .. }) | None => 0,
+                };
+                warn!("Manually opening circuit breaker for '{}'", key);
+                Op::Put(CircuitState::Open {
+                    opened_at: Instant::now(),
+                    failure_count,
+                })
+            });
+    }
+
     /// Get the number of tracked keys.
     #[allow(dead_code)] // Inspection method for debugging/monitoring
     pub fn tracked_count(&self) -> u64 {
@@ -324,6 +396,7 @@ impl CircuitBreaker {
     ///
     /// Returns a vector of `(key, state_name)` pairs where `state_name` is
     /// `closed`, `open`, or `half_open`.
+    #[allow(dead_code)] // Superseded by `snapshot` in the runtime
     pub fn get_all_states(&self) -> Vec<(String, String)> {
         self.states.run_pending_tasks();
         self.states
@@ -339,6 +412,20 @@ impl CircuitBreaker {
             .collect()
     }
 
+    /// Snapshot every tracked circuit, with failure counts and open times.
+    pub fn snapshot(&self) -> Vec<CircuitSnapshot> {
+        self.states.run_pending_tasks();
+        self.states
+            .iter()
+            .map(|(k, v)| CircuitSnapshot::new(k.deref().to_string(), &v))
+            .collect()
+    }
+
+    /// Snapshot the circuit for a key (closed if it is not tracked).
+    pub fn snapshot_key(&self, key: &str) -> CircuitSnapshot {
+        CircuitSnapshot::new(key.to_string(), &self.get_state(key))
+    }
+
     /// Get access to the internal states cache (for testing).
     #[cfg(test)]
     pub(crate) fn states(&self) -> &MokaCache<Arc<str>, CircuitState> {
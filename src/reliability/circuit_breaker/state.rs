@@ -5,7 +5,7 @@
 //! - **Open**: Too many failures, requests rejected
 //! - **`HalfOpen`**: Testing recovery - only ONE probe request allowed
 
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
 
 /// Circuit breaker state.
 #[derive(Debug, Clone)]
@@ -58,3 +58,40 @@ impl Default for CircuitState {
         Self::Closed { failure_count: 0 }
     }
 }
+
+/// Point-in-time view of one key's circuit, for monitoring and admin APIs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitSnapshot {
+    /// Circuit key (module name).
+    pub key: String,
+    /// State name: `closed`, `open`, or `half_open`.
+    pub state: &'static str,
+    /// Consecutive failures counted by the circuit.
+    pub failure_count: u32,
+    /// When the circuit opened (only for open circuits).
+    pub opened_at: Option<SystemTime>,
+}
+
+impl CircuitSnapshot {
+    /// Snapshot `state` for `key`.
+    pub(crate) fn new(key: String, state: &CircuitState) -> Self {
+        let (name, failure_count, opened_at) = match state {
+            CircuitState::Closed { failure_count } => ("closed", *failure_count, None),
+            CircuitState::Open {
+                opened_at,
+                failure_count,
+            } => (
+                "open",
+                *failure_count,
+                SystemTime::now().checked_sub(opened_at.elapsed()),
+            ),
+            CircuitState::HalfOpen { .. } => ("half_open", 0, None),
+        };
+        Self {
+            key,
+            state: name,
+            failure_count,
+            opened_at,
+        }
+    }
+}
@@ -504,6 +504,65 @@ fn test_get_all_states() {
     );
 }
 
+#[test]
+fn test_force_open_rejects_until_timeout() {
+    let config = CircuitBreakerConfig {
+        failure_threshold: 5,
+        timeout: Duration::from_millis(50),
+        ..Default::default()
+    };
+    let cb = CircuitBreaker::with_config(config);
+
+    cb.record_failure("test");
+    cb.force_open("test");
+    assert!(cb.is_open("test"));
+    assert_eq!(cb.failure_count("test"), 1);
+    assert!(cb.check_request("test").is_err());
+
+    // Untracked keys can be opened too
+    cb.force_open("fresh");
+    assert!(cb.check_request("fresh").is_err());
+
+    // Recovers through the usual half-open probe
+    thread::sleep(Duration::from_millis(60));
+    assert!(cb.check_request("test").is_ok());
+    cb.record_success("test");
+    assert_eq!(
+        cb.get_state("test"),
+        CircuitState::Closed { failure_count: 0 }
+    );
+}
+
+#[test]
+fn test_snapshot_reports_failures_and_opened_at() {
+    let config = CircuitBreakerConfig {
+        failure_threshold: 2,
+        ..Default::default()
+    };
+    let cb = CircuitBreaker::with_config(config);
+
+    cb.record_failure("closed-service");
+    cb.record_failure("open-service");
+    cb.record_failure("open-service");
+
+    let mut snapshots = cb.snapshot();
+    snapshots.sort_by(|a, b| a.key.cmp(&b.key));
+    assert_eq!(snapshots.len(), 2);
+
+    assert_eq!(snapshots[0].key, "closed-service");
+    assert_eq!(snapshots[0].state, "closed");
+    assert_eq!(snapshots[0].failure_count, 1);
+    assert!(snapshots[0].opened_at.is_none());
+
+    assert_eq!(snapshots[1].key, "open-service");
+    assert_eq!(snapshots[1].state, "open");
+    assert_eq!(snapshots[1].failure_count, 2);
+    let opened_at = snapshots[1].opened_at.expect("open circuit has opened_at");
+    assert!(opened_at.elapsed().unwrap_or_default() < Duration::from_secs(5));
+
+    assert_eq!(cb.snapshot_key("unknown").state, "closed");
+}
+
 // =========================================================================
 // ERROR TYPE TESTS
 // =========================================================================
@@ -735,3 +794,57 @@ fn test_failure_count_saturates() {
     let count = cb.failure_count("test");
     assert!(count > 0);
 }
+
+#[test]
+fn test_key_config_overrides_default() {
+    let cb = CircuitBreaker::new().with_key_config(
+        "flaky",
+        CircuitBreakerConfig {
+            failure_threshold: 2,
+            timeout: Duration::from_millis(50),
+            ..Default::default()
+        },
+    );
+    assert_eq!(cb.config_for("flaky").failure_threshold, 2);
+    assert_eq!(
+        cb.config_for("other").failure_threshold,
+        constants::CIRCUIT_BREAKER_FAILURE_THRESHOLD
+    );
+
+    for _ in 0..2 {
+        cb.record_failure("flaky");
+        cb.record_failure("other");
+    }
+    assert!(cb.is_open("flaky"));
+    assert!(!cb.is_open("other"));
+
+    // Recovers after its own timeout
+    thread::sleep(Duration::from_millis(100));
+    assert!(cb.check_request("flaky").is_ok());
+}
+
+#[test]
+fn test_reconfigure_keeps_circuit_states() {
+    let cb = CircuitBreaker::new();
+    cb.record_failure("flaky");
+
+    cb.reconfigure(
+        CircuitBreakerConfig {
+            failure_threshold: 3,
+            ..Default::default()
+        },
+        HashMap::from([(
+            "flaky".to_string(),
+            CircuitBreakerConfig {
+                failure_threshold: 2,
+                ..Default::default()
+            },
+        )]),
+    );
+    assert_eq!(cb.config_for("other").failure_threshold, 3);
+    assert_eq!(cb.failure_count("flaky"), 1);
+
+    // The failure recorded before counts toward the new threshold
+    cb.record_failure("flaky");
+    assert!(cb.is_open("flaky"));
+}
@@ -48,6 +48,7 @@ pub mod security;
 // Note: CircuitBreakerConfig and CircuitState are used by tests and external callers
 #[allow(unused_imports)]
 pub use circuit_breaker::{
-    CircuitBreaker, CircuitBreakerConfig, CircuitOpenError, CircuitOpenReason, CircuitState,
+    CircuitBreaker, CircuitBreakerConfig, CircuitOpenError, CircuitOpenReason, CircuitSnapshot,
+    CircuitState,
 };
 pub use security::is_http_host_allowed;
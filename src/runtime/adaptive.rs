@@ -0,0 +1,210 @@
+//! Adaptive per-module concurrency limits (AIMD).
+//!
+//! With `adaptive_concurrency` enabled, `max_per_module_requests` becomes a
+//! ceiling: each module's limit starts there and follows its own latency and
+//! error signal. A failed execution (trap, timeout) or one taking more than
+//! twice the module's smoothed latency is congestion, and cuts the limit by
+//! 10% (at most once per `limit` executions). Otherwise the limit grows by
+//! one after `limit` good executions, back up to the ceiling.
+//!
+//! The smoothed latency follows the module's workload, so handlers whose
+//! per-request cost drifts are not throttled for it; only sudden slowdowns
+//! and failures shrink the limit. Limits are applied to the module's
+//! semaphore: permits are added on increase and forgotten as they are
+//! released on decrease, so in-flight requests are never interrupted.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Factor applied to the limit on congestion.
+const BACKOFF: f64 = 0.9;
+/// Latency above this multiple of the smoothed latency is congestion.
+const LATENCY_TOLERANCE: f64 = 2.0;
+/// Weight of a new sample in the smoothed latency.
+const SMOOTHING: f64 = 0.1;
+/// Successful samples before latency is used as a signal.
+const WARMUP_SAMPLES: u64 = 10;
+
+/// Limit and latency estimate for one module.
+#[derive(Debug)]
+struct ModuleLimit {
+    limit: usize,
+    /// Permits still to be removed from the semaphore after a decrease.
+    debt: usize,
+    latency_ms: f64,
+    samples: u64,
+    /// Good executions since the limit last changed.
+    good: usize,
+    /// Executions until the limit may be decreased again.
+    cooldown: usize,
+}
+
+/// Concurrency limits for all modules.
+#[derive(Debug)]
+pub(crate) struct AdaptiveLimits {
+    min: usize,
+    max: usize,
+    modules: Mutex<HashMap<String, ModuleLimit>>,
+}
+
+impl AdaptiveLimits {
+    /// Limits between `min` and `max` (both at least 1).
+    pub(crate) fn new(min: usize, max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            min: min.clamp(1, max),
+            max,
+            modules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record an execution of `module` whose permits come from `semaphore`.
+    pub(crate) fn record(&self, module: &str, semaphore: &Semaphore, latency: Duration, ok: bool) {
+        let mut modules = self.modules.lock();
+        let state = modules
+            .entry(module.to_string())
+            .or_insert_with(|| ModuleLimit {
+                limit: self.max,
+                debt: 0,
+                latency_ms: 0.0,
+                samples: 0,
+                good: 0,
+                cooldown: 0,
+            });
+
+        let sample_ms = latency.as_secs_f64() * 1000.0;
+        let slow =
+            state.samples >= WARMUP_SAMPLES && sample_ms > state.latency_ms * LATENCY_TOLERANCE;
+        if ok {
+            state.latency_ms = if state.samples == 0 {
+                sample_ms
+            } else {
+                state.latency_ms + SMOOTHING * (sample_ms - state.latency_ms)
+            };
+            state.samples += 1;
+        }
+        state.cooldown = state.cooldown.saturating_sub(1);
+
+        if !ok || slow {
+            if state.cooldown == 0 && state.limit > self.min {
+                let reduced =
+                    ((state.limit as f64 * BACKOFF) as usize).clamp(self.min, state.limit - 1);
+                state.debt += state.limit - reduced;
+                state.limit = reduced;
+                state.cooldown = reduced;
+                state.good = 0;
+                tracing::debug!(
+                    "Concurrency limit for module '{}' decreased to {}",
+                    module,
+                    reduced
+                );
+            }
+        } else {
+            state.good += 1;
+            if state.good >= state.limit && state.limit < self.max {
+                state.limit += 1;
+                state.good = 0;
+                if state.debt > 0 {
+                    state.debt -= 1;
+                } else {
+                    semaphore.add_permits(1);
+                }
+                tracing::debug!(
+                    "Concurrency limit for module '{}' increased to {}",
+                    module,
+                    state.limit
+                );
+            }
+        }
+
+        if state.debt > 0 {
+            state.debt -= semaphore.forget_permits(state.debt);
+        }
+    }
+
+    /// Current limit of `module` (the ceiling until it has executed).
+    pub(crate) fn limit(&self, module: &str) -> usize {
+        self.modules
+            .lock()
+            .get(module)
+            .map_or(self.max, |state| state.limit)
+    }
+
+    /// Current limit of every module that has executed.
+    pub(crate) fn limits(&self) -> Vec<(String, usize)> {
+        self.modules
+            .lock()
+            .iter()
+            .map(|(module, state)| (module.clone(), state.limit))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAST: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn test_errors_decrease_and_successes_restore() {
+        let limits = AdaptiveLimits::new(2, 10);
+        let semaphore = Semaphore::new(10);
+        assert_eq!(limits.limit("m"), 10);
+
+        limits.record("m", &semaphore, FAST, false);
+        assert_eq!(limits.limit("m"), 9);
+        assert_eq!(semaphore.available_permits(), 9);
+
+        // Cooldown: a burst of failures only backs off once per window
+        for _ in 0..8 {
+            limits.record("m", &semaphore, FAST, false);
+        }
+        assert_eq!(limits.limit("m"), 9);
+        for _ in 0..100 {
+            limits.record("m", &semaphore, FAST, false);
+        }
+        assert_eq!(limits.limit("m"), 2);
+        assert_eq!(semaphore.available_permits(), 2);
+
+        for _ in 0..1000 {
+            limits.record("m", &semaphore, FAST, true);
+        }
+        assert_eq!(limits.limit("m"), 10);
+        assert_eq!(semaphore.available_permits(), 10);
+    }
+
+    #[test]
+    fn test_latency_spike_decreases() {
+        let limits = AdaptiveLimits::new(1, 10);
+        let semaphore = Semaphore::new(10);
+        for _ in 0..WARMUP_SAMPLES {
+            limits.record("m", &semaphore, FAST, true);
+        }
+        assert_eq!(limits.limit("m"), 10);
+
+        limits.record("m", &semaphore, Duration::from_millis(50), true);
+        assert_eq!(limits.limit("m"), 9);
+        assert_eq!(limits.limits(), vec![("m".to_string(), 9)]);
+    }
+
+    #[test]
+    fn test_decrease_waits_for_in_flight_permits() {
+        let limits = AdaptiveLimits::new(1, 2);
+        let semaphore = Semaphore::new(2);
+        let first = semaphore.try_acquire().unwrap();
+        let second = semaphore.try_acquire().unwrap();
+
+        limits.record("m", &semaphore, FAST, false);
+        assert_eq!(limits.limit("m"), 1);
+        drop(first);
+        drop(second);
+        assert_eq!(semaphore.available_permits(), 2);
+
+        // The released permit is forgotten on the next execution
+        limits.record("m", &semaphore, FAST, false);
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+}
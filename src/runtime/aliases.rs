@@ -0,0 +1,104 @@
+//! Versioned route aliases.
+//!
+//! `[server.aliases]` maps a route segment to a module file, so
+//! `/run/v1/*` and `/run/v2/*` can be served by `orders-1.4.wasm` and
+//! `orders-2.0.wasm` without renaming modules. Deprecated aliases answer with
+//! `Deprecation`, `Sunset`, and `Link: rel="successor-version"` headers
+//! (RFC 9745, RFC 8594), and every aliased request is counted per alias.
+
+use super::{RUN_PREFIX, SharedState};
+use hyper::header::{HeaderMap, HeaderName, HeaderValue};
+
+impl SharedState {
+    /// Map a `/run/<name>` route segment through the aliases.
+    ///
+    /// Returns the module to load and, if `name` is an alias, the alias name.
+    pub(crate) fn resolve_alias(&self, name: String) -> (String, Option<String>) {
+        match self.config.aliases.get(&name) {
+            Some(alias) => {
+                self.stats.record_alias_request(&name);
+                (alias.module_name().to_string(), Some(name))
+            },
+            None => (name, None),
+        }
+    }
+
+    /// Add deprecation headers for a response served through `alias`.
+    pub(crate) fn add_alias_headers(&self, alias: &str, headers: &mut HeaderMap) {
+        let Some(config) = self.config.aliases.get(alias) else {
+            return;
+        };
+        if !config.deprecated {
+            return;
+        }
+
+        headers.insert(
+            HeaderName::from_static("deprecation"),
+            HeaderValue::from_static("true"),
+        );
+        if let Some(sunset) = config.sunset.as_deref()
+            && let Ok(value) = HeaderValue::from_str(sunset)
+        {
+            headers.insert(HeaderName::from_static("sunset"), value);
+        }
+        if let Some(successor) = config.successor.as_deref()
+            && let Ok(value) = HeaderValue::from_str(&format!(
+                "<{RUN_PREFIX}{successor}/>; rel=\"successor-version\""
+            ))
+        {
+            headers.append(hyper::header::LINK, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::manifest::ModuleAlias;
+    use crate::runtime::Runtime;
+
+    #[test]
+    fn test_aliases_resolve_and_add_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        // Modules load lazily, the directory only has to contain one
+        std::fs::write(dir.path().join("orders-2.0.wasm"), b"").unwrap();
+        let runtime = Runtime::builder()
+            .modules_dir(dir.path())
+            .alias(
+                "v1",
+                ModuleAlias {
+                    deprecated: true,
+                    sunset: Some("Wed, 01 Jul 2026 00:00:00 GMT".to_string()),
+                    successor: Some("v2".to_string()),
+                    ..ModuleAlias::new("orders-1.4.wasm")
+                },
+            )
+            .alias("v2", ModuleAlias::new("orders-2.0"))
+            .build()
+            .unwrap();
+        let shared = runtime.shared();
+
+        assert_eq!(
+            shared.resolve_alias("v1".to_string()),
+            ("orders-1.4".to_string(), Some("v1".to_string()))
+        );
+        assert_eq!(
+            shared.resolve_alias("orders".to_string()),
+            ("orders".to_string(), None)
+        );
+
+        let mut headers = hyper::HeaderMap::new();
+        shared.add_alias_headers("v1", &mut headers);
+        assert_eq!(headers["deprecation"], "true");
+        assert_eq!(headers["sunset"], "Wed, 01 Jul 2026 00:00:00 GMT");
+        assert_eq!(headers["link"], "</run/v2/>; rel=\"successor-version\"");
+
+        let mut headers = hyper::HeaderMap::new();
+        shared.add_alias_headers("v2", &mut headers);
+        assert!(headers.is_empty());
+
+        let metrics = shared.get_prometheus_metrics();
+        assert!(metrics.contains(
+            "mik_alias_requests_total{alias=\"v1\",module=\"orders-1.4\",deprecated=\"true\"} 1"
+        ));
+    }
+}
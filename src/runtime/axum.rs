@@ -0,0 +1,139 @@
+//! Embedding a [`Runtime`] in an axum application (`axum` feature).
+//!
+//! [`router`] serves every request it receives through the runtime, so it can
+//! be nested under a prefix of an existing app:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use mik::runtime::Runtime;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let runtime = Arc::new(Runtime::builder().modules_dir("modules/").build()?);
+//!
+//! let app = axum::Router::new()
+//!     .route("/", axum::routing::get(|| async { "my app" }))
+//!     .nest("/wasm", mik::runtime::axum::router(runtime)); // /wasm/run/hello/
+//!
+//! let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
+//! axum::serve(listener, app).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! For custom handlers, [`Request`] is an extractor and [`Response`]
+//! implements `IntoResponse`.
+
+use crate::runtime::error::Error;
+use crate::runtime::{Request, Response, Runtime};
+use ::axum::Router;
+use ::axum::body::{Body, Bytes};
+use ::axum::extract::rejection::BytesRejection;
+use ::axum::extract::{DefaultBodyLimit, FromRequest, State};
+use ::axum::response::IntoResponse;
+use std::sync::Arc;
+
+/// A router passing every request to `runtime`.
+///
+/// Request bodies are limited to the runtime's `max_body_size_mb`. Runtime
+/// errors become JSON error responses with the error's status code, as they
+/// do in [`Server`](crate::runtime::Server).
+pub fn router(runtime: Arc<Runtime>) -> Router {
+    let limit = runtime.shared.max_body_size_bytes;
+    Router::new()
+        .fallback(handle)
+        .layer(DefaultBodyLimit::max(limit))
+        .with_state(runtime)
+}
+
+async fn handle(State(runtime): State<Arc<Runtime>>, request: Request) -> Response {
+    runtime
+        .handle_request(request)
+        .await
+        .unwrap_or_else(|err| error_response(&err))
+}
+
+fn error_response(err: &anyhow::Error) -> Response {
+    let status = err.downcast_ref::<Error>().map_or(500, Error::status_code);
+    let body = serde_json::json!({
+        "error": err.to_string(),
+        "status": status
+    });
+    Response::new(status).with_json(body.to_string())
+}
+
+impl<S: Send + Sync> FromRequest<S> for Request {
+    type Rejection = BytesRejection;
+
+    async fn from_request(
+        request: ::axum::extract::Request,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let (parts, body) = request.into_parts();
+        let body = Bytes::from_request(
+            ::axum::extract::Request::from_parts(parts.clone(), body),
+            state,
+        )
+        .await?;
+        Ok(Self::from(::axum::http::Request::from_parts(parts, body)))
+    }
+}
+
+impl IntoResponse for Response {
+    fn into_response(self) -> ::axum::response::Response {
+        match ::axum::http::Response::<Vec<u8>>::try_from(self) {
+            Ok(response) => response.map(Body::from),
+            Err(err) => (
+                ::axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Invalid response from handler: {err}"),
+            )
+                .into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_router_serves_runtime() {
+        let modules =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        if !modules.join("echo.wasm").exists() {
+            return;
+        }
+        let runtime = Arc::new(Runtime::builder().modules_dir(modules).build().unwrap());
+        let app = Router::new().nest("/wasm", router(runtime));
+
+        let response = app
+            .clone()
+            .oneshot(
+                ::axum::http::Request::post("/wasm/run/echo/")
+                    .header("host", "localhost")
+                    .body(Body::from("hello"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let response = app
+            .oneshot(
+                ::axum::http::Request::get("/wasm/run/missing/")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+    }
+
+    #[test]
+    fn test_invalid_response_header() {
+        let response = Response::ok()
+            .with_header("bad header", "x")
+            .into_response();
+        assert_eq!(response.status(), 500);
+    }
+}
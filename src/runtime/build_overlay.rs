@@ -0,0 +1,119 @@
+//! Build error overlay for `mik dev`.
+//!
+//! While the project's last build failed, `mik dev` keeps the compiler output
+//! in `build_error_file`. Module routes then answer with that error instead
+//! of running the stale module: an HTML page for browsers, plain text for
+//! everything else, both with status 500 and `X-Mik-Build-Error: true`. The
+//! file is removed when a build succeeds, and the rebuilt module is served.
+
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::header::{ACCEPT, CONTENT_TYPE};
+use hyper::{HeaderMap, Response};
+use std::path::Path;
+
+/// Header marking a build error response.
+pub const BUILD_ERROR_HEADER: &str = "X-Mik-Build-Error";
+
+/// The overlay for the build error in `file`, if there is one.
+pub(crate) async fn response(file: &Path, headers: &HeaderMap) -> Option<Response<Full<Bytes>>> {
+    let error = tokio::fs::read_to_string(file).await.ok()?;
+    if error.trim().is_empty() {
+        return None;
+    }
+
+    let html = headers
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+    let (content_type, body) = if html {
+        ("text/html; charset=utf-8", render_html(&error))
+    } else {
+        (
+            "text/plain; charset=utf-8",
+            format!("Build failed\n\n{error}"),
+        )
+    };
+    Response::builder()
+        .status(500)
+        .header(CONTENT_TYPE, content_type)
+        .header(BUILD_ERROR_HEADER, "true")
+        .header("Cache-Control", "no-store")
+        .body(Full::new(Bytes::from(body)))
+        .ok()
+}
+
+/// The overlay page for `error`.
+fn render_html(error: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Build failed</title>\
+         <style>body{{margin:0;background:#1e1e1e;color:#eee;font-family:sans-serif}}\
+         h1{{margin:0;padding:16px 24px;background:#b3261e;font-size:18px}}\
+         pre{{padding:16px 24px;white-space:pre-wrap;font-size:13px;line-height:1.4}}\
+         p{{padding:0 24px;color:#aaa}}</style></head>\
+         <body><h1>Build failed</h1><pre>{}</pre>\
+         <p>Fix the error and save: mik dev rebuilds and reloads automatically.</p>\
+         </body></html>\n",
+        escape(error)
+    )
+}
+
+/// Escape `text` for HTML content.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    async fn body(response: Response<Full<Bytes>>) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_no_overlay_without_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("build-error");
+        assert!(response(&file, &HeaderMap::new()).await.is_none());
+
+        std::fs::write(&file, "\n").unwrap();
+        assert!(response(&file, &HeaderMap::new()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_overlay_text_and_html() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("build-error");
+        std::fs::write(&file, "error: expected `<T>`").unwrap();
+
+        let text = response(&file, &HeaderMap::new()).await.unwrap();
+        assert_eq!(text.status(), 500);
+        assert_eq!(text.headers()[BUILD_ERROR_HEADER], "true");
+        assert!(body(text).await.contains("error: expected `<T>`"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, "text/html,*/*".parse().unwrap());
+        let html = response(&file, &headers).await.unwrap();
+        assert!(
+            html.headers()[CONTENT_TYPE]
+                .to_str()
+                .unwrap()
+                .starts_with("text/html")
+        );
+        let page = body(html).await;
+        assert!(page.contains("expected `&lt;T&gt;`"), "{page}");
+    }
+}
@@ -48,8 +48,11 @@
 //! ```
 
 use crate::constants;
-use crate::manifest::{Manifest, ServerConfig};
-use crate::runtime::host_config::HostConfig;
+use crate::manifest::{
+    ChaosPolicy, CircuitBreakerPolicy, EgressQuota, Manifest, ModuleAlias, Preopen,
+    ScriptCapabilities, ServerConfig, TenantLimits,
+};
+use crate::runtime::host_config::{ConfigError, HostConfig};
 use crate::runtime::{
     DEFAULT_CACHE_SIZE, DEFAULT_EXECUTION_TIMEOUT_SECS, DEFAULT_MAX_CACHE_MB,
     DEFAULT_MAX_CONCURRENT_REQUESTS, DEFAULT_MAX_PER_MODULE_REQUESTS, DEFAULT_MEMORY_LIMIT_BYTES,
@@ -57,6 +60,7 @@ use crate::runtime::{
 };
 use anyhow::{Context, Result};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tracing::info;
@@ -66,6 +70,9 @@ use super::{Host, Runtime};
 /// Default max request body size in MB.
 const DEFAULT_MAX_BODY_SIZE_MB: usize = constants::MAX_BODY_SIZE_BYTES / (1024 * 1024);
 
+/// Default max uploaded module size in MB.
+const DEFAULT_MAX_MODULE_SIZE_MB: usize = constants::MAX_MODULE_SIZE_BYTES / (1024 * 1024);
+
 /// Partial mik.toml manifest - only reads what the host needs.
 #[derive(Debug, Deserialize)]
 struct PartialManifest {
@@ -77,11 +84,14 @@ struct PartialManifest {
 /// Note: This is separate from `crate::manifest::ServerConfig` as it includes
 /// additional parsing helpers like serde defaults.
 #[derive(Debug, Default, Deserialize)]
+#[allow(clippy::struct_excessive_bools)] // Independent [server] switches
 struct TomlServerConfig {
     #[serde(default = "default_auto")]
     auto: bool,
     #[serde(default = "default_port")]
     port: u16,
+    #[serde(default)]
+    listen: Vec<String>,
     #[serde(default = "default_modules_dir")]
     modules: String,
     #[serde(default)]
@@ -94,22 +104,74 @@ struct TomlServerConfig {
     max_cache_mb: usize,
     #[serde(default = "default_execution_timeout_secs")]
     execution_timeout_secs: u64,
+    #[serde(default)]
+    max_request_timeout_secs: u64,
+    #[serde(default)]
+    init_timeout_secs: u64,
+    #[serde(default)]
+    execution_retries: u32,
+    #[serde(default = "default_execution_retry_backoff_ms")]
+    execution_retry_backoff_ms: u64,
     #[serde(default = "default_memory_limit_bytes")]
     memory_limit_bytes: usize,
     #[serde(default)]
     max_concurrent_requests: usize,
     #[serde(default = "default_max_body_size_mb")]
     max_body_size_mb: usize,
+    #[serde(default = "default_max_module_size_mb")]
+    max_module_size_mb: usize,
     #[serde(default)]
     max_per_module_requests: usize,
+    #[serde(default)]
+    max_per_tenant_requests: usize,
+    #[serde(default)]
+    module_queue_depth: usize,
+    #[serde(default = "default_module_queue_timeout_ms")]
+    module_queue_timeout_ms: u64,
+    #[serde(default)]
+    adaptive_concurrency: bool,
+    #[serde(default)]
+    adaptive_concurrency_min: usize,
     #[serde(default = "default_shutdown_timeout_secs")]
     shutdown_timeout_secs: u64,
     #[serde(default)]
+    log_level: Option<String>,
+    #[serde(default)]
     logging: bool,
     #[serde(default)]
     http_allowed: Vec<String>,
     #[serde(default)]
     scripts: Option<String>,
+    #[serde(default)]
+    aliases: BTreeMap<String, ModuleAlias>,
+    #[serde(default)]
+    egress_quotas: BTreeMap<String, EgressQuota>,
+    #[serde(default)]
+    circuit_breakers: BTreeMap<String, CircuitBreakerPolicy>,
+    #[serde(default)]
+    chaos: BTreeMap<String, ChaosPolicy>,
+    #[serde(default)]
+    tenant_limits: BTreeMap<String, TenantLimits>,
+    #[serde(default)]
+    trusted_proxies: Vec<String>,
+    #[serde(default)]
+    proxy_protocol: bool,
+    #[serde(default)]
+    tenant_uploads: bool,
+    #[serde(default)]
+    handlers_webhook: Option<String>,
+    #[serde(default)]
+    sql_modules: Vec<String>,
+    #[serde(default)]
+    script_capabilities: BTreeMap<String, ScriptCapabilities>,
+    #[serde(default)]
+    preopens: BTreeMap<String, Vec<Preopen>>,
+    #[serde(default)]
+    nn_models: BTreeMap<String, String>,
+    #[serde(default)]
+    nn_modules: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    oci_modules: BTreeMap<String, String>,
 }
 
 const fn default_auto() -> bool {
@@ -136,6 +198,18 @@ const fn default_max_body_size_mb() -> usize {
     DEFAULT_MAX_BODY_SIZE_MB
 }
 
+const fn default_max_module_size_mb() -> usize {
+    DEFAULT_MAX_MODULE_SIZE_MB
+}
+
+const fn default_execution_retry_backoff_ms() -> u64 {
+    constants::DEFAULT_EXECUTION_RETRY_BACKOFF_MS
+}
+
+const fn default_module_queue_timeout_ms() -> u64 {
+    constants::DEFAULT_MODULE_QUEUE_TIMEOUT_MS
+}
+
 const fn default_shutdown_timeout_secs() -> u64 {
     DEFAULT_SHUTDOWN_TIMEOUT_SECS
 }
@@ -166,9 +240,7 @@ impl SystemConfig {
         sys.refresh_memory();
 
         let total_memory_bytes = sys.total_memory();
-        let cpu_cores = std::thread::available_parallelism()
-            .map(std::num::NonZero::get)
-            .unwrap_or(4);
+        let cpu_cores = std::thread::available_parallelism().map_or(4, std::num::NonZero::get);
 
         // Cache size: ~1 module per 10MB of RAM, capped at 1000
         let cache_size = ((total_memory_bytes / (10 * 1024 * 1024)) as usize).clamp(10, 1000);
@@ -278,9 +350,9 @@ impl RuntimeBuilder {
 
     /// Load configuration from a manifest file.
     ///
-    /// This reads the `[server]` section from a mik.toml file and applies
-    /// the configuration to this builder. Subsequent builder methods can
-    /// override specific settings.
+    /// This reads the `[server]` section from a mik.toml file, with the
+    /// active profile merged in, and applies the configuration to this
+    /// builder. Subsequent builder methods can override specific settings.
     ///
     /// # Examples
     ///
@@ -301,15 +373,22 @@ impl RuntimeBuilder {
     /// Returns an error if:
     /// - The manifest file cannot be read
     /// - The manifest contains invalid TOML syntax
+    /// - The active profile (see [`crate::manifest::active_profile`]) is not
+    ///   defined in the manifest
     #[allow(clippy::wrong_self_convention)] // Builder method, not a From impl
     pub fn from_manifest_file(self, path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read {}", path.display()))?;
-        let manifest: PartialManifest = toml::from_str(&content)
+        let resolved = crate::manifest::read_resolved(path, false)?;
+        let manifest: PartialManifest = resolved
+            .table
+            .try_into()
             .with_context(|| format!("Failed to parse {}", path.display()))?;
 
-        Ok(self.apply_manifest_server_config(&manifest.server))
+        let mut builder = self.apply_manifest_server_config(&manifest.server);
+        builder.config.profile = resolved.profile;
+        builder.config.manifest_path = Some(path.to_path_buf());
+        builder.config.component_env = resolved.component_env;
+        Ok(builder)
     }
 
     /// Load configuration from a `Manifest` struct.
@@ -409,18 +488,61 @@ impl RuntimeBuilder {
             max_cache_bytes,
             static_dir: server.r#static.clone().map(PathBuf::from),
             port: server.port,
+            listen: server.listen.clone(),
             execution_timeout_secs: server.execution_timeout_secs,
+            max_request_timeout_secs: server.max_request_timeout_secs,
+            init_timeout_secs: server.init_timeout_secs,
+            execution_retries: server.execution_retries,
+            execution_retry_backoff_ms: server.execution_retry_backoff_ms,
             memory_limit_bytes: DEFAULT_MEMORY_LIMIT_BYTES,
             max_concurrent_requests,
             max_body_size_bytes: server.max_body_size_mb * 1024 * 1024,
+            max_module_size_bytes: server.max_module_size_mb * 1024 * 1024,
             max_per_module_requests,
+            max_per_tenant_requests: server.max_per_tenant_requests,
+            module_queue_depth: server.module_queue_depth,
+            module_queue_timeout_ms: server.module_queue_timeout_ms,
+            adaptive_concurrency: server.adaptive_concurrency,
+            adaptive_concurrency_min: server.adaptive_concurrency_min,
             shutdown_timeout_secs: server.shutdown_timeout_secs,
+            log_level: server.log_level.clone(),
             logging_enabled: server.logging,
             http_allowed: server.http_allowed.clone(),
             scripts_dir: server.scripts.clone().map(PathBuf::from),
             hot_reload: false,
             aot_cache_max_mb: 0,
             fuel_budget: None,
+            profile: None,
+            manifest_path: None,
+            component_env: None,
+            aliases: server.aliases.clone(),
+            egress_quotas: server.egress_quotas.clone(),
+            circuit_breakers: server.circuit_breakers.clone(),
+            chaos: server.chaos.clone(),
+            tenant_limits: server.tenant_limits.clone(),
+            trusted_proxies: server.trusted_proxies.clone(),
+            proxy_protocol: server.proxy_protocol,
+            tenant_uploads: server.tenant_uploads,
+            api_key: None,
+            module_signing_key: None,
+            handlers_webhook: server.handlers_webhook.clone(),
+            handlers_webhook_secret: None,
+            record_dir: None,
+            build_error_file: None,
+            dev_proxy: None,
+            dev_dashboard: None,
+            sql_modules: server.sql_modules.clone(),
+            sql_database: None,
+            script_capabilities: server.script_capabilities.clone(),
+            preopens: server.preopens.clone(),
+            nn_models: server
+                .nn_models
+                .iter()
+                .map(|(name, dir)| (name.clone(), PathBuf::from(dir)))
+                .collect(),
+            nn_modules: server.nn_modules.clone(),
+            oci_modules: server.oci_modules.clone(),
+            trace_scripts: false,
         };
 
         self
@@ -488,18 +610,61 @@ impl RuntimeBuilder {
             max_cache_bytes,
             static_dir: server.r#static.clone().map(PathBuf::from),
             port: server.port,
+            listen: server.listen.clone(),
             execution_timeout_secs: server.execution_timeout_secs,
+            max_request_timeout_secs: server.max_request_timeout_secs,
+            init_timeout_secs: server.init_timeout_secs,
+            execution_retries: server.execution_retries,
+            execution_retry_backoff_ms: server.execution_retry_backoff_ms,
             memory_limit_bytes: server.memory_limit_bytes,
             max_concurrent_requests,
             max_body_size_bytes: server.max_body_size_mb * 1024 * 1024,
+            max_module_size_bytes: server.max_module_size_mb * 1024 * 1024,
             max_per_module_requests,
+            max_per_tenant_requests: server.max_per_tenant_requests,
+            module_queue_depth: server.module_queue_depth,
+            module_queue_timeout_ms: server.module_queue_timeout_ms,
+            adaptive_concurrency: server.adaptive_concurrency,
+            adaptive_concurrency_min: server.adaptive_concurrency_min,
             shutdown_timeout_secs: server.shutdown_timeout_secs,
+            log_level: server.log_level.clone(),
             logging_enabled: server.logging,
             http_allowed: server.http_allowed.clone(),
             scripts_dir: server.scripts.clone().map(PathBuf::from),
             hot_reload: false,
             aot_cache_max_mb: 0,
             fuel_budget: None,
+            profile: None,
+            manifest_path: None,
+            component_env: None,
+            aliases: server.aliases.clone(),
+            egress_quotas: server.egress_quotas.clone(),
+            circuit_breakers: server.circuit_breakers.clone(),
+            chaos: server.chaos.clone(),
+            tenant_limits: server.tenant_limits.clone(),
+            trusted_proxies: server.trusted_proxies.clone(),
+            proxy_protocol: server.proxy_protocol,
+            tenant_uploads: server.tenant_uploads,
+            api_key: None,
+            module_signing_key: None,
+            handlers_webhook: server.handlers_webhook.clone(),
+            handlers_webhook_secret: None,
+            record_dir: None,
+            build_error_file: None,
+            dev_proxy: None,
+            dev_dashboard: None,
+            sql_modules: server.sql_modules.clone(),
+            sql_database: None,
+            script_capabilities: server.script_capabilities.clone(),
+            preopens: server.preopens.clone(),
+            nn_models: server
+                .nn_models
+                .iter()
+                .map(|(name, dir)| (name.clone(), PathBuf::from(dir)))
+                .collect(),
+            nn_modules: server.nn_modules.clone(),
+            oci_modules: server.oci_modules.clone(),
+            trace_scripts: false,
         };
 
         self
@@ -510,14 +675,257 @@ impl RuntimeBuilder {
     pub fn from_manifest_file_or_default(self) -> Self {
         let path = std::path::Path::new("mik.toml");
         if path.exists()
-            && let Ok(content) = std::fs::read_to_string(path)
-            && let Ok(manifest) = toml::from_str::<PartialManifest>(&content)
+            && let Ok(resolved) = crate::manifest::read_resolved(path, false)
+            && let Ok(manifest) = resolved.table.try_into::<PartialManifest>()
         {
-            return self.apply_manifest_server_config(&manifest.server);
+            let mut builder = self.apply_manifest_server_config(&manifest.server);
+            builder.config.profile = resolved.profile;
+            builder.config.manifest_path = Some(path.to_path_buf());
+            builder.config.component_env = resolved.component_env;
+            return builder;
         }
         self
     }
 
+    /// Serve `/run/<name>/*` with another module (versioned routes).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use mik::manifest::ModuleAlias;
+    /// use mik::runtime::Runtime;
+    ///
+    /// # fn example() -> anyhow::Result<()> {
+    /// let runtime = Runtime::builder()
+    ///     .alias("v1", ModuleAlias::new("orders-1.4"))
+    ///     .alias("v2", ModuleAlias::new("orders-2.0"))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn alias(mut self, name: impl Into<String>, alias: ModuleAlias) -> Self {
+        self.config.aliases.insert(name.into(), alias);
+        self
+    }
+
+    /// Limit the outgoing HTTP traffic of `module` (`"*"` for every module
+    /// without its own quota).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use mik::manifest::EgressQuota;
+    /// use mik::runtime::Runtime;
+    ///
+    /// # fn example() -> anyhow::Result<()> {
+    /// let runtime = Runtime::builder()
+    ///     .http_allowed(vec!["*".to_string()])
+    ///     .egress_quota("crawler", EgressQuota { daily_mb: Some(50), monthly_mb: None })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn egress_quota(mut self, module: impl Into<String>, quota: EgressQuota) -> Self {
+        self.config.egress_quotas.insert(module.into(), quota);
+        self
+    }
+
+    /// Override circuit breaker settings for `module` (`"*"` for every module
+    /// without its own entry).
+    pub fn circuit_breaker(
+        mut self,
+        module: impl Into<String>,
+        policy: CircuitBreakerPolicy,
+    ) -> Self {
+        self.config.circuit_breakers.insert(module.into(), policy);
+        self
+    }
+
+    /// Inject faults into requests to `module` (`"*"` for every module
+    /// without its own entry). For resilience testing only.
+    pub fn chaos(mut self, module: impl Into<String>, policy: ChaosPolicy) -> Self {
+        self.config.chaos.insert(module.into(), policy);
+        self
+    }
+
+    /// Limit requests, concurrency, and module storage of `tenant_id` (`"*"`
+    /// for every tenant without its own entry).
+    pub fn tenant_limits(mut self, tenant_id: impl Into<String>, limits: TenantLimits) -> Self {
+        self.config.tenant_limits.insert(tenant_id.into(), limits);
+        self
+    }
+
+    /// Trust `X-Forwarded-For` and `X-Client-Cert-Subject` from these proxies
+    /// (IP addresses or CIDR ranges) when reporting `mik:request-info`.
+    pub fn trusted_proxies(mut self, proxies: Vec<String>) -> Self {
+        self.config.trusted_proxies = proxies;
+        self
+    }
+
+    /// Require a PROXY protocol header on every connection accepted by a
+    /// [`Server`](crate::runtime::Server), replacing the peer address with
+    /// the client address it carries.
+    pub const fn proxy_protocol(mut self, enabled: bool) -> Self {
+        self.config.proxy_protocol = enabled;
+        self
+    }
+
+    /// Enable the gateway's tenant module upload and delete endpoints. They
+    /// also need an [`api_key`](Self::api_key).
+    pub const fn tenant_uploads(mut self, enabled: bool) -> Self {
+        self.config.tenant_uploads = enabled;
+        self
+    }
+
+    /// Require `key` in the `X-API-Key` header of the gateway's management
+    /// endpoints.
+    pub fn api_key(mut self, key: impl Into<String>) -> Self {
+        self.config.api_key = Some(key.into());
+        self
+    }
+
+    /// Require modules uploaded through the gateway to be signed with `key`
+    /// (HMAC-SHA256 in `X-Mik-Signature`).
+    pub fn module_signing_key(mut self, key: impl Into<String>) -> Self {
+        self.config.module_signing_key = Some(key.into());
+        self
+    }
+
+    /// Notify `url` with a `POST` whenever modules are added, removed or
+    /// updated.
+    pub fn handlers_webhook(mut self, url: impl Into<String>) -> Self {
+        self.config.handlers_webhook = Some(url.into());
+        self
+    }
+
+    /// Do not send handler webhooks, e.g. from all but one worker of a
+    /// cluster sharing the module directories.
+    pub fn disable_handlers_webhook(mut self) -> Self {
+        self.config.handlers_webhook = None;
+        self
+    }
+
+    /// Sign handler webhook bodies with `key` (HMAC-SHA256 in
+    /// `X-Mik-Signature`).
+    pub fn handlers_webhook_secret(mut self, key: impl Into<String>) -> Self {
+        self.config.handlers_webhook_secret = Some(key.into());
+        self
+    }
+
+    /// Record every module request and its response as JSON files in `dir`,
+    /// for `mik replay`. Credentials are redacted.
+    pub fn record_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.record_dir = Some(dir.into());
+        self
+    }
+
+    /// Serve the build error in `file` on module routes while it exists
+    /// (`mik dev`).
+    pub fn build_error_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.config.build_error_file = Some(file.into());
+        self
+    }
+
+    /// Forward paths outside `/run/` and `/tenant/` to the frontend dev
+    /// server at `url` (`mik dev --proxy`).
+    pub fn dev_proxy(mut self, url: impl Into<String>) -> Self {
+        self.config.dev_proxy = Some(url.into());
+        self
+    }
+
+    /// Serve the development dashboard at `/_dev/`, showing the tail of
+    /// `log_file` (`mik dev`).
+    pub fn dev_dashboard(mut self, log_file: impl Into<PathBuf>) -> Self {
+        self.config.dev_dashboard = Some(log_file.into());
+        self
+    }
+
+    /// Grant these modules (`"*"` for all) access to the `mik:sql` host
+    /// interface.
+    pub fn sql_modules(mut self, modules: Vec<String>) -> Self {
+        self.config.sql_modules = modules;
+        self
+    }
+
+    /// Serve `mik:sql` from this database instead of the daemon's `sql.db`.
+    pub fn sql_database(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.sql_database = Some(path.into());
+        self
+    }
+
+    /// Let `script` (`"*"` for scripts without an entry) use these daemon
+    /// services through `host.kv` and `host.storage`.
+    pub fn script_capabilities(
+        mut self,
+        script: impl Into<String>,
+        capabilities: ScriptCapabilities,
+    ) -> Self {
+        self.config
+            .script_capabilities
+            .insert(script.into(), capabilities);
+        self
+    }
+
+    /// Preopen a host directory for `module` (`"*"` for modules without
+    /// their own entry).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use mik::manifest::Preopen;
+    /// use mik::runtime::Runtime;
+    ///
+    /// # fn example() -> anyhow::Result<()> {
+    /// let runtime = Runtime::builder()
+    ///     .preopen("thumbnails", Preopen {
+    ///         path: "assets/fonts".to_string(),
+    ///         guest: "/fonts".to_string(),
+    ///         writable: false,
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn preopen(mut self, module: impl Into<String>, preopen: Preopen) -> Self {
+        self.config
+            .preopens
+            .entry(module.into())
+            .or_default()
+            .push(preopen);
+        self
+    }
+
+    /// Serve the ONNX model in `dir` (containing `model.onnx`) to `wasi-nn`
+    /// guests as `name`. Requires the `nn` feature.
+    pub fn nn_model(mut self, name: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        self.config.nn_models.insert(name.into(), dir.into());
+        self
+    }
+
+    /// Let `module` (`"*"` for modules without an entry) load these
+    /// `wasi-nn` models.
+    pub fn nn_module(mut self, module: impl Into<String>, models: Vec<String>) -> Self {
+        self.config.nn_modules.insert(module.into(), models);
+        self
+    }
+
+    /// Return script traces (`?trace=1`) to any caller, not only to callers
+    /// with the API key. For local development only.
+    pub const fn trace_scripts(mut self, enabled: bool) -> Self {
+        self.config.trace_scripts = enabled;
+        self
+    }
+
+    /// Pull `module` from the OCI `reference` (e.g.
+    /// `oci://ghcr.io/org/handler:1.2`) on first request when it is not in
+    /// the modules directory. Requires the `registry` feature.
+    pub fn oci_module(mut self, module: impl Into<String>, reference: impl Into<String>) -> Self {
+        self.config
+            .oci_modules
+            .insert(module.into(), reference.into());
+        self
+    }
+
     /// Set the modules directory or single component path.
     pub fn modules_dir(mut self, path: impl Into<PathBuf>) -> Self {
         self.config.modules_path = path.into();
@@ -571,12 +979,30 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Override settings from `MIK_<SETTING>` environment variables, on top
+    /// of mik.toml (see [`HostConfig::apply_env`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a variable does not parse as its setting's type.
+    pub fn env_overrides(mut self) -> std::result::Result<Self, ConfigError> {
+        self.config.apply_env(|var| std::env::var(var).ok())?;
+        Ok(self)
+    }
+
     /// Set the port (for reference, not used by Runtime directly).
     pub const fn port(mut self, port: u16) -> Self {
         self.config.port = port;
         self
     }
 
+    /// Also serve on these addresses (`host:port` or `unix:/path`) when run
+    /// by a [`Server`](crate::runtime::Server).
+    pub fn listen(mut self, addrs: Vec<String>) -> Self {
+        self.config.listen = addrs;
+        self
+    }
+
     /// Set the execution timeout.
     pub fn execution_timeout(mut self, timeout: Duration) -> Self {
         self.config.execution_timeout_secs = timeout.as_secs();
@@ -589,6 +1015,31 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Set the upper bound for per-request timeout overrides.
+    ///
+    /// Requests may set `X-Request-Timeout` or `grpc-timeout` to shorten or
+    /// extend their execution timeout up to this value.
+    pub fn max_request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.max_request_timeout_secs = timeout.as_secs();
+        self
+    }
+
+    /// Set the timeout for a component's `init`/`warmup` export.
+    ///
+    /// Defaults to the execution timeout.
+    pub fn init_timeout(mut self, timeout: Duration) -> Self {
+        self.config.init_timeout_secs = timeout.as_secs();
+        self
+    }
+
+    /// Retry executions that fail on instance exhaustion up to `retries`
+    /// times, waiting `backoff` (doubled with jitter) between attempts.
+    pub fn execution_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.config.execution_retries = retries;
+        self.config.execution_retry_backoff_ms = backoff.as_millis() as u64;
+        self
+    }
+
     /// Set the memory limit per request in bytes.
     pub const fn memory_limit(mut self, limit_bytes: usize) -> Self {
         self.config.memory_limit_bytes = limit_bytes;
@@ -607,12 +1058,42 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Set the maximum size of a module uploaded through the gateway, in
+    /// bytes.
+    pub const fn max_module_size(mut self, max_bytes: usize) -> Self {
+        self.config.max_module_size_bytes = max_bytes;
+        self
+    }
+
     /// Set the maximum concurrent requests per module.
     pub const fn max_per_module_requests(mut self, max: usize) -> Self {
         self.config.max_per_module_requests = max;
         self
     }
 
+    /// Set the maximum concurrent requests per tenant, across all of its
+    /// modules (0 = unlimited).
+    pub const fn max_per_tenant_requests(mut self, max: usize) -> Self {
+        self.config.max_per_tenant_requests = max;
+        self
+    }
+
+    /// Let up to `depth` requests wait for a module at its concurrency limit,
+    /// each for at most `timeout`, before they are shed with 429.
+    pub fn module_queue(mut self, depth: usize, timeout: Duration) -> Self {
+        self.config.module_queue_depth = depth;
+        self.config.module_queue_timeout_ms = timeout.as_millis() as u64;
+        self
+    }
+
+    /// Adapt each module's concurrency limit to its latency and errors
+    /// (AIMD), between `min` and `max_per_module_requests`.
+    pub const fn adaptive_concurrency(mut self, min: usize) -> Self {
+        self.config.adaptive_concurrency = true;
+        self.config.adaptive_concurrency_min = min;
+        self
+    }
+
     /// Set the graceful shutdown timeout.
     pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
         self.config.shutdown_timeout_secs = timeout.as_secs();
@@ -631,6 +1112,13 @@ impl RuntimeBuilder {
         self
     }
 
+    /// Give components exactly these environment variables instead of the
+    /// process environment.
+    pub fn component_env(mut self, vars: BTreeMap<String, String>) -> Self {
+        self.config.component_env = Some(vars);
+        self
+    }
+
     /// Enable hot-reload mode (bypasses persistent AOT cache).
     pub const fn hot_reload(mut self, enabled: bool) -> Self {
         self.config.hot_reload = enabled;
@@ -701,6 +1189,12 @@ mod tests {
         assert_eq!(builder.config.execution_timeout_secs, 60);
     }
 
+    #[test]
+    fn test_runtime_builder_max_request_timeout() {
+        let builder = RuntimeBuilder::new().max_request_timeout(Duration::from_secs(120));
+        assert_eq!(builder.config.max_request_timeout_secs, 120);
+    }
+
     #[test]
     fn test_runtime_builder_from_manifest() {
         let manifest = Manifest::default();
@@ -708,6 +1202,234 @@ mod tests {
         assert_eq!(builder.config.port, constants::DEFAULT_PORT);
     }
 
+    #[test]
+    fn test_runtime_builder_aliases_from_manifest_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.toml");
+        std::fs::write(
+            &path,
+            r#"
+[server.aliases]
+v1 = { module = "orders-1.4.wasm", deprecated = true, successor = "v2" }
+v2 = { module = "orders-2.0.wasm" }
+"#,
+        )
+        .unwrap();
+
+        let builder = RuntimeBuilder::new()
+            .from_manifest_file(&path)
+            .unwrap()
+            .alias("beta", ModuleAlias::new("orders-3.0"));
+        let aliases = &builder.config.aliases;
+        assert_eq!(aliases.len(), 3);
+        assert!(aliases["v1"].deprecated);
+        assert_eq!(aliases["v2"].module_name(), "orders-2.0");
+        assert_eq!(aliases["beta"].module_name(), "orders-3.0");
+    }
+
+    #[test]
+    fn test_runtime_builder_sql_modules_from_manifest_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.toml");
+        std::fs::write(
+            &path,
+            r#"
+[server]
+sql_modules = ["reports"]
+"#,
+        )
+        .unwrap();
+
+        let builder = RuntimeBuilder::new().from_manifest_file(&path).unwrap();
+        assert!(builder.config.sql_allowed("reports"));
+        assert!(!builder.config.sql_allowed("orders"));
+
+        let builder = builder.sql_modules(vec!["*".to_string()]);
+        assert!(builder.config.sql_allowed("orders"));
+    }
+
+    #[test]
+    fn test_runtime_builder_preopens_from_manifest_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.toml");
+        std::fs::write(
+            &path,
+            r#"
+[server.preopens]
+thumbnails = [{ path = "assets", guest = "/assets" }]
+"*" = [{ path = "tmp", guest = "/tmp", writable = true }]
+"#,
+        )
+        .unwrap();
+
+        let builder = RuntimeBuilder::new().from_manifest_file(&path).unwrap();
+        let thumbnails = builder.config.preopens("thumbnails");
+        assert_eq!(thumbnails.len(), 1);
+        assert_eq!(thumbnails[0].guest, "/assets");
+        assert!(!thumbnails[0].writable);
+        assert!(builder.config.preopens("orders")[0].writable);
+    }
+
+    #[test]
+    fn test_runtime_builder_script_capabilities_from_manifest_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.toml");
+        std::fs::write(
+            &path,
+            r"
+[server.script_capabilities]
+checkout = { kv = true, storage = true }
+",
+        )
+        .unwrap();
+
+        let builder = RuntimeBuilder::new().from_manifest_file(&path).unwrap();
+        let checkout = builder.config.script_capabilities("checkout");
+        assert!(checkout.kv && checkout.storage);
+        assert_eq!(
+            builder.config.script_capabilities("report"),
+            ScriptCapabilities::default()
+        );
+
+        let builder = builder.script_capabilities(
+            "*",
+            ScriptCapabilities {
+                kv: true,
+                storage: false,
+            },
+        );
+        let report = builder.config.script_capabilities("report");
+        assert!(report.kv && !report.storage);
+    }
+
+    #[test]
+    fn test_runtime_builder_egress_quotas_from_manifest_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.toml");
+        std::fs::write(
+            &path,
+            r#"
+[server.egress_quotas]
+"*" = { monthly_mb = 1024 }
+crawler = { daily_mb = 50 }
+"#,
+        )
+        .unwrap();
+
+        let builder = RuntimeBuilder::new()
+            .from_manifest_file(&path)
+            .unwrap()
+            .egress_quota(
+                "sync",
+                EgressQuota {
+                    daily_mb: Some(5),
+                    monthly_mb: None,
+                },
+            );
+        let config = &builder.config;
+        assert_eq!(config.egress_quota("crawler").unwrap().daily_mb, Some(50));
+        assert_eq!(config.egress_quota("sync").unwrap().daily_mb, Some(5));
+        // Modules without an entry use the "*" quota
+        assert_eq!(
+            config.egress_quota("other"),
+            Some(EgressQuota {
+                daily_mb: None,
+                monthly_mb: Some(1024),
+            })
+        );
+    }
+
+    #[test]
+    fn test_runtime_builder_circuit_breakers_from_manifest_file() {
+        let modules = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        if !modules.join("echo.wasm").exists() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.toml");
+        std::fs::write(
+            &path,
+            format!(
+                r#"
+[server]
+modules = '{}'
+
+[server.circuit_breakers]
+"*" = {{ recovery_timeout_secs = 10 }}
+payments = {{ failure_threshold = 20, probe_timeout_secs = 5 }}
+"#,
+                modules.display()
+            ),
+        )
+        .unwrap();
+
+        let runtime = RuntimeBuilder::new()
+            .from_manifest_file(&path)
+            .unwrap()
+            .circuit_breaker(
+                "thumbnails",
+                CircuitBreakerPolicy {
+                    failure_threshold: Some(2),
+                    ..Default::default()
+                },
+            )
+            .build()
+            .unwrap();
+        let breaker = &runtime.shared.circuit_breaker;
+
+        let payments = breaker.config_for("payments");
+        assert_eq!(payments.failure_threshold, 20);
+        assert_eq!(payments.probe_timeout, Duration::from_secs(5));
+        // Fields a module leaves out come from "*", then the defaults
+        assert_eq!(payments.timeout, Duration::from_secs(10));
+        assert_eq!(breaker.config_for("thumbnails").failure_threshold, 2);
+
+        let other = breaker.config_for("other");
+        assert_eq!(
+            other.failure_threshold,
+            constants::CIRCUIT_BREAKER_FAILURE_THRESHOLD
+        );
+        assert_eq!(other.timeout, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_runtime_builder_chaos_from_manifest_file() {
+        let modules = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        if !modules.join("echo.wasm").exists() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.toml");
+        let write = |chaos: &str| {
+            std::fs::write(
+                &path,
+                format!(
+                    "[server]\nmodules = '{}'\n\n[server.chaos]\n{chaos}\n",
+                    modules.display()
+                ),
+            )
+            .unwrap();
+        };
+
+        write("payments = { latency_ms = 500, latency_probability = 0.2 }");
+        let runtime = RuntimeBuilder::new()
+            .from_manifest_file(&path)
+            .unwrap()
+            .build()
+            .unwrap();
+        let policy = runtime.shared.config.chaos_policy("payments").unwrap();
+        assert_eq!(policy.latency_ms, Some(500));
+        assert_eq!(policy.latency_probability, Some(0.2));
+        assert!(runtime.shared.config.chaos_policy("orders").is_none());
+
+        write(r#""*" = { trap_probability = 2.0 }"#);
+        let result = RuntimeBuilder::new()
+            .from_manifest_file(&path)
+            .unwrap()
+            .build();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_runtime_builder_chaining() {
         let builder = RuntimeBuilder::new()
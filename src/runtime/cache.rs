@@ -2,17 +2,25 @@
 //!
 //! This module provides the module cache and component loading functionality:
 //! - `CachedComponent`: Component with size tracking for byte-aware eviction
+//!   and the exports detected at load time
 //! - `ModuleCache`: LRU cache using moka with byte-based eviction
 //! - Module loading with AOT cache integration
 
 use super::SharedState;
+use super::component_exports::ComponentExports;
+use super::core_adapter;
 use super::error;
+use super::init_hook;
 use super::module_path::ModulePath;
 use super::security;
 use anyhow::{Context, Result};
 use moka::sync::Cache as MokaCache;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::Semaphore;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::{debug, info};
 use wasmtime::component::Component;
 
@@ -20,6 +28,43 @@ use wasmtime::component::Component;
 pub(crate) struct CachedComponent {
     pub(crate) component: Arc<Component>,
     pub(crate) size_bytes: usize,
+    /// Worlds the component implements.
+    pub(crate) exports: ComponentExports,
+}
+
+/// Key a module's semaphore and circuit breaker are tracked under.
+///
+/// Tenant modules run as `{tenant}/{module}` but are tracked by cache key
+/// (`tenant:{tenant}/{module}`), so they never share state with a platform
+/// module.
+pub(crate) fn module_key(module: &str) -> Cow<'_, str> {
+    if module.contains('/') {
+        Cow::Owned(format!("tenant:{module}"))
+    } else {
+        Cow::Borrowed(module)
+    }
+}
+
+/// Check that a tenant module file resolves inside its tenant's directory.
+///
+/// Symlinks are resolved, so a module linked to another tenant's directory
+/// or outside `user_modules_dir` is rejected.
+fn ensure_tenant_owned(user_modules_dir: &Path, tenant_id: &str, wasm_path: &Path) -> Result<()> {
+    let escapes = || error::Error::path_traversal(wasm_path).into_anyhow();
+    let tenant_dir = user_modules_dir
+        .canonicalize()
+        .map_err(|_| escapes())?
+        .join(tenant_id);
+    let resolved = wasm_path.canonicalize().map_err(|_| escapes())?;
+    if !resolved.starts_with(&tenant_dir) {
+        tracing::warn!(
+            "Tenant '{}' module {} resolves outside its directory",
+            tenant_id,
+            wasm_path.display()
+        );
+        return Err(escapes());
+    }
+    Ok(())
 }
 
 /// Module cache with byte-aware eviction using moka.
@@ -51,9 +96,129 @@ impl SharedState {
         sem
     }
 
+    /// Acquire a concurrency permit for `module`.
+    ///
+    /// When the module is at its concurrency limit (`max_per_module_requests`
+    /// or its adaptive limit), the request waits in the module's queue if
+    /// `module_queue_depth` allows, for at most `module_queue_timeout_ms`. Returns `None` when the request is shed:
+    /// no queue, queue full, or no permit freed up in time.
+    pub(crate) async fn acquire_module_permit(&self, module: &str) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.get_module_semaphore(module);
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Some(permit);
+        }
+        let depth = self.config.module_queue_depth;
+        if depth == 0 {
+            return None;
+        }
+
+        let queued = self
+            .module_queues
+            .lock()
+            .entry(module.to_string())
+            .or_default()
+            .clone();
+        if queued.fetch_add(1, Ordering::AcqRel) >= depth {
+            queued.fetch_sub(1, Ordering::AcqRel);
+            debug!("Queue for module '{}' is full ({} waiting)", module, depth);
+            return None;
+        }
+        let timeout = Duration::from_millis(self.config.module_queue_timeout_ms);
+        let permit = tokio::time::timeout(timeout, semaphore.acquire_owned()).await;
+        queued.fetch_sub(1, Ordering::AcqRel);
+        permit.ok()?.ok()
+    }
+
+    /// Acquire a concurrency permit for `tenant_id` under its
+    /// `max_concurrent_requests` tenant limit, or `max_per_tenant_requests`,
+    /// shared by all of the tenant's modules.
+    ///
+    /// Returns `Ok(None)` when the tenant is unlimited and `Err(limit)` when
+    /// it is at its limit. Tenants do not queue: a tenant at its limit only
+    /// sheds its own requests.
+    pub(crate) fn try_acquire_tenant_permit(
+        &self,
+        tenant_id: &str,
+    ) -> Result<Option<OwnedSemaphorePermit>, usize> {
+        let limit = self
+            .live()
+            .tenant_limits(tenant_id)
+            .max_concurrent_requests
+            .unwrap_or(self.config.max_per_tenant_requests);
+        if limit == 0 {
+            return Ok(None);
+        }
+        let semaphore = self
+            .tenant_semaphores
+            .lock()
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+            .clone();
+        semaphore.try_acquire_owned().map(Some).map_err(|_| limit)
+    }
+
+    /// Requests currently waiting in each module's queue.
+    pub(crate) fn module_queue_lengths(&self) -> Vec<(String, usize)> {
+        self.module_queues
+            .lock()
+            .iter()
+            .map(|(module, queued)| (module.clone(), queued.load(Ordering::Acquire)))
+            .collect()
+    }
+
+    /// Concurrency limit currently applied to `module`.
+    pub(crate) fn module_concurrency_limit(&self, module: &str) -> usize {
+        self.adaptive_limits
+            .as_ref()
+            .map_or(self.config.max_per_module_requests, |limits| {
+                limits.limit(module)
+            })
+    }
+
+    /// Feed an execution of `module` to its adaptive concurrency limit.
+    ///
+    /// Does nothing without `adaptive_concurrency`, or for modules that were
+    /// not run through [`Self::acquire_module_permit`].
+    pub(crate) fn record_module_execution(&self, module: &str, latency: Duration, ok: bool) {
+        let Some(limits) = &self.adaptive_limits else {
+            return;
+        };
+        let key = module_key(module);
+        let Some(semaphore) = self.module_semaphores.lock().get(key.as_ref()).cloned() else {
+            return;
+        };
+        limits.record(&key, &semaphore, latency, ok);
+    }
+
     /// Get or load a module by name (async to avoid blocking the runtime).
-    #[allow(unsafe_code)] // SAFETY: Component::deserialize_file requires unsafe for AOT cache
     pub(crate) async fn get_or_load(&self, name: &str) -> Result<Arc<Component>> {
+        Ok(self.load_cached(name).await?.component.clone())
+    }
+
+    /// Get or load a module that must serve HTTP requests.
+    ///
+    /// Fails with [`error::Error::UnsupportedComponent`] if the module does not
+    /// export `wasi:http/incoming-handler`.
+    pub(crate) async fn get_or_load_http(&self, name: &str) -> Result<Arc<Component>> {
+        let cached = self.load_cached(name).await?;
+        cached
+            .exports
+            .require_http(name)
+            .map_err(error::Error::into_anyhow)?;
+        Ok(cached.component.clone())
+    }
+
+    /// The module pulled from its `oci_modules` reference, if it has one.
+    async fn pull_oci_module(&self, name: &str) -> Result<Option<PathBuf>> {
+        match &self.oci {
+            Some(oci) => oci.fetch(name).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Get or load a module with the exports detected when it was loaded.
+    #[allow(unsafe_code)] // SAFETY: Component::deserialize_file requires unsafe for AOT cache
+    pub(crate) async fn load_cached(&self, name: &str) -> Result<Arc<CachedComponent>> {
         // Security: sanitize module name to prevent path traversal
         let sanitized_name = security::sanitize_module_name(name).map_err(|e| {
             error::Error::InvalidRequest(format!("Invalid module name '{name}': {e}")).into_anyhow()
@@ -62,20 +227,24 @@ impl SharedState {
         // Check cache first (no lock needed - moka is thread-safe)
         if let Some(cached) = self.cache.get(&sanitized_name) {
             debug!("Cache hit: {}", sanitized_name);
-            return Ok(cached.component.clone());
+            self.stats.record_module_cache(true);
+            return Ok(cached);
         }
+        self.stats.record_module_cache(false);
 
         // Load from disk (async I/O)
-        let path = self.modules_dir.join(format!("{sanitized_name}.wasm"));
+        let mut path = self.modules_dir.join(format!("{sanitized_name}.wasm"));
         if !tokio::fs::try_exists(&path).await? {
-            return Err(error::Error::module_not_found(&sanitized_name).into_anyhow());
+            path = self
+                .pull_oci_module(&sanitized_name)
+                .await?
+                .ok_or_else(|| error::Error::module_not_found(&sanitized_name).into_anyhow())?;
         }
 
         // Get file size for byte-aware cache eviction (async I/O)
         let file_size = tokio::fs::metadata(&path)
             .await
-            .map(|m| m.len() as usize)
-            .unwrap_or(0);
+            .map_or(0, |m| m.len() as usize);
 
         info!("Loading module: {} ({} bytes)", sanitized_name, file_size);
 
@@ -86,6 +255,7 @@ impl SharedState {
 
         let engine = self.engine.clone();
         let aot_cache = self.aot_cache.clone();
+        let stats = self.stats.clone();
 
         // CPU-intensive component compilation - use spawn_blocking to avoid blocking the runtime
         let component = tokio::task::spawn_blocking(move || -> anyhow::Result<Component> {
@@ -95,6 +265,7 @@ impl SharedState {
                 match unsafe { Component::deserialize_file(&engine, &cached_path) } {
                     Ok(component) => {
                         tracing::debug!("AOT cache hit: {}", cached_path.display());
+                        stats.record_aot_cache(true);
                         return Ok(component);
                     },
                     Err(e) => {
@@ -107,7 +278,9 @@ impl SharedState {
             }
 
             // Compile from bytes
-            let component = Component::from_binary(&engine, &wasm_bytes)?;
+            stats.record_aot_cache(false);
+            let component =
+                Component::from_binary(&engine, &core_adapter::componentize(&wasm_bytes)?)?;
 
             // Store in content-addressable cache (unless in hot-reload mode)
             if !aot_cache.is_bypass() {
@@ -132,14 +305,22 @@ impl SharedState {
         .context("Task join failed")?
         .with_context(|| format!("Failed to load {}", path.display()))?;
 
-        let component = Arc::new(component);
+        let exports = ComponentExports::detect(&self.engine, &component);
+        debug!("Module {} exports: {}", sanitized_name, exports.describe());
+        if let Some(export) = exports.init {
+            init_hook::run(self, &component, &sanitized_name, export)
+                .await
+                .map_err(error::Error::into_anyhow)?;
+        }
 
         // Cache it with size tracking (moka handles eviction automatically)
         let cached_component = Arc::new(CachedComponent {
-            component: component.clone(),
+            component: Arc::new(component),
             size_bytes: file_size,
+            exports,
         });
-        self.cache.insert(sanitized_name.clone(), cached_component);
+        self.cache
+            .insert(sanitized_name.clone(), cached_component.clone());
 
         debug!(
             "Cache stats: {} entries, ~{} bytes total",
@@ -147,7 +328,7 @@ impl SharedState {
             self.cache.weighted_size()
         );
 
-        Ok(component)
+        Ok(cached_component)
     }
 
     /// Get or load a module using `ModulePath` (supports both platform and tenant modules).
@@ -155,6 +336,10 @@ impl SharedState {
     /// This method uses `ModulePath` for:
     /// - Cache key: `module_path.cache_key()` (e.g., "hello" or "tenant:abc/orders")
     /// - File path: `module_path.wasm_path(modules_dir, user_modules_dir)`
+    ///
+    /// Modules are served over HTTP, so ones without
+    /// `wasi:http/incoming-handler` fail with
+    /// [`error::Error::UnsupportedComponent`].
     #[allow(unsafe_code)] // SAFETY: Component::deserialize_file requires unsafe for AOT cache
     pub(crate) async fn get_or_load_module_path(
         &self,
@@ -181,11 +366,17 @@ impl SharedState {
         // Check cache first (no lock needed - moka is thread-safe)
         if let Some(cached) = self.cache.get(&cache_key) {
             debug!("Cache hit: {}", cache_key);
+            self.stats.record_module_cache(true);
+            cached
+                .exports
+                .require_http(&module_path.to_string())
+                .map_err(error::Error::into_anyhow)?;
             return Ok(cached.component.clone());
         }
+        self.stats.record_module_cache(false);
 
         // Resolve the WASM file path
-        let wasm_path = module_path
+        let mut wasm_path = module_path
             .wasm_path(&self.modules_dir, self.user_modules_dir.as_deref())
             .ok_or_else(|| {
                 if module_path.is_tenant() {
@@ -201,14 +392,25 @@ impl SharedState {
 
         // Load from disk (async I/O)
         if !tokio::fs::try_exists(&wasm_path).await? {
-            return Err(error::Error::module_not_found(module_path.to_string()).into_anyhow());
+            let pulled = if module_path.is_tenant() {
+                None
+            } else {
+                self.pull_oci_module(module_path.name()).await?
+            };
+            wasm_path = pulled.ok_or_else(|| {
+                error::Error::module_not_found(module_path.to_string()).into_anyhow()
+            })?;
+        }
+        if let (Some(tenant_id), Some(user_modules_dir)) =
+            (module_path.tenant_id(), self.user_modules_dir.as_deref())
+        {
+            ensure_tenant_owned(user_modules_dir, tenant_id, &wasm_path)?;
         }
 
         // Get file size for byte-aware cache eviction (async I/O)
         let file_size = tokio::fs::metadata(&wasm_path)
             .await
-            .map(|m| m.len() as usize)
-            .unwrap_or(0);
+            .map_or(0, |m| m.len() as usize);
 
         info!("Loading module: {} ({} bytes)", module_path, file_size);
 
@@ -219,6 +421,7 @@ impl SharedState {
 
         let engine = self.engine.clone();
         let aot_cache = self.aot_cache.clone();
+        let stats = self.stats.clone();
         let wasm_path_display = wasm_path.display().to_string();
 
         // CPU-intensive component compilation - use spawn_blocking to avoid blocking the runtime
@@ -229,6 +432,7 @@ impl SharedState {
                 match unsafe { Component::deserialize_file(&engine, &cached_path) } {
                     Ok(component) => {
                         tracing::debug!("AOT cache hit: {}", cached_path.display());
+                        stats.record_aot_cache(true);
                         return Ok(component);
                     },
                     Err(e) => {
@@ -241,7 +445,9 @@ impl SharedState {
             }
 
             // Compile from bytes
-            let component = Component::from_binary(&engine, &wasm_bytes)?;
+            stats.record_aot_cache(false);
+            let component =
+                Component::from_binary(&engine, &core_adapter::componentize(&wasm_bytes)?)?;
 
             // Store in content-addressable cache (unless in hot-reload mode)
             if !aot_cache.is_bypass() {
@@ -267,13 +473,24 @@ impl SharedState {
         .with_context(|| format!("Failed to load {wasm_path_display}"))?;
 
         let component = Arc::new(component);
+        let exports = ComponentExports::detect(&self.engine, &component);
+        debug!("Module {} exports: {}", cache_key, exports.describe());
+        if let Some(export) = exports.init {
+            init_hook::run(self, &component, &cache_key, export)
+                .await
+                .map_err(error::Error::into_anyhow)?;
+        }
 
         // Cache it with size tracking (moka handles eviction automatically)
         let cached_component = Arc::new(CachedComponent {
             component: component.clone(),
             size_bytes: file_size,
+            exports,
         });
         self.cache.insert(cache_key.clone(), cached_component);
+        exports
+            .require_http(&module_path.to_string())
+            .map_err(error::Error::into_anyhow)?;
 
         debug!(
             "Cache stats: {} entries, ~{} bytes total",
@@ -0,0 +1,125 @@
+//! Built-in canary component for deep health checks.
+//!
+//! `GET /health?deep=true` runs this tiny component the way a request runs a
+//! handler: in a store from [`create_store`] (pooling allocator slot, memory
+//! limiter, fuel budget, epoch deadline), instantiated with the runtime's
+//! linker. It writes to and reads back its linear memory, so a broken engine,
+//! exhausted pool or mis-set fuel budget fails the probe even when the cache
+//! statistics look healthy.
+//!
+//! The component is compiled on the first deep probe and reused after that.
+
+use anyhow::{Context, Result};
+use std::time::{Duration, Instant};
+use wasmtime::component::Component;
+use wasmtime_wasi::WasiCtxBuilder;
+
+use crate::constants;
+use crate::runtime::SharedState;
+use crate::runtime::request_info::RequestInfo;
+use crate::runtime::types::CanaryStatus;
+use crate::runtime::wasm_executor::create_store;
+
+/// Canary exporting `run: func() -> u32`, which fills 100 words of memory
+/// with their index and returns the last one.
+const CANARY_WAT: &str = r#"
+(component
+  (core module $canary
+    (memory 1)
+    (func (export "run") (result i32)
+      (local $i i32)
+      (loop $fill
+        (i32.store (i32.mul (local.get $i) (i32.const 4)) (local.get $i))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br_if $fill (i32.lt_u (local.get $i) (i32.const 100))))
+      (i32.load (i32.const 396))))
+  (core instance $instance (instantiate $canary))
+  (func (export "run") (result u32) (canon lift (core func $instance "run"))))
+"#;
+
+/// Value `run` returns when the canary executed correctly.
+const CANARY_EXPECTED: u32 = 99;
+
+/// Execute the canary and report whether it passed and how long it took.
+pub(crate) async fn probe(shared: &SharedState) -> CanaryStatus {
+    let start = Instant::now();
+    let timeout = Duration::from_millis(constants::CANARY_TIMEOUT_MS);
+    let result = tokio::time::timeout(timeout, run(shared, timeout))
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("Canary timed out after {timeout:?}")));
+    let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    match result {
+        Ok(()) => CanaryStatus {
+            passed: true,
+            latency_ms,
+            error: None,
+        },
+        Err(e) => {
+            tracing::warn!(error = %e, "Canary health probe failed");
+            CanaryStatus {
+                passed: false,
+                latency_ms,
+                error: Some(format!("{e:#}")),
+            }
+        },
+    }
+}
+
+async fn run(shared: &SharedState, timeout: Duration) -> Result<()> {
+    let component = shared
+        .canary
+        .get_or_try_init(|| async {
+            Component::new(&shared.engine, CANARY_WAT).context("Failed to compile canary")
+        })
+        .await?;
+
+    let mut store = create_store(
+        shared,
+        None,
+        WasiCtxBuilder::new(),
+        RequestInfo::default(),
+        timeout,
+    )?;
+    let instance = shared
+        .linker
+        .instantiate_async(&mut store, component)
+        .await
+        .context("Failed to instantiate canary")?;
+    let func = instance
+        .get_typed_func::<(), (u32,)>(&mut store, "run")
+        .context("Canary export missing")?;
+    let (value,) = func
+        .call_async(&mut store, ())
+        .await
+        .context("Canary call failed")?;
+    func.post_return_async(&mut store).await?;
+
+    anyhow::ensure!(
+        value == CANARY_EXPECTED,
+        "Canary returned {value}, expected {CANARY_EXPECTED}"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmtime::component::Linker;
+    use wasmtime::{Engine, Store};
+
+    #[test]
+    fn test_canary_returns_expected_value() {
+        let engine = Engine::default();
+        let component = Component::new(&engine, CANARY_WAT).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = Linker::new(&engine)
+            .instantiate(&mut store, &component)
+            .unwrap();
+        let func = instance
+            .get_typed_func::<(), (u32,)>(&mut store, "run")
+            .unwrap();
+        let (value,) = func.call(&mut store, ()).unwrap();
+        assert_eq!(value, CANARY_EXPECTED);
+    }
+}
@@ -0,0 +1,109 @@
+//! Fault injection for resilience testing.
+//!
+//! With `[server.chaos]` configured, every request to a module rolls the
+//! faults of its [`ChaosPolicy`] (or the `"*"` entry) independently:
+//!
+//! - **latency**: `latency_ms` is slept before the handler runs. It counts
+//!   against the execution timeout, so a delay past the deadline times out.
+//! - **trap**: the request fails as if the handler trapped, without running it.
+//! - **fuel starvation**: the handler runs with no fuel and traps on entry.
+//! - **drop**: the handler runs, then its response is discarded and the
+//!   connection closed without one.
+//!
+//! Injected failures go through the same paths as real ones, so they count
+//! towards circuit breakers and error metrics, and clients see what they
+//! would for a crashing or hanging module. This exercises retry and
+//! circuit breaker settings without rebuilding components like the `panic`
+//! and `infinite_loop` fixtures.
+//!
+//! Never enable in production.
+
+use crate::manifest::ChaosPolicy;
+use std::time::Duration;
+
+/// Faults rolled for one request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Faults {
+    /// Delay before the handler runs.
+    pub latency: Option<Duration>,
+    /// Fail without running the handler.
+    pub trap: bool,
+    /// Run the handler without fuel.
+    pub starve: bool,
+    /// Discard the handler's response.
+    pub drop: bool,
+}
+
+impl Faults {
+    /// Roll each fault of `policy`.
+    pub fn roll(policy: &ChaosPolicy) -> Self {
+        Self {
+            latency: policy
+                .latency_ms
+                .filter(|_| hit(policy.latency_probability))
+                .map(Duration::from_millis),
+            trap: hit(policy.trap_probability),
+            starve: hit(policy.fuel_starvation_probability),
+            drop: hit(policy.drop_probability),
+        }
+    }
+}
+
+/// Whether an event with probability `p` happens.
+fn hit(p: Option<f64>) -> bool {
+    p.is_some_and(|p| p > 0.0 && fastrand::f64() < p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roll_certain_and_impossible() {
+        let always = ChaosPolicy {
+            latency_ms: Some(250),
+            latency_probability: Some(1.0),
+            drop_probability: Some(1.0),
+            trap_probability: Some(1.0),
+            fuel_starvation_probability: Some(1.0),
+        };
+        assert_eq!(
+            Faults::roll(&always),
+            Faults {
+                latency: Some(Duration::from_millis(250)),
+                trap: true,
+                starve: true,
+                drop: true,
+            }
+        );
+
+        let never = ChaosPolicy {
+            latency_ms: Some(250),
+            latency_probability: Some(0.0),
+            drop_probability: Some(0.0),
+            ..Default::default()
+        };
+        for _ in 0..100 {
+            assert_eq!(Faults::roll(&never), Faults::default());
+        }
+    }
+
+    #[test]
+    fn test_latency_needs_duration() {
+        let policy = ChaosPolicy {
+            latency_probability: Some(1.0),
+            ..Default::default()
+        };
+        assert_eq!(Faults::roll(&policy).latency, None);
+    }
+
+    #[test]
+    fn test_roll_probability() {
+        let policy = ChaosPolicy {
+            trap_probability: Some(0.5),
+            ..Default::default()
+        };
+        let traps = (0..1000).filter(|_| Faults::roll(&policy).trap).count();
+        assert!((300..700).contains(&traps), "{traps} traps out of 1000");
+    }
+}
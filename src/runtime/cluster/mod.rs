@@ -64,8 +64,11 @@
 //! ```
 
 pub mod process;
+pub mod supervisor;
 pub mod task;
 
+pub use supervisor::{BackendPool, RestartPolicy, Supervisor};
+
 use crate::runtime::Runtime;
 use anyhow::Result;
 use std::net::SocketAddr;
@@ -148,13 +151,19 @@ pub struct Cluster {
     workers: Vec<WorkerHandle>,
     /// Base port for workers.
     base_port: u16,
+    /// Manifest used to spawn process workers (needed to restart them).
+    manifest_path: Option<String>,
 }
 
 impl Cluster {
     /// Create a new cluster with the given workers.
     #[must_use]
     pub fn new(workers: Vec<WorkerHandle>, base_port: u16) -> Self {
-        Self { workers, base_port }
+        Self {
+            workers,
+            base_port,
+            manifest_path: None,
+        }
     }
 
     /// Get the workers in this cluster.
@@ -228,6 +237,43 @@ impl Cluster {
         }
     }
 
+    /// Start crash detection and restart for process-based workers.
+    ///
+    /// Crashed workers are marked unhealthy in `pool` (typically
+    /// `lb.proxy().backend_pool()`), restarted with exponential backoff, and
+    /// marked healthy again once their health endpoint passes.
+    ///
+    /// Note: after a restart, the PIDs in [`workers`](Self::workers) are stale;
+    /// use [`Supervisor::worker_pid`] for the current ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cluster was not created with
+    /// [`ClusterBuilder::spawn_processes`] or has no process workers.
+    pub fn supervise(
+        &self,
+        policy: RestartPolicy,
+        pool: Option<BackendPool>,
+    ) -> Result<Supervisor> {
+        let manifest_path = self.manifest_path.clone().ok_or_else(|| {
+            anyhow::anyhow!("supervise requires a cluster created with spawn_processes")
+        })?;
+
+        let workers: Vec<(u32, u16)> = self
+            .workers
+            .iter()
+            .filter_map(|w| match w {
+                WorkerHandle::Process { pid, port, .. } => Some((*pid, *port)),
+                WorkerHandle::Task { .. } => None,
+            })
+            .collect();
+        if workers.is_empty() {
+            anyhow::bail!("Cluster has no process workers to supervise");
+        }
+
+        Ok(Supervisor::start(workers, manifest_path, policy, pool))
+    }
+
     /// Get health status from all workers.
     pub fn health(&self) -> Vec<crate::runtime::types::HealthStatus> {
         self.workers
@@ -333,7 +379,11 @@ impl ClusterBuilder {
 
         let workers = process::spawn_workers(self.worker_count, self.base_port, &manifest_path)?;
 
-        Ok(Cluster::new(workers, self.base_port))
+        Ok(Cluster {
+            workers,
+            base_port: self.base_port,
+            manifest_path: Some(manifest_path),
+        })
     }
 
     /// Spawn workers as tokio tasks.
@@ -419,4 +469,13 @@ mod tests {
         assert!(process_worker.is_process());
         assert!(!process_worker.is_task());
     }
+
+    #[test]
+    fn test_supervise_requires_process_cluster() {
+        let cluster = Cluster::new(Vec::new(), 3001);
+        let err = cluster
+            .supervise(RestartPolicy::default(), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("spawn_processes"));
+    }
 }
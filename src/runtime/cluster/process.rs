@@ -15,7 +15,7 @@ use nix::sys::signal::{Signal, kill};
 #[cfg(unix)]
 use nix::unistd::Pid;
 use std::net::SocketAddr;
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, Command, ExitStatus, Stdio};
 use tracing::{error, info};
 
 /// Spawn multiple worker processes.
@@ -61,7 +61,7 @@ pub fn spawn_workers(
 ///
 /// * `port` - Port for the worker to listen on
 /// * `manifest_path` - Path to the mik.toml manifest file
-fn spawn_worker(port: u16, manifest_path: &str) -> Result<WorkerHandle> {
+pub(super) fn spawn_worker(port: u16, manifest_path: &str) -> Result<WorkerHandle> {
     let current_exe = std::env::current_exe().context("Failed to get current executable path")?;
 
     let child = Command::new(&current_exe)
@@ -169,21 +169,73 @@ pub fn shutdown_all_workers() {
 
 /// Check if a worker process is still running.
 pub fn is_worker_running(pid: u32) -> bool {
-    if let Ok(processes) = CHILD_PROCESSES.lock() {
-        for child in processes.iter() {
+    if let Ok(mut processes) = CHILD_PROCESSES.lock() {
+        for child in processes.iter_mut() {
             if child.id() == pid {
-                // Try to check if process is still running
-                // Note: This is a simplification - in production, we'd use
-                // platform-specific APIs to check process status
-                return true;
+                return matches!(child.try_wait(), Ok(None));
             }
         }
     }
     false
 }
 
+/// Status of a tracked worker process.
+#[derive(Debug)]
+pub(super) enum WorkerStatus {
+    /// The process is still running.
+    Running,
+    /// The process exited and has been removed from tracking.
+    Exited(ExitStatus),
+    /// No tracked process has this PID (already reaped or killed).
+    Untracked,
+}
+
+/// Check whether a worker process has exited, reaping it if so.
+pub(super) fn try_reap_worker(pid: u32) -> WorkerStatus {
+    let Ok(mut processes) = CHILD_PROCESSES.lock() else {
+        return WorkerStatus::Untracked;
+    };
+    let Some(index) = processes.iter().position(|child| child.id() == pid) else {
+        return WorkerStatus::Untracked;
+    };
+    match processes[index].try_wait() {
+        Ok(None) => WorkerStatus::Running,
+        Ok(Some(status)) => {
+            // Already reaped by try_wait above
+            #[allow(clippy::zombie_processes)]
+            processes.swap_remove(index);
+            WorkerStatus::Exited(status)
+        },
+        Err(e) => {
+            error!("Failed to check worker process {}: {}", pid, e);
+            WorkerStatus::Running
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_try_reap_worker_reports_exit_status() {
+        let child = Command::new("sh").args(["-c", "exit 3"]).spawn().unwrap();
+        let pid = child.id();
+        store_child_process(child);
+
+        let status = loop {
+            match try_reap_worker(pid) {
+                WorkerStatus::Running => std::thread::sleep(std::time::Duration::from_millis(10)),
+                WorkerStatus::Exited(status) => break status,
+                WorkerStatus::Untracked => panic!("worker should be tracked"),
+            }
+        };
+        assert_eq!(status.code(), Some(3));
+        assert!(matches!(try_reap_worker(pid), WorkerStatus::Untracked));
+        assert!(!is_worker_running(pid));
+    }
+
     #[test]
     fn test_spawn_workers_count() {
         // This test would require mocking Command::spawn
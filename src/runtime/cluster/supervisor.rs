@@ -0,0 +1,385 @@
+//! Crash detection and restart for process-based workers.
+//!
+//! A [`Supervisor`] watches each worker process of a [`Cluster`](super::Cluster).
+//! When a worker exits with a failure status it is:
+//!
+//! 1. Marked unhealthy in the load balancer's backend pool (if one is attached)
+//! 2. Restarted on the same port after an exponential backoff
+//! 3. Marked healthy again once its health endpoint responds with 2xx
+//!
+//! A worker that exits successfully (e.g. after SIGTERM during shutdown) is
+//! treated as intentionally stopped and is not restarted.
+//!
+//! # Metrics
+//!
+//! - `mik_cluster_worker_restarts_total` - Worker restarts (labels: worker)
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mik::runtime::cluster::{ClusterBuilder, RestartPolicy};
+//! use mik::runtime::lb::LoadBalancer;
+//!
+//! let cluster = ClusterBuilder::new()
+//!     .workers(4)
+//!     .manifest_file("mik.toml")
+//!     .spawn_processes()?;
+//!
+//! let lb = LoadBalancer::builder()
+//!     .backends(cluster.worker_addrs().iter().map(ToString::to_string))
+//!     .build()?;
+//!
+//! let supervisor = cluster.supervise(RestartPolicy::default(), Some(lb.proxy().backend_pool()))?;
+//! lb.serve().await?;
+//! ```
+
+use super::process;
+use crate::runtime::lb::Backend;
+use metrics::{counter, describe_counter};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Shared backend list of a load balancer proxy.
+pub type BackendPool = Arc<RwLock<Vec<Backend>>>;
+
+/// Global flag to track if supervisor metrics have been registered.
+static SUPERVISOR_METRICS_REGISTERED: OnceLock<()> = OnceLock::new();
+
+fn register_supervisor_metrics() {
+    SUPERVISOR_METRICS_REGISTERED.get_or_init(|| {
+        describe_counter!(
+            "mik_cluster_worker_restarts_total",
+            "Total number of worker process restarts after a crash"
+        );
+    });
+}
+
+/// Restart behavior for crashed workers.
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    /// Delay before the first restart attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound for the restart delay.
+    pub max_backoff: Duration,
+    /// How often worker processes are checked for exit.
+    pub check_interval: Duration,
+    /// Health endpoint polled after a restart.
+    pub health_path: String,
+    /// How long to wait for a restarted worker to become healthy.
+    pub health_timeout: Duration,
+    /// Uptime after which a worker's backoff is reset.
+    pub reset_after: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            check_interval: Duration::from_secs(1),
+            health_path: "/health".to_string(),
+            health_timeout: Duration::from_secs(30),
+            reset_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Delay before restart attempt `attempt` (0-based), doubling each time.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt.min(31)).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// State of one supervised worker slot.
+#[derive(Debug)]
+struct WorkerSlot {
+    port: u16,
+    pid: AtomicU32,
+    restarts: AtomicU64,
+    stopped: AtomicBool,
+}
+
+/// Handle to running worker supervision.
+///
+/// Dropping the handle stops supervision; call [`stop`](Self::stop) before
+/// shutting workers down so they are not restarted.
+#[derive(Debug)]
+pub struct Supervisor {
+    slots: Vec<Arc<WorkerSlot>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Supervisor {
+    /// Start supervising the given `(pid, port)` worker processes.
+    pub(super) fn start(
+        workers: Vec<(u32, u16)>,
+        manifest_path: String,
+        policy: RestartPolicy,
+        pool: Option<BackendPool>,
+    ) -> Self {
+        register_supervisor_metrics();
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(2))
+            .build()
+            .unwrap_or_default();
+        let ctx = Arc::new(SupervisorContext {
+            manifest_path,
+            policy,
+            pool,
+            client,
+        });
+
+        let slots: Vec<Arc<WorkerSlot>> = workers
+            .into_iter()
+            .map(|(pid, port)| {
+                Arc::new(WorkerSlot {
+                    port,
+                    pid: AtomicU32::new(pid),
+                    restarts: AtomicU64::new(0),
+                    stopped: AtomicBool::new(false),
+                })
+            })
+            .collect();
+
+        let tasks = slots
+            .iter()
+            .map(|slot| tokio::spawn(supervise_worker(slot.clone(), ctx.clone())))
+            .collect();
+
+        Self { slots, tasks }
+    }
+
+    /// Number of restarts for the worker on `port`.
+    #[must_use]
+    pub fn restart_count(&self, port: u16) -> u64 {
+        self.slots
+            .iter()
+            .find(|slot| slot.port == port)
+            .map_or(0, |slot| slot.restarts.load(Ordering::Relaxed))
+    }
+
+    /// Total restarts across all workers.
+    #[must_use]
+    pub fn total_restarts(&self) -> u64 {
+        self.slots
+            .iter()
+            .map(|slot| slot.restarts.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Current process ID of the worker on `port` (changes after a restart).
+    #[must_use]
+    pub fn worker_pid(&self, port: u16) -> Option<u32> {
+        self.slots
+            .iter()
+            .find(|slot| slot.port == port)
+            .map(|slot| slot.pid.load(Ordering::Acquire))
+    }
+
+    /// Number of workers that exited cleanly and are no longer supervised.
+    #[must_use]
+    pub fn stopped_count(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.stopped.load(Ordering::Acquire))
+            .count()
+    }
+
+    /// Stop supervising. Workers keep running but will not be restarted.
+    pub fn stop(&self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for Supervisor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Settings shared by all worker supervision tasks.
+struct SupervisorContext {
+    manifest_path: String,
+    policy: RestartPolicy,
+    pool: Option<BackendPool>,
+    client: reqwest::Client,
+}
+
+/// Watch one worker slot until it stops cleanly or supervision is aborted.
+async fn supervise_worker(slot: Arc<WorkerSlot>, ctx: Arc<SupervisorContext>) {
+    let policy = &ctx.policy;
+    let addr = SocketAddr::from(([127, 0, 0, 1], slot.port));
+    let mut attempt = 0u32;
+    let mut started = Instant::now();
+
+    loop {
+        tokio::time::sleep(policy.check_interval).await;
+
+        let pid = slot.pid.load(Ordering::Acquire);
+        let status = match process::try_reap_worker(pid) {
+            process::WorkerStatus::Running => continue,
+            process::WorkerStatus::Untracked => {
+                // Removed by kill_all_workers/wait_all_workers: cluster is shutting down
+                slot.stopped.store(true, Ordering::Release);
+                return;
+            },
+            process::WorkerStatus::Exited(status) => status,
+        };
+
+        if status.success() {
+            info!(
+                "Worker process {} on port {} exited cleanly",
+                pid, slot.port
+            );
+            slot.stopped.store(true, Ordering::Release);
+            return;
+        }
+
+        error!(
+            "Worker process {} on port {} crashed ({}), restarting",
+            pid, slot.port, status
+        );
+        if let Some(pool) = &ctx.pool {
+            set_backend_health(pool, addr, false).await;
+        }
+
+        if started.elapsed() >= policy.reset_after {
+            attempt = 0;
+        }
+
+        // Respawn with backoff until the process starts
+        loop {
+            let delay = policy.backoff(attempt);
+            attempt = attempt.saturating_add(1);
+            tokio::time::sleep(delay).await;
+
+            match process::spawn_worker(slot.port, &ctx.manifest_path) {
+                Ok(handle) => {
+                    if let super::WorkerHandle::Process { pid, .. } = handle {
+                        slot.pid.store(pid, Ordering::Release);
+                    }
+                    break;
+                },
+                Err(e) => warn!(
+                    "Failed to restart worker on port {} (retrying in {:?}): {:#}",
+                    slot.port,
+                    policy.backoff(attempt),
+                    e
+                ),
+            }
+        }
+
+        started = Instant::now();
+        slot.restarts.fetch_add(1, Ordering::Relaxed);
+        counter!(
+            "mik_cluster_worker_restarts_total",
+            "worker" => slot.port.to_string()
+        )
+        .increment(1);
+
+        if wait_healthy(&ctx.client, addr, policy).await {
+            info!("Restarted worker on port {} is healthy", slot.port);
+            if let Some(pool) = &ctx.pool {
+                set_backend_health(pool, addr, true).await;
+            }
+        } else {
+            warn!(
+                "Restarted worker on port {} not healthy after {:?}; leaving it to health checks",
+                slot.port, policy.health_timeout
+            );
+        }
+    }
+}
+
+/// Poll the worker's health endpoint until it returns 2xx or the timeout elapses.
+async fn wait_healthy(client: &reqwest::Client, addr: SocketAddr, policy: &RestartPolicy) -> bool {
+    let url = format!("http://{addr}{}", policy.health_path);
+    let deadline = Instant::now() + policy.health_timeout;
+
+    while Instant::now() < deadline {
+        if let Ok(response) = client.get(&url).send().await
+            && response.status().is_success()
+        {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    false
+}
+
+/// Mark the HTTP backend at `addr` healthy or unhealthy.
+///
+/// Returns `true` if a matching backend was found.
+async fn set_backend_health(pool: &RwLock<Vec<Backend>>, addr: SocketAddr, healthy: bool) -> bool {
+    let target = addr.to_string();
+    let backends = pool.read().await;
+    let mut found = false;
+    for backend in backends.iter() {
+        if let Backend::Http(http) = backend
+            && http.authority() == target
+        {
+            if healthy {
+                backend.mark_healthy();
+            } else {
+                backend.mark_unhealthy();
+            }
+            found = true;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RestartPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_set_backend_health_matches_worker_address() {
+        let pool: BackendPool = Arc::new(RwLock::new(vec![
+            Backend::http("127.0.0.1:3001"),
+            Backend::http("http://127.0.0.1:3002"),
+        ]));
+        let worker = SocketAddr::from(([127, 0, 0, 1], 3002));
+
+        assert!(set_backend_health(&pool, worker, false).await);
+        {
+            let backends = pool.read().await;
+            assert!(backends[0].is_healthy());
+            assert!(!backends[1].is_healthy());
+        }
+
+        assert!(set_backend_health(&pool, worker, true).await);
+        assert!(pool.read().await[1].is_healthy());
+
+        let unknown = SocketAddr::from(([127, 0, 0, 1], 9999));
+        assert!(!set_backend_health(&pool, unknown, false).await);
+    }
+}
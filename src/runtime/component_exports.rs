@@ -0,0 +1,114 @@
+//! Which worlds a component implements, detected from its exports at load time.
+//!
+//! A component can serve HTTP (`wasi:http/incoming-handler`), run as a job
+//! (`mik:job/handler` or `wasi:cli/run`, see [`job`](crate::runtime::job)),
+//! or both. Routing a request to a component without the matching export
+//! fails with [`Error::UnsupportedComponent`] before instantiation instead of
+//! trapping when the export is called.
+
+use crate::runtime::error::Error;
+use crate::runtime::init_hook;
+use crate::runtime::job::JobExport;
+use wasmtime::Engine;
+use wasmtime::component::Component;
+
+/// Export prefix of `wasi:http/proxy` handlers.
+const HTTP_HANDLER_EXPORT: &str = "wasi:http/incoming-handler@0.2";
+
+/// Entry points a component exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct ComponentExports {
+    /// Exports `wasi:http/incoming-handler`.
+    pub http: bool,
+    /// Export used by jobs, if any.
+    pub job: Option<JobExport>,
+    /// `init`/`warmup` hook run after loading (see [`init_hook`](crate::runtime::init_hook)).
+    pub init: Option<&'static str>,
+}
+
+impl ComponentExports {
+    /// Inspect the exports of a compiled component.
+    pub(crate) fn detect(engine: &Engine, component: &Component) -> Self {
+        let ty = component.component_type();
+        let names: Vec<&str> = ty.exports(engine).map(|(name, _)| name).collect();
+        Self {
+            init: init_hook::detect(engine, component),
+            ..Self::from_names(names.iter().copied())
+        }
+    }
+
+    fn from_names<'a>(names: impl Iterator<Item = &'a str> + Clone) -> Self {
+        Self {
+            http: names
+                .clone()
+                .any(|name| name.starts_with(HTTP_HANDLER_EXPORT)),
+            job: JobExport::detect(names),
+            init: None,
+        }
+    }
+
+    /// Short description for logs and errors, e.g. `http+job`.
+    pub(crate) const fn describe(&self) -> &'static str {
+        match (self.http, self.job.is_some()) {
+            (true, true) => "http+job",
+            (true, false) => "http",
+            (false, true) => "job",
+            (false, false) => "unsupported",
+        }
+    }
+
+    /// Fail unless `module` can handle HTTP requests.
+    pub(crate) fn require_http(&self, module: &str) -> Result<(), Error> {
+        if self.http {
+            return Ok(());
+        }
+        let reason = if self.job.is_some() {
+            "does not export wasi:http/incoming-handler (it is a job component, run it with invoke_job)"
+        } else {
+            "does not export wasi:http/incoming-handler"
+        };
+        Err(Error::unsupported_component(module, reason))
+    }
+
+    /// The export to run `module` as a job with.
+    pub(crate) fn require_job(&self, module: &str) -> Result<JobExport, Error> {
+        self.job.ok_or_else(|| {
+            Error::unsupported_component(module, "exports neither mik:job/handler nor wasi:cli/run")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_worlds() {
+        let http = ComponentExports::from_names(["wasi:http/incoming-handler@0.2.3"].into_iter());
+        assert_eq!(
+            http,
+            ComponentExports {
+                http: true,
+                job: None,
+                init: None
+            }
+        );
+        assert!(http.require_http("api").is_ok());
+        assert!(http.require_job("api").is_err());
+
+        let both = ComponentExports::from_names(
+            ["wasi:http/incoming-handler@0.2.0", "mik:job/handler@0.1.0"].into_iter(),
+        );
+        assert_eq!(both.describe(), "http+job");
+        assert_eq!(both.require_job("sync").unwrap(), JobExport::Handler);
+
+        let job = ComponentExports::from_names(["wasi:cli/run@0.2.0"].into_iter());
+        let err = job.require_http("nightly").unwrap_err();
+        assert!(matches!(err, Error::UnsupportedComponent { .. }));
+        assert!(err.to_string().contains("run it with invoke_job"), "{err}");
+
+        let none = ComponentExports::from_names(["my:lib/utils@1.0.0"].into_iter());
+        assert_eq!(none.describe(), "unsupported");
+        assert!(none.require_http("lib").is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! What a component needs and provides, for `mik inspect`.
+//!
+//! [`inspect`] decodes a component's world (its imported and exported
+//! interfaces), lists its custom sections, breaks its size down by section
+//! kind and extracts its embedded OpenAPI document (see
+//! [`component_openapi`](super::component_openapi)). Core modules are
+//! inspected as the component they are adapted to at load time.
+//!
+//! Each import is checked against the interfaces mik provides, so a
+//! component needing, say, a custom `acme:cache` interface is caught before
+//! it is deployed, and imports that link but are restricted at runtime
+//! (`wasi:sockets` has no network access) are called out. The component is
+//! also linked against the runtime's own linker, which catches version and
+//! type mismatches too.
+
+use super::component_openapi;
+use super::core_adapter;
+use super::host::Host;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use wasmparser::{Parser, Payload};
+use wasmtime::component::Component;
+use wasmtime::{Config, Engine};
+use wit_component::{DecodedWasm, WitPrinter};
+use wit_parser::{Resolve, WorldId, WorldItem};
+
+/// Interfaces mik provides, by name prefix, with their runtime restrictions.
+const PROVIDED: &[(&str, Option<&str>)] = &[
+    ("wasi:io/", None),
+    ("wasi:clocks/", None),
+    ("wasi:random/", None),
+    ("wasi:cli/", None),
+    (
+        "wasi:filesystem/",
+        Some("only directories granted in [server.preopens]"),
+    ),
+    (
+        "wasi:sockets/",
+        Some("no network access is granted, calls fail at runtime"),
+    ),
+    ("wasi:http/types", None),
+    (
+        "wasi:http/outgoing-handler",
+        Some("only hosts listed in http_allowed"),
+    ),
+    ("mik:request-info/", None),
+    ("mik:sql/", Some("only for modules listed in sql_modules")),
+    #[cfg(feature = "nn")]
+    (
+        "wasi:nn/",
+        Some("only models granted in [server.nn_models]"),
+    ),
+];
+
+/// Whether mik can satisfy an import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Support {
+    /// Provided without restrictions.
+    Provided,
+    /// Provided, with a restriction that applies at runtime.
+    Restricted(&'static str),
+    /// Not provided: the component cannot be instantiated.
+    Missing,
+}
+
+/// An imported or exported interface or function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldEntry {
+    /// Interface name (`wasi:http/types@0.2.0`) or function name.
+    pub name: String,
+    /// Whether it is a function rather than an interface.
+    pub function: bool,
+}
+
+/// What [`inspect`] found in a component.
+#[derive(Debug)]
+pub struct ComponentInfo {
+    /// Size of the file.
+    pub size_bytes: usize,
+    /// Whether the file is a core module (adapted with WASI preview1).
+    pub core_module: bool,
+    /// The component's world, as WIT.
+    pub wit: String,
+    /// Imports, with what mik provides for them.
+    pub imports: Vec<(WorldEntry, Support)>,
+    /// Exports, with their role in mik (`HTTP handler`, `job`...) if any.
+    pub exports: Vec<(WorldEntry, Option<&'static str>)>,
+    /// Custom sections by name, with their total size.
+    pub custom_sections: BTreeMap<String, usize>,
+    /// Bytes by section kind, largest first.
+    pub sizes: Sizes,
+    /// The embedded OpenAPI document, or why it could not be extracted.
+    pub openapi: Result<Option<Value>, String>,
+    /// Why the runtime cannot instantiate the component, if it cannot.
+    pub link_error: Option<String>,
+}
+
+/// Inspect the component or core module `wasm`.
+pub async fn inspect(wasm: &[u8]) -> Result<ComponentInfo> {
+    let core_module = Parser::is_core_wasm(wasm);
+    if !core_module && !Parser::is_component(wasm) {
+        bail!("Not a WebAssembly component or module");
+    }
+    let component = core_adapter::componentize(wasm)?;
+
+    let (resolve, world) = match wit_component::decode(&component)
+        .context("Failed to decode the component's world")?
+    {
+        DecodedWasm::Component(resolve, world) => (resolve, world),
+        DecodedWasm::WitPackage(..) => bail!("This is a WIT package, not a component"),
+    };
+    let imports = world_entries(&resolve, world, true)
+        .into_iter()
+        .map(|entry| {
+            let support = if entry.function {
+                Support::Missing
+            } else {
+                support(&entry.name)
+            };
+            (entry, support)
+        })
+        .collect();
+    let exports = world_entries(&resolve, world, false)
+        .into_iter()
+        .map(|entry| {
+            let role = role(&entry.name);
+            (entry, role)
+        })
+        .collect();
+    let (sizes, custom_sections) = section_sizes(wasm)?;
+
+    Ok(ComponentInfo {
+        size_bytes: wasm.len(),
+        core_module,
+        wit: print_world(&resolve, world)?,
+        imports,
+        exports,
+        custom_sections,
+        sizes,
+        openapi: component_openapi::extract(wasm)
+            .await
+            .map_err(|e| format!("{e:#}")),
+        link_error: link(&component).err().map(|e| format!("{e:#}")),
+    })
+}
+
+/// What mik provides for the interface `name`.
+fn support(name: &str) -> Support {
+    PROVIDED
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map_or(Support::Missing, |(_, restriction)| match restriction {
+            Some(restriction) => Support::Restricted(restriction),
+            None => Support::Provided,
+        })
+}
+
+/// What mik uses the export `name` for.
+fn role(name: &str) -> Option<&'static str> {
+    if name.starts_with("wasi:http/incoming-handler@0.2") {
+        Some("HTTP handler")
+    } else if name.starts_with("mik:job/handler@0.1") || name.starts_with("wasi:cli/run@0.2") {
+        Some("job")
+    } else if name == "init" || name == "warmup" {
+        Some("init hook")
+    } else if name == component_openapi::OPENAPI_EXPORT {
+        Some("OpenAPI document")
+    } else {
+        None
+    }
+}
+
+/// Imported or exported interfaces and functions of `world`.
+fn world_entries(resolve: &Resolve, world: WorldId, imports: bool) -> Vec<WorldEntry> {
+    let world = &resolve.worlds[world];
+    let items = if imports {
+        &world.imports
+    } else {
+        &world.exports
+    };
+    items
+        .iter()
+        .filter_map(|(key, item)| {
+            let function = match item {
+                WorldItem::Interface { .. } => false,
+                WorldItem::Function(_) => true,
+                WorldItem::Type(_) => return None,
+            };
+            Some(WorldEntry {
+                name: resolve.name_world_key(key),
+                function,
+            })
+        })
+        .collect()
+}
+
+/// `world` as WIT, without its package declaration.
+fn print_world(resolve: &Resolve, world: WorldId) -> Result<String> {
+    let package = resolve.worlds[world]
+        .package
+        .context("Component world has no package")?;
+    let mut printer = WitPrinter::default();
+    printer.emit_docs(false);
+    printer.print(resolve, package, &[])?;
+    let wit = printer.output.to_string();
+    Ok(wit
+        .lines()
+        .skip_while(|line| !line.starts_with("world "))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Bytes by section kind, largest first.
+pub type Sizes = Vec<(&'static str, usize)>;
+
+/// Bytes by section kind, and custom sections by name, of `wasm` and the
+/// modules and components nested in it.
+pub fn section_sizes(wasm: &[u8]) -> Result<(Sizes, BTreeMap<String, usize>)> {
+    let mut sizes: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut custom = BTreeMap::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload.context("Failed to parse wasm")?;
+        let kind = match &payload {
+            // Nested modules and components are parsed section by section
+            Payload::ModuleSection { .. } | Payload::ComponentSection { .. } => continue,
+            Payload::CodeSectionStart { .. } => "code",
+            Payload::DataSection(_) => "data",
+            Payload::CustomSection(section) => {
+                *custom.entry(section.name().to_string()).or_default() += section.data().len();
+                "custom sections"
+            },
+            Payload::ComponentTypeSection(_)
+            | Payload::ComponentImportSection(_)
+            | Payload::ComponentExportSection(_)
+            | Payload::ComponentCanonicalSection(_)
+            | Payload::ComponentAliasSection(_)
+            | Payload::ComponentInstanceSection(_)
+            | Payload::ComponentStartSection { .. }
+            | Payload::InstanceSection(_)
+            | Payload::CoreTypeSection(_) => "component wiring",
+            _ => "declarations",
+        };
+        if let Some((_, range)) = payload.as_section() {
+            *sizes.entry(kind).or_default() += range.len();
+        }
+    }
+    let mut sizes: Vec<_> = sizes.into_iter().collect();
+    sizes.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    Ok((sizes, custom))
+}
+
+/// Link `component` against the runtime's linker.
+fn link(component: &[u8]) -> Result<()> {
+    let mut config = Config::new();
+    config.wasm_component_model(true);
+    config.async_support(true);
+    let engine = Engine::new(&config).context("Failed to create wasmtime engine")?;
+    let component = Component::new(&engine, component).context("Failed to compile component")?;
+    Host::create_linker(&engine)?.instantiate_pre(&component)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Component importing a provided, a restricted and an unknown interface.
+    const IMPORTS: &str = r#"
+        (component
+            (import "wasi:random/random@0.2.0" (instance
+                (export "get-random-u64" (func (result u64)))))
+            (import "wasi:sockets/instance-network@0.2.0" (instance))
+            (import "acme:cache/store@1.0.0" (instance
+                (export "size" (func (result u64)))))
+            (core module $m (@custom "mik:openapi" "{\"openapi\":\"3.0.0\"}")))
+    "#;
+
+    #[test]
+    fn test_support() {
+        assert_eq!(support("wasi:io/streams@0.2.0"), Support::Provided);
+        assert!(matches!(
+            support("wasi:sockets/tcp@0.2.0"),
+            Support::Restricted(_)
+        ));
+        assert_eq!(
+            support("wasi:http/incoming-handler@0.2.0"),
+            Support::Missing
+        );
+        assert_eq!(support("acme:cache/store@1.0.0"), Support::Missing);
+    }
+
+    #[test]
+    fn test_role() {
+        assert_eq!(
+            role("wasi:http/incoming-handler@0.2.3"),
+            Some("HTTP handler")
+        );
+        assert_eq!(role("wasi:cli/run@0.2.0"), Some("job"));
+        assert_eq!(role("warmup"), Some("init hook"));
+        assert_eq!(role("acme:lib/api@1.0.0"), None);
+    }
+
+    #[tokio::test]
+    async fn test_inspect_flags_missing_imports() {
+        let wasm = wat::parse_str(IMPORTS).unwrap();
+        let info = inspect(&wasm).await.unwrap();
+
+        let support: BTreeMap<_, _> = info
+            .imports
+            .iter()
+            .map(|(entry, support)| (entry.name.as_str(), *support))
+            .collect();
+        assert_eq!(support["wasi:random/random@0.2.0"], Support::Provided);
+        assert!(matches!(
+            support["wasi:sockets/instance-network@0.2.0"],
+            Support::Restricted(_)
+        ));
+        assert_eq!(support["acme:cache/store@1.0.0"], Support::Missing);
+        assert!(info.wit.starts_with("world "), "{}", info.wit);
+        assert!(info.wit.contains("acme:cache/store@1.0.0"), "{}", info.wit);
+
+        let link_error = info.link_error.unwrap();
+        assert!(
+            link_error.contains("acme:cache/store@1.0.0"),
+            "{link_error}"
+        );
+        assert!(info.custom_sections.contains_key("mik:openapi"));
+        assert_eq!(info.openapi.unwrap().unwrap()["openapi"], "3.0.0");
+        assert!(!info.core_module);
+    }
+
+    #[tokio::test]
+    async fn test_inspect_rejects_non_wasm() {
+        assert!(inspect(b"not wasm").await.is_err());
+    }
+
+    #[test]
+    fn test_section_sizes() {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1)
+                (data (i32.const 0) "hello")
+                (func (export "f") (result i32) i32.const 1)
+                (@custom "producers" "xyz"))"#,
+        )
+        .unwrap();
+        let (sizes, custom) = section_sizes(&wasm).unwrap();
+        let sizes: BTreeMap<_, _> = sizes.into_iter().collect();
+        assert!(sizes["code"] > 0);
+        assert!(sizes["data"] >= 5);
+        assert!(sizes["declarations"] > 0);
+        assert_eq!(custom["producers"], 3);
+    }
+}
@@ -0,0 +1,220 @@
+//! OpenAPI metadata embedded in a component, extracted by `mik build`.
+//!
+//! A component describes its routes and models in one of two ways:
+//!
+//! - a `mik:openapi` custom section holding the JSON document, in the
+//!   component or any core module inside it;
+//! - a root-level export returning the document:
+//!
+//! ```wit
+//! world handler {
+//!     export wasi:http/incoming-handler@0.2.0;
+//!     export openapi: func() -> string;
+//! }
+//! ```
+//!
+//! The custom section is preferred, since reading it runs no guest code. The
+//! export is called on a fresh instance with an empty WASI context (no
+//! environment, filesystem or network) and a fixed fuel budget; any other
+//! import traps if called. `result<string, string>` is accepted too, with the
+//! error failing the extraction.
+//!
+//! `mik build` writes the document as `<module>.openapi.json` next to the
+//! wasm, where the gateway's [`openapi`](crate::runtime::gateway) aggregation
+//! picks it up.
+
+use crate::runtime::core_adapter;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use wasmparser::{Parser, Payload};
+use wasmtime::component::{Component, Linker, ResourceTable, Val};
+use wasmtime::{Config, Engine, Store};
+use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
+
+/// Custom section holding the OpenAPI document.
+pub const OPENAPI_SECTION: &str = "mik:openapi";
+
+/// Root-level export returning the OpenAPI document.
+pub const OPENAPI_EXPORT: &str = "openapi";
+
+/// Fuel available to the `openapi` export.
+const EXPORT_FUEL: u64 = 1_000_000_000;
+
+/// The OpenAPI document embedded in `wasm`, if it has one.
+///
+/// Fails when the metadata is present but is not a JSON object, or when the
+/// export traps or returns an error.
+pub async fn extract(wasm: &[u8]) -> Result<Option<Value>> {
+    let raw = match custom_section(wasm)? {
+        Some(raw) => raw,
+        None => match call_export(wasm).await? {
+            Some(raw) => raw,
+            None => return Ok(None),
+        },
+    };
+    let spec: Value = serde_json::from_str(&raw).context("OpenAPI metadata is not valid JSON")?;
+    if !spec.is_object() {
+        bail!("OpenAPI metadata is not a JSON object");
+    }
+    Ok(Some(spec))
+}
+
+/// Contents of the first `mik:openapi` custom section, searching nested
+/// modules and components too.
+fn custom_section(wasm: &[u8]) -> Result<Option<String>> {
+    for payload in Parser::new(0).parse_all(wasm) {
+        if let Payload::CustomSection(section) = payload.context("Failed to parse wasm")?
+            && section.name() == OPENAPI_SECTION
+        {
+            let raw = std::str::from_utf8(section.data())
+                .with_context(|| format!("{OPENAPI_SECTION} section is not UTF-8"))?;
+            return Ok(Some(raw.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// Result of calling the `openapi` export, or `None` when the component
+/// does not export it.
+async fn call_export(wasm: &[u8]) -> Result<Option<String>> {
+    let mut config = Config::new();
+    config.wasm_component_model(true);
+    config.async_support(true);
+    config.consume_fuel(true);
+    let engine = Engine::new(&config).context("Failed to create wasmtime engine")?;
+
+    let component = Component::new(&engine, core_adapter::componentize(wasm)?)
+        .context("Failed to compile component")?;
+    let exported = component
+        .component_type()
+        .exports(&engine)
+        .any(|(name, _)| name == OPENAPI_EXPORT);
+    if !exported {
+        return Ok(None);
+    }
+
+    let mut linker: Linker<ExtractState> = Linker::new(&engine);
+    wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+    linker.define_unknown_imports_as_traps(&component)?;
+
+    let mut store = Store::new(
+        &engine,
+        ExtractState {
+            wasi: WasiCtx::builder().build(),
+            table: ResourceTable::new(),
+        },
+    );
+    store.set_fuel(EXPORT_FUEL)?;
+
+    let instance = linker
+        .instantiate_async(&mut store, &component)
+        .await
+        .context("Failed to instantiate component")?;
+    let func = instance
+        .get_func(&mut store, OPENAPI_EXPORT)
+        .with_context(|| format!("export '{OPENAPI_EXPORT}' is not a function"))?;
+    let mut results = vec![Val::Bool(false); func.ty(&store).results().len()];
+    func.call_async(&mut store, &[], &mut results)
+        .await
+        .with_context(|| format!("{OPENAPI_EXPORT}() failed"))?;
+
+    let value = match results.first() {
+        Some(Val::Result(Ok(value))) => value.as_deref(),
+        Some(Val::Result(Err(error))) => bail!(
+            "{OPENAPI_EXPORT}() returned an error: {}",
+            as_str(error.as_deref()).unwrap_or("unknown error")
+        ),
+        other => other,
+    };
+    as_str(value)
+        .map(|raw| Some(raw.to_string()))
+        .with_context(|| format!("{OPENAPI_EXPORT}() must return string or result<string, string>"))
+}
+
+fn as_str(value: Option<&Val>) -> Option<&str> {
+    match value {
+        Some(Val::String(s)) => Some(s),
+        _ => None,
+    }
+}
+
+/// Store state for calling the `openapi` export.
+struct ExtractState {
+    wasi: WasiCtx,
+    table: ResourceTable,
+}
+
+impl WasiView for ExtractState {
+    fn ctx(&mut self) -> WasiCtxView<'_> {
+        WasiCtxView {
+            ctx: &mut self.wasi,
+            table: &mut self.table,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Component exporting `openapi: func() -> string`.
+    const EXPORTS_OPENAPI: &str = r#"
+        (component
+            (core module $m
+                (memory (export "mem") 1)
+                (data (i32.const 16) "\20\00\00\00\19\00\00\00")
+                (data (i32.const 32) "{\"openapi\":\"3.0.0\",\"a\":1}")
+                (func (export "openapi") (result i32) i32.const 16))
+            (core instance $i (instantiate $m))
+            (func (export "openapi") (result string)
+                (canon lift (core func $i "openapi") (memory $i "mem"))))
+    "#;
+
+    /// Component exporting `openapi: func() -> result<string, string>`: `err("no routes")`.
+    const EXPORT_ERROR: &str = r#"
+        (component
+            (core module $m
+                (memory (export "mem") 1)
+                (data (i32.const 16) "\01\00\00\00\20\00\00\00\09\00\00\00")
+                (data (i32.const 32) "no routes")
+                (func (export "openapi") (result i32) i32.const 16))
+            (core instance $i (instantiate $m))
+            (func (export "openapi") (result (result string (error string)))
+                (canon lift (core func $i "openapi") (memory $i "mem"))))
+    "#;
+
+    #[tokio::test]
+    async fn test_extract_from_custom_section() {
+        let wasm = wat::parse_str(
+            r#"(component
+                (core module (@custom "mik:openapi" "{\"openapi\":\"3.0.0\"}")))"#,
+        )
+        .unwrap();
+        let spec = extract(&wasm).await.unwrap().unwrap();
+        assert_eq!(spec["openapi"], "3.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_extract_from_export() {
+        let wasm = wat::parse_str(EXPORTS_OPENAPI).unwrap();
+        let spec = extract(&wasm).await.unwrap().unwrap();
+        assert_eq!(spec["openapi"], "3.0.0");
+        assert_eq!(spec["a"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_extract_errors() {
+        let wasm = wat::parse_str(EXPORT_ERROR).unwrap();
+        let err = extract(&wasm).await.unwrap_err();
+        assert!(format!("{err:#}").contains("no routes"), "{err:#}");
+
+        let wasm = wat::parse_str(r#"(component (@custom "mik:openapi" "[1]"))"#).unwrap();
+        assert!(extract(&wasm).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_extract_without_metadata() {
+        let wasm = wat::parse_str("(component)").unwrap();
+        assert!(extract(&wasm).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,104 @@
+//! Plain core modules, turned into components at load time.
+//!
+//! Toolchains that only target `wasm32-wasip1` produce core modules importing
+//! `wasi_snapshot_preview1`. Those are wrapped with the WASI preview1 adapter
+//! before compilation: the command adapter for modules exporting `_start`
+//! (which then run as `wasi:cli/run` jobs), the reactor adapter otherwise.
+//! Exports declared in a `component-type` custom section by `wit-bindgen`,
+//! such as `wasi:http/incoming-handler`, are kept.
+//!
+//! The AOT cache is keyed by the original bytes, so the adapter only runs the
+//! first time a module is compiled.
+
+use anyhow::{Context, Result};
+use std::borrow::Cow;
+use wasi_preview1_component_adapter_provider::{
+    WASI_SNAPSHOT_PREVIEW1_ADAPTER_NAME, WASI_SNAPSHOT_PREVIEW1_COMMAND_ADAPTER,
+    WASI_SNAPSHOT_PREVIEW1_REACTOR_ADAPTER,
+};
+use wasmparser::{Parser, Payload};
+use wit_component::ComponentEncoder;
+
+/// `wasm_bytes` as a component: core modules are componentized, anything
+/// else is returned as is.
+pub(crate) fn componentize(wasm_bytes: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if !Parser::is_core_wasm(wasm_bytes) {
+        return Ok(Cow::Borrowed(wasm_bytes));
+    }
+
+    let adapter = if exports_start(wasm_bytes) {
+        WASI_SNAPSHOT_PREVIEW1_COMMAND_ADAPTER
+    } else {
+        WASI_SNAPSHOT_PREVIEW1_REACTOR_ADAPTER
+    };
+    tracing::info!("Componentizing core module with the WASI preview1 adapter");
+
+    ComponentEncoder::default()
+        .module(wasm_bytes)
+        .and_then(|encoder| {
+            encoder
+                .validate(true)
+                .adapter(WASI_SNAPSHOT_PREVIEW1_ADAPTER_NAME, adapter)
+        })
+        .and_then(|mut encoder| encoder.encode())
+        .map(Cow::Owned)
+        .context("Failed to componentize core module")
+}
+
+/// Whether a core module exports `_start`.
+fn exports_start(wasm_bytes: &[u8]) -> bool {
+    Parser::new(0).parse_all(wasm_bytes).any(|payload| {
+        let Ok(Payload::ExportSection(exports)) = payload else {
+            return false;
+        };
+        exports
+            .into_iter()
+            .any(|export| export.is_ok_and(|export| export.name == "_start"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::component_exports::ComponentExports;
+    use crate::runtime::job::JobExport;
+    use wasmtime::component::Component;
+    use wasmtime::{Config, Engine};
+
+    /// A preview1 command printing nothing.
+    const COMMAND: &str = r#"
+        (module
+            (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+            (memory (export "memory") 1)
+            (func (export "_start")))
+    "#;
+
+    #[test]
+    fn test_componentize_core_command() {
+        let core = wat::parse_str(COMMAND).unwrap();
+        assert!(exports_start(&core));
+
+        let component = componentize(&core).unwrap();
+        assert!(matches!(component, Cow::Owned(_)));
+        assert!(Parser::is_component(&component));
+
+        let engine = Engine::new(Config::new().wasm_component_model(true)).unwrap();
+        let component = Component::from_binary(&engine, &component).unwrap();
+        let exports = ComponentExports::detect(&engine, &component);
+        assert_eq!(exports.job, Some(JobExport::Command));
+        assert!(!exports.http);
+    }
+
+    #[test]
+    fn test_components_pass_through() {
+        let component = wat::parse_str("(component)").unwrap();
+        assert!(matches!(
+            componentize(&component).unwrap(),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            componentize(b"not wasm").unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -0,0 +1,247 @@
+//! Per-request execution deadlines.
+//!
+//! Callers can override the default execution timeout for a single request
+//! with either header:
+//!
+//! - `X-Request-Timeout: 500ms` (also `2s`, `1m`, or bare seconds like `10`)
+//! - `grpc-timeout: 500m` (gRPC format: integer + `H`/`M`/`S`/`m`/`u`/`n`)
+//!
+//! The requested value is capped by the server's `max_request_timeout_secs`,
+//! so interactive callers can ask for a tight deadline and batch callers for a
+//! longer one without either exceeding server policy.
+//!
+//! A caller that is itself working against a deadline sends what is left of
+//! it in `X-Mik-Deadline` (milliseconds, or a gRPC-style value like `500m`).
+//! That budget only ever shortens the timeout. The decremented budget is
+//! forwarded in the same header on outgoing wasi:http requests and on script
+//! `host.call()`s and `fetch()`es, so downstream work stops once the original
+//! caller has given up.
+
+use hyper::HeaderMap;
+use hyper::header::HeaderValue;
+use std::time::Duration;
+
+/// Header carrying a human-style request timeout.
+pub const REQUEST_TIMEOUT_HEADER: &str = "x-request-timeout";
+
+/// Header carrying a gRPC-style request timeout.
+pub const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Header carrying the caller's remaining deadline budget.
+pub const DEADLINE_HEADER: &str = "x-mik-deadline";
+
+/// Smallest deadline honored, so a zero value cannot fail every request.
+const MIN_REQUEST_TIMEOUT: Duration = Duration::from_millis(1);
+
+/// Parse an `X-Request-Timeout` value: `250ms`, `2s`, `1m`, or bare seconds.
+pub fn parse_request_timeout(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, unit) = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .map_or((value, ""), |idx| value.split_at(idx));
+    let number: f64 = number.parse().ok()?;
+    if !number.is_finite() || number < 0.0 {
+        return None;
+    }
+    let secs = match unit.trim() {
+        "" | "s" => number,
+        "ms" => number / 1000.0,
+        "m" => number * 60.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+/// Parse a `grpc-timeout` value (up to 8 digits followed by a unit).
+pub fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+    let (digits, unit) = value.split_at(value.len() - 1);
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let n: u64 = digits.parse().ok()?;
+    Some(match unit {
+        "H" => Duration::from_secs(n * 3600),
+        "M" => Duration::from_secs(n * 60),
+        "S" => Duration::from_secs(n),
+        "m" => Duration::from_millis(n),
+        "u" => Duration::from_micros(n),
+        "n" => Duration::from_nanos(n),
+        _ => return None,
+    })
+}
+
+/// Timeout requested by the caller, if any valid header is present.
+///
+/// `X-Request-Timeout` takes precedence over `grpc-timeout`.
+pub fn requested_timeout(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    header(REQUEST_TIMEOUT_HEADER)
+        .and_then(parse_request_timeout)
+        .or_else(|| header(GRPC_TIMEOUT_HEADER).and_then(parse_grpc_timeout))
+}
+
+/// Parse an `X-Mik-Deadline` value: bare milliseconds or a `grpc-timeout` value.
+pub fn parse_deadline(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) {
+        return value.parse().ok().map(Duration::from_millis);
+    }
+    parse_grpc_timeout(value)
+}
+
+/// Remaining budget the caller sent in `X-Mik-Deadline`, if valid.
+pub fn caller_budget(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(DEADLINE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_deadline)
+}
+
+/// `X-Mik-Deadline` value forwarding a `remaining` budget.
+pub fn deadline_header(remaining: Duration) -> HeaderValue {
+    HeaderValue::from(remaining.as_millis() as u64)
+}
+
+/// Resolve the execution timeout for a request.
+///
+/// Uses the caller's requested timeout clamped to `max`, or `default` when
+/// no (valid) timeout header is present, then caps it at the caller's
+/// `X-Mik-Deadline` budget.
+pub fn effective_timeout(headers: &HeaderMap, default: Duration, max: Duration) -> Duration {
+    let timeout = requested_timeout(headers).map_or(default, |requested| {
+        requested.clamp(MIN_REQUEST_TIMEOUT, max.max(MIN_REQUEST_TIMEOUT))
+    });
+    caller_budget(headers).map_or(timeout, |budget| {
+        timeout.min(budget).max(MIN_REQUEST_TIMEOUT)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_request_timeout() {
+        assert_eq!(parse_request_timeout("10"), Some(Duration::from_secs(10)));
+        assert_eq!(parse_request_timeout("2s"), Some(Duration::from_secs(2)));
+        assert_eq!(
+            parse_request_timeout("250ms"),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(parse_request_timeout("1m"), Some(Duration::from_secs(60)));
+        assert_eq!(
+            parse_request_timeout("1.5s"),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(parse_request_timeout("soon"), None);
+        assert_eq!(parse_request_timeout("5h"), None);
+        assert_eq!(parse_request_timeout(""), None);
+    }
+
+    #[test]
+    fn test_parse_grpc_timeout() {
+        assert_eq!(parse_grpc_timeout("500m"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_grpc_timeout("3S"), Some(Duration::from_secs(3)));
+        assert_eq!(parse_grpc_timeout("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_grpc_timeout("100u"), Some(Duration::from_micros(100)));
+        assert_eq!(parse_grpc_timeout("S"), None);
+        assert_eq!(parse_grpc_timeout("123456789S"), None);
+        assert_eq!(parse_grpc_timeout("1.5S"), None);
+        assert_eq!(parse_grpc_timeout("10x"), None);
+    }
+
+    #[test]
+    fn test_effective_timeout_defaults_and_caps() {
+        let default = Duration::from_secs(30);
+        let max = Duration::from_secs(120);
+
+        assert_eq!(effective_timeout(&HeaderMap::new(), default, max), default);
+        assert_eq!(
+            effective_timeout(&headers(&[("x-request-timeout", "500ms")]), default, max),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            effective_timeout(&headers(&[("x-request-timeout", "10m")]), default, max),
+            max
+        );
+        assert_eq!(
+            effective_timeout(&headers(&[("grpc-timeout", "90S")]), default, max),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            effective_timeout(&headers(&[("x-request-timeout", "0")]), default, max),
+            Duration::from_millis(1)
+        );
+        // Invalid header falls back to the default
+        assert_eq!(
+            effective_timeout(&headers(&[("x-request-timeout", "later")]), default, max),
+            default
+        );
+    }
+
+    #[test]
+    fn test_request_timeout_header_wins_over_grpc() {
+        let h = headers(&[("x-request-timeout", "2s"), ("grpc-timeout", "5S")]);
+        assert_eq!(requested_timeout(&h), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_parse_deadline() {
+        assert_eq!(parse_deadline("1500"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_deadline("500m"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_deadline("2S"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_deadline(""), None);
+        assert_eq!(parse_deadline("-5"), None);
+        assert_eq!(parse_deadline("1.5s"), None);
+    }
+
+    #[test]
+    fn test_caller_budget_only_shortens_timeout() {
+        let default = Duration::from_secs(30);
+        let max = Duration::from_secs(120);
+
+        assert_eq!(
+            effective_timeout(&headers(&[("x-mik-deadline", "250")]), default, max),
+            Duration::from_millis(250)
+        );
+        // A budget beyond the timeout does not extend it
+        assert_eq!(
+            effective_timeout(&headers(&[("x-mik-deadline", "600000")]), default, max),
+            default
+        );
+        assert_eq!(
+            effective_timeout(
+                &headers(&[("x-request-timeout", "90s"), ("x-mik-deadline", "40S")]),
+                default,
+                max
+            ),
+            Duration::from_secs(40)
+        );
+        assert_eq!(
+            effective_timeout(&headers(&[("x-mik-deadline", "0")]), default, max),
+            Duration::from_millis(1)
+        );
+    }
+
+    #[test]
+    fn test_deadline_header_round_trips() {
+        let value = deadline_header(Duration::from_micros(1_234_567));
+        assert_eq!(value, "1234");
+        assert_eq!(
+            parse_deadline(value.to_str().unwrap()),
+            Some(Duration::from_millis(1234))
+        );
+    }
+}
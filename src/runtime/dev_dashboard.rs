@@ -0,0 +1,341 @@
+//! Development dashboard for `mik dev`.
+//!
+//! With `dev_dashboard` set, `/_dev/` serves a page that polls
+//! `/_dev/state` and shows:
+//!
+//! - Loaded modules
+//! - The latest requests, with their timing spans
+//! - Circuit breaker states
+//! - KV keys from the local daemon, when it is running
+//! - The tail of the instance's log file
+//!
+//! Requests to the dashboard itself are not recorded. There is no queue
+//! service in the daemon yet, so there are no queue contents to show.
+
+use crate::daemon::http::client::DaemonClient;
+use crate::daemon::startup::DAEMON_PORT;
+use crate::runtime::SharedState;
+use crate::runtime::spans::{Span, SpanNode, span_tree};
+use anyhow::Result;
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::{Method, Response};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+/// Route prefix for the development dashboard.
+pub const DEV_PREFIX: &str = "/_dev/";
+
+/// Requests kept for the dashboard.
+const MAX_RECENT_REQUESTS: usize = 50;
+
+/// Log lines shown on the dashboard.
+const LOG_TAIL_LINES: usize = 100;
+
+/// Bytes read from the end of the log file for the tail.
+const LOG_TAIL_BYTES: u64 = 64 * 1024;
+
+/// KV keys shown on the dashboard.
+const MAX_KV_KEYS: usize = 100;
+
+/// How long to wait for the daemon before showing KV as unavailable.
+const DAEMON_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// A request as shown on the dashboard.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentRequest {
+    /// When the request started (RFC 3339).
+    pub timestamp: String,
+    pub method: String,
+    pub path: String,
+    /// Response status (None when the request failed without a response).
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Timing spans, nested by parent.
+    pub spans: Vec<SpanNode>,
+}
+
+impl RecentRequest {
+    /// A request that took `duration` and produced `spans`.
+    pub(crate) fn new(
+        method: &Method,
+        path: &str,
+        status: Result<u16, String>,
+        duration: Duration,
+        spans: &[Span],
+    ) -> Self {
+        let (status, error) = match status {
+            Ok(status) => (Some(status), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            method: method.to_string(),
+            path: path.to_string(),
+            status,
+            duration_ms: duration.as_millis() as u64,
+            error,
+            spans: span_tree(spans),
+        }
+    }
+}
+
+/// The latest requests, newest first.
+#[derive(Debug, Default)]
+pub struct RecentRequests {
+    requests: Mutex<VecDeque<RecentRequest>>,
+}
+
+impl RecentRequests {
+    /// Record `request`, dropping the oldest one past the limit.
+    pub(crate) fn push(&self, request: RecentRequest) {
+        let mut requests = self.requests.lock();
+        requests.push_front(request);
+        requests.truncate(MAX_RECENT_REQUESTS);
+    }
+
+    /// The recorded requests, newest first.
+    pub fn snapshot(&self) -> Vec<RecentRequest> {
+        self.requests.lock().iter().cloned().collect()
+    }
+}
+
+/// Circuit breaker state as shown on the dashboard.
+#[derive(Debug, Serialize)]
+struct CircuitState {
+    key: String,
+    state: &'static str,
+    failure_count: u32,
+}
+
+/// Everything the dashboard shows, served on `/_dev/state`.
+#[derive(Debug, Serialize)]
+struct DashboardState {
+    modules: Vec<String>,
+    requests: Vec<RecentRequest>,
+    circuit_breakers: Vec<CircuitState>,
+    /// KV keys, or None when the daemon is not running.
+    kv_keys: Option<Vec<String>>,
+    log_tail: Vec<String>,
+}
+
+/// Whether `path` is a dashboard route.
+pub(crate) fn is_dashboard_path(path: &str) -> bool {
+    path == "/_dev" || path.starts_with(DEV_PREFIX)
+}
+
+/// Handle a request to `/_dev/*`.
+pub(crate) async fn handle(
+    shared: &SharedState,
+    log_file: &Path,
+    path: &str,
+) -> Result<Response<Full<Bytes>>> {
+    match path.trim_end_matches('/') {
+        "/_dev" => Ok(Response::builder()
+            .header("Content-Type", "text/html; charset=utf-8")
+            .header("Cache-Control", "no-store")
+            .body(Full::new(Bytes::from_static(PAGE.as_bytes())))?),
+        "/_dev/state" => {
+            let state = dashboard_state(shared, log_file).await;
+            Ok(Response::builder()
+                .header("Content-Type", "application/json")
+                .header("Cache-Control", "no-store")
+                .body(Full::new(Bytes::from(serde_json::to_vec(&state)?)))?)
+        },
+        _ => super::request_handler::not_found("Unknown dashboard route"),
+    }
+}
+
+async fn dashboard_state(shared: &SharedState, log_file: &Path) -> DashboardState {
+    shared.cache.run_pending_tasks();
+    let mut modules: Vec<String> = shared.cache.iter().map(|(key, _)| (*key).clone()).collect();
+    modules.sort();
+
+    let circuit_breakers = shared
+        .circuit_breaker
+        .snapshot()
+        .into_iter()
+        .map(|snapshot| CircuitState {
+            key: snapshot.key,
+            state: snapshot.state,
+            failure_count: snapshot.failure_count,
+        })
+        .collect();
+
+    let log_file = log_file.to_path_buf();
+    let log_tail = tokio::task::spawn_blocking(move || read_tail(&log_file, LOG_TAIL_LINES))
+        .await
+        .unwrap_or_default();
+
+    DashboardState {
+        modules,
+        requests: shared
+            .recent_requests
+            .as_ref()
+            .map(RecentRequests::snapshot)
+            .unwrap_or_default(),
+        circuit_breakers,
+        kv_keys: kv_keys().await,
+        log_tail,
+    }
+}
+
+/// Keys in the local daemon's KV store, if it is running.
+async fn kv_keys() -> Option<Vec<String>> {
+    let client = DaemonClient::local(DAEMON_PORT).api_key_from_env();
+    if !client.is_healthy(DAEMON_TIMEOUT).await {
+        return None;
+    }
+    let mut keys = tokio::time::timeout(DAEMON_TIMEOUT, client.kv_list(""))
+        .await
+        .ok()?
+        .ok()?;
+    keys.truncate(MAX_KV_KEYS);
+    Some(keys)
+}
+
+/// The last `lines` lines of `path` (empty if it cannot be read).
+fn read_tail(path: &Path, lines: usize) -> Vec<String> {
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return Vec::new();
+    };
+    let len = file.metadata().map_or(0, |m| m.len());
+    let start = len.saturating_sub(LOG_TAIL_BYTES);
+    let mut bytes = Vec::new();
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut bytes).is_err() {
+        return Vec::new();
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let mut tail: Vec<String> = text.lines().rev().take(lines).map(String::from).collect();
+    // The first line may have been cut by the byte limit
+    if start > 0 && tail.len() == text.lines().count() {
+        tail.pop();
+    }
+    tail.reverse();
+    tail
+}
+
+/// The dashboard page. Polls `/_dev/state` every two seconds.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>mik dev</title>
+<style>
+  body { font: 14px/1.4 system-ui, sans-serif; margin: 0; background: #f6f7f9; color: #1d2430; }
+  header { background: #1d2430; color: #fff; padding: 10px 20px; font-weight: 600; }
+  main { display: grid; grid-template-columns: repeat(auto-fit, minmax(420px, 1fr)); gap: 16px; padding: 16px; }
+  section { background: #fff; border-radius: 6px; padding: 12px 16px; box-shadow: 0 1px 2px rgba(0,0,0,.08); overflow: auto; }
+  section.wide { grid-column: 1 / -1; }
+  h2 { font-size: 15px; margin: 0 0 8px; }
+  table { border-collapse: collapse; width: 100%; }
+  td, th { text-align: left; padding: 3px 8px 3px 0; vertical-align: top; }
+  th { color: #6b7280; font-weight: 500; }
+  .ok { color: #15803d; } .warn { color: #b45309; } .err { color: #b91c1c; }
+  .spans { color: #6b7280; font-size: 12px; }
+  pre { margin: 0; font-size: 12px; white-space: pre-wrap; max-height: 360px; overflow: auto; }
+  .empty { color: #9ca3af; }
+</style>
+</head>
+<body>
+<header>mik dev dashboard</header>
+<main>
+  <section class="wide"><h2>Recent requests</h2><div id="requests"></div></section>
+  <section><h2>Modules</h2><div id="modules"></div></section>
+  <section><h2>Circuit breakers</h2><div id="circuits"></div></section>
+  <section><h2>KV</h2><div id="kv"></div></section>
+  <section class="wide"><h2>Log</h2><pre id="log"></pre></section>
+</main>
+<script>
+const esc = s => String(s).replace(/[&<>"]/g, c => ({'&':'&amp;','<':'&lt;','>':'&gt;','"':'&quot;'}[c]));
+const empty = text => `<span class="empty">${text}</span>`;
+const spans = (nodes, depth = 0) => nodes.map(n =>
+  `<div style="margin-left:${depth * 12}px">${esc(n.name)} ${n.duration_ms} ms` +
+  `${n.error ? ` <span class="err">${esc(n.error)}</span>` : ''}</div>` + spans(n.children || [], depth + 1)).join('');
+const statusClass = s => s == null || s >= 500 ? 'err' : s >= 400 ? 'warn' : 'ok';
+async function refresh() {
+  let state;
+  try { state = await (await fetch('/_dev/state')).json(); } catch { return; }
+  document.getElementById('requests').innerHTML = state.requests.length
+    ? '<table><tr><th>Time</th><th>Request</th><th>Status</th><th>Duration</th><th>Spans</th></tr>' +
+      state.requests.map(r => `<tr><td>${esc(r.timestamp.slice(11, 19))}</td>` +
+        `<td>${esc(r.method)} ${esc(r.path)}</td>` +
+        `<td class="${statusClass(r.status)}">${r.status ?? esc(r.error)}</td>` +
+        `<td>${r.duration_ms} ms</td><td class="spans">${spans(r.spans)}</td></tr>`).join('') + '</table>'
+    : empty('No requests yet');
+  document.getElementById('modules').innerHTML = state.modules.length
+    ? state.modules.map(m => `<div>${esc(m)}</div>`).join('') : empty('No modules loaded yet');
+  document.getElementById('circuits').innerHTML = state.circuit_breakers.length
+    ? '<table><tr><th>Module</th><th>State</th><th>Failures</th></tr>' +
+      state.circuit_breakers.map(c => `<tr><td>${esc(c.key)}</td>` +
+        `<td class="${c.state === 'closed' ? 'ok' : 'err'}">${c.state}</td><td>${c.failure_count}</td></tr>`).join('') + '</table>'
+    : empty('No circuits tracked');
+  document.getElementById('kv').innerHTML = state.kv_keys == null ? empty('Services not running')
+    : state.kv_keys.length ? state.kv_keys.map(k => `<div>${esc(k)}</div>`).join('') : empty('Empty');
+  const log = document.getElementById('log');
+  const atBottom = log.scrollTop + log.clientHeight >= log.scrollHeight - 4;
+  log.textContent = state.log_tail.join('\n');
+  if (atBottom) log.scrollTop = log.scrollHeight;
+}
+refresh();
+setInterval(refresh, 2000);
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::spans::SpanBuilder;
+
+    #[test]
+    fn test_recent_requests_keep_the_latest() {
+        let requests = RecentRequests::default();
+        for i in 0..MAX_RECENT_REQUESTS + 5 {
+            let spans = [SpanBuilder::new("request").finish()];
+            requests.push(RecentRequest::new(
+                &Method::GET,
+                &format!("/run/app/{i}"),
+                Ok(200),
+                Duration::from_millis(3),
+                &spans,
+            ));
+        }
+        let snapshot = requests.snapshot();
+        assert_eq!(snapshot.len(), MAX_RECENT_REQUESTS);
+        assert_eq!(
+            snapshot[0].path,
+            format!("/run/app/{}", MAX_RECENT_REQUESTS + 4)
+        );
+        assert_eq!(snapshot[0].spans.len(), 1);
+    }
+
+    #[test]
+    fn test_read_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let log = (0..200)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&path, log).unwrap();
+
+        let tail = read_tail(&path, 3);
+        assert_eq!(tail, ["line 197", "line 198", "line 199"]);
+        assert!(read_tail(&dir.path().join("missing.log"), 3).is_empty());
+    }
+
+    #[test]
+    fn test_is_dashboard_path() {
+        assert!(is_dashboard_path("/_dev"));
+        assert!(is_dashboard_path("/_dev/state"));
+        assert!(!is_dashboard_path("/_devices"));
+    }
+}
@@ -0,0 +1,194 @@
+//! Reverse proxy to a frontend dev server for `mik dev --proxy`.
+//!
+//! With `dev_proxy` set, requests for paths the runtime does not serve
+//! itself (anything outside `/run/`, `/tenant/`, `/static/`, `/script/`,
+//! `/openapi/`, `/_mik/`, `/_dev/`, `/health` and `/metrics`) are forwarded
+//! to the dev server (Vite, Next, ...), so the frontend and its API share one
+//! origin.
+//!
+//! Responses are buffered. WebSocket upgrades (hot module replacement) are
+//! passed through: the handshake is forwarded, and once the dev server
+//! switches protocols both connections are spliced together until either
+//! side closes.
+
+use anyhow::{Context, Result};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{CONNECTION, HOST, HeaderMap, HeaderName, UPGRADE};
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::sync::LazyLock;
+use tracing::{debug, warn};
+
+/// Client for the dev server. Redirects are passed to the browser as is.
+static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap_or_default()
+});
+
+/// Forward `req` to the dev server at `upstream` (e.g.
+/// `http://localhost:5173`), reading at most `max_body` bytes of its body.
+pub(crate) async fn forward(
+    upstream: &str,
+    mut req: Request<Incoming>,
+    max_body: usize,
+) -> Result<Response<Full<Bytes>>> {
+    let url = format!(
+        "{}{}",
+        upstream.trim_end_matches('/'),
+        req.uri().path_and_query().map_or("/", |pq| pq.as_str())
+    );
+    let websocket = is_websocket_upgrade(req.headers());
+    let mut headers = req.headers().clone();
+    strip_hop_by_hop(&mut headers, websocket);
+    // The dev server sees its own host, as if it were called directly
+    headers.remove(HOST);
+
+    let client_upgrade = websocket.then(|| hyper::upgrade::on(&mut req));
+    let (parts, body) = req.into_parts();
+    let body = match Limited::new(body, max_body).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) => {
+            warn!("Dev proxy: failed to read request body: {e}");
+            return status_response(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large");
+        },
+    };
+
+    debug!("Dev proxy: {} {}", parts.method, url);
+    let upstream_response = match CLIENT
+        .request(parts.method, &url)
+        .headers(headers)
+        .body(body)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("Dev proxy: {upstream} is not reachable: {e}");
+            return status_response(
+                StatusCode::BAD_GATEWAY,
+                &format!("Frontend dev server {upstream} is not reachable"),
+            );
+        },
+    };
+
+    let status = upstream_response.status();
+    let mut builder = Response::builder().status(status);
+    for (name, value) in upstream_response.headers() {
+        builder = builder.header(name, value);
+    }
+
+    if let Some(client_upgrade) = client_upgrade
+        && status == StatusCode::SWITCHING_PROTOCOLS
+    {
+        tokio::spawn(async move {
+            if let Err(e) = splice(client_upgrade, upstream_response).await {
+                debug!("Dev proxy: WebSocket closed: {e:#}");
+            }
+        });
+        return Ok(builder.body(Full::new(Bytes::new()))?);
+    }
+
+    let mut response_headers = upstream_response.headers().clone();
+    let body = upstream_response
+        .bytes()
+        .await
+        .context("Failed to read dev server response")?;
+    strip_hop_by_hop(&mut response_headers, false);
+    let mut response = Response::new(Full::new(body));
+    *response.status_mut() = status;
+    *response.headers_mut() = response_headers;
+    Ok(response)
+}
+
+/// Copy bytes both ways between the upgraded client and dev server
+/// connections until one of them closes.
+async fn splice(client: hyper::upgrade::OnUpgrade, upstream: reqwest::Response) -> Result<()> {
+    let mut upstream = upstream
+        .upgrade()
+        .await
+        .context("Dev server upgrade failed")?;
+    let mut client = TokioIo::new(client.await.context("Client upgrade failed")?);
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+/// Whether `headers` ask to switch the connection to WebSocket.
+fn is_websocket_upgrade(headers: &HeaderMap) -> bool {
+    let has_token = |name: HeaderName, token: &str| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|value| value.trim().eq_ignore_ascii_case(token))
+    };
+    has_token(CONNECTION, "upgrade") && has_token(UPGRADE, "websocket")
+}
+
+/// Remove the headers that only apply to one connection, keeping
+/// `Connection` and `Upgrade` for a WebSocket handshake.
+fn strip_hop_by_hop(headers: &mut HeaderMap, websocket: bool) {
+    for name in [
+        "keep-alive",
+        "proxy-authenticate",
+        "proxy-authorization",
+        "te",
+        "trailer",
+        "transfer-encoding",
+        "content-length",
+    ] {
+        headers.remove(name);
+    }
+    if !websocket {
+        headers.remove(CONNECTION);
+        headers.remove(UPGRADE);
+    }
+}
+
+fn status_response(status: StatusCode, message: &str) -> Result<Response<Full<Bytes>>> {
+    Ok(Response::builder()
+        .status(status)
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .body(Full::new(Bytes::from(message.to_string())))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[test]
+    fn test_is_websocket_upgrade() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONNECTION, HeaderValue::from_static("keep-alive, Upgrade"));
+        headers.insert(UPGRADE, HeaderValue::from_static("websocket"));
+        assert!(is_websocket_upgrade(&headers));
+
+        headers.insert(UPGRADE, HeaderValue::from_static("h2c"));
+        assert!(!is_websocket_upgrade(&headers));
+        headers.remove(CONNECTION);
+        assert!(!is_websocket_upgrade(&headers));
+    }
+
+    #[test]
+    fn test_strip_hop_by_hop() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONNECTION, HeaderValue::from_static("Upgrade"));
+        headers.insert(UPGRADE, HeaderValue::from_static("websocket"));
+        headers.insert("keep-alive", HeaderValue::from_static("timeout=5"));
+        headers.insert("accept", HeaderValue::from_static("*/*"));
+
+        let mut websocket = headers.clone();
+        strip_hop_by_hop(&mut websocket, true);
+        assert!(websocket.contains_key(UPGRADE));
+        assert!(!websocket.contains_key("keep-alive"));
+
+        strip_hop_by_hop(&mut headers, false);
+        assert!(!headers.contains_key(CONNECTION));
+        assert!(!headers.contains_key(UPGRADE));
+        assert!(headers.contains_key("accept"));
+    }
+}
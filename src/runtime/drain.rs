@@ -0,0 +1,193 @@
+//! Graceful shutdown in drain phases.
+//!
+//! Shutting down a [`Server`](crate::runtime::Server) or calling
+//! [`Runtime::shutdown_graceful`](crate::runtime::Runtime::shutdown_graceful)
+//! runs three phases:
+//!
+//! 1. **Stop accepting**: listeners are closed and `Runtime::handle_request`
+//!    rejects new requests.
+//! 2. **Drain**: wait for in-flight requests to finish, up to the timeout
+//!    (`shutdown_timeout_secs`), logging progress every second.
+//! 3. **Cancel**: executions still running are interrupted at their next
+//!    epoch tick (every 10ms), so a spinning guest cannot hold up the exit.
+//!
+//! A final [`DrainReport`] is logged and returned.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use crate::runtime::SharedState;
+
+/// Interval between drain progress logs and in-flight checks.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Interval between in-flight checks.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time cancelled executions get to unwind before the report is taken.
+const CANCEL_GRACE: Duration = Duration::from_secs(1);
+
+/// Outcome of a graceful shutdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrainReport {
+    /// Requests in flight when draining started.
+    pub in_flight: usize,
+    /// Of those, requests that finished before the timeout.
+    pub completed: usize,
+    /// WASM executions interrupted after the timeout.
+    pub cancelled: u64,
+    /// Time spent draining.
+    pub elapsed: Duration,
+}
+
+/// In-flight request tracking and the cancellation switch for executions.
+#[derive(Default)]
+pub(crate) struct DrainState {
+    in_flight: AtomicUsize,
+    cancel: Arc<AtomicBool>,
+    cancelled: Arc<AtomicU64>,
+    shutdown_requested: Notify,
+}
+
+impl DrainState {
+    /// Count a request as in flight until the guard drops.
+    pub(crate) fn track(&self) -> InFlightGuard<'_> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(&self.in_flight)
+    }
+
+    /// Requests currently in flight.
+    pub(crate) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Wake a server waiting in [`shutdown_requested`](Self::shutdown_requested).
+    pub(crate) fn request_shutdown(&self) {
+        self.shutdown_requested.notify_one();
+    }
+
+    /// Wait until [`request_shutdown`](Self::request_shutdown) is called.
+    pub(crate) async fn shutdown_requested(&self) {
+        self.shutdown_requested.notified().await;
+    }
+
+    /// Epoch callback check: whether a store should trap now.
+    ///
+    /// Counts each store once, since a trapped store is not resumed.
+    pub(crate) fn canceller(&self) -> impl Fn() -> bool + Send + Sync + 'static {
+        let cancel = self.cancel.clone();
+        let cancelled = self.cancelled.clone();
+        move || {
+            let cancel = cancel.load(Ordering::Relaxed);
+            if cancel {
+                cancelled.fetch_add(1, Ordering::Relaxed);
+            }
+            cancel
+        }
+    }
+}
+
+/// Marks one request in flight (see [`DrainState::track`]).
+pub(crate) struct InFlightGuard<'a>(&'a AtomicUsize);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Run the drain and cancel phases for requests on `shared`.
+///
+/// The caller has already stopped accepting new requests.
+pub(crate) async fn drain(shared: &SharedState, timeout: Duration) -> DrainReport {
+    let state = &shared.drain;
+    let start = Instant::now();
+    let in_flight = state.in_flight();
+
+    if in_flight > 0 {
+        info!(in_flight, ?timeout, "Draining in-flight requests");
+    }
+    let mut last_progress = start;
+    while state.in_flight() > 0 && start.elapsed() < timeout {
+        tokio::time::sleep(POLL_INTERVAL.min(timeout.saturating_sub(start.elapsed()))).await;
+        if last_progress.elapsed() >= PROGRESS_INTERVAL && state.in_flight() > 0 {
+            last_progress = Instant::now();
+            info!(
+                in_flight = state.in_flight(),
+                remaining_secs = timeout.saturating_sub(start.elapsed()).as_secs(),
+                "Still draining"
+            );
+        }
+    }
+
+    let remaining = state.in_flight();
+    if remaining > 0 {
+        warn!(
+            in_flight = remaining,
+            "Drain timeout reached, cancelling remaining executions"
+        );
+        state.cancel.store(true, Ordering::SeqCst);
+        let cancel_start = Instant::now();
+        while state.in_flight() > 0 && cancel_start.elapsed() < CANCEL_GRACE {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    let report = DrainReport {
+        in_flight,
+        completed: in_flight.saturating_sub(remaining),
+        cancelled: state.cancelled.load(Ordering::Relaxed),
+        elapsed: start.elapsed(),
+    };
+    info!(
+        in_flight = report.in_flight,
+        completed = report.completed,
+        cancelled = report.cancelled,
+        elapsed_ms = report.elapsed.as_millis() as u64,
+        "Drain complete"
+    );
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_flight_guard() {
+        let state = DrainState::default();
+        let first = state.track();
+        let second = state.track();
+        assert_eq!(state.in_flight(), 2);
+        drop(first);
+        assert_eq!(state.in_flight(), 1);
+        drop(second);
+        assert_eq!(state.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_canceller_counts_interrupted_stores() {
+        let state = DrainState::default();
+        let check = state.canceller();
+        assert!(!check());
+        assert_eq!(state.cancelled.load(Ordering::Relaxed), 0);
+
+        state.cancel.store(true, Ordering::SeqCst);
+        assert!(check());
+        assert!(state.canceller()());
+        assert_eq!(state.cancelled.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_request_is_not_lost() {
+        let state = DrainState::default();
+        // Requested before anyone waits: the next wait returns immediately
+        state.request_shutdown();
+        tokio::time::timeout(Duration::from_secs(1), state.shutdown_requested())
+            .await
+            .unwrap();
+    }
+}
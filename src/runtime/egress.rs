@@ -0,0 +1,296 @@
+//! Per-module accounting and quotas for outgoing guest HTTP.
+//!
+//! Every outgoing request a module makes is metered: request bodies sent and
+//! response bodies received both count towards the module's traffic. With a
+//! quota in `[server.egress_quotas]`, a module that has used up its daily or
+//! monthly allowance gets a `429 Too Many Requests` response (with
+//! `Retry-After` set to the end of the window) instead of reaching the
+//! network.
+//!
+//! Quotas are checked before each request, so the request that crosses the
+//! limit still completes. Counters are kept in memory per process and reset
+//! on restart.
+
+use crate::manifest::EgressQuota;
+use crate::runtime::usage::UsageMeter;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDate, TimeZone, Utc};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use wasmtime_wasi_http::body::{HyperIncomingBody, HyperOutgoingBody};
+use wasmtime_wasi_http::types::IncomingResponse;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Traffic counters for one module.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ModuleEgress {
+    /// Request body bytes sent since startup.
+    pub(crate) sent_bytes: u64,
+    /// Response body bytes received since startup.
+    pub(crate) received_bytes: u64,
+    /// Requests rejected by the quota since startup.
+    pub(crate) blocked: u64,
+    day: Option<NaiveDate>,
+    day_bytes: u64,
+    month: Option<(i32, u32)>,
+    month_bytes: u64,
+}
+
+impl ModuleEgress {
+    /// Start new windows if `now` is past the current ones.
+    fn roll(&mut self, now: DateTime<Utc>) {
+        let day = now.date_naive();
+        if self.day != Some(day) {
+            self.day = Some(day);
+            self.day_bytes = 0;
+        }
+        let month = (now.year(), now.month());
+        if self.month != Some(month) {
+            self.month = Some(month);
+            self.month_bytes = 0;
+        }
+    }
+}
+
+/// A quota window a module has used up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct QuotaExceeded {
+    /// `"daily"` or `"monthly"`.
+    pub(crate) window: &'static str,
+    /// Seconds until the window resets.
+    pub(crate) retry_after_secs: u64,
+}
+
+/// Outgoing HTTP traffic per module.
+#[derive(Debug, Default)]
+pub(crate) struct EgressMeter {
+    modules: Mutex<HashMap<String, ModuleEgress>>,
+}
+
+impl EgressMeter {
+    /// Count bytes a module sent (`sent = true`) or received.
+    pub(crate) fn record(&self, module: &str, bytes: u64, sent: bool) {
+        self.record_at(module, bytes, sent, Utc::now());
+    }
+
+    fn record_at(&self, module: &str, bytes: u64, sent: bool, now: DateTime<Utc>) {
+        let mut modules = self.modules.lock();
+        let usage = modules.entry(module.to_string()).or_default();
+        usage.roll(now);
+        if sent {
+            usage.sent_bytes += bytes;
+        } else {
+            usage.received_bytes += bytes;
+        }
+        usage.day_bytes += bytes;
+        usage.month_bytes += bytes;
+    }
+
+    /// Check `module` against its quota, counting a blocked request if it
+    /// is exhausted.
+    pub(crate) fn check(&self, module: &str, quota: &EgressQuota) -> Result<(), QuotaExceeded> {
+        self.check_at(module, quota, Utc::now())
+    }
+
+    fn check_at(
+        &self,
+        module: &str,
+        quota: &EgressQuota,
+        now: DateTime<Utc>,
+    ) -> Result<(), QuotaExceeded> {
+        let mut modules = self.modules.lock();
+        let usage = modules.entry(module.to_string()).or_default();
+        usage.roll(now);
+
+        let over = |limit_mb: Option<u64>, used: u64| {
+            limit_mb.is_some_and(|mb| used >= mb.saturating_mul(BYTES_PER_MB))
+        };
+        let exceeded = if over(quota.monthly_mb, usage.month_bytes) {
+            Some(("monthly", next_month(now)))
+        } else if over(quota.daily_mb, usage.day_bytes) {
+            Some(("daily", next_day(now)))
+        } else {
+            None
+        };
+
+        match exceeded {
+            Some((window, reset)) => {
+                usage.blocked += 1;
+                Err(QuotaExceeded {
+                    window,
+                    retry_after_secs: (reset - now).num_seconds().max(1).unsigned_abs(),
+                })
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Counters for every module that has made outgoing requests.
+    pub(crate) fn usage(&self) -> Vec<(String, ModuleEgress)> {
+        let mut usage: Vec<_> = self
+            .modules
+            .lock()
+            .iter()
+            .map(|(module, usage)| (module.clone(), *usage))
+            .collect();
+        usage.sort_by(|a, b| a.0.cmp(&b.0));
+        usage
+    }
+}
+
+fn next_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    let midnight = now.date_naive().and_hms_opt(0, 0, 0).unwrap_or_default();
+    Utc.from_utc_datetime(&midnight) + ChronoDuration::days(1)
+}
+
+fn next_month(now: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map_or_else(|| next_day(now), |start| Utc.from_utc_datetime(&start))
+}
+
+/// Egress accounting for the module serving one request.
+#[derive(Clone)]
+pub(crate) struct EgressAccount {
+    pub(crate) module: String,
+    pub(crate) quota: Option<EgressQuota>,
+    pub(crate) meter: Arc<EgressMeter>,
+    /// Tenant billed for the traffic, for tenant modules.
+    pub(crate) tenant: Option<String>,
+    pub(crate) usage: Arc<UsageMeter>,
+}
+
+impl EgressAccount {
+    /// Check the module's quota before sending a request.
+    pub(crate) fn check(&self) -> Result<(), QuotaExceeded> {
+        match &self.quota {
+            Some(quota) => self.meter.check(&self.module, quota),
+            None => Ok(()),
+        }
+    }
+
+    /// Count bytes against the module and its tenant.
+    fn record(&self, bytes: u64, sent: bool) {
+        self.meter.record(&self.module, bytes, sent);
+        if let Some(tenant) = &self.tenant {
+            self.usage.record_egress(tenant, bytes, sent);
+        }
+    }
+
+    /// Count the request body as it is sent.
+    pub(crate) fn meter_request(
+        &self,
+        request: hyper::Request<HyperOutgoingBody>,
+    ) -> hyper::Request<HyperOutgoingBody> {
+        let account = self.clone();
+        request.map(|body| {
+            body.map_frame(move |frame| {
+                if let Some(data) = frame.data_ref() {
+                    account.record(data.len() as u64, true);
+                }
+                frame
+            })
+            .boxed_unsync()
+        })
+    }
+
+    /// Count the response body as the guest reads it.
+    pub(crate) fn meter_response(&self, mut response: IncomingResponse) -> IncomingResponse {
+        let account = self.clone();
+        response.resp = response.resp.map(|body| {
+            body.map_frame(move |frame| {
+                if let Some(data) = frame.data_ref() {
+                    account.record(data.len() as u64, false);
+                }
+                frame
+            })
+            .boxed_unsync()
+        });
+        response
+    }
+}
+
+impl QuotaExceeded {
+    /// The `429` response returned to the guest instead of sending.
+    pub(crate) fn into_response(
+        self,
+        module: &str,
+        between_bytes_timeout: std::time::Duration,
+    ) -> IncomingResponse {
+        let body: HyperIncomingBody = Full::new(Bytes::from(format!(
+            "{} egress quota exceeded for module '{module}'",
+            self.window
+        )))
+        .map_err(|never| match never {})
+        .boxed_unsync();
+
+        let resp = hyper::Response::builder()
+            .status(hyper::StatusCode::TOO_MANY_REQUESTS)
+            .header(hyper::header::RETRY_AFTER, self.retry_after_secs)
+            .header(hyper::header::CONTENT_TYPE, "text/plain")
+            .body(body)
+            .unwrap_or_else(|_| hyper::Response::new(HyperIncomingBody::default()));
+
+        IncomingResponse {
+            resp,
+            worker: None,
+            between_bytes_timeout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_quota_windows() {
+        let meter = EgressMeter::default();
+        let quota = EgressQuota {
+            daily_mb: Some(1),
+            monthly_mb: Some(2),
+        };
+        let noon = at(2026, 3, 31, 12);
+
+        meter.record_at("crawler", BYTES_PER_MB - 1, true, noon);
+        assert!(meter.check_at("crawler", &quota, noon).is_ok());
+
+        // Crossing the daily limit blocks until midnight UTC
+        meter.record_at("crawler", 1, false, noon);
+        assert_eq!(
+            meter.check_at("crawler", &quota, noon),
+            Err(QuotaExceeded {
+                window: "daily",
+                retry_after_secs: 12 * 3600,
+            })
+        );
+        assert!(meter.check_at("other", &quota, noon).is_ok());
+
+        // The next day starts fresh, until the monthly limit is reached
+        let tomorrow = at(2026, 4, 1, 0);
+        assert!(meter.check_at("crawler", &quota, tomorrow).is_ok());
+        let later = at(2026, 4, 2, 0);
+        meter.record_at("crawler", 2 * BYTES_PER_MB, true, at(2026, 4, 1, 1));
+        let exceeded = meter.check_at("crawler", &quota, later).unwrap_err();
+        assert_eq!(exceeded.window, "monthly");
+        assert_eq!(exceeded.retry_after_secs, 29 * 24 * 3600);
+
+        let usage = meter.usage();
+        assert_eq!(usage[0].0, "crawler");
+        assert_eq!(usage[0].1.sent_bytes, 3 * BYTES_PER_MB - 1);
+        assert_eq!(usage[0].1.received_bytes, 1);
+        assert_eq!(usage[0].1.blocked, 2);
+    }
+}
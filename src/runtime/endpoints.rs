@@ -4,7 +4,9 @@
 //! - `/health`: Health check with optional verbose mode
 //! - `/metrics`: Prometheus-format metrics
 
+use crate::constants;
 use crate::runtime::SharedState;
+use crate::runtime::canary;
 use crate::runtime::compression::maybe_compress_response;
 use crate::runtime::types::HealthDetail;
 use anyhow::Result;
@@ -17,7 +19,10 @@ use tracing::info;
 use uuid::Uuid;
 
 /// Handle health check endpoint.
-pub(crate) fn handle_health_endpoint(
+///
+/// `?verbose=true` lists loaded modules; `?deep=true` also executes the
+/// built-in canary component, answering 503 if it fails.
+pub(crate) async fn handle_health_endpoint(
     shared: &Arc<SharedState>,
     req: &Request<hyper::body::Incoming>,
     request_id: &Uuid,
@@ -25,29 +30,37 @@ pub(crate) fn handle_health_endpoint(
     start_time: Instant,
     client_accepts_gzip: bool,
 ) -> Result<Response<Full<Bytes>>> {
-    let duration = start_time.elapsed();
-    info!(duration_ms = duration.as_millis() as u64, "Health check");
-
-    let detail = if req
-        .uri()
-        .query()
-        .and_then(|q| q.split('&').find(|s| s.starts_with("verbose=")))
-        .and_then(|s| s.strip_prefix("verbose="))
-        .is_some_and(|v| v == "true" || v == "1")
-    {
+    let query = req.uri().query();
+    let detail = if query_flag(query, "verbose") {
         HealthDetail::Full
     } else {
         HealthDetail::Summary
     };
 
-    let health = shared.get_health_status(detail);
+    let mut health = shared.get_health_status(detail);
+    if query_flag(query, "deep") {
+        let canary = canary::probe(shared).await;
+        if !canary.passed {
+            health.status = constants::HEALTH_STATUS_UNHEALTHY.to_string();
+        }
+        health.canary = Some(canary);
+    }
+    let status = if health.canary.as_ref().is_some_and(|c| !c.passed) {
+        503
+    } else {
+        200
+    };
+
+    let duration = start_time.elapsed();
+    info!(duration_ms = duration.as_millis() as u64, "Health check");
+
     let body = serde_json::to_string_pretty(&health).unwrap_or_else(|e| {
         tracing::warn!(error = %e, "Failed to serialize health status to JSON");
         r#"{"status":"error"}"#.to_string()
     });
 
     let response = Response::builder()
-        .status(200)
+        .status(status)
         .header("Content-Type", "application/json")
         .header("X-Request-ID", request_id.to_string())
         .header("traceparent", traceparent)
@@ -56,6 +69,15 @@ pub(crate) fn handle_health_endpoint(
     Ok(maybe_compress_response(response, client_accepts_gzip))
 }
 
+/// Whether query parameter `name` is set to `true` or `1`.
+fn query_flag(query: Option<&str>, name: &str) -> bool {
+    query
+        .into_iter()
+        .flat_map(|q| q.split('&'))
+        .filter_map(|pair| pair.split_once('='))
+        .any(|(key, value)| key == name && (value == "true" || value == "1"))
+}
+
 /// Handle metrics endpoint (Prometheus format).
 pub(crate) fn handle_metrics_endpoint(
     shared: &Arc<SharedState>,
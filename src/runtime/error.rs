@@ -23,6 +23,11 @@ pub enum Error {
     #[error("failed to load module '{name}': {reason}")]
     ModuleLoadFailed { name: String, reason: String },
 
+    /// Module lacks the export needed to serve the route (e.g. a job
+    /// component called over HTTP).
+    #[error("unsupported component '{name}': {reason}")]
+    UnsupportedComponent { name: String, reason: String },
+
     /// Module execution timed out.
     #[error("module '{name}' timed out after {timeout_secs}s")]
     ExecutionTimeout { name: String, timeout_secs: u64 },
@@ -99,6 +104,14 @@ impl Error {
         }
     }
 
+    /// Create an unsupported component error.
+    pub fn unsupported_component(name: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::UnsupportedComponent {
+            name: name.into(),
+            reason: reason.into(),
+        }
+    }
+
     /// Create a script error.
     pub fn script_error(name: impl Into<String>, reason: impl Into<String>) -> Self {
         Self::ScriptError {
@@ -152,6 +165,7 @@ impl Error {
             Self::RateLimitExceeded { .. } => 429,
             Self::ExecutionTimeout { .. } => 504,
             Self::MemoryLimitExceeded { .. } => 507,
+            Self::UnsupportedComponent { .. } => 501,
             Self::ModuleLoadFailed { .. }
             | Self::ScriptError { .. }
             | Self::Config(_)
@@ -403,6 +417,13 @@ mod tests {
         assert_eq!(err.status_code(), 507);
     }
 
+    #[test]
+    fn test_status_code_unsupported_component() {
+        let err =
+            Error::unsupported_component("nightly", "does not export wasi:http/incoming-handler");
+        assert_eq!(err.status_code(), 501);
+    }
+
     #[test]
     fn test_status_code_module_load_failed() {
         let err = Error::module_load_failed("test", "error");
@@ -0,0 +1,344 @@
+//! Cached discovery responses for the gateway endpoints.
+//!
+//! `/_mik/handlers` and the aggregated OpenAPI specs are built from a scan of
+//! the module directories. [`DiscoveryIndex`] keeps the last response body of
+//! each endpoint and only rebuilds it after the directories change:
+//!
+//! - A file watcher on `modules_dir` and `user_modules_dir`, started on the
+//!   first gateway request, marks every cached response stale on any change.
+//! - Without a watcher (the directories could not be watched, e.g. one does
+//!   not exist yet), the modification times of the scanned directories are
+//!   compared instead. This catches modules being added, removed or renamed
+//!   into place, but not files rewritten in place.
+//! - Module uploads and deletes through the management endpoints (see
+//!   [`modules`](super::modules)) invalidate the index directly.
+//!
+//! Every response carries an `ETag` derived from its content, and requests
+//! with a matching `If-None-Match` get `304 Not Modified` with no body, so the
+//! gateway can poll cheaply. A rebuild that yields the same content keeps the
+//! previous body and ETag.
+//!
+//! [`changed`](DiscoveryIndex::changed) resolves after the watcher or an
+//! invalidation reports a change, for the handler webhook (see
+//! [`webhook`](super::webhook)).
+
+use anyhow::Result;
+use hyper::body::Bytes;
+use hyper::header::{HeaderMap, IF_NONE_MATCH};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+use tokio::sync::Notify;
+use tracing::{debug, info};
+
+/// A cached response body and its ETag.
+#[derive(Debug, Clone)]
+pub struct CachedBody {
+    /// Serialized JSON body.
+    pub body: Bytes,
+    /// Quoted strong ETag of the body's content.
+    pub etag: String,
+}
+
+/// Content built for a gateway endpoint.
+pub struct Built {
+    /// Serialized JSON body.
+    pub body: Bytes,
+    /// Bytes identifying the content, without volatile fields such as
+    /// timestamps. The ETag is derived from these.
+    pub content: Vec<u8>,
+}
+
+/// Cached gateway discovery responses.
+#[derive(Debug, Default)]
+pub struct DiscoveryIndex {
+    /// Bumped by the watcher and explicit invalidation.
+    generation: Arc<AtomicU64>,
+    /// Notified with every generation bump.
+    changes: Arc<Notify>,
+    /// The running watcher, or `None` if it could not be started.
+    watcher: OnceLock<Option<RecommendedWatcher>>,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    stamp: Stamp,
+    cached: CachedBody,
+}
+
+/// State of the module directories a cached response was built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stamp {
+    generation: u64,
+    /// Hash of directory modification times, when not watching.
+    mtimes: Option<u64>,
+}
+
+impl DiscoveryIndex {
+    /// Response for the endpoint `key`, built by `build` unless a cached
+    /// response is still current.
+    ///
+    /// `build` returning `None` (e.g. an unknown tenant) is not cached.
+    pub fn get_or_build(
+        &self,
+        key: &str,
+        modules_dir: &Path,
+        user_modules_dir: Option<&Path>,
+        build: impl FnOnce() -> Result<Option<Built>>,
+    ) -> Result<Option<CachedBody>> {
+        let stamp = self.stamp(modules_dir, user_modules_dir);
+        if let Some(entry) = self.entries.lock().get(key)
+            && entry.stamp == stamp
+        {
+            return Ok(Some(entry.cached.clone()));
+        }
+
+        let Some(fresh) = build()? else {
+            return Ok(None);
+        };
+        let etag = etag(&fresh.content);
+        let mut entries = self.entries.lock();
+        let cached = match entries.get(key) {
+            Some(entry) if entry.cached.etag == etag => entry.cached.clone(),
+            _ => CachedBody {
+                body: fresh.body,
+                etag,
+            },
+        };
+        entries.insert(
+            key.to_string(),
+            Entry {
+                stamp,
+                cached: cached.clone(),
+            },
+        );
+        Ok(Some(cached))
+    }
+
+    /// Mark every cached response stale.
+    pub fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.changes.notify_one();
+    }
+
+    /// Wait until the module directories change.
+    ///
+    /// Only changes reported by the watcher or [`invalidate`](Self::invalidate)
+    /// are seen; without a watcher, callers have to poll as well.
+    pub async fn changed(&self) {
+        self.changes.notified().await;
+    }
+
+    /// Start watching the module directories, if not already done.
+    ///
+    /// Returns whether a watcher is running.
+    pub fn watch_dirs(&self, modules_dir: &Path, user_modules_dir: Option<&Path>) -> bool {
+        self.watcher
+            .get_or_init(|| self.watch(modules_dir, user_modules_dir))
+            .is_some()
+    }
+
+    fn stamp(&self, modules_dir: &Path, user_modules_dir: Option<&Path>) -> Stamp {
+        let watching = self.watch_dirs(modules_dir, user_modules_dir);
+        Stamp {
+            generation: self.generation.load(Ordering::Relaxed),
+            mtimes: (!watching).then(|| dir_mtimes(modules_dir, user_modules_dir)),
+        }
+    }
+
+    /// Start a watcher bumping the generation on any change.
+    fn watch(
+        &self,
+        modules_dir: &Path,
+        user_modules_dir: Option<&Path>,
+    ) -> Option<RecommendedWatcher> {
+        let generation = self.generation.clone();
+        let changes = self.changes.clone();
+        let watcher = RecommendedWatcher::new(
+            move |result: notify::Result<Event>| {
+                if result.is_ok_and(|event| {
+                    matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    )
+                }) {
+                    generation.fetch_add(1, Ordering::Relaxed);
+                    changes.notify_one();
+                }
+            },
+            notify::Config::default(),
+        );
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                debug!("Gateway index not watching module directories: {}", e);
+                return None;
+            },
+        };
+        for dir in std::iter::once(modules_dir).chain(user_modules_dir) {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::Recursive) {
+                debug!(
+                    "Gateway index not watching {}, using modification times: {}",
+                    dir.display(),
+                    e
+                );
+                return None;
+            }
+        }
+        info!("Gateway index watching module directories");
+        Some(watcher)
+    }
+}
+
+/// Hash of the modification times of `modules_dir`, `user_modules_dir` and
+/// the tenant directories in it.
+fn dir_mtimes(modules_dir: &Path, user_modules_dir: Option<&Path>) -> u64 {
+    let mtime =
+        |dir: &Path| -> Option<SystemTime> { dir.metadata().and_then(|m| m.modified()).ok() };
+    let mut dirs: Vec<PathBuf> = std::iter::once(modules_dir)
+        .chain(user_modules_dir)
+        .map(Path::to_path_buf)
+        .collect();
+    if let Some(Ok(entries)) = user_modules_dir.map(std::fs::read_dir) {
+        let mut tenants: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+        tenants.sort();
+        dirs.extend(tenants);
+    }
+
+    let mut hasher = DefaultHasher::new();
+    for dir in &dirs {
+        dir.hash(&mut hasher);
+        mtime(dir).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Quoted strong ETag of `content`.
+fn etag(content: &[u8]) -> String {
+    let digest = Sha256::digest(content);
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// Whether the request's `If-None-Match` matches `etag`.
+///
+/// Weak tags (`W/"..."`) compare like strong ones, as RFC 9110 requires for
+/// `If-None-Match`.
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn built(content: &str) -> Built {
+        Built {
+            body: Bytes::from(content.to_string()),
+            content: content.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = etag(b"spec");
+        let mut headers = HeaderMap::new();
+        assert!(!if_none_match(&headers, &etag));
+
+        headers.insert(
+            IF_NONE_MATCH,
+            format!("\"other\", W/{etag}").parse().unwrap(),
+        );
+        assert!(if_none_match(&headers, &etag));
+
+        headers.insert(IF_NONE_MATCH, "\"other\"".parse().unwrap());
+        assert!(!if_none_match(&headers, &etag));
+
+        headers.insert(IF_NONE_MATCH, "*".parse().unwrap());
+        assert!(if_none_match(&headers, &etag));
+    }
+
+    #[test]
+    fn test_cached_until_invalidated() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = DiscoveryIndex::default();
+        let get = |content: &'static str| {
+            index
+                .get_or_build("handlers", dir.path(), None, || Ok(Some(built(content))))
+                .unwrap()
+                .unwrap()
+        };
+
+        let first = get("a");
+        assert_eq!(first.body, "a");
+        // Current: the builder is not called
+        let second = index
+            .get_or_build("handlers", dir.path(), None, || -> Result<_> {
+                unreachable!()
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.etag, first.etag);
+
+        index.invalidate();
+        let rebuilt = get("b");
+        assert_eq!(rebuilt.body, "b");
+        assert_ne!(rebuilt.etag, first.etag);
+
+        // Unknown resources are not cached
+        let missing = index
+            .get_or_build("tenant:x", dir.path(), None, || Ok(None))
+            .unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn test_rebuild_with_same_content_keeps_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = DiscoveryIndex::default();
+        let build = |body: &'static str| {
+            move || {
+                Ok(Some(Built {
+                    body: Bytes::from_static(body.as_bytes()),
+                    content: b"same".to_vec(),
+                }))
+            }
+        };
+
+        let first = index
+            .get_or_build("handlers", dir.path(), None, build("t1"))
+            .unwrap()
+            .unwrap();
+        index.invalidate();
+        let second = index
+            .get_or_build("handlers", dir.path(), None, build("t2"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.body, "t1");
+        assert_eq!(second.etag, first.etag);
+    }
+
+    #[test]
+    fn test_mtimes_change_with_tenant_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let modules = dir.path().join("modules");
+        let users = dir.path().join("users");
+        std::fs::create_dir_all(users.join("acme")).unwrap();
+
+        let before = dir_mtimes(&modules, Some(&users));
+        assert_eq!(before, dir_mtimes(&modules, Some(&users)));
+        std::fs::create_dir(users.join("globex")).unwrap();
+        assert_ne!(before, dir_mtimes(&modules, Some(&users)));
+    }
+}
@@ -6,22 +6,55 @@
 //! - `GET /_mik/handlers` - List all available handlers
 //! - `GET /_mik/openapi/platform` - Aggregated platform OpenAPI spec
 //! - `GET /_mik/openapi/tenant/{tenant-id}` - Aggregated tenant OpenAPI spec
+//! - `GET /_mik/usage/tenant/{tenant-id}` - Tenant usage counters
+//!
+//! And endpoints to inspect and operate circuit breakers, the latter
+//! authenticated:
+//!
+//! - `GET /_mik/circuit-breakers` - List all tracked circuit breakers
+//! - `POST /_mik/circuit-breakers/{module}/reset` - Close a circuit
+//! - `POST /_mik/circuit-breakers/{module}/open` - Open a circuit
+//!
+//! And authenticated endpoints to manage tenant modules (see [`modules`]):
+//!
+//! - `PUT /_mik/tenants/{tenant-id}/modules/{module}` - Upload or replace a module
+//! - `DELETE /_mik/tenants/{tenant-id}/modules/{module}` - Delete a module
+//!
+//! And an authenticated endpoint to reload the configuration, like `SIGHUP`
+//! (see [`reload`](crate::runtime::reload)):
+//!
+//! - `POST /_mik/reload` - Re-read mik.toml and apply the reloadable settings
+//!
+//! The handler list and OpenAPI specs are cached until the module directories
+//! change, and carry an `ETag` honoured by `If-None-Match` (see [`index`]).
+//! With `handlers_webhook` configured, changes are also pushed to the gateway
+//! (see [`webhook`]).
 
 pub mod discovery;
+pub mod index;
+pub mod modules;
 pub mod openapi;
 pub mod types;
+pub(crate) mod webhook;
 
 use self::types::{
-    ErrorResponse, HandlerAttributes, HandlerInfo, HandlerLinks, HandlersMetadata, HandlersResponse,
+    CircuitBreakerAttributes, CircuitBreakerInfo, CircuitBreakerResponse, CircuitBreakersMetadata,
+    CircuitBreakersResponse, ErrorResponse, HandlerAttributes, HandlerInfo, HandlerLinks,
+    HandlersMetadata, HandlersResponse, ReloadResponse, TenantUsageAttributes, TenantUsageInfo,
+    TenantUsageMetadata, TenantUsageResponse,
 };
-use crate::runtime::SharedState;
-use anyhow::Result;
-use chrono::Utc;
+use crate::reliability::CircuitSnapshot;
+use crate::runtime::usage::TenantUsage;
+use crate::runtime::{SharedState, security};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use http_body_util::Full;
-use hyper::Response;
 use hyper::body::Bytes;
+use hyper::header::HeaderMap;
+use hyper::{Method, Response};
+use percent_encoding::percent_decode_str;
 use std::sync::Arc;
-use tracing::debug;
+use tracing::{debug, info, warn};
 
 /// Route prefix for gateway API endpoints.
 pub const MIK_API_PREFIX: &str = "/_mik/";
@@ -32,10 +65,15 @@ pub const MIK_API_PREFIX: &str = "/_mik/";
 /// - `/_mik/handlers` - List all handlers
 /// - `/_mik/openapi/platform` - Platform OpenAPI spec
 /// - `/_mik/openapi/tenant/{id}` - Tenant OpenAPI spec
+/// - `/_mik/usage/tenant/{id}` - Tenant usage
+/// - `/_mik/circuit-breakers[/{module}/{action}]` - Circuit breakers
+/// - `/_mik/reload` - Configuration reload
 ///
 /// # Arguments
 ///
 /// * `shared` - Shared runtime state
+/// * `method` - Request method
+/// * `headers` - Request headers (for `If-None-Match` and `X-API-Key`)
 /// * `path` - Request path (must start with `/_mik/`)
 ///
 /// # Returns
@@ -43,21 +81,33 @@ pub const MIK_API_PREFIX: &str = "/_mik/";
 /// HTTP response with JSON body.
 pub fn handle_gateway_request(
     shared: &Arc<SharedState>,
+    method: &Method,
+    headers: &HeaderMap,
     path: &str,
 ) -> Result<Response<Full<Bytes>>> {
-    debug!("Gateway API request: {}", path);
+    debug!("Gateway API request: {} {}", method, path);
 
     let Some(api_path) = path.strip_prefix(MIK_API_PREFIX) else {
         return json_error(404, &ErrorResponse::not_found("Invalid gateway API path"));
     };
 
     match api_path {
-        "handlers" => handle_handlers(shared),
-        "openapi/platform" => handle_platform_openapi(shared),
+        "handlers" => handle_handlers(shared, headers),
+        "openapi/platform" => handle_platform_openapi(shared, headers),
         p if p.starts_with("openapi/tenant/") => {
             let tenant_id = p.strip_prefix("openapi/tenant/").unwrap_or("");
-            handle_tenant_openapi(shared, tenant_id)
+            handle_tenant_openapi(shared, headers, tenant_id)
         },
+        p if p.starts_with("usage/tenant/") => {
+            let tenant_id = p.strip_prefix("usage/tenant/").unwrap_or("");
+            handle_tenant_usage(shared, tenant_id)
+        },
+        "circuit-breakers" => handle_circuit_breakers(shared),
+        p if p.starts_with("circuit-breakers/") => {
+            let rest = p.strip_prefix("circuit-breakers/").unwrap_or("");
+            handle_circuit_breaker_action(shared, method, headers, rest)
+        },
+        "reload" => handle_reload(shared, method, headers),
         _ => json_error(
             404,
             &ErrorResponse::not_found(format!("Unknown gateway endpoint: {api_path}")),
@@ -68,7 +118,22 @@ pub fn handle_gateway_request(
 /// Handle GET /_mik/handlers endpoint.
 ///
 /// Returns a JSON:API-style list of all available handlers (platform + tenant).
-fn handle_handlers(shared: &Arc<SharedState>) -> Result<Response<Full<Bytes>>> {
+/// `meta.timestamp` is the time the list was last rebuilt.
+fn handle_handlers(
+    shared: &Arc<SharedState>,
+    headers: &HeaderMap,
+) -> Result<Response<Full<Bytes>>> {
+    let cached = shared.gateway_index.get_or_build(
+        "handlers",
+        &shared.modules_dir,
+        shared.user_modules_dir.as_deref(),
+        || build_handlers(shared).map(Some),
+    )?;
+    cached_response(headers, cached.context("Gateway index built no response")?)
+}
+
+/// Scan the module directories for the handler list.
+fn build_handlers(shared: &SharedState) -> Result<index::Built> {
     let modules_dir = &shared.modules_dir;
     let user_modules_dir = shared.user_modules_dir.as_deref();
 
@@ -124,6 +189,7 @@ fn handle_handlers(shared: &Arc<SharedState>) -> Result<Response<Full<Bytes>>> {
         });
     }
 
+    let content = serde_json::to_vec(&handlers)?;
     let response = HandlersResponse {
         data: handlers,
         meta: HandlersMetadata {
@@ -134,17 +200,26 @@ fn handle_handlers(shared: &Arc<SharedState>) -> Result<Response<Full<Bytes>>> {
         },
     };
 
-    json_response(200, &response)
+    Ok(index::Built {
+        body: Bytes::from(serde_json::to_vec(&response)?),
+        content,
+    })
 }
 
 /// Handle GET /_mik/openapi/platform endpoint.
 ///
 /// Returns the aggregated OpenAPI spec for all platform handlers.
-fn handle_platform_openapi(shared: &Arc<SharedState>) -> Result<Response<Full<Bytes>>> {
-    let modules_dir = &shared.modules_dir;
-    let spec = openapi::aggregate_platform_spec(modules_dir);
-
-    json_response(200, &spec)
+fn handle_platform_openapi(
+    shared: &Arc<SharedState>,
+    headers: &HeaderMap,
+) -> Result<Response<Full<Bytes>>> {
+    let cached = shared.gateway_index.get_or_build(
+        "openapi/platform",
+        &shared.modules_dir,
+        shared.user_modules_dir.as_deref(),
+        || spec_body(&openapi::aggregate_platform_spec(&shared.modules_dir)).map(Some),
+    )?;
+    cached_response(headers, cached.context("Gateway index built no response")?)
 }
 
 /// Handle GET /_mik/openapi/tenant/{tenant-id} endpoint.
@@ -152,6 +227,7 @@ fn handle_platform_openapi(shared: &Arc<SharedState>) -> Result<Response<Full<By
 /// Returns the aggregated OpenAPI spec for a specific tenant's handlers.
 fn handle_tenant_openapi(
     shared: &Arc<SharedState>,
+    headers: &HeaderMap,
     tenant_id: &str,
 ) -> Result<Response<Full<Bytes>>> {
     if tenant_id.is_empty() {
@@ -180,13 +256,21 @@ fn handle_tenant_openapi(
         );
     }
 
-    let spec = match &shared.user_modules_dir {
-        Some(dir) if dir.is_dir() => openapi::aggregate_tenant_spec(dir, tenant_id),
-        _ => None,
-    };
+    let cached = shared.gateway_index.get_or_build(
+        &format!("openapi/tenant/{tenant_id}"),
+        &shared.modules_dir,
+        shared.user_modules_dir.as_deref(),
+        || match &shared.user_modules_dir {
+            Some(dir) if dir.is_dir() => openapi::aggregate_tenant_spec(dir, tenant_id)
+                .as_ref()
+                .map(spec_body)
+                .transpose(),
+            _ => Ok(None),
+        },
+    )?;
 
-    match spec {
-        Some(s) => json_response(200, &s),
+    match cached {
+        Some(cached) => cached_response(headers, cached),
         None => json_error(
             404,
             &ErrorResponse::not_found(format!("Tenant not found: {tenant_id}")),
@@ -194,6 +278,202 @@ fn handle_tenant_openapi(
     }
 }
 
+/// Serialize an aggregated OpenAPI spec for the index.
+fn spec_body(spec: &serde_json::Value) -> Result<index::Built> {
+    let body = serde_json::to_vec(spec)?;
+    Ok(index::Built {
+        content: body.clone(),
+        body: Bytes::from(body),
+    })
+}
+
+/// Respond with a cached body, or `304 Not Modified` if the request's
+/// `If-None-Match` matches its ETag.
+fn cached_response(
+    headers: &HeaderMap,
+    cached: index::CachedBody,
+) -> Result<Response<Full<Bytes>>> {
+    let builder = Response::builder()
+        .header("ETag", &cached.etag)
+        .header("Cache-Control", "no-cache");
+    if index::if_none_match(headers, &cached.etag) {
+        return Ok(builder.status(304).body(Full::new(Bytes::new()))?);
+    }
+    Ok(builder
+        .status(200)
+        .header("Content-Type", "application/json")
+        .body(Full::new(cached.body))?)
+}
+
+/// Handle GET /_mik/usage/tenant/{tenant-id} endpoint.
+///
+/// Returns the tenant's usage since startup. Tenants with a module directory
+/// but no requests yet report zero usage.
+fn handle_tenant_usage(
+    shared: &Arc<SharedState>,
+    tenant_id: &str,
+) -> Result<Response<Full<Bytes>>> {
+    if security::sanitize_module_name(tenant_id).is_err() {
+        return json_error(
+            400,
+            &ErrorResponse::invalid_request("Invalid tenant ID format"),
+        );
+    }
+
+    let usage = shared.usage.tenant(tenant_id).or_else(|| {
+        shared
+            .user_modules_dir
+            .as_ref()
+            .is_some_and(|dir| dir.join(tenant_id).is_dir())
+            .then(TenantUsage::default)
+    });
+    let Some(usage) = usage else {
+        return json_error(
+            404,
+            &ErrorResponse::not_found(format!("Tenant not found: {tenant_id}")),
+        );
+    };
+
+    let response = TenantUsageResponse {
+        data: TenantUsageInfo {
+            id: tenant_id.to_string(),
+            resource_type: "tenant-usage".to_string(),
+            attributes: TenantUsageAttributes {
+                requests: usage.requests,
+                errors: usage.errors,
+                execution_time_ms: usage.execution_time.as_millis() as u64,
+                fuel_consumed: usage.fuel_consumed,
+                egress_sent_bytes: usage.egress_sent_bytes,
+                egress_received_bytes: usage.egress_received_bytes,
+            },
+        },
+        meta: TenantUsageMetadata {
+            timestamp: Utc::now().to_rfc3339(),
+        },
+    };
+    json_response(200, &response)
+}
+
+/// Handle GET /_mik/circuit-breakers endpoint.
+///
+/// Returns every tracked circuit with its state, failure count, and the time
+/// it opened. Modules that never failed are not tracked.
+fn handle_circuit_breakers(shared: &Arc<SharedState>) -> Result<Response<Full<Bytes>>> {
+    let mut snapshots = shared.circuit_breaker.snapshot();
+    snapshots.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let open_count = snapshots.iter().filter(|s| s.state == "open").count();
+    let response = CircuitBreakersResponse {
+        meta: CircuitBreakersMetadata {
+            total: snapshots.len(),
+            open_count,
+            timestamp: Utc::now().to_rfc3339(),
+        },
+        data: snapshots.into_iter().map(circuit_breaker_info).collect(),
+    };
+
+    json_response(200, &response)
+}
+
+/// Handle POST /_mik/circuit-breakers/{module}/{reset|open} endpoints.
+///
+/// Requires the API key. `reset` closes the circuit and clears its failure
+/// count; `open` rejects requests to the module until its recovery timeout
+/// elapses.
+fn handle_circuit_breaker_action(
+    shared: &Arc<SharedState>,
+    method: &Method,
+    headers: &HeaderMap,
+    rest: &str,
+) -> Result<Response<Full<Bytes>>> {
+    // Tenant circuit keys contain a slash, so the action is the last segment
+    let Some((key, action)) = rest.rsplit_once('/') else {
+        return json_error(
+            404,
+            &ErrorResponse::not_found(format!("Unknown gateway endpoint: circuit-breakers/{rest}")),
+        );
+    };
+    let key = percent_decode_str(key).decode_utf8_lossy();
+    if key.is_empty() || !matches!(action, "reset" | "open") {
+        return json_error(
+            404,
+            &ErrorResponse::not_found(format!("Unknown gateway endpoint: circuit-breakers/{rest}")),
+        );
+    }
+    if method != Method::POST {
+        return json_error(
+            405,
+            &ErrorResponse::method_not_allowed(format!("Use POST to {action} a circuit breaker")),
+        );
+    }
+    if let Some(resp) = modules::authenticate(shared, headers, "Circuit breaker control")? {
+        return Ok(resp);
+    }
+
+    if action == "reset" {
+        info!("Circuit breaker reset requested for '{}'", key);
+        shared.circuit_breaker.reset(&key);
+    } else {
+        info!("Circuit breaker open requested for '{}'", key);
+        shared.circuit_breaker.force_open(&key);
+    }
+
+    let response = CircuitBreakerResponse {
+        data: circuit_breaker_info(shared.circuit_breaker.snapshot_key(&key)),
+    };
+    json_response(200, &response)
+}
+
+/// Handle POST /_mik/reload endpoint.
+///
+/// Requires the API key. Returns the reloaded settings, or 422 with the
+/// reason when mik.toml is invalid and the running settings were kept.
+fn handle_reload(
+    shared: &Arc<SharedState>,
+    method: &Method,
+    headers: &HeaderMap,
+) -> Result<Response<Full<Bytes>>> {
+    if method != Method::POST {
+        return json_error(
+            405,
+            &ErrorResponse::method_not_allowed("Use POST to reload the configuration"),
+        );
+    }
+    if let Some(resp) = modules::authenticate(shared, headers, "Configuration reload")? {
+        return Ok(resp);
+    }
+
+    info!("Configuration reload requested");
+    match shared.reload() {
+        Ok(report) => json_response(
+            200,
+            &ReloadResponse {
+                changed: report.changed.iter().map(ToString::to_string).collect(),
+                timestamp: Utc::now().to_rfc3339(),
+            },
+        ),
+        Err(e) => {
+            warn!("Reload failed, keeping the current configuration: {e:#}");
+            json_error(422, &ErrorResponse::invalid_request(format!("{e:#}")))
+        },
+    }
+}
+
+/// Convert a circuit snapshot to its API resource.
+fn circuit_breaker_info(snapshot: CircuitSnapshot) -> CircuitBreakerInfo {
+    CircuitBreakerInfo {
+        id: snapshot.key,
+        resource_type: "circuit-breaker".to_string(),
+        attributes: CircuitBreakerAttributes {
+            state: snapshot.state.to_string(),
+            failure_count: snapshot.failure_count,
+            opened_at: snapshot
+                .opened_at
+                .map(|t| DateTime::<Utc>::from(t).to_rfc3339()),
+        },
+    }
+}
+
 /// Create a JSON response with the given status code and body.
 fn json_response<T: serde::Serialize>(status: u16, body: &T) -> Result<Response<Full<Bytes>>> {
     let json = serde_json::to_string(body)?;
@@ -250,4 +530,29 @@ mod tests {
         assert!(json.contains("\"links\""));
         assert!(json.contains("\"meta\""));
     }
+
+    #[test]
+    fn test_circuit_breaker_info_serialization() {
+        let open = circuit_breaker_info(CircuitSnapshot {
+            key: "auth".to_string(),
+            state: "open",
+            failure_count: 5,
+            opened_at: Some(std::time::UNIX_EPOCH),
+        });
+        let json = serde_json::to_value(&open).unwrap();
+        assert_eq!(json["id"], "auth");
+        assert_eq!(json["type"], "circuit-breaker");
+        assert_eq!(json["attributes"]["state"], "open");
+        assert_eq!(json["attributes"]["failure_count"], 5);
+        assert_eq!(json["attributes"]["opened_at"], "1970-01-01T00:00:00+00:00");
+
+        let closed = circuit_breaker_info(CircuitSnapshot {
+            key: "orders".to_string(),
+            state: "closed",
+            failure_count: 1,
+            opened_at: None,
+        });
+        let json = serde_json::to_value(&closed).unwrap();
+        assert!(json["attributes"].get("opened_at").is_none());
+    }
 }
@@ -0,0 +1,364 @@
+//! Tenant module management endpoints.
+//!
+//! Lets operators onboard tenant handlers without filesystem access:
+//!
+//! - `PUT /_mik/tenants/{tenant-id}/modules/{module}` - Upload or replace a module
+//! - `DELETE /_mik/tenants/{tenant-id}/modules/{module}` - Delete a module
+//!
+//! The endpoints are disabled unless they are enabled with `tenant_uploads =
+//! true` in `[server]` (`MIK_TENANT_UPLOADS`) and an API key is configured
+//! (`MIK_API_KEY` for `mik run`). Every request must carry the key in
+//! `X-API-Key`, which on its own only unlocks the read-only and operational
+//! endpoints. Uploads
+//! are limited to `max_module_size_mb` and must be WebAssembly. With a module
+//! signing key (`MIK_MODULE_SIGNING_KEY`), uploads must also carry
+//! `X-Mik-Signature: sha256=<hex HMAC-SHA256 of the body>`.
+//!
+//! Uploads that would take a tenant past the `max_storage_mb` of its
+//! `[server.tenant_limits]` fail with `507 Insufficient Storage`.
+//!
+//! A replaced or deleted module is evicted from the cache and its circuit
+//! breaker is reset, so the next request runs the new code.
+
+use super::types::{ErrorResponse, ModuleAttributes, ModuleInfo, ModuleResponse};
+use super::{json_error, json_response};
+use crate::runtime::SharedState;
+use crate::runtime::module_path::ModulePath;
+use crate::runtime::request_handler::collect_request_body;
+use crate::runtime::security;
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::header::HeaderMap;
+use hyper::{Method, Request, Response};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+
+/// Gateway path prefix (after `/_mik/`) of the management endpoints.
+pub const TENANTS_PATH: &str = "tenants/";
+
+/// Header carrying the upload signature.
+pub(crate) const SIGNATURE_HEADER: &str = "X-Mik-Signature";
+
+/// Magic bytes opening every WebAssembly binary (modules and components).
+const WASM_MAGIC: &[u8; 4] = b"\0asm";
+
+/// Handle a request under `/_mik/tenants/`.
+///
+/// `rest` is the path after `/_mik/tenants/`.
+pub async fn handle_module_request(
+    shared: &Arc<SharedState>,
+    req: Request<hyper::body::Incoming>,
+    rest: &str,
+) -> Result<Response<Full<Bytes>>> {
+    if !shared.config.tenant_uploads {
+        return json_error(
+            403,
+            &ErrorResponse::forbidden(
+                "Module management is disabled (set tenant_uploads = true in [server])",
+            ),
+        );
+    }
+    if let Some(resp) = authenticate(shared, req.headers(), "Module management")? {
+        return Ok(resp);
+    }
+
+    let Some((tenant_id, name)) = rest
+        .split_once("/modules/")
+        .filter(|(_, name)| !name.is_empty() && !name.contains('/'))
+    else {
+        return json_error(
+            404,
+            &ErrorResponse::not_found(format!("Unknown gateway endpoint: {TENANTS_PATH}{rest}")),
+        );
+    };
+    for (what, value) in [("tenant ID", tenant_id), ("module name", name)] {
+        if let Err(e) = security::sanitize_module_name(value) {
+            return json_error(
+                400,
+                &ErrorResponse::invalid_request(format!("Invalid {what} '{value}': {e}")),
+            );
+        }
+    }
+    let module_path = ModulePath::Tenant {
+        tenant_id: tenant_id.to_string(),
+        name: name.to_string(),
+    };
+
+    match *req.method() {
+        Method::PUT => upload_module(shared, req, module_path).await,
+        Method::DELETE => delete_module(shared, &module_path).await,
+        _ => json_error(
+            405,
+            &ErrorResponse::method_not_allowed("Use PUT to upload a module or DELETE to remove it"),
+        ),
+    }
+}
+
+/// Check the `X-API-Key` header against the configured key, for the
+/// endpoints of `feature` (e.g. "Module management").
+///
+/// Returns the error response to send when the request is not allowed.
+pub(crate) fn authenticate(
+    shared: &SharedState,
+    headers: &HeaderMap,
+    feature: &str,
+) -> Result<Option<Response<Full<Bytes>>>> {
+    if shared.config.api_key.is_none() {
+        return json_error(
+            403,
+            &ErrorResponse::forbidden(format!("{feature} is disabled (no API key configured)")),
+        )
+        .map(Some);
+    }
+    if has_api_key(shared, headers) {
+        return Ok(None);
+    }
+    warn!("{} request rejected: missing or invalid API key", feature);
+    json_error(
+        401,
+        &ErrorResponse::unauthorized("Missing or invalid X-API-Key header"),
+    )
+    .map(Some)
+}
+
+/// Whether the `X-API-Key` header matches the configured key (false when
+/// no key is configured).
+pub(crate) fn has_api_key(shared: &SharedState, headers: &HeaderMap) -> bool {
+    let Some(expected) = shared.config.api_key.as_deref() else {
+        return false;
+    };
+    let provided = headers
+        .get("X-API-Key")
+        .map(hyper::header::HeaderValue::as_bytes)
+        .unwrap_or_default();
+
+    // Constant-time comparison; only the key length can leak
+    provided.len() == expected.len() && bool::from(provided.ct_eq(expected.as_bytes()))
+}
+
+/// Handle PUT: validate the body and atomically write it to the tenant's
+/// directory.
+async fn upload_module(
+    shared: &Arc<SharedState>,
+    req: Request<hyper::body::Incoming>,
+    module_path: ModulePath,
+) -> Result<Response<Full<Bytes>>> {
+    let Some(user_modules_dir) = shared.user_modules_dir.as_deref() else {
+        return not_configured();
+    };
+    let (parts, body) = req.into_parts();
+    let wasm = match collect_request_body(body, shared.config.max_module_size_bytes).await? {
+        Ok(bytes) => bytes,
+        Err(resp) => return Ok(resp),
+    };
+
+    if !wasm.starts_with(WASM_MAGIC) {
+        return json_error(
+            400,
+            &ErrorResponse::invalid_request("Body is not a WebAssembly binary"),
+        );
+    }
+    if let Some(key) = shared.config.module_signing_key.as_deref()
+        && !verify_signature(key, &parts.headers, &wasm)
+    {
+        warn!(
+            "Rejected unsigned or badly signed upload of {}",
+            module_path
+        );
+        return json_error(
+            401,
+            &ErrorResponse::unauthorized(format!("Missing or invalid {SIGNATURE_HEADER} header")),
+        );
+    }
+
+    let wasm_path = module_path
+        .wasm_path(&shared.modules_dir, Some(user_modules_dir))
+        .context("Tenant module path")?;
+    let tenant_dir = wasm_path.parent().context("Tenant directory")?;
+    tokio::fs::create_dir_all(tenant_dir)
+        .await
+        .with_context(|| format!("Failed to create {}", tenant_dir.display()))?;
+    let replaced = tokio::fs::try_exists(&wasm_path).await?;
+
+    let tenant_id = module_path.tenant_id().unwrap_or_default();
+    if let Some(max_mb) = shared.live().tenant_limits(tenant_id).max_storage_mb {
+        let used = stored_bytes(tenant_dir, &wasm_path).await?;
+        let max_bytes = max_mb.saturating_mul(1024 * 1024);
+        if used + wasm.len() as u64 > max_bytes {
+            warn!(
+                "Rejected upload of {}: tenant storage limit of {} MB reached",
+                module_path, max_mb
+            );
+            return json_error(
+                507,
+                &ErrorResponse::quota_exceeded(format!(
+                    "Tenant '{tenant_id}' would exceed its storage limit of {max_mb} MB \
+                     ({used} bytes used, {} bytes uploaded)",
+                    wasm.len()
+                )),
+            );
+        }
+    }
+
+    // Write next to the target and rename, so requests never load a partial file
+    let partial = wasm_path.with_extension("wasm.partial");
+    tokio::fs::write(&partial, &wasm)
+        .await
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    tokio::fs::rename(&partial, &wasm_path)
+        .await
+        .with_context(|| format!("Failed to replace {}", wasm_path.display()))?;
+
+    evict(shared, &module_path);
+    info!(
+        "{} module {} ({} bytes)",
+        if replaced { "Replaced" } else { "Uploaded" },
+        module_path,
+        wasm.len()
+    );
+
+    let response = ModuleResponse {
+        data: module_info(&module_path, &wasm),
+    };
+    json_response(if replaced { 200 } else { 201 }, &response)
+}
+
+/// Handle DELETE: remove the module file and evict it.
+async fn delete_module(
+    shared: &Arc<SharedState>,
+    module_path: &ModulePath,
+) -> Result<Response<Full<Bytes>>> {
+    let Some(user_modules_dir) = shared.user_modules_dir.as_deref() else {
+        return not_configured();
+    };
+    let wasm_path = module_path
+        .wasm_path(&shared.modules_dir, Some(user_modules_dir))
+        .context("Tenant module path")?;
+
+    match tokio::fs::remove_file(&wasm_path).await {
+        Ok(()) => {},
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return json_error(
+                404,
+                &ErrorResponse::not_found(format!("Module not found: {module_path}")),
+            );
+        },
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to delete {}", wasm_path.display()));
+        },
+    }
+
+    evict(shared, module_path);
+    info!("Deleted module {}", module_path);
+    Ok(Response::builder()
+        .status(204)
+        .body(Full::new(Bytes::new()))?)
+}
+
+/// Bytes of the modules in `tenant_dir`, not counting `replaced`.
+async fn stored_bytes(tenant_dir: &Path, replaced: &Path) -> Result<u64> {
+    let mut used = 0;
+    let mut entries = tokio::fs::read_dir(tenant_dir)
+        .await
+        .with_context(|| format!("Failed to read {}", tenant_dir.display()))?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "wasm") && path != replaced {
+            used += entry.metadata().await?.len();
+        }
+    }
+    Ok(used)
+}
+
+/// Drop the cached component, circuit state and discovery responses of a
+/// changed module.
+fn evict(shared: &SharedState, module_path: &ModulePath) {
+    let key = module_path.cache_key();
+    shared.cache.invalidate(&key);
+    shared.circuit_breaker.reset(&key);
+    shared.gateway_index.invalidate();
+}
+
+/// Check `X-Mik-Signature: sha256=<hex>` against the HMAC-SHA256 of `body`.
+fn verify_signature(key: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+    let Some(signature) = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("sha256="))
+        .and_then(|v| hex::decode(v).ok())
+    else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(key.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    // Constant-time comparison
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Convert a tenant module to its API resource.
+fn module_info(module_path: &ModulePath, wasm: &[u8]) -> ModuleInfo {
+    ModuleInfo {
+        id: module_path.handler_name(),
+        resource_type: "module".to_string(),
+        attributes: ModuleAttributes {
+            name: module_path.name().to_string(),
+            tenant_id: module_path.tenant_id().unwrap_or_default().to_string(),
+            size_bytes: wasm.len() as u64,
+            sha256: hex::encode(Sha256::digest(wasm)),
+        },
+    }
+}
+
+fn not_configured() -> Result<Response<Full<Bytes>>> {
+    json_error(
+        404,
+        &ErrorResponse::not_found("Tenant modules not configured (user_modules directory not set)"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(key: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let body = b"\0asm\x01\0\0\0";
+        let mut headers = HeaderMap::new();
+        assert!(!verify_signature("secret", &headers, body));
+
+        headers.insert(SIGNATURE_HEADER, sign("secret", body).parse().unwrap());
+        assert!(verify_signature("secret", &headers, body));
+        assert!(!verify_signature("other", &headers, body));
+        assert!(!verify_signature("secret", &headers, b"\0asm\x01\0\0\x01"));
+
+        headers.insert(SIGNATURE_HEADER, "sha256=not-hex".parse().unwrap());
+        assert!(!verify_signature("secret", &headers, body));
+    }
+
+    #[test]
+    fn test_module_info_serialization() {
+        let path = ModulePath::Tenant {
+            tenant_id: "acme".to_string(),
+            name: "orders".to_string(),
+        };
+        let json = serde_json::to_value(module_info(&path, b"\0asm\x01\0\0\0")).unwrap();
+        assert_eq!(json["id"], "acme/orders");
+        assert_eq!(json["type"], "module");
+        assert_eq!(json["attributes"]["tenant_id"], "acme");
+        assert_eq!(json["attributes"]["size_bytes"], 8);
+        assert_eq!(json["attributes"]["sha256"].as_str().unwrap().len(), 64);
+    }
+}
@@ -66,6 +66,160 @@ pub struct HandlersMetadata {
     pub timestamp: String,
 }
 
+/// Response for GET /_mik/circuit-breakers endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CircuitBreakersResponse {
+    /// List of circuit breaker resources.
+    pub data: Vec<CircuitBreakerInfo>,
+    /// Response metadata.
+    pub meta: CircuitBreakersMetadata,
+}
+
+/// Response for circuit breaker actions (reset/open).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CircuitBreakerResponse {
+    /// The circuit breaker after the action.
+    pub data: CircuitBreakerInfo,
+}
+
+/// Individual circuit breaker resource (JSON:API style).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CircuitBreakerInfo {
+    /// Circuit key (module name, or `tenant:{tenant-id}/{module}` for tenant modules).
+    pub id: String,
+    /// Resource type (always "circuit-breaker").
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    /// Circuit breaker attributes.
+    pub attributes: CircuitBreakerAttributes,
+}
+
+/// Circuit breaker attributes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CircuitBreakerAttributes {
+    /// State: `closed`, `open`, or `half_open`.
+    pub state: String,
+    /// Consecutive failures counted by the circuit.
+    pub failure_count: u32,
+    /// When the circuit opened (ISO 8601, only for open circuits).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opened_at: Option<String>,
+}
+
+/// Metadata for circuit breakers response.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CircuitBreakersMetadata {
+    /// Total number of tracked circuits.
+    pub total: usize,
+    /// Number of open circuits.
+    pub open_count: usize,
+    /// Timestamp when this data was generated (ISO 8601).
+    pub timestamp: String,
+}
+
+/// Body of the handler webhook, sent when the handler set changes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HandlersChangedEvent {
+    /// Event type (always "handlers.changed").
+    pub event: String,
+    /// IDs of new handlers (as in `/_mik/handlers`).
+    pub added: Vec<String>,
+    /// IDs of handlers that no longer exist.
+    pub removed: Vec<String>,
+    /// IDs of handlers whose module or OpenAPI spec changed.
+    pub updated: Vec<String>,
+    /// Number of handlers after the change.
+    pub total: usize,
+    /// Timestamp when the change was detected (ISO 8601).
+    pub timestamp: String,
+}
+
+/// Response for POST /_mik/reload endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReloadResponse {
+    /// mik.toml keys whose values changed.
+    pub changed: Vec<String>,
+    /// Timestamp of the reload (ISO 8601).
+    pub timestamp: String,
+}
+
+/// Response for tenant module uploads.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModuleResponse {
+    /// The uploaded module.
+    pub data: ModuleInfo,
+}
+
+/// Tenant module resource (JSON:API style).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModuleInfo {
+    /// Module identifier (`{tenant-id}/{module}`).
+    pub id: String,
+    /// Resource type (always "module").
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    /// Module attributes.
+    pub attributes: ModuleAttributes,
+}
+
+/// Tenant module attributes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModuleAttributes {
+    /// Module name.
+    pub name: String,
+    /// Tenant owning the module.
+    pub tenant_id: String,
+    /// WASM module size in bytes.
+    pub size_bytes: u64,
+    /// SHA-256 digest of the module (hex).
+    pub sha256: String,
+}
+
+/// Response for GET /_mik/usage/tenant/{tenant-id} endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TenantUsageResponse {
+    /// The tenant's usage.
+    pub data: TenantUsageInfo,
+    /// Response metadata.
+    pub meta: TenantUsageMetadata,
+}
+
+/// Tenant usage resource (JSON:API style).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TenantUsageInfo {
+    /// Tenant ID.
+    pub id: String,
+    /// Resource type (always "tenant-usage").
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    /// Usage counters since startup.
+    pub attributes: TenantUsageAttributes,
+}
+
+/// Tenant usage counters since startup.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TenantUsageAttributes {
+    /// Executions of the tenant's modules.
+    pub requests: u64,
+    /// Executions that failed or were cancelled.
+    pub errors: u64,
+    /// Total execution time in milliseconds.
+    pub execution_time_ms: u64,
+    /// Fuel consumed by the tenant's modules.
+    pub fuel_consumed: u64,
+    /// Outgoing HTTP request body bytes sent.
+    pub egress_sent_bytes: u64,
+    /// Outgoing HTTP response body bytes received.
+    pub egress_received_bytes: u64,
+}
+
+/// Metadata for tenant usage response.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TenantUsageMetadata {
+    /// Timestamp when this data was generated (ISO 8601).
+    pub timestamp: String,
+}
+
 /// Discovered module information.
 #[derive(Debug, Clone)]
 pub struct DiscoveredModule {
@@ -114,6 +268,51 @@ impl ErrorResponse {
         }
     }
 
+    /// Create a method not allowed error.
+    pub fn method_not_allowed(message: impl Into<String>) -> Self {
+        Self {
+            error: "method_not_allowed".to_string(),
+            message: message.into(),
+            request_id: None,
+        }
+    }
+
+    /// Create an invalid request error.
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self {
+            error: "invalid_request".to_string(),
+            message: message.into(),
+            request_id: None,
+        }
+    }
+
+    /// Create an unauthorized error.
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self {
+            error: "unauthorized".to_string(),
+            message: message.into(),
+            request_id: None,
+        }
+    }
+
+    /// Create a forbidden error.
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self {
+            error: "forbidden".to_string(),
+            message: message.into(),
+            request_id: None,
+        }
+    }
+
+    /// Create a quota exceeded error.
+    pub fn quota_exceeded(message: impl Into<String>) -> Self {
+        Self {
+            error: "quota_exceeded".to_string(),
+            message: message.into(),
+            request_id: None,
+        }
+    }
+
     /// Create an internal error.
     pub fn internal(message: impl Into<String>) -> Self {
         Self {
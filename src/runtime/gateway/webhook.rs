@@ -0,0 +1,274 @@
+//! Handler change notifications.
+//!
+//! With `handlers_webhook` set, the server sends a `POST` to that URL
+//! whenever modules are added, removed or updated, so a gateway can update
+//! its routing right away instead of polling `/_mik/handlers`:
+//!
+//! ```json
+//! {
+//!   "event": "handlers.changed",
+//!   "added": ["acme/orders"],
+//!   "removed": [],
+//!   "updated": ["auth"],
+//!   "total": 3,
+//!   "timestamp": "2025-01-01T00:00:00+00:00"
+//! }
+//! ```
+//!
+//! IDs are the handler IDs of `/_mik/handlers`. A module counts as updated
+//! when its size, modification time or OpenAPI spec presence changes.
+//!
+//! Changes are picked up by the gateway index's watcher (see [`index`]) and
+//! debounced, so copying several modules at once sends one notification.
+//! Without a watcher, the directories are rescanned every few seconds. With a
+//! `handlers_webhook_secret` (`MIK_HANDLERS_WEBHOOK_SECRET` for `mik run`),
+//! bodies are signed like module uploads:
+//! `X-Mik-Signature: sha256=<hex HMAC-SHA256 of the body>`.
+//!
+//! Failed deliveries are retried a few times with backoff, then dropped with
+//! a warning; the next change sends a fresh diff against what was last seen.
+//!
+//! [`index`]: super::index
+
+use super::discovery::discover_all_modules;
+use super::modules::SIGNATURE_HEADER;
+use super::types::HandlersChangedEvent;
+use crate::runtime::SharedState;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Wait after a change before scanning, so bursts send one notification.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Rescan interval when the module directories are not watched.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Timeout for one delivery attempt.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delivery attempts per notification.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// State of one handler, compared between scans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HandlerState {
+    size_bytes: u64,
+    modified: Option<SystemTime>,
+    has_openapi: bool,
+}
+
+/// Handlers by ID.
+type Snapshot = BTreeMap<String, HandlerState>;
+
+/// Start notifying the configured `handlers_webhook`, if any.
+///
+/// The task runs until aborted.
+pub(crate) fn spawn(shared: &Arc<SharedState>) -> Option<JoinHandle<()>> {
+    let url = shared.config.handlers_webhook.clone()?;
+    let shared = shared.clone();
+    Some(tokio::spawn(async move {
+        if let Err(e) = run(&shared, &url).await {
+            warn!("Handler webhook disabled: {:#}", e);
+        }
+    }))
+}
+
+async fn run(shared: &SharedState, url: &str) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .context("Failed to create HTTP client")?;
+    let watching = shared
+        .gateway_index
+        .watch_dirs(&shared.modules_dir, shared.user_modules_dir.as_deref());
+    info!(
+        "Handler webhook: notifying {} of handler changes ({})",
+        url,
+        if watching { "watching" } else { "polling" }
+    );
+
+    let mut known = snapshot(shared);
+    loop {
+        if watching {
+            shared.gateway_index.changed().await;
+        } else {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        tokio::time::sleep(DEBOUNCE).await;
+
+        let current = snapshot(shared);
+        if let Some(event) = diff(&known, &current) {
+            notify(
+                &client,
+                url,
+                shared.config.handlers_webhook_secret.as_deref(),
+                &event,
+            )
+            .await;
+        }
+        known = current;
+    }
+}
+
+/// Scan the module directories.
+fn snapshot(shared: &SharedState) -> Snapshot {
+    let (platform, tenant) =
+        discover_all_modules(&shared.modules_dir, shared.user_modules_dir.as_deref());
+    platform
+        .into_iter()
+        .chain(tenant)
+        .map(|module| {
+            let id = match &module.tenant_id {
+                Some(tenant_id) => format!("{tenant_id}/{}", module.name),
+                None => module.name.clone(),
+            };
+            let state = HandlerState {
+                size_bytes: module.size_bytes,
+                modified: module
+                    .wasm_path
+                    .metadata()
+                    .and_then(|meta| meta.modified())
+                    .ok(),
+                has_openapi: module.openapi_path.is_some(),
+            };
+            (id, state)
+        })
+        .collect()
+}
+
+/// The change from `before` to `after`, or `None` if the handlers are the same.
+fn diff(before: &Snapshot, after: &Snapshot) -> Option<HandlersChangedEvent> {
+    let added: Vec<String> = after
+        .keys()
+        .filter(|id| !before.contains_key(*id))
+        .cloned()
+        .collect();
+    let removed: Vec<String> = before
+        .keys()
+        .filter(|id| !after.contains_key(*id))
+        .cloned()
+        .collect();
+    let updated: Vec<String> = after
+        .iter()
+        .filter(|(id, state)| before.get(*id).is_some_and(|old| old != *state))
+        .map(|(id, _)| id.clone())
+        .collect();
+    if added.is_empty() && removed.is_empty() && updated.is_empty() {
+        return None;
+    }
+    Some(HandlersChangedEvent {
+        event: "handlers.changed".to_string(),
+        added,
+        removed,
+        updated,
+        total: after.len(),
+        timestamp: Utc::now().to_rfc3339(),
+    })
+}
+
+/// Deliver `event`, retrying failures with backoff.
+async fn notify(
+    client: &reqwest::Client,
+    url: &str,
+    secret: Option<&str>,
+    event: &HandlersChangedEvent,
+) {
+    let body = match serde_json::to_vec(event) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to serialize handler webhook: {}", e);
+            return;
+        },
+    };
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut req = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body.clone());
+        if let Some(secret) = secret {
+            req = req.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+        let error = match req.send().await {
+            Ok(resp) if resp.status().is_success() => {
+                debug!(
+                    added = event.added.len(),
+                    removed = event.removed.len(),
+                    updated = event.updated.len(),
+                    "Handler webhook delivered"
+                );
+                return;
+            },
+            Ok(resp) => format!("status {}", resp.status()),
+            Err(e) => e.to_string(),
+        };
+        if attempt == MAX_ATTEMPTS {
+            warn!(
+                "Handler webhook to {} failed after {} attempts: {}",
+                url, MAX_ATTEMPTS, error
+            );
+        } else {
+            debug!("Handler webhook attempt {} failed: {}", attempt, error);
+            tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 of `body`.
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(size_bytes: u64) -> HandlerState {
+        HandlerState {
+            size_bytes,
+            modified: None,
+            has_openapi: false,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let before: Snapshot = [
+            ("auth".to_string(), state(10)),
+            ("acme/orders".to_string(), state(20)),
+        ]
+        .into();
+        assert!(diff(&before, &before).is_none());
+
+        let after: Snapshot = [
+            ("auth".to_string(), state(11)),
+            ("globex/orders".to_string(), state(20)),
+        ]
+        .into();
+        let event = diff(&before, &after).unwrap();
+        assert_eq!(event.event, "handlers.changed");
+        assert_eq!(event.added, ["globex/orders"]);
+        assert_eq!(event.removed, ["acme/orders"]);
+        assert_eq!(event.updated, ["auth"]);
+        assert_eq!(event.total, 2);
+    }
+
+    #[test]
+    fn test_sign() {
+        let signature = sign("secret", b"{}");
+        let hex = signature.strip_prefix("sha256=").unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(b"{}");
+        assert!(mac.verify_slice(&hex::decode(hex).unwrap()).is_ok());
+    }
+}
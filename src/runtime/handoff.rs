@@ -0,0 +1,311 @@
+//! Zero-downtime upgrades via listener socket handoff.
+//!
+//! Sending `SIGUSR2` to a running server re-executes the current binary with
+//! the same arguments and passes it the listening socket:
+//!
+//! 1. The old process duplicates its listener fd (without `CLOEXEC`) and
+//!    spawns the new binary with `MIK_LISTEN_FD` and `MIK_UPGRADE_PARENT` set.
+//! 2. The new process adopts the socket instead of binding, starts accepting,
+//!    then sends `SIGTERM` to the old process.
+//! 3. The old process stops accepting and drains in-flight connections as in
+//!    a normal graceful shutdown.
+//!
+//! Both processes accept from the same kernel socket during the overlap, so
+//! queued connections are never reset. If the new binary fails to start, the
+//! old process simply keeps serving.
+//!
+//! Alternatively, [`bind_listener`] can set `SO_REUSEPORT` so an independently
+//! started process can bind the same port while the old one drains.
+//!
+//! Handoff is Unix-only; on other platforms servers always bind normally.
+
+use anyhow::{Context, Result};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::SocketAddr;
+
+/// Environment variable carrying the inherited listener fd.
+pub const LISTEN_FD_ENV: &str = "MIK_LISTEN_FD";
+
+/// Environment variable carrying the pid of the process handing off.
+pub const UPGRADE_PARENT_ENV: &str = "MIK_UPGRADE_PARENT";
+
+/// Listen backlog for sockets bound by [`bind_listener`].
+const LISTEN_BACKLOG: i32 = 1024;
+
+/// Create the server listener for `addr`.
+///
+/// Adopts a socket handed off by a previous process when one is available,
+/// otherwise binds a new socket (with `SO_REUSEPORT` on Unix if `reuse_port`).
+/// Returns the listener and whether it was inherited.
+///
+/// # Errors
+///
+/// Returns an error if the socket cannot be created or bound.
+pub fn bind_listener(addr: SocketAddr, reuse_port: bool) -> Result<(std::net::TcpListener, bool)> {
+    #[cfg(unix)]
+    if let Some(listener) = unix::inherited_listener(addr) {
+        return Ok((listener, true));
+    }
+
+    Ok((bind_socket(addr, reuse_port)?, false))
+}
+
+/// Bind a new listening socket for `addr`, with `SO_REUSEPORT` on Unix if
+/// `reuse_port`.
+///
+/// # Errors
+///
+/// Returns an error if the socket cannot be created or bound.
+pub fn bind_socket(addr: SocketAddr, reuse_port: bool) -> Result<std::net::TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))
+        .context("Failed to create listener socket")?;
+    // Matches std/tokio bind behavior on Unix (fast restart after TIME_WAIT)
+    #[cfg(unix)]
+    socket
+        .set_reuse_address(true)
+        .context("Failed to set SO_REUSEADDR")?;
+    #[cfg(unix)]
+    if reuse_port {
+        socket
+            .set_reuse_port(true)
+            .context("Failed to set SO_REUSEPORT")?;
+    }
+    #[cfg(not(unix))]
+    if reuse_port {
+        tracing::warn!("SO_REUSEPORT is not supported on this platform, ignoring");
+    }
+    socket
+        .bind(&addr.into())
+        .with_context(|| format!("Failed to bind {addr}"))?;
+    socket
+        .listen(LISTEN_BACKLOG)
+        .with_context(|| format!("Failed to listen on {addr}"))?;
+    socket
+        .set_nonblocking(true)
+        .context("Failed to set listener non-blocking")?;
+
+    Ok(socket.into())
+}
+
+/// Tell the process that handed off the listener to start draining.
+///
+/// Call once the inherited listener is accepting connections. No-op when the
+/// listener was not inherited.
+pub fn notify_parent() {
+    #[cfg(unix)]
+    unix::notify_parent();
+}
+
+/// Waits for upgrade requests (`SIGUSR2`).
+///
+/// Never resolves on platforms without signal support.
+pub struct UpgradeSignal {
+    #[cfg(unix)]
+    signal: Option<tokio::signal::unix::Signal>,
+}
+
+impl UpgradeSignal {
+    /// Register the upgrade signal handler.
+    #[must_use]
+    pub fn new() -> Self {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{SignalKind, signal};
+            let signal = signal(SignalKind::user_defined2())
+                .inspect_err(|e| tracing::warn!(error = %e, "Failed to setup SIGUSR2 handler"))
+                .ok();
+            Self { signal }
+        }
+        #[cfg(not(unix))]
+        Self {}
+    }
+
+    /// Wait for the next upgrade request.
+    pub async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = &mut self.signal
+            && signal.recv().await.is_some()
+        {
+            return;
+        }
+        std::future::pending::<()>().await;
+    }
+}
+
+impl Default for UpgradeSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Start a new copy of the current binary that takes over `listener`.
+///
+/// Returns the new process id. The caller keeps serving until the new process
+/// signals it to drain (see [`notify_parent`]).
+///
+/// # Errors
+///
+/// Returns an error if the socket cannot be duplicated or the binary cannot
+/// be started.
+#[cfg(unix)]
+pub fn spawn_upgrade(listener: &tokio::net::TcpListener) -> Result<u32> {
+    unix::spawn_upgrade(listener)
+}
+
+/// Start a new copy of the current binary that takes over `listener`.
+///
+/// # Errors
+///
+/// Always fails: socket handoff requires Unix.
+#[cfg(not(unix))]
+pub fn spawn_upgrade(_listener: &tokio::net::TcpListener) -> Result<u32> {
+    anyhow::bail!("Listener handoff is only supported on Unix")
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{LISTEN_FD_ENV, UPGRADE_PARENT_ENV};
+    use anyhow::{Context, Result};
+    use nix::sys::signal::{Signal, kill};
+    use nix::unistd::Pid;
+    use socket2::{SockRef, Socket};
+    use std::net::SocketAddr;
+    use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd, RawFd};
+
+    /// Pid of the process that handed us the listener, once adopted.
+    static HANDOFF_PARENT: std::sync::OnceLock<u32> = std::sync::OnceLock::new();
+
+    pub(super) fn inherited_listener(addr: SocketAddr) -> Option<std::net::TcpListener> {
+        let fd: RawFd = std::env::var(LISTEN_FD_ENV).ok()?.parse().ok()?;
+        let parent: u32 = std::env::var(UPGRADE_PARENT_ENV).ok()?.parse().ok()?;
+
+        // The variables leak into child processes (e.g. cluster workers), which
+        // must not adopt whatever happens to live at that fd number
+        if std::os::unix::process::parent_id() != parent {
+            return None;
+        }
+
+        match adopt_listener(fd, addr) {
+            Ok(listener) => {
+                let _ = HANDOFF_PARENT.set(parent);
+                tracing::info!(fd, parent, "Adopted listener from previous process");
+                Some(listener)
+            },
+            Err(e) => {
+                tracing::warn!(fd, error = %e, "Ignoring inherited listener");
+                None
+            },
+        }
+    }
+
+    /// Take ownership of `fd` if it is a TCP socket bound to `addr`'s port.
+    #[allow(unsafe_code)] // SAFETY: adopting an inherited fd requires from_raw_fd
+    pub(super) fn adopt_listener(fd: RawFd, addr: SocketAddr) -> Result<std::net::TcpListener> {
+        // Validate through a borrowed reference first, so an unrelated fd is
+        // never closed by us
+        // SAFETY: `fd` is only borrowed for the duration of the checks below.
+        let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+        let socket = SockRef::from(&borrowed);
+        let local = socket
+            .local_addr()
+            .ok()
+            .and_then(|a| a.as_socket())
+            .context("fd is not a TCP socket")?;
+        anyhow::ensure!(
+            socket.r#type().is_ok_and(|t| t == socket2::Type::STREAM),
+            "fd is not a stream socket"
+        );
+        anyhow::ensure!(
+            local.port() == addr.port() || addr.port() == 0,
+            "fd is bound to {local}, expected port {}",
+            addr.port()
+        );
+
+        // SAFETY: validated above as a TCP socket handed to this process.
+        let socket = unsafe { Socket::from_raw_fd(fd) };
+        socket.set_cloexec(true).context("Failed to set CLOEXEC")?;
+        socket
+            .set_nonblocking(true)
+            .context("Failed to set listener non-blocking")?;
+        Ok(socket.into())
+    }
+
+    pub(super) fn notify_parent() {
+        let Some(&parent) = HANDOFF_PARENT.get() else {
+            return;
+        };
+        #[allow(clippy::cast_possible_wrap)] // pids fit in i32
+        match kill(Pid::from_raw(parent as i32), Signal::SIGTERM) {
+            Ok(()) => tracing::info!(parent, "Signaled previous process to drain"),
+            Err(e) => tracing::warn!(parent, error = %e, "Failed to signal previous process"),
+        }
+    }
+
+    pub(super) fn spawn_upgrade(listener: &tokio::net::TcpListener) -> Result<u32> {
+        let exe = std::env::current_exe().context("Failed to locate current executable")?;
+
+        // The duplicate is inherited by the child; ours closes when it drops
+        let handoff = SockRef::from(listener)
+            .try_clone()
+            .context("Failed to duplicate listener")?;
+        handoff
+            .set_cloexec(false)
+            .context("Failed to clear CLOEXEC on listener")?;
+
+        // The new process outlives this one and is reparented on exit
+        #[allow(clippy::zombie_processes)]
+        let child = std::process::Command::new(&exe)
+            .args(std::env::args_os().skip(1))
+            .env(LISTEN_FD_ENV, handoff.as_raw_fd().to_string())
+            .env(UPGRADE_PARENT_ENV, std::process::id().to_string())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", exe.display()))?;
+
+        Ok(child.id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reuse_port_allows_second_bind() {
+        let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let (first, inherited) = bind_listener(addr, true).unwrap();
+        assert!(!inherited);
+
+        let addr = first.local_addr().unwrap();
+        let (second, _) = bind_listener(addr, true).unwrap();
+        assert_eq!(second.local_addr().unwrap(), addr);
+    }
+
+    #[test]
+    fn test_bind_without_reuse_port_conflicts() {
+        let addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+        let (first, _) = bind_listener(addr, false).unwrap();
+        assert!(bind_listener(first.local_addr().unwrap(), false).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_adopt_listener_validates_fd() {
+        use std::os::fd::IntoRawFd;
+
+        let (listener, _) = bind_listener("127.0.0.1:0".parse().unwrap(), false).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let fd = listener.into_raw_fd();
+
+        // Wrong port: rejected, and the fd stays open for the real owner
+        let other: SocketAddr = "127.0.0.1:1".parse().unwrap();
+        assert!(unix::adopt_listener(fd, other).is_err());
+
+        let adopted = unix::adopt_listener(fd, addr).unwrap();
+        assert_eq!(adopted.local_addr().unwrap(), addr);
+
+        // Not a socket
+        let file = std::fs::File::open("/dev/null").unwrap();
+        assert!(unix::adopt_listener(std::os::fd::AsRawFd::as_raw_fd(&file), addr).is_err());
+    }
+}
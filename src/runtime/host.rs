@@ -3,18 +3,30 @@
 //! This module contains the `Host` struct which manages wasmtime engine setup,
 //! epoch interruption threads, and module loading configuration.
 
+use super::adaptive;
 use super::aot_cache;
+use super::component_exports::ComponentExports;
+use super::core_adapter;
+use super::drain;
 use super::error;
+use super::gateway;
 use super::host_config::HostConfig;
 use super::host_state::HostState;
-use super::reliability;
+use super::reliability::{self, CircuitBreakerConfig};
+use super::reload::LiveConfig;
+use super::request_info;
+use super::sql;
+use super::tenant_limits;
 use super::{CachedComponent, ModuleCache, SharedState};
 use crate::constants;
+use crate::daemon::http::client::DaemonClient;
+use crate::daemon::services::sql::SqlService;
+use crate::manifest::CircuitBreakerPolicy;
 use anyhow::{Context, Result};
 use moka::sync::Cache as MokaCache;
-use parking_lot::Mutex;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use parking_lot::{Mutex, RwLock};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
@@ -56,6 +68,20 @@ impl Host {
         Engine::new(&wasm_config).context("Failed to create wasmtime engine")
     }
 
+    /// Create the linker providing every host interface modules can import:
+    /// WASI preview 2, outgoing `wasi:http`, `mik:request-info`, `mik:sql`
+    /// and, with the `nn` feature, `wasi-nn`.
+    pub(crate) fn create_linker(engine: &Engine) -> Result<Linker<HostState>> {
+        let mut linker: Linker<HostState> = Linker::new(engine);
+        wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+        wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker)?;
+        request_info::add_to_linker(&mut linker)?;
+        sql::add_to_linker(&mut linker)?;
+        #[cfg(feature = "nn")]
+        super::nn::add_to_linker(&mut linker)?;
+        Ok(linker)
+    }
+
     /// Start the background epoch incrementer thread.
     fn start_epoch_thread(engine: &Engine) -> Arc<AtomicBool> {
         let epoch_shutdown = Arc::new(AtomicBool::new(false));
@@ -77,7 +103,10 @@ impl Host {
     ) -> Result<(PathBuf, Option<Arc<Component>>, Option<String>)> {
         if config.modules_path.is_file() {
             info!("Single component mode: {}", config.modules_path.display());
-            let component = Component::from_file(engine, &config.modules_path)
+            let wasm_bytes = std::fs::read(&config.modules_path)
+                .with_context(|| format!("Failed to read {}", config.modules_path.display()))?;
+            let component = core_adapter::componentize(&wasm_bytes)
+                .and_then(|bytes| Component::from_binary(engine, &bytes))
                 .context("Failed to load component")?;
 
             let name = config
@@ -89,6 +118,11 @@ impl Host {
                     |s| s.strip_suffix("-composed").unwrap_or(s).to_string(),
                 );
 
+            // Single component mode only serves HTTP: reject other worlds up front
+            ComponentExports::detect(engine, &component)
+                .require_http(&name)
+                .map_err(|e| error::Error::Config(e.to_string()).into_anyhow())?;
+
             let modules_dir = config
                 .modules_path
                 .parent()
@@ -134,6 +168,52 @@ impl Host {
         }
     }
 
+    /// Check that preopened directories exist and guest paths are absolute.
+    fn check_preopens(config: &HostConfig) -> Result<()> {
+        for (module, preopens) in &config.preopens {
+            for preopen in preopens {
+                if !preopen.guest.starts_with('/') {
+                    return Err(error::Error::Config(format!(
+                        "Preopen guest path '{}' for '{module}' must be absolute",
+                        preopen.guest
+                    ))
+                    .into_anyhow());
+                }
+                if !Path::new(&preopen.path).is_dir() {
+                    return Err(error::Error::Config(format!(
+                        "Preopen directory for '{module}' not found: {}",
+                        preopen.path
+                    ))
+                    .into_anyhow());
+                }
+                info!(
+                    "Capability: {} -> {} ({}) for {module}",
+                    preopen.path,
+                    preopen.guest,
+                    if preopen.writable { "rw" } else { "ro" }
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Open the database for `mik:sql` when any module is granted access.
+    fn open_sql(config: &HostConfig) -> Result<Option<SqlService>> {
+        if config.sql_modules.is_empty() {
+            return Ok(None);
+        }
+        let path = match &config.sql_database {
+            Some(path) => path.clone(),
+            None => crate::daemon::services::get_data_dir()
+                .context("Failed to get data directory")?
+                .join("sql.db"),
+        };
+        let sql = SqlService::file(&path)
+            .with_context(|| format!("Failed to open SQL database: {}", path.display()))?;
+        info!("SQL database: {}", path.display());
+        Ok(Some(sql))
+    }
+
     /// Create the AOT cache based on configuration.
     fn create_aot_cache(config: &HostConfig) -> Result<aot_cache::AotCache> {
         if config.hot_reload {
@@ -159,6 +239,46 @@ impl Host {
         Ok(cache)
     }
 
+    /// Create the circuit breaker with the `[server.circuit_breakers]` settings.
+    fn create_circuit_breaker(config: &HostConfig) -> reliability::CircuitBreaker {
+        let (defaults, modules) = Self::circuit_breaker_configs(&config.circuit_breakers);
+        modules.into_iter().fold(
+            reliability::CircuitBreaker::with_config(defaults),
+            |breaker, (module, config)| breaker.with_key_config(module, config),
+        )
+    }
+
+    /// Default and per-module circuit breaker settings for `policies`, with
+    /// the `"*"` entry applied to the defaults.
+    pub(crate) fn circuit_breaker_configs(
+        policies: &BTreeMap<String, CircuitBreakerPolicy>,
+    ) -> (CircuitBreakerConfig, HashMap<String, CircuitBreakerConfig>) {
+        let apply = |policy: &CircuitBreakerPolicy, mut breaker: CircuitBreakerConfig| {
+            if let Some(threshold) = policy.failure_threshold {
+                breaker.failure_threshold = threshold;
+            }
+            if let Some(secs) = policy.recovery_timeout_secs {
+                breaker.timeout = Duration::from_secs(secs);
+            }
+            if let Some(secs) = policy.probe_timeout_secs {
+                breaker.probe_timeout = Duration::from_secs(secs);
+            }
+            breaker
+        };
+
+        let defaults = policies
+            .get("*")
+            .map_or_else(CircuitBreakerConfig::default, |policy| {
+                apply(policy, CircuitBreakerConfig::default())
+            });
+        let modules = policies
+            .iter()
+            .filter(|(module, _)| *module != "*")
+            .map(|(module, policy)| (module.clone(), apply(policy, defaults.clone())))
+            .collect();
+        (defaults, modules)
+    }
+
     /// Log enabled capabilities.
     fn log_capabilities(config: &HostConfig) {
         if config.logging_enabled {
@@ -174,6 +294,37 @@ impl Host {
                 );
             }
         }
+        if !config.sql_modules.is_empty() {
+            info!(
+                "Capability: mik:sql enabled for {}",
+                config.sql_modules.join(", ")
+            );
+        }
+        for (module, models) in &config.nn_modules {
+            info!(
+                "Capability: wasi-nn enabled for {} ({})",
+                module,
+                models.join(", ")
+            );
+        }
+        if !config.script_capabilities.is_empty() {
+            info!(
+                "Capability: host.kv/host.storage enabled for scripts {}",
+                config
+                    .script_capabilities
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        if let Some(vars) = &config.component_env {
+            let vars = crate::manifest::mask_secret_vars(vars)
+                .into_iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>();
+            info!("Component environment: {}", vars.join(", "));
+        }
     }
 
     /// Create a new host with the given configuration.
@@ -185,9 +336,7 @@ impl Host {
         let engine = Self::create_engine(&config)?;
         let epoch_shutdown = Self::start_epoch_thread(&engine);
 
-        let mut linker: Linker<HostState> = Linker::new(&engine);
-        wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
-        wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker)?;
+        let linker = Self::create_linker(&engine)?;
 
         // Create moka cache with byte-aware eviction
         let cache: ModuleCache = MokaCache::builder()
@@ -201,19 +350,30 @@ impl Host {
         let (modules_dir, single_component, single_component_name) =
             Self::determine_module_mode(&config, &engine)?;
 
-        // Validate static directory if provided
-        let static_dir = config.static_dir.clone().filter(|dir| {
-            if dir.is_dir() {
-                info!("Static files: {} -> /static/", dir.display());
-                true
-            } else {
-                warn!("Static directory not found: {}", dir.display());
-                false
-            }
-        });
-
         Self::log_capabilities(&config);
+        if !config.chaos.is_empty() {
+            warn!(
+                "Chaos mode: injecting faults into requests to {} (not for production)",
+                config
+                    .chaos
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        Self::check_preopens(&config)?;
+        if let Some(dir) = &config.record_dir {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            info!("Recording module requests to {}", dir.display());
+        }
         let aot_cache = Self::create_aot_cache(&config)?;
+        let sql = Self::open_sql(&config)?;
+        let nn = super::nn::NnModels::load(&config)?;
+        let oci = super::oci::OciModules::new(&config)?;
+        let daemon = (!config.script_capabilities.is_empty())
+            .then(|| DaemonClient::local(crate::daemon::startup::DAEMON_PORT).api_key_from_env());
 
         // Resolve fuel budget: use configured value or default
         let fuel_budget = config.fuel_budget.unwrap_or(constants::DEFAULT_FUEL_BUDGET);
@@ -240,19 +400,56 @@ impl Host {
             cache,
             single_component,
             single_component_name,
-            static_dir,
             execution_timeout: Duration::from_secs(config.execution_timeout_secs),
+            max_request_timeout: Duration::from_secs(if config.max_request_timeout_secs > 0 {
+                config.max_request_timeout_secs
+            } else {
+                config.execution_timeout_secs
+            }),
+            init_timeout: Duration::from_secs(if config.init_timeout_secs > 0 {
+                config.init_timeout_secs
+            } else {
+                config.execution_timeout_secs
+            }),
             memory_limit_bytes: config.memory_limit_bytes,
             max_body_size_bytes: config.max_body_size_bytes,
             shutdown: Arc::new(AtomicBool::new(false)),
+            drain: drain::DrainState::default(),
             request_counter: AtomicU64::new(0),
-            circuit_breaker: reliability::CircuitBreaker::new(),
+            circuit_breaker: Self::create_circuit_breaker(&config),
             request_semaphore: Arc::new(Semaphore::new(config.max_concurrent_requests)),
             module_semaphores: Mutex::new(HashMap::new()),
-            http_allowed: Arc::new(config.http_allowed.clone()),
+            module_queues: Mutex::new(HashMap::new()),
+            tenant_semaphores: Mutex::new(HashMap::new()),
+            tenant_rates: tenant_limits::TenantRateLimiter::default(),
+            gateway_index: gateway::index::DiscoveryIndex::default(),
+            adaptive_limits: config.adaptive_concurrency.then(|| {
+                adaptive::AdaptiveLimits::new(
+                    config.adaptive_concurrency_min,
+                    config.max_per_module_requests,
+                )
+            }),
+            live: RwLock::new(Arc::new(LiveConfig::new(&config))),
             scripts_dir: config.scripts_dir.clone(),
             aot_cache,
             fuel_budget,
+            canary: tokio::sync::OnceCell::new(),
+            stats: Arc::default(),
+            egress: Arc::default(),
+            usage: Arc::default(),
+            trusted_proxies: config
+                .trusted_proxies
+                .iter()
+                .filter_map(|value| request_info::TrustedProxy::parse(value).ok())
+                .collect(),
+            sql,
+            nn,
+            oci,
+            daemon,
+            recent_requests: config
+                .dev_dashboard
+                .as_ref()
+                .map(|_| super::dev_dashboard::RecentRequests::default()),
             config,
         });
 
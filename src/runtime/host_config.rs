@@ -1,10 +1,26 @@
 //! Host configuration types and validation.
 //!
 //! This module contains the configuration structures for the WASI HTTP runtime host.
+//!
+//! Settings are layered, each layer overriding the previous one:
+//!
+//! 1. Built-in defaults (and auto-detected limits, see `[server] auto`)
+//! 2. `[server]` in mik.toml
+//! 3. `[profiles.<name>.server]` for the active profile
+//! 4. `MIK_<SETTING>` environment variables ([`HostConfig::apply_env`]),
+//!    e.g. `MIK_MEMORY_LIMIT_BYTES` for `memory_limit_bytes`
+//! 5. Command-line flags (`mik run --port`)
 
 use crate::constants;
+use crate::manifest::{
+    ChaosPolicy, CircuitBreakerPolicy, EgressQuota, ModuleAlias, Preopen, ScriptCapabilities,
+    TenantLimits,
+};
+use crate::runtime::listener::ListenAddr;
+use crate::runtime::request_info::TrustedProxy;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
-use tracing::warn;
+use tracing::{info, warn};
 
 /// Maximum execution timeout (5 minutes).
 pub const MAX_EXECUTION_TIMEOUT_SECS: u64 = 300;
@@ -30,14 +46,85 @@ pub const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
 pub enum ConfigError {
     #[error("invalid execution_timeout_secs={value}: {reason}")]
     Timeout { value: u64, reason: &'static str },
+    #[error("invalid max_request_timeout_secs={value}: {reason}")]
+    RequestTimeout { value: u64, reason: &'static str },
+    #[error("invalid init_timeout_secs={value}: {reason}")]
+    InitTimeout { value: u64, reason: &'static str },
     #[error("invalid memory_limit_bytes={value}: {reason}")]
     MemoryLimit { value: usize, reason: &'static str },
     #[error("invalid concurrency configuration: {reason}")]
     Concurrency { reason: &'static str },
+    #[error("invalid trusted_proxies entry '{value}': expected an IP address or CIDR range")]
+    TrustedProxy { value: String },
+    #[error("invalid listen entry '{value}': expected host:port or unix:/path")]
+    Listen { value: String },
+    #[error("invalid handlers_webhook '{value}': expected an http:// or https:// URL")]
+    HandlersWebhook { value: String },
+    #[error("invalid log_level '{value}': {reason}")]
+    LogLevel { value: String, reason: String },
+    #[error("invalid chaos {field}={value} for '{module}': must be between 0.0 and 1.0")]
+    Chaos {
+        module: String,
+        field: &'static str,
+        value: f64,
+    },
+    #[error("invalid {var}='{value}': expected {expected}")]
+    EnvOverride {
+        var: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+/// Prefix of the environment variables overriding `[server]` settings.
+pub const ENV_PREFIX: &str = "MIK_";
+
+/// `[server]` settings that `MIK_<SETTING>` environment variables override.
+///
+/// Lists are comma-separated, booleans are `true`/`false` (or `1`/`0`).
+pub const ENV_SETTINGS: &[&str] = &[
+    "port",
+    "listen",
+    "modules",
+    "user_modules",
+    "static",
+    "scripts",
+    "cache_size",
+    "max_cache_mb",
+    "execution_timeout_secs",
+    "max_request_timeout_secs",
+    "init_timeout_secs",
+    "execution_retries",
+    "execution_retry_backoff_ms",
+    "memory_limit_bytes",
+    "max_concurrent_requests",
+    "max_body_size_mb",
+    "max_module_size_mb",
+    "max_per_module_requests",
+    "max_per_tenant_requests",
+    "module_queue_depth",
+    "module_queue_timeout_ms",
+    "adaptive_concurrency",
+    "adaptive_concurrency_min",
+    "shutdown_timeout_secs",
+    "log_level",
+    "logging",
+    "http_allowed",
+    "trusted_proxies",
+    "proxy_protocol",
+    "tenant_uploads",
+    "handlers_webhook",
+    "sql_modules",
+];
+
+/// The environment variable overriding the `[server]` setting `key`.
+pub fn env_var(key: &str) -> String {
+    format!("{ENV_PREFIX}{}", key.to_ascii_uppercase())
 }
 
 /// Configuration for the host.
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)] // Independent feature switches
 pub struct HostConfig {
     /// Directory containing .wasm modules, or single component path.
     pub modules_path: PathBuf,
@@ -52,18 +139,45 @@ pub struct HostConfig {
     pub static_dir: Option<PathBuf>,
     /// Port to bind to (from mik.toml).
     pub port: u16,
+    /// Additional addresses to serve on (`host:port` or `unix:/path`).
+    pub listen: Vec<String>,
     /// Timeout for WASM execution (in seconds).
     pub execution_timeout_secs: u64,
+    /// Upper bound for per-request `X-Request-Timeout` / `grpc-timeout`
+    /// overrides (in seconds, 0 = `execution_timeout_secs`).
+    pub max_request_timeout_secs: u64,
+    /// Timeout for a component's `init`/`warmup` export (in seconds,
+    /// 0 = `execution_timeout_secs`).
+    pub init_timeout_secs: u64,
+    /// Retries for executions failing on instance exhaustion (0 = none).
+    pub execution_retries: u32,
+    /// Delay before the first execution retry (in milliseconds).
+    pub execution_retry_backoff_ms: u64,
     /// Memory limit per request (in bytes).
     pub memory_limit_bytes: usize,
     /// Maximum concurrent requests.
     pub max_concurrent_requests: usize,
     /// Maximum request body size (in bytes).
     pub max_body_size_bytes: usize,
+    /// Maximum size of a module uploaded through the gateway (in bytes).
+    pub max_module_size_bytes: usize,
     /// Maximum concurrent requests per module.
     pub max_per_module_requests: usize,
+    /// Maximum concurrent requests per tenant, across all of its modules
+    /// (0 = unlimited).
+    pub max_per_tenant_requests: usize,
+    /// Requests that may wait for a module at its limit (0 = no queue).
+    pub module_queue_depth: usize,
+    /// Maximum time a request waits in a module's queue (in milliseconds).
+    pub module_queue_timeout_ms: u64,
+    /// Adapt per-module limits below `max_per_module_requests` (AIMD).
+    pub adaptive_concurrency: bool,
+    /// Lowest adaptive per-module limit (0 is treated as 1).
+    pub adaptive_concurrency_min: usize,
     /// Graceful shutdown drain timeout in seconds.
     pub shutdown_timeout_secs: u64,
+    /// Log filter for the server, in `RUST_LOG` syntax.
+    pub log_level: Option<String>,
     /// Enable wasi:logging for WASM modules.
     pub logging_enabled: bool,
     /// Allowed hosts for outgoing HTTP requests.
@@ -77,6 +191,75 @@ pub struct HostConfig {
     /// Fuel budget per request (None = use `DEFAULT_FUEL_BUDGET`).
     /// Fuel provides deterministic CPU limiting complementing epoch-based preemption.
     pub fuel_budget: Option<u64>,
+    /// Manifest profile the configuration was loaded with (shown in health output).
+    pub profile: Option<String>,
+    /// mik.toml the configuration was loaded from, re-read on reload (see
+    /// [`crate::runtime::reload`]).
+    pub manifest_path: Option<PathBuf>,
+    /// Environment variables components see (`[env].expose`), or `None` to
+    /// inherit the process environment.
+    pub component_env: Option<BTreeMap<String, String>>,
+    /// Route aliases: `/run/<alias>/*` is served by another module.
+    pub aliases: BTreeMap<String, ModuleAlias>,
+    /// Outgoing HTTP quotas per module (`"*"` = default for other modules).
+    pub egress_quotas: BTreeMap<String, EgressQuota>,
+    /// Circuit breaker settings per module (`"*"` = default for other modules).
+    pub circuit_breakers: BTreeMap<String, CircuitBreakerPolicy>,
+    /// Faults injected per module for resilience testing (`"*"` = default
+    /// for other modules).
+    pub chaos: BTreeMap<String, ChaosPolicy>,
+    /// Limits per tenant (`"*"` = default for other tenants).
+    pub tenant_limits: BTreeMap<String, TenantLimits>,
+    /// Proxies (IPs or CIDR ranges) allowed to set `X-Forwarded-For` and
+    /// `X-Client-Cert-Subject` for `mik:request-info`.
+    pub trusted_proxies: Vec<String>,
+    /// Require a PROXY protocol (v1/v2) header on every connection.
+    pub proxy_protocol: bool,
+    /// Enable the gateway's tenant module upload and delete endpoints
+    /// (requires [`api_key`](Self::api_key)).
+    pub tenant_uploads: bool,
+    /// Key required in `X-API-Key` by the gateway's management endpoints
+    /// (None = endpoints disabled).
+    pub api_key: Option<String>,
+    /// HMAC-SHA256 key uploaded modules must be signed with (None = unsigned
+    /// uploads accepted).
+    pub module_signing_key: Option<String>,
+    /// URL notified when the handler set changes (None = no notifications).
+    pub handlers_webhook: Option<String>,
+    /// HMAC-SHA256 key handler webhook bodies are signed with (None =
+    /// unsigned).
+    pub handlers_webhook_secret: Option<String>,
+    /// Directory module requests and responses are recorded to (None = no
+    /// recording, see [`recorder`](super::recorder)).
+    pub record_dir: Option<PathBuf>,
+    /// File holding the project's last build error, served on module routes
+    /// while it exists (None = no overlay, see
+    /// [`build_overlay`](super::build_overlay)).
+    pub build_error_file: Option<PathBuf>,
+    /// Frontend dev server that paths the runtime does not serve are
+    /// forwarded to, WebSocket upgrades included (`mik dev --proxy`).
+    pub dev_proxy: Option<String>,
+    /// Serve the development dashboard at `/_dev/` (`mik dev`), with the
+    /// tail of this log file.
+    pub dev_dashboard: Option<PathBuf>,
+    /// Modules allowed to use the `mik:sql` host interface (`"*"` = all).
+    pub sql_modules: Vec<String>,
+    /// Database used by `mik:sql` (None = `sql.db` in the daemon data
+    /// directory).
+    pub sql_database: Option<PathBuf>,
+    /// Daemon services per script (`"*"` = default for other scripts).
+    pub script_capabilities: BTreeMap<String, ScriptCapabilities>,
+    /// Directories preopened per module (`"*"` = default for other modules).
+    pub preopens: BTreeMap<String, Vec<Preopen>>,
+    /// `wasi-nn` models by name: directory containing `model.onnx`.
+    pub nn_models: BTreeMap<String, PathBuf>,
+    /// Models each module may load (`"*"` = default for other modules).
+    pub nn_modules: BTreeMap<String, Vec<String>>,
+    /// OCI references of modules pulled on first request.
+    pub oci_modules: BTreeMap<String, String>,
+    /// Return script traces (`?trace=1`) to any caller (`mik run --local`).
+    /// Otherwise only `mik dev` and callers with the API key get them.
+    pub trace_scripts: bool,
 }
 
 impl Default for HostConfig {
@@ -88,27 +271,120 @@ impl Default for HostConfig {
             max_cache_bytes: constants::DEFAULT_CACHE_MB * 1024 * 1024,
             static_dir: None,
             port: constants::DEFAULT_PORT,
+            listen: Vec::new(),
             execution_timeout_secs: constants::MAX_WASM_TIMEOUT_SECS,
+            max_request_timeout_secs: 0,
+            init_timeout_secs: 0,
+            execution_retries: 0,
+            execution_retry_backoff_ms: constants::DEFAULT_EXECUTION_RETRY_BACKOFF_MS,
             memory_limit_bytes: DEFAULT_MEMORY_LIMIT_BYTES,
             max_concurrent_requests: constants::DEFAULT_MAX_CONCURRENT_REQUESTS,
             max_body_size_bytes: constants::MAX_BODY_SIZE_BYTES,
+            max_module_size_bytes: constants::MAX_MODULE_SIZE_BYTES,
             max_per_module_requests: constants::DEFAULT_MAX_PER_MODULE_REQUESTS,
+            max_per_tenant_requests: 0,
+            module_queue_depth: 0,
+            module_queue_timeout_ms: constants::DEFAULT_MODULE_QUEUE_TIMEOUT_MS,
+            adaptive_concurrency: false,
+            adaptive_concurrency_min: 0,
             shutdown_timeout_secs: DEFAULT_SHUTDOWN_TIMEOUT_SECS,
+            log_level: None,
             logging_enabled: false,
             http_allowed: Vec::new(),
             scripts_dir: None,
             hot_reload: false,
             aot_cache_max_mb: 0,
             fuel_budget: None,
+            profile: None,
+            manifest_path: None,
+            component_env: None,
+            aliases: BTreeMap::new(),
+            egress_quotas: BTreeMap::new(),
+            circuit_breakers: BTreeMap::new(),
+            chaos: BTreeMap::new(),
+            tenant_limits: BTreeMap::new(),
+            trusted_proxies: Vec::new(),
+            proxy_protocol: false,
+            tenant_uploads: false,
+            api_key: None,
+            module_signing_key: None,
+            handlers_webhook: None,
+            handlers_webhook_secret: None,
+            record_dir: None,
+            build_error_file: None,
+            dev_proxy: None,
+            dev_dashboard: None,
+            sql_modules: Vec::new(),
+            sql_database: None,
+            script_capabilities: BTreeMap::new(),
+            preopens: BTreeMap::new(),
+            nn_models: BTreeMap::new(),
+            nn_modules: BTreeMap::new(),
+            oci_modules: BTreeMap::new(),
+            trace_scripts: false,
         }
     }
 }
 
 impl HostConfig {
+    /// Egress quota for `module`, falling back to the `"*"` entry.
+    pub fn egress_quota(&self, module: &str) -> Option<EgressQuota> {
+        self.egress_quotas
+            .get(module)
+            .or_else(|| self.egress_quotas.get("*"))
+            .copied()
+    }
+
+    /// Faults to inject into `module`'s requests, falling back to the `"*"` entry.
+    pub fn chaos_policy(&self, module: &str) -> Option<&ChaosPolicy> {
+        self.chaos.get(module).or_else(|| self.chaos.get("*"))
+    }
+
+    /// Limits for `tenant_id`, falling back to the `"*"` entry.
+    pub fn tenant_limits(&self, tenant_id: &str) -> TenantLimits {
+        self.tenant_limits
+            .get(tenant_id)
+            .or_else(|| self.tenant_limits.get("*"))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Whether `module` may use the `mik:sql` host interface.
+    pub fn sql_allowed(&self, module: &str) -> bool {
+        self.sql_modules.iter().any(|m| m == "*" || m == module)
+    }
+
+    /// Directories preopened for `module`, falling back to the `"*"` entry.
+    pub fn preopens(&self, module: &str) -> &[Preopen] {
+        self.preopens
+            .get(module)
+            .or_else(|| self.preopens.get("*"))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// `wasi-nn` models `module` may load, falling back to the `"*"` entry.
+    pub fn nn_models_for(&self, module: &str) -> &[String] {
+        self.nn_modules
+            .get(module)
+            .or_else(|| self.nn_modules.get("*"))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Daemon services `script` may use, falling back to the `"*"` entry.
+    pub fn script_capabilities(&self, script: &str) -> ScriptCapabilities {
+        self.script_capabilities
+            .get(script)
+            .or_else(|| self.script_capabilities.get("*"))
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Validate configuration values.
     ///
     /// Checks that all configuration values are within acceptable bounds:
     /// - `execution_timeout_secs` must be > 0 and <= 300
+    /// - `max_request_timeout_secs` must be <= 300
+    /// - `init_timeout_secs` must be <= 300
     /// - `memory_limit_bytes` must be >= 1MB and <= 4GB
     /// - `max_concurrent_requests` must be > 0
     /// - `max_per_module_requests` must not exceed `max_concurrent_requests`
@@ -146,6 +422,22 @@ impl HostConfig {
             });
         }
 
+        // Validate per-request timeout cap (0 = use execution timeout)
+        if self.max_request_timeout_secs > MAX_EXECUTION_TIMEOUT_SECS {
+            return Err(ConfigError::RequestTimeout {
+                value: self.max_request_timeout_secs,
+                reason: "must be <= 300 seconds (5 minutes)",
+            });
+        }
+
+        // Validate init hook timeout (0 = use execution timeout)
+        if self.init_timeout_secs > MAX_EXECUTION_TIMEOUT_SECS {
+            return Err(ConfigError::InitTimeout {
+                value: self.init_timeout_secs,
+                reason: "must be <= 300 seconds (5 minutes)",
+            });
+        }
+
         // Validate memory limit (must be >= 1MB and <= 4GB)
         if self.memory_limit_bytes < MIN_MEMORY_LIMIT_BYTES {
             return Err(ConfigError::MemoryLimit {
@@ -174,6 +466,62 @@ impl HostConfig {
             });
         }
 
+        // Validate trusted proxy addresses
+        if let Some(value) = self
+            .trusted_proxies
+            .iter()
+            .find(|value| TrustedProxy::parse(value).is_err())
+        {
+            return Err(ConfigError::TrustedProxy {
+                value: value.clone(),
+            });
+        }
+
+        // Validate additional listen addresses
+        if let Some(value) = self
+            .listen
+            .iter()
+            .find(|value| value.parse::<ListenAddr>().is_err())
+        {
+            return Err(ConfigError::Listen {
+                value: value.clone(),
+            });
+        }
+
+        // Validate the handler webhook URL
+        if let Some(value) = &self.handlers_webhook
+            && !reqwest::Url::parse(value)
+                .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+        {
+            return Err(ConfigError::HandlersWebhook {
+                value: value.clone(),
+            });
+        }
+
+        if let Some(value) = &self.log_level
+            && let Err(e) = tracing_subscriber::EnvFilter::try_new(value)
+        {
+            return Err(ConfigError::LogLevel {
+                value: value.clone(),
+                reason: e.to_string(),
+            });
+        }
+
+        // Validate fault injection probabilities
+        for (module, policy) in &self.chaos {
+            if let Some((field, value)) = policy
+                .probabilities()
+                .into_iter()
+                .find_map(|(field, p)| p.filter(|p| !(0.0..=1.0).contains(p)).map(|p| (field, p)))
+            {
+                return Err(ConfigError::Chaos {
+                    module: module.clone(),
+                    field,
+                    value,
+                });
+            }
+        }
+
         // Warn if modules_path doesn't exist (non-fatal)
         if !self.modules_path.exists() {
             warn!(
@@ -184,6 +532,108 @@ impl HostConfig {
 
         Ok(())
     }
+
+    /// Override settings from `MIK_<SETTING>` environment variables (see
+    /// [`ENV_SETTINGS`]), looked up with `lookup`. Empty variables are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::EnvOverride`] for the first variable whose value
+    /// does not parse as its setting's type.
+    pub fn apply_env(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> std::result::Result<(), ConfigError> {
+        for key in ENV_SETTINGS {
+            let var = env_var(key);
+            let Some(value) = lookup(&var).filter(|value| !value.trim().is_empty()) else {
+                continue;
+            };
+            self.set(key, value.trim())
+                .map_err(|expected| ConfigError::EnvOverride {
+                    var: var.clone(),
+                    value: value.clone(),
+                    expected,
+                })?;
+            info!(var = %var, "Setting overridden from environment");
+        }
+        Ok(())
+    }
+
+    /// Set the `[server]` setting `key` from its text form, or return what
+    /// was expected.
+    fn set(&mut self, key: &str, value: &str) -> std::result::Result<(), &'static str> {
+        const MB: usize = 1024 * 1024;
+        let path = || Some(PathBuf::from(value));
+        match key {
+            "port" => self.port = value.parse().map_err(|_| "a port number (0-65535)")?,
+            "listen" => self.listen = list(value),
+            "modules" => self.modules_path = PathBuf::from(value),
+            "user_modules" => self.user_modules_path = path(),
+            "static" => self.static_dir = path(),
+            "scripts" => self.scripts_dir = path(),
+            "cache_size" => self.cache_size = number(value)?,
+            "max_cache_mb" => self.max_cache_bytes = megabytes(value, MB)?,
+            "execution_timeout_secs" => self.execution_timeout_secs = number(value)?,
+            "max_request_timeout_secs" => self.max_request_timeout_secs = number(value)?,
+            "init_timeout_secs" => self.init_timeout_secs = number(value)?,
+            "execution_retries" => self.execution_retries = number(value)?,
+            "execution_retry_backoff_ms" => self.execution_retry_backoff_ms = number(value)?,
+            "memory_limit_bytes" => self.memory_limit_bytes = number(value)?,
+            "max_concurrent_requests" => self.max_concurrent_requests = number(value)?,
+            "max_body_size_mb" => self.max_body_size_bytes = megabytes(value, MB)?,
+            "max_module_size_mb" => self.max_module_size_bytes = megabytes(value, MB)?,
+            "max_per_module_requests" => self.max_per_module_requests = number(value)?,
+            "max_per_tenant_requests" => self.max_per_tenant_requests = number(value)?,
+            "module_queue_depth" => self.module_queue_depth = number(value)?,
+            "module_queue_timeout_ms" => self.module_queue_timeout_ms = number(value)?,
+            "adaptive_concurrency" => self.adaptive_concurrency = boolean(value)?,
+            "adaptive_concurrency_min" => self.adaptive_concurrency_min = number(value)?,
+            "shutdown_timeout_secs" => self.shutdown_timeout_secs = number(value)?,
+            "log_level" => self.log_level = Some(value.to_string()),
+            "logging" => self.logging_enabled = boolean(value)?,
+            "http_allowed" => self.http_allowed = list(value),
+            "trusted_proxies" => self.trusted_proxies = list(value),
+            "proxy_protocol" => self.proxy_protocol = boolean(value)?,
+            "tenant_uploads" => self.tenant_uploads = boolean(value)?,
+            "handlers_webhook" => self.handlers_webhook = Some(value.to_string()),
+            "sql_modules" => self.sql_modules = list(value),
+            _ => return Err("a known setting"),
+        }
+        Ok(())
+    }
+}
+
+/// Parse a non-negative integer setting.
+fn number<T: std::str::FromStr>(value: &str) -> std::result::Result<T, &'static str> {
+    value.parse().map_err(|_| "a non-negative integer")
+}
+
+/// Parse a size in MB into bytes.
+fn megabytes(value: &str, mb: usize) -> std::result::Result<usize, &'static str> {
+    number::<usize>(value)?
+        .checked_mul(mb)
+        .ok_or("a size in MB")
+}
+
+/// Parse a boolean setting.
+fn boolean(value: &str) -> std::result::Result<bool, &'static str> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err("true or false"),
+    }
+}
+
+/// Parse a comma-separated list setting.
+fn list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
 }
 
 #[cfg(test)]
@@ -211,6 +661,110 @@ mod tests {
         assert!(err.to_string().contains("must be greater than 0"));
     }
 
+    #[test]
+    fn test_handlers_webhook_must_be_http_url() {
+        let config = |url: &str| HostConfig {
+            handlers_webhook: Some(url.to_string()),
+            ..Default::default()
+        };
+
+        assert!(
+            config("https://gateway.internal/hooks/handlers")
+                .validate()
+                .is_ok()
+        );
+        assert!(matches!(
+            config("gateway.internal/hooks").validate().unwrap_err(),
+            ConfigError::HandlersWebhook { .. }
+        ));
+        assert!(config("ftp://gateway.internal/").validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_log_level() {
+        let mut config = HostConfig {
+            log_level: Some("mik=debug,wasmtime=warn".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.log_level = Some("mik=loud".to_string());
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::LogLevel { .. }
+        ));
+    }
+
+    #[test]
+    fn test_chaos_probability_out_of_range_is_invalid() {
+        let config = |p: f64| {
+            let mut config = HostConfig::default();
+            config.chaos.insert(
+                "payments".to_string(),
+                ChaosPolicy {
+                    latency_ms: Some(100),
+                    latency_probability: Some(0.5),
+                    drop_probability: Some(p),
+                    ..Default::default()
+                },
+            );
+            config
+        };
+
+        assert!(config(0.0).validate().is_ok());
+        assert!(config(1.0).validate().is_ok());
+        assert!(matches!(
+            config(1.5).validate().unwrap_err(),
+            ConfigError::Chaos {
+                field: "drop_probability",
+                ..
+            }
+        ));
+        assert!(config(-0.1).validate().is_err());
+        assert!(config(f64::NAN).validate().is_err());
+    }
+
+    #[test]
+    fn test_chaos_policy_falls_back_to_wildcard() {
+        let mut config = HostConfig::default();
+        assert!(config.chaos_policy("payments").is_none());
+
+        let trap = ChaosPolicy {
+            trap_probability: Some(0.1),
+            ..Default::default()
+        };
+        config.chaos.insert("*".to_string(), ChaosPolicy::default());
+        config.chaos.insert("payments".to_string(), trap);
+        assert_eq!(config.chaos_policy("payments"), Some(&trap));
+        assert_eq!(config.chaos_policy("orders"), Some(&ChaosPolicy::default()));
+    }
+
+    #[test]
+    fn test_excessive_init_timeout_is_invalid() {
+        let config = HostConfig {
+            init_timeout_secs: 301,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::InitTimeout { value: 301, .. }
+        ));
+    }
+
+    #[test]
+    fn test_excessive_request_timeout_cap_is_invalid() {
+        let config = HostConfig {
+            max_request_timeout_secs: 301,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::RequestTimeout { value: 301, .. }
+        ));
+    }
+
     #[test]
     fn test_excessive_timeout_is_invalid() {
         let config = HostConfig {
@@ -314,6 +868,19 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_invalid_listen_address() {
+        let config = HostConfig {
+            listen: vec!["unix:/run/mik.sock".to_string(), "localhost".to_string()],
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            ConfigError::Listen { value } if value == "localhost"
+        ));
+    }
+
     #[test]
     fn test_config_error_display() {
         let timeout_err = ConfigError::Timeout {
@@ -334,6 +901,59 @@ mod tests {
         assert!(concurrency_err.to_string().contains("concurrency"));
     }
 
+    #[test]
+    fn test_apply_env() {
+        let vars: BTreeMap<&str, &str> = [
+            ("MIK_MEMORY_LIMIT_BYTES", "268435456"),
+            ("MIK_MAX_BODY_SIZE_MB", " 20 "),
+            ("MIK_HTTP_ALLOWED", "api.example.com, *.internal"),
+            ("MIK_LOGGING", "1"),
+            ("MIK_PORT", ""),
+        ]
+        .into_iter()
+        .collect();
+        let mut config = HostConfig::default();
+        config
+            .apply_env(|var| vars.get(var).map(ToString::to_string))
+            .unwrap();
+
+        assert_eq!(config.memory_limit_bytes, 256 * 1024 * 1024);
+        assert_eq!(config.max_body_size_bytes, 20 * 1024 * 1024);
+        assert_eq!(config.http_allowed, ["api.example.com", "*.internal"]);
+        assert!(config.logging_enabled);
+        // Empty variables are ignored
+        assert_eq!(config.port, constants::DEFAULT_PORT);
+    }
+
+    #[test]
+    fn test_apply_env_reports_invalid_values() {
+        let mut config = HostConfig::default();
+        let err = config
+            .apply_env(|var| (var == "MIK_MEMORY_LIMIT_BYTES").then(|| "128MB".to_string()))
+            .unwrap_err();
+        assert!(
+            matches!(&err, ConfigError::EnvOverride { var, .. } if var == "MIK_MEMORY_LIMIT_BYTES")
+        );
+        assert_eq!(
+            err.to_string(),
+            "invalid MIK_MEMORY_LIMIT_BYTES='128MB': expected a non-negative integer"
+        );
+
+        let err = config
+            .apply_env(|var| (var == "MIK_PROXY_PROTOCOL").then(|| "maybe".to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("expected true or false"), "{err}");
+    }
+
+    #[test]
+    fn test_env_settings_are_all_settable() {
+        let mut config = HostConfig::default();
+        for key in ENV_SETTINGS {
+            assert_ne!(config.set(key, "1"), Err("a known setting"), "{key}");
+        }
+        assert_eq!(env_var("max_cache_mb"), "MIK_MAX_CACHE_MB");
+    }
+
     #[test]
     fn test_config_error_is_error_trait() {
         let err: Box<dyn std::error::Error> = Box::new(ConfigError::Timeout {
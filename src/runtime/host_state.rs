@@ -3,18 +3,26 @@
 //! This module provides the core state types used by the wasmtime runtime:
 //! - [`HyperCompatibleBody`]: Wrapper for HTTP body compatibility
 //! - [`HostState`]: Per-request WASI/HTTP context and resource limits
+//! - [`add_preopens`]: Directories granted to a module's WASI context
 
 use http_body_util::Full;
 use hyper::body::Bytes;
+use std::collections::BTreeMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context as TaskContext, Poll};
+use std::time::Instant;
 use tracing::{debug, warn};
 use wasmtime::component::ResourceTable;
-use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
+use wasmtime_wasi::{DirPerms, FilePerms, WasiCtx, WasiCtxBuilder, WasiCtxView, WasiView};
 use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
 
+use crate::daemon::services::sql::SqlService;
+use crate::manifest::Preopen;
+use crate::runtime::deadline::{self, DEADLINE_HEADER};
+use crate::runtime::egress::EgressAccount;
 use crate::runtime::reliability::is_http_host_allowed;
+use crate::runtime::request_info::RequestInfo;
 
 /// Wrapper around `Full<Bytes>` that produces `hyper::Error` (for wasmtime-wasi-http compatibility).
 ///
@@ -57,6 +65,49 @@ pub(crate) struct HostState {
     pub(crate) http_allowed: Arc<Vec<String>>,
     /// Memory limit for this request (bytes).
     pub(crate) memory_limit: usize,
+    /// When the execution times out; forwarded on outgoing requests.
+    pub(crate) deadline: Instant,
+    /// Verified request metadata served by `mik:request-info`.
+    pub(crate) request_info: RequestInfo,
+    /// Egress metering and quota for the module (see [`crate::runtime::egress`]).
+    pub(crate) egress: Option<EgressAccount>,
+    /// Database for `mik:sql`, set only when the module has been granted access.
+    pub(crate) sql: Option<SqlService>,
+    /// `wasi-nn` state exposing the models granted to the module.
+    #[cfg(feature = "nn")]
+    pub(crate) nn: wasmtime_wasi_nn::wit::WasiNnCtx,
+}
+
+/// Preopen `preopens` in a module's WASI context.
+///
+/// Read-only directories can be listed and read; writable ones also allow
+/// creating, writing and removing files.
+pub(crate) fn add_preopens(wasi: &mut WasiCtxBuilder, preopens: &[Preopen]) -> anyhow::Result<()> {
+    for preopen in preopens {
+        let (dir_perms, file_perms) = if preopen.writable {
+            (DirPerms::all(), FilePerms::all())
+        } else {
+            (DirPerms::READ, FilePerms::READ)
+        };
+        wasi.preopened_dir(&preopen.path, &preopen.guest, dir_perms, file_perms)
+            .map_err(|e| anyhow::anyhow!("Failed to preopen {}: {e}", preopen.path))?;
+    }
+    Ok(())
+}
+
+/// Set a module's environment: the `[env].expose` variables when configured,
+/// otherwise the process environment.
+pub(crate) fn add_env(wasi: &mut WasiCtxBuilder, vars: Option<&BTreeMap<String, String>>) {
+    match vars {
+        Some(vars) => {
+            for (name, value) in vars {
+                wasi.env(name, value);
+            }
+        },
+        None => {
+            wasi.inherit_env();
+        },
+    }
 }
 
 /// `ResourceLimiter` implementation to enforce per-request memory limits.
@@ -109,10 +160,11 @@ impl WasiHttpView for HostState {
 
     fn send_request(
         &mut self,
-        request: hyper::Request<wasmtime_wasi_http::body::HyperOutgoingBody>,
-        config: wasmtime_wasi_http::types::OutgoingRequestConfig,
+        mut request: hyper::Request<wasmtime_wasi_http::body::HyperOutgoingBody>,
+        mut config: wasmtime_wasi_http::types::OutgoingRequestConfig,
     ) -> wasmtime_wasi_http::HttpResult<wasmtime_wasi_http::types::HostFutureIncomingResponse> {
         use wasmtime_wasi_http::bindings::http::types::ErrorCode;
+        use wasmtime_wasi_http::types::{HostFutureIncomingResponse, default_send_request_handler};
 
         // If no allowed hosts configured, deny all outgoing requests
         if self.http_allowed.is_empty() {
@@ -131,9 +183,43 @@ impl WasiHttpView for HostState {
 
         debug!("Outgoing HTTP allowed: {}", host);
 
-        // Delegate to default implementation
-        Ok(wasmtime_wasi_http::types::default_send_request(
-            request, config,
-        ))
+        // Forward what is left of the deadline, and don't wait past it
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            warn!("Outgoing HTTP skipped: execution deadline exceeded");
+            return Err(ErrorCode::ConnectionTimeout.into());
+        }
+        let budget =
+            deadline::caller_budget(request.headers()).map_or(remaining, |b| b.min(remaining));
+        request
+            .headers_mut()
+            .insert(DEADLINE_HEADER, deadline::deadline_header(budget));
+        config.connect_timeout = config.connect_timeout.min(remaining);
+        config.first_byte_timeout = config.first_byte_timeout.min(remaining);
+
+        let Some(egress) = self.egress.clone() else {
+            return Ok(wasmtime_wasi_http::types::default_send_request(
+                request, config,
+            ));
+        };
+
+        if let Err(exceeded) = egress.check() {
+            warn!(
+                module = %egress.module,
+                window = exceeded.window,
+                "Outgoing HTTP blocked: egress quota exceeded"
+            );
+            let response = exceeded.into_response(&egress.module, config.between_bytes_timeout);
+            return Ok(HostFutureIncomingResponse::ready(Ok(Ok(response))));
+        }
+
+        // Same as the default implementation, with both bodies metered
+        let request = egress.meter_request(request);
+        let handle = wasmtime_wasi::runtime::spawn(async move {
+            Ok(default_send_request_handler(request, config)
+                .await
+                .map(|response| egress.meter_response(response)))
+        });
+        Ok(HostFutureIncomingResponse::pending(handle))
     }
 }
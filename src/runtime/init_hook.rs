@@ -0,0 +1,204 @@
+//! Optional `init`/`warmup` export run once after a component is loaded.
+//!
+//! A component may export a root-level function without parameters named
+//! `init` (preferred) or `warmup`, returning nothing or `result<_, string>`:
+//!
+//! ```wit
+//! world handler {
+//!     export wasi:http/incoming-handler@0.2.0;
+//!     export init: func() -> result<_, string>;
+//! }
+//! ```
+//!
+//! The hook runs before the component is cached, on a fresh instance with the
+//! usual fuel and memory limits and its own `init_timeout_secs`. An error,
+//! trap or timeout fails the load (`ModuleLoadFailed`), so the module is not
+//! served and the next request tries again. Modules are loaded again after
+//! cache eviction and restarts (including hot reload), which runs the hook
+//! again.
+
+use crate::runtime::SharedState;
+use crate::runtime::error::Error;
+use crate::runtime::host_state::add_env;
+use crate::runtime::request_info::RequestInfo;
+use crate::runtime::wasm_executor::create_store;
+use wasmtime::Engine;
+use wasmtime::component::types::ComponentItem;
+use wasmtime::component::{Component, Val};
+use wasmtime_wasi::WasiCtxBuilder;
+
+/// Export names recognized as init hooks, in order of preference.
+const INIT_EXPORTS: [&str; 2] = ["init", "warmup"];
+
+/// The init hook `component` exports, if any.
+pub(crate) fn detect(engine: &Engine, component: &Component) -> Option<&'static str> {
+    let ty = component.component_type();
+    INIT_EXPORTS.into_iter().find(|name| {
+        ty.exports(engine).any(|(export, item)| {
+            export == *name
+                && matches!(&item, ComponentItem::ComponentFunc(func) if func.params().len() == 0)
+        })
+    })
+}
+
+/// Run `module`'s init hook `export`.
+pub(crate) async fn run(
+    shared: &SharedState,
+    component: &Component,
+    module: &str,
+    export: &str,
+) -> Result<(), Error> {
+    let timeout = shared.init_timeout;
+    let started = std::time::Instant::now();
+    let result = tokio::time::timeout(timeout, call(shared, component, module, export))
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {timeout:?}")));
+
+    match result {
+        Ok(()) => {
+            tracing::info!(
+                module = module,
+                duration_ms = started.elapsed().as_millis() as u64,
+                "{export}() completed"
+            );
+            Ok(())
+        },
+        Err(reason) => {
+            tracing::warn!(module = module, "{export}() failed: {reason}");
+            Err(Error::module_load_failed(
+                module,
+                format!("{export}() failed: {reason}"),
+            ))
+        },
+    }
+}
+
+async fn call(
+    shared: &SharedState,
+    component: &Component,
+    module: &str,
+    export: &str,
+) -> Result<(), String> {
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.inherit_stdio();
+    add_env(&mut wasi, shared.config.component_env.as_ref());
+    let mut store = create_store(
+        shared,
+        Some(module),
+        wasi,
+        RequestInfo::default(),
+        shared.init_timeout,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let instance = shared
+        .linker
+        .instantiate_async(&mut store, component)
+        .await
+        .map_err(|e| format!("instantiation failed: {e:#}"))?;
+    let func = instance
+        .get_func(&mut store, export)
+        .ok_or_else(|| format!("export '{export}' not found"))?;
+    let mut results = vec![Val::Bool(false); func.ty(&store).results().len()];
+    func.call_async(&mut store, &[], &mut results)
+        .await
+        .map_err(|e| format!("{e:#}"))?;
+
+    match results.first() {
+        Some(Val::Result(Err(error))) => Err(match error.as_deref() {
+            Some(Val::String(message)) => message.clone(),
+            _ => "returned an error".to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `init` returning `result<_, string>`: `err("config missing")`.
+    const INIT_ERROR: &str = r#"
+        (component
+            (core module $m
+                (memory (export "mem") 1)
+                (data (i32.const 16) "\01\00\00\00\20\00\00\00\0e\00\00\00")
+                (data (i32.const 32) "config missing")
+                (func (export "init") (result i32) i32.const 16))
+            (core instance $i (instantiate $m))
+            (func (export "init") (result (result (error string)))
+                (canon lift (core func $i "init") (memory $i "mem"))))
+    "#;
+
+    fn component(shared: &SharedState, wat: &str) -> Component {
+        Component::new(&shared.engine, wat).unwrap()
+    }
+
+    fn runtime() -> Option<crate::runtime::Runtime> {
+        let modules =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        modules.join("echo.wasm").exists().then(|| {
+            crate::runtime::Runtime::builder()
+                .modules_dir(modules)
+                .build()
+                .unwrap()
+        })
+    }
+
+    #[test]
+    fn test_detect_init_export() {
+        let Some(runtime) = runtime() else { return };
+        let shared = &runtime.shared;
+
+        let warmup = component(
+            shared,
+            r#"(component
+                (core module $m (func (export "f")))
+                (core instance $i (instantiate $m))
+                (func (export "warmup") (canon lift (core func $i "f"))))"#,
+        );
+        assert_eq!(detect(&shared.engine, &warmup), Some("warmup"));
+        assert_eq!(
+            detect(&shared.engine, &component(shared, INIT_ERROR)),
+            Some("init")
+        );
+        assert_eq!(
+            detect(&shared.engine, &component(shared, "(component)")),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_init_hook_results() {
+        let Some(runtime) = runtime() else { return };
+        let shared = &runtime.shared;
+
+        let ok = component(
+            shared,
+            r#"(component
+                (core module $m (func (export "f")))
+                (core instance $i (instantiate $m))
+                (func (export "init") (canon lift (core func $i "f"))))"#,
+        );
+        assert!(run(shared, &ok, "ok", "init").await.is_ok());
+
+        let err = run(shared, &component(shared, INIT_ERROR), "cfg", "init")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ModuleLoadFailed { .. }));
+        assert!(
+            err.to_string().contains("init() failed: config missing"),
+            "{err}"
+        );
+
+        let trap = component(
+            shared,
+            r#"(component
+                (core module $m (func (export "f") unreachable))
+                (core instance $i (instantiate $m))
+                (func (export "warmup") (canon lift (core func $i "f"))))"#,
+        );
+        let err = run(shared, &trap, "trap", "warmup").await.unwrap_err();
+        assert!(err.to_string().contains("warmup() failed"), "{err}");
+    }
+}
@@ -0,0 +1,178 @@
+//! Background jobs: running a component without an HTTP request.
+//!
+//! [`Runtime::invoke_job`](crate::runtime::Runtime::invoke_job) runs one of
+//! two exports, so the same component can serve HTTP and do scheduled or
+//! queued work:
+//!
+//! - `mik:job/handler` (see `wit/job.wit`): `run(payload)` returns the output
+//!   bytes or an error message.
+//! - `wasi:cli/run`: the payload is the component's stdin and its stdout is
+//!   the output. A non-zero exit fails the job.
+//!
+//! Jobs run under the same fuel, memory, and timeout limits as HTTP requests
+//! and go through the module's circuit breaker and concurrency limit.
+
+use crate::runtime::SharedState;
+use crate::runtime::host_state::add_env;
+use crate::runtime::request_info::RequestInfo;
+use crate::runtime::wasm_executor::create_store;
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use wasmtime::component::Component;
+use wasmtime_wasi::WasiCtxBuilder;
+use wasmtime_wasi::p2::bindings::Command;
+use wasmtime_wasi::p2::pipe::{MemoryInputPipe, MemoryOutputPipe};
+
+wasmtime::component::bindgen!({
+    path: "wit/job.wit",
+    world: "job",
+    exports: { default: async },
+});
+
+/// Export a job runs through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum JobExport {
+    /// `mik:job/handler.run`
+    Handler,
+    /// `wasi:cli/run.run`
+    Command,
+}
+
+impl JobExport {
+    /// Pick the export from a component's export names (`mik:job` first).
+    pub(crate) fn detect<'a>(exports: impl Iterator<Item = &'a str>) -> Option<Self> {
+        let mut command = false;
+        for name in exports {
+            if name.starts_with("mik:job/handler@0.1") {
+                return Some(Self::Handler);
+            }
+            command |= name.starts_with("wasi:cli/run@0.2");
+        }
+        command.then_some(Self::Command)
+    }
+}
+
+/// Run `module` as a job with `payload`, returning its output.
+pub(crate) async fn invoke_job(
+    shared: &Arc<SharedState>,
+    module: &str,
+    payload: Vec<u8>,
+) -> Result<Vec<u8>> {
+    shared
+        .circuit_breaker
+        .check_request(module)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let _permit = shared
+        .get_module_semaphore(module)
+        .acquire_owned()
+        .await
+        .context("Module semaphore closed")?;
+
+    let cached = shared.load_cached(module).await?;
+    let export = cached
+        .exports
+        .require_job(module)
+        .map_err(crate::runtime::error::Error::into_anyhow)?;
+
+    let result = run(shared, &cached.component, module, export, payload).await;
+    match &result {
+        Ok(_) => shared.circuit_breaker.record_success(module),
+        Err(_) => shared.circuit_breaker.record_failure(module),
+    }
+    result
+}
+
+async fn run(
+    shared: &SharedState,
+    component: &Component,
+    module: &str,
+    export: JobExport,
+    payload: Vec<u8>,
+) -> Result<Vec<u8>> {
+    let timeout = shared.execution_timeout;
+    let stdout = MemoryOutputPipe::new(shared.max_body_size_bytes);
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.inherit_stderr();
+    add_env(&mut wasi, shared.config.component_env.as_ref());
+    if export == JobExport::Command {
+        wasi.stdin(MemoryInputPipe::new(payload.clone()))
+            .stdout(stdout.clone());
+    } else {
+        wasi.inherit_stdout();
+    }
+    let mut store = create_store(shared, Some(module), wasi, RequestInfo::default(), timeout)?;
+
+    let job = async {
+        match export {
+            JobExport::Handler => {
+                let job = Job::instantiate_async(&mut store, component, &shared.linker)
+                    .await
+                    .context("Failed to instantiate job")?;
+                job.mik_job_handler()
+                    .call_run(&mut store, &payload)
+                    .await
+                    .context("Job call failed")?
+                    .map_err(|e| anyhow::anyhow!("Job '{module}' failed: {e}"))
+            },
+            JobExport::Command => {
+                let command = Command::instantiate_async(&mut store, component, &shared.linker)
+                    .await
+                    .context("Failed to instantiate command")?;
+                command
+                    .wasi_cli_run()
+                    .call_run(&mut store)
+                    .await
+                    .context("Job call failed")?
+                    .map_err(|()| anyhow::anyhow!("Job '{module}' exited with an error"))?;
+                Ok(stdout.contents().to_vec())
+            },
+        }
+    };
+    tokio::time::timeout(timeout, job)
+        .await
+        .map_err(|_| anyhow::anyhow!("Job timed out after {timeout:?}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_export_detection() {
+        assert_eq!(
+            JobExport::detect(["wasi:cli/run@0.2.3", "mik:job/handler@0.1.0"].into_iter()),
+            Some(JobExport::Handler)
+        );
+        assert_eq!(
+            JobExport::detect(["wasi:cli/run@0.2.0"].into_iter()),
+            Some(JobExport::Command)
+        );
+        assert_eq!(
+            JobExport::detect(["wasi:http/incoming-handler@0.2.0"].into_iter()),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invoke_job_requires_job_export() {
+        let modules =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        if !modules.join("echo.wasm").exists() {
+            return;
+        }
+        let runtime = crate::runtime::Runtime::builder()
+            .modules_dir(modules)
+            .build()
+            .unwrap();
+
+        let err = runtime
+            .invoke_job("echo", b"{}".to_vec())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("exports neither mik:job/handler nor wasi:cli/run"),
+            "{err}"
+        );
+    }
+}
@@ -331,7 +331,7 @@ impl fmt::Display for BackendState {
 /// HTTP backend that forwards requests over the network.
 #[derive(Debug)]
 pub struct HttpBackend {
-    /// Address of the backend (e.g., "127.0.0.1:3001").
+    /// Address of the backend (e.g., "127.0.0.1:3001" or "https://10.0.0.5:3001").
     address: String,
     /// Weight for weighted load balancing (higher = more traffic).
     weight: u32,
@@ -405,8 +405,45 @@ impl HttpBackend {
     }
 
     /// Get the full URL for a given path.
+    ///
+    /// Addresses without a scheme are reached over plain HTTP.
     pub fn url(&self, path: &str) -> String {
-        format!("http://{}{}", self.address, path)
+        if self.address.contains("://") {
+            format!("{}{}", self.address.trim_end_matches('/'), path)
+        } else {
+            format!("http://{}{}", self.address, path)
+        }
+    }
+
+    /// Check if the backend is reached over TLS (`https://` address).
+    pub fn is_tls(&self) -> bool {
+        self.address.starts_with("https://")
+    }
+
+    /// Get the `host:port` of the backend, without scheme or path.
+    ///
+    /// Falls back to the scheme's default port when none is given.
+    pub fn authority(&self) -> String {
+        let (rest, default_port) = if let Some(rest) = self.address.strip_prefix("https://") {
+            (rest, 443)
+        } else {
+            (
+                self.address
+                    .strip_prefix("http://")
+                    .unwrap_or(&self.address),
+                80,
+            )
+        };
+        let authority = rest.split('/').next().unwrap_or(rest);
+
+        let has_port = authority
+            .rsplit_once(':')
+            .is_some_and(|(_, port)| !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()));
+        if has_port {
+            authority.to_string()
+        } else {
+            format!("{authority}:{default_port}")
+        }
     }
 
     /// Check if the backend is healthy.
@@ -884,6 +921,24 @@ mod tests {
         let backend = HttpBackend::new("127.0.0.1:3001".to_string());
         assert_eq!(backend.url("/health"), "http://127.0.0.1:3001/health");
         assert_eq!(backend.url("/run/echo/"), "http://127.0.0.1:3001/run/echo/");
+        assert!(!backend.is_tls());
+    }
+
+    #[test]
+    fn test_https_backend_url_and_authority() {
+        let backend = HttpBackend::new("https://10.0.0.5:3001/".to_string());
+        assert!(backend.is_tls());
+        assert_eq!(backend.url("/health"), "https://10.0.0.5:3001/health");
+        assert_eq!(backend.authority(), "10.0.0.5:3001");
+
+        let backend = HttpBackend::new("https://workers.internal".to_string());
+        assert_eq!(backend.authority(), "workers.internal:443");
+
+        let backend = HttpBackend::new("http://[::1]".to_string());
+        assert_eq!(backend.authority(), "[::1]:80");
+
+        let backend = HttpBackend::new("127.0.0.1:3001".to_string());
+        assert_eq!(backend.authority(), "127.0.0.1:3001");
     }
 
     #[test]
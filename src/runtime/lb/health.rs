@@ -24,6 +24,7 @@ use anyhow::{Context, Result};
 
 use super::backend::Backend;
 use super::metrics::LbMetrics;
+use super::tls::BackendTlsConfig;
 
 /// Type of health check to perform.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -64,7 +65,7 @@ impl Default for HealthCheckConfig {
     fn default() -> Self {
         Self {
             interval: Duration::from_secs(5),
-            timeout: Duration::from_millis(2000),
+            timeout: Duration::from_secs(2),
             check_type: HealthCheckType::default(),
             unhealthy_threshold: 3,
             healthy_threshold: 2,
@@ -115,15 +116,34 @@ impl HealthCheck {
     ///
     /// Returns an error if the HTTP client cannot be created (e.g., TLS configuration issues).
     pub(super) fn new(config: HealthCheckConfig) -> Result<Self> {
+        Self::with_tls(config, None)
+    }
+
+    /// Create a health check service that reaches `https://` backends with
+    /// the given TLS settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created (e.g., TLS configuration issues).
+    pub(super) fn with_tls(
+        config: HealthCheckConfig,
+        tls: Option<&BackendTlsConfig>,
+    ) -> Result<Self> {
         // Only create HTTP client if we're doing HTTP health checks
         let client = match &config.check_type {
-            HealthCheckType::Http { .. } => Some(
-                reqwest::Client::builder()
+            HealthCheckType::Http { .. } => {
+                let mut builder = reqwest::Client::builder()
                     .timeout(config.timeout)
-                    .pool_max_idle_per_host(1)
-                    .build()
-                    .context("failed to create HTTP client - check TLS configuration")?,
-            ),
+                    .pool_max_idle_per_host(1);
+                if let Some(tls) = tls {
+                    builder = tls.apply(builder)?;
+                }
+                Some(
+                    builder
+                        .build()
+                        .context("failed to create HTTP client - check TLS configuration")?,
+                )
+            },
             HealthCheckType::Tcp => None,
         };
 
@@ -150,7 +170,7 @@ impl HealthCheck {
                             false
                         }
                     },
-                    HealthCheckType::Tcp => self.check_tcp_address(http_backend.address()).await,
+                    HealthCheckType::Tcp => self.check_tcp_address(&http_backend.authority()).await,
                 }
             },
             Backend::Runtime(runtime_backend) => {
@@ -218,12 +238,20 @@ impl HealthCheck {
 /// that supports both HTTP and Runtime backends.
 pub struct HealthChecker {
     config: HealthCheckConfig,
+    tls: Option<BackendTlsConfig>,
 }
 
 impl HealthChecker {
     /// Create a new health checker with the given configuration.
     pub fn new(config: HealthCheckConfig) -> Self {
-        Self { config }
+        Self { config, tls: None }
+    }
+
+    /// Use the given TLS settings when checking `https://` backends.
+    #[must_use]
+    pub fn with_backend_tls(mut self, tls: Option<BackendTlsConfig>) -> Self {
+        self.tls = tls;
+        self
     }
 
     /// Run continuous health checks for all backends.
@@ -231,7 +259,7 @@ impl HealthChecker {
     /// This method runs forever, performing health checks at the configured interval.
     /// It updates backend health status and metrics.
     pub async fn run(&self, backends: Arc<RwLock<Vec<Backend>>>, metrics: LbMetrics) {
-        run_health_checks_internal(backends, self.config.clone(), metrics).await;
+        run_health_checks_internal(backends, self.config.clone(), self.tls.clone(), metrics).await;
     }
 
     /// Get the health check configuration.
@@ -244,9 +272,10 @@ impl HealthChecker {
 async fn run_health_checks_internal(
     backends: Arc<RwLock<Vec<Backend>>>,
     config: HealthCheckConfig,
+    tls: Option<BackendTlsConfig>,
     metrics: LbMetrics,
 ) {
-    let health_check = HealthCheck::new(config.clone())
+    let health_check = HealthCheck::with_tls(config.clone(), tls.as_ref())
         .expect("failed to create health check service - check TLS configuration");
     let mut interval = tokio::time::interval(config.interval);
 
@@ -336,7 +365,7 @@ mod tests {
     fn test_health_check_config_default() {
         let config = HealthCheckConfig::default();
         assert_eq!(config.interval, Duration::from_secs(5));
-        assert_eq!(config.timeout, Duration::from_millis(2000));
+        assert_eq!(config.timeout, Duration::from_secs(2));
         assert_eq!(
             config.check_type,
             HealthCheckType::Http {
@@ -13,6 +13,10 @@
 //! - `mik_lb_backends_healthy` - Number of healthy backends
 //! - `mik_lb_backends_total` - Total number of backends
 //! - `mik_lb_active_connections` - Active connections per backend (labels: backend)
+//!
+//! ## Mirror Metrics
+//! - `mik_lb_mirror_requests_total` - Mirrored requests (labels: target, status)
+//! - `mik_lb_mirror_duration_seconds` - Mirrored request duration (labels: target)
 
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use std::sync::OnceLock;
@@ -43,6 +47,16 @@ pub fn register_lb_metrics() {
             "mik_lb_active_connections",
             "Active connections per backend"
         );
+
+        // Mirror metrics
+        describe_counter!(
+            "mik_lb_mirror_requests_total",
+            "Total number of requests mirrored to a shadow backend"
+        );
+        describe_histogram!(
+            "mik_lb_mirror_duration_seconds",
+            "Mirrored request duration in seconds"
+        );
     });
 }
 
@@ -121,6 +135,29 @@ impl LbMetrics {
         self.record_request(backend, "failure", duration_secs);
     }
 
+    /// Records a request mirrored to a shadow backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The shadow backend address
+    /// * `status` - The mirror outcome ("success" or "failure")
+    /// * `duration_secs` - The mirrored request duration in seconds
+    #[allow(clippy::unused_self)]
+    pub fn record_mirror(&self, target: &str, status: &str, duration_secs: f64) {
+        counter!(
+            "mik_lb_mirror_requests_total",
+            "target" => target.to_string(),
+            "status" => status.to_string()
+        )
+        .increment(1);
+
+        histogram!(
+            "mik_lb_mirror_duration_seconds",
+            "target" => target.to_string()
+        )
+        .record(duration_secs);
+    }
+
     /// Sets the number of healthy backends.
     ///
     /// # Arguments
@@ -221,6 +258,14 @@ mod tests {
         metrics.record_failure("127.0.0.1:3001", 0.5);
     }
 
+    #[test]
+    fn test_record_mirror() {
+        let metrics = LbMetrics::new();
+
+        metrics.record_mirror("127.0.0.1:4001", "success", 0.1);
+        metrics.record_mirror("127.0.0.1:4001", "failure", 0.3);
+    }
+
     #[test]
     fn test_set_backends_gauges() {
         let metrics = LbMetrics::new();
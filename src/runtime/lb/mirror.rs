@@ -0,0 +1,201 @@
+//! Request mirroring (shadow traffic) for the load balancer.
+//!
+//! Duplicates a percentage of incoming requests to a shadow backend. Shadow
+//! responses are discarded and shadow failures never affect the client, so a
+//! new worker build can be exercised with production traffic safely.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mik::runtime::lb::{LoadBalancer, MirrorConfig};
+//!
+//! let lb = LoadBalancer::builder()
+//!     .backend("127.0.0.1:3001")
+//!     .mirror(Some(MirrorConfig::new("127.0.0.1:4001", 10))) // 10% of traffic
+//!     .build()?;
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+use super::backend::HttpBackend;
+use super::metrics::LbMetrics;
+use crate::runtime::request::Request;
+
+/// Configuration for mirroring requests to a shadow backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorConfig {
+    /// Address of the shadow backend (e.g., "127.0.0.1:4001").
+    pub target: String,
+    /// Percentage of requests to mirror (0-100).
+    pub percent: u8,
+}
+
+impl MirrorConfig {
+    /// Create a mirror configuration. `percent` is clamped to 100.
+    pub fn new(target: impl Into<String>, percent: u8) -> Self {
+        Self {
+            target: target.into(),
+            percent: percent.min(100),
+        }
+    }
+}
+
+/// Shadow backend that receives a sampled copy of proxied requests.
+///
+/// Sampling is deterministic: with `percent = 25`, exactly one in every four
+/// requests is mirrored, spread evenly rather than in bursts.
+#[derive(Debug)]
+pub(crate) struct Mirror {
+    /// Shadow backend receiving mirrored requests.
+    backend: HttpBackend,
+    /// Percentage of requests to mirror (0-100).
+    percent: u64,
+    /// Number of requests seen, used for sampling.
+    seen: AtomicU64,
+}
+
+impl Mirror {
+    /// Create a mirror from its configuration.
+    pub(crate) fn new(config: MirrorConfig) -> Self {
+        Self {
+            backend: HttpBackend::new(config.target),
+            percent: u64::from(config.percent.min(100)),
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    /// Get the shadow backend address.
+    pub(crate) fn target(&self) -> &str {
+        self.backend.address()
+    }
+
+    /// Decide whether the next request should be mirrored.
+    ///
+    /// Mirrors request `n` when `floor((n + 1) * p / 100)` advances past
+    /// `floor(n * p / 100)`, which yields exactly `p` mirrors per 100 requests.
+    pub(crate) fn should_mirror(&self) -> bool {
+        if self.percent == 0 {
+            return false;
+        }
+        let n = self.seen.fetch_add(1, Ordering::Relaxed);
+        (n + 1) * self.percent / 100 > n * self.percent / 100
+    }
+
+    /// Send a copy of the request to the shadow backend in the background.
+    ///
+    /// The response is discarded; only the outcome is recorded in metrics.
+    pub(crate) fn dispatch(
+        self: &Arc<Self>,
+        req: &Request,
+        client: &reqwest::Client,
+        timeout: Duration,
+        metrics: &LbMetrics,
+    ) {
+        let mirror = Arc::clone(self);
+        let req = req.clone();
+        let client = client.clone();
+        let metrics = metrics.clone();
+
+        tokio::spawn(async move {
+            let start = Instant::now();
+            let result = mirror.backend.forward(&req, &client, timeout).await;
+            let duration = start.elapsed().as_secs_f64();
+
+            match result {
+                Ok(response) => {
+                    metrics.record_mirror(mirror.target(), "success", duration);
+                    debug!(
+                        shadow = %mirror.target(),
+                        status = response.status,
+                        "Mirrored request completed"
+                    );
+                },
+                Err(e) => {
+                    metrics.record_mirror(mirror.target(), "failure", duration);
+                    debug!(
+                        shadow = %mirror.target(),
+                        error = %e,
+                        "Mirrored request failed"
+                    );
+                },
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirror_config_clamps_percent() {
+        let config = MirrorConfig::new("127.0.0.1:4001", 150);
+        assert_eq!(config.percent, 100);
+    }
+
+    #[test]
+    fn test_should_mirror_zero_percent() {
+        let mirror = Mirror::new(MirrorConfig::new("127.0.0.1:4001", 0));
+        assert!((0..100).all(|_| !mirror.should_mirror()));
+    }
+
+    #[test]
+    fn test_should_mirror_full_percent() {
+        let mirror = Mirror::new(MirrorConfig::new("127.0.0.1:4001", 100));
+        assert!((0..100).all(|_| mirror.should_mirror()));
+    }
+
+    #[test]
+    fn test_should_mirror_exact_ratio() {
+        let mirror = Mirror::new(MirrorConfig::new("127.0.0.1:4001", 25));
+        let mirrored: Vec<bool> = (0..8).map(|_| mirror.should_mirror()).collect();
+        assert_eq!(
+            mirrored,
+            vec![false, false, false, true, false, false, false, true]
+        );
+
+        let mirror = Mirror::new(MirrorConfig::new("127.0.0.1:4001", 10));
+        let count = (0..1000).filter(|_| mirror.should_mirror()).count();
+        assert_eq!(count, 100);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_sends_copy_to_shadow() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = tokio::sync::oneshot::channel::<String>();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            let _ = tx.send(String::from_utf8_lossy(&buf[..n]).to_string());
+        });
+
+        let mirror = Arc::new(Mirror::new(MirrorConfig::new(addr.to_string(), 100)));
+        let req = Request::new("POST", "/run/orders").with_body(b"{}".to_vec());
+        mirror.dispatch(
+            &req,
+            &reqwest::Client::new(),
+            Duration::from_secs(2),
+            &LbMetrics::new(),
+        );
+
+        let received = tokio::time::timeout(Duration::from_secs(2), rx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(received.starts_with("POST /run/orders"));
+    }
+}
@@ -63,20 +63,25 @@ pub mod backend;
 mod circuit_breaker;
 pub mod health;
 pub mod metrics;
+pub mod mirror;
 pub mod proxy;
 pub mod selection;
 pub mod server;
+pub mod tls;
 
 // Re-export main types for convenience
 pub use backend::{
     Backend, BackendHealth, BackendType, HttpBackend, RuntimeBackend, RuntimeHandler,
 };
 pub use health::{HealthCheckConfig, HealthCheckType, HealthChecker};
+pub use mirror::MirrorConfig;
 pub use proxy::{Proxy, ProxyBuilder, Request, Response};
 pub use selection::{LoadBalanceStrategy, RoundRobin, Selection};
 pub use server::{LoadBalancer, LoadBalancerBuilder};
+pub use tls::BackendTlsConfig;
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -92,7 +97,7 @@ const DEFAULT_LISTEN_ADDR: &str = "0.0.0.0:3000";
 pub struct LoadBalancerConfig {
     /// Address to listen on.
     pub listen_addr: SocketAddr,
-    /// Backend addresses.
+    /// Backend addresses (`host:port`, or `https://host:port` for TLS).
     pub backends: Vec<String>,
     /// Health check configuration.
     pub health_check: HealthCheckConfig,
@@ -107,6 +112,10 @@ pub struct LoadBalancerConfig {
     /// Use HTTP/2 only (with prior knowledge) for backend connections.
     /// Enable this when all backends support HTTP/2 for better performance.
     pub http2_only: bool,
+    /// Shadow traffic configuration (disabled when `None`).
+    pub mirror: Option<MirrorConfig>,
+    /// TLS options for `https://` backends (platform defaults when `None`).
+    pub backend_tls: Option<BackendTlsConfig>,
 }
 
 impl Default for LoadBalancerConfig {
@@ -124,6 +133,8 @@ impl Default for LoadBalancerConfig {
             pool_idle_timeout_secs: 90,
             tcp_keepalive_secs: 60,
             http2_only: false,
+            mirror: None,
+            backend_tls: None,
         }
     }
 }
@@ -183,6 +194,11 @@ impl LoadBalancerConfig {
             pool_idle_timeout_secs: lb_config.pool_idle_timeout_secs,
             tcp_keepalive_secs: lb_config.tcp_keepalive_secs,
             http2_only: lb_config.http2_only,
+            mirror: lb_config
+                .mirror_backend
+                .as_ref()
+                .map(|target| MirrorConfig::new(target.clone(), lb_config.mirror_percent)),
+            backend_tls: backend_tls_from_manifest(lb_config),
         }
     }
 
@@ -195,7 +211,9 @@ impl LoadBalancerConfig {
             .max_connections_per_backend(self.max_connections_per_backend)
             .pool_idle_timeout(Duration::from_secs(self.pool_idle_timeout_secs))
             .tcp_keepalive(Duration::from_secs(self.tcp_keepalive_secs))
-            .http2_only(self.http2_only);
+            .http2_only(self.http2_only)
+            .mirror(self.mirror)
+            .backend_tls(self.backend_tls);
 
         for backend in self.backends {
             builder = builder.backend(backend);
@@ -218,6 +236,16 @@ impl LoadBalancerConfig {
     }
 }
 
+/// Collect the manifest's backend TLS paths, if any are set.
+fn backend_tls_from_manifest(lb_config: &LbConfig) -> Option<BackendTlsConfig> {
+    let tls = BackendTlsConfig {
+        ca_cert: lb_config.backend_ca_cert.as_ref().map(PathBuf::from),
+        client_cert: lb_config.backend_client_cert.as_ref().map(PathBuf::from),
+        client_key: lb_config.backend_client_key.as_ref().map(PathBuf::from),
+    };
+    (tls != BackendTlsConfig::default()).then_some(tls)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,6 +273,11 @@ mod tests {
             pool_idle_timeout_secs: 120,
             tcp_keepalive_secs: 30,
             http2_only: true,
+            mirror_backend: Some("127.0.0.1:4001".to_string()),
+            mirror_percent: 20,
+            backend_ca_cert: Some("/etc/mik/ca.pem".to_string()),
+            backend_client_cert: Some("/etc/mik/lb.pem".to_string()),
+            backend_client_key: Some("/etc/mik/lb-key.pem".to_string()),
         };
 
         let config = LoadBalancerConfig::from_manifest(
@@ -260,10 +293,19 @@ mod tests {
         assert_eq!(config.pool_idle_timeout_secs, 120);
         assert_eq!(config.tcp_keepalive_secs, 30);
         assert!(config.http2_only);
+        assert_eq!(config.mirror, Some(MirrorConfig::new("127.0.0.1:4001", 20)));
+        assert_eq!(
+            config.backend_tls,
+            Some(
+                BackendTlsConfig::default()
+                    .ca_cert("/etc/mik/ca.pem")
+                    .client_identity("/etc/mik/lb.pem", "/etc/mik/lb-key.pem")
+            )
+        );
 
         // Check health check config
-        assert_eq!(config.health_check.interval, Duration::from_millis(10000));
-        assert_eq!(config.health_check.timeout, Duration::from_millis(3000));
+        assert_eq!(config.health_check.interval, Duration::from_secs(10));
+        assert_eq!(config.health_check.timeout, Duration::from_secs(3));
         assert_eq!(config.health_check.path(), "/healthz");
         assert_eq!(config.health_check.unhealthy_threshold, 5);
         assert_eq!(config.health_check.healthy_threshold, 3);
@@ -281,8 +323,10 @@ mod tests {
         assert_eq!(config.pool_idle_timeout_secs, 90);
         assert_eq!(config.tcp_keepalive_secs, 60);
         assert!(!config.http2_only);
-        assert_eq!(config.health_check.interval, Duration::from_millis(5000));
-        assert_eq!(config.health_check.timeout, Duration::from_millis(2000));
+        assert!(config.mirror.is_none());
+        assert!(config.backend_tls.is_none());
+        assert_eq!(config.health_check.interval, Duration::from_secs(5));
+        assert_eq!(config.health_check.timeout, Duration::from_secs(2));
         assert_eq!(config.health_check.path(), "/health");
         assert_eq!(config.health_check.unhealthy_threshold, 3);
         assert_eq!(config.health_check.healthy_threshold, 2);
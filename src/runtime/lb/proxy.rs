@@ -57,7 +57,9 @@ use tracing::{debug, error, warn};
 use super::backend::{Backend, BackendHealth, HttpBackend};
 use super::health::{HealthCheckConfig, HealthChecker};
 use super::metrics::LbMetrics;
+use super::mirror::{Mirror, MirrorConfig};
 use super::selection::{LoadBalanceStrategy, Selection};
+use super::tls::BackendTlsConfig;
 
 // Import Request/Response types from runtime
 pub use crate::runtime::request::{Request, Response};
@@ -85,6 +87,8 @@ pub struct Proxy {
     http_client: reqwest::Client,
     /// Metrics collector.
     metrics: LbMetrics,
+    /// Optional shadow backend receiving mirrored requests.
+    mirror: Option<Arc<Mirror>>,
 }
 
 impl std::fmt::Debug for Proxy {
@@ -92,6 +96,7 @@ impl std::fmt::Debug for Proxy {
         f.debug_struct("Proxy")
             .field("request_timeout", &self.request_timeout)
             .field("has_health_checker", &self.health_checker.is_some())
+            .field("mirror", &self.mirror_target())
             .finish_non_exhaustive()
     }
 }
@@ -142,6 +147,13 @@ impl Proxy {
 
         let backend_id = backend.id().to_string();
 
+        // Send a shadow copy before forwarding; its outcome never affects the client
+        if let Some(mirror) = &self.mirror
+            && mirror.should_mirror()
+        {
+            mirror.dispatch(&req, &self.http_client, self.request_timeout, &self.metrics);
+        }
+
         // Track request start
         backend.start_request();
         self.metrics
@@ -189,11 +201,24 @@ impl Proxy {
         }
     }
 
+    /// Get the shadow backend address, if mirroring is enabled.
+    pub fn mirror_target(&self) -> Option<&str> {
+        self.mirror.as_deref().map(Mirror::target)
+    }
+
     /// Get the list of backends.
     pub async fn backends(&self) -> Vec<Backend> {
         self.backends.read().await.clone()
     }
 
+    /// Get a shared handle to the backend list.
+    ///
+    /// Lets external supervisors (e.g. cluster worker restarts) mark backends
+    /// healthy or unhealthy while the proxy is serving.
+    pub fn backend_pool(&self) -> Arc<RwLock<Vec<Backend>>> {
+        self.backends.clone()
+    }
+
     /// Get health status for all backends.
     pub async fn health_status(&self) -> Vec<BackendHealth> {
         let backends = self.backends.read().await;
@@ -285,6 +310,8 @@ pub struct ProxyBuilder {
     pool_idle_timeout: Duration,
     tcp_keepalive: Duration,
     http2_only: bool,
+    mirror: Option<MirrorConfig>,
+    backend_tls: Option<BackendTlsConfig>,
 }
 
 impl ProxyBuilder {
@@ -299,6 +326,8 @@ impl ProxyBuilder {
             pool_idle_timeout: Duration::from_secs(90),
             tcp_keepalive: Duration::from_secs(60),
             http2_only: false,
+            mirror: None,
+            backend_tls: None,
         }
     }
 
@@ -375,12 +404,33 @@ impl ProxyBuilder {
         self
     }
 
+    /// Mirror a percentage of requests to a shadow backend.
+    ///
+    /// Mirrored requests are sent in the background after backend selection;
+    /// their responses are discarded. Pass `None` to disable mirroring.
+    #[must_use]
+    pub fn mirror(mut self, config: Option<MirrorConfig>) -> Self {
+        self.mirror = config;
+        self
+    }
+
+    /// Set TLS options for `https://` backends.
+    ///
+    /// Applies to proxied, mirrored, and health check requests. Pass `None`
+    /// to use the platform trust store with no client certificate.
+    #[must_use]
+    pub fn backend_tls(mut self, config: Option<BackendTlsConfig>) -> Self {
+        self.backend_tls = config;
+        self
+    }
+
     /// Build the [`Proxy`].
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - No backends were configured
+    /// - The backend TLS certificates cannot be loaded
     /// - The HTTP client cannot be created
     pub fn build(self) -> Result<Proxy> {
         if self.backends.is_empty() {
@@ -398,6 +448,12 @@ impl ProxyBuilder {
             client_builder = client_builder.http2_prior_knowledge();
         }
 
+        if let Some(tls) = &self.backend_tls {
+            client_builder = tls
+                .apply(client_builder)
+                .context("Invalid backend TLS configuration")?;
+        }
+
         let http_client = client_builder
             .build()
             .context("Failed to create HTTP client")?;
@@ -406,9 +462,9 @@ impl ProxyBuilder {
         let strategy: Box<dyn Selection> = self.strategy.into_selector(self.backends.len());
 
         // Create health checker if configured
-        let health_checker = self
-            .health_check
-            .map(|config| Arc::new(HealthChecker::new(config)));
+        let health_checker = self.health_check.map(|config| {
+            Arc::new(HealthChecker::new(config).with_backend_tls(self.backend_tls.clone()))
+        });
 
         Ok(Proxy {
             backends: Arc::new(RwLock::new(self.backends)),
@@ -417,6 +473,7 @@ impl ProxyBuilder {
             request_timeout: self.request_timeout,
             http_client,
             metrics: LbMetrics::new(),
+            mirror: self.mirror.map(|config| Arc::new(Mirror::new(config))),
         })
     }
 }
@@ -465,6 +522,22 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_proxy_builder_with_mirror() {
+        let proxy = Proxy::builder()
+            .http_backend("127.0.0.1:3001")
+            .mirror(Some(MirrorConfig::new("127.0.0.1:4001", 10)))
+            .build()
+            .unwrap();
+        assert_eq!(proxy.mirror_target(), Some("127.0.0.1:4001"));
+
+        let proxy = Proxy::builder()
+            .http_backend("127.0.0.1:3001")
+            .build()
+            .unwrap();
+        assert!(proxy.mirror_target().is_none());
+    }
+
     #[test]
     fn test_request_builder() {
         let req = Request::new("GET", "/api/users")
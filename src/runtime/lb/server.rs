@@ -57,8 +57,10 @@ use tracing::{debug, error, info};
 
 use super::backend::{Backend, HttpBackend};
 use super::health::HealthCheckConfig;
+use super::mirror::MirrorConfig;
 use super::proxy::{Proxy, ProxyBuilder, Request, Response};
 use super::selection::LoadBalanceStrategy;
+use super::tls::BackendTlsConfig;
 
 /// Default address for the load balancer to listen on.
 const DEFAULT_LISTEN_ADDR: &str = "0.0.0.0:3000";
@@ -135,6 +137,9 @@ impl LoadBalancer {
         for (i, backend) in backends.iter().enumerate() {
             info!("  Backend {}: {}", i + 1, backend.id());
         }
+        if let Some(target) = self.proxy.mirror_target() {
+            info!("  Mirror: {}", target);
+        }
 
         // Start health check background task
         let _health_handle = self.proxy.start_health_checks();
@@ -285,6 +290,10 @@ pub struct LoadBalancerBuilder {
     tcp_keepalive: Duration,
     /// Use HTTP/2 only for backend connections.
     http2_only: bool,
+    /// Shadow traffic configuration.
+    mirror: Option<MirrorConfig>,
+    /// TLS options for `https://` backends.
+    backend_tls: Option<BackendTlsConfig>,
 }
 
 impl Default for LoadBalancerBuilder {
@@ -308,6 +317,8 @@ impl LoadBalancerBuilder {
             pool_idle_timeout: Duration::from_secs(90),
             tcp_keepalive: Duration::from_secs(60),
             http2_only: false,
+            mirror: None,
+            backend_tls: None,
         }
     }
 
@@ -319,6 +330,8 @@ impl LoadBalancerBuilder {
     }
 
     /// Add a backend by address string (e.g., "127.0.0.1:3001").
+    ///
+    /// Use an `https://` address to reach the backend over TLS.
     #[must_use]
     pub fn backend(mut self, address: impl Into<String>) -> Self {
         self.backends.push(address.into());
@@ -388,12 +401,29 @@ impl LoadBalancerBuilder {
         self
     }
 
+    /// Mirror a percentage of requests to a shadow backend.
+    ///
+    /// Pass `None` to disable mirroring.
+    #[must_use]
+    pub fn mirror(mut self, config: Option<MirrorConfig>) -> Self {
+        self.mirror = config;
+        self
+    }
+
+    /// Set TLS options (CA pinning, client certificate) for `https://` backends.
+    #[must_use]
+    pub fn backend_tls(mut self, config: Option<BackendTlsConfig>) -> Self {
+        self.backend_tls = config;
+        self
+    }
+
     /// Build the [`LoadBalancer`].
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - No backends were configured
+    /// - The backend TLS certificates cannot be loaded
     /// - The HTTP client cannot be created
     pub fn build(self) -> Result<LoadBalancer> {
         if self.backends.is_empty() {
@@ -415,7 +445,9 @@ impl LoadBalancerBuilder {
             .max_connections_per_backend(self.max_connections_per_backend)
             .pool_idle_timeout(self.pool_idle_timeout)
             .tcp_keepalive(self.tcp_keepalive)
-            .http2_only(self.http2_only);
+            .http2_only(self.http2_only)
+            .mirror(self.mirror)
+            .backend_tls(self.backend_tls);
 
         for backend in backends {
             proxy_builder = proxy_builder.backend(backend);
@@ -0,0 +1,171 @@
+//! TLS settings for connections from the load balancer to its backends.
+//!
+//! Backends addressed as `https://host:port` are reached over TLS. By default
+//! the platform trust store is used; setting a CA bundle pins trust to that
+//! bundle only, and a client certificate enables mutual TLS so workers can
+//! reject traffic that did not come through the load balancer.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mik::runtime::lb::{BackendTlsConfig, LoadBalancer};
+//!
+//! let tls = BackendTlsConfig::default()
+//!     .ca_cert("/etc/mik/workers-ca.pem")
+//!     .client_identity("/etc/mik/lb.pem", "/etc/mik/lb-key.pem");
+//!
+//! let lb = LoadBalancer::builder()
+//!     .backend("https://10.0.0.5:3001")
+//!     .backend_tls(Some(tls))
+//!     .build()?;
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+/// TLS configuration for backend connections.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackendTlsConfig {
+    /// PEM bundle of CA certificates to trust.
+    ///
+    /// When set, only these CAs are trusted and the platform roots are
+    /// ignored, pinning backends to certificates issued by this bundle.
+    pub ca_cert: Option<PathBuf>,
+    /// PEM client certificate presented to backends (mutual TLS).
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key (PKCS#8) for `client_cert`.
+    pub client_key: Option<PathBuf>,
+}
+
+impl BackendTlsConfig {
+    /// Pin trust to the CA certificates in a PEM bundle.
+    #[must_use]
+    pub fn ca_cert(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_cert = Some(path.into());
+        self
+    }
+
+    /// Present a client certificate to backends.
+    #[must_use]
+    pub fn client_identity(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.client_cert = Some(cert.into());
+        self.client_key = Some(key.into());
+        self
+    }
+
+    /// Apply this configuration to an HTTP client builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a certificate or key cannot be read or parsed, if
+    /// only one of `client_cert` and `client_key` is set, or if the binary
+    /// was built without a TLS backend.
+    pub(crate) fn apply(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        if self.ca_cert.is_none() && self.client_cert.is_none() && self.client_key.is_none() {
+            return Ok(builder);
+        }
+        apply_tls(self, builder)
+    }
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+fn apply_tls(
+    config: &BackendTlsConfig,
+    mut builder: reqwest::ClientBuilder,
+) -> Result<reqwest::ClientBuilder> {
+    if let Some(path) = &config.ca_cert {
+        let pem = read_pem(path)?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Invalid CA certificate bundle: {}", path.display()))?;
+        if certs.is_empty() {
+            bail!("No certificates found in CA bundle: {}", path.display());
+        }
+        builder = builder.tls_certs_only(certs);
+    }
+
+    match (&config.client_cert, &config.client_key) {
+        (Some(cert_path), Some(key_path)) => {
+            let cert = read_pem(cert_path)?;
+            let key = read_pem(key_path)?;
+            let identity = client_identity(&cert, &key).with_context(|| {
+                format!(
+                    "Invalid client certificate or key: {}, {}",
+                    cert_path.display(),
+                    key_path.display()
+                )
+            })?;
+            builder = builder.identity(identity);
+        },
+        (None, None) => {},
+        _ => bail!("Backend TLS client_cert and client_key must be set together"),
+    }
+
+    Ok(builder)
+}
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+fn apply_tls(
+    _config: &BackendTlsConfig,
+    _builder: reqwest::ClientBuilder,
+) -> Result<reqwest::ClientBuilder> {
+    bail!("Backend TLS requires mik to be built with the `native-tls` or `rustls` feature")
+}
+
+#[cfg(feature = "native-tls")]
+fn client_identity(cert: &[u8], key: &[u8]) -> reqwest::Result<reqwest::Identity> {
+    reqwest::Identity::from_pkcs8_pem(cert, key)
+}
+
+#[cfg(all(feature = "rustls", not(feature = "native-tls")))]
+fn client_identity(cert: &[u8], key: &[u8]) -> reqwest::Result<reqwest::Identity> {
+    let mut pem = cert.to_vec();
+    pem.push(b'\n');
+    pem.extend_from_slice(key);
+    reqwest::Identity::from_pem(&pem)
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls"))]
+fn read_pem(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+#[cfg(all(test, any(feature = "native-tls", feature = "rustls")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_leaves_builder_untouched() {
+        let builder = BackendTlsConfig::default()
+            .apply(reqwest::Client::builder())
+            .unwrap();
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_missing_ca_file_is_an_error() {
+        let config = BackendTlsConfig::default().ca_cert("/nonexistent/ca.pem");
+        let err = config.apply(reqwest::Client::builder()).unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/ca.pem"));
+    }
+
+    #[test]
+    fn test_invalid_ca_bundle_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ca.pem");
+        std::fs::write(&path, "not a certificate").unwrap();
+
+        let config = BackendTlsConfig::default().ca_cert(&path);
+        assert!(config.apply(reqwest::Client::builder()).is_err());
+    }
+
+    #[test]
+    fn test_client_cert_requires_key() {
+        let config = BackendTlsConfig {
+            client_cert: Some(PathBuf::from("/etc/mik/lb.pem")),
+            ..Default::default()
+        };
+        let err = config.apply(reqwest::Client::builder()).unwrap_err();
+        assert!(err.to_string().contains("set together"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Listen addresses for [`Server`](crate::runtime::Server).
+//!
+//! A server always binds its primary TCP address and can serve the same
+//! runtime on additional addresses at once, written as `host:port` or
+//! `unix:/path/to/socket`:
+//!
+//! ```toml
+//! [server]
+//! port = 3000
+//! listen = ["[::1]:3000", "unix:/run/mik.sock"]
+//! ```
+//!
+//! Unix socket peers have no IP address and are reported as `127.0.0.1`, so
+//! a reverse proxy on the socket is trusted only if `trusted_proxies`
+//! includes loopback.
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::str::FromStr;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Prefix marking a Unix domain socket path.
+const UNIX_PREFIX: &str = "unix:";
+
+/// Remote address reported for connections without one (Unix sockets).
+const LOCAL_PEER: SocketAddr = SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+/// An address the server accepts connections on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddr {
+    /// TCP socket address.
+    Tcp(SocketAddr),
+    /// Unix domain socket path (Unix only).
+    Unix(PathBuf),
+}
+
+/// Error returned when a listen address cannot be parsed.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("invalid listen address '{0}': expected host:port or unix:/path")]
+pub struct ListenAddrError(String);
+
+impl FromStr for ListenAddr {
+    type Err = ListenAddrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix(UNIX_PREFIX) {
+            if path.is_empty() {
+                return Err(ListenAddrError(s.to_string()));
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        s.parse()
+            .map(Self::Tcp)
+            .map_err(|_| ListenAddrError(s.to_string()))
+    }
+}
+
+impl fmt::Display for ListenAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "http://{addr}"),
+            Self::Unix(path) => write!(f, "{UNIX_PREFIX}{}", path.display()),
+        }
+    }
+}
+
+impl From<SocketAddr> for ListenAddr {
+    fn from(addr: SocketAddr) -> Self {
+        Self::Tcp(addr)
+    }
+}
+
+/// Connection accepted by a [`Listener`].
+pub(crate) trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// A bound listener for one [`ListenAddr`].
+pub(crate) enum Listener {
+    Tcp(tokio::net::TcpListener),
+    #[cfg(unix)]
+    Unix {
+        listener: tokio::net::UnixListener,
+        path: PathBuf,
+    },
+}
+
+impl Listener {
+    /// Bind `addr`, with `SO_REUSEPORT` on TCP sockets if `reuse_port`.
+    ///
+    /// A stale socket file left at a Unix socket path is replaced.
+    pub(crate) fn bind(addr: &ListenAddr, reuse_port: bool) -> Result<Self> {
+        match addr {
+            ListenAddr::Tcp(addr) => {
+                let listener = crate::runtime::handoff::bind_socket(*addr, reuse_port)?;
+                Ok(Self::Tcp(tokio::net::TcpListener::from_std(listener)?))
+            },
+            #[cfg(unix)]
+            ListenAddr::Unix(path) => {
+                if path.exists() {
+                    std::fs::remove_file(path).with_context(|| {
+                        format!("Failed to remove stale socket {}", path.display())
+                    })?;
+                }
+                let listener = tokio::net::UnixListener::bind(path)
+                    .with_context(|| format!("Failed to bind {}", path.display()))?;
+                Ok(Self::Unix {
+                    listener,
+                    path: path.clone(),
+                })
+            },
+            #[cfg(not(unix))]
+            ListenAddr::Unix(path) => {
+                anyhow::bail!(
+                    "Cannot bind {}: Unix sockets are not supported on this platform",
+                    path.display()
+                )
+            },
+        }
+    }
+
+    /// Accept the next connection and the peer address it came from.
+    pub(crate) async fn accept(&self) -> std::io::Result<(Box<dyn Connection>, SocketAddr)> {
+        match self {
+            Self::Tcp(listener) => {
+                let (stream, remote_addr) = listener.accept().await?;
+                Ok((Box::new(stream), remote_addr))
+            },
+            #[cfg(unix)]
+            Self::Unix { listener, .. } => {
+                let (stream, _) = listener.accept().await?;
+                Ok((Box::new(stream), LOCAL_PEER))
+            },
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Listener {
+    fn drop(&mut self) {
+        if let Self::Unix { path, .. } = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listen_addr() {
+        assert_eq!(
+            "0.0.0.0:3000".parse::<ListenAddr>().unwrap(),
+            ListenAddr::Tcp(SocketAddr::from(([0, 0, 0, 0], 3000)))
+        );
+        assert!(matches!(
+            "[::1]:3000".parse::<ListenAddr>().unwrap(),
+            ListenAddr::Tcp(addr) if addr.is_ipv6()
+        ));
+        assert_eq!(
+            "unix:/run/mik.sock".parse::<ListenAddr>().unwrap(),
+            ListenAddr::Unix(PathBuf::from("/run/mik.sock"))
+        );
+        assert!("unix:".parse::<ListenAddr>().is_err());
+        assert!("localhost".parse::<ListenAddr>().is_err());
+        assert!("3000".parse::<ListenAddr>().is_err());
+    }
+
+    #[test]
+    fn test_listen_addr_display() {
+        let tcp: ListenAddr = "127.0.0.1:3000".parse().unwrap();
+        assert_eq!(tcp.to_string(), "http://127.0.0.1:3000");
+        let unix: ListenAddr = "unix:/tmp/mik.sock".parse().unwrap();
+        assert_eq!(unix.to_string(), "unix:/tmp/mik.sock");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_listener_replaces_stale_socket_and_cleans_up() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mik.sock");
+        std::fs::write(&path, b"stale").unwrap();
+
+        let listener = Listener::bind(&ListenAddr::Unix(path.clone()), false).unwrap();
+        let mut client = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let (mut conn, remote_addr) = listener.accept().await.unwrap();
+        assert_eq!(remote_addr, LOCAL_PEER);
+
+        client.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        conn.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        drop(listener);
+        assert!(!path.exists());
+    }
+}
@@ -50,31 +50,60 @@
 //! # }
 //! ```
 
+mod adaptive;
+mod aliases;
 pub mod aot_cache;
+#[cfg(feature = "axum")]
+pub mod axum;
+pub mod build_overlay;
 pub mod builder;
 mod cache;
+mod canary;
+mod chaos;
 pub mod cluster;
+mod component_exports;
+pub mod component_info;
+pub mod component_openapi;
 pub mod compression;
+pub(crate) mod core_adapter;
+pub mod deadline;
+pub mod dev_dashboard;
+mod dev_proxy;
+pub mod drain;
+mod egress;
 pub mod endpoints;
 pub mod error;
 pub mod gateway;
+pub mod handoff;
 mod host;
 pub mod host_config;
 pub mod host_state;
+mod init_hook;
+pub mod job;
 pub mod lb;
+pub mod listener;
 pub mod module_path;
+mod nn;
 mod observability;
+pub(crate) mod oci;
+pub mod proxy_protocol;
+pub mod recorder;
 pub mod reliability;
+pub mod reload;
 pub mod request;
 pub mod request_handler;
+pub mod request_info;
 pub mod schema_handler;
 pub mod script;
 pub mod security;
 pub mod server;
 pub mod spans;
+mod sql;
 pub mod static_files;
+mod tenant_limits;
 pub mod trace_context;
 pub mod types;
+mod usage;
 pub mod wasm_executor;
 
 #[cfg(test)]
@@ -90,9 +119,15 @@ pub(crate) use host::Host;
 // Re-export main builder type
 #[allow(unused_imports)]
 pub use builder::RuntimeBuilder;
+#[allow(unused_imports)]
+pub use drain::DrainReport;
 pub use host_config::{DEFAULT_MEMORY_LIMIT_BYTES, DEFAULT_SHUTDOWN_TIMEOUT_SECS, HostConfig};
+#[allow(unused_imports)]
+pub use reload::ReloadReport;
 // New library-first API types - for external consumers
 #[allow(unused_imports)]
+pub use listener::ListenAddr;
+#[allow(unused_imports)]
 pub use request::{Request, Response};
 #[allow(unused_imports)]
 pub use request_handler::handle_request;
@@ -101,7 +136,7 @@ pub use server::{Server, ServerBuilder};
 #[allow(unused_imports)]
 pub use static_files::guess_content_type;
 #[allow(unused_imports)]
-pub use types::{ErrorCategory, HealthDetail, HealthStatus, MemoryStats};
+pub use types::{CanaryStatus, ErrorCategory, HealthDetail, HealthStatus, MemoryStats};
 // Cluster orchestration - for external consumers
 #[allow(unused_imports)]
 pub use cluster::{Cluster, ClusterBuilder, WorkerHandle};
@@ -109,11 +144,11 @@ pub use cluster::{Cluster, ClusterBuilder, WorkerHandle};
 use crate::constants;
 use anyhow::Result;
 use host_state::HostState;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
 use tokio::sync::Semaphore;
 use tracing::error;
@@ -190,28 +225,68 @@ pub struct SharedState {
     pub(crate) single_component: Option<Arc<Component>>,
     /// Name of the single component (derived from filename, for routing).
     pub(crate) single_component_name: Option<String>,
-    pub(crate) static_dir: Option<PathBuf>,
     pub(crate) execution_timeout: Duration,
+    /// Upper bound for per-request timeout overrides (see [`deadline`]).
+    pub(crate) max_request_timeout: Duration,
+    /// Timeout for `init`/`warmup` exports (see [`init_hook`]).
+    pub(crate) init_timeout: Duration,
     /// Memory limit per request (enforced via `ResourceLimiter`).
     pub(crate) memory_limit_bytes: usize,
     pub(crate) max_body_size_bytes: usize,
     pub(crate) shutdown: Arc<AtomicBool>,
+    /// In-flight requests and execution cancellation (see [`drain`]).
+    pub(crate) drain: drain::DrainState,
     pub(crate) request_counter: AtomicU64,
     pub(crate) config: HostConfig,
     pub(crate) circuit_breaker: reliability::CircuitBreaker,
     pub(crate) request_semaphore: Arc<Semaphore>,
     pub(crate) module_semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
-    pub(crate) http_allowed: Arc<Vec<String>>,
+    /// Requests waiting for a permit per module (see `module_queue_depth`).
+    pub(crate) module_queues: Mutex<HashMap<String, Arc<AtomicUsize>>>,
+    /// Concurrency limit per tenant across its modules (see `max_per_tenant_requests`).
+    /// Only tenants with a directory in `user_modules_dir` get an entry.
+    pub(crate) tenant_semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+    /// Request rate per tenant (see [`tenant_limits`]).
+    pub(crate) tenant_rates: tenant_limits::TenantRateLimiter,
+    /// Cached `/_mik/handlers` and OpenAPI responses (see [`gateway::index`]).
+    pub(crate) gateway_index: gateway::index::DiscoveryIndex,
+    /// Per-module limits when `adaptive_concurrency` is enabled (see [`adaptive`]).
+    pub(crate) adaptive_limits: Option<adaptive::AdaptiveLimits>,
+    /// Settings that change on reload (see [`reload`]).
+    pub(crate) live: RwLock<Arc<reload::LiveConfig>>,
     /// Scripts directory (optional, for JS orchestration).
     pub(crate) scripts_dir: Option<PathBuf>,
     /// Content-addressable AOT cache for compiled components.
     pub(crate) aot_cache: aot_cache::AotCache,
     /// Fuel budget per request for deterministic CPU limiting.
     pub(crate) fuel_budget: u64,
+    /// Canary component run by deep health checks, compiled on first use.
+    pub(crate) canary: tokio::sync::OnceCell<Component>,
+    /// Cache and concurrency counters exported on `/metrics`.
+    pub(crate) stats: Arc<observability::RuntimeStats>,
+    /// Outgoing HTTP traffic per module (see [`egress`]).
+    pub(crate) egress: Arc<egress::EgressMeter>,
+    /// Usage per tenant, for billing and quotas (see [`usage`]).
+    pub(crate) usage: Arc<usage::UsageMeter>,
+    /// Proxies whose forwarding headers are trusted (see [`request_info`]).
+    pub(crate) trusted_proxies: Vec<request_info::TrustedProxy>,
+    /// Database served by `mik:sql` to granted modules (see [`sql`]).
+    pub(crate) sql: Option<crate::daemon::services::sql::SqlService>,
+    /// Models served over `wasi-nn` to granted modules (see [`nn`]).
+    pub(crate) nn: Option<nn::NnModels>,
+    /// Modules pulled from OCI registries on first request (see [`oci`]).
+    pub(crate) oci: Option<oci::OciModules>,
+    /// Local daemon serving `host.kv` and `host.storage` to scripts granted
+    /// in `script_capabilities`.
+    pub(crate) daemon: Option<crate::daemon::http::client::DaemonClient>,
+    /// Latest requests shown on the development dashboard (only with
+    /// `dev_dashboard`, see [`dev_dashboard`]).
+    pub(crate) recent_requests: Option<dev_dashboard::RecentRequests>,
 }
 
 // Cache methods (get_module_semaphore, get_or_load) are defined in cache.rs
 // Health and metrics methods are defined in observability.rs
+// Live configuration and reload methods are defined in reload.rs
 // Host struct and initialization are defined in host.rs
 
 // =============================================================================
@@ -258,14 +333,12 @@ pub struct SharedState {
 ///         .build()?
 /// );
 ///
-/// // Use with any HTTP framework (axum, actix, warp, etc.)
-/// // let axum_handler = move |req: axum::Request| {
-/// //     let runtime = runtime.clone();
-/// //     async move {
-/// //         let mik_req = Request::from(req);
-/// //         runtime.handle_request(mik_req).await
-/// //     }
-/// // };
+/// // Any framework built on the `http` crate: collect the body, then convert.
+/// // With the `axum` feature, `mik::runtime::axum::router` does this for you.
+/// let req = hyper::Request::get("/run/hello/").body(Vec::new())?;
+/// let response = runtime.handle_request(Request::from(req)).await?;
+/// let response = hyper::Response::<Vec<u8>>::try_from(response)?;
+/// # let _ = response;
 /// # Ok(())
 /// # }
 /// ```
@@ -316,12 +389,14 @@ impl Runtime {
     /// Create a runtime from a Host (internal conversion).
     ///
     /// Note: We intentionally consume `Host` to take ownership of the epoch shutdown,
-    /// preventing the Host's Drop impl from signaling shutdown prematurely.
-    #[allow(clippy::needless_pass_by_value)]
-    pub(crate) fn from_host(host: Host) -> Self {
+    /// leaving it a fresh flag so the Host's Drop impl does not stop the epoch
+    /// thread this runtime relies on.
+    pub(crate) fn from_host(mut host: Host) -> Self {
+        let epoch_shutdown =
+            std::mem::replace(&mut host.epoch_shutdown, Arc::new(AtomicBool::new(false)));
         Self {
             shared: host.shared.clone(),
-            epoch_shutdown: host.epoch_shutdown.clone(),
+            epoch_shutdown,
         }
     }
 
@@ -336,7 +411,8 @@ impl Runtime {
     ///
     /// # Returns
     ///
-    /// The response from the WASM handler, or an error if processing failed.
+    /// The response from the WASM handler, or an error if processing failed
+    /// or the runtime is shutting down.
     ///
     /// # Examples
     ///
@@ -364,6 +440,10 @@ impl Runtime {
         use http_body_util::{BodyExt, Full};
         use hyper::body::Bytes;
         use std::net::{IpAddr, Ipv4Addr};
+        use tracing::Instrument;
+
+        anyhow::ensure!(!self.is_shutting_down(), "Runtime is shutting down");
+        let _in_flight = self.shared.drain.track();
 
         // Convert our Request to hyper request format
         let mut hyper_req = hyper::Request::builder()
@@ -382,7 +462,16 @@ impl Runtime {
         let remote_addr = std::net::SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
 
         // Process the request through our internal handler
-        let result = self.handle_request_internal(hyper_req, remote_addr).await?;
+        let span = tracing::info_span!(
+            "request",
+            method = %req.method,
+            path = %req.path,
+            timeout_ms = tracing::field::Empty
+        );
+        let result = self
+            .handle_request_internal(hyper_req, remote_addr)
+            .instrument(span)
+            .await?;
 
         // Convert hyper response back to our Response type
         let status = result.status().as_u16();
@@ -412,6 +501,57 @@ impl Runtime {
         })
     }
 
+    /// Run the single component's `init`/`warmup` export, if it has one.
+    ///
+    /// Modules in a directory run their hook when they are first loaded.
+    /// A single component is loaded when the runtime is built, so call this
+    /// before serving traffic; [`Server::serve`](server::Server::serve) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns the hook's error if it fails, traps, or exceeds the init
+    /// timeout.
+    pub async fn warmup(&self) -> Result<()> {
+        let (Some(component), Some(name)) = (
+            &self.shared.single_component,
+            &self.shared.single_component_name,
+        ) else {
+            return Ok(());
+        };
+        match init_hook::detect(&self.shared.engine, component) {
+            Some(export) => init_hook::run(&self.shared, component, name, export)
+                .await
+                .map_err(error::Error::into_anyhow),
+            None => Ok(()),
+        }
+    }
+
+    /// Run a module as a background job, without an HTTP request.
+    ///
+    /// The module must export `mik:job/handler` (see `wit/job.wit`) or
+    /// `wasi:cli/run`, in which case `payload` is its stdin and its stdout
+    /// is returned. Jobs get the same fuel, memory, and timeout limits as
+    /// HTTP requests (see [`job`]).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use mik::runtime::Runtime;
+    ///
+    /// # async fn example() -> anyhow::Result<()> {
+    /// let runtime = Runtime::builder()
+    ///     .modules_dir("modules/")
+    ///     .build()?;
+    ///
+    /// let output = runtime.invoke_job("cleanup", br#"{"days": 30}"#.to_vec()).await?;
+    /// println!("{}", String::from_utf8_lossy(&output));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn invoke_job(&self, module: &str, payload: Vec<u8>) -> Result<Vec<u8>> {
+        job::invoke_job(&self.shared, module, payload).await
+    }
+
     /// Internal request handling that works with Full<Bytes> body.
     async fn handle_request_internal(
         &self,
@@ -490,7 +630,7 @@ impl Runtime {
 
         // Handle static file requests
         if path.starts_with(STATIC_PREFIX) {
-            return match &self.shared.static_dir {
+            return match &self.shared.live().static_dir {
                 Some(dir) => serve_static_file(dir, &path)
                     .await
                     .map(|resp| maybe_compress_response(resp, client_accepts_gzip)),
@@ -508,6 +648,7 @@ impl Runtime {
         if module.is_empty() {
             return not_found("No module specified. Use /run/<module>/");
         }
+        let (module, alias) = self.shared.resolve_alias(module);
 
         // Resolve module
         let (component, module_name, module_permit) = {
@@ -528,37 +669,44 @@ impl Runtime {
                     tracing::warn!("Circuit breaker blocked request to '{}': {}", module, e);
                     let err = error::Error::circuit_breaker_open(&module);
                     let mut resp = error_response(&err)?;
-                    resp.headers_mut()
-                        .insert("Retry-After", "30".parse().expect("valid header value"));
-                    return Ok(resp);
-                }
-
-                // Acquire per-module semaphore permit
-                let module_semaphore = self.shared.get_module_semaphore(&module);
-                let module_permit = if let Ok(permit) = module_semaphore.try_acquire_owned() {
-                    Some(permit)
-                } else {
-                    tracing::warn!(
-                        "Module '{}' overloaded (max {} concurrent requests)",
-                        module,
-                        self.shared.config.max_per_module_requests
+                    resp.headers_mut().insert(
+                        "Retry-After",
+                        request_handler::circuit_retry_after(&self.shared, &module),
                     );
-                    let err = error::Error::rate_limit_exceeded(format!(
-                        "Module '{}' overloaded (max {} concurrent)",
-                        module, self.shared.config.max_per_module_requests
-                    ));
-                    let mut resp = error_response(&err)?;
-                    resp.headers_mut()
-                        .insert("Retry-After", "5".parse().expect("valid header value"));
                     return Ok(resp);
-                };
+                }
 
-                match self.shared.get_or_load(&module).await {
+                // Acquire per-module semaphore permit, waiting in the module's queue if enabled
+                let module_permit =
+                    if let Some(permit) = self.shared.acquire_module_permit(&module).await {
+                        Some(permit)
+                    } else {
+                        self.shared.stats.record_module_overload(&module);
+                        tracing::warn!(
+                            "Module '{}' overloaded (max {} concurrent requests)",
+                            module,
+                            self.shared.module_concurrency_limit(&module)
+                        );
+                        let err = error::Error::rate_limit_exceeded(format!(
+                            "Module '{}' overloaded (max {} concurrent)",
+                            module,
+                            self.shared.module_concurrency_limit(&module)
+                        ));
+                        let mut resp = error_response(&err)?;
+                        resp.headers_mut()
+                            .insert("Retry-After", "5".parse().expect("valid header value"));
+                        return Ok(resp);
+                    };
+
+                match self.shared.get_or_load_http(&module).await {
                     Ok(comp) => (comp, Some(module.clone()), module_permit),
                     Err(e) => {
+                        if let Some(err) = request_handler::unsupported_component(&e) {
+                            return error_response(err);
+                        }
                         tracing::warn!("Module load failed: {}", e);
                         self.shared.circuit_breaker.record_failure(&module);
-                        let err = error::Error::module_not_found(&module);
+                        let err = request_handler::load_error(&e, &module);
                         return error_response(&err);
                     },
                 }
@@ -574,11 +722,20 @@ impl Runtime {
         uri_parts.path_and_query = Some(handler_path.parse()?);
         new_parts.uri = hyper::Uri::from_parts(uri_parts)?;
 
+        // Embedded requests have no connection, so no client IP
+        new_parts.extensions.insert(request_handler::route_info(
+            request_info::RequestInfo::default(),
+            &path,
+            module_name.as_deref(),
+            alias.as_deref(),
+        ));
+
         let req = hyper::Request::from_parts(new_parts, HyperCompatibleBody(body));
 
         // Execute WASM request
         let _module_permit = module_permit;
-        let result = execute_wasm_request(self.shared.clone(), component, req).await;
+        let result =
+            execute_wasm_request(self.shared.clone(), component, module_name.as_deref(), req).await;
 
         // Record success/failure in circuit breaker
         if let Some(ref module) = module_name {
@@ -588,7 +745,12 @@ impl Runtime {
             }
         }
 
-        result.map(|resp| maybe_compress_response(resp, client_accepts_gzip))
+        result.map(|mut resp| {
+            if let Some(alias) = &alias {
+                self.shared.add_alias_headers(alias, resp.headers_mut());
+            }
+            maybe_compress_response(resp, client_accepts_gzip)
+        })
     }
 
     /// Get the health status of the runtime.
@@ -620,7 +782,18 @@ impl Runtime {
     /// Check if static file serving is enabled.
     #[must_use]
     pub fn has_static_files(&self) -> bool {
-        self.shared.static_dir.is_some()
+        self.shared.live().static_dir.is_some()
+    }
+
+    /// Re-read mik.toml and apply the settings that can change while
+    /// serving, without dropping compiled modules (see [`reload`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, with nothing applied, if the runtime was not built
+    /// from a mik.toml or the file is invalid.
+    pub fn reload_config(&self) -> Result<ReloadReport> {
+        self.shared.reload()
     }
 
     /// Get the configured port (from manifest or builder).
@@ -641,6 +814,29 @@ impl Runtime {
     /// to begin its shutdown sequence.
     pub fn shutdown(&self) {
         self.shared.shutdown.store(true, Ordering::SeqCst);
+        self.shared.drain.request_shutdown();
+    }
+
+    /// Shut down without a [`Server`]: reject new requests, wait up to
+    /// `timeout` for in-flight ones, then cancel executions still running.
+    ///
+    /// Executions started after this returns are cancelled as well, so the
+    /// runtime should be dropped afterwards. See [`drain`] for the phases.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use mik::runtime::Runtime;
+    /// use std::time::Duration;
+    ///
+    /// # async fn example(runtime: Runtime) {
+    /// let report = runtime.shutdown_graceful(Duration::from_secs(10)).await;
+    /// println!("{} of {} requests completed", report.completed, report.in_flight);
+    /// # }
+    /// ```
+    pub async fn shutdown_graceful(&self, timeout: Duration) -> DrainReport {
+        self.shutdown();
+        drain::drain(&self.shared, timeout).await
     }
 
     /// Check if shutdown has been requested.
@@ -801,4 +997,205 @@ mod tests {
         // Verify consume_fuel is enabled in engine config
         // (This is validated by the engine creation succeeding with fuel operations)
     }
+
+    #[tokio::test]
+    async fn test_dropped_request_cancels_execution() {
+        let modules =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        if !modules.join("slow_response.wasm").exists() {
+            return;
+        }
+        let runtime = Runtime::builder().modules_dir(&modules).build().unwrap();
+        let request = |delay_secs: u64| {
+            Request::new("POST", "/run/slow_response/")
+                .with_header("host", "localhost")
+                .with_body_str(format!(r#"{{"delay_secs": {delay_secs}}}"#))
+        };
+
+        // Load the module, then give up long before the handler would answer
+        let response = runtime.handle_request(request(0)).await.unwrap();
+        assert_eq!(response.status, 200);
+        let started = std::time::Instant::now();
+        let result = tokio::time::timeout(
+            std::time::Duration::from_millis(200),
+            runtime.handle_request(request(10)),
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert_eq!(runtime.shared.stats.cancelled_requests(), 1);
+        assert!(
+            runtime
+                .shared
+                .get_prometheus_metrics()
+                .contains("mik_requests_cancelled_total 1")
+        );
+
+        // The module is still served afterwards
+        let response = runtime.handle_request(request(0)).await.unwrap();
+        assert_eq!(response.status, 200);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_module_queue_absorbs_bursts() {
+        let modules =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        if !modules.join("slow_response.wasm").exists() {
+            return;
+        }
+        let build = |timeout_ms| {
+            Arc::new(
+                Runtime::builder()
+                    .modules_dir(&modules)
+                    .max_per_module_requests(1)
+                    .module_queue(1, std::time::Duration::from_millis(timeout_ms))
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let call = |runtime: &Arc<Runtime>, delay_secs: u64| {
+            let runtime = runtime.clone();
+            tokio::spawn(async move {
+                let request = Request::new("POST", "/run/slow_response/")
+                    .with_header("host", "localhost")
+                    .with_body_str(format!(r#"{{"delay_secs": {delay_secs}}}"#));
+                runtime.handle_request(request).await.unwrap().status
+            })
+        };
+        let pause = || tokio::time::sleep(std::time::Duration::from_millis(200));
+
+        // One request runs, one waits for it, the next is shed
+        let runtime = build(10_000);
+        assert_eq!(call(&runtime, 0).await.unwrap(), 200);
+        let running = call(&runtime, 3);
+        pause().await;
+        let queued = call(&runtime, 0);
+        pause().await;
+        assert_eq!(call(&runtime, 0).await.unwrap(), 429);
+        assert!(
+            runtime
+                .shared
+                .get_prometheus_metrics()
+                .contains("mik_module_queue_depth{module=\"slow_response\"} 1")
+        );
+        assert_eq!(running.await.unwrap(), 200);
+        assert_eq!(queued.await.unwrap(), 200);
+
+        // Waiting longer than the queue timeout is shed too
+        let runtime = build(100);
+        assert_eq!(call(&runtime, 0).await.unwrap(), 200);
+        let running = call(&runtime, 2);
+        pause().await;
+        assert_eq!(call(&runtime, 0).await.unwrap(), 429);
+        assert_eq!(running.await.unwrap(), 200);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_tenant_limits_and_isolation() {
+        use request_handler::{ModuleResolution, resolve_tenant_module};
+
+        let echo = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/modules/echo.wasm");
+        if !echo.exists() {
+            return;
+        }
+        let modules = tempfile::tempdir().unwrap();
+        std::fs::copy(&echo, modules.path().join("echo.wasm")).unwrap();
+        let users = tempfile::tempdir().unwrap();
+        for tenant in ["tenant-a", "tenant-b"] {
+            std::fs::create_dir(users.path().join(tenant)).unwrap();
+            std::fs::copy(&echo, users.path().join(tenant).join("echo.wasm")).unwrap();
+        }
+        std::os::unix::fs::symlink(
+            users.path().join("tenant-a/echo.wasm"),
+            users.path().join("tenant-b/stolen.wasm"),
+        )
+        .unwrap();
+
+        let runtime = Runtime::builder()
+            .modules_dir(modules.path())
+            .user_modules_dir(users.path())
+            .max_per_tenant_requests(1)
+            .build()
+            .unwrap();
+        let shared = &runtime.shared;
+        let status = |resolution: &ModuleResolution| match resolution {
+            ModuleResolution::Success { .. } => 200,
+            ModuleResolution::Response(resp) => resp.status().as_u16(),
+        };
+
+        // A tenant at its limit is shed without affecting other tenants
+        let held = resolve_tenant_module(shared, "/tenant/tenant-a/echo/")
+            .await
+            .unwrap();
+        assert_eq!(status(&held), 200);
+        let shed = resolve_tenant_module(shared, "/tenant/tenant-a/echo/")
+            .await
+            .unwrap();
+        assert_eq!(status(&shed), 429);
+        let other = resolve_tenant_module(shared, "/tenant/tenant-b/echo/")
+            .await
+            .unwrap();
+        assert_eq!(status(&other), 200);
+        drop((held, other));
+
+        // Links into another tenant's directory are not served
+        let stolen = resolve_tenant_module(shared, "/tenant/tenant-b/stolen/")
+            .await
+            .unwrap();
+        assert_eq!(status(&stolen), 404);
+
+        // Invalid names are rejected before any per-module state exists
+        let invalid = resolve_tenant_module(shared, "/tenant/..%2Fetc/echo/")
+            .await
+            .unwrap();
+        assert_eq!(status(&invalid), 400);
+        assert!(
+            !shared
+                .module_semaphores
+                .lock()
+                .keys()
+                .any(|key| key.contains(".."))
+        );
+    }
+
+    #[test]
+    fn test_host_checks_preopens() {
+        use crate::manifest::Preopen;
+
+        let modules =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        if !modules.join("echo.wasm").exists() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let build = |path: &std::path::Path, guest: &str| {
+            Runtime::builder()
+                .modules_dir(&modules)
+                .preopen(
+                    "echo",
+                    Preopen {
+                        path: path.to_string_lossy().into_owned(),
+                        guest: guest.to_string(),
+                        writable: false,
+                    },
+                )
+                .build()
+        };
+
+        assert!(build(dir.path(), "/assets").is_ok());
+        let err = build(dir.path(), "assets").unwrap_err();
+        assert!(err.to_string().contains("must be absolute"), "{err}");
+        let err = build(&dir.path().join("missing"), "/assets").unwrap_err();
+        assert!(err.to_string().contains("not found"), "{err}");
+
+        // The granted directory is opened for each store of the module
+        let runtime = build(dir.path(), "/assets").unwrap();
+        let mut wasi = wasmtime_wasi::WasiCtxBuilder::new();
+        assert!(
+            host_state::add_preopens(&mut wasi, runtime.shared.config.preopens("echo")).is_ok()
+        );
+        assert!(runtime.shared.config.preopens("other").is_empty());
+    }
 }
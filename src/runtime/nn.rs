@@ -0,0 +1,153 @@
+//! `wasi-nn` for inference handlers (`nn` feature).
+//!
+//! Models are provided by the host: `[server.nn_models]` names directories
+//! holding a `model.onnx`, loaded once at startup and shared by all requests.
+//! Guests open them with `wasi:nn/graph.load-by-name`, and only see the models
+//! granted to their module in `[server.nn_modules]`. Loading models from
+//! guest-supplied bytes is not available, so every model in memory is one the
+//! host loaded and accounted for (`memory.nn_model_bytes` in `/health`,
+//! `mik_nn_model_bytes` in `/metrics`).
+
+use crate::runtime::host_config::HostConfig;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Models loaded at startup.
+pub(crate) struct NnModels {
+    /// Bytes on disk per model, reported as its memory footprint.
+    sizes: BTreeMap<String, usize>,
+    #[cfg(feature = "nn")]
+    graphs: std::collections::HashMap<String, wasmtime_wasi_nn::Graph>,
+}
+
+impl NnModels {
+    /// Load the models in `config.nn_models`, if any.
+    pub(crate) fn load(config: &HostConfig) -> Result<Option<Self>> {
+        if config.nn_models.is_empty() {
+            return Ok(None);
+        }
+        if !cfg!(feature = "nn") {
+            anyhow::bail!("[server.nn_models] requires mik to be built with the nn feature");
+        }
+        for (module, models) in &config.nn_modules {
+            if let Some(missing) = models.iter().find(|m| !config.nn_models.contains_key(*m)) {
+                anyhow::bail!("nn_modules: '{module}' is granted unknown model '{missing}'");
+            }
+        }
+
+        let mut loaded = Self {
+            sizes: BTreeMap::new(),
+            #[cfg(feature = "nn")]
+            graphs: std::collections::HashMap::new(),
+        };
+        for (name, dir) in &config.nn_models {
+            let size = std::fs::metadata(dir.join("model.onnx"))
+                .with_context(|| format!("nn model '{name}': {}/model.onnx", dir.display()))?
+                .len() as usize;
+            #[cfg(feature = "nn")]
+            loaded.graphs.insert(name.clone(), load_graph(dir)?);
+            info!("nn model '{}': {} ({} bytes)", name, dir.display(), size);
+            loaded.sizes.insert(name.clone(), size);
+        }
+        Ok(Some(loaded))
+    }
+
+    /// Bytes per model.
+    pub(crate) const fn sizes(&self) -> &BTreeMap<String, usize> {
+        &self.sizes
+    }
+
+    /// Total bytes of all loaded models.
+    pub(crate) fn total_bytes(&self) -> usize {
+        self.sizes.values().sum()
+    }
+
+    /// A `wasi-nn` context exposing only `allowed` models.
+    #[cfg(feature = "nn")]
+    pub(crate) fn ctx(&self, allowed: &[String]) -> wasmtime_wasi_nn::wit::WasiNnCtx {
+        let graphs = allowed
+            .iter()
+            .filter_map(|name| Some((name.clone(), self.graphs.get(name)?.clone())))
+            .collect();
+        wasmtime_wasi_nn::wit::WasiNnCtx::new([], AllowedModels(graphs).into())
+    }
+}
+
+/// A `wasi-nn` context without models, for modules granted none.
+#[cfg(feature = "nn")]
+pub(crate) fn empty_ctx() -> wasmtime_wasi_nn::wit::WasiNnCtx {
+    wasmtime_wasi_nn::wit::WasiNnCtx::new(
+        [],
+        AllowedModels(std::collections::HashMap::new()).into(),
+    )
+}
+
+/// Add the `wasi:nn` interfaces to `linker`.
+#[cfg(feature = "nn")]
+pub(crate) fn add_to_linker(
+    linker: &mut wasmtime::component::Linker<crate::runtime::host_state::HostState>,
+) -> Result<()> {
+    wasmtime_wasi_nn::wit::add_to_linker(linker, |state| {
+        wasmtime_wasi_nn::wit::WasiNnView::new(&mut state.table, &mut state.nn)
+    })
+}
+
+#[cfg(feature = "nn")]
+fn load_graph(dir: &std::path::Path) -> Result<wasmtime_wasi_nn::Graph> {
+    use wasmtime_wasi_nn::backend::BackendFromDir;
+    use wasmtime_wasi_nn::backend::onnx::OnnxBackend;
+
+    OnnxBackend::default()
+        .load_from_dir(dir, wasmtime_wasi_nn::wit::ExecutionTarget::Cpu)
+        .with_context(|| format!("Failed to load ONNX model from {}", dir.display()))
+}
+
+/// Registry of the models one module may load.
+#[cfg(feature = "nn")]
+struct AllowedModels(std::collections::HashMap<String, wasmtime_wasi_nn::Graph>);
+
+#[cfg(feature = "nn")]
+impl wasmtime_wasi_nn::GraphRegistry for AllowedModels {
+    fn get(&self, name: &str) -> Option<&wasmtime_wasi_nn::Graph> {
+        self.0.get(name)
+    }
+
+    fn get_mut(&mut self, name: &str) -> Option<&mut wasmtime_wasi_nn::Graph> {
+        self.0.get_mut(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_without_models() {
+        assert!(NnModels::load(&HostConfig::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_load_checks_grants() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = HostConfig::default();
+        config
+            .nn_models
+            .insert("sentiment".to_string(), dir.path().to_path_buf());
+        config
+            .nn_modules
+            .insert("classifier".to_string(), vec!["missing".to_string()]);
+        assert_eq!(config.nn_models_for("classifier"), ["missing"]);
+        assert!(config.nn_models_for("other").is_empty());
+
+        let err = NnModels::load(&config).err().unwrap().to_string();
+        if cfg!(feature = "nn") {
+            assert!(err.contains("unknown model 'missing'"), "{err}");
+        } else {
+            assert!(
+                err.contains("requires mik to be built with the nn feature"),
+                "{err}"
+            );
+        }
+    }
+}
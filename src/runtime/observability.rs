@@ -3,12 +3,101 @@
 //! This module provides runtime observability features:
 //! - Health status with cache and memory information
 //! - Prometheus-format metrics export
+//! - Cache and concurrency counters used by `mik tune`
 //! - Platform-specific memory usage tracking
 
 use super::SharedState;
 use super::types::{HealthDetail, HealthStatus, MemoryStats};
+use super::usage::TenantUsage;
 use crate::constants;
-use std::sync::atomic::Ordering;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Reads one per-tenant counter for `/metrics`.
+type TenantCounter = fn(&TenantUsage) -> f64;
+
+/// Counters for cache effectiveness and concurrency pressure.
+///
+/// Exported on `/metrics` so `mik tune` can compare two scrapes and suggest
+/// configuration changes.
+#[derive(Default)]
+pub(crate) struct RuntimeStats {
+    module_cache_hits: AtomicU64,
+    module_cache_misses: AtomicU64,
+    aot_cache_hits: AtomicU64,
+    aot_cache_misses: AtomicU64,
+    /// Requests that found no free global permit (instance pool exhausted).
+    permit_waits: AtomicU64,
+    /// Executions dropped because the client disconnected.
+    cancelled_requests: AtomicU64,
+    /// Executions retried after a transient failure.
+    execution_retries: AtomicU64,
+    /// Requests rejected by a per-module limit, keyed by module.
+    module_overloads: Mutex<HashMap<String, u64>>,
+    /// Requests served through a route alias, keyed by alias.
+    alias_requests: Mutex<HashMap<String, u64>>,
+}
+
+impl RuntimeStats {
+    /// Record a module cache lookup.
+    pub(crate) fn record_module_cache(&self, hit: bool) {
+        let counter = if hit {
+            &self.module_cache_hits
+        } else {
+            &self.module_cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record an AOT cache lookup made while loading a module.
+    pub(crate) fn record_aot_cache(&self, hit: bool) {
+        let counter = if hit {
+            &self.aot_cache_hits
+        } else {
+            &self.aot_cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a request that had to wait for a global concurrency permit.
+    pub(crate) fn record_permit_wait(&self) {
+        self.permit_waits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record an execution cancelled by a client disconnect.
+    pub(crate) fn record_cancelled_request(&self) {
+        self.cancelled_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Executions cancelled by client disconnects so far.
+    pub(crate) fn cancelled_requests(&self) -> u64 {
+        self.cancelled_requests.load(Ordering::Relaxed)
+    }
+
+    /// Record an execution retried after a transient failure.
+    pub(crate) fn record_execution_retry(&self) {
+        self.execution_retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a request rejected because its module was at its limit.
+    pub(crate) fn record_module_overload(&self, module: &str) {
+        *self
+            .module_overloads
+            .lock()
+            .entry(module.to_string())
+            .or_default() += 1;
+    }
+
+    /// Record a request served through a route alias.
+    pub(crate) fn record_alias_request(&self, alias: &str) {
+        *self
+            .alias_requests
+            .lock()
+            .entry(alias.to_string())
+            .or_default() += 1;
+    }
+}
 
 impl SharedState {
     /// Get health status with configurable detail level.
@@ -41,8 +130,17 @@ impl SharedState {
             memory: MemoryStats {
                 allocated_bytes: get_memory_usage(),
                 limit_per_request_bytes: self.config.memory_limit_bytes,
+                nn_model_bytes: self.nn.as_ref().map(super::nn::NnModels::total_bytes),
             },
+            profile: self.config.profile.clone(),
+            environment: self
+                .config
+                .component_env
+                .as_ref()
+                .filter(|_| detail == HealthDetail::Full)
+                .map(crate::manifest::mask_secret_vars),
             loaded_modules,
+            canary: None,
         }
     }
 
@@ -53,7 +151,7 @@ impl SharedState {
         let total_requests = self.request_counter.load(Ordering::Relaxed);
         let cache_entries = self.cache.entry_count();
         let cache_bytes = self.cache.weighted_size();
-        let circuit_states = self.circuit_breaker.get_all_states();
+        let circuit_states = self.circuit_breaker.snapshot();
 
         let mut output = String::with_capacity(2048);
 
@@ -89,21 +187,247 @@ impl SharedState {
         output
             .push_str("# HELP mik_circuit_breaker_state Circuit breaker state per module (0=closed, 1=open, 2=half-open)\n");
         output.push_str("# TYPE mik_circuit_breaker_state gauge\n");
-        for (module, state) in &circuit_states {
-            let state_value = match state.as_str() {
+        for circuit in &circuit_states {
+            let state_value = match circuit.state {
                 "open" => 1,
                 "half_open" => 2,
                 _ => 0, // closed or unknown
             };
             let _ = writeln!(
                 output,
-                "mik_circuit_breaker_state{{module=\"{module}\"}} {state_value}"
+                "mik_circuit_breaker_state{{module=\"{}\"}} {state_value}",
+                circuit.key
+            );
+        }
+        if !circuit_states.is_empty() {
+            output.push('\n');
+        }
+
+        output.push_str(
+            "# HELP mik_circuit_breaker_failures Consecutive failures counted by the circuit breaker per module\n",
+        );
+        output.push_str("# TYPE mik_circuit_breaker_failures gauge\n");
+        for circuit in &circuit_states {
+            let _ = writeln!(
+                output,
+                "mik_circuit_breaker_failures{{module=\"{}\"}} {}",
+                circuit.key, circuit.failure_count
             );
         }
         if !circuit_states.is_empty() {
             output.push('\n');
         }
 
+        output.push_str("# HELP mik_cache_capacity_entries Maximum number of cached modules\n");
+        output.push_str("# TYPE mik_cache_capacity_entries gauge\n");
+        let _ = writeln!(
+            output,
+            "mik_cache_capacity_entries {}\n",
+            self.config.cache_size
+        );
+
+        output.push_str(
+            "# HELP mik_max_per_module_requests Maximum concurrent requests per module\n",
+        );
+        output.push_str("# TYPE mik_max_per_module_requests gauge\n");
+        let _ = writeln!(
+            output,
+            "mik_max_per_module_requests {}\n",
+            self.config.max_per_module_requests
+        );
+
+        let stats = &self.stats;
+        for (name, help, counter) in [
+            (
+                "mik_module_cache_hits_total",
+                "Module lookups served from the in-memory cache",
+                &stats.module_cache_hits,
+            ),
+            (
+                "mik_module_cache_misses_total",
+                "Module lookups that had to load from disk",
+                &stats.module_cache_misses,
+            ),
+            (
+                "mik_aot_cache_hits_total",
+                "Module loads served from the AOT compilation cache",
+                &stats.aot_cache_hits,
+            ),
+            (
+                "mik_aot_cache_misses_total",
+                "Module loads that required compilation",
+                &stats.aot_cache_misses,
+            ),
+            (
+                "mik_request_permit_waits_total",
+                "Requests that waited for a free concurrency permit",
+                &stats.permit_waits,
+            ),
+            (
+                "mik_requests_cancelled_total",
+                "WASM executions cancelled because the client disconnected",
+                &stats.cancelled_requests,
+            ),
+            (
+                "mik_execution_retries_total",
+                "WASM executions retried after instances were exhausted",
+                &stats.execution_retries,
+            ),
+        ] {
+            let _ = writeln!(output, "# HELP {name} {help}");
+            let _ = writeln!(output, "# TYPE {name} counter");
+            let _ = writeln!(output, "{name} {}\n", counter.load(Ordering::Relaxed));
+        }
+
+        output.push_str(
+            "# HELP mik_module_overload_total Requests rejected by the per-module concurrency limit\n",
+        );
+        output.push_str("# TYPE mik_module_overload_total counter\n");
+        let overloads = stats.module_overloads.lock();
+        for (module, count) in overloads.iter() {
+            let _ = writeln!(
+                output,
+                "mik_module_overload_total{{module=\"{module}\"}} {count}"
+            );
+        }
+        if !overloads.is_empty() {
+            output.push('\n');
+        }
+        drop(overloads);
+
+        if self.config.module_queue_depth > 0 {
+            output.push_str(
+                "# HELP mik_module_queue_depth Requests waiting for a module at its concurrency limit\n",
+            );
+            output.push_str("# TYPE mik_module_queue_depth gauge\n");
+            for (module, queued) in self.module_queue_lengths() {
+                let _ = writeln!(
+                    output,
+                    "mik_module_queue_depth{{module=\"{module}\"}} {queued}"
+                );
+            }
+            output.push('\n');
+        }
+
+        if let Some(limits) = &self.adaptive_limits {
+            output.push_str(
+                "# HELP mik_module_concurrency_limit Adaptive concurrency limit per module\n",
+            );
+            output.push_str("# TYPE mik_module_concurrency_limit gauge\n");
+            for (module, limit) in limits.limits() {
+                let _ = writeln!(
+                    output,
+                    "mik_module_concurrency_limit{{module=\"{module}\"}} {limit}"
+                );
+            }
+            output.push('\n');
+        }
+
+        output.push_str("# HELP mik_alias_requests_total Requests served through a route alias\n");
+        output.push_str("# TYPE mik_alias_requests_total counter\n");
+        let alias_requests = stats.alias_requests.lock();
+        for (alias, count) in alias_requests.iter() {
+            let (module, deprecated) = self
+                .config
+                .aliases
+                .get(alias)
+                .map_or(("", false), |a| (a.module_name(), a.deprecated));
+            let _ = writeln!(
+                output,
+                "mik_alias_requests_total{{alias=\"{alias}\",module=\"{module}\",deprecated=\"{deprecated}\"}} {count}"
+            );
+        }
+        if !alias_requests.is_empty() {
+            output.push('\n');
+        }
+        drop(alias_requests);
+
+        let egress = self.egress.usage();
+        output.push_str("# HELP mik_egress_bytes_total Outgoing HTTP body bytes per module\n");
+        output.push_str("# TYPE mik_egress_bytes_total counter\n");
+        for (module, usage) in &egress {
+            for (direction, bytes) in [
+                ("sent", usage.sent_bytes),
+                ("received", usage.received_bytes),
+            ] {
+                let _ = writeln!(
+                    output,
+                    "mik_egress_bytes_total{{module=\"{module}\",direction=\"{direction}\"}} {bytes}"
+                );
+            }
+        }
+        output.push_str(
+            "# HELP mik_egress_blocked_total Outgoing HTTP requests rejected by the egress quota\n",
+        );
+        output.push_str("# TYPE mik_egress_blocked_total counter\n");
+        for (module, usage) in &egress {
+            let _ = writeln!(
+                output,
+                "mik_egress_blocked_total{{module=\"{module}\"}} {}",
+                usage.blocked
+            );
+        }
+        if !egress.is_empty() {
+            output.push('\n');
+        }
+
+        let tenants = self.usage.usage();
+        let counters: [(&str, &str, TenantCounter); 4] = [
+            (
+                "mik_tenant_requests_total",
+                "Executions of a tenant's modules",
+                |u| u.requests as f64,
+            ),
+            (
+                "mik_tenant_errors_total",
+                "Failed or cancelled executions of a tenant's modules",
+                |u| u.errors as f64,
+            ),
+            (
+                "mik_tenant_execution_seconds_total",
+                "Execution time of a tenant's modules",
+                |u| u.execution_time.as_secs_f64(),
+            ),
+            (
+                "mik_tenant_fuel_consumed_total",
+                "Fuel consumed by a tenant's modules",
+                |u| u.fuel_consumed as f64,
+            ),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(output, "# HELP {name} {help}");
+            let _ = writeln!(output, "# TYPE {name} counter");
+            for (tenant, usage) in &tenants {
+                let _ = writeln!(output, "{name}{{tenant=\"{tenant}\"}} {}", value(usage));
+            }
+        }
+        output
+            .push_str("# HELP mik_tenant_egress_bytes_total Outgoing HTTP body bytes per tenant\n");
+        output.push_str("# TYPE mik_tenant_egress_bytes_total counter\n");
+        for (tenant, usage) in &tenants {
+            for (direction, bytes) in [
+                ("sent", usage.egress_sent_bytes),
+                ("received", usage.egress_received_bytes),
+            ] {
+                let _ = writeln!(
+                    output,
+                    "mik_tenant_egress_bytes_total{{tenant=\"{tenant}\",direction=\"{direction}\"}} {bytes}"
+                );
+            }
+        }
+        if !tenants.is_empty() {
+            output.push('\n');
+        }
+
+        if let Some(nn) = &self.nn {
+            output.push_str("# HELP mik_nn_model_bytes Size of loaded wasi-nn models in bytes\n");
+            output.push_str("# TYPE mik_nn_model_bytes gauge\n");
+            for (model, bytes) in nn.sizes() {
+                let _ = writeln!(output, "mik_nn_model_bytes{{model=\"{model}\"}} {bytes}");
+            }
+            output.push('\n');
+        }
+
         // Memory usage (if available)
         if let Some(mem) = get_memory_usage() {
             output.push_str("# HELP mik_memory_bytes Process memory usage in bytes\n");
@@ -0,0 +1,298 @@
+//! Modules pulled from OCI registries on first request (`registry` feature).
+//!
+//! `[server.oci_modules]` maps module names to references such as
+//! `oci://ghcr.io/org/handler:1.2`. A module that isn't in the modules
+//! directory but has a reference is pulled the first time it is requested:
+//! the manifest is checked against a pinned `@sha256:` digest, the wasm layer
+//! against the digest in the manifest, and the layer is stored in the blob
+//! cache shared with `mik pull` (`~/.cache/mik/oci/blobs/{algo}/{hash}`).
+//! Later loads in the same process reuse the blob without contacting the
+//! registry, and after a restart only the manifest is fetched again.
+//!
+//! Registries are reached over HTTPS, except `localhost` and `127.0.0.1`.
+//! `MIK_OCI_USERNAME` and `MIK_OCI_PASSWORD` are sent as basic credentials
+//! when set, otherwise pulls are anonymous.
+
+use crate::runtime::error;
+use crate::runtime::host_config::HostConfig;
+use crate::runtime::security;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Username for registry basic auth.
+pub const OCI_USERNAME_ENV: &str = "MIK_OCI_USERNAME";
+/// Password (or token) for registry basic auth.
+pub const OCI_PASSWORD_ENV: &str = "MIK_OCI_PASSWORD";
+
+/// Modules served from OCI references, by module name.
+pub(crate) struct OciModules {
+    references: BTreeMap<String, String>,
+    /// Blobs pulled by this process. Pulls are serialized by this lock, so a
+    /// module requested concurrently is only downloaded once.
+    pulled: tokio::sync::Mutex<HashMap<String, PathBuf>>,
+}
+
+impl OciModules {
+    /// Check the references in `config.oci_modules`, if any.
+    pub(crate) fn new(config: &HostConfig) -> Result<Option<Self>> {
+        if config.oci_modules.is_empty() {
+            return Ok(None);
+        }
+        if !cfg!(feature = "registry") {
+            anyhow::bail!(
+                "[server.oci_modules] requires mik to be built with the registry feature"
+            );
+        }
+        for (name, reference) in &config.oci_modules {
+            security::sanitize_module_name(name)
+                .map_err(|e| anyhow::anyhow!("oci_modules: invalid module name '{name}': {e}"))?;
+            #[cfg(feature = "registry")]
+            parse_reference(reference)?;
+            info!("OCI module '{}': {}", name, reference);
+        }
+        Ok(Some(Self {
+            references: config.oci_modules.clone(),
+            pulled: tokio::sync::Mutex::default(),
+        }))
+    }
+
+    /// Path of the wasm for `name`, pulled on first use. `None` if the
+    /// module has no OCI reference.
+    pub(crate) async fn fetch(&self, name: &str) -> Result<Option<PathBuf>> {
+        let Some(reference) = self.references.get(name) else {
+            return Ok(None);
+        };
+        let mut pulled = self.pulled.lock().await;
+        if let Some(path) = pulled.get(name) {
+            return Ok(Some(path.clone()));
+        }
+        info!("Pulling module '{}' from {}", name, reference);
+        let path = pull(reference).await.map_err(|e| {
+            error::Error::module_load_failed(name, format!("failed to pull {reference}: {e:#}"))
+                .into_anyhow()
+        })?;
+        pulled.insert(name.to_string(), path.clone());
+        Ok(Some(path))
+    }
+}
+
+/// Parse an OCI reference, with or without the `oci://` scheme.
+#[cfg(feature = "registry")]
+fn parse_reference(reference: &str) -> Result<oci_client::Reference> {
+    reference
+        .strip_prefix("oci://")
+        .unwrap_or(reference)
+        .parse()
+        .with_context(|| format!("Invalid OCI reference: {reference}"))
+}
+
+/// Pull the wasm layer of `reference` into the blob cache.
+#[cfg(feature = "registry")]
+async fn pull(reference: &str) -> Result<PathBuf> {
+    use crate::reliability::retry::{RetryConfig, retry_anyhow};
+    use oci_client::client::{ClientConfig, ClientProtocol};
+    use oci_client::secrets::RegistryAuth;
+
+    let reference = parse_reference(reference)?;
+    let registry = reference.resolve_registry().to_string();
+    let host = registry.split(':').next().unwrap_or_default();
+    let protocol = if host == "localhost" || host == "127.0.0.1" {
+        ClientProtocol::HttpsExcept(vec![registry])
+    } else {
+        ClientProtocol::Https
+    };
+    let client = oci_client::Client::new(ClientConfig {
+        protocol,
+        ..ClientConfig::default()
+    });
+    let auth = match (
+        std::env::var(OCI_USERNAME_ENV),
+        std::env::var(OCI_PASSWORD_ENV),
+    ) {
+        (Ok(username), Ok(password)) => RegistryAuth::Basic(username, password),
+        _ => RegistryAuth::Anonymous,
+    };
+
+    // The client checks the manifest against a digest pinned in the reference
+    let (manifest, _digest) = retry_anyhow(RetryConfig::network(), "oci_pull_manifest", || {
+        let (client, reference, auth) = (client.clone(), reference.clone(), auth.clone());
+        async move {
+            client
+                .pull_manifest(&reference, &auth)
+                .await
+                .context("Failed to pull manifest")
+        }
+    })
+    .await?;
+    let layer = wasm_layer(&manifest)?.clone();
+
+    if let Some(path) = cached_blob(&layer.digest) {
+        return Ok(path);
+    }
+
+    // The client checks the blob against the layer digest
+    let data = retry_anyhow(RetryConfig::network(), "oci_pull_blob", || {
+        let (client, reference, layer) = (client.clone(), reference.clone(), layer.clone());
+        async move {
+            let mut data = Vec::new();
+            client
+                .pull_blob(&reference, &layer, &mut data)
+                .await
+                .context("Failed to pull blob")?;
+            Ok(data)
+        }
+    })
+    .await?;
+    cache_blob(&layer.digest, &data)
+}
+
+#[cfg(not(feature = "registry"))]
+async fn pull(_reference: &str) -> Result<PathBuf> {
+    anyhow::bail!("OCI modules require mik to be built with the registry feature")
+}
+
+/// The layer holding the component: the first wasm or octet-stream layer,
+/// or the first layer.
+#[cfg(feature = "registry")]
+pub(crate) fn wasm_layer(
+    manifest: &oci_client::manifest::OciManifest,
+) -> Result<&oci_client::manifest::OciDescriptor> {
+    let layers = match manifest {
+        oci_client::manifest::OciManifest::Image(img) => &img.layers,
+        oci_client::manifest::OciManifest::ImageIndex(_) => {
+            anyhow::bail!("Image index not supported, expected single image");
+        },
+    };
+    layers
+        .iter()
+        .find(|l| {
+            l.media_type.contains("wasm")
+                || l.media_type.contains("octet-stream")
+                || l.media_type.is_empty()
+        })
+        .or_else(|| layers.first())
+        .ok_or_else(|| anyhow::anyhow!("No layers found in manifest"))
+}
+
+/// Get the OCI cache directory for content-addressable storage.
+fn blob_cache_dir() -> Result<PathBuf> {
+    let cache_dir = dirs::cache_dir()
+        .ok_or_else(|| anyhow::anyhow!("Failed to determine cache directory"))?
+        .join("mik")
+        .join("oci")
+        .join("blobs");
+    Ok(cache_dir)
+}
+
+/// Get cached blob path from digest.
+fn blob_path(digest: &str) -> Result<PathBuf> {
+    // Digest format: "sha256:abc123..."
+    let (algo, hash) = digest
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("Invalid digest format: {digest}"))?;
+    if !algo.chars().all(|c| c.is_ascii_alphanumeric())
+        || !hash.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        anyhow::bail!("Invalid digest format: {digest}");
+    }
+    Ok(blob_cache_dir()?.join(algo).join(hash))
+}
+
+/// Check if a blob is cached and matches its digest, and return its path.
+///
+/// Entries that don't match their digest are removed.
+pub(crate) fn cached_blob(digest: &str) -> Option<PathBuf> {
+    let path = blob_path(digest).ok().filter(|p| p.exists())?;
+    let data = fs::read(&path).ok()?;
+    if let Err(e) = verify_digest(digest, &data) {
+        tracing::warn!("Discarding cached blob: {e}");
+        let _ = fs::remove_file(&path);
+        return None;
+    }
+    Some(path)
+}
+
+/// Save a blob to the cache.
+pub(crate) fn cache_blob(digest: &str, data: &[u8]) -> Result<PathBuf> {
+    let path = blob_path(digest)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, data).with_context(|| format!("Failed to cache {}", path.display()))?;
+    Ok(path)
+}
+
+/// Check that `data` hashes to `digest` (`sha256:<hex>` or `sha512:<hex>`).
+pub(crate) fn verify_digest(digest: &str, data: &[u8]) -> Result<()> {
+    let (algo, expected) = digest
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("Invalid digest format: {digest}"))?;
+    let actual = match algo {
+        "sha256" => hex::encode(Sha256::digest(data)),
+        "sha512" => hex::encode(Sha512::digest(data)),
+        _ => anyhow::bail!("Unsupported digest algorithm: {algo}"),
+    };
+    if !actual.eq_ignore_ascii_case(expected) {
+        anyhow::bail!("Digest mismatch: expected {digest}, got {algo}:{actual}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_digest() {
+        let digest = format!("sha256:{}", hex::encode(Sha256::digest(b"component")));
+        assert!(verify_digest(&digest, b"component").is_ok());
+
+        let err = verify_digest(&digest, b"tampered").unwrap_err();
+        assert!(err.to_string().contains("Digest mismatch"), "{err}");
+
+        assert!(verify_digest("md5:abc", b"component").is_err());
+        assert!(verify_digest("no-algorithm", b"component").is_err());
+    }
+
+    #[test]
+    fn test_blob_path_rejects_traversal() {
+        assert!(blob_path("sha256:abc").is_ok());
+        assert!(blob_path("sha256:../../etc/passwd").is_err());
+        assert!(blob_path("abc").is_err());
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_entries() {
+        let mut config = HostConfig::default();
+        assert!(OciModules::new(&config).unwrap().is_none());
+
+        config
+            .oci_modules
+            .insert("../evil".into(), "oci://ghcr.io/org/handler:1.2".into());
+        assert!(OciModules::new(&config).is_err());
+    }
+
+    #[cfg(feature = "registry")]
+    #[test]
+    fn test_parse_reference() {
+        let reference = parse_reference("oci://ghcr.io/org/handler:1.2").unwrap();
+        assert_eq!(reference.registry(), "ghcr.io");
+        assert_eq!(reference.repository(), "org/handler");
+        assert_eq!(reference.tag(), Some("1.2"));
+        assert!(parse_reference("oci://Not A Reference").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_unknown_module() {
+        let mut config = HostConfig::default();
+        config
+            .oci_modules
+            .insert("handler".into(), "oci://ghcr.io/org/handler:1.2".into());
+        let modules = OciModules::new(&config).unwrap().unwrap();
+        assert!(modules.fetch("other").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,253 @@
+//! PROXY protocol (v1 and v2) for connections from a load balancer.
+//!
+//! With [`proxy_protocol`](crate::runtime::HostConfig::proxy_protocol)
+//! enabled, every connection must start with a PROXY protocol header, as sent
+//! by `HAProxy` (`send-proxy` / `send-proxy-v2`), AWS NLB, or nginx
+//! (`proxy_protocol on`). The source address in the header replaces the peer
+//! address of the connection for logging, `mik:request-info`, and
+//! `X-Forwarded-For` trust decisions.
+//!
+//! Only the load balancer may be able to reach such a listener: a client
+//! connecting directly could claim any address.
+//!
+//! See <https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt>.
+
+use anyhow::{Context, Result, bail};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Signature opening a v2 header.
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+/// Prefix of a v1 header.
+const V1_PREFIX: &[u8; 6] = b"PROXY ";
+
+/// Maximum v1 header length, including the trailing CRLF.
+const V1_MAX_LEN: usize = 107;
+
+/// Read the PROXY protocol header at the start of `stream`.
+///
+/// Consumes exactly the header, leaving the HTTP request in the stream.
+/// Returns the original client address, or `None` for health checks the
+/// proxy sends on its own behalf (v1 `UNKNOWN`, v2 `LOCAL`, or a
+/// non-IP address family).
+///
+/// # Errors
+///
+/// Returns an error if the stream does not start with a valid header.
+pub async fn read_header<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Option<SocketAddr>> {
+    let mut prefix = [0u8; 6];
+    stream
+        .read_exact(&mut prefix)
+        .await
+        .context("Connection closed before PROXY header")?;
+
+    if &prefix == V1_PREFIX {
+        read_v1(stream).await
+    } else if prefix == V2_SIGNATURE[..6] {
+        read_v2(stream).await
+    } else {
+        bail!("Connection did not start with a PROXY protocol header")
+    }
+}
+
+/// Read the rest of a v1 header (`TCP4 <src> <dst> <sport> <dport>\r\n`).
+async fn read_v1<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Option<SocketAddr>> {
+    // Byte by byte, so nothing after the CRLF is consumed
+    let mut line = Vec::with_capacity(V1_MAX_LEN);
+    line.extend_from_slice(V1_PREFIX);
+    while !line.ends_with(b"\r\n") {
+        if line.len() >= V1_MAX_LEN {
+            bail!("PROXY v1 header too long");
+        }
+        line.push(
+            stream
+                .read_u8()
+                .await
+                .context("Truncated PROXY v1 header")?,
+        );
+    }
+
+    let line = std::str::from_utf8(&line[V1_PREFIX.len()..line.len() - 2])
+        .context("PROXY v1 header is not ASCII")?;
+    parse_v1(line)
+}
+
+fn parse_v1(line: &str) -> Result<Option<SocketAddr>> {
+    let fields: Vec<&str> = line.split(' ').collect();
+    match fields.as_slice() {
+        ["UNKNOWN", ..] => Ok(None),
+        [family @ ("TCP4" | "TCP6"), src, _dst, sport, _dport] => {
+            let ip: IpAddr = src.parse().context("Invalid PROXY v1 source address")?;
+            if ip.is_ipv4() != (*family == "TCP4") {
+                bail!("PROXY v1 source address does not match {family}");
+            }
+            let port: u16 = sport.parse().context("Invalid PROXY v1 source port")?;
+            Ok(Some(SocketAddr::new(ip, port)))
+        },
+        _ => bail!("Malformed PROXY v1 header"),
+    }
+}
+
+/// Read the rest of a v2 header after the first 6 signature bytes.
+async fn read_v2<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Option<SocketAddr>> {
+    let mut header = [0u8; 10];
+    stream
+        .read_exact(&mut header)
+        .await
+        .context("Truncated PROXY v2 header")?;
+    if header[..6] != V2_SIGNATURE[6..] {
+        bail!("Invalid PROXY v2 signature");
+    }
+
+    let version_command = header[6];
+    let family = header[7];
+    let len = usize::from(u16::from_be_bytes([header[8], header[9]]));
+
+    let mut addresses = vec![0u8; len];
+    stream
+        .read_exact(&mut addresses)
+        .await
+        .context("Truncated PROXY v2 addresses")?;
+
+    if version_command >> 4 != 2 {
+        bail!("Unsupported PROXY protocol version");
+    }
+    match version_command & 0x0F {
+        // LOCAL: the proxy's own connection, e.g. a health check
+        0x0 => return Ok(None),
+        0x1 => {},
+        _ => bail!("Unsupported PROXY v2 command"),
+    }
+
+    parse_v2_addresses(family >> 4, &addresses)
+}
+
+fn parse_v2_addresses(family: u8, addresses: &[u8]) -> Result<Option<SocketAddr>> {
+    match family {
+        // AF_INET: src(4) dst(4) sport(2) dport(2)
+        0x1 => {
+            let Some(block) = addresses.get(..12) else {
+                bail!("PROXY v2 IPv4 address block too short");
+            };
+            let ip = Ipv4Addr::new(block[0], block[1], block[2], block[3]);
+            let port = u16::from_be_bytes([block[8], block[9]]);
+            Ok(Some(SocketAddr::new(IpAddr::V4(ip), port)))
+        },
+        // AF_INET6: src(16) dst(16) sport(2) dport(2)
+        0x2 => {
+            let Some(block) = addresses.get(..36) else {
+                bail!("PROXY v2 IPv6 address block too short");
+            };
+            let mut src = [0u8; 16];
+            src.copy_from_slice(&block[..16]);
+            let port = u16::from_be_bytes([block[32], block[33]]);
+            Ok(Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::from(src)), port)))
+        },
+        // AF_UNSPEC or AF_UNIX: no IP to report
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    async fn read(input: &[u8]) -> (Result<Option<SocketAddr>>, Vec<u8>) {
+        let mut stream = input;
+        let result = read_header(&mut stream).await;
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).await.unwrap();
+        (result, rest)
+    }
+
+    fn v2(command: u8, family: u8, addresses: &[u8]) -> Vec<u8> {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.push(0x20 | command);
+        header.push(family);
+        header.extend_from_slice(&u16::try_from(addresses.len()).unwrap().to_be_bytes());
+        header.extend_from_slice(addresses);
+        header
+    }
+
+    #[tokio::test]
+    async fn test_v1_tcp4() {
+        let (addr, rest) =
+            read(b"PROXY TCP4 203.0.113.7 10.0.0.1 51234 3000\r\nGET / HTTP/1.1\r\n").await;
+        assert_eq!(addr.unwrap(), Some("203.0.113.7:51234".parse().unwrap()));
+        assert_eq!(rest, b"GET / HTTP/1.1\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_v1_tcp6_and_unknown() {
+        let (addr, _) = read(b"PROXY TCP6 2001:db8::1 2001:db8::2 443 3000\r\n").await;
+        assert_eq!(addr.unwrap(), Some("[2001:db8::1]:443".parse().unwrap()));
+
+        let (addr, rest) = read(b"PROXY UNKNOWN\r\nGET").await;
+        assert_eq!(addr.unwrap(), None);
+        assert_eq!(rest, b"GET");
+    }
+
+    #[tokio::test]
+    async fn test_v1_rejects_malformed() {
+        assert!(read(b"PROXY TCP4 203.0.113.7\r\n").await.0.is_err());
+        assert!(read(b"PROXY TCP4 2001:db8::1 ::1 1 2\r\n").await.0.is_err());
+        assert!(
+            read(b"PROXY TCP4 1.2.3.4 5.6.7.8 99999 1\r\n")
+                .await
+                .0
+                .is_err()
+        );
+        assert!(
+            read(&[b"PROXY ".as_slice(), &[b'A'; 200]].concat())
+                .await
+                .0
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_header_is_rejected() {
+        assert!(read(b"GET / HTTP/1.1\r\n\r\n").await.0.is_err());
+        assert!(read(b"PRO").await.0.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_v2_proxy_ipv4() {
+        let mut input = v2(
+            0x1,
+            0x11,
+            &[198, 51, 100, 9, 10, 0, 0, 1, 0x1F, 0x90, 0x0B, 0xB8],
+        );
+        input.extend_from_slice(b"GET");
+        let (addr, rest) = read(&input).await;
+        assert_eq!(addr.unwrap(), Some("198.51.100.9:8080".parse().unwrap()));
+        assert_eq!(rest, b"GET");
+    }
+
+    #[tokio::test]
+    async fn test_v2_proxy_ipv6_with_tlvs() {
+        let mut addresses = Ipv6Addr::LOCALHOST.octets().to_vec();
+        addresses.extend_from_slice(&[0; 16]);
+        addresses.extend_from_slice(&[0x00, 0x50, 0x0B, 0xB8]);
+        // Trailing TLV (PP2_TYPE_NOOP) is skipped
+        addresses.extend_from_slice(&[0x04, 0x00, 0x01, 0x00]);
+        let (addr, rest) = read(&v2(0x1, 0x21, &addresses)).await;
+        assert_eq!(addr.unwrap(), Some("[::1]:80".parse().unwrap()));
+        assert!(rest.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_v2_local_and_unspec() {
+        let (addr, _) = read(&v2(0x0, 0x00, &[])).await;
+        assert_eq!(addr.unwrap(), None);
+        let (addr, _) = read(&v2(0x1, 0x00, &[])).await;
+        assert_eq!(addr.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_v2_rejects_short_addresses() {
+        assert!(read(&v2(0x1, 0x11, &[1, 2, 3])).await.0.is_err());
+    }
+}
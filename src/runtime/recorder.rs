@@ -0,0 +1,287 @@
+//! Request/response recording for regression testing.
+//!
+//! With a `record_dir` (`mik run --record <dir>`), every module request is
+//! written with its response as one JSON file in that directory, named
+//! `<unix-ms>-<uuid>.json` so a listing sorts in arrival order:
+//!
+//! ```json
+//! {
+//!   "recorded_at": "2025-01-01T00:00:00+00:00",
+//!   "module": "orders",
+//!   "request": {
+//!     "method": "POST",
+//!     "uri": "/run/orders/?page=2",
+//!     "headers": { "authorization": "[redacted]", "content-type": "application/json" },
+//!     "body": "{\"id\":1}"
+//!   },
+//!   "response": { "status": 200, "headers": { "content-type": "application/json" }, "body": "..." }
+//! }
+//! ```
+//!
+//! Recordings are sanitized: credentials and cookies are replaced with
+//! `[redacted]` ([`REDACTED_HEADERS`]). Bodies are stored as text when they
+//! are UTF-8 and as `body_base64` otherwise. A request whose handler failed
+//! has no `response`, and the failure in `error` instead.
+//!
+//! `mik replay <dir>` sends the recorded requests to another build and diffs
+//! the responses.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::Utc;
+use hyper::HeaderMap;
+use hyper::body::Bytes;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Headers whose values are never written to disk.
+pub const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+/// Value recorded in place of a redacted header.
+pub const REDACTED: &str = "[redacted]";
+
+/// One recorded request and what the runtime answered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    /// When the request completed (RFC 3339).
+    pub recorded_at: String,
+    /// Module that handled the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    pub request: RecordedRequest,
+    /// The response, or `None` if the handler failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<RecordedResponse>,
+    /// Why the handler failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A request as received, before routing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query as received.
+    pub uri: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub body: RecordedBody,
+}
+
+/// A response as sent, before compression.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub body: RecordedBody,
+}
+
+/// A body, as text when it is UTF-8.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedBody {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_base64: Option<String>,
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok("") => Self::default(),
+            Ok(text) => Self {
+                body: Some(text.to_string()),
+                body_base64: None,
+            },
+            Err(_) => Self {
+                body: None,
+                body_base64: Some(BASE64.encode(bytes)),
+            },
+        }
+    }
+
+    /// The body's bytes.
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        match (&self.body, &self.body_base64) {
+            (Some(text), _) => Ok(text.clone().into_bytes()),
+            (None, Some(encoded)) => BASE64.decode(encoded).context("Invalid body_base64"),
+            (None, None) => Ok(Vec::new()),
+        }
+    }
+}
+
+impl RecordedRequest {
+    pub(crate) fn new(method: &str, uri: &str, headers: &HeaderMap, body: &Bytes) -> Self {
+        Self {
+            method: method.to_string(),
+            uri: uri.to_string(),
+            headers: sanitize(headers),
+            body: RecordedBody::new(body),
+        }
+    }
+}
+
+impl RecordedResponse {
+    pub(crate) fn new(status: u16, headers: &HeaderMap, body: &[u8]) -> Self {
+        Self {
+            status,
+            headers: sanitize(headers),
+            body: RecordedBody::new(body),
+        }
+    }
+}
+
+/// Headers by lowercase name, repeated values joined with `, ` and
+/// credentials redacted.
+fn sanitize(headers: &HeaderMap) -> BTreeMap<String, String> {
+    let mut sanitized = BTreeMap::new();
+    for name in headers.keys() {
+        let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+            REDACTED.to_string()
+        } else {
+            headers
+                .get_all(name)
+                .iter()
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        sanitized.insert(name.as_str().to_string(), value);
+    }
+    sanitized
+}
+
+/// Write a recording to `dir` in the background, warning on failure.
+pub(crate) fn record(
+    dir: &Path,
+    module: Option<&str>,
+    request: RecordedRequest,
+    outcome: Result<RecordedResponse, String>,
+) {
+    let now = Utc::now();
+    let (response, error) = match outcome {
+        Ok(response) => (Some(response), None),
+        Err(error) => (None, Some(error)),
+    };
+    let recording = Recording {
+        recorded_at: now.to_rfc3339(),
+        module: module.map(str::to_string),
+        request,
+        response,
+        error,
+    };
+    let path = dir.join(format!(
+        "{:013}-{}.json",
+        now.timestamp_millis(),
+        Uuid::new_v4()
+    ));
+    tokio::spawn(async move {
+        let result = match serde_json::to_vec_pretty(&recording) {
+            Ok(json) => tokio::fs::write(&path, json).await.map_err(Into::into),
+            Err(e) => Err(anyhow::Error::from(e)),
+        };
+        if let Err(e) = result {
+            warn!("Failed to write recording {}: {}", path.display(), e);
+        }
+    });
+}
+
+/// Recordings in `dir`, oldest first.
+pub fn load_dir(dir: &Path) -> Result<Vec<(PathBuf, Recording)>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let json = std::fs::read(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let recording = serde_json::from_slice(&json)
+                .with_context(|| format!("Invalid recording {}", path.display()))?;
+            Ok((path, recording))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_redacts_credentials() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer secret".parse().unwrap());
+        headers.insert("x-api-key", "key".parse().unwrap());
+        headers.append("accept", "text/plain".parse().unwrap());
+        headers.append("accept", "application/json".parse().unwrap());
+
+        let sanitized = sanitize(&headers);
+        assert_eq!(sanitized["authorization"], REDACTED);
+        assert_eq!(sanitized["x-api-key"], REDACTED);
+        assert_eq!(sanitized["accept"], "text/plain, application/json");
+    }
+
+    #[test]
+    fn test_body_round_trip() {
+        for bytes in [&b""[..], b"{\"a\":1}", &[0xff, 0x00, 0x10]] {
+            let body = RecordedBody::new(bytes);
+            let json = serde_json::to_string(&body).unwrap();
+            let parsed: RecordedBody = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed.bytes().unwrap(), bytes);
+        }
+        assert!(RecordedBody::new(&[0xff]).body_base64.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_record_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("cookie", "session=1".parse().unwrap());
+        let request = RecordedRequest::new("POST", "/run/echo/?x=1", &headers, &Bytes::from("hi"));
+
+        record(
+            dir.path(),
+            Some("echo"),
+            request.clone(),
+            Ok(RecordedResponse::new(200, &HeaderMap::new(), b"hi")),
+        );
+        record(dir.path(), None, request, Err("Handler call failed".into()));
+
+        let mut recordings = Vec::new();
+        for _ in 0..100 {
+            recordings = load_dir(dir.path()).unwrap_or_default();
+            if recordings.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(recordings.len(), 2);
+        let ok = recordings
+            .iter()
+            .map(|(_, recording)| recording)
+            .find(|recording| recording.response.is_some())
+            .unwrap();
+        assert_eq!(ok.module.as_deref(), Some("echo"));
+        assert_eq!(ok.request.uri, "/run/echo/?x=1");
+        assert_eq!(ok.request.headers["cookie"], REDACTED);
+        assert_eq!(ok.response.as_ref().unwrap().body.bytes().unwrap(), b"hi");
+        assert!(recordings.iter().any(|(_, r)| r.error.is_some()));
+    }
+}
@@ -10,7 +10,7 @@
 //! - [`is_http_host_allowed`] - Security utility for validating HTTP hosts
 
 // Re-export circuit breaker
-pub use crate::reliability::CircuitBreaker;
+pub use crate::reliability::{CircuitBreaker, CircuitBreakerConfig};
 
 // Re-export security utilities
 pub use crate::reliability::security::is_http_host_allowed;
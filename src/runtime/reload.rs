@@ -0,0 +1,349 @@
+//! Reloading part of the configuration without restarting the server.
+//!
+//! Sending `SIGHUP` to a running server (or `POST /_mik/reload` on the
+//! gateway) re-reads its mik.toml, with the active profile and `MIK_<SETTING>`
+//! environment variables applied, and swaps in the settings that can change
+//! while serving:
+//!
+//! - `http_allowed`
+//! - `static`
+//! - `log_level`, when logging to stdout without `RUST_LOG`
+//! - `[server.tenant_limits]` and `[server.egress_quotas]`
+//! - `[server.circuit_breakers]`, keeping the state of each circuit
+//!
+//! Compiled modules stay cached and requests in flight finish with the
+//! settings they started with. Other settings take effect on restart. A
+//! mik.toml that fails to parse or validate is rejected as a whole and the
+//! running settings are kept.
+
+use super::SharedState;
+use super::builder::RuntimeBuilder;
+use super::host::Host;
+use super::host_config::HostConfig;
+use crate::manifest::{CircuitBreakerPolicy, EgressQuota, TenantLimits};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use tracing::{info, warn};
+
+/// Applies a log filter (`RUST_LOG` syntax) to the process's subscriber.
+type LogFilterHandler = Box<dyn Fn(&str) -> Result<()> + Send + Sync>;
+
+/// Set once by whoever installed the subscriber (see [`set_log_filter_handler`]).
+static LOG_FILTER: OnceLock<LogFilterHandler> = OnceLock::new();
+
+/// Let reloads change the log filter with `handler`.
+///
+/// The runtime does not install a tracing subscriber itself, so `log_level`
+/// only reloads once the process registers how to change its filter. Only
+/// the first handler registered is kept.
+pub fn set_log_filter_handler(handler: impl Fn(&str) -> Result<()> + Send + Sync + 'static) {
+    let _ = LOG_FILTER.set(Box::new(handler));
+}
+
+/// The settings a reload can change, read by requests as they start.
+#[derive(Debug)]
+pub(crate) struct LiveConfig {
+    /// Allowed hosts for outgoing HTTP requests.
+    pub(crate) http_allowed: Arc<Vec<String>>,
+    /// Static files directory, when it exists.
+    pub(crate) static_dir: Option<PathBuf>,
+    pub(crate) log_level: Option<String>,
+    pub(crate) tenant_limits: BTreeMap<String, TenantLimits>,
+    pub(crate) egress_quotas: BTreeMap<String, EgressQuota>,
+    pub(crate) circuit_breakers: BTreeMap<String, CircuitBreakerPolicy>,
+}
+
+impl LiveConfig {
+    /// The reloadable settings of `config`.
+    pub(crate) fn new(config: &HostConfig) -> Self {
+        let static_dir = config.static_dir.clone().filter(|dir| {
+            if dir.is_dir() {
+                info!("Static files: {} -> /static/", dir.display());
+                true
+            } else {
+                warn!("Static directory not found: {}", dir.display());
+                false
+            }
+        });
+        Self {
+            http_allowed: Arc::new(config.http_allowed.clone()),
+            static_dir,
+            log_level: config.log_level.clone(),
+            tenant_limits: config.tenant_limits.clone(),
+            egress_quotas: config.egress_quotas.clone(),
+            circuit_breakers: config.circuit_breakers.clone(),
+        }
+    }
+
+    /// Limits for `tenant_id`, falling back to the `"*"` entry.
+    pub(crate) fn tenant_limits(&self, tenant_id: &str) -> TenantLimits {
+        self.tenant_limits
+            .get(tenant_id)
+            .or_else(|| self.tenant_limits.get("*"))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Outgoing HTTP quota for `module`, falling back to the `"*"` entry.
+    pub(crate) fn egress_quota(&self, module: &str) -> Option<EgressQuota> {
+        self.egress_quotas
+            .get(module)
+            .or_else(|| self.egress_quotas.get("*"))
+            .copied()
+    }
+}
+
+/// What a reload changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReloadReport {
+    /// mik.toml keys whose values changed, in the order they were applied.
+    pub changed: Vec<&'static str>,
+}
+
+impl SharedState {
+    /// The settings currently in effect for reloadable keys.
+    pub(crate) fn live(&self) -> Arc<LiveConfig> {
+        self.live.read().clone()
+    }
+
+    /// Re-read mik.toml and apply the reloadable settings that changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, with nothing applied, if the runtime was not loaded
+    /// from a mik.toml or the file is invalid.
+    pub(crate) fn reload(&self) -> Result<ReloadReport> {
+        let path = self
+            .config
+            .manifest_path
+            .as_ref()
+            .context("Configuration was not loaded from a mik.toml")?;
+        let builder = RuntimeBuilder::new()
+            .from_manifest_file(path)?
+            .env_overrides()
+            .context("Invalid environment override")?;
+        let config = builder.config();
+        config
+            .validate()
+            .with_context(|| format!("Invalid configuration in {}", path.display()))?;
+
+        let current = self.live();
+        let mut next = LiveConfig::new(config);
+        let mut report = ReloadReport::default();
+
+        // The only step that can fail, so it goes first
+        if next.log_level != current.log_level {
+            if let Some(set_filter) = LOG_FILTER.get() {
+                set_filter(next.log_level.as_deref().unwrap_or("info"))
+                    .context("Failed to change the log filter")?;
+                report.changed.push("log_level");
+            } else {
+                warn!("log_level is not reloadable with RUST_LOG set, restart to change it");
+                next.log_level.clone_from(&current.log_level);
+            }
+        }
+        if next.http_allowed != current.http_allowed {
+            report.changed.push("http_allowed");
+        }
+        if next.static_dir != current.static_dir {
+            report.changed.push("static");
+        }
+        if next.tenant_limits != current.tenant_limits {
+            // Tenants get semaphores with their new limits on their next request
+            self.tenant_semaphores.lock().clear();
+            report.changed.push("tenant_limits");
+        }
+        if next.egress_quotas != current.egress_quotas {
+            report.changed.push("egress_quotas");
+        }
+        if next.circuit_breakers != current.circuit_breakers {
+            let (defaults, modules) = Host::circuit_breaker_configs(&next.circuit_breakers);
+            self.circuit_breaker.reconfigure(defaults, modules);
+            report.changed.push("circuit_breakers");
+        }
+        *self.live.write() = Arc::new(next);
+
+        if report.changed.is_empty() {
+            info!("Reloaded {}: no changes", path.display());
+        } else {
+            info!("Reloaded {}: {}", path.display(), report.changed.join(", "));
+        }
+        Ok(report)
+    }
+}
+
+/// Waits for reload requests (`SIGHUP`).
+///
+/// Never resolves on platforms without signal support.
+pub struct ReloadSignal {
+    #[cfg(unix)]
+    signal: Option<tokio::signal::unix::Signal>,
+}
+
+impl ReloadSignal {
+    /// Register the reload signal handler.
+    #[must_use]
+    pub fn new() -> Self {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{SignalKind, signal};
+            let signal = signal(SignalKind::hangup())
+                .inspect_err(|e| warn!(error = %e, "Failed to setup SIGHUP handler"))
+                .ok();
+            Self { signal }
+        }
+        #[cfg(not(unix))]
+        Self {}
+    }
+
+    /// Wait for the next reload request.
+    pub async fn recv(&mut self) {
+        #[cfg(unix)]
+        if let Some(signal) = &mut self.signal
+            && signal.recv().await.is_some()
+        {
+            return;
+        }
+        std::future::pending::<()>().await;
+    }
+}
+
+impl Default for ReloadSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Runtime;
+    use std::fs;
+
+    /// Minimal WASM module: magic number + version.
+    const WASM: [u8; 8] = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+
+    fn runtime(dir: &std::path::Path, server: &str) -> Runtime {
+        fs::create_dir_all(dir.join("modules")).unwrap();
+        fs::write(dir.join("modules/app.wasm"), WASM).unwrap();
+        let path = dir.join("mik.toml");
+        write_manifest(dir, server);
+        Runtime::builder()
+            .from_manifest_file(&path)
+            .unwrap()
+            .modules_dir(dir.join("modules"))
+            .build()
+            .unwrap()
+    }
+
+    fn write_manifest(dir: &std::path::Path, server: &str) {
+        fs::write(
+            dir.join("mik.toml"),
+            format!("[project]\nname = \"app\"\n\n[server]\n{server}"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_live_config_fallbacks() {
+        let mut config = HostConfig::default();
+        config.tenant_limits.insert(
+            "*".to_string(),
+            TenantLimits {
+                requests_per_second: Some(10),
+                ..Default::default()
+            },
+        );
+        config.egress_quotas.insert(
+            "api".to_string(),
+            EgressQuota {
+                daily_mb: Some(5),
+                monthly_mb: None,
+            },
+        );
+        let live = LiveConfig::new(&config);
+        assert_eq!(live.tenant_limits("acme").requests_per_second, Some(10));
+        assert_eq!(live.egress_quota("api").unwrap().daily_mb, Some(5));
+        assert!(live.egress_quota("other").is_none());
+    }
+
+    #[test]
+    fn test_reload_applies_changed_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = runtime(dir.path(), "http_allowed = [\"a.example.com\"]\n");
+        let shared = runtime.shared();
+        shared.circuit_breaker.record_failure("api");
+
+        let report = shared.reload().unwrap();
+        assert!(report.changed.is_empty());
+
+        fs::create_dir(dir.path().join("public")).unwrap();
+        write_manifest(
+            dir.path(),
+            &format!(
+                "http_allowed = [\"b.example.com\"]\nstatic = \"{}\"\nport = 9999\n\n\
+                 [server.circuit_breakers.api]\nfailure_threshold = 2\n\n\
+                 [server.tenant_limits.acme]\nrequests_per_second = 3\n",
+                dir.path().join("public").display()
+            ),
+        );
+        let report = shared.reload().unwrap();
+        assert_eq!(
+            report.changed,
+            [
+                "http_allowed",
+                "static",
+                "tenant_limits",
+                "circuit_breakers"
+            ]
+        );
+
+        let live = shared.live();
+        assert_eq!(*live.http_allowed, ["b.example.com"]);
+        assert!(live.static_dir.is_some());
+        assert_eq!(live.tenant_limits("acme").requests_per_second, Some(3));
+        assert_eq!(
+            shared.circuit_breaker.config_for("api").failure_threshold,
+            2
+        );
+        // The failure recorded before the reload still counts
+        shared.circuit_breaker.record_failure("api");
+        assert!(shared.circuit_breaker.is_open("api"));
+        // Settings that need a restart are left alone
+        assert_eq!(shared.config.port, runtime.port());
+        assert_ne!(runtime.port(), 9999);
+    }
+
+    #[test]
+    fn test_reload_rejects_invalid_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = runtime(dir.path(), "http_allowed = [\"a.example.com\"]\n");
+        let shared = runtime.shared();
+
+        write_manifest(
+            dir.path(),
+            "http_allowed = [\"b.example.com\"]\nexecution_timeout_secs = 0\n",
+        );
+        let err = shared.reload().unwrap_err();
+        assert!(
+            format!("{err:#}").contains("execution_timeout_secs"),
+            "{err:#}"
+        );
+        assert_eq!(*shared.live().http_allowed, ["a.example.com"]);
+
+        write_manifest(dir.path(), "http_allowed = [");
+        assert!(shared.reload().is_err());
+        assert_eq!(*shared.live().http_allowed, ["a.example.com"]);
+    }
+
+    #[test]
+    fn test_reload_without_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("app.wasm"), WASM).unwrap();
+        let runtime = Runtime::builder().modules_dir(dir.path()).build().unwrap();
+        assert!(runtime.shared().reload().is_err());
+    }
+}
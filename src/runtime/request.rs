@@ -339,6 +339,47 @@ impl Default for Response {
 // Conversions to/from hyper types
 // =============================================================================
 
+/// Convert a request whose body has already been collected.
+///
+/// Headers with non-UTF-8 values are skipped.
+impl<B: Into<Vec<u8>>> From<hyper::Request<B>> for Request {
+    fn from(request: hyper::Request<B>) -> Self {
+        let (parts, body) = request.into_parts();
+        Self {
+            method: parts.method.to_string(),
+            path: parts
+                .uri
+                .path_and_query()
+                .map_or_else(|| parts.uri.path().to_string(), ToString::to_string),
+            headers: parts
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    value
+                        .to_str()
+                        .ok()
+                        .map(|v| (name.to_string(), v.to_string()))
+                })
+                .collect(),
+            body: body.into(),
+        }
+    }
+}
+
+impl TryFrom<Response> for hyper::Response<Vec<u8>> {
+    type Error = hyper::http::Error;
+
+    fn try_from(response: Response) -> Result<Self, Self::Error> {
+        let mut builder = hyper::Response::builder().status(response.status);
+
+        for (name, value) in response.headers {
+            builder = builder.header(name, value);
+        }
+
+        builder.body(response.body)
+    }
+}
+
 impl TryFrom<Response> for hyper::Response<Full<Bytes>> {
     type Error = hyper::http::Error;
 
@@ -404,6 +445,29 @@ mod tests {
         assert_eq!(request.body, br#"{"name": "Alice"}"#);
     }
 
+    #[test]
+    fn test_http_conversions() {
+        let request = hyper::Request::post("http://localhost/run/api/users?page=2")
+            .header("Content-Type", "application/json")
+            .body(Bytes::from_static(b"{}"))
+            .unwrap();
+        let request = Request::from(request);
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/run/api/users?page=2");
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.body, b"{}");
+
+        let response = hyper::Response::<Vec<u8>>::try_from(
+            Response::created().with_header("Location", "/users/1"),
+        )
+        .unwrap();
+        assert_eq!(response.status(), 201);
+        assert_eq!(response.headers()["location"], "/users/1");
+
+        let invalid = Response::ok().with_header("bad header", "x");
+        assert!(hyper::Response::<Vec<u8>>::try_from(invalid).is_err());
+    }
+
     #[test]
     fn test_request_header_case_insensitive() {
         let request = Request::new("GET", "/").with_header("Content-Type", "text/plain");
@@ -7,14 +7,21 @@
 //! - Request path rewriting
 
 use crate::constants;
+use crate::runtime::build_overlay;
+use crate::runtime::cache::module_key;
 use crate::runtime::compression::{accepts_gzip, maybe_compress_response};
+use crate::runtime::dev_dashboard::{self, RecentRequest};
+use crate::runtime::dev_proxy;
 use crate::runtime::endpoints::{handle_health_endpoint, handle_metrics_endpoint};
 use crate::runtime::error::{self, Error};
 use crate::runtime::gateway::{self, MIK_API_PREFIX};
 use crate::runtime::host_state::HyperCompatibleBody;
 use crate::runtime::module_path::ModulePath;
+use crate::runtime::recorder::{self, RecordedRequest, RecordedResponse};
+use crate::runtime::request_info::{RequestInfo, resolve_client_ip};
 use crate::runtime::schema_handler;
 use crate::runtime::script;
+use crate::runtime::security;
 use crate::runtime::spans::{SpanBuilder, SpanCollector, SpanSummary};
 use crate::runtime::static_files::serve_static_file;
 use crate::runtime::trace_context::extract_trace_context;
@@ -27,9 +34,11 @@ use crate::runtime::{
 use anyhow::Result;
 use http_body_util::{BodyExt, Full, Limited};
 use hyper::body::Bytes;
+use hyper::header::HeaderValue;
 use hyper::{Request, Response, Uri};
 use percent_encoding::percent_decode_str;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::time::Instant;
@@ -40,10 +49,6 @@ use wasmtime::component::Component;
 /// Maximum allowed path length (prevents `DoS` via extremely long URLs).
 const MAX_PATH_LENGTH: usize = constants::MAX_PATH_LENGTH;
 
-/// Retry-After header value (seconds) for circuit breaker responses.
-/// Tells clients to wait 30 seconds before retrying a failed service.
-const CIRCUIT_BREAKER_RETRY_AFTER_SECS: &str = "30";
-
 /// Retry-After header value (seconds) for module overload responses.
 /// Tells clients to wait 5 seconds before retrying an overloaded module.
 const MODULE_OVERLOAD_RETRY_AFTER_SECS: &str = "5";
@@ -69,6 +74,7 @@ pub async fn handle_request(
 ) -> Result<Response<Full<Bytes>>> {
     let request_id = Uuid::new_v4();
     let start_time = Instant::now();
+    let _in_flight = shared.drain.track();
 
     // Extract or generate W3C Trace Context
     let trace_ctx = extract_trace_context(req.headers());
@@ -79,6 +85,7 @@ pub async fn handle_request(
 
     let method = req.method();
     let path = req.uri().path();
+    let client_ip = resolve_client_ip(remote_addr.ip(), req.headers(), &shared.trusted_proxies);
 
     let span = tracing::info_span!(
         "request",
@@ -86,7 +93,9 @@ pub async fn handle_request(
         trace_id = %trace_id,
         method = %method,
         path = %path,
-        remote_addr = %remote_addr
+        remote_addr = %remote_addr,
+        client_ip = %client_ip,
+        timeout_ms = tracing::field::Empty
     );
     let _enter = span.enter();
     info!("Request started");
@@ -102,7 +111,8 @@ pub async fn handle_request(
             &traceparent,
             start_time,
             client_accepts_gzip,
-        );
+        )
+        .await;
     }
     if path == METRICS_PATH {
         return handle_metrics_endpoint(
@@ -114,6 +124,17 @@ pub async fn handle_request(
         );
     }
 
+    // Development dashboard (`mik dev`)
+    if let Some(log_file) = &shared.config.dev_dashboard
+        && dev_dashboard::is_dashboard_path(path)
+    {
+        return dev_dashboard::handle(&shared, log_file, path).await;
+    }
+    let recorded = shared
+        .recent_requests
+        .is_some()
+        .then(|| (req.method().clone(), req.uri().path().to_string()));
+
     // Create span collector and root request span for timing data
     let span_collector = SpanCollector::new();
     let request_span = SpanBuilder::new("request");
@@ -140,6 +161,13 @@ pub async fn handle_request(
 
     // Collect and log span summary
     let spans = span_collector.collect();
+    if let (Some(recent), Some((method, path))) = (&shared.recent_requests, recorded) {
+        let status = result
+            .as_ref()
+            .map(|resp| resp.status().as_u16())
+            .map_err(ToString::to_string);
+        recent.push(RecentRequest::new(&method, &path, status, duration, &spans));
+    }
     if !spans.is_empty() {
         let summary = SpanSummary::new(&trace_id, duration.as_millis() as u64, spans);
         info!(
@@ -323,6 +351,10 @@ pub(crate) enum ModuleResolution {
         handler_path: String,
         module_name: Option<String>,
         module_permit: Option<tokio::sync::OwnedSemaphorePermit>,
+        /// Tenant concurrency permit, held for tenant modules.
+        tenant_permit: Option<tokio::sync::OwnedSemaphorePermit>,
+        /// Route alias the request came through, if any.
+        alias: Option<String>,
     },
     /// Early return with a response (error or not found).
     Response(Response<Full<Bytes>>),
@@ -350,6 +382,7 @@ pub(crate) async fn resolve_module(
             "No module specified. Use /run/<module>/",
         )?));
     }
+    let (module, alias) = shared.resolve_alias(module);
 
     // Single component mode: check if module matches
     if let (Some(comp), Some(expected_name)) =
@@ -361,6 +394,8 @@ pub(crate) async fn resolve_module(
                 handler_path,
                 module_name: Some(module),
                 module_permit: None,
+                tenant_permit: None,
+                alias,
             });
         }
         let err = error::Error::module_not_found(&module);
@@ -368,7 +403,16 @@ pub(crate) async fn resolve_module(
     }
 
     // Multi-module mode: load platform module
-    resolve_multi_module(shared, &module, handler_path).await
+    let mut resolution = resolve_multi_module(shared, &module, handler_path).await?;
+    match &mut resolution {
+        ModuleResolution::Success { alias: slot, .. } => *slot = alias,
+        ModuleResolution::Response(resp) => {
+            if let Some(alias) = &alias {
+                shared.add_alias_headers(alias, resp.headers_mut());
+            }
+        },
+    }
+    Ok(resolution)
 }
 
 /// Resolves a tenant module from `/tenant/<tenant-id>/<module>/*` path.
@@ -400,14 +444,89 @@ pub(crate) async fn resolve_tenant_module(
     };
 
     // Ensure it's actually a tenant path (has both tenant_id and module name)
-    if !module_path.is_tenant() {
+    let Some(tenant_id) = module_path.tenant_id() else {
         return Ok(ModuleResolution::Response(not_found(
             "Invalid tenant path. Use /tenant/<tenant-id>/<module>/",
         )?));
+    };
+
+    // Validate before any per-module state (breaker, semaphore) is created
+    if let Err(e) = security::sanitize_module_name(tenant_id) {
+        return Ok(ModuleResolution::Response(error_response(
+            &error::Error::InvalidRequest(format!("Invalid tenant ID '{tenant_id}': {e}")),
+        )?));
+    }
+    if let Err(e) = security::sanitize_module_name(module_path.name()) {
+        return Ok(ModuleResolution::Response(error_response(
+            &error::Error::InvalidRequest(format!(
+                "Invalid module name '{}': {e}",
+                module_path.name()
+            )),
+        )?));
+    }
+
+    // Rate buckets and semaphores are kept per tenant, so only create them
+    // for tenants that exist
+    let tenant_dir = shared
+        .user_modules_dir
+        .as_deref()
+        .map(|dir| dir.join(tenant_id));
+    let tenant_exists = match tenant_dir {
+        Some(dir) => tokio::fs::metadata(dir)
+            .await
+            .is_ok_and(|meta| meta.is_dir()),
+        None => false,
+    };
+    if !tenant_exists {
+        return Ok(ModuleResolution::Response(error_response(
+            &error::Error::module_not_found(module_path.to_string()),
+        )?));
+    }
+
+    let limits = shared.live().tenant_limits(tenant_id);
+    if let Some(rate) = limits.requests_per_second
+        && let Err(wait) = shared.tenant_rates.try_acquire(tenant_id, rate)
+    {
+        warn!(
+            "Tenant '{}' rate limited (max {} requests/sec)",
+            tenant_id, rate
+        );
+        return Ok(ModuleResolution::Response(tenant_limit_response(
+            tenant_id,
+            "requests_per_second",
+            u64::from(rate),
+            wait.as_secs_f64().ceil().max(1.0) as u64,
+        )?));
     }
 
+    // Acquire the tenant's permit before the module's, so a busy tenant
+    // cannot fill the queues of modules it shares with nobody
+    let tenant_permit = match shared.try_acquire_tenant_permit(tenant_id) {
+        Ok(permit) => permit,
+        Err(limit) => {
+            warn!(
+                "Tenant '{}' overloaded (max {} concurrent requests)",
+                tenant_id, limit
+            );
+            return Ok(ModuleResolution::Response(tenant_limit_response(
+                tenant_id,
+                "concurrent_requests",
+                limit as u64,
+                MODULE_OVERLOAD_RETRY_AFTER_SECS.parse()?,
+            )?));
+        },
+    };
+
     // Load tenant module
-    resolve_multi_module_path(shared, module_path, handler_path).await
+    let mut resolution = resolve_multi_module_path(shared, module_path, handler_path).await?;
+    if let ModuleResolution::Success {
+        tenant_permit: slot,
+        ..
+    } = &mut resolution
+    {
+        *slot = tenant_permit;
+    }
+    Ok(resolution)
 }
 
 /// Resolves a module in multi-module mode (circuit breaker, semaphore, loading).
@@ -421,28 +540,25 @@ pub(crate) async fn resolve_multi_module(
         warn!("Circuit breaker blocked request to '{}': {}", module, e);
         let err = error::Error::circuit_breaker_open(module);
         let mut resp = error_response(&err)?;
-        resp.headers_mut().insert(
-            "Retry-After",
-            CIRCUIT_BREAKER_RETRY_AFTER_SECS
-                .to_string()
-                .parse()
-                .expect("valid Retry-After header value"),
-        );
+        resp.headers_mut()
+            .insert("Retry-After", circuit_retry_after(shared, module));
         return Ok(ModuleResolution::Response(resp));
     }
 
-    // Acquire per-module semaphore permit
-    let module_semaphore = shared.get_module_semaphore(module);
-    let module_permit = if let Ok(permit) = module_semaphore.try_acquire_owned() {
+    // Acquire per-module semaphore permit, waiting in the module's queue if enabled
+    let module_permit = if let Some(permit) = shared.acquire_module_permit(module).await {
         Some(permit)
     } else {
+        shared.stats.record_module_overload(module);
         warn!(
             "Module '{}' overloaded (max {} concurrent requests)",
-            module, shared.config.max_per_module_requests
+            module,
+            shared.module_concurrency_limit(module)
         );
         let err = error::Error::rate_limit_exceeded(format!(
             "Module '{}' overloaded (max {} concurrent)",
-            module, shared.config.max_per_module_requests
+            module,
+            shared.module_concurrency_limit(module)
         ));
         let mut resp = error_response(&err)?;
         resp.headers_mut().insert(
@@ -455,19 +571,25 @@ pub(crate) async fn resolve_multi_module(
         return Ok(ModuleResolution::Response(resp));
     };
 
-    match shared.get_or_load(module).await {
+    match shared.get_or_load_http(module).await {
         Ok(comp) => Ok(ModuleResolution::Success {
             component: comp,
             handler_path,
             module_name: Some(module.to_string()),
             module_permit,
+            tenant_permit: None,
+            alias: None,
         }),
         Err(e) => {
+            if let Some(err) = unsupported_component(&e) {
+                warn!("{}", err);
+                return Ok(ModuleResolution::Response(error_response(err)?));
+            }
             warn!("Module load failed: {}", e);
             // Record failure in circuit breaker
             shared.circuit_breaker.record_failure(module);
             // Use typed error for response
-            let err = error::Error::module_not_found(module);
+            let err = load_error(&e, module);
             Ok(ModuleResolution::Response(error_response(&err)?))
         },
     }
@@ -491,28 +613,25 @@ pub(crate) async fn resolve_multi_module_path(
         warn!("Circuit breaker blocked request to '{}': {}", cache_key, e);
         let err = error::Error::circuit_breaker_open(&handler_name);
         let mut resp = error_response(&err)?;
-        resp.headers_mut().insert(
-            "Retry-After",
-            CIRCUIT_BREAKER_RETRY_AFTER_SECS
-                .to_string()
-                .parse()
-                .expect("valid Retry-After header value"),
-        );
+        resp.headers_mut()
+            .insert("Retry-After", circuit_retry_after(shared, &cache_key));
         return Ok(ModuleResolution::Response(resp));
     }
 
-    // Acquire per-module semaphore permit
-    let module_semaphore = shared.get_module_semaphore(&cache_key);
-    let module_permit = if let Ok(permit) = module_semaphore.try_acquire_owned() {
+    // Acquire per-module semaphore permit, waiting in the module's queue if enabled
+    let module_permit = if let Some(permit) = shared.acquire_module_permit(&cache_key).await {
         Some(permit)
     } else {
+        shared.stats.record_module_overload(&cache_key);
         warn!(
             "Module '{}' overloaded (max {} concurrent requests)",
-            handler_name, shared.config.max_per_module_requests
+            handler_name,
+            shared.module_concurrency_limit(&cache_key)
         );
         let err = error::Error::rate_limit_exceeded(format!(
             "Module '{}' overloaded (max {} concurrent)",
-            handler_name, shared.config.max_per_module_requests
+            handler_name,
+            shared.module_concurrency_limit(&cache_key)
         ));
         let mut resp = error_response(&err)?;
         resp.headers_mut().insert(
@@ -531,13 +650,19 @@ pub(crate) async fn resolve_multi_module_path(
             handler_path,
             module_name: Some(handler_name),
             module_permit,
+            tenant_permit: None,
+            alias: None,
         }),
         Err(e) => {
+            if let Some(err) = unsupported_component(&e) {
+                warn!("{}", err);
+                return Ok(ModuleResolution::Response(error_response(err)?));
+            }
             warn!("Module load failed: {}", e);
             // Record failure in circuit breaker
             shared.circuit_breaker.record_failure(&cache_key);
             // Use typed error for response
-            let err = error::Error::module_not_found(&handler_name);
+            let err = load_error(&e, &handler_name);
             Ok(ModuleResolution::Response(error_response(&err)?))
         },
     }
@@ -592,11 +717,43 @@ pub(crate) fn rewrite_request_path(
     Ok(Request::from_parts(parts, body))
 }
 
+/// Add the matched host route to request metadata for `mik:request-info`.
+pub(crate) fn route_info(
+    info: RequestInfo,
+    path: &str,
+    module: Option<&str>,
+    alias: Option<&str>,
+) -> RequestInfo {
+    let param = |name: &str, value: &str| (name.to_string(), value.to_string());
+
+    if let Some(tenant_path) = path.strip_prefix(TENANT_PREFIX)
+        && let Some((ModulePath::Tenant { tenant_id, name }, _)) =
+            parse_module_path_route(tenant_path)
+    {
+        let params = vec![param("tenant", &tenant_id), param("module", &name)];
+        return info
+            .with_route(format!("{TENANT_PREFIX}{{tenant}}/{{module}}/*"), params)
+            .with_tenant(Some(tenant_id));
+    }
+
+    let module = module.unwrap_or_default();
+    match alias {
+        Some(alias) => info.with_route(
+            format!("{RUN_PREFIX}{alias}/*"),
+            vec![param("alias", alias), param("module", module)],
+        ),
+        None => info.with_route(
+            format!("{RUN_PREFIX}{{module}}/*"),
+            vec![param("module", module)],
+        ),
+    }
+}
+
 /// Inner request handler with full context.
 pub(crate) async fn handle_request_inner(
     shared: Arc<SharedState>,
     req: Request<hyper::body::Incoming>,
-    _remote_addr: SocketAddr,
+    remote_addr: SocketAddr,
     client_accepts_gzip: bool,
     trace_id: &str,
     span_collector: SpanCollector,
@@ -610,6 +767,15 @@ pub(crate) async fn handle_request_inner(
         return Ok(resp);
     }
 
+    // Handle tenant module management: /_mik/tenants/* (own body size limit)
+    if let Some(rest) = path
+        .strip_prefix(MIK_API_PREFIX)
+        .and_then(|p| p.strip_prefix(gateway::modules::TENANTS_PATH))
+    {
+        let rest = rest.to_string();
+        return gateway::modules::handle_module_request(&shared, req, &rest).await;
+    }
+
     // Check Content-Length header (fast path for body size limit)
     if let Some(resp) = validate_content_length(req.headers(), max_body) {
         return Ok(resp);
@@ -617,7 +783,7 @@ pub(crate) async fn handle_request_inner(
 
     // Handle static file requests
     if path.starts_with(STATIC_PREFIX) {
-        return match &shared.static_dir {
+        return match &shared.live().static_dir {
             Some(dir) => serve_static_file(dir, path)
                 .await
                 .map(|resp| maybe_compress_response(resp, client_accepts_gzip)),
@@ -627,7 +793,7 @@ pub(crate) async fn handle_request_inner(
 
     // Handle gateway API requests: /_mik/*
     if path.starts_with(MIK_API_PREFIX) {
-        return gateway::handle_gateway_request(&shared, path)
+        return gateway::handle_gateway_request(&shared, req.method(), req.headers(), path)
             .map(|resp| maybe_compress_response(resp, client_accepts_gzip));
     }
 
@@ -664,6 +830,22 @@ pub(crate) async fn handle_request_inner(
         };
     }
 
+    // Serve the last build error instead of the stale module (`mik dev`)
+    if path.starts_with(RUN_PREFIX)
+        && let Some(file) = &shared.config.build_error_file
+        && let Some(overlay) = build_overlay::response(file, req.headers()).await
+    {
+        return Ok(overlay);
+    }
+
+    // Everything else goes to the frontend dev server (`mik dev --proxy`)
+    if let Some(upstream) = &shared.config.dev_proxy
+        && !path.starts_with(RUN_PREFIX)
+        && !path.starts_with(TENANT_PREFIX)
+    {
+        return dev_proxy::forward(upstream, req, max_body).await;
+    }
+
     // Resolve module component (platform or tenant)
     let resolution = if path.starts_with(TENANT_PREFIX) {
         resolve_tenant_module(&shared, path).await?
@@ -671,39 +853,68 @@ pub(crate) async fn handle_request_inner(
         resolve_module(&shared, path).await?
     };
 
-    let (component, handler_path, module_name, module_permit) = match resolution {
+    let (component, handler_path, module_name, permits, alias) = match resolution {
         ModuleResolution::Success {
             component,
             handler_path,
             module_name,
             module_permit,
-        } => (component, handler_path, module_name, module_permit),
+            tenant_permit,
+            alias,
+        } => (
+            component,
+            handler_path,
+            module_name,
+            (module_permit, tenant_permit),
+            alias,
+        ),
         ModuleResolution::Response(resp) => return Ok(resp),
     };
 
+    let request_info =
+        RequestInfo::from_connection(remote_addr.ip(), req.headers(), &shared.trusted_proxies);
+    let request_info = route_info(request_info, path, module_name.as_deref(), alias.as_deref());
+
     // Rewrite the request URI and collect body
+    let recorded_uri = shared.config.record_dir.as_ref().map(|_| {
+        req.uri()
+            .path_and_query()
+            .map_or_else(|| "/".to_string(), ToString::to_string)
+    });
     let req = rewrite_request_path(req, handler_path)?;
-    let (parts, body) = req.into_parts();
+    let (mut parts, body) = req.into_parts();
+    parts.extensions.insert(request_info);
     let body_bytes = match collect_request_body(body, max_body).await? {
         Ok(bytes) => bytes,
         Err(resp) => return Ok(resp),
     };
+    let recorded = recorded_uri
+        .map(|uri| RecordedRequest::new(parts.method.as_str(), &uri, &parts.headers, &body_bytes));
     let req = Request::from_parts(parts, HyperCompatibleBody(Full::new(body_bytes)));
 
-    // Execute WASM request (keep module_permit in scope for semaphore)
-    let _module_permit = module_permit;
+    // Execute WASM request (keep module and tenant permits in scope for semaphores)
+    let _permits = permits;
     let exec_start = Instant::now();
-    let result = execute_wasm_request(shared.clone(), component, req).await;
+    let result = execute_wasm_request(shared.clone(), component, module_name.as_deref(), req).await;
     let exec_duration = exec_start.elapsed();
 
-    // Record success/failure in circuit breaker
+    // Record success/failure in circuit breaker (tenant modules by cache key)
     if let Some(ref module) = module_name {
+        let key = module_key(module);
         match &result {
-            Ok(_) => shared.circuit_breaker.record_success(module),
-            Err(_) => shared.circuit_breaker.record_failure(module),
+            Ok(_) => shared.circuit_breaker.record_success(&key),
+            Err(_) => shared.circuit_breaker.record_failure(&key),
         }
     }
 
+    // Record the exchange as the handler produced it (`record_dir`)
+    let result = match (&shared.config.record_dir, recorded) {
+        (Some(dir), Some(request)) => {
+            record_exchange(dir, module_name.as_deref(), request, result).await
+        },
+        _ => result,
+    };
+
     // Add gateway response headers (X-Mik-Duration-Ms, X-Mik-Handler)
     result.map(|mut resp| {
         // Add execution duration header
@@ -717,10 +928,41 @@ pub(crate) async fn handle_request_inner(
         {
             resp.headers_mut().insert("X-Mik-Handler", handler_value);
         }
+        if let Some(ref alias) = alias {
+            shared.add_alias_headers(alias, resp.headers_mut());
+        }
         maybe_compress_response(resp, client_accepts_gzip)
     })
 }
 
+/// Write `request` and its `result` to `dir`, passing the result on.
+async fn record_exchange(
+    dir: &Path,
+    module: Option<&str>,
+    request: RecordedRequest,
+    result: Result<Response<Full<Bytes>>>,
+) -> Result<Response<Full<Bytes>>> {
+    match result {
+        Ok(resp) => {
+            let (parts, body) = resp.into_parts();
+            let body = body.collect().await?.to_bytes();
+            let response = RecordedResponse::new(parts.status.as_u16(), &parts.headers, &body);
+            recorder::record(dir, module, request, Ok(response));
+            Ok(Response::from_parts(parts, Full::new(body)))
+        },
+        Err(e) => {
+            recorder::record(dir, module, request, Err(format!("{e:#}")));
+            Err(e)
+        },
+    }
+}
+
+/// Retry-After header value for circuit breaker responses: the recovery
+/// timeout configured for `key`.
+pub(crate) fn circuit_retry_after(shared: &SharedState, key: &str) -> HeaderValue {
+    HeaderValue::from(shared.circuit_breaker.config_for(key).timeout.as_secs())
+}
+
 /// Categorize an error by walking the error chain.
 ///
 /// Uses `to_string()` instead of `format!("{:?}")` to avoid potential panics
@@ -740,6 +982,9 @@ pub(crate) fn categorize_error(error: &anyhow::Error) -> ErrorCategory {
         if msg.contains("Not found") || msg.contains("Invalid") {
             return ErrorCategory::InvalidRequest;
         }
+        if msg.contains("unsupported component") {
+            return ErrorCategory::UnsupportedComponent;
+        }
         if msg.contains("Failed to instantiate") {
             return ErrorCategory::Instantiation;
         }
@@ -760,6 +1005,27 @@ pub(crate) fn categorize_error(error: &anyhow::Error) -> ErrorCategory {
     ErrorCategory::Internal
 }
 
+/// The [`Error::UnsupportedComponent`] in a load error, if that is the cause.
+///
+/// Such modules are deterministic configuration mistakes, so they are
+/// reported as-is and not counted against the circuit breaker.
+pub(crate) fn unsupported_component(error: &anyhow::Error) -> Option<&Error> {
+    error
+        .downcast_ref::<Error>()
+        .filter(|e| matches!(e, Error::UnsupportedComponent { .. }))
+}
+
+/// Typed error for a module that failed to load.
+///
+/// Failed `init`/`warmup` hooks are reported as load failures; anything
+/// else (missing file, invalid name) as not found.
+pub(crate) fn load_error(error: &anyhow::Error, module: &str) -> Error {
+    match error.downcast_ref::<Error>() {
+        Some(Error::ModuleLoadFailed { name, reason }) => Error::module_load_failed(name, reason),
+        _ => Error::module_not_found(module),
+    }
+}
+
 /// Create a 404 Not Found response.
 pub(crate) fn not_found(message: &str) -> Result<Response<Full<Bytes>>> {
     Ok(Response::builder()
@@ -783,6 +1049,35 @@ pub(crate) fn error_response(err: &Error) -> Result<Response<Full<Bytes>>> {
         .body(Full::new(Bytes::from(body.to_string())))?)
 }
 
+/// 429 response for a tenant over one of its limits.
+///
+/// Extends the [`error_response`] body with `code`, `tenant`, `limit`, and
+/// `max`, so clients can tell which limit was hit without parsing the
+/// message.
+fn tenant_limit_response(
+    tenant_id: &str,
+    limit: &str,
+    max: u64,
+    retry_after_secs: u64,
+) -> Result<Response<Full<Bytes>>> {
+    let err = Error::rate_limit_exceeded(format!(
+        "Tenant '{tenant_id}' exceeded its {limit} limit of {max}"
+    ));
+    let body = serde_json::json!({
+        "error": err.to_string(),
+        "status": err.status_code(),
+        "code": "tenant_limit_exceeded",
+        "tenant": tenant_id,
+        "limit": limit,
+        "max": max,
+    });
+    Ok(Response::builder()
+        .status(err.status_code())
+        .header("Content-Type", "application/json")
+        .header("Retry-After", retry_after_secs)
+        .body(Full::new(Bytes::from(body.to_string())))?)
+}
+
 /// Convert a typed error to its error category for logging.
 impl From<&Error> for ErrorCategory {
     fn from(err: &Error) -> Self {
@@ -793,6 +1088,7 @@ impl From<&Error> for ErrorCategory {
             Error::ScriptNotFound { .. } | Error::ScriptError { .. } => Self::Script,
             Error::CircuitBreakerOpen { .. } | Error::RateLimitExceeded { .. } => Self::Reliability,
             Error::MemoryLimitExceeded { .. } => Self::Execution,
+            Error::UnsupportedComponent { .. } => Self::UnsupportedComponent,
             _ => Self::Internal,
         }
     }
@@ -0,0 +1,245 @@
+//! `mik:request-info` host interface.
+//!
+//! Gives components request metadata that the host has established itself
+//! (see `wit/request-info.wit`), instead of ad-hoc headers a client could
+//! forge:
+//!
+//! - client IP, resolved through `X-Forwarded-For` only for connections from
+//!   [`trusted_proxies`](crate::runtime::HostConfig::trusted_proxies)
+//! - TLS client certificate subject, passed by a trusted proxy that
+//!   terminated mutual TLS in `X-Client-Cert-Subject`
+//! - the host route that matched and its captured parameters
+//! - the tenant id for tenant routes
+//!
+//! The metadata travels with the request as an extension and is copied into
+//! the store's [`HostState`] when the component is instantiated.
+
+use crate::runtime::host_state::HostState;
+use anyhow::{Result, bail};
+use hyper::HeaderMap;
+use std::net::IpAddr;
+use wasmtime::component::{HasSelf, Linker};
+
+wasmtime::component::bindgen!({
+    path: "wit/request-info.wit",
+    world: "host",
+});
+
+use self::mik::request_info::info;
+
+/// Header a trusted proxy uses to pass the verified client certificate subject.
+pub const CLIENT_CERT_SUBJECT_HEADER: &str = "x-client-cert-subject";
+
+/// Verified metadata for one request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestInfo {
+    /// Client IP address.
+    pub client_ip: Option<IpAddr>,
+    /// Subject of the verified TLS client certificate.
+    pub tls_client_subject: Option<String>,
+    /// Host route that matched, e.g. `/run/{module}/*`.
+    pub route_pattern: String,
+    /// Parameters captured by the route.
+    pub route_params: Vec<(String, String)>,
+    /// Tenant for tenant routes.
+    pub tenant_id: Option<String>,
+}
+
+impl RequestInfo {
+    /// Client identity for a connection from `remote`.
+    ///
+    /// Forwarding headers are only honored when `remote` is a trusted proxy.
+    pub fn from_connection(remote: IpAddr, headers: &HeaderMap, trusted: &[TrustedProxy]) -> Self {
+        let from_proxy = is_trusted(remote, trusted);
+        Self {
+            client_ip: Some(resolve_client_ip(remote, headers, trusted)),
+            tls_client_subject: from_proxy
+                .then(|| headers.get(CLIENT_CERT_SUBJECT_HEADER))
+                .flatten()
+                .and_then(|value| value.to_str().ok())
+                .filter(|subject| !subject.is_empty())
+                .map(String::from),
+            ..Self::default()
+        }
+    }
+
+    /// Set the matched route and its parameters.
+    #[must_use]
+    pub fn with_route(mut self, pattern: impl Into<String>, params: Vec<(String, String)>) -> Self {
+        self.route_pattern = pattern.into();
+        self.route_params = params;
+        self
+    }
+
+    /// Set the tenant id.
+    #[must_use]
+    pub fn with_tenant(mut self, tenant_id: Option<String>) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
+}
+
+/// An address or CIDR range whose forwarding headers are trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrustedProxy {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl TrustedProxy {
+    /// Parse `10.0.0.1`, `10.0.0.0/8`, or `fd00::/8`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address or prefix length is invalid.
+    pub fn parse(value: &str) -> Result<Self> {
+        let (address, prefix) = match value.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (value, None),
+        };
+        let Ok(network) = address.trim().parse::<IpAddr>() else {
+            bail!("invalid trusted proxy address '{value}'");
+        };
+        let network = network.to_canonical();
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix {
+            Some(prefix) => match prefix.trim().parse::<u8>() {
+                Ok(len) if len <= max => len,
+                _ => bail!("invalid prefix length in trusted proxy '{value}'"),
+            },
+            None => max,
+        };
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+
+    /// Whether `ip` falls inside this range.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => prefix_matches(
+                network.to_bits().into(),
+                ip.to_bits().into(),
+                32,
+                self.prefix_len,
+            ),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(network.to_bits(), ip.to_bits(), 128, self.prefix_len)
+            },
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, bits: u8, prefix_len: u8) -> bool {
+    let shift = u32::from(bits - prefix_len);
+    network.checked_shr(shift).unwrap_or(0) == ip.checked_shr(shift).unwrap_or(0)
+}
+
+fn is_trusted(ip: IpAddr, trusted: &[TrustedProxy]) -> bool {
+    trusted.iter().any(|proxy| proxy.contains(ip))
+}
+
+/// Client IP for a connection from `remote`.
+///
+/// For trusted proxies, this is the rightmost `X-Forwarded-For` entry that is
+/// not itself a trusted proxy; entries further left are client-controlled.
+pub fn resolve_client_ip(remote: IpAddr, headers: &HeaderMap, trusted: &[TrustedProxy]) -> IpAddr {
+    if !is_trusted(remote, trusted) {
+        return remote;
+    }
+    let forwarded: Vec<IpAddr> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|entry| entry.trim().parse().ok())
+        .collect();
+
+    forwarded
+        .iter()
+        .rev()
+        .find(|ip| !is_trusted(**ip, trusted))
+        .or_else(|| forwarded.first())
+        .copied()
+        .unwrap_or(remote)
+}
+
+impl info::Host for HostState {
+    fn get(&mut self) -> info::RequestInfo {
+        let request = &self.request_info;
+        info::RequestInfo {
+            client_ip: request.client_ip.map(|ip| ip.to_string()),
+            tls_client_subject: request.tls_client_subject.clone(),
+            route_pattern: request.route_pattern.clone(),
+            route_params: request.route_params.clone(),
+            tenant_id: request.tenant_id.clone(),
+        }
+    }
+}
+
+/// Add `mik:request-info` to the linker.
+pub(crate) fn add_to_linker(linker: &mut Linker<HostState>) -> Result<()> {
+    info::add_to_linker::<HostState, HasSelf<HostState>>(linker, |state| state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trusted(values: &[&str]) -> Vec<TrustedProxy> {
+        values
+            .iter()
+            .map(|v| TrustedProxy::parse(v).unwrap())
+            .collect()
+    }
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", value.parse().unwrap());
+        headers.insert(CLIENT_CERT_SUBJECT_HEADER, "CN=client".parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_trusted_proxy_ranges() {
+        let proxy = TrustedProxy::parse("10.0.0.0/8").unwrap();
+        assert!(proxy.contains("10.1.2.3".parse().unwrap()));
+        assert!(proxy.contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!proxy.contains("11.0.0.1".parse().unwrap()));
+
+        let proxy = TrustedProxy::parse("fd00::/8").unwrap();
+        assert!(proxy.contains("fd12::1".parse().unwrap()));
+        assert!(!proxy.contains("10.0.0.1".parse().unwrap()));
+
+        assert!(
+            TrustedProxy::parse("0.0.0.0/0")
+                .unwrap()
+                .contains("8.8.8.8".parse().unwrap())
+        );
+        assert!(TrustedProxy::parse("10.0.0.1/33").is_err());
+        assert!(TrustedProxy::parse("proxy.local").is_err());
+    }
+
+    #[test]
+    fn test_forwarded_headers_need_trusted_proxy() {
+        let proxies = trusted(&["10.0.0.0/8"]);
+        let headers = forwarded("1.2.3.4, 10.0.0.7");
+
+        // Direct client: headers are ignored
+        let info = RequestInfo::from_connection("5.6.7.8".parse().unwrap(), &headers, &proxies);
+        assert_eq!(info.client_ip, Some("5.6.7.8".parse().unwrap()));
+        assert_eq!(info.tls_client_subject, None);
+
+        // Through the proxy chain: rightmost untrusted hop is the client
+        let info = RequestInfo::from_connection("10.0.0.1".parse().unwrap(), &headers, &proxies);
+        assert_eq!(info.client_ip, Some("1.2.3.4".parse().unwrap()));
+        assert_eq!(info.tls_client_subject.as_deref(), Some("CN=client"));
+
+        // A spoofed leftmost entry does not win over the proxy-appended one
+        let headers = forwarded("9.9.9.9, 1.2.3.4");
+        let info = RequestInfo::from_connection("10.0.0.1".parse().unwrap(), &headers, &proxies);
+        assert_eq!(info.client_ip, Some("1.2.3.4".parse().unwrap()));
+    }
+}
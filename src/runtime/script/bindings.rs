@@ -1,12 +1,13 @@
 //! Host bindings for `JavaScript` scripts.
 //!
-//! Provides the `host.call()` bridge between synchronous `JavaScript` and async Rust.
+//! Provides the `host.call()`, `host.kv`, `host.storage` and `fetch()` bridge
+//! between synchronous `JavaScript` and async Rust.
 
 use std::cell::RefCell;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
-use super::types::HostMessage;
+use super::types::{FetchRequest, FetchResult, HostMessage, ServiceOp, ServiceResult};
 
 /// Bridge for sync JS -> async Rust communication
 pub(crate) struct HostBridge {
@@ -100,3 +101,59 @@ pub(crate) fn native_host_call(module: String, options_json: String) -> rquickjs
         serde_json::to_string(&result).map_err(|_| rquickjs::Error::Exception)
     })
 }
+
+/// Native `host_service` function - takes an operation JSON (`{"op": "kv.get", ...}`),
+/// returns a [`ServiceResult`] JSON.
+#[allow(clippy::needless_pass_by_value)] // rquickjs FFI requires owned values for JS function bindings
+pub(crate) fn native_host_service(op_json: String) -> rquickjs::Result<String> {
+    HOST_BRIDGE.with(|cell| {
+        let bridge = cell.borrow();
+        let bridge = bridge.as_ref().ok_or(rquickjs::Error::Exception)?;
+
+        let result = match serde_json::from_str::<ServiceOp>(&op_json) {
+            Ok(op) => {
+                // Send message and block for response
+                let (resp_tx, resp_rx) = std::sync::mpsc::channel();
+                bridge
+                    .tx
+                    .send(HostMessage::Service {
+                        op,
+                        response_tx: resp_tx,
+                    })
+                    .map_err(|_| rquickjs::Error::Exception)?;
+                resp_rx.recv().map_err(|_| rquickjs::Error::Exception)?
+            },
+            Err(e) => ServiceResult::failed(format!("Invalid host service call: {e}")),
+        };
+
+        serde_json::to_string(&result).map_err(|_| rquickjs::Error::Exception)
+    })
+}
+
+/// Native `host_fetch` function - takes a [`FetchRequest`] JSON, returns a
+/// [`FetchResult`] JSON.
+#[allow(clippy::needless_pass_by_value)] // rquickjs FFI requires owned values for JS function bindings
+pub(crate) fn native_host_fetch(request_json: String) -> rquickjs::Result<String> {
+    HOST_BRIDGE.with(|cell| {
+        let bridge = cell.borrow();
+        let bridge = bridge.as_ref().ok_or(rquickjs::Error::Exception)?;
+
+        let result = match serde_json::from_str::<FetchRequest>(&request_json) {
+            Ok(request) => {
+                // Send message and block for response
+                let (resp_tx, resp_rx) = std::sync::mpsc::channel();
+                bridge
+                    .tx
+                    .send(HostMessage::Fetch {
+                        request,
+                        response_tx: resp_tx,
+                    })
+                    .map_err(|_| rquickjs::Error::Exception)?;
+                resp_rx.recv().map_err(|_| rquickjs::Error::Exception)?
+            },
+            Err(e) => FetchResult::failed(format!("Invalid fetch call: {e}")),
+        };
+
+        serde_json::to_string(&result).map_err(|_| rquickjs::Error::Exception)
+    })
+}
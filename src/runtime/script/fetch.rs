@@ -0,0 +1,181 @@
+//! `fetch()` for scripts.
+//!
+//! Sends outgoing HTTP requests from `JavaScript` through the same client
+//! wasi:http guests use, with the same `http_allowed` host allowlist. Request
+//! and response bodies are capped at the server's `max_body_size_mb`.
+
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Bytes;
+use std::time::{Duration, Instant};
+use wasmtime_wasi_http::types::{OutgoingRequestConfig, default_send_request_handler};
+
+use super::types::{FetchRequest, FetchResult};
+use crate::runtime::SharedState;
+use crate::runtime::deadline::{self, DEADLINE_HEADER};
+use crate::runtime::reliability::is_http_host_allowed;
+
+/// Execute a single `fetch()` call, if the host is allowed.
+///
+/// With a `deadline`, the request waits no longer than what is left of it and
+/// forwards that budget in `X-Mik-Deadline`.
+pub(crate) async fn execute_fetch(
+    shared: &SharedState,
+    request: FetchRequest,
+    deadline: Option<Instant>,
+) -> FetchResult {
+    let uri = match check_fetch(
+        &request,
+        &shared.live().http_allowed,
+        shared.max_body_size_bytes,
+    ) {
+        Ok(uri) => uri,
+        Err(e) => return FetchResult::failed(e),
+    };
+    let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+    if remaining.is_some_and(|r| r.is_zero()) {
+        return FetchResult::failed(format!(
+            "fetch {} skipped: caller deadline exceeded",
+            request.url
+        ));
+    }
+    match send(
+        &request,
+        uri,
+        remaining.map_or(shared.execution_timeout, |r| {
+            r.min(shared.execution_timeout)
+        }),
+        remaining.is_some(),
+        shared.max_body_size_bytes,
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => FetchResult::failed(format!("fetch {} failed: {e}", request.url)),
+    }
+}
+
+/// Validate a request against the allowlist and body limit.
+fn check_fetch(
+    request: &FetchRequest,
+    allowed: &[String],
+    max_body_size: usize,
+) -> Result<hyper::Uri, String> {
+    let uri: hyper::Uri = request
+        .url
+        .parse()
+        .map_err(|e| format!("Invalid fetch URL '{}': {e}", request.url))?;
+    if !matches!(uri.scheme_str(), Some("http" | "https")) {
+        return Err(format!(
+            "Invalid fetch URL '{}': expected http or https",
+            request.url
+        ));
+    }
+    let host = uri.host().unwrap_or("");
+    if !is_http_host_allowed(host, allowed) {
+        return Err(format!("fetch denied: host '{host}' not in http_allowed"));
+    }
+    let body_len = request.body.as_ref().map_or(0, String::len);
+    if body_len > max_body_size {
+        return Err(format!(
+            "fetch body too large: {body_len} bytes (max: {max_body_size} bytes)"
+        ));
+    }
+    Ok(uri)
+}
+
+async fn send(
+    request: &FetchRequest,
+    uri: hyper::Uri,
+    timeout: Duration,
+    forward_deadline: bool,
+    max_body_size: usize,
+) -> Result<FetchResult, String> {
+    let use_tls = uri.scheme_str() == Some("https");
+    let authority = uri.authority().map(ToString::to_string).unwrap_or_default();
+
+    let mut builder = hyper::Request::builder()
+        .method(request.method.as_str())
+        .uri(uri)
+        .header(hyper::header::HOST, authority);
+    for (key, value) in &request.headers {
+        builder = builder.header(key.as_str(), value.as_str());
+    }
+    if forward_deadline && let Some(headers) = builder.headers_mut() {
+        headers.insert(DEADLINE_HEADER, deadline::deadline_header(timeout));
+    }
+    let body = Full::new(Bytes::from(request.body.clone().unwrap_or_default()))
+        .map_err(|never| match never {})
+        .boxed_unsync();
+    let outgoing = builder.body(body).map_err(|e| e.to_string())?;
+
+    let config = OutgoingRequestConfig {
+        use_tls,
+        connect_timeout: timeout,
+        first_byte_timeout: timeout,
+        between_bytes_timeout: timeout,
+    };
+    let response = default_send_request_handler(outgoing, config)
+        .await
+        .map_err(|e| e.to_string())?;
+    // Drives the connection, so it must outlive the body read
+    let _worker = response.worker;
+    let (parts, body) = response.resp.into_parts();
+
+    let body = tokio::time::timeout(timeout, Limited::new(body, max_body_size).collect())
+        .await
+        .map_err(|_| "timed out reading response body".to_string())?
+        .map_err(|e| format!("response body: {e} (max: {max_body_size} bytes)"))?
+        .to_bytes();
+
+    let headers = parts
+        .headers
+        .iter()
+        .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
+        .collect();
+    Ok(FetchResult {
+        status: parts.status.as_u16(),
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+        error: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str, body: Option<&str>) -> FetchRequest {
+        FetchRequest {
+            url: url.to_string(),
+            method: "POST".to_string(),
+            headers: std::collections::BTreeMap::new(),
+            body: body.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_check_fetch_enforces_allowlist() {
+        let allowed = vec!["api.example.com".to_string(), "*.internal".to_string()];
+
+        let uri =
+            check_fetch(&request("https://api.example.com/v1", None), &allowed, 1024).unwrap();
+        assert_eq!(uri.host(), Some("api.example.com"));
+        assert!(check_fetch(&request("http://db.internal/x", None), &allowed, 1024).is_ok());
+
+        let err = check_fetch(&request("https://evil.com/", None), &allowed, 1024).unwrap_err();
+        assert!(err.contains("host 'evil.com' not in http_allowed"));
+        assert!(check_fetch(&request("https://api.example.com/", None), &[], 1024).is_err());
+    }
+
+    #[test]
+    fn test_check_fetch_rejects_bad_urls_and_large_bodies() {
+        let allowed = vec!["*".to_string()];
+
+        assert!(check_fetch(&request("not a url", None), &allowed, 1024).is_err());
+        assert!(check_fetch(&request("ftp://example.com/", None), &allowed, 1024).is_err());
+
+        let err =
+            check_fetch(&request("https://example.com/", Some("12345")), &allowed, 4).unwrap_err();
+        assert!(err.contains("fetch body too large: 5 bytes"));
+    }
+}
@@ -6,11 +6,17 @@ use anyhow::{Context, Result};
 use http_body_util::Full;
 use hyper::body::Bytes;
 use std::sync::Arc;
+use std::time::Instant;
 
 use super::types::HostCallResult;
 use crate::runtime::SharedState;
+use crate::runtime::deadline::{self, DEADLINE_HEADER};
+use crate::runtime::request_handler::unsupported_component;
 
 /// Execute a single handler call (check circuit breaker, rate limit, call WASM).
+///
+/// With a `deadline`, the handler gets what is left of it in `X-Mik-Deadline`,
+/// and calls made after it has passed fail without running the handler.
 pub(crate) async fn execute_handler_call(
     shared: Arc<SharedState>,
     module: &str,
@@ -19,9 +25,20 @@ pub(crate) async fn execute_handler_call(
     headers: Vec<(String, String)>,
     body: Option<serde_json::Value>,
     trace_id: &str,
+    deadline: Option<Instant>,
 ) -> Result<HostCallResult> {
     use http_body_util::BodyExt;
 
+    let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+    if remaining.is_some_and(|r| r.is_zero()) {
+        return Ok(HostCallResult {
+            status: 504,
+            headers: vec![],
+            body: serde_json::json!({"error": "DEADLINE_EXCEEDED", "message": "Caller deadline exceeded"}),
+            error: Some("DEADLINE_EXCEEDED".to_string()),
+        });
+    }
+
     // Check circuit breaker
     if let Err(e) = shared.circuit_breaker.check_request(module) {
         return Ok(HostCallResult {
@@ -33,8 +50,8 @@ pub(crate) async fn execute_handler_call(
     }
 
     // Acquire per-module semaphore
-    let module_semaphore = shared.get_module_semaphore(module);
-    let Ok(_permit) = module_semaphore.try_acquire() else {
+    let Some(_permit) = shared.acquire_module_permit(module).await else {
+        shared.stats.record_module_overload(module);
         return Ok(HostCallResult {
             status: 429,
             headers: vec![],
@@ -44,8 +61,16 @@ pub(crate) async fn execute_handler_call(
     };
 
     // Load the WASM module
-    let component = match shared.get_or_load(module).await {
+    let component = match shared.get_or_load_http(module).await {
         Ok(comp) => comp,
+        Err(e) if unsupported_component(&e).is_some() => {
+            return Ok(HostCallResult {
+                status: 501,
+                headers: vec![],
+                body: serde_json::json!({"error": "UNSUPPORTED_COMPONENT", "message": e.to_string()}),
+                error: Some("UNSUPPORTED_COMPONENT".to_string()),
+            });
+        },
         Err(e) => {
             shared.circuit_breaker.record_failure(module);
             return Ok(HostCallResult {
@@ -77,6 +102,13 @@ pub(crate) async fn execute_handler_call(
         req_builder = req_builder.header(key.as_str(), value.as_str());
     }
 
+    // Forward the remaining budget, replacing any set by the script
+    if let Some(remaining) = remaining
+        && let Some(headers) = req_builder.headers_mut()
+    {
+        headers.insert(DEADLINE_HEADER, deadline::deadline_header(remaining));
+    }
+
     // Add content-type if body present
     if !body_bytes.is_empty() {
         req_builder = req_builder.header("content-type", "application/json");
@@ -88,7 +120,8 @@ pub(crate) async fn execute_handler_call(
 
     // Execute the WASM handler
     let result =
-        crate::runtime::execute_wasm_request_internal(shared.clone(), component, req).await;
+        crate::runtime::execute_wasm_request_internal(shared.clone(), component, Some(module), req)
+            .await;
 
     match result {
         Ok(response) => {
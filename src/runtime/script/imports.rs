@@ -0,0 +1,296 @@
+//! Imports between scripts.
+//!
+//! Scripts can share helpers with `import`/`require`. Only relative
+//! specifiers (`./lib/validate`, `../shared.js`) are resolved, always against
+//! the importing file and never outside the scripts directory. There is no
+//! `node_modules` lookup.
+//!
+//! Imported files are CommonJS-style modules: they get `module`, `exports`
+//! and `require`, and run once per script execution. Single-line ESM
+//! `import`/`export` statements are rewritten to the same form:
+//!
+//! ```js
+//! import { isEmail } from "./lib/validate";   // var { isEmail } = require("./lib/validate");
+//! export function isEmail(s) { ... }          // function isEmail(s) { ... } exports.isEmail = isEmail;
+//! ```
+
+use std::path::Path;
+
+use crate::runtime::security;
+
+/// A resolved script module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ImportedScript {
+    /// Path relative to the scripts directory, e.g. `lib/validate.js`.
+    pub id: String,
+    /// Source rewritten to CommonJS form.
+    pub source: String,
+}
+
+/// Resolve `specifier` imported from `from` (a path relative to `scripts_dir`)
+/// and load it.
+pub(crate) fn load_import(
+    scripts_dir: &Path,
+    from: &str,
+    specifier: &str,
+) -> Result<ImportedScript, String> {
+    if !specifier.starts_with("./") && !specifier.starts_with("../") {
+        return Err(format!(
+            "Cannot import '{specifier}': only relative paths within the scripts directory are supported"
+        ));
+    }
+
+    let base = Path::new(from).parent().unwrap_or_else(|| Path::new(""));
+    let joined = base.join(specifier);
+    let mut relative = security::sanitize_file_path(&joined.to_string_lossy())
+        .map_err(|e| format!("Cannot import '{specifier}' from '{from}': {e}"))?;
+    if relative.extension().is_none() {
+        relative.set_extension("js");
+    }
+
+    let path = security::validate_path_within_base(scripts_dir, &relative)
+        .map_err(|e| format!("Cannot import '{specifier}' from '{from}': {e}"))?;
+    let source = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Cannot import '{specifier}' from '{from}': {e}"))?;
+
+    Ok(ImportedScript {
+        id: module_id(&relative),
+        source: rewrite_exports(&rewrite_imports(&source)),
+    })
+}
+
+fn module_id(relative: &Path) -> String {
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// Relative path of the script served at `/script/<name>`.
+pub(crate) fn entry_id(script_name: &str) -> String {
+    format!("{script_name}.js")
+}
+
+/// Rewrite single-line `import` statements to `require` calls.
+pub(crate) fn rewrite_imports(source: &str) -> String {
+    source
+        .lines()
+        .map(|line| rewrite_import_line(line).unwrap_or_else(|| line.to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn rewrite_import_line(line: &str) -> Option<String> {
+    let statement = line.trim().strip_prefix("import ")?.trim();
+    let statement = statement.strip_suffix(';').unwrap_or(statement).trim();
+
+    // import "./setup";
+    if let Some(specifier) = quoted(statement) {
+        return Some(format!("require({specifier});"));
+    }
+
+    let (clause, specifier) = statement.rsplit_once(" from ")?;
+    let specifier = quoted(specifier.trim())?;
+    let clause = clause.trim();
+    let require = format!("require({specifier})");
+
+    // import * as ns from "./lib";
+    if let Some(namespace) = clause.strip_prefix("* as ") {
+        return Some(format!("var {} = {require};", namespace.trim()));
+    }
+
+    let (default, named) = match clause.split_once('{') {
+        Some((default, named)) => (default.trim().trim_end_matches(',').trim(), Some(named)),
+        None => (clause, None),
+    };
+    let mut out = Vec::new();
+    if let Some(named) = named {
+        let bindings = named
+            .trim_end()
+            .strip_suffix('}')?
+            .split(',')
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+            .map(|b| match b.split_once(" as ") {
+                Some((name, alias)) => format!("{}: {}", name.trim(), alias.trim()),
+                None => b.to_string(),
+            })
+            .collect::<Vec<_>>();
+        out.push(format!("var {{ {} }} = {require};", bindings.join(", ")));
+    }
+    if !default.is_empty() {
+        out.push(format!("var {default} = __import_default({require});"));
+    }
+    Some(out.join(" "))
+}
+
+/// The quoted specifier, if `s` is exactly one string literal.
+fn quoted(s: &str) -> Option<&str> {
+    let quote = s.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    (s.len() >= 2 && s.ends_with(quote) && !s[1..s.len() - 1].contains(quote)).then_some(s)
+}
+
+/// Rewrite single-line `export` statements of an imported file to `exports`.
+fn rewrite_exports(source: &str) -> String {
+    let mut exported = Vec::new();
+    let mut lines = Vec::new();
+    for line in source.lines() {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let Some(rest) = line.trim_start().strip_prefix("export ") else {
+            lines.push(line.to_string());
+            continue;
+        };
+        if let Some(value) = rest.strip_prefix("default ") {
+            lines.push(format!("{indent}exports.default = {value}"));
+        } else if let Some(names) = rest.trim().strip_prefix('{') {
+            // export { a, b as c };
+            let names = names.trim_end_matches(';').trim_end().trim_end_matches('}');
+            for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                match name.split_once(" as ") {
+                    Some((local, alias)) => {
+                        exported.push((alias.trim().to_string(), local.trim().to_string()));
+                    },
+                    None => exported.push((name.to_string(), name.to_string())),
+                }
+            }
+        } else {
+            if let Some(name) = declared_name(rest) {
+                exported.push((name.to_string(), name.to_string()));
+            }
+            lines.push(format!("{indent}{rest}"));
+        }
+    }
+    for (alias, local) in exported {
+        lines.push(format!("exports.{alias} = {local};"));
+    }
+    lines.join("\n")
+}
+
+/// Name declared by `function f`, `async function f`, `class C`, `const x`, ...
+fn declared_name(declaration: &str) -> Option<&str> {
+    let rest = declaration.strip_prefix("async ").unwrap_or(declaration);
+    let rest = [
+        "function* ",
+        "function ",
+        "class ",
+        "const ",
+        "let ",
+        "var ",
+    ]
+    .iter()
+    .find_map(|keyword| rest.strip_prefix(keyword))?;
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(rest.len());
+    (end > 0).then(|| &rest[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_imports() {
+        assert_eq!(
+            rewrite_imports(r#"import { isEmail, trim as clean } from "./lib/validate";"#),
+            r#"var { isEmail, trim: clean } = require("./lib/validate");"#
+        );
+        assert_eq!(
+            rewrite_imports("import * as util from './util.js'"),
+            "var util = require('./util.js');"
+        );
+        assert_eq!(
+            rewrite_imports(r#"import money, { round } from "./money";"#),
+            r#"var { round } = require("./money"); var money = __import_default(require("./money"));"#
+        );
+        assert_eq!(
+            rewrite_imports(r#"import "./setup";"#),
+            r#"require("./setup");"#
+        );
+        // Not an import statement
+        assert_eq!(rewrite_imports("var important = 1;"), "var important = 1;");
+    }
+
+    #[test]
+    fn test_rewrite_exports() {
+        let source = "export function isEmail(s) { return s.includes('@'); }\n\
+                      export const MAX = 3;\n\
+                      function helper() {}\n\
+                      export { helper as help };\n\
+                      export default { name: 'validate' };";
+        let rewritten = rewrite_exports(source);
+        assert!(rewritten.contains("function isEmail(s)"));
+        assert!(rewritten.contains("const MAX = 3;"));
+        assert!(rewritten.contains("exports.default = { name: 'validate' };"));
+        assert!(
+            rewritten.ends_with(
+                "exports.isEmail = isEmail;\nexports.MAX = MAX;\nexports.help = helper;"
+            )
+        );
+        assert!(!rewritten.contains("export "));
+    }
+
+    #[test]
+    fn test_load_import_resolves_within_scripts_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let scripts = dir.path().join("scripts");
+        std::fs::create_dir_all(scripts.join("lib")).unwrap();
+        std::fs::write(scripts.join("lib/validate.js"), "export const A = 1;").unwrap();
+        std::fs::write(scripts.join("shared.js"), "module.exports = 2;").unwrap();
+        std::fs::write(dir.path().join("secret.js"), "").unwrap();
+
+        let import = load_import(&scripts, "checkout.js", "./lib/validate").unwrap();
+        assert_eq!(import.id, "lib/validate.js");
+        assert!(import.source.contains("exports.A = A;"));
+
+        let import = load_import(&scripts, "lib/validate.js", "../shared.js").unwrap();
+        assert_eq!(import.id, "shared.js");
+
+        for specifier in ["../secret", "./lib/../../secret", "lodash", "/etc/passwd"] {
+            assert!(
+                load_import(&scripts, "checkout.js", specifier).is_err(),
+                "{specifier} should not resolve"
+            );
+        }
+        assert!(load_import(&scripts, "checkout.js", "./missing").is_err());
+    }
+
+    #[test]
+    fn test_script_imports_execute() {
+        use super::super::bindings::HostBridge;
+        use super::super::runtime::run_js_script;
+        use std::sync::Arc;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lib")).unwrap();
+        std::fs::write(
+            dir.path().join("lib/validate.js"),
+            "import { MIN } from \"../limits\";\n\
+             export function isLong(s) { return s.length >= MIN; }",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("limits.js"), "module.exports = { MIN: 3 };").unwrap();
+
+        let script = "import { isLong } from \"./lib/validate\";\n\
+                      const limits = require(\"./limits\");\n\
+                      export default function(input) { return [isLong(input.name), limits.MIN]; }";
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let bridge = Arc::new(HostBridge { tx });
+        let result = run_js_script(
+            "checkout",
+            script,
+            Some(dir.path().to_path_buf()),
+            &serde_json::json!({"name": "alice"}),
+            bridge.clone(),
+        )
+        .unwrap();
+        assert_eq!(result, serde_json::json!([true, 3]));
+
+        let err = run_js_script(
+            "checkout",
+            "import _ from \"lodash\";\nexport default function(input) { return 1; }",
+            Some(dir.path().to_path_buf()),
+            &serde_json::Value::Null,
+            bridge,
+        )
+        .unwrap_err();
+        assert!(err.contains("only relative paths"), "{err}");
+    }
+}
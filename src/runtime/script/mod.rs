@@ -6,18 +6,28 @@
 //!
 //! Scripts have access to:
 //! - `host.call(module, options)` - Call WASM handlers
+//! - `host.kv` / `host.storage` - Daemon KV and storage, when granted in
+//!   `[server.script_capabilities]`
+//! - `fetch(url, options)` - Outgoing HTTP to hosts in `http_allowed`
+//! - `import`/`require` of other files in the scripts directory
+//! - `std` - Hashing, encoding, UUIDs and capped sleep (see [`stdlib`])
 //! - `input` - Request body (JSON)
 //!
 //! Scripts do NOT have:
-//! - Network access (no fetch)
+//! - Network access beyond `http_allowed`
 //! - Filesystem access
-//! - Module imports (no require)
+//! - `node_modules` or imports from outside the scripts directory
 //! - Shell/process access
 
 mod bindings;
 mod context;
+mod fetch;
 mod handler;
+mod imports;
 mod runtime;
+mod service;
+mod stdlib;
+mod trace;
 mod types;
 
 use anyhow::{Context, Result};
@@ -25,9 +35,11 @@ use http_body_util::Full;
 use hyper::body::Bytes;
 use hyper::{Request, Response};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
 
 use crate::runtime::SharedState;
+use crate::runtime::deadline;
 use crate::runtime::security;
 use crate::runtime::spans::{SpanBuilder, SpanCollector};
 
@@ -35,8 +47,11 @@ use crate::runtime::spans::{SpanBuilder, SpanCollector};
 pub(crate) use types::{HostCallResult, HostMessage, ScriptResponse};
 
 use bindings::HostBridge;
+use fetch::execute_fetch;
 use handler::execute_handler_call;
 use runtime::run_js_script;
+use service::execute_service_call;
+use types::TracedCall;
 
 // =============================================================================
 // Public API
@@ -65,6 +80,13 @@ pub(crate) async fn handle_script_request(
         .filter(|s| !s.is_empty())
         .ok_or_else(|| anyhow::anyhow!("Missing script name"))?;
 
+    // ?trace=1 returns spans and host calls along with the result
+    let tracing =
+        trace::is_requested(req.uri().query()) && trace::is_allowed(&shared, req.headers());
+
+    // Budget left for host.call() and fetch(), if the caller sent one
+    let deadline = deadline::caller_budget(req.headers()).map(|budget| Instant::now() + budget);
+
     // Sanitize script name to prevent path traversal
     let script_name = security::sanitize_module_name(script_name)
         .map_err(|e| anyhow::anyhow!("Invalid script name: {e}"))?;
@@ -121,23 +143,43 @@ pub(crate) async fn handle_script_request(
             .map_err(|e| anyhow::anyhow!("Invalid JSON in request body: {e}"))?
     };
 
-    // Execute script with host.call() bridge
+    // Execute script with host.call() bridge. When tracing, spans go to a
+    // collector of their own so they can be returned, then to the request's.
+    let script_collector = if tracing {
+        SpanCollector::new()
+    } else {
+        span_collector.clone()
+    };
+    let mut calls = Vec::new();
     let script_span = SpanBuilder::with_parent(format!("script.{script_name}"), parent_span_id);
     let script_span_id = script_span.span_id().to_string();
     let result = execute_script(
         shared,
+        &script_name,
         &script,
         &input,
         trace_id,
-        span_collector.clone(),
+        script_collector.clone(),
         &script_span_id,
+        deadline,
+        tracing.then_some(&mut calls),
     )
     .await;
 
     // Record script span
+    let total_ms = script_span.elapsed_ms();
     match &result {
-        Ok(_) => span_collector.add(script_span.finish()),
-        Err(e) => span_collector.add(script_span.finish_with_error(e.to_string())),
+        Ok(_) => script_collector.add(script_span.finish()),
+        Err(e) => script_collector.add(script_span.finish_with_error(e.to_string())),
+    }
+
+    if tracing {
+        let spans = script_collector.collect();
+        let trace = trace::ScriptTrace::new(trace_id, total_ms, &spans, calls);
+        for span in spans {
+            span_collector.add(span);
+        }
+        return trace::response(result, trace);
     }
 
     let result = result?;
@@ -155,13 +197,19 @@ pub(crate) async fn handle_script_request(
 // =============================================================================
 
 /// Execute a `JavaScript` script with `host.call()` capability.
+///
+/// `deadline` is forwarded to every `host.call()` and `fetch()`.
+#[allow(clippy::too_many_arguments)] // Script input, tracing context and deadline
 async fn execute_script(
     shared: Arc<SharedState>,
+    script_name: &str,
     script: &str,
     input: &serde_json::Value,
     trace_id: &str,
     span_collector: SpanCollector,
     parent_span_id: &str,
+    deadline: Option<Instant>,
+    mut calls: Option<&mut Vec<TracedCall>>,
 ) -> Result<ScriptResponse> {
     // Channel for host.call() messages
     let (host_tx, mut host_rx) = mpsc::unbounded_channel::<HostMessage>();
@@ -173,12 +221,21 @@ async fn execute_script(
     let bridge = Arc::new(HostBridge { tx: host_tx });
     let bridge_clone = bridge.clone();
 
+    let capabilities = shared.config.script_capabilities(script_name);
     let input_clone = input.clone();
     let script_owned = script.to_string();
+    let script_name_owned = script_name.to_string();
+    let scripts_dir = shared.scripts_dir.clone();
 
     // Spawn JS execution in blocking thread
     let mut js_handle = tokio::task::spawn_blocking(move || {
-        run_js_script(&script_owned, &input_clone, bridge_clone)
+        run_js_script(
+            &script_name_owned,
+            &script_owned,
+            scripts_dir,
+            &input_clone,
+            bridge_clone,
+        )
     });
 
     // Process host.call() messages while JS runs
@@ -215,6 +272,13 @@ async fn execute_script(
 
                         // Track handler call timing (child of script span)
                         let handler_span = SpanBuilder::with_parent(format!("handler.{module}"), parent_span_id);
+                        let traced_request = calls.is_some().then(|| serde_json::json!({
+                            "module": module,
+                            "method": method,
+                            "path": path,
+                            "headers": headers,
+                            "body": body,
+                        }));
 
                         let result = execute_handler_call(
                             shared.clone(),
@@ -224,8 +288,17 @@ async fn execute_script(
                             headers,
                             body,
                             trace_id,
+                            deadline,
                         ).await;
 
+                        if let (Some(calls), Some(request)) = (calls.as_deref_mut(), traced_request) {
+                            let response = match &result {
+                                Ok(resp) => serde_json::to_value(resp).unwrap_or_default(),
+                                Err(e) => serde_json::json!({ "error": e.to_string() }),
+                            };
+                            calls.push(TracedCall::new("host.call", &request, &response, handler_span.elapsed_ms()));
+                        }
+
                         // Record handler span based on result
                         match &result {
                             Ok(resp) if resp.status >= 400 => {
@@ -256,6 +329,37 @@ async fn execute_script(
                             }
                         }
                     }
+                    Some(HostMessage::Service { op, response_tx }) => {
+                        let service_span = SpanBuilder::with_parent(format!("host.{}", op.name()), parent_span_id);
+                        let traced = calls.is_some().then(|| op.clone());
+                        let result = execute_service_call(
+                            shared.daemon.as_ref(),
+                            script_name,
+                            capabilities,
+                            op,
+                        ).await;
+                        if let (Some(calls), Some(op)) = (calls.as_deref_mut(), traced) {
+                            calls.push(TracedCall::new(&format!("host.{}", op.name()), &op, &result, service_span.elapsed_ms()));
+                        }
+                        match &result.error {
+                            Some(e) => span_collector.add(service_span.finish_with_error(e.clone())),
+                            None => span_collector.add(service_span.finish()),
+                        }
+                        let _ = response_tx.send(result);
+                    }
+                    Some(HostMessage::Fetch { request, response_tx }) => {
+                        let fetch_span = SpanBuilder::with_parent("fetch", parent_span_id);
+                        let traced = calls.is_some().then(|| request.clone());
+                        let result = execute_fetch(&shared, request, deadline).await;
+                        if let (Some(calls), Some(request)) = (calls.as_deref_mut(), traced) {
+                            calls.push(TracedCall::new("fetch", &request, &result, fetch_span.elapsed_ms()));
+                        }
+                        match &result.error {
+                            Some(e) => span_collector.add(fetch_span.finish_with_error(e.clone())),
+                            None => span_collector.add(fetch_span.finish()),
+                        }
+                        let _ = response_tx.send(result);
+                    }
                     None => {
                         // Channel closed, JS finished
                         break;
@@ -3,11 +3,16 @@
 //! Handles rquickjs Runtime/Context creation and script execution.
 
 use rquickjs::{Context as JsContext, FromJs, Function, Object, Runtime, Value as JsValue};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use super::bindings::{HostBridge, HostBridgeGuard, native_host_call};
+use super::bindings::{
+    HostBridge, HostBridgeGuard, native_host_call, native_host_fetch, native_host_service,
+};
 use super::context::{js_to_json, preprocess_script};
+use super::imports::{entry_id, load_import, rewrite_imports};
+use super::stdlib;
 
 /// Maximum iterations to wait for async Promise resolution.
 const MAX_ASYNC_ITERATIONS: usize = 10000;
@@ -16,9 +21,14 @@ const MAX_ASYNC_ITERATIONS: usize = 10000;
 /// Prevents DoS from scripts that create infinite microtasks.
 const ASYNC_TIMEOUT_SECS: u64 = 5;
 
-/// Run `JavaScript` with `host.call()` capability (blocking).
+/// Run `JavaScript` with the `host` object (blocking).
+///
+/// `script_name` and `scripts_dir` locate the script for `import`/`require`
+/// (see [`super::imports`]).
 pub(crate) fn run_js_script(
+    script_name: &str,
     script: &str,
+    scripts_dir: Option<PathBuf>,
     input: &serde_json::Value,
     bridge: Arc<HostBridge>,
 ) -> std::result::Result<serde_json::Value, String> {
@@ -41,19 +51,151 @@ pub(crate) fn run_js_script(
             .set("__host_call", host_call_fn)
             .map_err(|e| format!("Failed to set __host_call: {e}"))?;
 
-        // Create host.call() wrapper in JavaScript
+        // Register native __host_service function (host.kv, host.storage)
+        let host_service_fn = Function::new(ctx.clone(), native_host_service)
+            .map_err(|e| format!("Failed to create host_service function: {e}"))?;
+
+        globals
+            .set("__host_service", host_service_fn)
+            .map_err(|e| format!("Failed to set __host_service: {e}"))?;
+
+        // Register native __host_fetch function (fetch)
+        let host_fetch_fn = Function::new(ctx.clone(), native_host_fetch)
+            .map_err(|e| format!("Failed to create host_fetch function: {e}"))?;
+
+        globals
+            .set("__host_fetch", host_fetch_fn)
+            .map_err(|e| format!("Failed to set __host_fetch: {e}"))?;
+
+        // Register native __load_script function (import, require)
+        let load_script_fn = Function::new(
+            ctx.clone(),
+            move |specifier: String, from: String| -> rquickjs::Result<String> {
+                let result = scripts_dir
+                    .as_deref()
+                    .ok_or_else(|| "Scripts directory not configured".to_string())
+                    .and_then(|dir| load_import(dir, &from, &specifier));
+                let json = match result {
+                    Ok(import) => serde_json::json!({ "id": import.id, "source": import.source }),
+                    Err(error) => serde_json::json!({ "error": error }),
+                };
+                Ok(json.to_string())
+            },
+        )
+        .map_err(|e| format!("Failed to create load_script function: {e}"))?;
+
+        globals
+            .set("__load_script", load_script_fn)
+            .map_err(|e| format!("Failed to set __load_script: {e}"))?;
+
+        // Register the std object (crypto, encoding, uuid, sleep)
+        stdlib::register(&ctx)?;
+
+        // Create host wrappers in JavaScript
         let host_wrapper = r"
+            var __service = function(op, args) {
+                args.op = op;
+                var result = JSON.parse(__host_service(JSON.stringify(args)));
+                if (result.error) {
+                    throw new Error(result.error);
+                }
+                return result.value;
+            };
             var host = {
                 call: function(module, options) {
                     options = options || {};
                     var result = __host_call(module, JSON.stringify(options));
                     return JSON.parse(result);
+                },
+                kv: {
+                    get: function(key) {
+                        return __service('kv.get', { key: key });
+                    },
+                    set: function(key, value, options) {
+                        options = options || {};
+                        __service('kv.set', { key: key, value: String(value), ttl: options.ttl });
+                    },
+                    delete: function(key) {
+                        __service('kv.delete', { key: key });
+                    }
+                },
+                storage: {
+                    get: function(path) {
+                        return __service('storage.get', { path: path });
+                    },
+                    put: function(path, data, options) {
+                        options = options || {};
+                        var contentType = options.contentType;
+                        if (typeof data !== 'string') {
+                            data = JSON.stringify(data);
+                            contentType = contentType || 'application/json';
+                        }
+                        __service('storage.put', { path: path, data: data, contentType: contentType });
+                    },
+                    delete: function(path) {
+                        __service('storage.delete', { path: path });
+                    }
+                }
+            };
+            var fetch = function(url, options) {
+                options = options || {};
+                var body = options.body;
+                if (body !== undefined && body !== null && typeof body !== 'string') {
+                    body = JSON.stringify(body);
                 }
+                var result = JSON.parse(__host_fetch(JSON.stringify({
+                    url: String(url),
+                    method: options.method,
+                    headers: options.headers,
+                    body: body
+                })));
+                if (result.error) {
+                    throw new Error(result.error);
+                }
+                return {
+                    status: result.status,
+                    ok: result.status >= 200 && result.status < 300,
+                    headers: result.headers,
+                    text: function() { return result.body; },
+                    json: function() { return JSON.parse(result.body); }
+                };
             };
         ";
         ctx.eval::<(), _>(host_wrapper)
             .map_err(|e| format!("Failed to create host wrapper: {e}"))?;
 
+        // Create require() for the entry script; imported files get their own
+        let entry_json = serde_json::to_string(&entry_id(script_name))
+            .map_err(|e| format!("Failed to serialize script name: {e}"))?;
+        let require_wrapper = format!(
+            r"
+            var __modules = {{}};
+            var __import_default = function(m) {{
+                return m && m.default !== undefined ? m.default : m;
+            }};
+            var __require_from = function(from) {{
+                return function(specifier) {{
+                    var loaded = JSON.parse(__load_script(String(specifier), from));
+                    if (loaded.error) {{
+                        throw new Error(loaded.error);
+                    }}
+                    var cached = __modules[loaded.id];
+                    if (cached) {{
+                        return cached.exports;
+                    }}
+                    var module = {{ exports: {{}} }};
+                    __modules[loaded.id] = module;
+                    var body = new Function('module', 'exports', 'require', loaded.source);
+                    body(module, module.exports, __require_from(loaded.id));
+                    return module.exports;
+                }};
+            }};
+            var require = __require_from({entry_json});
+        "
+        );
+        ctx.eval::<(), _>(require_wrapper.as_str())
+            .map_err(|e| format!("Failed to create require: {e}"))?;
+
         // Set input object
         let input_json =
             serde_json::to_string(&input).map_err(|e| format!("Failed to serialize input: {e}"))?;
@@ -62,10 +204,10 @@ pub(crate) fn run_js_script(
             .map_err(|e| format!("Failed to set input: {e}"))?;
 
         // Preprocess and execute the script
-        let wrapped_script = preprocess_script(script);
+        let wrapped_script = preprocess_script(&rewrite_imports(script));
         let result: JsValue<'_> = ctx
             .eval(wrapped_script.as_str())
-            .map_err(|e| format!("Script error: {e}"))?;
+            .map_err(|e| format!("Script error: {}", error_message(&ctx, &e)))?;
 
         // Check if this is an async result object
         if let Ok(obj) = Object::from_js(&ctx, result.clone())
@@ -81,6 +223,19 @@ pub(crate) fn run_js_script(
     // _guard is dropped here, clearing the thread-local bridge
 }
 
+/// Message of a JS error, including the thrown value for exceptions.
+fn error_message(ctx: &rquickjs::Ctx<'_>, error: &rquickjs::Error) -> String {
+    if !error.is_exception() {
+        return error.to_string();
+    }
+    let thrown = ctx.catch();
+    thrown
+        .as_exception()
+        .and_then(rquickjs::Exception::message)
+        .or_else(|| thrown.as_string().and_then(|s| s.to_string().ok()))
+        .unwrap_or_else(|| error.to_string())
+}
+
 /// Resolve an async script result by running pending jobs.
 fn resolve_async_result<'js>(
     runtime: &Runtime,
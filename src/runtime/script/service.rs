@@ -0,0 +1,120 @@
+//! Daemon service calls for scripts.
+//!
+//! Executes `host.kv.*` and `host.storage.*` calls from `JavaScript` against
+//! the local daemon's HTTP API. The daemon owns the KV and storage files, so
+//! the runtime goes through the API rather than opening them itself.
+
+use anyhow::Result;
+
+use super::types::{ServiceOp, ServiceResult};
+use crate::daemon::http::client::DaemonClient;
+use crate::manifest::ScriptCapabilities;
+
+/// Execute a single service call, if `capabilities` allow it.
+pub(crate) async fn execute_service_call(
+    daemon: Option<&DaemonClient>,
+    script: &str,
+    capabilities: ScriptCapabilities,
+    op: ServiceOp,
+) -> ServiceResult {
+    let (service, granted) = match op {
+        ServiceOp::KvGet { .. } | ServiceOp::KvSet { .. } | ServiceOp::KvDelete { .. } => {
+            ("kv", capabilities.kv)
+        },
+        _ => ("storage", capabilities.storage),
+    };
+    if !granted {
+        return ServiceResult::failed(format!(
+            "host.{service} is not enabled for script '{script}' (see [server.script_capabilities])"
+        ));
+    }
+    let Some(daemon) = daemon else {
+        return ServiceResult::failed("Daemon services are not configured");
+    };
+
+    let name = op.name();
+    match run(daemon, op).await {
+        Ok(value) => ServiceResult { value, error: None },
+        Err(e) => ServiceResult::failed(format!("host.{name} failed: {e:#}")),
+    }
+}
+
+async fn run(daemon: &DaemonClient, op: ServiceOp) -> Result<serde_json::Value> {
+    let value = match op {
+        ServiceOp::KvGet { key } => daemon.kv_get(&key).await?.into(),
+        ServiceOp::KvSet { key, value, ttl } => {
+            daemon.kv_set(&key, &value, ttl).await?;
+            serde_json::Value::Null
+        },
+        ServiceOp::KvDelete { key } => {
+            daemon.kv_delete(&key).await?;
+            serde_json::Value::Null
+        },
+        ServiceOp::StorageGet { path } => daemon
+            .storage_get(&path)
+            .await?
+            .map(|data| String::from_utf8_lossy(&data).into_owned())
+            .into(),
+        ServiceOp::StoragePut {
+            path,
+            data,
+            content_type,
+        } => {
+            daemon
+                .storage_put(&path, data.into_bytes(), content_type.as_deref())
+                .await?;
+            serde_json::Value::Null
+        },
+        ServiceOp::StorageDelete { path } => {
+            daemon.storage_delete(&path).await?;
+            serde_json::Value::Null
+        },
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_service_call_requires_capability() {
+        let op = ServiceOp::KvGet {
+            key: "a".to_string(),
+        };
+        let result =
+            execute_service_call(None, "checkout", ScriptCapabilities::default(), op).await;
+        let error = result.error.unwrap();
+        assert!(error.contains("host.kv is not enabled for script 'checkout'"));
+
+        let op = ServiceOp::StorageDelete {
+            path: "a.txt".to_string(),
+        };
+        let kv_only = ScriptCapabilities {
+            kv: true,
+            storage: false,
+        };
+        let result = execute_service_call(None, "checkout", kv_only, op).await;
+        assert!(
+            result
+                .error
+                .unwrap()
+                .contains("host.storage is not enabled")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_call_reports_daemon_errors() {
+        // Nothing listens on port 1
+        let daemon = DaemonClient::local(1);
+        let op = ServiceOp::KvGet {
+            key: "a".to_string(),
+        };
+        let capabilities = ScriptCapabilities {
+            kv: true,
+            storage: false,
+        };
+        let result = execute_service_call(Some(&daemon), "checkout", capabilities, op).await;
+        assert!(result.error.unwrap().starts_with("host.kv.get failed:"));
+    }
+}
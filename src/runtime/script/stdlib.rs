@@ -0,0 +1,202 @@
+//! Built-in `std` object for scripts.
+//!
+//! Scripts cannot load packages from `node_modules`, so common primitives are
+//! provided natively:
+//!
+//! - `std.crypto.sha256(data)`, `std.crypto.hmacSha256(key, data)` - hex digests
+//! - `std.encoding.base64Encode/base64Decode`, `std.encoding.hexEncode/hexDecode`
+//! - `std.uuid()` - random (v4) UUID
+//! - `std.sleep(ms)` - pause, at most [`MAX_SLEEP_MS`] per script in total
+//!
+//! Strings are treated as UTF-8 bytes. Decoding returns a string, with
+//! invalid UTF-8 replaced.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hmac::{Hmac, Mac};
+use rquickjs::{Ctx, Function};
+use sha2::{Digest, Sha256};
+use std::cell::Cell;
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Total time a script may spend in `std.sleep()`.
+///
+/// Kept well below the async resolution timeout so sleeping scripts still
+/// finish.
+pub(crate) const MAX_SLEEP_MS: u64 = 1000;
+
+/// JS wrapper exposing the natives as `std`.
+const STD_WRAPPER: &str = r"
+    var __std_call = function(result) {
+        result = JSON.parse(result);
+        if (result.error) {
+            throw new Error(result.error);
+        }
+        return result.value;
+    };
+    var std = {
+        crypto: {
+            sha256: function(data) { return __std_sha256(String(data)); },
+            hmacSha256: function(key, data) { return __std_hmac_sha256(String(key), String(data)); }
+        },
+        encoding: {
+            base64Encode: function(data) { return __std_base64_encode(String(data)); },
+            base64Decode: function(data) { return __std_call(__std_base64_decode(String(data))); },
+            hexEncode: function(data) { return __std_hex_encode(String(data)); },
+            hexDecode: function(data) { return __std_call(__std_hex_decode(String(data))); }
+        },
+        uuid: function() { return __std_uuid(); },
+        sleep: function(ms) {
+            __std_call(__std_sleep(Number(ms) || 0));
+            return Promise.resolve();
+        }
+    };
+";
+
+/// Register the `std` object in `ctx`.
+pub(crate) fn register(ctx: &Ctx<'_>) -> Result<(), String> {
+    let slept_ms = Cell::new(0u64);
+
+    set(ctx, "__std_sha256", |data: String| {
+        hex::encode(Sha256::digest(data.as_bytes()))
+    })?;
+    set(ctx, "__std_hmac_sha256", |key: String, data: String| {
+        let mut mac =
+            HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+        mac.update(data.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    })?;
+    set(ctx, "__std_base64_encode", |data: String| {
+        BASE64.encode(data.as_bytes())
+    })?;
+    set(ctx, "__std_base64_decode", |data: String| {
+        decoded_json(
+            BASE64
+                .decode(data.trim())
+                .map_err(|e| format!("Invalid base64: {e}")),
+        )
+    })?;
+    set(ctx, "__std_hex_encode", |data: String| {
+        hex::encode(data.as_bytes())
+    })?;
+    set(ctx, "__std_hex_decode", |data: String| {
+        decoded_json(hex::decode(data.trim()).map_err(|e| format!("Invalid hex: {e}")))
+    })?;
+    set(ctx, "__std_uuid", || uuid::Uuid::new_v4().to_string())?;
+    set(ctx, "__std_sleep", move |ms: f64| {
+        match sleep(&slept_ms, ms) {
+            Ok(()) => serde_json::json!({ "value": null }),
+            Err(error) => serde_json::json!({ "error": error }),
+        }
+        .to_string()
+    })?;
+
+    ctx.eval::<(), _>(STD_WRAPPER)
+        .map_err(|e| format!("Failed to create std object: {e}"))
+}
+
+fn set<'js, F, P>(ctx: &Ctx<'js>, name: &str, f: F) -> Result<(), String>
+where
+    F: rquickjs::function::IntoJsFunc<'js, P> + 'js,
+{
+    let function =
+        Function::new(ctx.clone(), f).map_err(|e| format!("Failed to create {name}: {e}"))?;
+    ctx.globals()
+        .set(name, function)
+        .map_err(|e| format!("Failed to set {name}: {e}"))
+}
+
+/// JSON `{"value": ...}` or `{"error": ...}` for decoded bytes.
+fn decoded_json(decoded: Result<Vec<u8>, String>) -> String {
+    match decoded {
+        Ok(bytes) => serde_json::json!({ "value": String::from_utf8_lossy(&bytes) }),
+        Err(error) => serde_json::json!({ "error": error }),
+    }
+    .to_string()
+}
+
+/// Sleep for `ms`, charging it against the script's [`MAX_SLEEP_MS`] budget.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // clamped to 0..=MAX_SLEEP_MS
+fn sleep(slept_ms: &Cell<u64>, ms: f64) -> Result<(), String> {
+    let ms = ms.clamp(0.0, MAX_SLEEP_MS as f64) as u64;
+    let total = slept_ms.get() + ms;
+    if total > MAX_SLEEP_MS {
+        return Err(format!(
+            "std.sleep budget exceeded: scripts may sleep at most {MAX_SLEEP_MS}ms in total"
+        ));
+    }
+    slept_ms.set(total);
+    std::thread::sleep(Duration::from_millis(ms));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rquickjs::{Context as JsContext, Runtime};
+
+    fn eval(script: &str) -> Result<String, String> {
+        let runtime = Runtime::new().unwrap();
+        let context = JsContext::full(&runtime).unwrap();
+        context.with(|ctx| {
+            register(&ctx)?;
+            ctx.eval::<String, _>(script).map_err(|e| {
+                let thrown = ctx.catch();
+                thrown
+                    .as_exception()
+                    .and_then(rquickjs::Exception::message)
+                    .unwrap_or_else(|| e.to_string())
+            })
+        })
+    }
+
+    #[test]
+    fn test_std_crypto() {
+        assert_eq!(
+            eval("std.crypto.sha256('abc')").unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // RFC 4231 test case 2
+        assert_eq!(
+            eval("std.crypto.hmacSha256('Jefe', 'what do ya want for nothing?')").unwrap(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_std_encoding() {
+        assert_eq!(
+            eval("std.encoding.base64Encode('hello')").unwrap(),
+            "aGVsbG8="
+        );
+        assert_eq!(
+            eval("std.encoding.base64Decode('aGVsbG8=')").unwrap(),
+            "hello"
+        );
+        assert_eq!(eval("std.encoding.hexEncode('hi')").unwrap(), "6869");
+        assert_eq!(eval("std.encoding.hexDecode('6869')").unwrap(), "hi");
+
+        let err = eval("std.encoding.hexDecode('zz')").unwrap_err();
+        assert!(err.starts_with("Invalid hex"), "{err}");
+    }
+
+    #[test]
+    fn test_std_uuid() {
+        let id = eval("std.uuid()").unwrap();
+        let parsed = uuid::Uuid::parse_str(&id).unwrap();
+        assert_eq!(parsed.get_version_num(), 4);
+        assert_ne!(id, eval("std.uuid()").unwrap());
+    }
+
+    #[test]
+    fn test_std_sleep_budget() {
+        let slept = Cell::new(MAX_SLEEP_MS - 15);
+        assert!(sleep(&slept, 10.0).is_ok());
+        assert!(sleep(&slept, -5.0).is_ok());
+        assert_eq!(slept.get(), MAX_SLEEP_MS - 5);
+        assert!(sleep(&slept, 10.0).unwrap_err().contains("budget exceeded"));
+        assert!(sleep(&slept, 5.0).is_ok());
+    }
+}
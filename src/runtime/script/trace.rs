@@ -0,0 +1,144 @@
+//! Script tracing (`POST /script/<name>?trace=1`).
+//!
+//! A traced request runs the script as usual but returns, next to the
+//! result, the span tree of the execution and every host call (`host.call`,
+//! `host.kv.*`, `host.storage.*`, `fetch`) with its request, response and
+//! timing. Failed scripts still return their trace, with the error.
+//!
+//! Traces include headers and bodies the caller would not otherwise see, so
+//! they are only returned in dev mode (`mik dev`, `mik run --local`) or to
+//! callers with the API key. Otherwise the parameter is ignored.
+
+use anyhow::Result;
+use http_body_util::Full;
+use hyper::Response;
+use hyper::body::Bytes;
+use serde::Serialize;
+
+use super::types::{ScriptResponse, TracedCall};
+use crate::runtime::SharedState;
+use crate::runtime::gateway::modules::has_api_key;
+use crate::runtime::spans::{Span, SpanNode, span_tree};
+
+/// Whether the query string asks for a trace (`trace=1` or `trace=true`).
+pub(crate) fn is_requested(query: Option<&str>) -> bool {
+    query.is_some_and(|query| {
+        query
+            .split('&')
+            .any(|pair| matches!(pair, "trace" | "trace=1" | "trace=true"))
+    })
+}
+
+/// Whether this caller may receive traces: always in dev mode, otherwise
+/// only with the API key.
+pub(crate) fn is_allowed(shared: &SharedState, headers: &hyper::HeaderMap) -> bool {
+    shared.config.trace_scripts
+        || shared.config.dev_dashboard.is_some()
+        || has_api_key(shared, headers)
+}
+
+/// Everything recorded while a traced script ran.
+#[derive(Debug, Serialize)]
+pub(crate) struct ScriptTrace {
+    pub trace_id: String,
+    pub total_ms: u64,
+    pub spans: Vec<SpanNode>,
+    pub calls: Vec<TracedCall>,
+}
+
+impl ScriptTrace {
+    pub fn new(trace_id: &str, total_ms: u64, spans: &[Span], calls: Vec<TracedCall>) -> Self {
+        Self {
+            trace_id: trace_id.to_string(),
+            total_ms,
+            spans: span_tree(spans),
+            calls,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TracedResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    calls_executed: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    trace: ScriptTrace,
+}
+
+/// Response for a traced script: 200 with the result, or 500 with the error.
+pub(crate) fn response(
+    result: Result<ScriptResponse>,
+    trace: ScriptTrace,
+) -> Result<Response<Full<Bytes>>> {
+    let (status, body) = match result {
+        Ok(response) => (
+            200,
+            TracedResponse {
+                result: Some(response.result),
+                calls_executed: Some(response.calls_executed),
+                error: None,
+                trace,
+            },
+        ),
+        Err(e) => (
+            500,
+            TracedResponse {
+                result: None,
+                calls_executed: None,
+                error: Some(e.to_string()),
+                trace,
+            },
+        ),
+    };
+    Ok(Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(serde_json::to_vec(&body)?)))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::spans::SpanBuilder;
+    use http_body_util::BodyExt;
+
+    #[test]
+    fn test_is_requested() {
+        assert!(is_requested(Some("trace=1")));
+        assert!(is_requested(Some("a=b&trace=true")));
+        assert!(is_requested(Some("trace")));
+        assert!(!is_requested(Some("trace=0")));
+        assert!(!is_requested(Some("retrace=1")));
+        assert!(!is_requested(None));
+    }
+
+    #[tokio::test]
+    async fn test_traced_response() {
+        let script = SpanBuilder::new("script.checkout");
+        let auth = SpanBuilder::with_parent("handler.auth", script.span_id()).finish();
+        let call = TracedCall::new(
+            "host.call",
+            &serde_json::json!({ "module": "auth" }),
+            &serde_json::json!({ "status": 200 }),
+            3,
+        );
+        let trace = ScriptTrace::new("trace-1", 5, &[auth, script.finish()], vec![call]);
+
+        let response = super::response(Err(anyhow::anyhow!("Script error: boom")), trace).unwrap();
+        assert_eq!(response.status(), 500);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "Script error: boom");
+        assert!(json.get("result").is_none());
+        assert_eq!(json["trace"]["trace_id"], "trace-1");
+        assert_eq!(
+            json["trace"]["spans"][0]["children"][0]["name"],
+            "handler.auth"
+        );
+        assert_eq!(json["trace"]["calls"][0]["kind"], "host.call");
+        assert_eq!(json["trace"]["calls"][0]["response"]["status"], 200);
+    }
+}
@@ -3,6 +3,7 @@
 //! Contains message types for JS-to-Rust communication and response structures.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Message from JS to async handler for `host.call()`
 #[derive(Debug)]
@@ -15,6 +16,110 @@ pub(crate) enum HostMessage {
         body: Option<serde_json::Value>,
         response_tx: std::sync::mpsc::Sender<HostCallResult>,
     },
+    /// `host.kv.*` and `host.storage.*` calls
+    Service {
+        op: ServiceOp,
+        response_tx: std::sync::mpsc::Sender<ServiceResult>,
+    },
+    /// `fetch()` calls
+    Fetch {
+        request: FetchRequest,
+        response_tx: std::sync::mpsc::Sender<FetchResult>,
+    },
+}
+
+/// An outgoing HTTP request made with `fetch()`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct FetchRequest {
+    pub url: String,
+    #[serde(default = "default_fetch_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+fn default_fetch_method() -> String {
+    "GET".to_string()
+}
+
+/// Result of a `fetch()` call
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct FetchResult {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FetchResult {
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            error: Some(error.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// A daemon service operation requested by a script.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op")]
+pub(crate) enum ServiceOp {
+    #[serde(rename = "kv.get")]
+    KvGet { key: String },
+    #[serde(rename = "kv.set")]
+    KvSet {
+        key: String,
+        value: String,
+        #[serde(default)]
+        ttl: Option<u64>,
+    },
+    #[serde(rename = "kv.delete")]
+    KvDelete { key: String },
+    #[serde(rename = "storage.get")]
+    StorageGet { path: String },
+    #[serde(rename = "storage.put")]
+    StoragePut {
+        path: String,
+        data: String,
+        #[serde(default, rename = "contentType")]
+        content_type: Option<String>,
+    },
+    #[serde(rename = "storage.delete")]
+    StorageDelete { path: String },
+}
+
+impl ServiceOp {
+    /// Name of the operation as called from JS, e.g. `kv.get`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::KvGet { .. } => "kv.get",
+            Self::KvSet { .. } => "kv.set",
+            Self::KvDelete { .. } => "kv.delete",
+            Self::StorageGet { .. } => "storage.get",
+            Self::StoragePut { .. } => "storage.put",
+            Self::StorageDelete { .. } => "storage.delete",
+        }
+    }
+}
+
+/// Result of a `host.kv.*` or `host.storage.*` call
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct ServiceResult {
+    pub value: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ServiceResult {
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            value: serde_json::Value::Null,
+            error: Some(error.into()),
+        }
+    }
 }
 
 /// Result of a `host.call()` invocation
@@ -27,6 +132,32 @@ pub(crate) struct HostCallResult {
     pub error: Option<String>,
 }
 
+/// A host call recorded for `?trace=1` (see [`super::trace`]).
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct TracedCall {
+    /// `host.call`, `host.kv.get`, `fetch`, ...
+    pub kind: String,
+    pub request: serde_json::Value,
+    pub response: serde_json::Value,
+    pub duration_ms: u64,
+}
+
+impl TracedCall {
+    pub fn new(
+        kind: &str,
+        request: &impl Serialize,
+        response: &impl Serialize,
+        duration_ms: u64,
+    ) -> Self {
+        Self {
+            kind: kind.to_string(),
+            request: serde_json::to_value(request).unwrap_or_default(),
+            response: serde_json::to_value(response).unwrap_or_default(),
+            duration_ms,
+        }
+    }
+}
+
 /// Request body for script execution
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -76,6 +207,50 @@ mod tests {
         assert_eq!(json["error"], "HANDLER_ERROR");
     }
 
+    #[test]
+    fn test_service_op_deserialization() {
+        let op: ServiceOp =
+            serde_json::from_value(json!({"op": "kv.set", "key": "a", "value": "1", "ttl": 60}))
+                .unwrap();
+        assert_eq!(
+            op,
+            ServiceOp::KvSet {
+                key: "a".to_string(),
+                value: "1".to_string(),
+                ttl: Some(60),
+            }
+        );
+
+        let op: ServiceOp = serde_json::from_value(
+            json!({"op": "storage.put", "path": "a/b.json", "data": "{}", "contentType": "application/json"}),
+        )
+        .unwrap();
+        assert_eq!(op.name(), "storage.put");
+
+        assert!(serde_json::from_value::<ServiceOp>(json!({"op": "queue.push"})).is_err());
+        assert!(serde_json::from_value::<ServiceOp>(json!({"op": "kv.get"})).is_err());
+    }
+
+    #[test]
+    fn test_fetch_request_defaults() {
+        let request: FetchRequest =
+            serde_json::from_value(json!({"url": "https://api.example.com/v1"})).unwrap();
+        assert_eq!(request.method, "GET");
+        assert!(request.headers.is_empty());
+        assert!(request.body.is_none());
+
+        let request: FetchRequest = serde_json::from_value(json!({
+            "url": "https://api.example.com/v1",
+            "method": "POST",
+            "headers": {"content-type": "application/json"},
+            "body": "{}"
+        }))
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.headers["content-type"], "application/json");
+        assert_eq!(request.body.as_deref(), Some("{}"));
+    }
+
     #[test]
     fn test_script_response_serialization() {
         let response = ScriptResponse {
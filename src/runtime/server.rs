@@ -9,9 +9,13 @@
 //! # Architecture
 //!
 //! The server layer is intentionally thin - it only handles:
-//! - TCP listener management
+//! - Listener management (TCP and Unix sockets, see
+//!   [`listener`](crate::runtime::listener))
 //! - Connection acceptance
 //! - Graceful shutdown coordination
+//! - Zero-downtime upgrades (`SIGUSR2` hands the listener to a new binary,
+//!   see [`handoff`](crate::runtime::handoff))
+//! - Configuration reloads (`SIGHUP`, see [`reload`](crate::runtime::reload))
 //!
 //! All request handling logic is delegated to the underlying [`Runtime`].
 //!
@@ -33,30 +37,42 @@
 //! # }
 //! ```
 
+use crate::constants;
+use crate::runtime::drain;
+use crate::runtime::gateway;
+use crate::runtime::handoff::{self, UpgradeSignal};
+use crate::runtime::listener::{ListenAddr, Listener};
+use crate::runtime::proxy_protocol;
+use crate::runtime::reload::ReloadSignal;
 use crate::runtime::{
     HEALTH_PATH, METRICS_PATH, OPENAPI_PREFIX, RUN_PREFIX, Runtime, SCRIPT_PREFIX, STATIC_PREFIX,
+    SharedState,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use hyper::service::service_fn;
 use hyper_util::rt::{TokioExecutor, TokioIo};
 use hyper_util::server::conn::auto::Builder as HttpConnectionBuilder;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::Ordering;
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpListener;
 use tracing::{debug, error, info, warn};
 
-/// Shutdown polling interval in milliseconds.
-const SHUTDOWN_POLL_INTERVAL_MS: u64 = 100;
+/// Delay before accepting again after an accept error, in milliseconds.
+const ACCEPT_ERROR_BACKOFF_MS: u64 = 100;
 
 /// HTTP server that wraps a [`Runtime`] and serves requests on a network address.
 ///
 /// The server handles:
-/// - TCP connection management
+/// - TCP and Unix socket connection management, on one or more listeners
 /// - HTTP/1.1 and HTTP/2 auto-detection
 /// - Graceful shutdown with connection draining
 /// - Concurrent request limiting via semaphore
+/// - Listener handoff to an upgraded binary on `SIGUSR2` (Unix)
+/// - PROXY protocol headers from a load balancer (see
+///   [`proxy_protocol`](crate::runtime::proxy_protocol))
 ///
 /// # Examples
 ///
@@ -78,13 +94,41 @@ const SHUTDOWN_POLL_INTERVAL_MS: u64 = 100;
 pub struct Server {
     runtime: Runtime,
     addr: SocketAddr,
+    listeners: Vec<ListenAddr>,
+    reuse_port: bool,
 }
 
 impl Server {
     /// Create a new server with the given runtime and address.
     #[must_use]
     pub fn new(runtime: Runtime, addr: SocketAddr) -> Self {
-        Self { runtime, addr }
+        Self {
+            runtime,
+            addr,
+            listeners: Vec::new(),
+            reuse_port: false,
+        }
+    }
+
+    /// Also serve on `addr`, alongside the primary address.
+    ///
+    /// Addresses from the runtime's
+    /// [`listen`](crate::runtime::HostConfig::listen) setting are added
+    /// automatically when serving.
+    #[must_use]
+    pub fn with_listener(mut self, addr: impl Into<ListenAddr>) -> Self {
+        self.listeners.push(addr.into());
+        self
+    }
+
+    /// Set `SO_REUSEPORT` on the listener (Unix only).
+    ///
+    /// Lets a separately started server bind the same address while this one
+    /// drains. Not needed for `SIGUSR2` upgrades, which share the socket.
+    #[must_use]
+    pub const fn with_reuse_port(mut self, reuse_port: bool) -> Self {
+        self.reuse_port = reuse_port;
+        self
     }
 
     /// Create a server by parsing an address string.
@@ -103,6 +147,12 @@ impl Server {
         self.addr
     }
 
+    /// Get the additional addresses the server will bind to.
+    #[must_use]
+    pub fn listeners(&self) -> &[ListenAddr] {
+        &self.listeners
+    }
+
     /// Get a reference to the underlying runtime.
     #[must_use]
     pub const fn runtime(&self) -> &Runtime {
@@ -117,26 +167,54 @@ impl Server {
 
     /// Start serving HTTP requests.
     ///
-    /// This method binds to the configured address and starts accepting
-    /// connections. It runs until a shutdown signal is received (SIGTERM/SIGINT).
+    /// This method binds to the configured address and any additional
+    /// listeners and starts accepting connections on all of them. It runs
+    /// until a shutdown signal is received (SIGTERM/SIGINT).
     ///
     /// # Graceful Shutdown
     ///
-    /// When a shutdown signal is received:
+    /// When a shutdown signal is received or [`Runtime::shutdown`] is called:
     /// 1. Stop accepting new connections
-    /// 2. Wait for in-flight requests to complete (with timeout)
-    /// 3. Return
+    /// 2. Wait for in-flight requests to complete, up to `shutdown_timeout_secs`
+    /// 3. Cancel WASM executions still running, log a drain report, and return
+    ///
+    /// See [`drain`](crate::runtime::drain) for details.
+    ///
+    /// # Zero-Downtime Upgrade
+    ///
+    /// On `SIGUSR2` the server starts the current binary again with the same
+    /// arguments and hands it the listening socket. Once the new process is
+    /// accepting, it sends `SIGTERM` here and this server drains as above.
+    ///
+    /// Only the primary listener is handed off. Additional Unix sockets are
+    /// rebound by the new process; additional TCP listeners need
+    /// [`with_reuse_port`](Self::with_reuse_port) to be bound twice.
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - The address cannot be bound
+    /// - The single component's `init`/`warmup` export fails
+    /// - An address cannot be bound
     /// - A fatal server error occurs
     pub async fn serve(self) -> Result<()> {
-        let listener = TcpListener::bind(self.addr).await?;
+        self.runtime.warmup().await?;
+        let (listener, inherited) = handoff::bind_listener(self.addr, self.reuse_port)?;
+        let listener = TcpListener::from_std(listener)?;
         let shared = self.runtime.shared.clone();
 
+        let mut extra_addrs = self.listeners.clone();
+        for addr in &shared.config.listen {
+            extra_addrs.push(addr.parse()?);
+        }
+        let extra_listeners = extra_addrs
+            .iter()
+            .map(|addr| Listener::bind(addr, self.reuse_port))
+            .collect::<Result<Vec<_>>>()?;
+
         info!("Serving on http://{}", self.addr);
+        for addr in &extra_addrs {
+            info!("Serving on {}", addr);
+        }
         info!("Health endpoint: {}", HEALTH_PATH);
         info!("Metrics endpoint: {}", METRICS_PATH);
 
@@ -164,100 +242,157 @@ impl Server {
             shutdown_signal.store(true, Ordering::SeqCst);
         });
 
-        // Track active connection tasks
-        let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
-        let active_connections = Arc::new(AtomicU64::new(0));
+        let mut upgrade_signal = UpgradeSignal::new();
+        let mut reload_signal = ReloadSignal::new();
+
+        let extra_accept_tasks: Vec<_> = extra_listeners
+            .into_iter()
+            .map(|extra| tokio::spawn(accept_loop(extra, shared.clone())))
+            .collect();
+        let webhook_task = gateway::webhook::spawn(&shared);
+
+        // Now accepting: a previous process handing off can start draining
+        if inherited {
+            handoff::notify_parent();
+        }
 
         loop {
             tokio::select! {
                 accept_result = listener.accept() => {
                     let (stream, remote_addr) = accept_result?;
+                    let shutting_down = shared.shutdown.load(Ordering::SeqCst);
+
+                    spawn_connection(&shared, stream, remote_addr).await;
 
-                    // Check if shutdown has been initiated
-                    if shared.shutdown.load(Ordering::SeqCst) {
-                        debug!("Rejecting new connection during shutdown");
+                    // Serve the connection already accepted, then stop accepting
+                    if shutting_down {
+                        debug!("Shutdown initiated, no longer accepting connections");
                         break;
                     }
+                }
 
-                    let io = TokioIo::new(stream);
-                    let shared = shared.clone();
-                    let active_conns = active_connections.clone();
-                    let shutdown_tx = shutdown_tx.clone();
-
-                    // Acquire semaphore permit to limit concurrent requests
-                    let Ok(permit) = shared.request_semaphore.clone().acquire_owned().await else {
-                        warn!("Failed to acquire request permit, semaphore closed");
-                        continue;
-                    };
-
-                    // Increment active connection count
-                    active_conns.fetch_add(1, Ordering::SeqCst);
-
-                    tokio::spawn(async move {
-                        let _permit = permit;
-                        let _shutdown_guard = shutdown_tx;
-
-                        let service = service_fn(move |req| {
-                            let shared = shared.clone();
-                            async move {
-                                crate::runtime::request_handler::handle_request(shared, req, remote_addr).await
-                            }
-                        });
-
-                        let builder = HttpConnectionBuilder::new(TokioExecutor::new());
-                        if let Err(e) = builder.serve_connection(io, service).await {
-                            error!("Connection error: {}", e);
-                        }
-
-                        active_conns.fetch_sub(1, Ordering::SeqCst);
-                    });
+                () = upgrade_signal.recv() => {
+                    match handoff::spawn_upgrade(&listener) {
+                        Ok(pid) => info!(pid, "Started upgraded server, draining once it takes over"),
+                        Err(e) => error!("Upgrade failed, continuing to serve: {e:#}"),
+                    }
+                }
+
+                () = reload_signal.recv() => {
+                    info!("Received SIGHUP, reloading configuration");
+                    if let Err(e) = shared.reload() {
+                        error!("Reload failed, keeping the current configuration: {e:#}");
+                    }
                 }
 
                 _ = &mut shutdown_handle => {
                     break;
                 }
+
+                // Runtime::shutdown() from an embedder
+                () = shared.drain.shutdown_requested() => {
+                    break;
+                }
             }
         }
 
-        // Shutdown sequence
+        // Phase 1: stop accepting
         info!("Initiating graceful shutdown...");
         drop(listener);
-        drop(shutdown_tx);
+        for task in extra_accept_tasks.into_iter().chain(webhook_task) {
+            task.abort();
+        }
 
-        // Wait for in-flight requests
+        // Phases 2 and 3: drain in-flight requests, then cancel the rest
         let drain_timeout = Duration::from_secs(shared.config.shutdown_timeout_secs);
-        let active_count = active_connections.load(Ordering::SeqCst);
+        drain::drain(&shared, drain_timeout).await;
 
-        if active_count > 0 {
-            info!(
-                "Waiting for {} active connections to complete (timeout: {:?})",
-                active_count, drain_timeout
-            );
+        info!("Shutdown complete");
+        Ok(())
+    }
+}
 
-            if tokio::time::timeout(drain_timeout, async {
-                while active_connections.load(Ordering::SeqCst) > 0 {
-                    tokio::time::sleep(Duration::from_millis(SHUTDOWN_POLL_INTERVAL_MS)).await;
-                }
-                shutdown_rx.recv().await;
-            })
-            .await
-            .is_ok()
-            {
-                info!("All connections completed gracefully");
-            } else {
-                let remaining = active_connections.load(Ordering::SeqCst);
-                warn!("Shutdown timeout - {} connections still active", remaining);
+/// Accept connections from an additional listener until the task is aborted.
+async fn accept_loop(listener: Listener, shared: Arc<SharedState>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, remote_addr)) => {
+                spawn_connection(&shared, stream, remote_addr).await;
+            },
+            Err(e) => {
+                warn!("Failed to accept connection: {}", e);
+                tokio::time::sleep(Duration::from_millis(ACCEPT_ERROR_BACKOFF_MS)).await;
+            },
+        }
+    }
+}
+
+/// Serve an accepted connection on its own task once a request permit is
+/// available.
+async fn spawn_connection<I>(shared: &Arc<SharedState>, mut stream: I, remote_addr: SocketAddr)
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let shared = shared.clone();
+
+    // Acquire semaphore permit to limit concurrent requests
+    let permit = if let Ok(permit) = shared.request_semaphore.clone().try_acquire_owned() {
+        permit
+    } else {
+        // All slots busy: count the wait (pool exhaustion signal for `mik tune`)
+        shared.stats.record_permit_wait();
+        let Ok(permit) = shared.request_semaphore.clone().acquire_owned().await else {
+            warn!("Failed to acquire request permit, semaphore closed");
+            return;
+        };
+        permit
+    };
+
+    tokio::spawn(async move {
+        let _permit = permit;
+
+        let remote_addr = if shared.config.proxy_protocol {
+            match proxy_client_addr(&mut stream, remote_addr).await {
+                Ok(addr) => addr,
+                Err(e) => {
+                    warn!(remote_addr = %remote_addr, "Rejected connection: {:#}", e);
+                    return;
+                },
             }
         } else {
-            info!("No active connections to drain");
+            remote_addr
+        };
+
+        let io = TokioIo::new(stream);
+        let service = service_fn(move |req| {
+            let shared = shared.clone();
+            async move {
+                crate::runtime::request_handler::handle_request(shared, req, remote_addr).await
+            }
+        });
+
+        let builder = HttpConnectionBuilder::new(TokioExecutor::new());
+        if let Err(e) = builder.serve_connection_with_upgrades(io, service).await {
+            error!("Connection error: {}", e);
         }
+    });
+}
 
-        info!("Shutdown complete");
-        Ok(())
-    }
+/// Client address from the connection's PROXY protocol header, or `peer` for
+/// connections the proxy makes on its own behalf (health checks).
+async fn proxy_client_addr<I: AsyncRead + Unpin>(
+    stream: &mut I,
+    peer: SocketAddr,
+) -> Result<SocketAddr> {
+    let timeout = Duration::from_secs(constants::PROXY_HEADER_TIMEOUT_SECS);
+    let header = tokio::time::timeout(timeout, proxy_protocol::read_header(stream))
+        .await
+        .context("Timed out waiting for PROXY header")??;
+    Ok(header.unwrap_or(peer))
 }
 
-/// Wait for a shutdown signal (SIGTERM/SIGINT on Unix, Ctrl+C on Windows).
+/// Wait for a shutdown signal (SIGTERM/SIGINT on Unix, Ctrl+C or a stop
+/// request from `mik stop` on Windows).
 async fn wait_for_shutdown_signal() {
     #[cfg(unix)]
     {
@@ -275,7 +410,27 @@ async fn wait_for_shutdown_signal() {
         }
     }
 
-    #[cfg(not(unix))]
+    #[cfg(windows)]
+    {
+        let ctrl_c = async {
+            match tokio::signal::ctrl_c().await {
+                Ok(()) => info!("Received Ctrl+C"),
+                Err(e) => {
+                    // Detached instances have no console; they stop on request
+                    warn!("Failed to listen for ctrl_c: {}", e);
+                    std::future::pending::<()>().await;
+                },
+            }
+        };
+        tokio::select! {
+            () = ctrl_c => {}
+            () = crate::daemon::process::stop_requested() => {
+                info!("Received stop request");
+            }
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
     {
         if let Err(e) = tokio::signal::ctrl_c().await {
             error!("Failed to listen for ctrl_c: {}", e);
@@ -308,6 +463,8 @@ async fn wait_for_shutdown_signal() {
 pub struct ServerBuilder {
     runtime: Runtime,
     addr: Option<SocketAddr>,
+    listeners: Vec<ListenAddr>,
+    reuse_port: bool,
 }
 
 impl ServerBuilder {
@@ -317,6 +474,8 @@ impl ServerBuilder {
         Self {
             runtime,
             addr: None,
+            listeners: Vec::new(),
+            reuse_port: false,
         }
     }
 
@@ -337,6 +496,16 @@ impl ServerBuilder {
         self
     }
 
+    /// Also serve on an address (`host:port` or `unix:/path`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be parsed.
+    pub fn listen(mut self, addr: impl AsRef<str>) -> Result<Self> {
+        self.listeners.push(addr.as_ref().parse()?);
+        Ok(self)
+    }
+
     /// Set the port to bind to (uses 0.0.0.0 as the host).
     #[must_use]
     pub fn port(mut self, port: u16) -> Self {
@@ -344,6 +513,13 @@ impl ServerBuilder {
         self
     }
 
+    /// Set `SO_REUSEPORT` on the listener (Unix only).
+    #[must_use]
+    pub const fn reuse_port(mut self, reuse_port: bool) -> Self {
+        self.reuse_port = reuse_port;
+        self
+    }
+
     /// Build the server.
     ///
     /// Uses the configured address or defaults to 127.0.0.1:3000.
@@ -352,7 +528,9 @@ impl ServerBuilder {
         let addr = self
             .addr
             .unwrap_or_else(|| SocketAddr::from(([127, 0, 0, 1], 3000)));
-        Server::new(self.runtime, addr)
+        let mut server = Server::new(self.runtime, addr).with_reuse_port(self.reuse_port);
+        server.listeners = self.listeners;
+        server
     }
 }
 
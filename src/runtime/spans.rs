@@ -161,7 +161,6 @@ impl SpanBuilder {
     }
 
     /// Get elapsed time so far (without completing the span).
-    #[allow(dead_code)]
     pub fn elapsed_ms(&self) -> u64 {
         self.start.elapsed().as_millis() as u64
     }
@@ -199,7 +198,7 @@ impl SpanCollector {
     /// Get the number of spans collected.
     #[allow(dead_code)]
     pub fn len(&self) -> usize {
-        self.spans.lock().map(|g| g.len()).unwrap_or(0)
+        self.spans.lock().map_or(0, |g| g.len())
     }
 
     /// Check if no spans have been collected.
@@ -239,6 +238,46 @@ impl SpanSummary {
     }
 }
 
+/// A span with its children, for returning a trace as a tree.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpanNode {
+    #[serde(flatten)]
+    pub span: Span,
+    /// Child spans, in completion order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SpanNode>,
+}
+
+/// Arrange spans into trees by `parent_id`.
+///
+/// Spans whose parent is not among `spans` become roots.
+pub fn span_tree(spans: &[Span]) -> Vec<SpanNode> {
+    fn children_of(
+        parent: Option<&str>,
+        spans: &[Span],
+        is_root: &dyn Fn(&Span) -> bool,
+    ) -> Vec<SpanNode> {
+        spans
+            .iter()
+            .filter(|s| match parent {
+                Some(id) => s.parent_id.as_deref() == Some(id),
+                None => is_root(s),
+            })
+            .map(|s| SpanNode {
+                span: s.clone(),
+                children: children_of(Some(&s.span_id), spans, is_root),
+            })
+            .collect()
+    }
+
+    let is_root = |s: &Span| {
+        s.parent_id
+            .as_ref()
+            .is_none_or(|parent| !spans.iter().any(|p| &p.span_id == parent))
+    };
+    children_of(None, spans, &is_root)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -429,4 +468,29 @@ mod tests {
         assert_eq!(json["handler_calls"], 0);
         assert_eq!(json["spans"].as_array().unwrap().len(), 1);
     }
+
+    #[test]
+    fn test_span_tree() {
+        let script = SpanBuilder::new("script.checkout");
+        let script_id = script.span_id().to_string();
+        let auth = SpanBuilder::with_parent("handler.auth", &script_id).finish();
+        let orphan = SpanBuilder::with_parent("handler.orphan", "missing").finish();
+        let fetch = SpanBuilder::with_parent("fetch", &script_id).finish_with_error("denied");
+
+        let tree = span_tree(&[auth, orphan, fetch, script.finish()]);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].span.name, "handler.orphan");
+        assert_eq!(tree[1].span.name, "script.checkout");
+        let children: Vec<_> = tree[1]
+            .children
+            .iter()
+            .map(|c| c.span.name.as_str())
+            .collect();
+        assert_eq!(children, ["handler.auth", "fetch"]);
+
+        let json = serde_json::to_value(&tree[1]).unwrap();
+        assert_eq!(json["name"], "script.checkout");
+        assert_eq!(json["children"][1]["error"], "denied");
+        assert!(json["children"][0].get("children").is_none());
+    }
 }
@@ -0,0 +1,141 @@
+//! `mik:sql` host interface.
+//!
+//! Lets components query the mik SQL database directly (see `wit/sql.wit`)
+//! instead of making HTTP requests to the daemon's `/sql` endpoints.
+//!
+//! Access is granted per module through
+//! [`sql_modules`](crate::runtime::HostConfig::sql_modules). Modules without
+//! a grant, and tenant modules, get `not-permitted` from every call.
+
+use crate::daemon::services::sql::{self as service, SqlService};
+use crate::runtime::host_state::HostState;
+use anyhow::Result;
+use futures::TryStreamExt;
+use wasmtime::component::{HasSelf, Linker};
+
+wasmtime::component::bindgen!({
+    path: "wit/sql.wit",
+    world: "host",
+    imports: { default: async },
+});
+
+use self::mik::sql::database::{self, QueryResult, SqlError};
+
+impl From<database::Value> for service::Value {
+    fn from(value: database::Value) -> Self {
+        match value {
+            database::Value::Null => Self::Null,
+            database::Value::Integer(i) => Self::Integer(i),
+            database::Value::Real(r) => Self::Real(r),
+            database::Value::Text(s) => Self::Text(s),
+            database::Value::Blob(b) => Self::Blob(b),
+        }
+    }
+}
+
+impl From<service::Value> for database::Value {
+    fn from(value: service::Value) -> Self {
+        match value {
+            service::Value::Null => Self::Null,
+            service::Value::Integer(i) => Self::Integer(i),
+            service::Value::Real(r) => Self::Real(r),
+            service::Value::Text(s) => Self::Text(s),
+            service::Value::Blob(b) => Self::Blob(b),
+        }
+    }
+}
+
+fn failed(error: &anyhow::Error) -> SqlError {
+    SqlError::Failed(format!("{error:#}"))
+}
+
+fn params(params: Vec<database::Value>) -> Vec<service::Value> {
+    params.into_iter().map(service::Value::from).collect()
+}
+
+async fn query(
+    sql: &SqlService,
+    statement: &str,
+    params: &[service::Value],
+) -> Result<QueryResult> {
+    // Streamed so column names are known even when no rows match
+    let stream = sql.query_stream(statement, params).await?;
+    let columns = stream.columns().to_vec();
+    let rows = stream
+        .map_ok(|row| row.values.into_iter().map(database::Value::from).collect())
+        .try_collect()
+        .await?;
+    Ok(QueryResult { columns, rows })
+}
+
+impl database::Host for HostState {
+    async fn query(
+        &mut self,
+        sql: String,
+        params: Vec<database::Value>,
+    ) -> Result<QueryResult, SqlError> {
+        let Some(service) = self.sql.clone() else {
+            return Err(SqlError::NotPermitted);
+        };
+        query(&service, &sql, &self::params(params))
+            .await
+            .map_err(|e| failed(&e))
+    }
+
+    async fn execute(
+        &mut self,
+        sql: String,
+        params: Vec<database::Value>,
+    ) -> Result<u64, SqlError> {
+        let Some(service) = self.sql.clone() else {
+            return Err(SqlError::NotPermitted);
+        };
+        service
+            .execute(&sql, &self::params(params))
+            .await
+            .map(|affected| affected as u64)
+            .map_err(|e| failed(&e))
+    }
+}
+
+/// Add `mik:sql` to the linker.
+pub(crate) fn add_to_linker(linker: &mut Linker<HostState>) -> Result<()> {
+    database::add_to_linker::<HostState, HasSelf<HostState>>(linker, |state| state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_query_result_conversion() {
+        let sql = SqlService::memory().unwrap();
+        sql.execute_batch("CREATE TABLE t (id INTEGER, name TEXT, score REAL, data BLOB)")
+            .await
+            .unwrap();
+        let inserted = params(vec![
+            database::Value::Integer(1),
+            database::Value::Text("a".to_string()),
+            database::Value::Null,
+            database::Value::Blob(vec![1, 2]),
+        ]);
+        sql.execute("INSERT INTO t VALUES (?, ?, ?, ?)", &inserted)
+            .await
+            .unwrap();
+
+        let result = query(&sql, "SELECT * FROM t", &[]).await.unwrap();
+        assert_eq!(result.columns, ["id", "name", "score", "data"]);
+        assert_eq!(result.rows.len(), 1);
+        assert!(matches!(result.rows[0][0], database::Value::Integer(1)));
+        assert!(matches!(&result.rows[0][1], database::Value::Text(s) if s == "a"));
+        assert!(matches!(result.rows[0][2], database::Value::Null));
+        assert!(matches!(&result.rows[0][3], database::Value::Blob(b) if b == &[1, 2]));
+
+        // Columns are reported for empty results too
+        let empty = query(&sql, "SELECT id FROM t WHERE id = 2", &[])
+            .await
+            .unwrap();
+        assert_eq!(empty.columns, ["id"]);
+        assert!(empty.rows.is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+//! Per-tenant request rate limits.
+//!
+//! `requests_per_second` in `[server.tenant_limits]` is enforced with a
+//! token bucket per tenant that holds up to one second's worth of requests,
+//! so an idle tenant can burst to its rate before being held to it. Requests
+//! over the limit are rejected with 429 and a `Retry-After` of the time
+//! until the next token.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Token buckets per tenant.
+#[derive(Debug, Default)]
+pub(crate) struct TenantRateLimiter {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TenantRateLimiter {
+    /// Take a token for one request of `tenant_id` limited to `rate`
+    /// requests per second.
+    ///
+    /// Returns the time until a token is available when the bucket is empty.
+    pub(crate) fn try_acquire(&self, tenant_id: &str, rate: u32) -> Result<(), Duration> {
+        self.try_acquire_at(tenant_id, rate, Instant::now())
+    }
+
+    fn try_acquire_at(&self, tenant_id: &str, rate: u32, now: Instant) -> Result<(), Duration> {
+        let rate = f64::from(rate.max(1));
+        let mut buckets = self.buckets.lock();
+        let bucket = buckets
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| Bucket {
+                tokens: rate,
+                updated: now,
+            });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = elapsed.mul_add(rate, bucket.tokens).min(rate);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_bursts_then_refills() {
+        let limiter = TenantRateLimiter::default();
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            assert!(limiter.try_acquire_at("acme", 2, start).is_ok());
+        }
+        let wait = limiter.try_acquire_at("acme", 2, start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        // Other tenants have their own bucket
+        assert!(limiter.try_acquire_at("globex", 2, start).is_ok());
+
+        // Half a second refills one token at 2 requests/sec
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.try_acquire_at("acme", 2, later).is_ok());
+        assert!(limiter.try_acquire_at("acme", 2, later).is_err());
+
+        // An idle tenant never banks more than one second's worth
+        let idle = start + Duration::from_secs(60);
+        for _ in 0..2 {
+            assert!(limiter.try_acquire_at("acme", 2, idle).is_ok());
+        }
+        assert!(limiter.try_acquire_at("acme", 2, idle).is_err());
+    }
+}
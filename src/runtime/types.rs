@@ -3,6 +3,7 @@
 //! This module contains data types used across the runtime for health checks,
 //! error categorization, and memory statistics.
 
+use std::collections::BTreeMap;
 use std::fmt;
 
 use serde::Serialize;
@@ -14,6 +15,8 @@ pub enum ErrorCategory {
     ModuleLoad,
     /// Invalid request (routing, path, etc).
     InvalidRequest,
+    /// Component lacks the export the route needs.
+    UnsupportedComponent,
     /// Component instantiation failed.
     Instantiation,
     /// Handler execution failed.
@@ -35,6 +38,7 @@ impl fmt::Display for ErrorCategory {
         match self {
             Self::ModuleLoad => write!(f, "module_load"),
             Self::InvalidRequest => write!(f, "invalid_request"),
+            Self::UnsupportedComponent => write!(f, "unsupported_component"),
             Self::Instantiation => write!(f, "instantiation"),
             Self::Execution => write!(f, "execution"),
             Self::StaticFile => write!(f, "static_file"),
@@ -75,9 +79,31 @@ pub struct HealthStatus {
     pub total_requests: u64,
     /// Memory statistics.
     pub memory: MemoryStats,
+    /// Active manifest profile, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Environment exposed to components by `[env].expose`, secrets masked
+    /// (optional, only included with ?verbose=true).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<BTreeMap<String, String>>,
     /// List of loaded modules (optional, only included with ?verbose=true).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub loaded_modules: Option<Vec<String>>,
+    /// Canary execution result (optional, only included with ?deep=true).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canary: Option<CanaryStatus>,
+}
+
+/// Result of executing the built-in canary component.
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryStatus {
+    /// Whether the canary ran and returned the expected value.
+    pub passed: bool,
+    /// Time to instantiate and run the canary (milliseconds).
+    pub latency_ms: f64,
+    /// Why the canary failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// Memory statistics for health check.
@@ -87,4 +113,7 @@ pub struct MemoryStats {
     pub allocated_bytes: Option<usize>,
     /// Memory limit per request.
     pub limit_per_request_bytes: usize,
+    /// Size of the `wasi-nn` models loaded at startup (if any).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nn_model_bytes: Option<usize>,
 }
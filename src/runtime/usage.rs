@@ -0,0 +1,124 @@
+//! Per-tenant usage metering.
+//!
+//! Requests to tenant modules (`/tenant/{tenant-id}/{module}/*`) are metered
+//! per tenant: executions, failed executions, execution time, fuel consumed,
+//! and outgoing HTTP body bytes. The counters are served on
+//! `GET /_mik/usage/tenant/{tenant-id}` and as `mik_tenant_*` metrics on
+//! `/metrics`, for billing and quota enforcement.
+//!
+//! Executions cancelled by a client disconnect count as failed, since they
+//! still used resources. Counters are kept in memory per process and reset
+//! on restart.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Usage counters for one tenant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct TenantUsage {
+    /// Executions of the tenant's modules.
+    pub(crate) requests: u64,
+    /// Executions that failed or were cancelled.
+    pub(crate) errors: u64,
+    /// Wall-clock execution time.
+    pub(crate) execution_time: Duration,
+    /// Fuel consumed by the tenant's modules.
+    pub(crate) fuel_consumed: u64,
+    /// Outgoing HTTP request body bytes sent.
+    pub(crate) egress_sent_bytes: u64,
+    /// Outgoing HTTP response body bytes received.
+    pub(crate) egress_received_bytes: u64,
+}
+
+/// Usage counters per tenant.
+#[derive(Debug, Default)]
+pub(crate) struct UsageMeter {
+    tenants: Mutex<HashMap<String, TenantUsage>>,
+}
+
+impl UsageMeter {
+    /// Count one execution that took `elapsed`.
+    pub(crate) fn record_execution(&self, tenant: &str, elapsed: Duration, ok: bool) {
+        self.update(tenant, |usage| {
+            usage.requests += 1;
+            usage.errors += u64::from(!ok);
+            usage.execution_time += elapsed;
+        });
+    }
+
+    /// Count fuel consumed by one execution.
+    pub(crate) fn record_fuel(&self, tenant: &str, fuel: u64) {
+        self.update(tenant, |usage| usage.fuel_consumed += fuel);
+    }
+
+    /// Count outgoing HTTP bytes sent (`sent = true`) or received.
+    pub(crate) fn record_egress(&self, tenant: &str, bytes: u64, sent: bool) {
+        self.update(tenant, |usage| {
+            if sent {
+                usage.egress_sent_bytes += bytes;
+            } else {
+                usage.egress_received_bytes += bytes;
+            }
+        });
+    }
+
+    /// Counters for `tenant`, if it has been metered.
+    pub(crate) fn tenant(&self, tenant: &str) -> Option<TenantUsage> {
+        self.tenants.lock().get(tenant).copied()
+    }
+
+    /// Counters for every metered tenant, sorted by tenant ID.
+    pub(crate) fn usage(&self) -> Vec<(String, TenantUsage)> {
+        let mut usage: Vec<_> = self
+            .tenants
+            .lock()
+            .iter()
+            .map(|(tenant, usage)| (tenant.clone(), *usage))
+            .collect();
+        usage.sort_by(|a, b| a.0.cmp(&b.0));
+        usage
+    }
+
+    fn update(&self, tenant: &str, f: impl FnOnce(&mut TenantUsage)) {
+        let mut tenants = self.tenants.lock();
+        if let Some(usage) = tenants.get_mut(tenant) {
+            f(usage);
+        } else {
+            f(tenants.entry(tenant.to_string()).or_default());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_is_tracked_per_tenant() {
+        let meter = UsageMeter::default();
+        meter.record_execution("acme", Duration::from_millis(30), true);
+        meter.record_execution("acme", Duration::from_millis(20), false);
+        meter.record_fuel("acme", 1_000);
+        meter.record_egress("acme", 100, true);
+        meter.record_egress("acme", 400, false);
+        meter.record_execution("globex", Duration::from_millis(5), true);
+
+        assert_eq!(
+            meter.tenant("acme"),
+            Some(TenantUsage {
+                requests: 2,
+                errors: 1,
+                execution_time: Duration::from_millis(50),
+                fuel_consumed: 1_000,
+                egress_sent_bytes: 100,
+                egress_received_bytes: 400,
+            })
+        );
+        assert_eq!(meter.tenant("globex").unwrap().requests, 1);
+        assert!(meter.tenant("initech").is_none());
+
+        let tenants: Vec<_> = meter.usage().into_iter().map(|(t, _)| t).collect();
+        assert_eq!(tenants, ["acme", "globex"]);
+    }
+}
@@ -3,17 +3,26 @@
 //! This module provides the core WASM execution functions:
 //! - [`execute_wasm_request`]: Execute a WASM HTTP handler
 //! - [`execute_wasm_request_internal`]: Public API for script orchestration
+//! - [`create_store`]: Store with per-request limits, also used by jobs
 
+use crate::constants;
 use crate::runtime::SharedState;
-use crate::runtime::host_state::{HostState, HyperCompatibleBody};
+use crate::runtime::chaos::Faults;
+use crate::runtime::deadline;
+use crate::runtime::egress::EgressAccount;
+use crate::runtime::host_state::{HostState, HyperCompatibleBody, add_env, add_preopens};
+use crate::runtime::request_info::RequestInfo;
 use anyhow::{Context, Result};
+use backon::{ExponentialBuilder, Retryable};
 use http_body_util::{BodyExt, Full};
 use hyper::body::Bytes;
 use hyper::{Request, Response};
 use std::sync::Arc;
-use wasmtime::Store;
+use std::time::{Duration, Instant};
 use wasmtime::component::{Component, ResourceTable};
+use wasmtime::{PoolConcurrencyLimitError, Store, UpdateDeadline};
 use wasmtime_wasi::WasiCtxBuilder;
+use wasmtime_wasi_http::bindings::Proxy;
 use wasmtime_wasi_http::bindings::http::types::Scheme;
 use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
 
@@ -21,52 +30,159 @@ use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
 pub(crate) async fn execute_wasm_request_internal(
     shared: Arc<SharedState>,
     component: Arc<Component>,
+    module: Option<&str>,
     req: Request<Full<Bytes>>,
 ) -> Result<Response<Full<Bytes>>> {
     let (parts, body) = req.into_parts();
     let req = Request::from_parts(parts, HyperCompatibleBody(body));
-    execute_wasm_request(shared, component, req).await
+    execute_wasm_request(shared, component, module, req).await
 }
 
 /// Execute a WASM request (internal helper).
 ///
-/// Body is pre-collected with size limits already enforced.
+/// Body is pre-collected with size limits already enforced. Outgoing HTTP
+/// is metered against `module` when it is known.
+///
+/// Dropping the returned future cancels the execution. hyper does this when
+/// the client disconnects (connection closed on HTTP/1, stream reset on
+/// HTTP/2): the store is dropped with the future, so the guest stops at its
+/// next epoch yield and its instance, memory and remaining fuel are released
+/// instead of producing a response nobody reads.
 pub(crate) async fn execute_wasm_request(
     shared: Arc<SharedState>,
     component: Arc<Component>,
+    module: Option<&str>,
     req: Request<HyperCompatibleBody>,
 ) -> Result<Response<Full<Bytes>>> {
-    // Create fresh WASI context
-    let wasi = WasiCtxBuilder::new().inherit_stdio().inherit_env().build();
+    let tenant = req
+        .extensions()
+        .get::<RequestInfo>()
+        .and_then(|info| info.tenant_id.clone());
+    let guard = CancelGuard {
+        shared: &shared,
+        module,
+        tenant,
+        started: Instant::now(),
+        armed: true,
+    };
+    let result = execute(&shared, component, module, req).await;
+    let elapsed = guard.started.elapsed();
+    if let Some(module) = module {
+        shared.record_module_execution(module, elapsed, result.is_ok());
+    }
+    if let Some(tenant) = &guard.tenant {
+        shared
+            .usage
+            .record_execution(tenant, elapsed, result.is_ok());
+    }
+    guard.disarm();
+    result
+}
 
-    // Use pre-computed Arc (cheap pointer copy instead of cloning Vec)
-    let http_allowed = shared.http_allowed.clone();
+/// Reports an execution whose future was dropped before it finished.
+struct CancelGuard<'a> {
+    shared: &'a SharedState,
+    module: Option<&'a str>,
+    tenant: Option<String>,
+    started: Instant,
+    armed: bool,
+}
 
-    let state = HostState {
-        wasi,
-        http: WasiHttpCtx::new(),
-        table: ResourceTable::new(),
-        http_allowed,
-        memory_limit: shared.memory_limit_bytes,
-    };
+impl CancelGuard<'_> {
+    fn disarm(mut self) {
+        self.armed = false;
+    }
+}
 
-    let mut store = Store::new(&shared.engine, state);
+impl Drop for CancelGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.shared.stats.record_cancelled_request();
+            if let Some(tenant) = &self.tenant {
+                self.shared
+                    .usage
+                    .record_execution(tenant, self.started.elapsed(), false);
+            }
+            tracing::info!(
+                module = self.module.unwrap_or("component"),
+                elapsed_ms = self.started.elapsed().as_millis() as u64,
+                "Client disconnected, WASM execution cancelled"
+            );
+        }
+    }
+}
 
-    // Enable ResourceLimiter for memory enforcement
-    store.limiter(|state| state);
+async fn execute(
+    shared: &Arc<SharedState>,
+    component: Arc<Component>,
+    module: Option<&str>,
+    req: Request<HyperCompatibleBody>,
+) -> Result<Response<Full<Bytes>>> {
+    // Resolve the deadline before the request is moved into the store
+    let timeout = deadline::effective_timeout(
+        req.headers(),
+        shared.execution_timeout,
+        shared.max_request_timeout,
+    );
+    tracing::Span::current().record("timeout_ms", timeout.as_millis() as u64);
 
-    // Configure epoch deadline for async yielding (100 epochs/second, so timeout_secs * 100)
-    // Using epoch_deadline_async_yield_and_update instead of set_epoch_deadline because:
-    // 1. On shutdown, the epoch incrementer thread stops, causing WASM to hit its deadline
-    // 2. With async yielding, WASM will yield (return Pending) instead of trapping
-    // 3. The tokio::time::timeout wrapper will then cancel the execution gracefully
-    // This provides cooperative cancellation during shutdown rather than abrupt traps.
-    let timeout_epochs = shared.execution_timeout.as_secs().saturating_mul(100);
-    store.epoch_deadline_async_yield_and_update(timeout_epochs);
+    let deadline = Instant::now() + timeout;
 
-    // Set fuel budget for deterministic CPU limiting
-    // Fuel provides deterministic limits complementing epoch-based preemption
-    store.set_fuel(shared.fuel_budget)?;
+    // Faults injected for resilience testing (`[server.chaos]`)
+    let faults = module
+        .and_then(|module| shared.config.chaos_policy(module))
+        .map(Faults::roll)
+        .unwrap_or_default();
+    if faults != Faults::default() {
+        tracing::warn!(
+            module = module.unwrap_or("component"),
+            ?faults,
+            "Chaos: injecting faults"
+        );
+    }
+    if let Some(latency) = faults.latency {
+        tokio::time::sleep_until((deadline.min(Instant::now() + latency)).into()).await;
+        if Instant::now() >= deadline {
+            anyhow::bail!("WASM execution timed out after {timeout:?} (chaos latency)");
+        }
+    }
+    if faults.trap {
+        return Err(anyhow::anyhow!("chaos: injected trap")).context("Handler call failed");
+    }
+
+    // Metadata for mik:request-info, attached by the request handler
+    let request_info = req
+        .extensions()
+        .get::<RequestInfo>()
+        .cloned()
+        .unwrap_or_default();
+
+    // Nothing has run in the guest yet, so exhausted instances can be retried
+    let backoff = ExponentialBuilder::default()
+        .with_min_delay(Duration::from_millis(
+            shared.config.execution_retry_backoff_ms,
+        ))
+        .with_max_delay(Duration::from_millis(
+            constants::EXECUTION_RETRY_MAX_BACKOFF_MS,
+        ))
+        .with_max_times(shared.config.execution_retries as usize)
+        .with_jitter();
+    let (mut store, proxy) = (|| instantiate(shared, &component, module, &request_info, deadline))
+        .retry(backoff)
+        .when(is_transient)
+        .notify(|err, delay| {
+            shared.stats.record_execution_retry();
+            tracing::warn!(
+                module = module.unwrap_or("component"),
+                delay_ms = delay.as_millis() as u64,
+                error = %err,
+                "Transient execution failure, retrying"
+            );
+        })
+        .await?;
+    if faults.starve {
+        store.set_fuel(0)?;
+    }
 
     // Create response channel
     let (sender, receiver) = tokio::sync::oneshot::channel();
@@ -75,36 +191,36 @@ pub(crate) async fn execute_wasm_request(
     let req_resource = store.data_mut().new_incoming_request(Scheme::Http, req)?;
     let out_resource = store.data_mut().new_response_outparam(sender)?;
 
-    // Instantiate and call handler with timeout enforcement
-    let timeout = shared.execution_timeout;
-
-    let proxy = tokio::time::timeout(
-        timeout,
-        wasmtime_wasi_http::bindings::Proxy::instantiate_async(
-            &mut store,
-            &component,
-            &shared.linker,
-        ),
-    )
-    .await
-    .map_err(|_| anyhow::anyhow!("WASM instantiation timed out after {timeout:?}"))?
-    .context("Failed to instantiate proxy")?;
-
-    tokio::time::timeout(timeout, async {
+    // Call handler with the rest of the timeout
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let call = tokio::time::timeout(remaining, async {
         proxy
             .wasi_http_incoming_handler()
             .call_handle(&mut store, req_resource, out_resource)
             .await
     })
-    .await
-    .map_err(|_| anyhow::anyhow!("WASM execution timed out after {timeout:?}"))?
-    .context("Handler call failed")?;
+    .await;
+
+    // Bill the fuel used, whether or not the handler succeeded
+    if let Some(tenant) = &request_info.tenant_id
+        && let Ok(left) = store.get_fuel()
+    {
+        shared
+            .usage
+            .record_fuel(tenant, shared.fuel_budget.saturating_sub(left));
+    }
+
+    call.map_err(|_| anyhow::anyhow!("WASM execution timed out after {timeout:?}"))?
+        .context("Handler call failed")?;
 
     // Get response
     let response = receiver
         .await
         .context("No response received")?
         .context("Response error")?;
+    if faults.drop {
+        anyhow::bail!("chaos: response dropped");
+    }
 
     // Convert to hyper response
     let mut builder = Response::builder().status(response.status());
@@ -122,3 +238,158 @@ pub(crate) async fn execute_wasm_request(
 
     Ok(builder.body(Full::new(body_bytes))?)
 }
+
+/// Create a store and instantiate `component` in it before `deadline`.
+async fn instantiate(
+    shared: &SharedState,
+    component: &Component,
+    module: Option<&str>,
+    request_info: &RequestInfo,
+    deadline: Instant,
+) -> Result<(Store<HostState>, Proxy)> {
+    let timeout = deadline.saturating_duration_since(Instant::now());
+
+    // Create fresh WASI context
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.inherit_stdio();
+    add_env(&mut wasi, shared.config.component_env.as_ref());
+
+    let mut store = create_store(shared, module, wasi, request_info.clone(), timeout)?;
+    let proxy = tokio::time::timeout(
+        timeout,
+        Proxy::instantiate_async(&mut store, component, &shared.linker),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("WASM instantiation timed out after {timeout:?}"))?
+    .context("Failed to instantiate proxy")?;
+    Ok((store, proxy))
+}
+
+/// Whether an instantiation failure is worth retrying: the pooling
+/// allocator had no free instance, memory, table or stack. Anything else
+/// (link errors, traps in start functions, timeouts) fails the same way again
+/// or has used up the deadline.
+fn is_transient(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(<dyn std::error::Error>::is::<PoolConcurrencyLimitError>)
+}
+
+/// Create a store for running code from `module`.
+///
+/// Applies the per-request memory limit, fuel budget and an epoch deadline
+/// for `timeout`, and grants `mik:sql`, `wasi-nn` models and the configured
+/// preopens to host modules.
+pub(crate) fn create_store(
+    shared: &SharedState,
+    module: Option<&str>,
+    mut wasi: WasiCtxBuilder,
+    request_info: RequestInfo,
+    timeout: Duration,
+) -> Result<Store<HostState>> {
+    // Use pre-computed Arc (cheap pointer copy instead of cloning Vec)
+    let live = shared.live();
+    let http_allowed = live.http_allowed.clone();
+
+    // mik:sql is for granted host modules; tenant modules never get it
+    let sql = shared.sql.clone().filter(|_| {
+        request_info.tenant_id.is_none()
+            && module.is_some_and(|module| shared.config.sql_allowed(module))
+    });
+
+    // Tenant modules never get host directories
+    if let Some(module) = module.filter(|_| request_info.tenant_id.is_none()) {
+        add_preopens(&mut wasi, shared.config.preopens(module))?;
+    }
+
+    // wasi-nn models likewise
+    #[cfg(feature = "nn")]
+    let nn = match (&shared.nn, module) {
+        (Some(models), Some(module)) if request_info.tenant_id.is_none() => {
+            models.ctx(shared.config.nn_models_for(module))
+        },
+        _ => crate::runtime::nn::empty_ctx(),
+    };
+
+    let state = HostState {
+        wasi: wasi.build(),
+        http: WasiHttpCtx::new(),
+        table: ResourceTable::new(),
+        http_allowed,
+        memory_limit: shared.memory_limit_bytes,
+        deadline: Instant::now() + timeout,
+        egress: module.map(|module| EgressAccount {
+            module: module.to_string(),
+            quota: live.egress_quota(module),
+            meter: shared.egress.clone(),
+            tenant: request_info.tenant_id.clone(),
+            usage: shared.usage.clone(),
+        }),
+        request_info,
+        sql,
+        #[cfg(feature = "nn")]
+        nn,
+    };
+
+    let mut store = Store::new(&shared.engine, state);
+
+    // Enable ResourceLimiter for memory enforcement
+    store.limiter(|state| state);
+
+    // Yield to the executor on every epoch (100 epochs/second, one per 10ms)
+    // instead of only at the deadline, so a guest spinning without host calls
+    // still gives the runtime a chance to act:
+    // 1. The caller's tokio::time::timeout fires and cancels the execution
+    // 2. A dropped future (client disconnect) stops the guest at the next tick
+    // 3. During shutdown, execution is cancelled cooperatively rather than trapped
+    // A guest still running a second past `timeout` is interrupted as a backstop,
+    // and so is one still running when a shutdown drain times out.
+    let max_epochs = (timeout.as_millis() as u64).div_ceil(10) + 100;
+    let mut epochs = 0;
+    let cancelled = shared.drain.canceller();
+    store.epoch_deadline_callback(move |_| {
+        epochs += 1;
+        Ok(if epochs > max_epochs || cancelled() {
+            UpdateDeadline::Interrupt
+        } else {
+            UpdateDeadline::Yield(1)
+        })
+    });
+    store.set_epoch_deadline(1);
+
+    // Set fuel budget for deterministic CPU limiting
+    // Fuel provides deterministic limits complementing epoch-based preemption
+    store.set_fuel(shared.fuel_budget)?;
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmtime::component::Linker;
+    use wasmtime::{Config, Engine, InstanceAllocationStrategy, PoolingAllocationConfig};
+
+    #[test]
+    fn test_pool_exhaustion_is_transient() {
+        let mut pool = PoolingAllocationConfig::default();
+        pool.total_component_instances(1);
+        let mut config = Config::new();
+        config.allocation_strategy(InstanceAllocationStrategy::Pooling(pool));
+        let engine = Engine::new(&config).unwrap();
+        let component = Component::new(&engine, "(component)").unwrap();
+        let linker = Linker::<()>::new(&engine);
+
+        let mut first = Store::new(&engine, ());
+        linker.instantiate(&mut first, &component).unwrap();
+        let mut second = Store::new(&engine, ());
+        let err = linker
+            .instantiate(&mut second, &component)
+            .context("Failed to instantiate proxy")
+            .unwrap_err();
+        assert!(is_transient(&err));
+
+        assert!(!is_transient(&anyhow::anyhow!(
+            "WASM instantiation timed out after 1s"
+        )));
+    }
+}
@@ -0,0 +1,623 @@
+//! Ephemeral runtime fixture for integration-testing components.
+//!
+//! [`TestRuntime`] loads a component into a throwaway modules directory and
+//! runs requests through the real executor, without binding a port.
+//! Responses come back as [`TestResponse`], with assertion helpers and the
+//! tracing spans recorded while the request was handled.
+//!
+//! Enable with the `testing` feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! mik = { version = "*", features = ["testing"] }
+//! ```
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mik::testing::TestRuntime;
+//! use serde_json::json;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let runtime = TestRuntime::with_component(std::fs::read("echo.wasm")?)?;
+//!
+//! runtime
+//!     .post("/")
+//!     .json(&json!({ "message": "hello" }))
+//!     .send()
+//!     .await?
+//!     .assert_status(200)
+//!     .assert_header("content-type", "application/json")
+//!     .assert_json(json!({ "message": "hello" }))
+//!     .assert_span("request");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Services
+//!
+//! With [`TestRuntimeBuilder::services`], the fixture runs its own embedded
+//! KV, SQL and storage services, empty at the start, to seed before sending
+//! requests and inspect afterwards:
+//!
+//! - SQL is the `mik:sql` database, so components granted access see seeded
+//!   tables directly.
+//! - The daemon's `/kv`, `/sql` and `/storage` endpoints are served at
+//!   [`TestServices::url`], and outgoing HTTP to `127.0.0.1` is allowed.
+//!   Components that call the daemon at a configurable address reach the
+//!   fixture's services there; [`TestRuntimeBuilder::services_port`] binds a
+//!   fixed port (such as the daemon's 9919) for components that hardcode it.
+//!
+//! ```no_run
+//! use mik::testing::TestRuntime;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let runtime = TestRuntime::builder()
+//!     .fixture("dist/orders.wasm")
+//!     .services()
+//!     .build()?;
+//!
+//! let services = runtime.services();
+//! services.sql().execute_batch("CREATE TABLE orders (item TEXT)").await?;
+//! services.kv().set("feature/orders", b"on", None).await?;
+//!
+//! runtime.get("/orders").send().await?.assert_status(200);
+//! assert!(services.storage().get_object("receipts/1.json").await?.is_some());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::daemon::http::services_router;
+use crate::daemon::services::kv::KvStore;
+use crate::daemon::services::sql::SqlService;
+use crate::daemon::services::storage::StorageService;
+use crate::runtime::{Request, Response, Runtime, RuntimeBuilder};
+use anyhow::{Context as _, Result};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tokio::task::JoinHandle;
+use tracing::Subscriber;
+use tracing::field::{Field, Visit};
+use tracing::instrument::WithSubscriber;
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Module name used by [`TestRuntime::with_component`].
+pub const DEFAULT_MODULE: &str = "component";
+
+/// A runtime serving components from a temporary directory.
+///
+/// The directory is removed when the fixture is dropped.
+pub struct TestRuntime {
+    runtime: Runtime,
+    module: String,
+    services: Option<TestServices>,
+    _dir: TempDir,
+}
+
+impl TestRuntime {
+    /// Serve a single component as [`DEFAULT_MODULE`].
+    pub fn with_component(bytes: impl AsRef<[u8]>) -> Result<Self> {
+        Self::builder().component(DEFAULT_MODULE, bytes).build()
+    }
+
+    /// Serve the component file at `path`, named after the file
+    /// (`dist/orders.wasm` is served at `/run/orders/`).
+    pub fn with_fixture(path: impl AsRef<Path>) -> Result<Self> {
+        Self::builder().fixture(path).build()
+    }
+
+    /// Create a builder for several components or custom runtime settings.
+    pub fn builder() -> TestRuntimeBuilder {
+        TestRuntimeBuilder::default()
+    }
+
+    /// The underlying runtime.
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    /// Module that relative request paths are sent to.
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// The fixture's embedded services.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the fixture was built with
+    /// [`TestRuntimeBuilder::services`].
+    #[track_caller]
+    pub fn services(&self) -> &TestServices {
+        self.services
+            .as_ref()
+            .expect("services are not enabled, use TestRuntimeBuilder::services()")
+    }
+
+    /// Start a request to the default module.
+    ///
+    /// `path` is relative to the module: `"/greet"` requests
+    /// `/run/<module>/greet`.
+    pub fn request(&self, method: &str, path: &str) -> TestRequest<'_> {
+        let path = format!("/run/{}/{}", self.module, path.trim_start_matches('/'));
+        TestRequest {
+            runtime: self,
+            request: Request::new(method, path),
+        }
+    }
+
+    /// Start a `GET` request to the default module.
+    pub fn get(&self, path: &str) -> TestRequest<'_> {
+        self.request("GET", path)
+    }
+
+    /// Start a `POST` request to the default module.
+    pub fn post(&self, path: &str) -> TestRequest<'_> {
+        self.request("POST", path)
+    }
+
+    /// Start a `PUT` request to the default module.
+    pub fn put(&self, path: &str) -> TestRequest<'_> {
+        self.request("PUT", path)
+    }
+
+    /// Start a `DELETE` request to the default module.
+    pub fn delete(&self, path: &str) -> TestRequest<'_> {
+        self.request("DELETE", path)
+    }
+
+    /// Send a request with an absolute path (`/run/other/`, `/health`, ...).
+    pub async fn send(&self, mut request: Request) -> Result<TestResponse> {
+        // WASI HTTP requires an authority
+        if request.header("host").is_none() {
+            request = request.with_header("host", "localhost");
+        }
+
+        let recorder = SpanRecorder::default();
+        let spans = recorder.spans.clone();
+        let subscriber = tracing_subscriber::registry().with(recorder);
+
+        let response = self
+            .runtime
+            .handle_request(request)
+            .with_subscriber(subscriber)
+            .await?;
+
+        let spans = std::mem::take(&mut *spans.lock());
+        Ok(TestResponse { response, spans })
+    }
+}
+
+type Configure = Box<dyn FnOnce(RuntimeBuilder) -> RuntimeBuilder>;
+
+/// Where a component's bytes come from.
+enum Source {
+    Bytes(Vec<u8>),
+    File(PathBuf),
+}
+
+/// Builder for [`TestRuntime`].
+#[derive(Default)]
+pub struct TestRuntimeBuilder {
+    components: Vec<(String, Source)>,
+    configure: Option<Configure>,
+    /// Port for the services, `Some(0)` for any free port.
+    services: Option<u16>,
+}
+
+impl TestRuntimeBuilder {
+    /// Add a component served at `/run/<name>/`.
+    ///
+    /// The first component is the target of relative request paths.
+    #[must_use]
+    pub fn component(mut self, name: impl Into<String>, bytes: impl AsRef<[u8]>) -> Self {
+        self.components
+            .push((name.into(), Source::Bytes(bytes.as_ref().to_vec())));
+        self
+    }
+
+    /// Add the component file at `path`, served under its file name without
+    /// the extension. The file is read by [`build`](Self::build).
+    #[must_use]
+    pub fn fixture(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.components
+            .push((name, Source::File(path.to_path_buf())));
+        self
+    }
+
+    /// Run embedded KV, SQL and storage services (see [`TestServices`]).
+    ///
+    /// Grants every component `mik:sql` access and allows outgoing HTTP to
+    /// `127.0.0.1`; a [`configure`](Self::configure) closure setting
+    /// `http_allowed` has to keep `127.0.0.1` in the list.
+    #[must_use]
+    pub fn services(self) -> Self {
+        self.services_port(0)
+    }
+
+    /// Like [`services`](Self::services), serving them on `port`.
+    #[must_use]
+    pub const fn services_port(mut self, port: u16) -> Self {
+        self.services = Some(port);
+        self
+    }
+
+    /// Adjust the runtime configuration (timeouts, allowed hosts, ...).
+    ///
+    /// The modules directory is set by the fixture.
+    #[must_use]
+    pub fn configure(
+        mut self,
+        configure: impl FnOnce(RuntimeBuilder) -> RuntimeBuilder + 'static,
+    ) -> Self {
+        self.configure = Some(Box::new(configure));
+        self
+    }
+
+    /// Write the components to a temporary directory and build the runtime.
+    pub fn build(self) -> Result<TestRuntime> {
+        let module = self
+            .components
+            .first()
+            .map(|(name, _)| name.clone())
+            .context("TestRuntime needs at least one component")?;
+
+        let dir = tempfile::tempdir().context("Failed to create modules directory")?;
+        for (name, source) in &self.components {
+            crate::security::sanitize_module_name(name)
+                .map_err(|e| anyhow::anyhow!("Invalid component name '{name}': {e}"))?;
+            let path = dir.path().join(format!("{name}.wasm"));
+            match source {
+                Source::Bytes(bytes) => std::fs::write(&path, bytes)
+                    .with_context(|| format!("Failed to write {}", path.display()))?,
+                Source::File(fixture) => {
+                    std::fs::copy(fixture, &path)
+                        .with_context(|| format!("Failed to read fixture {}", fixture.display()))?;
+                },
+            }
+        }
+
+        let services = self.services.map(TestServices::start).transpose()?;
+
+        let mut builder = Runtime::builder();
+        if let Some(services) = &services {
+            builder = builder
+                .http_allowed(vec!["127.0.0.1".to_string()])
+                .sql_modules(vec!["*".to_string()])
+                .sql_database(services.dir.path().join(SQL_DATABASE));
+        }
+        if let Some(configure) = self.configure {
+            builder = configure(builder);
+        }
+        let runtime = builder
+            .modules_dir(dir.path())
+            .build()
+            .context("Failed to build test runtime")?;
+
+        Ok(TestRuntime {
+            runtime,
+            module,
+            services,
+            _dir: dir,
+        })
+    }
+}
+
+/// File name of the services' SQL database.
+const SQL_DATABASE: &str = "sql.db";
+
+/// Embedded KV, SQL and storage services of a [`TestRuntime`].
+///
+/// KV and storage are in memory; SQL is a database file shared with the
+/// runtime's `mik:sql` interface. The HTTP server stops when the fixture is
+/// dropped.
+pub struct TestServices {
+    kv: KvStore,
+    sql: SqlService,
+    storage: StorageService,
+    url: String,
+    server: JoinHandle<()>,
+    dir: TempDir,
+}
+
+impl TestServices {
+    /// Open the services and serve them on `127.0.0.1:<port>`.
+    ///
+    /// Must be called inside a Tokio runtime.
+    fn start(port: u16) -> Result<Self> {
+        let handle = tokio::runtime::Handle::try_current()
+            .context("TestRuntime services must be built inside a Tokio runtime")?;
+        let dir = tempfile::tempdir().context("Failed to create services directory")?;
+
+        let kv = KvStore::memory();
+        let sql = SqlService::file(dir.path().join(SQL_DATABASE))?;
+        let storage = StorageService::memory();
+        let router = services_router(
+            kv.clone(),
+            sql.clone(),
+            storage.clone(),
+            &dir.path().join("state.redb"),
+        )?;
+
+        let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+            .with_context(|| format!("Failed to bind services to port {port}"))?;
+        listener.set_nonblocking(true)?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let server = handle.spawn(async move {
+            let Ok(listener) = tokio::net::TcpListener::from_std(listener) else {
+                return;
+            };
+            let _ = axum::serve(listener, router).await;
+        });
+
+        Ok(Self {
+            kv,
+            sql,
+            storage,
+            url,
+            server,
+            dir,
+        })
+    }
+
+    /// The KV store.
+    pub fn kv(&self) -> &KvStore {
+        &self.kv
+    }
+
+    /// The SQL database, also the components' `mik:sql` database.
+    pub fn sql(&self) -> &SqlService {
+        &self.sql
+    }
+
+    /// The object storage.
+    pub fn storage(&self) -> &StorageService {
+        &self.storage
+    }
+
+    /// Base URL of the services' HTTP API, e.g. `http://127.0.0.1:41234`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for TestServices {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// A request being built against a [`TestRuntime`].
+pub struct TestRequest<'a> {
+    runtime: &'a TestRuntime,
+    request: Request,
+}
+
+impl TestRequest<'_> {
+    /// Add a header.
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request = self.request.with_header(name, value);
+        self
+    }
+
+    /// Set a raw body.
+    #[must_use]
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.request = self.request.with_body(body.into());
+        self
+    }
+
+    /// Set a JSON body and `Content-Type: application/json`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` cannot be serialized.
+    #[must_use]
+    pub fn json(self, value: &impl Serialize) -> Self {
+        let body = serde_json::to_vec(value).expect("request body must serialize to JSON");
+        self.header("content-type", "application/json").body(body)
+    }
+
+    /// Send the request through the executor.
+    pub async fn send(self) -> Result<TestResponse> {
+        self.runtime.send(self.request).await
+    }
+}
+
+/// A response with assertion helpers.
+///
+/// Assertions panic with the response body in the message and return
+/// `&Self`, so they can be chained.
+#[derive(Debug)]
+pub struct TestResponse {
+    response: Response,
+    spans: Vec<RecordedSpan>,
+}
+
+impl TestResponse {
+    /// Status code.
+    pub fn status(&self) -> u16 {
+        self.response.status
+    }
+
+    /// Header value (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.response.header(name)
+    }
+
+    /// Body bytes.
+    pub fn body(&self) -> &[u8] {
+        &self.response.body
+    }
+
+    /// Body as text (lossy UTF-8).
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.response.body).into_owned()
+    }
+
+    /// Body parsed as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.response.body).context("Response body is not valid JSON")
+    }
+
+    /// Spans closed while the request was handled, in closing order.
+    pub fn spans(&self) -> &[RecordedSpan] {
+        &self.spans
+    }
+
+    /// The underlying response.
+    pub fn into_response(self) -> Response {
+        self.response
+    }
+
+    /// Assert the status code.
+    #[track_caller]
+    pub fn assert_status(&self, expected: u16) -> &Self {
+        assert_eq!(
+            self.status(),
+            expected,
+            "unexpected status, body: {}",
+            self.text()
+        );
+        self
+    }
+
+    /// Assert a header value.
+    #[track_caller]
+    pub fn assert_header(&self, name: &str, expected: &str) -> &Self {
+        assert_eq!(
+            self.header(name),
+            Some(expected),
+            "unexpected '{name}' header"
+        );
+        self
+    }
+
+    /// Assert the body equals `expected` as JSON (key order is ignored).
+    #[track_caller]
+    #[allow(clippy::needless_pass_by_value)] // Takes `json!(...)` without a borrow
+    pub fn assert_json(&self, expected: serde_json::Value) -> &Self {
+        let Ok(actual) = self.json::<serde_json::Value>() else {
+            panic!("expected a JSON body, got: {}", self.text());
+        };
+        assert_eq!(actual, expected, "unexpected JSON body");
+        self
+    }
+
+    /// Assert a span named `name` was recorded.
+    #[track_caller]
+    pub fn assert_span(&self, name: &str) -> &Self {
+        self.span(name);
+        self
+    }
+
+    /// The first recorded span named `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no such span was recorded.
+    #[track_caller]
+    pub fn span(&self, name: &str) -> &RecordedSpan {
+        self.spans
+            .iter()
+            .find(|span| span.name == name)
+            .unwrap_or_else(|| {
+                let names: Vec<_> = self.spans.iter().map(|span| span.name.as_str()).collect();
+                panic!("no span named '{name}', recorded: {names:?}")
+            })
+    }
+}
+
+/// A tracing span recorded by [`TestRuntime`].
+#[derive(Debug, Clone)]
+pub struct RecordedSpan {
+    /// Span name, e.g. `request`.
+    pub name: String,
+    /// Field values, formatted as they would be logged.
+    pub fields: BTreeMap<String, String>,
+    /// Time between creating and closing the span.
+    pub duration: Duration,
+}
+
+impl RecordedSpan {
+    /// A field value, if it was recorded.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+}
+
+/// Span still open, stored in the registry's span extensions.
+struct OpenSpan {
+    fields: BTreeMap<String, String>,
+    started: Instant,
+}
+
+/// Layer collecting closed spans.
+#[derive(Default)]
+struct SpanRecorder {
+    spans: Arc<Mutex<Vec<RecordedSpan>>>,
+}
+
+impl<S> Layer<S> for SpanRecorder
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = BTreeMap::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(OpenSpan {
+                fields,
+                started: Instant::now(),
+            });
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(open) = span.extensions_mut().get_mut::<OpenSpan>()
+        {
+            values.record(&mut FieldVisitor(&mut open.fields));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id)
+            && let Some(open) = span.extensions_mut().remove::<OpenSpan>()
+        {
+            self.spans.lock().push(RecordedSpan {
+                name: span.name().to_string(),
+                fields: open.fields,
+                duration: open.started.elapsed(),
+            });
+        }
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
@@ -37,6 +37,7 @@ use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 // Import the real runtime
+use mik::manifest::{ChaosPolicy, TenantLimits};
 use mik::runtime::{Runtime, Server};
 
 /// A test host that runs the mikrozen runtime on an ephemeral port.
@@ -842,6 +843,21 @@ pub struct RealTestHostBuilder {
     max_body_size_mb: usize,
     /// Cache size (number of modules).
     cache_size: usize,
+    /// API key for the gateway's management endpoints.
+    api_key: Option<String>,
+    /// Enable the tenant module upload and delete endpoints.
+    tenant_uploads: bool,
+    /// Limits per tenant.
+    tenant_limits: Vec<(String, TenantLimits)>,
+    /// URL notified when the handler set changes.
+    handlers_webhook: Option<String>,
+    /// Faults injected per module.
+    chaos: Vec<(String, ChaosPolicy)>,
+    /// Directory requests are recorded to.
+    record_dir: Option<PathBuf>,
+    dev_proxy: Option<String>,
+    dev_dashboard: Option<PathBuf>,
+    oci_modules: Vec<(String, String)>,
 }
 
 impl Default for RealTestHostBuilder {
@@ -862,6 +878,15 @@ impl Default for RealTestHostBuilder {
             max_concurrent_requests: 10,
             max_body_size_mb: 1,
             cache_size: 10,
+            api_key: None,
+            tenant_uploads: false,
+            tenant_limits: Vec::new(),
+            handlers_webhook: None,
+            chaos: Vec::new(),
+            record_dir: None,
+            dev_proxy: None,
+            dev_dashboard: None,
+            oci_modules: Vec::new(),
         }
     }
 }
@@ -947,6 +972,83 @@ impl RealTestHostBuilder {
         self
     }
 
+    /// Set the API key for the gateway's management endpoints.
+    #[allow(dead_code)]
+    #[must_use]
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Enable the tenant module upload and delete endpoints.
+    #[allow(dead_code)]
+    #[must_use]
+    pub const fn with_tenant_uploads(mut self) -> Self {
+        self.tenant_uploads = true;
+        self
+    }
+
+    /// Set the limits of a tenant (`"*"` for every other tenant).
+    #[allow(dead_code)]
+    #[must_use]
+    pub fn with_tenant_limits(
+        mut self,
+        tenant_id: impl Into<String>,
+        limits: TenantLimits,
+    ) -> Self {
+        self.tenant_limits.push((tenant_id.into(), limits));
+        self
+    }
+
+    /// Notify `url` when the handler set changes.
+    #[allow(dead_code)]
+    #[must_use]
+    pub fn with_handlers_webhook(mut self, url: impl Into<String>) -> Self {
+        self.handlers_webhook = Some(url.into());
+        self
+    }
+
+    /// Inject faults into requests to a module (`"*"` for every other module).
+    #[allow(dead_code)]
+    #[must_use]
+    pub fn with_chaos(mut self, module: impl Into<String>, policy: ChaosPolicy) -> Self {
+        self.chaos.push((module.into(), policy));
+        self
+    }
+
+    /// Record module requests and responses to `dir`.
+    #[allow(dead_code)]
+    #[must_use]
+    pub fn with_record_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.record_dir = Some(dir.into());
+        self
+    }
+
+    /// Forward paths outside `/run/` and `/tenant/` to `url`.
+    #[allow(dead_code)]
+    #[must_use]
+    pub fn with_dev_proxy(mut self, url: impl Into<String>) -> Self {
+        self.dev_proxy = Some(url.into());
+        self
+    }
+
+    /// Serve the development dashboard, tailing `log_file`.
+    #[allow(dead_code)]
+    #[must_use]
+    pub fn with_dev_dashboard(mut self, log_file: impl Into<PathBuf>) -> Self {
+        self.dev_dashboard = Some(log_file.into());
+        self
+    }
+
+    /// Pull `module` from the OCI `reference` on first request.
+    #[allow(dead_code)]
+    #[must_use]
+    pub fn with_oci_module(mut self, module: &str, reference: impl Into<String>) -> Self {
+        self.oci_modules
+            .push((module.to_string(), reference.into()));
+        self
+    }
+
     /// Start the real mik runtime.
     ///
     /// This starts the actual wasmtime-based runtime with all production features:
@@ -1002,8 +1104,42 @@ impl RealTestHostBuilder {
             builder = builder.user_modules_dir(user_modules_dir);
         }
 
-        // Note: scripts_dir is set via manifest, not builder method
-        // For now, scripts won't work with RealTestHost
+        if let Some(api_key) = self.api_key {
+            builder = builder.api_key(api_key);
+        }
+        builder = builder.tenant_uploads(self.tenant_uploads);
+
+        for (tenant_id, limits) in self.tenant_limits {
+            builder = builder.tenant_limits(tenant_id, limits);
+        }
+
+        if let Some(url) = self.handlers_webhook {
+            builder = builder.handlers_webhook(url);
+        }
+
+        for (module, policy) in self.chaos {
+            builder = builder.chaos(module, policy);
+        }
+
+        if let Some(dir) = self.record_dir {
+            builder = builder.record_dir(dir);
+        }
+
+        if let Some(url) = self.dev_proxy {
+            builder = builder.dev_proxy(url);
+        }
+
+        if let Some(log_file) = self.dev_dashboard {
+            builder = builder.dev_dashboard(log_file);
+        }
+
+        for (module, reference) in self.oci_modules {
+            builder = builder.oci_module(module, reference);
+        }
+
+        if let Some(scripts_dir) = self.scripts_dir {
+            builder = builder.scripts_dir(scripts_dir);
+        }
 
         let runtime = builder.build()?;
         let server = Server::new(runtime, addr);
@@ -334,6 +334,56 @@ async fn test_shutdown_no_hanging_connections() {
     assert_eq!(health.status(), 200);
 }
 
+/// Test that `Runtime::shutdown_graceful` cancels executions that outlive
+/// the drain timeout and rejects requests afterwards.
+#[tokio::test]
+async fn test_shutdown_graceful_cancels_stuck_execution() {
+    if !fixture_exists("infinite_loop.wasm") {
+        eprintln!("Skipping: infinite_loop.wasm fixture not found");
+        return;
+    }
+
+    let runtime = Arc::new(
+        mik::runtime::Runtime::builder()
+            .modules_dir(fixtures_dir())
+            .execution_timeout(Duration::from_secs(30))
+            .fuel_budget(u64::MAX)
+            .build()
+            .expect("Failed to build runtime"),
+    );
+
+    let request = tokio::spawn({
+        let runtime = runtime.clone();
+        async move {
+            runtime
+                .handle_request(
+                    mik::runtime::Request::new("GET", "/run/infinite_loop/")
+                        .with_header("Host", "localhost"),
+                )
+                .await
+        }
+    });
+    // Let the guest start spinning
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let report = runtime.shutdown_graceful(Duration::from_millis(200)).await;
+    assert_eq!(report.in_flight, 1);
+    assert_eq!(report.completed, 0);
+    assert_eq!(report.cancelled, 1);
+    assert!(report.elapsed < Duration::from_secs(5));
+
+    let response = tokio::time::timeout(Duration::from_secs(5), request)
+        .await
+        .expect("Cancelled request should finish")
+        .unwrap();
+    assert!(response.map_or(true, |r| r.status >= 500));
+
+    let rejected = runtime
+        .handle_request(mik::runtime::Request::new("GET", "/health"))
+        .await;
+    assert!(rejected.is_err());
+}
+
 // =============================================================================
 // Unit Tests (No fixtures required)
 // =============================================================================
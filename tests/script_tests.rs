@@ -16,7 +16,7 @@
 #[path = "common.rs"]
 mod common;
 
-use common::TestHost;
+use common::{RealTestHost, TestHost};
 use std::path::PathBuf;
 
 /// Get the path to the test scripts directory.
@@ -1261,3 +1261,63 @@ export default function(input) {
         );
     }
 }
+
+// =============================================================================
+// Trace Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_script_trace_requires_api_key() {
+    let host = RealTestHost::builder()
+        .with_scripts_dir(scripts_dir())
+        .with_api_key("test-key")
+        .start()
+        .await
+        .expect("Failed to start test host");
+    let traced = |key: Option<&'static str>| {
+        let mut request = host
+            .client()
+            .post(host.url("/script/echo?trace=1"))
+            .json(&serde_json::json!({"secret": "value"}));
+        if let Some(key) = key {
+            request = request.header("X-API-Key", key);
+        }
+        request.send()
+    };
+
+    // Without the key, the parameter is ignored
+    for key in [None, Some("wrong")] {
+        let resp = traced(key).await.expect("Failed to execute script");
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = resp.json().await.expect("Failed to parse response");
+        assert_eq!(body["result"]["secret"], "value");
+        assert!(body.get("trace").is_none(), "Trace leaked: {body}");
+    }
+
+    let resp = traced(Some("test-key"))
+        .await
+        .expect("Failed to execute script");
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.expect("Failed to parse response");
+    assert_eq!(body["result"]["secret"], "value");
+    assert!(body["trace"]["spans"].is_array());
+}
+
+#[tokio::test]
+async fn test_script_trace_allowed_in_dev_mode() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let host = RealTestHost::builder()
+        .with_scripts_dir(scripts_dir())
+        .with_dev_dashboard(dir.path().join("app.log"))
+        .start()
+        .await
+        .expect("Failed to start test host");
+
+    let resp = host
+        .post_json("/script/echo?trace=1", &serde_json::json!({"a": 1}))
+        .await
+        .expect("Failed to execute script");
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.expect("Failed to parse response");
+    assert!(body["trace"]["spans"].is_array());
+}
@@ -13,6 +13,7 @@ mod common;
 
 use common::RealTestHost;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Check if required fixtures exist.
 fn fixtures_available() -> bool {
@@ -432,6 +433,133 @@ async fn test_gateway_handlers_links_format() {
     }
 }
 
+#[tokio::test]
+async fn test_gateway_circuit_breaker_open_and_reset() {
+    require_fixtures!();
+
+    let (modules_dir, user_modules_dir) = get_fixture_dirs();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(&user_modules_dir)
+        .with_api_key("test-key")
+        .start()
+        .await
+        .expect("Failed to start test host");
+    let action = |path: &str| {
+        host.client()
+            .post(host.url(path))
+            .header("X-API-Key", "test-key")
+            .send()
+    };
+
+    // Actions require POST
+    let resp = host
+        .get("/_mik/circuit-breakers/echo/open")
+        .await
+        .expect("Failed to call circuit breaker endpoint");
+    assert_eq!(resp.status(), 405);
+
+    let resp = action("/_mik/circuit-breakers/echo/open")
+        .await
+        .expect("Failed to open circuit breaker");
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.expect("Failed to parse response");
+    assert_eq!(body["data"]["attributes"]["state"], "open");
+    assert!(body["data"]["attributes"]["opened_at"].is_string());
+
+    let resp = host
+        .post_json("/run/echo/", &serde_json::json!({}))
+        .await
+        .expect("Failed to call module");
+    assert_eq!(resp.status(), 503, "Open circuit should reject requests");
+
+    let resp = host
+        .get("/_mik/circuit-breakers")
+        .await
+        .expect("Failed to list circuit breakers");
+    let body: serde_json::Value = resp.json().await.expect("Failed to parse response");
+    assert_eq!(body["meta"]["open_count"], 1);
+    assert_eq!(body["data"][0]["id"], "echo");
+
+    let metrics = host
+        .get("/metrics")
+        .await
+        .expect("Failed to get metrics")
+        .text()
+        .await
+        .expect("Failed to read metrics");
+    assert!(metrics.contains("mik_circuit_breaker_state{module=\"echo\"} 1"));
+    assert!(metrics.contains("mik_circuit_breaker_failures{module=\"echo\"} 0"));
+
+    let resp = action("/_mik/circuit-breakers/echo/reset")
+        .await
+        .expect("Failed to reset circuit breaker");
+    let body: serde_json::Value = resp.json().await.expect("Failed to parse response");
+    assert_eq!(body["data"]["attributes"]["state"], "closed");
+
+    let resp = host
+        .post_json("/run/echo/", &serde_json::json!({}))
+        .await
+        .expect("Failed to call module");
+    assert_eq!(resp.status(), 200, "Reset circuit should allow requests");
+}
+
+#[tokio::test]
+async fn test_gateway_circuit_breaker_actions_require_api_key() {
+    require_fixtures!();
+
+    let (modules_dir, user_modules_dir) = get_fixture_dirs();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(&user_modules_dir)
+        .with_api_key("test-key")
+        .start()
+        .await
+        .expect("Failed to start test host");
+
+    let resp = host
+        .post_text("/_mik/circuit-breakers/echo/open", "")
+        .await
+        .expect("Failed to call circuit breaker endpoint");
+    assert_eq!(resp.status(), 401);
+    let resp = host
+        .client()
+        .post(host.url("/_mik/circuit-breakers/echo/open"))
+        .header("X-API-Key", "wrong")
+        .send()
+        .await
+        .expect("Failed to call circuit breaker endpoint");
+    assert_eq!(resp.status(), 401);
+
+    // The circuit was not opened
+    let resp = host
+        .post_json("/run/echo/", &serde_json::json!({}))
+        .await
+        .expect("Failed to call module");
+    assert_eq!(resp.status(), 200);
+
+    // Listing stays public
+    let resp = host
+        .get("/_mik/circuit-breakers")
+        .await
+        .expect("Failed to list circuit breakers");
+    assert_eq!(resp.status(), 200);
+
+    // Without a configured key, actions are disabled
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .start()
+        .await
+        .expect("Failed to start test host");
+    let resp = host
+        .post_text("/_mik/circuit-breakers/echo/reset", "")
+        .await
+        .expect("Failed to call circuit breaker endpoint");
+    assert_eq!(resp.status(), 403);
+}
+
 // =============================================================================
 // Response Headers Tests
 // =============================================================================
@@ -512,3 +640,427 @@ async fn test_tenant_response_includes_duration_header() {
         .expect("Duration should be a valid number");
     assert!(duration < 10000, "Duration should be reasonable (< 10s)");
 }
+
+// =============================================================================
+// Module Management Tests (/_mik/tenants/*)
+// =============================================================================
+
+#[tokio::test]
+async fn test_tenant_module_upload_replace_delete() {
+    require_fixtures!();
+
+    let (modules_dir, fixture_user_modules) = get_fixture_dirs();
+    let user_modules_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let wasm = std::fs::read(fixture_user_modules.join("tenant-abc/orders.wasm")).unwrap();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(user_modules_dir.path())
+        .with_api_key("test-key")
+        .with_tenant_uploads()
+        .start()
+        .await
+        .expect("Failed to start test host");
+    let url = host.url("/_mik/tenants/acme/modules/orders");
+    let upload = |key: &'static str, body: Vec<u8>| {
+        host.client()
+            .put(&url)
+            .header("X-API-Key", key)
+            .body(body)
+            .send()
+    };
+
+    // Rejected without the key or with a non-WASM body
+    assert_eq!(upload("wrong", wasm.clone()).await.unwrap().status(), 401);
+    assert_eq!(
+        upload("test-key", b"not wasm".to_vec())
+            .await
+            .unwrap()
+            .status(),
+        400
+    );
+
+    // Upload, then replace
+    let resp = upload("test-key", wasm.clone()).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["data"]["id"], "acme/orders");
+    assert_eq!(body["data"]["attributes"]["size_bytes"], wasm.len());
+
+    let resp = host
+        .post_json("/tenant/acme/orders/", &serde_json::json!({"test": true}))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200, "Uploaded module should be served");
+
+    assert_eq!(
+        upload("test-key", wasm.clone()).await.unwrap().status(),
+        200
+    );
+
+    // Delete evicts the cached module
+    let delete = || {
+        host.client()
+            .delete(&url)
+            .header("X-API-Key", "test-key")
+            .send()
+    };
+    assert_eq!(delete().await.unwrap().status(), 204);
+    assert_eq!(delete().await.unwrap().status(), 404);
+    let resp = host
+        .post_json("/tenant/acme/orders/", &serde_json::json!({"test": true}))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404, "Deleted module should not be served");
+}
+
+#[tokio::test]
+async fn test_gateway_discovery_etags() {
+    require_fixtures!();
+
+    let (modules_dir, fixture_user_modules) = get_fixture_dirs();
+    let user_modules_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let wasm = std::fs::read(fixture_user_modules.join("tenant-abc/orders.wasm")).unwrap();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(user_modules_dir.path())
+        .with_api_key("test-key")
+        .with_tenant_uploads()
+        .start()
+        .await
+        .expect("Failed to start test host");
+    let get = |path: &'static str, etag: Option<String>| {
+        let mut req = host.client().get(host.url(path));
+        if let Some(etag) = etag {
+            req = req.header("If-None-Match", etag);
+        }
+        req.send()
+    };
+    let etag = |resp: &reqwest::Response| resp.headers()["etag"].to_str().unwrap().to_string();
+
+    let resp = get("/_mik/handlers", None).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let first = etag(&resp);
+    let resp = get("/_mik/openapi/platform", None).await.unwrap();
+    let platform = etag(&resp);
+    assert_ne!(first, platform);
+
+    // Unchanged: 304 without a body
+    let resp = get("/_mik/handlers", Some(first.clone())).await.unwrap();
+    assert_eq!(resp.status(), 304);
+    assert_eq!(etag(&resp), first);
+    assert!(resp.bytes().await.unwrap().is_empty());
+
+    // Uploading a module changes the handler list
+    let resp = host
+        .client()
+        .put(host.url("/_mik/tenants/acme/modules/orders"))
+        .header("X-API-Key", "test-key")
+        .body(wasm)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let resp = get("/_mik/handlers", Some(first.clone())).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_ne!(etag(&resp), first);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["meta"]["tenant_count"], 1);
+
+    // The platform spec did not change
+    let resp = get("/_mik/openapi/platform", Some(platform)).await.unwrap();
+    assert_eq!(resp.status(), 304);
+
+    // Tenant specs are cached per tenant
+    let resp = get("/_mik/openapi/tenant/acme", None).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = get("/_mik/openapi/tenant/acme", Some(etag(&resp)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 304);
+    let resp = get("/_mik/openapi/tenant/globex", None).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_handlers_webhook_on_module_changes() {
+    require_fixtures!();
+
+    // Receiver standing in for the gateway
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<serde_json::Value>();
+    let app = axum::Router::new().route(
+        "/hooks/handlers",
+        axum::routing::post(move |axum::Json(event): axum::Json<serde_json::Value>| {
+            let tx = tx.clone();
+            async move {
+                let _ = tx.send(event);
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let hook_url = format!("http://{}/hooks/handlers", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let (modules_dir, fixture_user_modules) = get_fixture_dirs();
+    let user_modules_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let wasm = std::fs::read(fixture_user_modules.join("tenant-abc/orders.wasm")).unwrap();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(user_modules_dir.path())
+        .with_api_key("test-key")
+        .with_tenant_uploads()
+        .with_handlers_webhook(hook_url)
+        .start()
+        .await
+        .expect("Failed to start test host");
+    let url = host.url("/_mik/tenants/acme/modules/orders");
+    async fn next_event(
+        rx: &mut tokio::sync::mpsc::UnboundedReceiver<serde_json::Value>,
+    ) -> serde_json::Value {
+        tokio::time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .expect("No webhook received")
+            .unwrap()
+    }
+
+    let resp = host
+        .client()
+        .put(&url)
+        .header("X-API-Key", "test-key")
+        .body(wasm)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let event = next_event(&mut rx).await;
+    assert_eq!(event["event"], "handlers.changed");
+    assert_eq!(event["added"], serde_json::json!(["acme/orders"]));
+    assert_eq!(event["removed"], serde_json::json!([]));
+
+    let resp = host
+        .client()
+        .delete(&url)
+        .header("X-API-Key", "test-key")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 204);
+    let event = next_event(&mut rx).await;
+    assert_eq!(event["removed"], serde_json::json!(["acme/orders"]));
+}
+
+#[tokio::test]
+async fn test_tenant_module_management_disabled_without_key() {
+    require_fixtures!();
+
+    let (modules_dir, user_modules_dir) = get_fixture_dirs();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(&user_modules_dir)
+        .start()
+        .await
+        .expect("Failed to start test host");
+
+    let resp = host
+        .client()
+        .delete(host.url("/_mik/tenants/tenant-abc/modules/orders"))
+        .header("X-API-Key", "anything")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 403);
+    assert!(user_modules_dir.join("tenant-abc/orders.wasm").exists());
+}
+
+#[tokio::test]
+async fn test_tenant_module_management_requires_opt_in() {
+    require_fixtures!();
+
+    let (modules_dir, user_modules_dir) = get_fixture_dirs();
+
+    // The API key alone does not enable uploads or deletes
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(&user_modules_dir)
+        .with_api_key("test-key")
+        .start()
+        .await
+        .expect("Failed to start test host");
+
+    let resp = host
+        .client()
+        .delete(host.url("/_mik/tenants/tenant-abc/modules/orders"))
+        .header("X-API-Key", "test-key")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 403);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(
+        body["message"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("tenant_uploads"),
+        "{body}"
+    );
+    assert!(user_modules_dir.join("tenant-abc/orders.wasm").exists());
+
+    let resp = host
+        .client()
+        .put(host.url("/_mik/tenants/acme/modules/orders"))
+        .header("X-API-Key", "test-key")
+        .body(b"\0asm".to_vec())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 403);
+}
+
+// =============================================================================
+// Usage Metering Tests (/_mik/usage/tenant/*)
+// =============================================================================
+
+#[tokio::test]
+async fn test_tenant_usage_is_metered() {
+    require_fixtures!();
+
+    let (modules_dir, user_modules_dir) = get_fixture_dirs();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(&user_modules_dir)
+        .start()
+        .await
+        .expect("Failed to start test host");
+
+    // Known tenant without requests reports zero usage
+    let resp = host.get("/_mik/usage/tenant/tenant-xyz").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["data"]["attributes"]["requests"], 0);
+
+    for _ in 0..2 {
+        let resp = host
+            .post_json("/tenant/tenant-abc/orders/", &serde_json::json!({"n": 1}))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+    }
+    // Platform modules are not billed to any tenant
+    host.post_json("/run/echo/", &serde_json::json!({"n": 1}))
+        .await
+        .unwrap();
+
+    let resp = host.get("/_mik/usage/tenant/tenant-abc").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let usage = &body["data"]["attributes"];
+    assert_eq!(body["data"]["id"], "tenant-abc");
+    assert_eq!(usage["requests"], 2);
+    assert_eq!(usage["errors"], 0);
+    assert!(usage["fuel_consumed"].as_u64().unwrap() > 0);
+
+    let metrics = host.get("/metrics").await.unwrap().text().await.unwrap();
+    assert!(metrics.contains("mik_tenant_requests_total{tenant=\"tenant-abc\"} 2"));
+    assert!(!metrics.contains("tenant=\"tenant-xyz\""));
+
+    let resp = host.get("/_mik/usage/tenant/unknown").await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_tenant_limits_return_machine_readable_errors() {
+    require_fixtures!();
+
+    let (modules_dir, fixture_user_modules) = get_fixture_dirs();
+    let user_modules_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let wasm = std::fs::read(fixture_user_modules.join("tenant-abc/orders.wasm")).unwrap();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(user_modules_dir.path())
+        .with_api_key("test-key")
+        .with_tenant_uploads()
+        .with_tenant_limits(
+            "acme",
+            mik::manifest::TenantLimits {
+                requests_per_second: Some(1),
+                max_concurrent_requests: None,
+                max_storage_mb: Some(1),
+            },
+        )
+        .start()
+        .await
+        .expect("Failed to start test host");
+    let upload = |name: &str, body: Vec<u8>| {
+        host.client()
+            .put(host.url(&format!("/_mik/tenants/acme/modules/{name}")))
+            .header("X-API-Key", "test-key")
+            .body(body)
+            .send()
+    };
+
+    // Uploads past the storage limit are rejected; replacing is not
+    assert_eq!(upload("orders", wasm.clone()).await.unwrap().status(), 201);
+    let mut padded = b"\0asm".to_vec();
+    padded.resize(1024 * 1024, 0);
+    let resp = upload("big", padded).await.unwrap();
+    assert_eq!(resp.status(), 507);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["error"], "quota_exceeded");
+    assert_eq!(upload("orders", wasm).await.unwrap().status(), 200);
+
+    // At 1 request/sec, back-to-back requests are shed with the limit named
+    let mut limited = None;
+    for _ in 0..3 {
+        let resp = host
+            .post_json("/tenant/acme/orders/", &serde_json::json!({}))
+            .await
+            .unwrap();
+        if resp.status() == 429 {
+            limited = Some(resp);
+            break;
+        }
+    }
+    let resp = limited.expect("Requests over the rate limit should be shed");
+    assert!(resp.headers().contains_key("retry-after"));
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "tenant_limit_exceeded");
+    assert_eq!(body["tenant"], "acme");
+    assert_eq!(body["limit"], "requests_per_second");
+    assert_eq!(body["max"], 1);
+}
+
+#[tokio::test]
+async fn test_tenant_limits_skip_unknown_tenants() {
+    require_fixtures!();
+
+    let (modules_dir, user_modules_dir) = get_fixture_dirs();
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(&modules_dir)
+        .with_user_modules_dir(&user_modules_dir)
+        .with_tenant_limits(
+            "*",
+            mik::manifest::TenantLimits {
+                requests_per_second: Some(1),
+                max_concurrent_requests: Some(1),
+                max_storage_mb: None,
+            },
+        )
+        .start()
+        .await
+        .expect("Failed to start test host");
+
+    // Unknown tenants are not found before any limit state is kept for them
+    for _ in 0..3 {
+        let resp = host
+            .post_json("/tenant/no-such-tenant/orders/", &serde_json::json!({}))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 404);
+    }
+}
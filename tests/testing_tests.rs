@@ -0,0 +1,117 @@
+//! Tests for the `mik::testing` fixture (requires the `testing` feature).
+#![cfg(feature = "testing")]
+
+use mik::testing::TestRuntime;
+use serde_json::json;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn echo_wasm() -> Option<Vec<u8>> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("modules")
+        .join("echo.wasm");
+    std::fs::read(path).ok()
+}
+
+#[tokio::test]
+async fn test_runtime_fixture_runs_component() {
+    let Some(echo) = echo_wasm() else {
+        eprintln!("Skipping: echo.wasm not found. Run build script first.");
+        return;
+    };
+
+    let runtime = TestRuntime::builder()
+        .component("echo", echo)
+        .configure(|builder| builder.execution_timeout(Duration::from_secs(10)))
+        .build()
+        .unwrap();
+
+    let response = runtime
+        .post("/")
+        .json(&json!({"message": "hello"}))
+        .send()
+        .await
+        .unwrap();
+    response
+        .assert_status(200)
+        .assert_json(json!({"message": "hello"}))
+        .assert_span("request");
+    assert_eq!(response.span("request").field("path"), Some("/run/echo/"));
+    assert!(response.span("request").field("timeout_ms").is_some());
+
+    runtime
+        .send(mik::runtime::Request::new("GET", "/run/missing/"))
+        .await
+        .unwrap()
+        .assert_status(404);
+}
+
+#[test]
+fn test_fixture_requires_component() {
+    assert!(TestRuntime::builder().build().is_err());
+}
+
+#[tokio::test]
+async fn test_fixture_with_services() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("modules")
+        .join("echo.wasm");
+    if !path.exists() {
+        eprintln!("Skipping: echo.wasm not found. Run build script first.");
+        return;
+    }
+
+    let runtime = TestRuntime::builder()
+        .fixture(&path)
+        .services()
+        .build()
+        .unwrap();
+    assert_eq!(runtime.module(), "echo");
+    runtime
+        .post("/")
+        .body("hi")
+        .send()
+        .await
+        .unwrap()
+        .assert_status(200);
+
+    // Seeded state is visible through the services' HTTP API
+    let services = runtime.services();
+    services.kv().set("greeting", b"hello", None).await.unwrap();
+    services
+        .sql()
+        .execute_batch("CREATE TABLE t (id INTEGER); INSERT INTO t VALUES (1);")
+        .await
+        .unwrap();
+
+    let client = reqwest::Client::new();
+    let kv: serde_json::Value = client
+        .get(format!("{}/kv/greeting", services.url()))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(kv["value"], "hello");
+
+    let rows: serde_json::Value = client
+        .post(format!("{}/sql/query", services.url()))
+        .json(&json!({ "sql": "SELECT id FROM t" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(rows["rows"][0][0], 1, "{rows}");
+}
+
+#[test]
+fn test_fixture_must_exist() {
+    assert!(TestRuntime::with_fixture("missing/component.wasm").is_err());
+}
@@ -7,7 +7,13 @@
 //! - Timeout/epoch interruption (infinite_loop.wasm)
 //! - Memory limits (memory_hog.wasm)
 //! - Fuel metering (fuel_burner.wasm)
+//! - Injected faults (`[server.chaos]`)
+//! - Request recording (`record_dir`)
+//! - Forwarding to a frontend dev server (`dev_proxy`)
+//! - The development dashboard (`dev_dashboard`)
+//! - Modules pulled from an OCI registry (`oci_modules`)
 
+use mik::manifest::ChaosPolicy;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
@@ -399,6 +405,513 @@ async fn test_fuel_burner_exhausts_fuel() {
     assert_eq!(health.status(), 200);
 }
 
+// =============================================================================
+// Chaos Tests (Fault Injection)
+// =============================================================================
+
+/// Whether the request failed, with a 5xx or a closed connection.
+fn failed(result: &reqwest::Result<reqwest::Response>) -> bool {
+    result
+        .as_ref()
+        .map_or(true, |resp| resp.status().is_server_error())
+}
+
+#[tokio::test]
+async fn test_chaos_faults_fail_requests() {
+    require_fixture!("echo.wasm");
+
+    let faults = [
+        ChaosPolicy {
+            trap_probability: Some(1.0),
+            ..Default::default()
+        },
+        ChaosPolicy {
+            fuel_starvation_probability: Some(1.0),
+            ..Default::default()
+        },
+        ChaosPolicy {
+            drop_probability: Some(1.0),
+            ..Default::default()
+        },
+    ];
+    for policy in faults {
+        let host = RealTestHost::builder()
+            .with_modules_dir(fixtures_dir())
+            .with_chaos("echo", policy)
+            .start()
+            .await
+            .expect("Failed to start host");
+
+        let result = host
+            .post_json("/run/echo/", &serde_json::json!({"message": "hello"}))
+            .await;
+        assert!(failed(&result), "{policy:?} should fail the request");
+
+        let health = host
+            .get("/health")
+            .await
+            .expect("Server should still be running");
+        assert_eq!(health.status(), 200);
+    }
+}
+
+#[tokio::test]
+async fn test_chaos_latency_delays_response() {
+    require_fixture!("echo.wasm");
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .with_chaos(
+            "*",
+            ChaosPolicy {
+                latency_ms: Some(300),
+                latency_probability: Some(1.0),
+                ..Default::default()
+            },
+        )
+        .start()
+        .await
+        .expect("Failed to start host");
+
+    let start = Instant::now();
+    let resp = host
+        .post_json("/run/echo/", &serde_json::json!({"message": "hello"}))
+        .await
+        .expect("Request failed");
+    assert_eq!(resp.status(), 200);
+    assert!(
+        start.elapsed() >= Duration::from_millis(300),
+        "Latency not injected, took {:?}",
+        start.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn test_chaos_latency_past_timeout_times_out() {
+    require_fixture!("echo.wasm");
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .with_execution_timeout(1)
+        .with_chaos(
+            "echo",
+            ChaosPolicy {
+                latency_ms: Some(60_000),
+                latency_probability: Some(1.0),
+                ..Default::default()
+            },
+        )
+        .start()
+        .await
+        .expect("Failed to start host");
+
+    let start = Instant::now();
+    let result = host
+        .post_json("/run/echo/", &serde_json::json!({"message": "hello"}))
+        .await;
+    assert!(failed(&result));
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "Should stop at the execution timeout, took {:?}",
+        start.elapsed()
+    );
+}
+
+// =============================================================================
+// Recording Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_requests_are_recorded() {
+    require_fixture!("echo.wasm");
+
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .with_record_dir(dir.path())
+        .start()
+        .await
+        .expect("Failed to start host");
+
+    let resp = host
+        .client()
+        .post(host.url("/run/echo/?page=2"))
+        .header("Authorization", "Bearer secret")
+        .json(&serde_json::json!({"message": "hello"}))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(resp.status(), 200);
+
+    // Recordings are written in the background
+    let mut recordings = Vec::new();
+    for _ in 0..50 {
+        recordings = mik::runtime::recorder::load_dir(dir.path()).unwrap_or_default();
+        if !recordings.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(recordings.len(), 1, "Expected one recording");
+
+    let (_, recording) = &recordings[0];
+    assert_eq!(recording.module.as_deref(), Some("echo"));
+    assert_eq!(recording.request.method, "POST");
+    assert_eq!(recording.request.uri, "/run/echo/?page=2");
+    assert_eq!(recording.request.headers["authorization"], "[redacted]");
+    let response = recording.response.as_ref().expect("Response recorded");
+    assert_eq!(response.status, 200);
+    let body: serde_json::Value =
+        serde_json::from_slice(&response.body.bytes().unwrap()).expect("JSON body");
+    assert_eq!(body["message"], "hello");
+}
+
+// =============================================================================
+// Dev Proxy Tests
+// =============================================================================
+
+/// Minimal frontend dev server: serves `/src/main.js` and echoes bytes back
+/// on WebSocket connections after the handshake.
+async fn start_dev_server() -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut head = Vec::new();
+                let mut byte = [0u8; 1];
+                while !head.ends_with(b"\r\n\r\n") {
+                    if stream.read(&mut byte).await.unwrap_or(0) == 0 {
+                        return;
+                    }
+                    head.push(byte[0]);
+                }
+                let head = String::from_utf8_lossy(&head).to_ascii_lowercase();
+                if head.contains("upgrade: websocket") {
+                    let _ = stream
+                        .write_all(
+                            b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                              Connection: Upgrade\r\n\r\n",
+                        )
+                        .await;
+                    let (mut reader, mut writer) = stream.split();
+                    let _ = tokio::io::copy(&mut reader, &mut writer).await;
+                } else {
+                    let body = format!("// {}", head.lines().next().unwrap_or_default());
+                    let _ = stream
+                        .write_all(
+                            format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: text/javascript\r\n\
+                                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                                body.len()
+                            )
+                            .as_bytes(),
+                        )
+                        .await;
+                }
+            });
+        }
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn test_dev_proxy_forwards_other_paths() {
+    require_fixture!("echo.wasm");
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .with_dev_proxy(start_dev_server().await)
+        .start()
+        .await
+        .expect("Failed to start host");
+
+    let resp = host
+        .client()
+        .get(host.url("/src/main.js?v=1"))
+        .send()
+        .await
+        .expect("Request failed");
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "text/javascript");
+    assert_eq!(
+        resp.text().await.unwrap(),
+        "// get /src/main.js?v=1 http/1.1"
+    );
+
+    // Module routes are still served by the runtime
+    let resp = host
+        .post_json("/run/echo/", &serde_json::json!({"message": "hello"}))
+        .await
+        .expect("Request failed");
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_dev_proxy_passes_websockets_through() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    require_fixture!("echo.wasm");
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .with_dev_proxy(start_dev_server().await)
+        .start()
+        .await
+        .expect("Failed to start host");
+
+    let mut stream = tokio::net::TcpStream::connect(host.addr()).await.unwrap();
+    stream
+        .write_all(
+            b"GET /@vite/hmr HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\n\
+              Upgrade: websocket\r\nSec-WebSocket-Version: 13\r\n\
+              Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+        )
+        .await
+        .unwrap();
+
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        assert_eq!(
+            stream.read(&mut byte).await.unwrap(),
+            1,
+            "Connection closed"
+        );
+        head.push(byte[0]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    assert!(head.starts_with("HTTP/1.1 101"), "{head}");
+
+    stream.write_all(b"hot update").await.unwrap();
+    let mut echoed = [0u8; 10];
+    tokio::time::timeout(Duration::from_secs(5), stream.read_exact(&mut echoed))
+        .await
+        .expect("No echo through the proxy")
+        .unwrap();
+    assert_eq!(&echoed, b"hot update");
+}
+
+// =============================================================================
+// Dev Dashboard Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_dev_dashboard_shows_requests_and_log() {
+    require_fixture!("echo.wasm");
+
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let log_file = dir.path().join("app.log");
+    std::fs::write(&log_file, "Server started\n").unwrap();
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .with_dev_dashboard(&log_file)
+        .start()
+        .await
+        .expect("Failed to start host");
+
+    let resp = host
+        .post_json("/run/echo/", &serde_json::json!({"message": "hello"}))
+        .await
+        .expect("Request failed");
+    assert_eq!(resp.status(), 200);
+
+    let page = host.get("/_dev/").await.expect("Request failed");
+    assert_eq!(page.status(), 200);
+    assert!(page.text().await.unwrap().contains("/_dev/state"));
+
+    let state: serde_json::Value = host
+        .get("/_dev/state")
+        .await
+        .expect("Request failed")
+        .json()
+        .await
+        .expect("JSON state");
+    let requests = state["requests"].as_array().unwrap();
+    let echo = requests
+        .iter()
+        .find(|r| r["path"] == "/run/echo/")
+        .expect("Echo request recorded");
+    assert_eq!(echo["method"], "POST");
+    assert_eq!(echo["status"], 200);
+    assert!(!echo["spans"].as_array().unwrap().is_empty());
+    // Dashboard requests themselves are not recorded
+    assert!(requests.iter().all(|r| r["path"] != "/_dev/"));
+    assert!(
+        state["modules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|m| m == "echo")
+    );
+    assert_eq!(state["log_tail"], serde_json::json!(["Server started"]));
+}
+
+#[tokio::test]
+async fn test_dev_dashboard_disabled_by_default() {
+    require_fixture!("echo.wasm");
+
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .start()
+        .await
+        .expect("Failed to start host");
+    let resp = host.get("/_dev/state").await.expect("Request failed");
+    assert_eq!(resp.status(), 404);
+}
+
+// =============================================================================
+// OCI Module Tests
+// =============================================================================
+
+/// Requests served by [`start_registry`], by kind.
+#[derive(Default)]
+struct RegistryHits {
+    manifests: std::sync::atomic::AtomicUsize,
+    blobs: std::sync::atomic::AtomicUsize,
+}
+
+/// echo.wasm with a custom section named `tag`, so each test pulls a blob
+/// that isn't in the shared OCI cache yet.
+fn unique_echo(tag: &str) -> Vec<u8> {
+    let mut wasm = std::fs::read(fixtures_dir().join("echo.wasm")).unwrap();
+    let payload = [&[tag.len() as u8], tag.as_bytes()].concat();
+    wasm.push(0);
+    wasm.push(payload.len() as u8);
+    wasm.extend(payload);
+    wasm
+}
+
+/// Start a minimal OCI registry serving one image, `test/echo:1.0`, whose
+/// layer is `served` but whose manifest announces the digest of `layer`.
+async fn start_registry(layer: Vec<u8>, served: Vec<u8>) -> (String, std::sync::Arc<RegistryHits>) {
+    use sha2::{Digest, Sha256};
+    use std::sync::atomic::Ordering;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let digest = format!("sha256:{}", hex::encode(Sha256::digest(&layer)));
+    let manifest = serde_json::json!({
+        "schemaVersion": 2,
+        "mediaType": "application/vnd.oci.image.manifest.v1+json",
+        "config": {
+            "mediaType": "application/vnd.oci.empty.v1+json",
+            "digest": format!("sha256:{}", hex::encode(Sha256::digest(b"{}"))),
+            "size": 2
+        },
+        "layers": [{"mediaType": "application/wasm", "digest": digest, "size": layer.len()}]
+    })
+    .to_string();
+    let hits = std::sync::Arc::new(RegistryHits::default());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let registry_hits = hits.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let (manifest, served, hits) =
+                (manifest.clone(), served.clone(), registry_hits.clone());
+            tokio::spawn(async move {
+                loop {
+                    let mut head = Vec::new();
+                    let mut byte = [0u8; 1];
+                    while !head.ends_with(b"\r\n\r\n") {
+                        if stream.read(&mut byte).await.unwrap_or(0) == 0 {
+                            return;
+                        }
+                        head.push(byte[0]);
+                    }
+                    let head = String::from_utf8_lossy(&head);
+                    let path = head.split(' ').nth(1).unwrap_or_default();
+                    let (content_type, body) = if path == "/v2/test/echo/manifests/1.0" {
+                        hits.manifests.fetch_add(1, Ordering::SeqCst);
+                        (
+                            "application/vnd.oci.image.manifest.v1+json",
+                            manifest.as_bytes().to_vec(),
+                        )
+                    } else if path.starts_with("/v2/test/echo/blobs/") {
+                        hits.blobs.fetch_add(1, Ordering::SeqCst);
+                        ("application/octet-stream", served.clone())
+                    } else {
+                        ("application/json", b"{}".to_vec())
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\n\
+                         Content-Length: {}\r\n\r\n",
+                        body.len()
+                    );
+                    if stream.write_all(response.as_bytes()).await.is_err()
+                        || stream.write_all(&body).await.is_err()
+                    {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    (format!("oci://{addr}/test/echo:1.0"), hits)
+}
+
+#[tokio::test]
+async fn test_oci_module_pulled_on_first_request() {
+    use std::sync::atomic::Ordering;
+    require_fixture!("echo.wasm");
+
+    let wasm = unique_echo(&format!("pulled-{}", std::process::id()));
+    let (reference, hits) = start_registry(wasm.clone(), wasm).await;
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .with_oci_module("remote-echo", reference)
+        .start()
+        .await
+        .expect("Failed to start host");
+    assert_eq!(hits.manifests.load(Ordering::SeqCst), 0);
+
+    for _ in 0..2 {
+        let resp = host
+            .post_json(
+                "/run/remote-echo/",
+                &serde_json::json!({"message": "hello"}),
+            )
+            .await
+            .expect("Request failed");
+        assert_eq!(resp.status(), 200);
+    }
+    assert_eq!(hits.manifests.load(Ordering::SeqCst), 1);
+    assert_eq!(hits.blobs.load(Ordering::SeqCst), 1);
+
+    // Modules without a reference are still not found
+    let resp = host.get("/run/missing/").await.expect("Request failed");
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_oci_module_rejects_digest_mismatch() {
+    require_fixture!("echo.wasm");
+
+    let id = std::process::id();
+    let (reference, _hits) = start_registry(
+        unique_echo(&format!("announced-{id}")),
+        unique_echo(&format!("tampered-{id}")),
+    )
+    .await;
+    let host = RealTestHost::builder()
+        .with_modules_dir(fixtures_dir())
+        .with_oci_module("remote-echo", reference)
+        .start()
+        .await
+        .expect("Failed to start host");
+
+    let resp = host
+        .post_json(
+            "/run/remote-echo/",
+            &serde_json::json!({"message": "hello"}),
+        )
+        .await
+        .expect("Request failed");
+    assert!(resp.status().is_server_error(), "{}", resp.status());
+}
+
 // =============================================================================
 // Module Not Found Tests
 // =============================================================================